@@ -0,0 +1,33 @@
+//! Benchmarks for bulk account generation.
+//!
+//! Run with `cargo bench -p chain-forge-bitcoin-accounts`. Generating 1,000
+//! accounts should stay well under a second; a regression here usually means
+//! the seed or master key is being derived per account again.
+
+use chain_forge_bitcoin_accounts::AccountGenerator;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+fn generate_accounts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_accounts");
+    group.sample_size(10);
+    for count in [10u32, 100, 1_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            let generator = AccountGenerator::from_mnemonic(MNEMONIC).unwrap();
+            b.iter(|| generator.generate_accounts(count).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn derive_account(c: &mut Criterion) {
+    let generator = AccountGenerator::from_mnemonic(MNEMONIC).unwrap();
+    c.bench_function("derive_account", |b| {
+        b.iter(|| generator.derive_account(500).unwrap())
+    });
+}
+
+criterion_group!(benches, generate_accounts, derive_account);
+criterion_main!(benches);
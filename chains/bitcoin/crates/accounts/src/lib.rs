@@ -1,9 +1,13 @@
 use bip39::Mnemonic;
 use bitcoin::address::NetworkChecked;
-use bitcoin::bip32::{DerivationPath, Xpriv};
-use bitcoin::secp256k1::{Secp256k1, SecretKey};
-use bitcoin::{Address, CompressedPublicKey, Network, PrivateKey};
-use chain_forge_common::{ChainError, Result};
+use bitcoin::bip32::{ChildNumber, DerivationPath, Xpriv};
+use bitcoin::secp256k1::{All, Message, Secp256k1, SecretKey, Signing, Verification};
+use bitcoin::{Address, CompressedPublicKey, Network, PrivateKey, PublicKey};
+use chain_forge_common::{
+    AccountStore, ChainError, Protection, Result, Signer, SignerKeystore, StorageBackend,
+};
+use chain_forge_config::Config;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::str::FromStr;
@@ -11,11 +15,120 @@ use std::str::FromStr;
 #[cfg(test)]
 mod tests;
 
+/// Script type of account addresses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AddressType {
+    /// P2PKH (`m...`/`n...` on regtest), derived with BIP44 paths
+    Legacy,
+    /// P2WPKH nested in P2SH (`2...` on regtest), derived with BIP49 paths
+    P2shSegwit,
+    /// Native SegWit P2WPKH (`bcrt1q...` on regtest)
+    #[default]
+    Bech32,
+    /// Taproot key-path P2TR (`bcrt1p...` on regtest), derived with BIP86 paths
+    Taproot,
+}
+
+impl AddressType {
+    /// Path of the external chain accounts of this type are derived from
+    ///
+    /// Bech32 accounts keep the BIP44 path they have always used, so a
+    /// mnemonic derives the same accounts as before address types existed.
+    pub fn chain_path(self) -> &'static str {
+        match self {
+            Self::Legacy | Self::Bech32 => "m/44'/0'/0'/0",
+            Self::P2shSegwit => "m/49'/0'/0'/0",
+            Self::Taproot => "m/86'/0'/0'/0",
+        }
+    }
+
+    /// Address of this type for a public key
+    fn address<C: Verification>(
+        self,
+        secp: &Secp256k1<C>,
+        public_key: &PublicKey,
+        network: Network,
+    ) -> Address {
+        let compressed = CompressedPublicKey(public_key.inner);
+        match self {
+            Self::Legacy => Address::p2pkh(public_key.pubkey_hash(), network),
+            Self::P2shSegwit => Address::p2shwpkh(&compressed, network),
+            Self::Bech32 => Address::p2wpkh(&compressed, network),
+            // BIP86: the key is tweaked with no script tree
+            Self::Taproot => {
+                Address::p2tr(secp, public_key.inner.x_only_public_key().0, None, network)
+            }
+        }
+    }
+
+    /// Wrap a key expression (a public key or WIF) in this type's descriptor
+    ///
+    /// Taproot descriptors take x-only public keys; a WIF works for any type.
+    pub fn descriptor(self, key: &str) -> String {
+        match self {
+            Self::Legacy => format!("pkh({})", key),
+            Self::P2shSegwit => format!("sh(wpkh({}))", key),
+            Self::Bech32 => format!("wpkh({})", key),
+            Self::Taproot => format!("tr({})", key),
+        }
+    }
+
+    /// Type of an account address
+    ///
+    /// Any P2SH address is taken to be P2SH-SegWit, the only kind accounts
+    /// have.
+    pub fn of_address(address: &str) -> Result<Self> {
+        let address = Address::from_str(address)
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid address: {}", e)))?
+            .assume_checked();
+        match address.address_type() {
+            Some(bitcoin::AddressType::P2pkh) => Ok(Self::Legacy),
+            Some(bitcoin::AddressType::P2sh) => Ok(Self::P2shSegwit),
+            Some(bitcoin::AddressType::P2wpkh) => Ok(Self::Bech32),
+            Some(bitcoin::AddressType::P2tr) => Ok(Self::Taproot),
+            other => Err(ChainError::AccountGeneration(format!(
+                "Unsupported address type {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+impl FromStr for AddressType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "legacy" | "p2pkh" => Ok(Self::Legacy),
+            "p2sh-segwit" | "p2sh-p2wpkh" => Ok(Self::P2shSegwit),
+            "bech32" | "p2wpkh" => Ok(Self::Bech32),
+            "taproot" | "p2tr" => Ok(Self::Taproot),
+            _ => Err(format!(
+                "Invalid address type '{}': expected legacy, p2sh-segwit, bech32, or taproot",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for AddressType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Legacy => "legacy",
+            Self::P2shSegwit => "p2sh-segwit",
+            Self::Bech32 => "bech32",
+            Self::Taproot => "taproot",
+        })
+    }
+}
+
 /// Bitcoin account with keypair and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BitcoinAccount {
-    /// Bitcoin address (P2WPKH bech32 format, bcrt1... for regtest)
+    /// Bitcoin address, of the generator's [`AddressType`] (P2WPKH bech32,
+    /// bcrt1q... on regtest, by default)
     pub address: String,
     /// Hex-encoded compressed public key
     pub public_key: String,
@@ -25,29 +138,52 @@ pub struct BitcoinAccount {
     pub wif: String,
     /// BIP39 mnemonic phrase used to derive this account
     pub mnemonic: Option<String>,
-    /// BIP44 derivation path
+    /// BIP32 derivation path (BIP44, BIP49, or BIP86 style)
     pub derivation_path: Option<String>,
+    /// Fingerprint of the master key `derivation_path` starts from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_fingerprint: Option<String>,
     /// Balance in BTC
     pub balance: f64,
+    /// Key is held by an external signer rather than stored with the account
+    #[serde(default)]
+    pub external_signer: bool,
+    /// Optional human-readable label (e.g. "alice")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 impl BitcoinAccount {
-    /// Create a new account from a secret key
+    /// Create a new P2WPKH account from a secret key
     pub fn from_secret_key(
         secret_key: SecretKey,
         network: Network,
         mnemonic: Option<String>,
         path: Option<String>,
     ) -> Result<Self> {
-        let secp = Secp256k1::new();
-        let private_key = PrivateKey::new(secret_key, network);
-        let public_key = private_key.public_key(&secp);
-
-        // Create compressed public key for P2WPKH address
-        let compressed = CompressedPublicKey(public_key.inner);
+        Self::from_secret_key_in(
+            &Secp256k1::new(),
+            secret_key,
+            network,
+            AddressType::Bech32,
+            mnemonic,
+            path,
+        )
+    }
 
-        // Create P2WPKH (native SegWit bech32) address
-        let address = Address::p2wpkh(&compressed, network);
+    /// [`Self::from_secret_key`] with an existing secp256k1 context and any
+    /// address type
+    fn from_secret_key_in<C: Signing + Verification>(
+        secp: &Secp256k1<C>,
+        secret_key: SecretKey,
+        network: Network,
+        address_type: AddressType,
+        mnemonic: Option<String>,
+        path: Option<String>,
+    ) -> Result<Self> {
+        let private_key = PrivateKey::new(secret_key, network);
+        let public_key = private_key.public_key(secp);
+        let address = address_type.address(secp, &public_key, network);
 
         Ok(Self {
             address: address.to_string(),
@@ -56,12 +192,28 @@ impl BitcoinAccount {
             wif: private_key.to_wif(),
             mnemonic,
             derivation_path: path,
+            master_fingerprint: None,
             balance: 0.0,
+            external_signer: false,
+            label: None,
         })
     }
 
+    /// Create an account from a WIF-encoded private key
+    pub fn from_wif(wif: &str, network: Network) -> Result<Self> {
+        let private_key = PrivateKey::from_wif(wif)
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid WIF: {}", e)))?;
+        Self::from_secret_key(private_key.inner, network, None, None)
+    }
+
     /// Get the secret key for this account
     pub fn secret_key(&self) -> Result<SecretKey> {
+        if self.external_signer {
+            return Err(ChainError::AccountGeneration(format!(
+                "Account {} uses an external signer",
+                self.address
+            )));
+        }
         SecretKey::from_slice(&self.private_key)
             .map_err(|e| ChainError::AccountGeneration(format!("Invalid secret key bytes: {}", e)))
     }
@@ -73,12 +225,224 @@ impl BitcoinAccount {
             .require_network(network)
             .map_err(|e| ChainError::AccountGeneration(format!("Network mismatch: {}", e)))
     }
+
+    /// Script type of this account's address
+    pub fn address_type(&self) -> Result<AddressType> {
+        AddressType::of_address(&self.address)
+    }
+
+    /// Output descriptor for this account's address, with checksum
+    ///
+    /// `pkh(...)`, `sh(wpkh(...))`, `wpkh(...)`, or `tr(...)` depending on the
+    /// address type. Key origin info (`[fingerprint/path]`) is included when
+    /// the account was derived from a mnemonic.
+    pub fn descriptor(&self) -> Result<String> {
+        let address_type = self.address_type()?;
+        let key = match address_type {
+            // Taproot keys are x-only: drop the parity byte
+            AddressType::Taproot => self.public_key.get(2..).unwrap_or(""),
+            _ => self.public_key.as_str(),
+        };
+        let descriptor = address_type.descriptor(&format!("{}{}", self.key_origin(), key));
+        Ok(format!(
+            "{}#{}",
+            descriptor,
+            descriptor_checksum(&descriptor)?
+        ))
+    }
+
+    /// Output descriptor holding this account's private key (WIF), with
+    /// checksum, for `importdescriptors` or wallets such as Sparrow
+    pub fn private_descriptor(&self) -> Result<String> {
+        let wif = self.exportable_wif()?;
+        let descriptor = self
+            .address_type()?
+            .descriptor(&format!("{}{}", self.key_origin(), wif));
+        Ok(format!(
+            "{}#{}",
+            descriptor,
+            descriptor_checksum(&descriptor)?
+        ))
+    }
+
+    /// Private key in Electrum's import format, e.g. `p2wpkh:<WIF>`
+    ///
+    /// Electrum has no Taproot support, so Taproot accounts can't be exported.
+    pub fn electrum_key(&self) -> Result<String> {
+        let prefix = match self.address_type()? {
+            AddressType::Legacy => "p2pkh",
+            AddressType::P2shSegwit => "p2wpkh-p2sh",
+            AddressType::Bech32 => "p2wpkh",
+            AddressType::Taproot => {
+                return Err(ChainError::AccountGeneration(format!(
+                    "Account {} is a taproot account, which Electrum can't import",
+                    self.address
+                )))
+            }
+        };
+        Ok(format!("{}:{}", prefix, self.exportable_wif()?))
+    }
+
+    /// Key origin (`[fingerprint/path]`) of an account derived from a mnemonic
+    fn key_origin(&self) -> String {
+        match (&self.master_fingerprint, &self.derivation_path) {
+            (Some(fingerprint), Some(path)) => {
+                format!("[{}{}]", fingerprint, path.trim_start_matches('m'))
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn exportable_wif(&self) -> Result<&str> {
+        if self.external_signer || self.wif.is_empty() {
+            return Err(ChainError::AccountGeneration(format!(
+                "Account {} uses an external signer; its key can't be exported",
+                self.address
+            )));
+        }
+        Ok(&self.wif)
+    }
+
+    /// Move this account's key into an external signer keystore
+    ///
+    /// The private key, WIF and mnemonic are removed from the account; signing
+    /// must then go through [`EmulatedSigner`] or another [`Signer`].
+    /// Only P2WPKH accounts can be detached, since external signers produce
+    /// ECDSA signatures for P2WPKH inputs.
+    pub fn detach_key(&mut self, keystore: &SignerKeystore) -> Result<()> {
+        if self.address_type()? != AddressType::Bech32 {
+            return Err(ChainError::AccountGeneration(format!(
+                "Account {} is not a bech32 (P2WPKH) account; external signers only support those",
+                self.address
+            )));
+        }
+        keystore.insert(&self.address, std::mem::take(&mut self.private_key))?;
+        self.wif.clear();
+        self.mnemonic = None;
+        self.external_signer = true;
+        Ok(())
+    }
+}
+
+/// Characters allowed in descriptors, in checksum symbol order
+const DESCRIPTOR_INPUT_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const DESCRIPTOR_CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Compute the 8-character checksum of a descriptor (BIP380)
+pub fn descriptor_checksum(descriptor: &str) -> Result<String> {
+    const GENERATOR: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+
+    fn polymod(checksum: u64, value: u64) -> u64 {
+        let top = checksum >> 35;
+        let mut checksum = ((checksum & 0x7ffffffff) << 5) ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+        checksum
+    }
+
+    let mut checksum = 1;
+    let mut groups = Vec::with_capacity(3);
+    for c in descriptor.chars() {
+        let position = DESCRIPTOR_INPUT_CHARSET.find(c).ok_or_else(|| {
+            ChainError::AccountGeneration(format!("Invalid descriptor character '{}'", c))
+        })? as u64;
+        checksum = polymod(checksum, position & 31);
+        groups.push(position >> 5);
+        if groups.len() == 3 {
+            checksum = polymod(checksum, groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups.as_slice() {
+        [a] => checksum = polymod(checksum, *a),
+        [a, b] => checksum = polymod(checksum, a * 3 + b),
+        _ => {}
+    }
+    for _ in 0..8 {
+        checksum = polymod(checksum, 0);
+    }
+    checksum ^= 1;
+
+    Ok((0..8)
+        .map(|i| DESCRIPTOR_CHECKSUM_CHARSET[((checksum >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
 }
 
-/// Account generator for Bitcoin using BIP39/BIP44
+/// Signer holding a secp256k1 secret key in process
+pub struct LocalSigner {
+    address: String,
+    secret_key: SecretKey,
+}
+
+/// Software emulator of a hardware wallet for an external signer account
+///
+/// The emulator is a [`LocalSigner`] whose key lives in the signer keystore
+/// rather than in `accounts.json`.
+pub type EmulatedSigner = LocalSigner;
+
+impl LocalSigner {
+    /// Create a signer from an account's own secret key
+    pub fn from_account(account: &BitcoinAccount) -> Result<Self> {
+        Ok(Self {
+            address: account.address.clone(),
+            secret_key: account.secret_key()?,
+        })
+    }
+
+    /// Load the signer for an address from a keystore
+    pub fn load(keystore: &SignerKeystore, address: &str) -> Result<Self> {
+        let secret = keystore.get(address)?;
+        let secret_key = SecretKey::from_slice(&secret).map_err(|e| {
+            ChainError::AccountGeneration(format!("Invalid secret key bytes: {}", e))
+        })?;
+        Ok(Self {
+            address: address.to_string(),
+            secret_key,
+        })
+    }
+}
+
+impl Signer for LocalSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Sign a 32-byte sighash, returning a DER-encoded ECDSA signature
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let message = Message::from_digest_slice(payload)
+            .map_err(|e| ChainError::Other(format!("Payload must be a 32-byte sighash: {}", e)))?;
+        let secp = Secp256k1::signing_only();
+        let signature = secp.sign_ecdsa(&message, &self.secret_key);
+        Ok(signature.serialize_der().to_vec())
+    }
+}
+
+/// Account generator for Bitcoin using BIP39 and BIP44/49/86 paths
+///
+/// The seed, master key, and account chain key are derived once when the
+/// generator is created, so each account only costs one child derivation.
+/// Accounts are P2WPKH unless another type is set with
+/// [`Self::with_address_type`].
 pub struct AccountGenerator {
-    mnemonic: Mnemonic,
+    phrase: String,
     network: Network,
+    secp: Secp256k1<All>,
+    master_key: Xpriv,
+    address_type: AddressType,
+    chain_key: Xpriv,
+    master_fingerprint: String,
+    /// Index of the first account generated
+    start_index: u32,
 }
 
 impl AccountGenerator {
@@ -96,7 +460,7 @@ impl AccountGenerator {
         let mnemonic = Mnemonic::from_entropy(&entropy).map_err(|e| {
             ChainError::AccountGeneration(format!("Failed to generate mnemonic: {}", e))
         })?;
-        Ok(Self { mnemonic, network })
+        Self::with_mnemonic(mnemonic, network)
     }
 
     /// Create a generator from an existing mnemonic phrase for regtest
@@ -108,12 +472,55 @@ impl AccountGenerator {
     pub fn from_mnemonic_with_network(phrase: &str, network: Network) -> Result<Self> {
         let mnemonic = Mnemonic::parse(phrase)
             .map_err(|e| ChainError::AccountGeneration(format!("Invalid mnemonic: {}", e)))?;
-        Ok(Self { mnemonic, network })
+        Self::with_mnemonic(mnemonic, network)
+    }
+
+    fn with_mnemonic(mnemonic: Mnemonic, network: Network) -> Result<Self> {
+        // Get seed from mnemonic
+        let seed = mnemonic.to_seed("");
+
+        // Create master key from seed
+        let secp = Secp256k1::new();
+        let master_key = Xpriv::new_master(network, &seed).map_err(|e| {
+            ChainError::AccountGeneration(format!("Failed to create master key: {}", e))
+        })?;
+
+        let address_type = AddressType::default();
+        let chain_key = derive_chain_key(&secp, &master_key, address_type)?;
+
+        Ok(Self {
+            phrase: mnemonic.to_string(),
+            master_fingerprint: master_key.fingerprint(&secp).to_string(),
+            network,
+            secp,
+            master_key,
+            address_type,
+            chain_key,
+            start_index: 0,
+        })
+    }
+
+    /// Derive accounts of another address type, from that type's path
+    pub fn with_address_type(mut self, address_type: AddressType) -> Result<Self> {
+        self.chain_key = derive_chain_key(&self.secp, &self.master_key, address_type)?;
+        self.address_type = address_type;
+        Ok(self)
+    }
+
+    /// Generate accounts from `index` on instead of from 0
+    pub fn with_start_index(mut self, index: u32) -> Self {
+        self.start_index = index;
+        self
+    }
+
+    /// Get the address type accounts are derived as
+    pub fn address_type(&self) -> AddressType {
+        self.address_type
     }
 
     /// Get the mnemonic phrase
     pub fn mnemonic_phrase(&self) -> String {
-        self.mnemonic.to_string()
+        self.phrase.clone()
     }
 
     /// Get the network
@@ -121,52 +528,62 @@ impl AccountGenerator {
         self.network
     }
 
-    /// Generate multiple accounts from the mnemonic
+    /// Generate multiple accounts from the mnemonic, from the start index on
+    ///
+    /// Accounts are derived in parallel and returned in index order.
     pub fn generate_accounts(&self, count: u32) -> Result<Vec<BitcoinAccount>> {
-        let mut accounts = Vec::new();
-
-        for index in 0..count {
-            let account = self.derive_account(index)?;
-            accounts.push(account);
-        }
-
-        Ok(accounts)
+        let end = self.start_index.checked_add(count).ok_or_else(|| {
+            ChainError::AccountGeneration(format!(
+                "Cannot generate {} accounts from index {}",
+                count, self.start_index
+            ))
+        })?;
+        (self.start_index..end)
+            .into_par_iter()
+            .map(|index| self.derive_account(index))
+            .collect()
     }
 
     /// Derive a single account at the given index
-    /// Uses Bitcoin's standard BIP44 derivation path: m/44'/0'/0'/0/{index}
+    /// Uses the address type's chain path, e.g. m/44'/0'/0'/0/{index} for bech32
     /// Note: coin type 0 is for Bitcoin mainnet, but works for regtest too
     pub fn derive_account(&self, index: u32) -> Result<BitcoinAccount> {
-        let derivation_path = format!("m/44'/0'/0'/0/{}", index);
-
-        // Get seed from mnemonic
-        let seed = self.mnemonic.to_seed("");
-
-        // Create master key from seed
-        let secp = Secp256k1::new();
-        let master_key = Xpriv::new_master(self.network, &seed).map_err(|e| {
-            ChainError::AccountGeneration(format!("Failed to create master key: {}", e))
-        })?;
-
-        // Parse derivation path
-        let path = DerivationPath::from_str(&derivation_path).map_err(|e| {
-            ChainError::AccountGeneration(format!("Invalid derivation path: {}", e))
+        let child = ChildNumber::from_normal_idx(index).map_err(|e| {
+            ChainError::AccountGeneration(format!("Invalid account index {}: {}", index, e))
         })?;
 
         // Derive child key
-        let derived_key = master_key
-            .derive_priv(&secp, &path)
+        let derived_key = self
+            .chain_key
+            .derive_priv(&self.secp, &[child])
             .map_err(|e| ChainError::AccountGeneration(format!("Failed to derive key: {}", e)))?;
 
-        BitcoinAccount::from_secret_key(
+        let mut account = BitcoinAccount::from_secret_key_in(
+            &self.secp,
             derived_key.private_key,
             self.network,
+            self.address_type,
             Some(self.mnemonic_phrase()),
-            Some(derivation_path),
-        )
+            Some(format!("{}/{}", self.address_type.chain_path(), index)),
+        )?;
+        account.master_fingerprint = Some(self.master_fingerprint.clone());
+        Ok(account)
     }
 }
 
+/// Derive the key every account of `address_type` is a child of
+fn derive_chain_key(
+    secp: &Secp256k1<All>,
+    master_key: &Xpriv,
+    address_type: AddressType,
+) -> Result<Xpriv> {
+    let path = DerivationPath::from_str(address_type.chain_path())
+        .map_err(|e| ChainError::AccountGeneration(format!("Invalid derivation path: {}", e)))?;
+    master_key
+        .derive_priv(secp, &path)
+        .map_err(|e| ChainError::AccountGeneration(format!("Failed to derive key: {}", e)))
+}
+
 impl Default for AccountGenerator {
     fn default() -> Self {
         Self::new().expect("Failed to create default account generator")
@@ -176,18 +593,50 @@ impl Default for AccountGenerator {
 /// Accounts storage manager for Bitcoin
 pub struct AccountsStorage {
     accounts_file: std::path::PathBuf,
+    protection: Protection,
+    backend: StorageBackend,
 }
 
+/// Serialized account fields that are encrypted at rest: the private key, WIF, and mnemonic
+const SECRET_FIELDS: &[&str] = &["privateKey", "wif", "mnemonic"];
+
 impl AccountsStorage {
     /// Create a new storage manager (uses default path under data_dir/bitcoin/accounts.json)
     pub fn new(data_dir: &Path) -> Self {
         let accounts_file = data_dir.join("bitcoin").join("accounts.json");
-        Self { accounts_file }
+        Self::with_path(accounts_file)
     }
 
     /// Create a storage manager with a specific file path
+    ///
+    /// Accounts are kept by the backend set with `accounts_storage` in
+    /// `chain-forge.toml`. Secrets are encrypted if `CHAIN_FORGE_PASSPHRASE`
+    /// is set.
     pub fn with_path(accounts_file: std::path::PathBuf) -> Self {
-        Self { accounts_file }
+        Self {
+            accounts_file,
+            protection: Protection::from_env(),
+            backend: Config::load()
+                .map(|config| config.accounts_storage)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Use `protection` instead of the one from the environment
+    pub fn with_protection(mut self, protection: Protection) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Use `backend` instead of the configured one
+    pub fn with_backend(mut self, backend: StorageBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    fn store(&self) -> Box<dyn AccountStore> {
+        self.backend
+            .open(&self.accounts_file, self.protection.clone())
     }
 
     /// Get the accounts file path
@@ -195,39 +644,41 @@ impl AccountsStorage {
         &self.accounts_file
     }
 
-    /// Save accounts to file
-    pub fn save(&self, accounts: &[BitcoinAccount]) -> Result<()> {
-        // Ensure directory exists
-        if let Some(parent) = self.accounts_file.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    /// Backend the accounts are kept by
+    pub fn backend(&self) -> StorageBackend {
+        self.backend
+    }
 
-        let json = serde_json::to_string_pretty(accounts)?;
-        std::fs::write(&self.accounts_file, json)?;
-        Ok(())
+    /// Save accounts
+    ///
+    /// With the file backend the file is replaced atomically, so a crash
+    /// mid-save leaves the previous accounts in place.
+    pub fn save(&self, accounts: &[BitcoinAccount]) -> Result<()> {
+        let accounts = accounts
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        self.store().save(&accounts, SECRET_FIELDS)
     }
 
-    /// Load accounts from file
+    /// Load accounts, upgrading an older file format and decrypting secrets
+    /// if they are encrypted
     pub fn load(&self) -> Result<Vec<BitcoinAccount>> {
-        if !self.accounts_file.exists() {
-            return Ok(Vec::new());
-        }
-
-        let json = std::fs::read_to_string(&self.accounts_file)?;
-        let accounts: Vec<BitcoinAccount> = serde_json::from_str(&json)?;
-        Ok(accounts)
+        Ok(self
+            .store()
+            .load()?
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<serde_json::Result<_>>()?)
     }
 
-    /// Check if accounts file exists
+    /// Check if any accounts are stored
     pub fn exists(&self) -> bool {
-        self.accounts_file.exists()
+        self.store().exists()
     }
 
-    /// Delete the accounts file
+    /// Delete the stored accounts
     pub fn delete(&self) -> Result<()> {
-        if self.accounts_file.exists() {
-            std::fs::remove_file(&self.accounts_file)?;
-        }
-        Ok(())
+        self.store().delete()
     }
 }
@@ -40,6 +40,37 @@ fn test_deterministic_derivation() {
     }
 }
 
+#[test]
+fn test_bulk_generation_matches_single_derivation() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let accounts = generator.generate_accounts(64).unwrap();
+
+    assert_eq!(accounts.len(), 64);
+    for (i, account) in accounts.iter().enumerate() {
+        let single = generator.derive_account(i as u32).unwrap();
+        assert_eq!(account.address, single.address);
+        assert_eq!(account.wif, single.wif);
+        assert_eq!(account.derivation_path, single.derivation_path);
+        assert_eq!(account.master_fingerprint.as_deref(), Some("73c5da0a"));
+    }
+}
+
+#[test]
+fn test_start_index() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC)
+        .unwrap()
+        .with_start_index(20);
+    let accounts = generator.generate_accounts(3).unwrap();
+
+    assert_eq!(accounts.len(), 3);
+    for (i, account) in (20..).zip(&accounts) {
+        assert_eq!(
+            account.address,
+            generator.derive_account(i).unwrap().address
+        );
+    }
+}
+
 #[test]
 fn test_derivation_paths() {
     let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
@@ -89,6 +120,19 @@ fn test_wif_format() {
     );
 }
 
+#[test]
+fn test_account_from_wif() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let derived = generator.derive_account(0).unwrap();
+
+    let account = BitcoinAccount::from_wif(&derived.wif, Network::Regtest).unwrap();
+    assert_eq!(account.address, derived.address);
+    assert_eq!(account.public_key, derived.public_key);
+    assert!(account.mnemonic.is_none());
+
+    assert!(BitcoinAccount::from_wif("not-a-wif", Network::Regtest).is_err());
+}
+
 #[test]
 fn test_storage_save_load() {
     let temp_dir = tempdir().unwrap();
@@ -112,6 +156,31 @@ fn test_storage_save_load() {
     }
 }
 
+#[test]
+fn test_storage_encrypted_save_load() {
+    let temp_dir = tempdir().unwrap();
+    let protection = Protection::Passphrase("test passphrase".to_string());
+    let storage = AccountsStorage::new(temp_dir.path()).with_protection(protection.clone());
+
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let accounts = generator.generate_accounts(2).unwrap();
+    storage.save(&accounts).unwrap();
+
+    // Addresses are readable on disk, keys and the mnemonic are not
+    let on_disk = std::fs::read_to_string(storage.accounts_file()).unwrap();
+    assert!(on_disk.contains(&accounts[0].address));
+    assert!(!on_disk.contains(&accounts[0].wif));
+    assert!(!on_disk.contains("abandon"));
+
+    let loaded = storage.load().unwrap();
+    assert_eq!(loaded[1].wif, accounts[1].wif);
+    assert_eq!(loaded[1].private_key, accounts[1].private_key);
+    assert_eq!(loaded[1].mnemonic, accounts[1].mnemonic);
+
+    let locked = AccountsStorage::new(temp_dir.path()).with_protection(Protection::Plaintext);
+    assert!(locked.load().is_err());
+}
+
 #[test]
 fn test_storage_delete() {
     let temp_dir = tempdir().unwrap();
@@ -179,3 +248,234 @@ fn test_multiple_account_indices() {
         );
     }
 }
+
+#[test]
+fn test_detach_key_to_external_signer() {
+    let dir = tempdir().unwrap();
+    let keystore = SignerKeystore::with_path(dir.path().join("signers.json"));
+
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let mut account = generator.derive_account(0).unwrap();
+    let secret_key = account.secret_key().unwrap();
+
+    account.detach_key(&keystore).unwrap();
+
+    assert!(account.external_signer);
+    assert!(account.private_key.is_empty());
+    assert!(account.wif.is_empty());
+    assert!(account.mnemonic.is_none());
+    assert!(account.secret_key().is_err());
+
+    // Signatures from the emulator verify against the account's public key
+    let signer = EmulatedSigner::load(&keystore, &account.address).unwrap();
+    assert_eq!(signer.address(), account.address);
+
+    let digest = [7u8; 32];
+    let der = signer.sign(&digest).unwrap();
+
+    let secp = Secp256k1::new();
+    let signature = bitcoin::secp256k1::ecdsa::Signature::from_der(&der).unwrap();
+    let message = Message::from_digest_slice(&digest).unwrap();
+    assert!(secp
+        .verify_ecdsa(&message, &signature, &secret_key.public_key(&secp))
+        .is_ok());
+
+    // Only 32-byte digests are accepted
+    assert!(signer.sign(b"not a digest").is_err());
+}
+
+#[test]
+fn test_local_signer_from_account() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let account = generator.derive_account(0).unwrap();
+
+    let signer = LocalSigner::from_account(&account).unwrap();
+    assert_eq!(signer.address(), account.address);
+
+    let digest = [1u8; 32];
+    let der = signer.sign(&digest).unwrap();
+    let signature = bitcoin::secp256k1::ecdsa::Signature::from_der(&der).unwrap();
+
+    let secp = Secp256k1::new();
+    let public_key = account.secret_key().unwrap().public_key(&secp);
+    let message = Message::from_digest_slice(&digest).unwrap();
+    assert!(secp.verify_ecdsa(&message, &signature, &public_key).is_ok());
+}
+
+#[test]
+fn test_descriptor_checksum() {
+    // Test vectors from BIP380
+    assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+    assert_eq!(
+        descriptor_checksum("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)").unwrap(),
+        "02wpgw69"
+    );
+    assert!(descriptor_checksum("raw(dé)").is_err());
+}
+
+#[test]
+fn test_descriptor_with_origin() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let account = generator.derive_account(2).unwrap();
+
+    // Master fingerprint of the "abandon ... about" test mnemonic
+    assert_eq!(account.master_fingerprint.as_deref(), Some("73c5da0a"));
+
+    let descriptor = account.descriptor().unwrap();
+    let (body, checksum) = descriptor.split_once('#').unwrap();
+    assert_eq!(
+        body,
+        format!("wpkh([73c5da0a/44'/0'/0'/0/2]{})", account.public_key)
+    );
+    assert_eq!(checksum, descriptor_checksum(body).unwrap());
+}
+
+#[test]
+fn test_export_formats() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let account = generator.derive_account(0).unwrap();
+
+    let descriptor = account.private_descriptor().unwrap();
+    let (body, checksum) = descriptor.split_once('#').unwrap();
+    assert_eq!(
+        body,
+        format!("wpkh([73c5da0a/44'/0'/0'/0/0]{})", account.wif)
+    );
+    assert_eq!(checksum, descriptor_checksum(body).unwrap());
+    assert_eq!(
+        account.electrum_key().unwrap(),
+        format!("p2wpkh:{}", account.wif)
+    );
+
+    let taproot = generator
+        .with_address_type(AddressType::Taproot)
+        .unwrap()
+        .derive_account(0)
+        .unwrap();
+    assert!(taproot.private_descriptor().unwrap().starts_with("tr(["));
+    assert!(taproot.electrum_key().is_err());
+
+    let dir = tempdir().unwrap();
+    let keystore = SignerKeystore::with_path(dir.path().join("keys.json"));
+    let mut external = account.clone();
+    external.detach_key(&keystore).unwrap();
+    assert!(external.private_descriptor().is_err());
+}
+
+#[test]
+fn test_descriptor_without_origin() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let wif = generator.derive_account(0).unwrap().wif;
+
+    let account = BitcoinAccount::from_wif(&wif, Network::Regtest).unwrap();
+    assert!(account
+        .descriptor()
+        .unwrap()
+        .starts_with(&format!("wpkh({})#", account.public_key)));
+}
+
+#[test]
+fn test_address_types_match_bip_vectors() {
+    let generator = |address_type| {
+        AccountGenerator::from_mnemonic_with_network(TEST_MNEMONIC, Network::Bitcoin)
+            .unwrap()
+            .with_address_type(address_type)
+            .unwrap()
+    };
+
+    // BIP44 and BIP86 test vectors for the "abandon ... about" mnemonic
+    let legacy = generator(AddressType::Legacy).derive_account(0).unwrap();
+    assert_eq!(legacy.address, "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA");
+    assert_eq!(legacy.derivation_path.as_deref(), Some("m/44'/0'/0'/0/0"));
+
+    let taproot = generator(AddressType::Taproot).derive_account(0).unwrap();
+    assert_eq!(
+        taproot.address,
+        "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+    );
+    assert_eq!(taproot.derivation_path.as_deref(), Some("m/86'/0'/0'/0/0"));
+
+    let nested = generator(AddressType::P2shSegwit)
+        .derive_account(0)
+        .unwrap();
+    assert!(nested.address.starts_with('3'));
+    assert_eq!(nested.derivation_path.as_deref(), Some("m/49'/0'/0'/0/0"));
+
+    // Bech32 accounts keep their original path and addresses
+    let default = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    assert_eq!(
+        generator(AddressType::Bech32)
+            .derive_account(0)
+            .unwrap()
+            .public_key,
+        default.derive_account(0).unwrap().public_key
+    );
+}
+
+#[test]
+fn test_address_type_round_trip() {
+    for address_type in [
+        AddressType::Legacy,
+        AddressType::P2shSegwit,
+        AddressType::Bech32,
+        AddressType::Taproot,
+    ] {
+        let account = AccountGenerator::from_mnemonic(TEST_MNEMONIC)
+            .unwrap()
+            .with_address_type(address_type)
+            .unwrap()
+            .derive_account(0)
+            .unwrap();
+        assert_eq!(account.address_type().unwrap(), address_type);
+        assert_eq!(
+            address_type.to_string().parse::<AddressType>().unwrap(),
+            address_type
+        );
+    }
+    assert_eq!("p2tr".parse::<AddressType>().unwrap(), AddressType::Taproot);
+    assert!("segwit".parse::<AddressType>().is_err());
+}
+
+#[test]
+fn test_descriptors_by_address_type() {
+    let derive = |address_type| {
+        AccountGenerator::from_mnemonic(TEST_MNEMONIC)
+            .unwrap()
+            .with_address_type(address_type)
+            .unwrap()
+            .derive_account(0)
+            .unwrap()
+    };
+
+    let legacy = derive(AddressType::Legacy);
+    assert!(legacy.descriptor().unwrap().starts_with(&format!(
+        "pkh([73c5da0a/44'/0'/0'/0/0]{})#",
+        legacy.public_key
+    )));
+
+    let nested = derive(AddressType::P2shSegwit);
+    assert!(nested.descriptor().unwrap().starts_with(&format!(
+        "sh(wpkh([73c5da0a/49'/0'/0'/0/0]{}))#",
+        nested.public_key
+    )));
+
+    let taproot = derive(AddressType::Taproot);
+    assert!(taproot.descriptor().unwrap().starts_with(&format!(
+        "tr([73c5da0a/86'/0'/0'/0/0]{})#",
+        &taproot.public_key[2..]
+    )));
+}
+
+#[test]
+fn test_only_bech32_accounts_detach() {
+    let dir = tempdir().unwrap();
+    let keystore = SignerKeystore::with_path(dir.path().join("keys.json"));
+    let mut taproot = AccountGenerator::from_mnemonic(TEST_MNEMONIC)
+        .unwrap()
+        .with_address_type(AddressType::Taproot)
+        .unwrap()
+        .derive_account(0)
+        .unwrap();
+    assert!(taproot.detach_key(&keystore).is_err());
+    assert!(!taproot.external_signer);
+}
@@ -1,13 +1,38 @@
-use chain_forge_bitcoin_accounts::AccountsStorage;
-use chain_forge_bitcoin_core::{BitcoinConfig, BitcoinProvider, InstanceInfo};
+use chain_forge_bitcoin_accounts::{
+    AccountGenerator, AccountsStorage, AddressType, BitcoinAccount, EmulatedSigner,
+};
+use chain_forge_bitcoin_core::{
+    analyze, analyze_instance, confirm, public, reconcile_instance, BalanceReport, BitcoinChain,
+    BitcoinConfig, BitcoinProvider, BitcoinSnapshot, Confirmations, InstanceInfo, PaymentUri,
+    PublicNetwork, Treasury, WalletAnalysis, NODE_PORT_STRIDE,
+};
 use chain_forge_bitcoin_rpc::BitcoinRpcClient;
-use chain_forge_cli_utils::OutputFormat;
-use chain_forge_common::{validate_name, ChainProvider};
-use chain_forge_config::Config;
-use clap::{Parser, Subcommand};
-use eyre::Result;
+use chain_forge_cli_utils::{
+    daemon, data_dir, format_btc, logging, progress, write_secret_file, AmountArgs, DataDirArgs,
+    ExitCode, LogArgs, OutputFormat, BTC_DECIMALS,
+};
+use chain_forge_common::{
+    gap_scan, validate_name, ChainError, ChainProvider, ChainType, Message, NodeRegistry,
+    NodeStatus, RemoteSigner, Signer, SignerKeystore, DEFAULT_GAP_LIMIT,
+};
+use chain_forge_config::{Config, Preset};
+use chain_forge_events::ChainEvent;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use eyre::{Result, WrapErr};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
 use tabled::{Table, Tabled};
 
+/// How long `start --detach` waits for the node to come up
+const DETACH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long `stop` waits for a detached node before killing it
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// BTC an account needs beyond the amount for `pay` to pick it, to cover fees
+const PAY_FEE_MARGIN: f64 = 0.001;
+
 #[derive(Parser)]
 #[command(name = "cf-bitcoin")]
 #[command(about = "Chain Forge - Bitcoin local development tool", long_about = None)]
@@ -15,13 +40,34 @@ use tabled::{Table, Tabled};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    data: DataDirArgs,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Start local Bitcoin regtest node with pre-funded accounts
-    Start {
-        /// Instance ID for isolation (allows multiple nodes with separate state)
+    /// Start local Bitcoin regtest or signet node with pre-funded accounts
+    Start(StartArgs),
+
+    /// Track accounts on a public test network instead of running a node
+    Attach {
+        /// Public test network to attach to
+        #[arg(
+            long,
+            default_value = "testnet4",
+            value_parser = ["testnet4", "testnet", "signet"]
+        )]
+        network: String,
+
+        /// Esplora API to read balances from instead of the network's public one
+        #[arg(long)]
+        api_url: Option<String>,
+
+        /// Instance ID to record the accounts under
         #[arg(short, long, default_value = "default")]
         instance: String,
 
@@ -29,49 +75,50 @@ enum Commands {
         #[arg(short = 'n', long)]
         name: Option<String>,
 
-        /// Number of accounts to generate
+        /// Number of accounts to derive
         #[arg(short, long, default_value = "10")]
         accounts: u32,
 
-        /// Initial balance for each account in BTC
-        #[arg(short, long, default_value = "10.0")]
-        balance: f64,
-
-        /// RPC port for the node
-        #[arg(long, default_value = "18443")]
-        rpc_port: u16,
-
-        /// P2P network port
-        #[arg(long, default_value = "18444")]
-        p2p_port: u16,
-
-        /// Optional mnemonic phrase to use for account generation
+        /// Optional mnemonic phrase to derive the accounts from
         #[arg(short, long)]
         mnemonic: Option<String>,
+    },
 
-        /// RPC username
-        #[arg(long, default_value = "chainforge")]
-        rpc_user: String,
+    /// List all generated accounts with their balances
+    Accounts {
+        #[command(subcommand)]
+        command: Option<AccountsCommands>,
 
-        /// RPC password
-        #[arg(long, default_value = "chainforge")]
-        rpc_password: String,
+        /// Instance ID to query
+        #[arg(short, long, default_value = "default")]
+        instance: String,
 
-        /// Show verbose bitcoind output
-        #[arg(short, long, default_value = "false")]
-        verbose: bool,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
 
-        /// Keep instance data on stop (default: clean up)
-        #[arg(long, default_value = "false")]
-        keep_data: bool,
+        #[command(flatten)]
+        amount: AmountArgs,
     },
 
-    /// List all generated accounts with their balances
-    Accounts {
-        /// Instance ID to query
+    /// Find funded accounts derived from a mnemonic, beyond the generated ones
+    Scan {
+        /// Instance ID whose node (and mnemonic) to use
         #[arg(short, long, default_value = "default")]
         instance: String,
 
+        /// Mnemonic to scan instead of the instance's
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+
+        /// Derivation index to start scanning from
+        #[arg(long, default_value = "0")]
+        start_index: u32,
+
+        /// Stop after this many empty accounts in a row
+        #[arg(long, default_value_t = DEFAULT_GAP_LIMIT)]
+        gap_limit: u32,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
@@ -88,6 +135,11 @@ enum Commands {
         /// Instance ID to use
         #[arg(short, long, default_value = "default")]
         instance: String,
+
+        /// Blocks to mine to confirm the transaction, or `none` to leave it
+        /// unconfirmed (default: the instance's, set with `start --confirm`)
+        #[arg(long, value_name = "BLOCKS")]
+        confirm: Option<Confirmations>,
     },
 
     /// Transfer BTC from one account to another
@@ -104,6 +156,94 @@ enum Commands {
         /// Instance ID to use
         #[arg(short, long, default_value = "default")]
         instance: String,
+
+        /// Sign through a remote signing service (HSM/KMS gateway) instead of the node wallet
+        #[arg(long, env = "CF_SIGNER_URL")]
+        signer_url: Option<String>,
+
+        /// Bearer token for the remote signing service
+        #[arg(long, env = "CF_SIGNER_TOKEN", hide_env_values = true)]
+        signer_token: Option<String>,
+
+        /// Blocks to mine to confirm the transaction, or `none` to leave it
+        /// unconfirmed (default: the instance's, set with `start --confirm`)
+        #[arg(long, value_name = "BLOCKS")]
+        confirm: Option<Confirmations>,
+    },
+
+    /// Pay a BIP21 payment link (bitcoin:<address>?amount=...) from a funded account
+    Pay {
+        /// BIP21 URI to pay
+        uri: String,
+
+        /// Account address to pay from (default: first account with enough funds)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Amount of BTC to send, required if the URI has no amount
+        #[arg(long)]
+        amount: Option<f64>,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Blocks to mine to confirm the payment, or `none` to leave it
+        /// unconfirmed (default: the instance's, set with `start --confirm`)
+        #[arg(long, value_name = "BLOCKS")]
+        confirm: Option<Confirmations>,
+    },
+
+    /// Send a low-fee parent and a child that pays for it as one package (CPFP)
+    Cpfp {
+        /// Source account address (pays the parent)
+        from: String,
+
+        /// Destination account address (receives the parent output and spends it in the child)
+        to: String,
+
+        /// Amount of BTC the parent sends
+        amount: f64,
+
+        /// Parent fee in BTC (0 relies on package relay)
+        #[arg(long, default_value = "0")]
+        parent_fee: f64,
+
+        /// Child fee in BTC, covering both transactions
+        #[arg(long, default_value = "0.0002")]
+        child_fee: f64,
+
+        /// Mine a block to confirm the package
+        #[arg(long, default_value = "false")]
+        mine: bool,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Drop an unconfirmed transaction and its descendants from the mempool
+    Evict {
+        /// Transaction ID
+        txid: String,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Abandon a stuck wallet transaction so its inputs can be spent again
+    Abandon {
+        /// Transaction ID
+        txid: String,
+
+        /// Evict the transaction from the mempool first
+        #[arg(long, default_value = "false")]
+        evict: bool,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
     },
 
     /// Mine blocks to an address
@@ -116,11 +256,103 @@ enum Commands {
         #[arg(short, long)]
         address: Option<String>,
 
+        /// Node to mine on, in multi-node mode (0 is the wallet node)
+        #[arg(long, default_value = "0")]
+        node: usize,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Inspect or partition the nodes of a multi-node instance
+    Nodes {
+        #[command(subcommand)]
+        command: NodesCommands,
+    },
+
+    /// Override the node clock for locktime and median-time-past testing
+    Time {
+        /// Set the clock to a Unix timestamp
+        #[arg(long, conflicts_with_all = ["advance", "reset"])]
+        set: Option<i64>,
+
+        /// Move the clock forward by this many seconds
+        #[arg(long, conflicts_with = "reset")]
+        advance: Option<i64>,
+
+        /// Restore the system clock
+        #[arg(long, default_value = "false")]
+        reset: bool,
+
+        /// Blocks to mine after changing the clock (11 moves median time past up to it)
+        #[arg(short, long, default_value = "0")]
+        mine: u32,
+
         /// Instance ID to use
         #[arg(short, long, default_value = "default")]
         instance: String,
     },
 
+    /// Report address reuse, dust outputs, and unconfirmed chains among the accounts
+    Analyze {
+        /// Instance ID to analyze
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+
+        /// Exit with a failure code if anything is found
+        #[arg(long, default_value = "false")]
+        strict: bool,
+    },
+
+    /// Compare the wallet, UTXO set, and cached balances of the accounts
+    AuditBalances {
+        /// Instance ID to audit
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+
+        /// Exit with a failure code if any balances disagree
+        #[arg(long, default_value = "false")]
+        strict: bool,
+    },
+
+    /// Save, restore, or list chain snapshots
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// Derive account addresses from a mnemonic without starting a node
+    Derive {
+        /// Mnemonic phrase to derive from (a random one is generated if omitted)
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+
+        /// Number of accounts to derive
+        #[arg(short, long, default_value = "10")]
+        count: u32,
+
+        /// Derivation index of the first account
+        #[arg(long, default_value = "0")]
+        start_index: u32,
+
+        /// Address type: legacy, p2sh-segwit, bech32, or taproot
+        #[arg(long, value_name = "TYPE", default_value = "bech32")]
+        address_type: AddressType,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
     /// Show current configuration
     Config {
         /// Instance ID to show config for
@@ -136,27 +368,440 @@ enum Commands {
     },
 }
 
+// Options for starting a node, shared by `start` and `snapshot restore`
+#[derive(Args)]
+struct StartArgs {
+    /// Instance ID for isolation (allows multiple nodes with separate state)
+    #[arg(short, long, default_value = "default")]
+    instance: String,
+
+    /// Human-readable name for the instance
+    #[arg(short = 'n', long)]
+    name: Option<String>,
+
+    /// Number of accounts to generate (default: 10)
+    #[arg(short, long)]
+    accounts: Option<u32>,
+
+    /// Initial balance for each account in BTC (default: 10.0)
+    #[arg(short, long)]
+    balance: Option<f64>,
+
+    /// Built-in preset to start from: ci, demo, or load-test (explicit flags take precedence)
+    #[arg(long)]
+    preset: Option<String>,
+
+    /// Profile from chain-forge.toml (`[bitcoin.<NAME>]`) to start from;
+    /// explicit flags and presets take precedence
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// RPC port for the node (default: 18443, or derived from the instance ID
+    /// with `port_strategy = "hashed"`)
+    #[arg(long)]
+    rpc_port: Option<u16>,
+
+    /// P2P network port (default: 18444, or the RPC port + 1 when it's hashed)
+    #[arg(long)]
+    p2p_port: Option<u16>,
+
+    /// Optional mnemonic phrase to use for account generation
+    #[arg(short, long)]
+    mnemonic: Option<String>,
+
+    /// Derivation index of the first account (default: 0)
+    #[arg(long, default_value = "0")]
+    start_index: u32,
+
+    /// RPC username (default: chainforge)
+    #[arg(long)]
+    rpc_user: Option<String>,
+
+    /// RPC password (default: chainforge)
+    #[arg(long)]
+    rpc_password: Option<String>,
+
+    /// Show verbose bitcoind output
+    #[arg(short, long, default_value = "false")]
+    verbose: bool,
+
+    /// Keep instance data on stop (default: move it to the trash)
+    #[arg(long, default_value = "false")]
+    keep_data: bool,
+
+    /// Run the node in the background and return once it's ready
+    #[arg(long, default_value = "false")]
+    detach: bool,
+
+    /// Build and serve BIP157/158 compact block filters
+    #[arg(long, default_value = "false")]
+    block_filters: bool,
+
+    /// Account indices whose keys are held by an emulated external signer (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    external_signer: Vec<u32>,
+
+    /// Mine to and fund accounts from this account index instead of the wallet
+    #[arg(long, conflicts_with = "treasury_wif")]
+    treasury_account: Option<u32>,
+
+    /// Mine to and fund accounts from this external key (WIF) instead of the wallet
+    #[arg(long)]
+    treasury_wif: Option<String>,
+
+    /// Encrypt the node wallet with this passphrase
+    #[arg(long, env = "CF_BITCOIN_WALLET_PASSPHRASE", hide_env_values = true)]
+    wallet_passphrase: Option<String>,
+
+    /// Boot from a chain snapshot created with 'cf-bitcoin snapshot create'
+    #[arg(long)]
+    snapshot: Option<String>,
+
+    /// Run a private signet instead of regtest
+    #[arg(long, default_value = "false")]
+    signet: bool,
+
+    /// Hex-encoded script blocks must satisfy (default: 51, OP_TRUE, so blocks can be mined without a key)
+    #[arg(long, requires = "signet")]
+    signet_challenge: Option<String>,
+
+    /// Generate the signet's block-signing key and sign mined blocks with it
+    #[arg(
+        long,
+        default_value = "false",
+        requires = "signet",
+        conflicts_with = "signet_challenge"
+    )]
+    managed_signer: bool,
+
+    /// Mine a block every this many seconds while the node runs
+    #[arg(long, value_name = "SECS")]
+    block_interval: Option<u64>,
+
+    /// Blocks fund, transfer, and pay mine to confirm by default, or `none`
+    #[arg(long, value_name = "BLOCKS", default_value = "1")]
+    confirm: Confirmations,
+
+    /// Account address type: legacy, p2sh-segwit, bech32, or taproot
+    #[arg(long, value_name = "TYPE", default_value = "bech32")]
+    address_type: AddressType,
+
+    /// Extra chain parameter for bitcoind, as NAME=VALUE (repeatable, e.g. testactivationheight=segwit@1)
+    #[arg(long = "chain-param", value_name = "NAME=VALUE")]
+    chain_params: Vec<String>,
+
+    /// Number of connected nodes to run (node N uses the RPC and P2P ports plus 10*N)
+    #[arg(long, default_value = "1")]
+    nodes: u32,
+
+    /// Store account secrets in plaintext even if `encrypt_accounts` is set
+    /// and no passphrase is
+    #[arg(long, default_value = "false")]
+    insecure_plaintext: bool,
+}
+
+#[derive(Subcommand)]
+enum AccountsCommands {
+    /// Export account keys for Bitcoin Core, Sparrow, or Electrum
+    Export {
+        /// Export format
+        #[arg(short, long, value_enum)]
+        format: KeyExportFormat,
+
+        /// File to write to (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Instance ID to export
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum KeyExportFormat {
+    /// `importdescriptors` request with a private descriptor per account
+    Descriptors,
+    /// Electrum's private key import list (`p2wpkh:<WIF>` lines)
+    Electrum,
+}
+
+#[derive(Subcommand)]
+enum NodesCommands {
+    /// Show each node's ports, height, connections, and mempool size
+    List {
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Disconnect a node from the network so its chain and mempool diverge
+    Isolate {
+        /// Node to disconnect
+        #[arg(value_name = "NODE")]
+        node: usize,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Reconnect an isolated node; the chain with the most work wins
+    Rejoin {
+        /// Node to reconnect
+        #[arg(value_name = "NODE")]
+        node: usize,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Archive an instance's chain, wallet, and accounts
+    Create {
+        /// Instance ID to snapshot
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Snapshot name (default: <instance>-height-<N>)
+        #[arg(short = 'n', long)]
+        name: Option<String>,
+    },
+
+    /// Start an instance from a snapshot
+    Restore {
+        /// Snapshot to restore
+        #[arg(value_name = "NAME")]
+        from: String,
+
+        #[command(flatten)]
+        start: StartArgs,
+    },
+
+    /// List saved snapshots
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
 #[derive(Tabled)]
 struct AccountDisplay {
     #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Balance (BTC)")]
+    balance: String,
+}
+
+#[derive(Tabled)]
+struct SnapshotDisplay {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Instance")]
+    source_instance: String,
+    #[tabled(rename = "Height")]
+    height: u64,
+    #[tabled(rename = "Created")]
+    created_at: String,
+}
+
+#[derive(Tabled)]
+struct NodeDisplay {
+    #[tabled(rename = "Node")]
     index: usize,
+    #[tabled(rename = "RPC Port")]
+    rpc_port: u16,
+    #[tabled(rename = "P2P Port")]
+    p2p_port: u16,
+    #[tabled(rename = "Height")]
+    height: String,
+    #[tabled(rename = "Connections")]
+    connections: String,
+    #[tabled(rename = "Mempool")]
+    mempool: String,
+}
+
+#[derive(Tabled)]
+struct ScanDisplay {
+    #[tabled(rename = "Index")]
+    index: u32,
+    #[tabled(rename = "Path")]
+    path: String,
     #[tabled(rename = "Address")]
     address: String,
     #[tabled(rename = "Balance (BTC)")]
     balance: String,
+    #[tabled(rename = "In Instance")]
+    tracked: String,
+}
+
+#[derive(Tabled)]
+struct DerivedAccountDisplay {
+    #[tabled(rename = "Index")]
+    index: u32,
+    #[tabled(rename = "Path")]
+    path: String,
+    #[tabled(rename = "Address")]
+    address: String,
 }
 
 /// Get RPC client for a specific instance
 fn get_rpc_client_for_instance(instance_id: &str) -> Result<BitcoinRpcClient> {
-    let info = InstanceInfo::load(instance_id).map_err(|e| eyre::eyre!("{}", e))?;
+    let info = InstanceInfo::load(instance_id)?;
+
+    info.wallet_client()
+        .map_err(|e| eyre::eyre!("Failed to create RPC client: {}", e))
+}
 
-    BitcoinRpcClient::new_with_wallet(
-        info.rpc_url,
-        info.rpc_user,
-        info.rpc_password,
-        "chain-forge",
-    )
-    .map_err(|e| eyre::eyre!("Failed to create RPC client: {}", e))
+/// Evict a transaction from the mempool, exiting on failure
+///
+/// The node's clock is put back to the instance's `time` override, if any.
+fn evict(rpc_client: &BitcoinRpcClient, instance: &str, txid: &str) {
+    let restore_time = InstanceInfo::load(instance)
+        .ok()
+        .and_then(|info| info.mock_time)
+        .unwrap_or(0);
+
+    match rpc_client.evict_from_mempool(txid, restore_time) {
+        Ok(evicted) => {
+            println!(
+                "🗑️  Evicted {} transaction(s) from the mempool",
+                evicted.len()
+            );
+            for evicted in &evicted {
+                println!("   {}", evicted);
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::from_chain_error(&e).exit();
+        }
+    }
+}
+
+/// Format an account index, with its label if it has one
+fn account_index(index: usize, label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("{} ({})", index, label),
+        None => index.to_string(),
+    }
+}
+
+/// Print the findings of `cf-bitcoin analyze`
+fn print_analysis(analysis: &WalletAnalysis) {
+    println!(
+        "🔍 Analyzed {} account(s): {} warning(s)",
+        analysis.accounts_checked,
+        analysis.warning_count()
+    );
+
+    if !analysis.address_reuse.is_empty() {
+        println!();
+        println!("⚠️  Address reuse");
+        for reuse in &analysis.address_reuse {
+            println!(
+                "   Account {}: {} paid by {} transactions",
+                account_index(reuse.index, reuse.label.as_deref()),
+                reuse.address,
+                reuse.txids.len()
+            );
+        }
+    }
+
+    if !analysis.dust_outputs.is_empty() {
+        println!();
+        println!(
+            "⚠️  Dust outputs (below {} sats)",
+            analyze::DUST_THRESHOLD_SATS
+        );
+        for dust in &analysis.dust_outputs {
+            println!(
+                "   Account {}: {}:{} holds {} sats",
+                dust.index, dust.txid, dust.vout, dust.amount_sats
+            );
+        }
+    }
+
+    if !analysis.unconfirmed_chains.is_empty() {
+        println!();
+        println!("⚠️  Unconfirmed chains");
+        for chain in &analysis.unconfirmed_chains {
+            let limit = if chain.at_limit() {
+                " (at the mempool limit, cannot be spent until mined)"
+            } else {
+                ""
+            };
+            println!(
+                "   Account {}: {}:{} is {} transactions deep{}",
+                chain.index, chain.txid, chain.vout, chain.ancestor_count, limit
+            );
+        }
+    }
+
+    if analysis.is_clean() {
+        println!("✅ No address reuse, dust, or unconfirmed chains found");
+    }
+}
+
+#[derive(Tabled)]
+struct BalanceDisplay {
+    #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Cached (BTC)")]
+    cached: String,
+    #[tabled(rename = "Wallet (BTC)")]
+    wallet: String,
+    #[tabled(rename = "UTXO Set (BTC)")]
+    utxo_set: String,
+    #[tabled(rename = "Status")]
+    status: &'static str,
+}
+
+/// Print the report of `cf-bitcoin audit-balances`
+fn print_balance_report(report: &BalanceReport) {
+    let rows: Vec<BalanceDisplay> = report
+        .accounts
+        .iter()
+        .map(|account| BalanceDisplay {
+            index: account_index(account.index, account.label.as_deref()),
+            address: account.address.clone(),
+            cached: format_btc(account.cached),
+            wallet: format_btc(account.wallet),
+            utxo_set: format_btc(account.utxo_set),
+            status: if account.discrepancies.is_empty() {
+                "ok"
+            } else {
+                "mismatch"
+            },
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+
+    for account in &report.accounts {
+        for discrepancy in &account.discrepancies {
+            println!(
+                "⚠️  Account {}: {}",
+                account_index(account.index, account.label.as_deref()),
+                discrepancy.hint()
+            );
+        }
+    }
+
+    if report.is_clean() {
+        println!("✅ Balances of {} account(s) agree", report.accounts.len());
+    }
 }
 
 /// Get accounts storage for a specific instance
@@ -169,73 +814,117 @@ fn get_storage_for_instance(instance_id: &str) -> AccountsStorage {
     AccountsStorage::with_path(accounts_file)
 }
 
+/// An account as printed by `accounts --format json`, with its output
+/// descriptor added
+#[derive(Serialize)]
+struct AccountJson<'a> {
+    #[serde(flatten)]
+    account: &'a BitcoinAccount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    descriptor: Option<String>,
+}
+
+/// Pick the signer for a transfer source, if it is not signed by the node wallet
+///
+/// A remote signing service takes precedence; otherwise external signer
+/// accounts sign through the instance's emulated signer.
+fn signer_for(
+    instance_id: &str,
+    from: Option<&BitcoinAccount>,
+    signer_url: Option<String>,
+    signer_token: Option<String>,
+) -> Result<Option<Box<dyn Signer>>> {
+    match (signer_url, from) {
+        (Some(url), Some(account)) => Ok(Some(Box::new(
+            RemoteSigner::new(&url, &account.address).with_token(signer_token),
+        ))),
+        (Some(_), None) => Err(eyre::eyre!(
+            "Source address is not an account of instance '{}'",
+            instance_id
+        )),
+        (None, Some(account)) if account.external_signer => {
+            let keystore = SignerKeystore::with_path(
+                BitcoinConfig::with_instance(instance_id).signer_keystore_file(),
+            );
+            Ok(Some(Box::new(EmulatedSigner::load(
+                &keystore,
+                &account.address,
+            )?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Mine blocks to confirm a send operation, as many as `confirm` says or
+/// the instance's default
+fn confirm_send(rpc_client: &BitcoinRpcClient, instance_id: &str, confirm: Option<Confirmations>) {
+    let confirmations = confirm.unwrap_or_else(|| {
+        InstanceInfo::load(instance_id)
+            .map(|info| info.confirmations)
+            .unwrap_or_default()
+    });
+    if confirmations == Confirmations::NONE {
+        println!("⏸️  Leaving transaction unconfirmed (in the mempool)");
+        return;
+    }
+
+    println!("⛏️  Mining {} to confirm transaction...", confirmations);
+    match confirm::confirm(rpc_client, instance_id, confirmations) {
+        Ok(blocks) => {
+            if let Some(block) = blocks.last() {
+                println!("   Block mined: {}", block);
+            }
+        }
+        Err(e) => eprintln!("⚠️  Failed to mine confirmation blocks: {}", e),
+    }
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Bitcoin, instance_id, event);
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+    data_dir::init(&cli.data);
+    logging::init(&cli.log);
+    progress::show_startup_progress();
+
+    if let Err(e) = run(cli).await {
+        eprintln!("Error: {:?}", e);
+        ExitCode::from_report(&e).exit();
+    }
+}
 
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Start {
+        Commands::Start(args) => start(args).await?,
+
+        Commands::Attach {
+            network,
+            api_url,
             instance,
             name,
             accounts,
-            balance,
-            rpc_port,
-            p2p_port,
             mnemonic,
-            rpc_user,
-            rpc_password,
-            verbose,
-            keep_data,
-        } => {
-            // Validate instance name
-            if let Err(e) = validate_name(&instance) {
-                eprintln!("❌ Invalid instance name: {}", e);
-                std::process::exit(1);
-            }
-
-            // Validate display name if provided
-            if let Some(ref n) = name {
-                if let Err(e) = validate_name(n) {
-                    eprintln!("❌ Invalid display name: {}", e);
-                    std::process::exit(1);
-                }
-            }
-
-            // Create instance-specific config
-            let mut config = BitcoinConfig::with_instance(&instance);
-            config.rpc_url = format!("http://localhost:{}", rpc_port);
-            config.rpc_port = rpc_port;
-            config.p2p_port = p2p_port;
-            config.accounts = accounts;
-            config.initial_balance = balance;
-            config.mnemonic = mnemonic;
-            config.rpc_user = rpc_user;
-            config.rpc_password = rpc_password;
-            config.verbose = verbose;
-            config.name = name;
-
-            let mut provider = BitcoinProvider::with_config(config.clone());
-            provider.set_keep_data(keep_data);
-            provider.start(config)?;
-
-            println!("💡 Tip: Keep this terminal open to keep the node running");
-            println!(
-                "   Run 'cf-bitcoin accounts --instance {}' in another terminal to see your accounts",
-                instance
-            );
-            println!(
-                "   Run 'cf-bitcoin mine --instance {}' to mine new blocks",
-                instance
-            );
-            println!();
+        } => attach(network, api_url, instance, name, accounts, mnemonic)?,
 
-            // Keep the process alive
-            tokio::signal::ctrl_c().await?;
-            println!();
-            provider.stop()?;
-        }
+        Commands::Snapshot { command } => run_snapshot(command).await?,
+
+        Commands::Nodes { command } => run_nodes(command)?,
 
-        Commands::Accounts { instance, format } => {
+        Commands::Accounts {
+            command: Some(command),
+            ..
+        } => run_accounts(command)?,
+
+        Commands::Accounts {
+            command: None,
+            instance,
+            format,
+            amount,
+        } => {
             let storage = get_storage_for_instance(&instance);
 
             let mut accounts = storage.load()?;
@@ -249,8 +938,8 @@ async fn main() -> Result<()> {
             }
 
             // Update balances from blockchain
-            let balances_updated = match get_rpc_client_for_instance(&instance) {
-                Ok(rpc_client) => match rpc_client.update_balances(&mut accounts) {
+            let balances_updated = match InstanceInfo::load(&instance) {
+                Ok(info) => match info.update_balances(&mut accounts) {
                     Ok(_) => {
                         // Save updated balances back to storage
                         if let Err(e) = storage.save(&accounts) {
@@ -273,6 +962,13 @@ async fn main() -> Result<()> {
 
             match format {
                 OutputFormat::Json => {
+                    let accounts: Vec<AccountJson> = accounts
+                        .iter()
+                        .map(|account| AccountJson {
+                            account,
+                            descriptor: account.descriptor().ok(),
+                        })
+                        .collect();
                     println!("{}", serde_json::to_string_pretty(&accounts)?);
                 }
                 OutputFormat::Table => {
@@ -280,9 +976,9 @@ async fn main() -> Result<()> {
                         .iter()
                         .enumerate()
                         .map(|(i, acc)| AccountDisplay {
-                            index: i,
+                            index: account_index(i, acc.label.as_deref()),
                             address: acc.address.clone(),
-                            balance: format!("{:.8}", acc.balance),
+                            balance: amount.format(acc.balance, BTC_DECIMALS),
                         })
                         .collect();
 
@@ -299,10 +995,19 @@ async fn main() -> Result<()> {
             }
         }
 
+        Commands::Scan {
+            instance,
+            mnemonic,
+            start_index,
+            gap_limit,
+            format,
+        } => scan(instance, mnemonic, start_index, gap_limit, format)?,
+
         Commands::Fund {
             address,
             amount,
             instance,
+            confirm,
         } => {
             let rpc_client = get_rpc_client_for_instance(&instance)?;
 
@@ -311,32 +1016,40 @@ async fn main() -> Result<()> {
                     "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
                     instance
                 );
-                std::process::exit(1);
+                ExitCode::NodeUnreachable.exit();
             }
 
-            println!("💰 Sending {} BTC to {} (from wallet)...", amount, address);
+            let treasury = InstanceInfo::load(&instance)?.treasury;
+            match &treasury {
+                Some(treasury) => println!(
+                    "💰 Sending {} BTC to {} (from treasury {})...",
+                    amount, address, treasury
+                ),
+                None => println!("💰 Sending {} BTC to {} (from wallet)...", amount, address),
+            }
 
-            match rpc_client.send_to_address(&address, amount) {
+            match rpc_client.fund_address(treasury.as_deref(), &address, amount) {
                 Ok(txid) => {
                     println!("✅ Transaction sent!");
                     println!("   TxID: {}", txid);
+                    publish(
+                        &instance,
+                        ChainEvent::FundsDispensed {
+                            address: Some(address.clone()),
+                            amount,
+                        },
+                    );
 
-                    // Mine a block to confirm using a wallet address (not user account)
-                    println!("⛏️  Mining block to confirm transaction...");
-                    if let Ok(mining_addr) = rpc_client.get_new_address(Some("mining")) {
-                        if let Ok(blocks) = rpc_client.mine_blocks(1, &mining_addr) {
-                            println!("   Block mined: {}", blocks[0]);
-                        }
-                    }
+                    confirm_send(&rpc_client, &instance, confirm);
 
                     // Show updated balance
                     if let Ok(balance) = rpc_client.get_balance(&address) {
-                        println!("   New balance: {} BTC", balance);
+                        println!("   New balance: {} BTC", format_btc(balance));
                     }
                 }
                 Err(e) => {
-                    eprintln!("❌ Transaction failed: {}", e);
-                    std::process::exit(1);
+                    eprintln!("❌ {}", Message::TransactionFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
                 }
             }
         }
@@ -346,6 +1059,9 @@ async fn main() -> Result<()> {
             to,
             amount,
             instance,
+            signer_url,
+            signer_token,
+            confirm,
         } => {
             let rpc_client = get_rpc_client_for_instance(&instance)?;
 
@@ -354,7 +1070,7 @@ async fn main() -> Result<()> {
                     "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
                     instance
                 );
-                std::process::exit(1);
+                ExitCode::NodeUnreachable.exit();
             }
 
             println!("💸 Transferring {} BTC", amount);
@@ -364,52 +1080,275 @@ async fn main() -> Result<()> {
 
             // Show source balance before
             if let Ok(from_balance) = rpc_client.get_balance(&from) {
-                println!("   Source balance: {} BTC", from_balance);
+                println!("   Source balance: {} BTC", format_btc(from_balance));
             }
 
-            match rpc_client.send_from_address(&from, &to, amount) {
+            let from_account = get_storage_for_instance(&instance)
+                .load()?
+                .into_iter()
+                .find(|a| a.address == from);
+            let signer = signer_for(&instance, from_account.as_ref(), signer_url, signer_token)?;
+
+            let result = match (&signer, &from_account) {
+                (Some(signer), Some(account)) => {
+                    println!("🔏 Signing with external signer...");
+                    rpc_client.send_from_address_with_signer(account, &to, amount, signer.as_ref())
+                }
+                _ => rpc_client.send_from_address(&from, &to, amount),
+            };
+
+            match result {
                 Ok(txid) => {
                     println!("✅ Transaction sent!");
                     println!("   TxID: {}", txid);
 
-                    // Mine a block to confirm
-                    println!("⛏️  Mining block to confirm transaction...");
-                    if let Ok(mining_addr) = rpc_client.get_new_address(Some("mining")) {
-                        if let Ok(blocks) = rpc_client.mine_blocks(1, &mining_addr) {
-                            println!("   Block mined: {}", blocks[0]);
-                        }
-                    }
+                    confirm_send(&rpc_client, &instance, confirm);
 
                     // Show updated balances
                     println!();
                     println!("Updated balances:");
                     if let Ok(from_balance) = rpc_client.get_balance(&from) {
-                        println!("   From: {} BTC", from_balance);
+                        println!("   From: {} BTC", format_btc(from_balance));
                     }
                     if let Ok(to_balance) = rpc_client.get_balance(&to) {
-                        println!("   To:   {} BTC", to_balance);
+                        println!("   To:   {} BTC", format_btc(to_balance));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", Message::TransferFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+
+        Commands::Pay {
+            uri,
+            from,
+            amount,
+            instance,
+            confirm,
+        } => {
+            let info = InstanceInfo::load(&instance)?;
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let payment = match PaymentUri::parse(&uri).and_then(|payment| {
+                payment
+                    .require_network(info.chain.network())
+                    .map(|_| payment)
+            }) {
+                Ok(payment) => payment,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+            let Some(amount) = amount.or(payment.amount) else {
+                eprintln!("❌ The payment link has no amount; pass --amount");
+                ExitCode::Validation.exit();
+            };
+
+            let from = match from {
+                Some(from) => from,
+                None => {
+                    let accounts = get_storage_for_instance(&instance).load()?;
+                    let funded = accounts.iter().find(|account| {
+                        !account.external_signer
+                            && rpc_client
+                                .get_balance(&account.address)
+                                .map_or(false, |balance| balance >= amount + PAY_FEE_MARGIN)
+                    });
+                    match funded {
+                        Some(account) => account.address.clone(),
+                        None => {
+                            eprintln!(
+                                "❌ No account of instance '{}' has {} BTC to pay with",
+                                instance, amount
+                            );
+                            ExitCode::Validation.exit();
+                        }
                     }
                 }
+            };
+
+            println!("💸 Paying {} BTC", amount);
+            if let Some(label) = &payment.label {
+                println!("   Label:   {}", label);
+            }
+            if let Some(message) = &payment.message {
+                println!("   Message: {}", message);
+            }
+            println!("   From: {}", &from[..40.min(from.len())]);
+            println!(
+                "   To:   {}",
+                &payment.address[..40.min(payment.address.len())]
+            );
+            println!();
+
+            match rpc_client.send_from_address(&from, &payment.address, amount) {
+                Ok(txid) => {
+                    println!("✅ Payment sent!");
+                    println!("   TxID: {}", txid);
+
+                    confirm_send(&rpc_client, &instance, confirm);
+                }
                 Err(e) => {
-                    eprintln!("❌ Transfer failed: {}", e);
-                    std::process::exit(1);
+                    eprintln!("❌ {}", Message::PaymentFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
                 }
             }
         }
 
+        Commands::Cpfp {
+            from,
+            to,
+            amount,
+            parent_fee,
+            child_fee,
+            mine,
+            instance,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            println!("📦 Building CPFP package");
+            println!(
+                "   Parent: {} BTC from {} (fee {} BTC)",
+                amount,
+                &from[..40.min(from.len())],
+                parent_fee
+            );
+            println!(
+                "   Child:  spends to {} (fee {} BTC)",
+                &to[..40.min(to.len())],
+                child_fee
+            );
+            println!();
+
+            let package =
+                match rpc_client.build_cpfp_package(&from, &to, amount, parent_fee, child_fee) {
+                    Ok(package) => package,
+                    Err(e) => {
+                        eprintln!("❌ Failed to build package: {}", e);
+                        ExitCode::from_chain_error(&e).exit();
+                    }
+                };
+
+            let result = match rpc_client
+                .submit_package(&[package.parent_hex.clone(), package.child_hex.clone()])
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("❌ Package submission failed: {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            };
+
+            if !result.is_success() {
+                eprintln!("❌ Package rejected: {}", result.message);
+                for tx in result.transactions.iter().filter(|tx| tx.error.is_some()) {
+                    eprintln!("   {}: {}", tx.txid, tx.error.as_deref().unwrap_or(""));
+                }
+                ExitCode::Validation.exit();
+            }
+
+            println!("✅ Package accepted!");
+            println!("   Parent TxID: {}", package.parent_txid);
+            println!("   Child TxID:  {}", package.child_txid);
+            for txid in &result.replaced {
+                println!("   Replaced:    {}", txid);
+            }
+
+            if mine {
+                println!("⛏️  Mining block to confirm package...");
+                if let Ok(mining_addr) = rpc_client.get_new_address(Some("mining")) {
+                    if let Ok(blocks) = rpc_client.mine_blocks(1, &mining_addr) {
+                        println!("   Block mined: {}", blocks[0]);
+                        publish(&instance, ChainEvent::blocks_mined(1));
+                    }
+                }
+            }
+        }
+
+        Commands::Evict { txid, instance } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            evict(&rpc_client, &instance, &txid);
+        }
+
+        Commands::Abandon {
+            txid,
+            evict: evict_first,
+            instance,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            if evict_first {
+                evict(&rpc_client, &instance, &txid);
+            }
+
+            if let Err(e) = rpc_client.abandon_transaction(&txid) {
+                eprintln!("❌ {}", e);
+                if !evict_first {
+                    eprintln!("   Transactions in the mempool can't be abandoned; add --evict to drop it first");
+                }
+                ExitCode::from_chain_error(&e).exit();
+            }
+            println!("✅ Abandoned {}", txid);
+            println!("   Its inputs can be spent again");
+        }
+
         Commands::Mine {
             blocks,
             address,
+            node,
             instance,
         } => {
             let rpc_client = get_rpc_client_for_instance(&instance)?;
+            let node_client = match InstanceInfo::load(&instance)?.node_client(node) {
+                Ok(client) => client,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
 
             if !rpc_client.is_node_running() {
                 eprintln!(
                     "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
                     instance
                 );
-                std::process::exit(1);
+                ExitCode::NodeUnreachable.exit();
             }
 
             // Use provided address or a wallet-generated address
@@ -420,31 +1359,226 @@ async fn main() -> Result<()> {
                     // Get a wallet address for mining (not a user account)
                     rpc_client
                         .get_new_address(Some("mining"))
-                        .map_err(|e| eyre::eyre!("Failed to get mining address: {}", e))?
+                        .wrap_err("Failed to get mining address")?
                 }
             };
 
-            println!(
-                "⛏️  Mining {} block(s) to {}...",
-                blocks,
-                &mining_address[..20]
-            );
+            if node == 0 {
+                println!(
+                    "⛏️  Mining {} block(s) to {}...",
+                    blocks,
+                    &mining_address[..20]
+                );
+            } else {
+                println!(
+                    "⛏️  Mining {} block(s) to {} on node {}...",
+                    blocks,
+                    &mining_address[..20],
+                    node
+                );
+            }
 
-            match rpc_client.mine_blocks(blocks, &mining_address) {
+            match node_client.mine_blocks(blocks, &mining_address) {
                 Ok(block_hashes) => {
                     println!("✅ Mined {} block(s)!", block_hashes.len());
+                    publish(
+                        &instance,
+                        ChainEvent::blocks_mined(block_hashes.len() as u64),
+                    );
                     for (i, hash) in block_hashes.iter().enumerate() {
                         println!("   Block {}: {}...", i + 1, &hash[..16]);
                     }
 
                     // Show current block height
-                    if let Ok(count) = rpc_client.get_block_count() {
+                    if let Ok(count) = node_client.get_block_count() {
                         println!("   Current height: {}", count);
                     }
                 }
                 Err(e) => {
-                    eprintln!("❌ Mining failed: {}", e);
-                    std::process::exit(1);
+                    eprintln!("❌ {}", Message::MiningFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+
+        Commands::Time {
+            set,
+            advance,
+            reset,
+            mine,
+            instance,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let mut info = InstanceInfo::load(&instance)?;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+
+            let mock_time = match (set, advance, reset) {
+                (Some(timestamp), _, _) => Some(timestamp),
+                (None, Some(seconds), _) => Some(info.mock_time.unwrap_or(now) + seconds),
+                (None, None, true) => None,
+                (None, None, false) => info.mock_time,
+            };
+
+            if set.is_some() || advance.is_some() || reset {
+                if let Some(timestamp) = mock_time {
+                    if timestamp <= 0 {
+                        eprintln!("❌ Invalid timestamp: {}", timestamp);
+                        ExitCode::Validation.exit();
+                    }
+                }
+
+                rpc_client.set_mock_time(mock_time.unwrap_or(0))?;
+                info.mock_time = mock_time;
+                info.save()?;
+
+                match mock_time {
+                    Some(timestamp) => println!("🕐 Node clock set to {}", timestamp),
+                    None => println!("🕐 Node clock restored to system time"),
+                }
+            }
+
+            if mine > 0 {
+                let mining_address = rpc_client
+                    .get_new_address(Some("mining"))
+                    .wrap_err("Failed to get mining address")?;
+                rpc_client.mine_blocks(mine, &mining_address)?;
+                publish(&instance, ChainEvent::blocks_mined(mine as u64));
+                println!("⛏️  Mined {} block(s)", mine);
+            }
+
+            println!();
+            match mock_time {
+                Some(timestamp) => println!("   Node time:        {} (mocked)", timestamp),
+                None => println!("   Node time:        {} (system)", now),
+            }
+            println!(
+                "   Median time past: {}",
+                rpc_client.get_median_time_past()?
+            );
+        }
+
+        Commands::Analyze {
+            instance,
+            format,
+            strict,
+        } => {
+            let analysis = match analyze_instance(&instance) {
+                Ok(analysis) => analysis,
+                Err(ChainError::NotRunning) => {
+                    eprintln!(
+                        "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                        instance
+                    );
+                    ExitCode::NodeUnreachable.exit();
+                }
+                Err(e) => {
+                    eprintln!("❌ Analysis failed: {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&analysis)?);
+                }
+                OutputFormat::Table => print_analysis(&analysis),
+            }
+
+            if strict && !analysis.is_clean() {
+                ExitCode::Failure.exit();
+            }
+        }
+
+        Commands::AuditBalances {
+            instance,
+            format,
+            strict,
+        } => {
+            let report = match reconcile_instance(&instance) {
+                Ok(report) => report,
+                Err(ChainError::NotRunning) => {
+                    eprintln!(
+                        "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                        instance
+                    );
+                    ExitCode::NodeUnreachable.exit();
+                }
+                Err(e) => {
+                    eprintln!("❌ Balance audit failed: {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+                OutputFormat::Table => print_balance_report(&report),
+            }
+
+            if strict && !report.is_clean() {
+                ExitCode::Failure.exit();
+            }
+        }
+
+        Commands::Derive {
+            mnemonic,
+            count,
+            start_index,
+            address_type,
+            format,
+        } => {
+            let generator = derive_generator(mnemonic.as_deref(), start_index, address_type)?;
+            let accounts = generator.generate_accounts(count)?;
+
+            match format {
+                OutputFormat::Json => {
+                    let derived: Vec<serde_json::Value> = accounts
+                        .iter()
+                        .zip(start_index..)
+                        .map(|(acc, i)| {
+                            serde_json::json!({
+                                "index": i,
+                                "derivationPath": acc.derivation_path,
+                                "address": acc.address,
+                                "publicKey": acc.public_key,
+                                "descriptor": acc.descriptor().ok(),
+                            })
+                        })
+                        .collect();
+                    let output = serde_json::json!({
+                        "mnemonic": generator.mnemonic_phrase(),
+                        "accounts": derived,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
+                OutputFormat::Table => {
+                    println!("🔑 Mnemonic: {}", generator.mnemonic_phrase());
+                    println!();
+
+                    let display_accounts: Vec<DerivedAccountDisplay> = accounts
+                        .iter()
+                        .zip(start_index..)
+                        .map(|(acc, i)| DerivedAccountDisplay {
+                            index: i,
+                            path: acc.derivation_path.clone().unwrap_or_default(),
+                            address: acc.address.clone(),
+                        })
+                        .collect();
+
+                    let table = Table::new(display_accounts).to_string();
+                    println!("{}", table);
                 }
             }
         }
@@ -472,6 +1606,8 @@ async fn main() -> Result<()> {
                     println!("  RPC URL: {}", info.rpc_url);
                     println!("  RPC Port: {}", info.rpc_port);
                     println!("  P2P Port: {}", info.p2p_port);
+                    println!("  Confirmations: {}", info.confirmations);
+                    println!("  Address type: {}", info.address_type);
                     println!("  Accounts: {}", info.accounts_count);
                 }
                 Err(_) => {
@@ -507,20 +1643,760 @@ async fn main() -> Result<()> {
         }
 
         Commands::Stop { instance } => {
-            println!("Note: Use Ctrl+C to stop the node running in 'start' mode");
-            println!(
-                "      Instance '{}' should be stopped from its terminal",
-                instance
-            );
+            if let Some(network) = InstanceInfo::load(&instance)
+                .ok()
+                .and_then(|info| info.network)
+            {
+                public::detach(&instance)?;
+                println!("✅ Detached instance '{}' from {}", instance, network);
+                return Ok(());
+            }
+
+            let instance_dir = BitcoinConfig::with_instance(&instance).instance_dir();
+            let stopped = match daemon::stop(&instance_dir, STOP_TIMEOUT) {
+                Ok(stopped) => stopped,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Failure.exit();
+                }
+            };
+
+            if let Some(pid) = stopped {
+                println!("✅ Stopped instance '{}' (pid {})", instance, pid);
+                // A node that had to be killed couldn't update the registry
+                let _ = NodeRegistry::new().update_status(
+                    &NodeRegistry::node_id(ChainType::Bitcoin, &instance),
+                    NodeStatus::Stopped,
+                );
+            } else {
+                println!("Note: Use Ctrl+C to stop the node running in 'start' mode");
+                println!(
+                    "      Instance '{}' should be stopped from its terminal",
+                    instance
+                );
+            }
 
             // Mark instance as stopped if info exists
             if let Ok(mut info) = InstanceInfo::load(&instance) {
                 info.running = false;
                 let _ = info.save();
-                println!("      Marked instance '{}' as stopped", instance);
+                if stopped.is_none() {
+                    println!("      Marked instance '{}' as stopped", instance);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Derive accounts for a public test network and record them as an instance
+fn attach(
+    network: String,
+    api_url: Option<String>,
+    instance: String,
+    name: Option<String>,
+    accounts: u32,
+    mnemonic: Option<String>,
+) -> Result<()> {
+    let network = match network.as_str() {
+        "testnet" => PublicNetwork::Testnet,
+        "signet" => PublicNetwork::Signet,
+        _ => PublicNetwork::Testnet4,
+    };
+
+    if let Err(e) = validate_name(&instance) {
+        eprintln!("❌ {}", Message::InvalidInstanceName(e.to_string()));
+        ExitCode::Validation.exit();
+    }
+    if let Some(ref n) = name {
+        if let Err(e) = validate_name(n) {
+            eprintln!("❌ {}", Message::InvalidDisplayName(e.to_string()));
+            ExitCode::Validation.exit();
+        }
+    }
+
+    let mut config = BitcoinConfig::with_instance(&instance);
+    config.rpc_url = api_url.unwrap_or_else(|| network.default_api_url().to_string());
+    config.accounts = accounts;
+    config.mnemonic = mnemonic;
+    config.name = name;
+
+    println!("🌐 Attaching instance '{}' to {}...", instance, network);
+    println!("   API URL: {}", config.rpc_url);
+    println!();
+
+    let accounts = public::attach(&config, network)?;
+
+    let display_accounts: Vec<AccountDisplay> = accounts
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| AccountDisplay {
+            index: account_index(i, acc.label.as_deref()),
+            address: acc.address.clone(),
+            balance: format_btc(acc.balance),
+        })
+        .collect();
+    println!("{}", Table::new(display_accounts));
+    println!();
+    println!(
+        "✅ Instance '{}' tracks {} accounts on {}",
+        instance,
+        accounts.len(),
+        network
+    );
+    println!("   Fund the accounts from a {} faucet", network);
+    println!("   Run 'cf-bitcoin stop --instance {}' to detach", instance);
+
+    Ok(())
+}
+
+/// Start a node and keep it running until Ctrl+C
+async fn start(args: StartArgs) -> Result<()> {
+    let StartArgs {
+        instance,
+        name,
+        accounts,
+        balance,
+        rpc_port,
+        p2p_port,
+        mnemonic,
+        start_index,
+        rpc_user,
+        rpc_password,
+        verbose,
+        keep_data,
+        detach,
+        block_filters,
+        external_signer,
+        treasury_account,
+        treasury_wif,
+        wallet_passphrase,
+        preset,
+        profile,
+        snapshot,
+        signet,
+        signet_challenge,
+        managed_signer,
+        block_interval,
+        confirm,
+        address_type,
+        chain_params,
+        nodes,
+        insecure_plaintext,
+    } = args;
+
+    // Presets, then the profile, fill in anything not given explicitly
+    let preset = match Preset::resolve(preset.as_deref()) {
+        Ok(preset) => preset,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::Validation.exit();
+        }
+    };
+    let profile = match profile.map(|name| Config::load().and_then(|c| c.bitcoin_profile(&name))) {
+        Some(Ok(profile)) => Some(profile),
+        Some(Err(e)) => {
+            eprintln!("❌ {}", e);
+            ExitCode::Validation.exit();
+        }
+        None => None,
+    };
+    let accounts = accounts
+        .or(preset.map(|p| p.accounts))
+        .or(profile.as_ref().map(|p| p.accounts))
+        .unwrap_or(10);
+    let balance = balance
+        .or(preset.map(|p| p.bitcoin_balance))
+        .or(profile.as_ref().map(|p| p.initial_balance))
+        .unwrap_or(10.0);
+    let rpc_port = rpc_port.or(profile.as_ref().map(|p| p.rpc_port));
+    let p2p_port = p2p_port.or(profile.as_ref().map(|p| p.p2p_port));
+    let rpc_user = rpc_user
+        .or(profile.as_ref().map(|p| p.rpc_user.clone()))
+        .unwrap_or_else(|| "chainforge".to_string());
+    let rpc_password = rpc_password
+        .or(profile.as_ref().map(|p| p.rpc_password.clone()))
+        .unwrap_or_else(|| "chainforge".to_string());
+    // Without --profile, `[bitcoin.default]` still decides what stop does
+    // with the instance data
+    let cleanup = match &profile {
+        Some(profile) => profile.cleanup(),
+        None => Config::load()
+            .and_then(|c| c.bitcoin_profile("default"))
+            .map(|p| p.cleanup())
+            .unwrap_or_default(),
+    };
+
+    // Validate instance name
+    if let Err(e) = validate_name(&instance) {
+        eprintln!("❌ {}", Message::InvalidInstanceName(e.to_string()));
+        ExitCode::Validation.exit();
+    }
+
+    // Validate display name if provided
+    if let Some(ref n) = name {
+        if let Err(e) = validate_name(n) {
+            eprintln!("❌ {}", Message::InvalidDisplayName(e.to_string()));
+            ExitCode::Validation.exit();
+        }
+    }
+
+    if nodes == 0 {
+        eprintln!("❌ --nodes must be at least 1");
+        ExitCode::Validation.exit();
+    }
+
+    if block_interval == Some(0) {
+        eprintln!("❌ --block-interval must be at least 1 second");
+        ExitCode::Validation.exit();
+    }
+
+    // Account secrets are encrypted with CHAIN_FORGE_PASSPHRASE when set;
+    // `encrypt_accounts` makes that mandatory
+    if let Err(e) = Config::load().and_then(|c| c.check_accounts_protection(insecure_plaintext)) {
+        eprintln!("❌ {}", e);
+        ExitCode::Validation.exit();
+    }
+
+    // Ports not given explicitly follow the configured port strategy
+    let (rpc_port, p2p_port) = match rpc_port {
+        Some(rpc_port) => (rpc_port, p2p_port.unwrap_or(18444)),
+        None => {
+            let span = u16::try_from(nodes - 1)
+                .ok()
+                .and_then(|peers| peers.checked_mul(NODE_PORT_STRIDE))
+                .and_then(|offset| offset.checked_add(2))
+                .unwrap_or(u16::MAX);
+            match Config::load()
+                .and_then(|c| c.instance_port(ChainType::Bitcoin, &instance, 18443, span))
+            {
+                Ok(port) => (port, p2p_port.unwrap_or(port + 1)),
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            }
+        }
+    };
+
+    let chain = if managed_signer {
+        match BitcoinChain::managed_signet() {
+            Ok(chain) => chain,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Failure.exit();
             }
         }
+    } else if signet {
+        match BitcoinChain::signet(signet_challenge) {
+            Ok(chain) => chain,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+        }
+    } else {
+        BitcoinChain::Regtest
+    };
+
+    if detach {
+        let instance_dir = BitcoinConfig::with_instance(&instance).instance_dir();
+        let node_id = NodeRegistry::node_id(ChainType::Bitcoin, &instance);
+        println!("⏳ Starting instance '{}' in the background...", instance);
+        match daemon::detach(&instance_dir, &node_id, DETACH_TIMEOUT) {
+            Ok(pid) => {
+                println!("✅ Node running in the background (pid {})", pid);
+                println!("   Logs: {}", daemon::log_path(&instance_dir).display());
+                println!(
+                    "   Run 'cf-bitcoin stop --instance {}' to stop it",
+                    instance
+                );
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Failure.exit();
+            }
+        }
+        return Ok(());
     }
 
+    // Create instance-specific config
+    let mut config = BitcoinConfig::with_instance(&instance);
+    config.rpc_url = format!("http://localhost:{}", rpc_port);
+    config.rpc_port = rpc_port;
+    config.p2p_port = p2p_port;
+    config.accounts = accounts;
+    config.initial_balance = balance;
+    config.mnemonic = mnemonic;
+    config.start_index = start_index;
+    config.rpc_user = rpc_user;
+    config.rpc_password = rpc_password;
+    config.verbose = verbose;
+    config.name = name;
+    config.block_filter_index = block_filters;
+    config.external_signers = external_signer;
+    config.treasury = treasury_account
+        .map(Treasury::Account)
+        .or(treasury_wif.map(Treasury::Wif));
+    config.account_labels = preset.map(|p| p.labels()).unwrap_or_default();
+    config.wallet_passphrase = wallet_passphrase;
+    config.snapshot = snapshot;
+    config.chain = chain;
+    config.chain_params = chain_params;
+    config.nodes = nodes;
+    config.block_interval = block_interval;
+    config.confirmations = confirm;
+    config.address_type = address_type;
+
+    let instance_dir = config.instance_dir();
+    logging::log_to_instance(&instance_dir);
+    let mut provider = BitcoinProvider::with_config(config.clone());
+    provider.set_cleanup(cleanup);
+    if keep_data {
+        provider.set_keep_data(true);
+    }
+    provider.start(config)?;
+
+    println!("💡 Tip: Keep this terminal open to keep the node running");
+    println!(
+        "   Run 'cf-bitcoin accounts --instance {}' in another terminal to see your accounts",
+        instance
+    );
+    println!(
+        "   Run 'cf-bitcoin mine --instance {}' to mine new blocks",
+        instance
+    );
+    println!();
+
+    // Keep the process alive
+    tokio::signal::ctrl_c().await?;
+    println!();
+    provider.stop()?;
+    daemon::clear_own_pid(&instance_dir);
+
     Ok(())
 }
+
+/// Account generator for `derive`, from `mnemonic` or a random one
+fn derive_generator(
+    mnemonic: Option<&str>,
+    start_index: u32,
+    address_type: AddressType,
+) -> Result<AccountGenerator> {
+    let generator = match mnemonic {
+        Some(phrase) => AccountGenerator::from_mnemonic(phrase)?,
+        None => AccountGenerator::new()?,
+    };
+    Ok(generator
+        .with_address_type(address_type)?
+        .with_start_index(start_index))
+}
+
+/// Check derived accounts for funds until `gap_limit` in a row are empty
+fn scan(
+    instance: String,
+    mnemonic: Option<String>,
+    start_index: u32,
+    gap_limit: u32,
+    format: OutputFormat,
+) -> Result<()> {
+    let info = InstanceInfo::load(&instance)?;
+    let rpc_client = get_rpc_client_for_instance(&instance)?;
+    if !rpc_client.is_node_running() {
+        eprintln!(
+            "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+            instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    // Accounts the instance already has are marked in the output
+    let known = get_storage_for_instance(&instance)
+        .load()
+        .unwrap_or_default();
+    let mnemonic = mnemonic.or_else(|| known.iter().find_map(|acc| acc.mnemonic.clone()));
+    let Some(mnemonic) = mnemonic else {
+        eprintln!(
+            "❌ Instance '{}' has no mnemonic to scan. Pass one with --mnemonic",
+            instance
+        );
+        ExitCode::Validation.exit();
+    };
+    let generator = AccountGenerator::from_mnemonic_with_network(&mnemonic, info.chain.network())?
+        .with_address_type(info.address_type)?;
+
+    // The whole batch is checked with a single UTXO set scan
+    let funded = gap_scan(
+        start_index,
+        gap_limit,
+        |index| generator.derive_account(index),
+        |batch: &[BitcoinAccount]| {
+            let addresses: Vec<&str> = batch.iter().map(|acc| acc.address.as_str()).collect();
+            rpc_client.get_address_balances(&addresses)
+        },
+    )?;
+    let tracked = |acc: &BitcoinAccount| known.iter().any(|k| k.address == acc.address);
+
+    match format {
+        OutputFormat::Json => {
+            let json: Vec<serde_json::Value> = funded
+                .iter()
+                .map(|found| {
+                    serde_json::json!({
+                        "index": found.index,
+                        "derivationPath": found.account.derivation_path,
+                        "address": found.account.address,
+                        "balance": found.balance,
+                        "tracked": tracked(&found.account),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Table => {
+            if funded.is_empty() {
+                println!(
+                    "No funded accounts found from index {} (gap limit {})",
+                    start_index, gap_limit
+                );
+                return Ok(());
+            }
+            let display: Vec<ScanDisplay> = funded
+                .iter()
+                .map(|found| ScanDisplay {
+                    index: found.index,
+                    path: found.account.derivation_path.clone().unwrap_or_default(),
+                    address: found.account.address.clone(),
+                    balance: format_btc(found.balance),
+                    tracked: if tracked(&found.account) { "yes" } else { "no" }.to_string(),
+                })
+                .collect();
+            println!("{}", Table::new(display));
+            println!();
+            println!("✅ Found {} funded accounts", funded.len());
+            if let Some(last) = funded.iter().rev().find(|f| !tracked(&f.account)) {
+                println!(
+                    "   Restart with '--mnemonic <PHRASE> --accounts {}' to track them all",
+                    last.index + 1
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_accounts(command: AccountsCommands) -> Result<()> {
+    match command {
+        AccountsCommands::Export {
+            format,
+            output,
+            instance,
+        } => {
+            let accounts = get_storage_for_instance(&instance).load()?;
+            if accounts.is_empty() {
+                eprintln!(
+                    "❌ No accounts found for instance '{}'. Run 'cf-bitcoin start --instance {}' first.",
+                    instance, instance
+                );
+                ExitCode::Validation.exit();
+            }
+
+            // Accounts whose keys can't be exported in this format are
+            // skipped rather than failing the whole export
+            let mut exported = Vec::new();
+            for (i, account) in accounts.iter().enumerate() {
+                let key = match format {
+                    KeyExportFormat::Descriptors => account.private_descriptor(),
+                    KeyExportFormat::Electrum => account.electrum_key(),
+                };
+                match key {
+                    Ok(key) => exported.push((account_index(i, account.label.as_deref()), key)),
+                    Err(e) => eprintln!("⚠️  Skipping {}: {}", account.address, e),
+                }
+            }
+
+            let contents = match format {
+                // Timestamp 0 makes the importing wallet rescan for the
+                // accounts' history
+                KeyExportFormat::Descriptors => {
+                    let request: Vec<serde_json::Value> = exported
+                        .iter()
+                        .map(|(index, desc)| {
+                            serde_json::json!({
+                                "desc": desc,
+                                "timestamp": 0,
+                                "label": format!("chain-forge {}", index),
+                            })
+                        })
+                        .collect();
+                    serde_json::to_string_pretty(&request)?
+                }
+                KeyExportFormat::Electrum => exported
+                    .iter()
+                    .map(|(_, key)| key.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+
+            match output {
+                Some(path) => {
+                    write_secret_file(&path, format!("{}\n", contents).as_bytes())
+                        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!(
+                        "✅ Exported {} accounts to {}",
+                        exported.len(),
+                        path.display()
+                    );
+                }
+                None => println!("{}", contents),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_snapshot(command: SnapshotCommands) -> Result<()> {
+    match command {
+        SnapshotCommands::Create { instance, name } => {
+            if let Some(Err(e)) = name.as_deref().map(validate_name) {
+                eprintln!("❌ {}", Message::InvalidSnapshotName(e.to_string()));
+                ExitCode::Validation.exit();
+            }
+
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+            if !rpc_client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Bitcoin node is not running. Start it with 'cf-bitcoin start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            // Make sure the chain on disk matches the tip before archiving
+            let height = rpc_client.flush_chain_state()?;
+            let name = name.unwrap_or_else(|| format!("{}-height-{}", instance, height));
+
+            let chain = InstanceInfo::load(&instance)?.chain;
+            let instance_dir = BitcoinConfig::with_instance(&instance).instance_dir();
+            let spinner = progress::spinner(format!("Archiving the chain at height {}", height));
+            let result = BitcoinSnapshot::create(&name, &instance, height, chain, &instance_dir);
+            spinner.finish_and_clear();
+            let snapshot = result?;
+            println!("✅ Snapshot '{}' saved at height {}", snapshot.name, height);
+            println!("   Archive: {}", snapshot.archive_path().display());
+            println!();
+            println!("💡 Start an instance from it with:");
+            println!(
+                "   cf-bitcoin snapshot restore {} --instance <instance> --rpc-port <port> --p2p-port <port>",
+                snapshot.name
+            );
+        }
+
+        SnapshotCommands::Restore { from, start: args } => {
+            if let Err(e) = BitcoinSnapshot::load(&from) {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+            start(StartArgs {
+                snapshot: Some(from),
+                ..args
+            })
+            .await?;
+        }
+
+        SnapshotCommands::List { format } => {
+            let snapshots = BitcoinSnapshot::list()?;
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&snapshots)?);
+                }
+                OutputFormat::Table => {
+                    if snapshots.is_empty() {
+                        println!(
+                            "No snapshots found. Create one with 'cf-bitcoin snapshot create'"
+                        );
+                        return Ok(());
+                    }
+                    let display: Vec<SnapshotDisplay> = snapshots
+                        .into_iter()
+                        .map(|s| SnapshotDisplay {
+                            name: s.name,
+                            source_instance: s.source_instance,
+                            height: s.height,
+                            created_at: s.created_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                        })
+                        .collect();
+                    println!("{}", Table::new(display));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a `nodes` subcommand
+fn run_nodes(command: NodesCommands) -> Result<()> {
+    let (instance, node, active) = match command {
+        NodesCommands::List { instance, format } => return list_nodes(&instance, format),
+        NodesCommands::Isolate { node, instance } => (instance, node, false),
+        NodesCommands::Rejoin { node, instance } => (instance, node, true),
+    };
+
+    let info = InstanceInfo::load(&instance)?;
+    let client = match info.node_client(node) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::Validation.exit();
+        }
+    };
+    if !client.is_node_running() {
+        eprintln!(
+            "❌ Error: Node {} is not running. Start it with 'cf-bitcoin start --instance {}'",
+            node, instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    // Added peers are only retried every minute, so reconnect right away
+    let reconnect = || -> std::result::Result<(), ChainError> {
+        client.set_network_active(true)?;
+        let p2p_ports = std::iter::once(info.p2p_port).chain(info.peers.iter().map(|p| p.p2p_port));
+        for (index, port) in p2p_ports.enumerate() {
+            if index != node {
+                client.connect_peer(&format!("127.0.0.1:{}", port))?;
+            }
+        }
+        Ok(())
+    };
+    let result = if active {
+        reconnect()
+    } else {
+        client.set_network_active(false)
+    };
+
+    match result {
+        Ok(()) if active => {
+            println!("🔗 Node {} rejoined the network", node);
+            println!("   Nodes sync to the chain with the most work within a few seconds");
+        }
+        Ok(()) => {
+            println!("✂️  Node {} is isolated", node);
+            println!("   Blocks and transactions it creates stay local until it rejoins");
+            println!(
+                "   Run 'cf-bitcoin nodes rejoin {} --instance {}' to reconnect it",
+                node, instance
+            );
+        }
+        Err(e) => {
+            eprintln!("❌ Changing node {}'s network state failed: {}", node, e);
+            ExitCode::from_chain_error(&e).exit();
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the state of every node of an instance
+fn list_nodes(instance: &str, format: OutputFormat) -> Result<()> {
+    #[derive(Serialize)]
+    struct NodeJson {
+        index: usize,
+        rpc_url: String,
+        rpc_port: u16,
+        p2p_port: u16,
+        height: Option<u64>,
+        connections: Option<usize>,
+        mempool: Option<u64>,
+    }
+
+    let info = InstanceInfo::load(instance)?;
+    let ports = std::iter::once((info.rpc_url.clone(), info.rpc_port, info.p2p_port)).chain(
+        info.peers
+            .iter()
+            .map(|peer| (peer.rpc_url.clone(), peer.rpc_port, peer.p2p_port)),
+    );
+
+    // Nodes that can't be reached are listed without their state
+    let nodes: Vec<NodeJson> = ports
+        .enumerate()
+        .map(|(index, (rpc_url, rpc_port, p2p_port))| {
+            let client = info.node_client(index).ok();
+            NodeJson {
+                index,
+                rpc_url,
+                rpc_port,
+                p2p_port,
+                height: client.as_ref().and_then(|c| c.get_block_count().ok()),
+                connections: client.as_ref().and_then(|c| c.get_connection_count().ok()),
+                mempool: client.as_ref().and_then(|c| c.get_mempool_size().ok()),
+            }
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&nodes)?),
+        OutputFormat::Table => {
+            let show = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            let display: Vec<NodeDisplay> = nodes
+                .into_iter()
+                .map(|node| NodeDisplay {
+                    index: node.index,
+                    rpc_port: node.rpc_port,
+                    p2p_port: node.p2p_port,
+                    height: show(node.height.map(|h| h.to_string())),
+                    connections: show(node.connections.map(|c| c.to_string())),
+                    mempool: show(node.mempool.map(|m| m.to_string())),
+                })
+                .collect();
+            println!("{}", Table::new(display));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_derive_taproot() {
+        let cli = Cli::try_parse_from([
+            "cf-bitcoin",
+            "derive",
+            "--mnemonic",
+            TEST_MNEMONIC,
+            "--count",
+            "1",
+            "--address-type",
+            "taproot",
+        ])
+        .unwrap();
+        let Commands::Derive {
+            mnemonic,
+            start_index,
+            address_type,
+            ..
+        } = cli.command
+        else {
+            panic!("expected the derive command");
+        };
+
+        let account = derive_generator(mnemonic.as_deref(), start_index, address_type)
+            .unwrap()
+            .generate_accounts(1)
+            .unwrap()
+            .remove(0);
+        // BIP86 path and a regtest P2TR address
+        assert_eq!(account.derivation_path.as_deref(), Some("m/86'/0'/0'/0/0"));
+        assert!(account.address.starts_with("bcrt1p"), "{}", account.address);
+    }
+}
@@ -0,0 +1,267 @@
+//! Wallet hygiene checks for an instance's accounts.
+//!
+//! Looks for the habits a well-behaved wallet avoids: paying the same address
+//! more than once, leaving outputs too small to spend economically, and
+//! building long chains of unconfirmed transactions that the mempool will
+//! eventually refuse. Useful for checking that an app under test follows
+//! good practice before it meets a real network.
+
+use crate::{BitcoinConfig, InstanceInfo};
+use chain_forge_bitcoin_accounts::{AccountsStorage, BitcoinAccount};
+use chain_forge_bitcoin_rpc::{BitcoinRpcClient, BitcoinUtxo};
+use chain_forge_common::{ChainError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Smallest P2WPKH output Bitcoin Core relays at the default dust relay fee
+pub const DUST_THRESHOLD_SATS: u64 = 294;
+
+/// Bitcoin Core's default limit on unconfirmed ancestors, the transaction
+/// itself included
+pub const MEMPOOL_ANCESTOR_LIMIT: u64 = 25;
+
+/// Address that has been paid by more than one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressReuse {
+    pub index: usize,
+    pub address: String,
+    pub label: Option<String>,
+    /// Transactions that paid the address
+    pub txids: Vec<String>,
+}
+
+/// Unspent output below the dust threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustOutput {
+    pub index: usize,
+    pub address: String,
+    pub txid: String,
+    pub vout: u32,
+    /// Amount in satoshis
+    pub amount_sats: u64,
+}
+
+/// Unspent output created by a transaction that spends other unconfirmed
+/// transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnconfirmedChain {
+    pub index: usize,
+    pub address: String,
+    pub txid: String,
+    pub vout: u32,
+    /// Unconfirmed transactions in the chain, this one included
+    pub ancestor_count: u64,
+}
+
+impl UnconfirmedChain {
+    /// Whether the mempool will reject a transaction spending this output
+    pub fn at_limit(&self) -> bool {
+        self.ancestor_count >= MEMPOOL_ANCESTOR_LIMIT
+    }
+}
+
+/// Findings for an instance's accounts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAnalysis {
+    pub accounts_checked: usize,
+    pub address_reuse: Vec<AddressReuse>,
+    pub dust_outputs: Vec<DustOutput>,
+    pub unconfirmed_chains: Vec<UnconfirmedChain>,
+}
+
+impl WalletAnalysis {
+    /// Total number of findings
+    pub fn warning_count(&self) -> usize {
+        self.address_reuse.len() + self.dust_outputs.len() + self.unconfirmed_chains.len()
+    }
+
+    /// Whether nothing was found
+    pub fn is_clean(&self) -> bool {
+        self.warning_count() == 0
+    }
+}
+
+/// Analyze the accounts of a running instance
+pub fn analyze_instance(instance_id: &str) -> Result<WalletAnalysis> {
+    let info = InstanceInfo::load(instance_id)?;
+    let client = info.wallet_client()?;
+    if !client.is_node_running() {
+        return Err(ChainError::NotRunning);
+    }
+
+    let storage =
+        AccountsStorage::with_path(BitcoinConfig::with_instance(instance_id).accounts_file());
+    analyze_accounts(&client, &storage.load()?)
+}
+
+/// Analyze `accounts` against the node's wallet and mempool
+pub fn analyze_accounts(
+    client: &BitcoinRpcClient,
+    accounts: &[BitcoinAccount],
+) -> Result<WalletAnalysis> {
+    let ancestors = client.get_mempool_ancestor_counts()?;
+    let mut analysis = WalletAnalysis {
+        accounts_checked: accounts.len(),
+        ..Default::default()
+    };
+
+    for (index, account) in accounts.iter().enumerate() {
+        let txids = client.list_received_txids(&account.address)?;
+        analysis
+            .address_reuse
+            .extend(find_reuse(index, account, &txids));
+
+        let utxos = client.list_unspent(&account.address)?;
+        analysis
+            .dust_outputs
+            .extend(find_dust(index, &account.address, &utxos));
+        analysis.unconfirmed_chains.extend(find_unconfirmed_chains(
+            index,
+            &account.address,
+            &utxos,
+            &ancestors,
+        ));
+    }
+
+    Ok(analysis)
+}
+
+/// Reuse of an account's address, if more than one transaction paid it
+fn find_reuse(index: usize, account: &BitcoinAccount, txids: &[String]) -> Option<AddressReuse> {
+    let txids: BTreeSet<&String> = txids.iter().collect();
+    if txids.len() < 2 {
+        return None;
+    }
+
+    Some(AddressReuse {
+        index,
+        address: account.address.clone(),
+        label: account.label.clone(),
+        txids: txids.into_iter().cloned().collect(),
+    })
+}
+
+fn find_dust(index: usize, address: &str, utxos: &[BitcoinUtxo]) -> Vec<DustOutput> {
+    utxos
+        .iter()
+        .map(|utxo| (utxo, (utxo.amount * 1e8).round() as u64))
+        .filter(|(_, sats)| *sats < DUST_THRESHOLD_SATS)
+        .map(|(utxo, sats)| DustOutput {
+            index,
+            address: address.to_string(),
+            txid: utxo.txid.clone(),
+            vout: utxo.vout,
+            amount_sats: sats,
+        })
+        .collect()
+}
+
+fn find_unconfirmed_chains(
+    index: usize,
+    address: &str,
+    utxos: &[BitcoinUtxo],
+    ancestors: &HashMap<String, u64>,
+) -> Vec<UnconfirmedChain> {
+    utxos
+        .iter()
+        .filter(|utxo| utxo.confirmations == 0)
+        .filter_map(|utxo| {
+            // Missing if the transaction was mined since the mempool was read
+            let count = *ancestors.get(&utxo.txid)?;
+            (count > 1).then(|| UnconfirmedChain {
+                index,
+                address: address.to_string(),
+                txid: utxo.txid.clone(),
+                vout: utxo.vout,
+                ancestor_count: count,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_forge_bitcoin_accounts::AccountGenerator;
+
+    const ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+    fn utxo(txid: &str, amount: f64, confirmations: i64) -> BitcoinUtxo {
+        BitcoinUtxo {
+            txid: txid.to_string(),
+            vout: 0,
+            amount,
+            confirmations,
+            locked: false,
+        }
+    }
+
+    fn account() -> BitcoinAccount {
+        AccountGenerator::new().unwrap().derive_account(0).unwrap()
+    }
+
+    #[test]
+    fn test_find_reuse() {
+        let account = account();
+        let once = vec!["a".to_string(), "a".to_string()];
+        assert!(find_reuse(0, &account, &once).is_none());
+
+        let twice = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+        let reuse = find_reuse(3, &account, &twice).unwrap();
+        assert_eq!(reuse.index, 3);
+        assert_eq!(reuse.txids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_find_dust() {
+        let utxos = vec![
+            utxo("a", 0.00000293, 1),
+            utxo("b", 0.00000294, 1),
+            utxo("c", 1.0, 1),
+        ];
+        let dust = find_dust(0, ADDRESS, &utxos);
+        assert_eq!(dust.len(), 1);
+        assert_eq!(dust[0].txid, "a");
+        assert_eq!(dust[0].amount_sats, 293);
+    }
+
+    #[test]
+    fn test_find_unconfirmed_chains() {
+        let utxos = vec![
+            utxo("confirmed", 1.0, 3),
+            utxo("single", 1.0, 0),
+            utxo("chained", 1.0, 0),
+            utxo("mined", 1.0, 0),
+        ];
+        let ancestors = HashMap::from([
+            ("confirmed".to_string(), 1),
+            ("single".to_string(), 1),
+            ("chained".to_string(), MEMPOOL_ANCESTOR_LIMIT),
+        ]);
+
+        let chains = find_unconfirmed_chains(0, ADDRESS, &utxos, &ancestors);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].txid, "chained");
+        assert!(chains[0].at_limit());
+    }
+
+    #[test]
+    fn test_warning_count() {
+        let mut analysis = WalletAnalysis::default();
+        assert!(analysis.is_clean());
+
+        analysis.dust_outputs.push(DustOutput {
+            index: 0,
+            address: ADDRESS.to_string(),
+            txid: "a".to_string(),
+            vout: 0,
+            amount_sats: 1,
+        });
+        assert_eq!(analysis.warning_count(), 1);
+        assert!(!analysis.is_clean());
+    }
+}
@@ -0,0 +1,137 @@
+//! BIP21 payment URIs (`bitcoin:<address>?amount=<btc>&label=...`).
+
+use bitcoin::address::NetworkUnchecked;
+use bitcoin::{Address, Network};
+use chain_forge_common::uri::percent_decode;
+use chain_forge_common::{ChainError, Result};
+
+/// Most decimal places a BTC amount can have (one satoshi)
+const MAX_DECIMALS: usize = 8;
+
+/// A parsed BIP21 payment URI
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentUri {
+    pub address: String,
+    /// Requested amount in BTC
+    pub amount: Option<f64>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+}
+
+impl PaymentUri {
+    /// Parse a `bitcoin:` URI
+    ///
+    /// Unknown parameters are ignored, except `req-` parameters, which a
+    /// payer must understand and are rejected.
+    pub fn parse(uri: &str) -> Result<Self> {
+        let invalid = |reason: String| ChainError::Config(format!("Invalid BIP21 URI: {}", reason));
+
+        let rest = uri
+            .get(..8)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("bitcoin:"))
+            .map(|_| &uri[8..])
+            .ok_or_else(|| invalid("expected the bitcoin: scheme".to_string()))?;
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| invalid(format!("bad address {}: {}", address, e)))?;
+
+        let mut payment = Self {
+            address: address.to_string(),
+            amount: None,
+            label: None,
+            message: None,
+        };
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)
+                .ok_or_else(|| invalid(format!("bad percent-encoding in {}", name)))?;
+            match name {
+                "amount" => {
+                    payment.amount = Some(
+                        parse_amount(&value)
+                            .ok_or_else(|| invalid(format!("bad amount {}", value)))?,
+                    )
+                }
+                "label" => payment.label = Some(value),
+                "message" => payment.message = Some(value),
+                _ if name.starts_with("req-") => {
+                    return Err(invalid(format!("unsupported required parameter {}", name)))
+                }
+                _ => {}
+            }
+        }
+        Ok(payment)
+    }
+
+    /// Check that the address is valid on `network`
+    pub fn require_network(&self, network: Network) -> Result<()> {
+        let address: Address<NetworkUnchecked> = self
+            .address
+            .parse()
+            .map_err(|e| ChainError::Config(format!("Invalid address: {}", e)))?;
+        address.require_network(network).map_err(|_| {
+            ChainError::Config(format!(
+                "Address {} is not a {} address",
+                self.address, network
+            ))
+        })?;
+        Ok(())
+    }
+}
+
+/// Parse a BIP21 amount: a plain decimal number of BTC with at most eight
+/// decimal places
+fn parse_amount(value: &str) -> Option<f64> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() && fraction.is_empty()
+        || !digits(whole)
+        || !digits(fraction)
+        || fraction.len() > MAX_DECIMALS
+    {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+    #[test]
+    fn test_parse() {
+        let uri = format!(
+            "bitcoin:{}?amount=0.1&label=Test%20Shop&message=Order+42&foo=bar",
+            ADDRESS
+        );
+        let payment = PaymentUri::parse(&uri).unwrap();
+        assert_eq!(payment.address, ADDRESS);
+        assert_eq!(payment.amount, Some(0.1));
+        assert_eq!(payment.label.as_deref(), Some("Test Shop"));
+        assert_eq!(payment.message.as_deref(), Some("Order 42"));
+
+        let bare = PaymentUri::parse(&format!("BITCOIN:{}", ADDRESS)).unwrap();
+        assert_eq!(bare.amount, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_uris() {
+        assert!(PaymentUri::parse(&format!("solana:{}", ADDRESS)).is_err());
+        assert!(PaymentUri::parse("bitcoin:not-an-address").is_err());
+        assert!(PaymentUri::parse(&format!("bitcoin:{}?req-pop=1", ADDRESS)).is_err());
+        for amount in ["1e-3", "-1", "0.123456789", ".", "1,5"] {
+            let uri = format!("bitcoin:{}?amount={}", ADDRESS, amount);
+            assert!(PaymentUri::parse(&uri).is_err(), "accepted {}", amount);
+        }
+    }
+
+    #[test]
+    fn test_require_network() {
+        let payment = PaymentUri::parse(&format!("bitcoin:{}", ADDRESS)).unwrap();
+        assert!(payment.require_network(Network::Regtest).is_ok());
+        assert!(payment.require_network(Network::Bitcoin).is_err());
+    }
+}
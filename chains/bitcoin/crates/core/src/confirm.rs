@@ -0,0 +1,158 @@
+//! Confirmation policy for send operations.
+//!
+//! Nothing confirms on regtest unless a block is mined, so `fund`,
+//! `transfer`, and `pay` mine blocks after sending. How many is set per call
+//! with `--confirm` (or `confirm` in API requests) and otherwise follows the
+//! instance's default from `start --confirm`, which is one block. `0` (or
+//! `none`) leaves the transaction in the mempool, so apps can be tested
+//! against zero-conf payments.
+
+use chain_forge_bitcoin_rpc::BitcoinRpcClient;
+use chain_forge_common::{ChainType, Result};
+use chain_forge_events::ChainEvent;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+/// Blocks mined after a send operation to confirm it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmations(pub u32);
+
+impl Confirmations {
+    /// Leave transactions unconfirmed
+    pub const NONE: Self = Self(0);
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl FromStr for Confirmations {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("none") {
+            return Ok(Self::NONE);
+        }
+        s.parse().map(Self).map_err(|_| {
+            format!(
+                "Invalid confirmations '{}': expected a number of blocks or 'none'",
+                s
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for Confirmations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "none"),
+            1 => write!(f, "1 block"),
+            blocks => write!(f, "{} blocks", blocks),
+        }
+    }
+}
+
+impl Serialize for Confirmations {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Confirmations {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Blocks(u32),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Blocks(blocks) => Ok(Self(blocks)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl JsonSchema for Confirmations {
+    fn schema_name() -> String {
+        "Confirmations".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let none = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(vec!["none".into()]),
+            ..Default::default()
+        };
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![gen.subschema_for::<u32>(), none.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Mine blocks to confirm a send operation, returning their hashes
+///
+/// Blocks go to a fresh wallet address rather than any account, so account
+/// balances only change by what was sent.
+pub fn confirm(
+    client: &BitcoinRpcClient,
+    instance_id: &str,
+    confirmations: Confirmations,
+) -> Result<Vec<String>> {
+    if confirmations == Confirmations::NONE {
+        return Ok(Vec::new());
+    }
+
+    let address = client.get_new_address(Some("mining"))?;
+    let blocks = client.mine_blocks(confirmations.0, &address)?;
+    chain_forge_events::publish(
+        ChainType::Bitcoin,
+        instance_id,
+        ChainEvent::blocks_mined(blocks.len() as u64),
+    );
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "none".parse::<Confirmations>().unwrap(),
+            Confirmations::NONE
+        );
+        assert_eq!("0".parse::<Confirmations>().unwrap(), Confirmations::NONE);
+        assert_eq!("6".parse::<Confirmations>().unwrap(), Confirmations(6));
+        assert!("-1".parse::<Confirmations>().is_err());
+        assert!("soon".parse::<Confirmations>().is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let none: Confirmations = serde_json::from_str("\"none\"").unwrap();
+        assert_eq!(none, Confirmations::NONE);
+        let six: Confirmations = serde_json::from_str("6").unwrap();
+        assert_eq!(six, Confirmations(6));
+        assert_eq!(serde_json::to_string(&six).unwrap(), "6");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Confirmations::NONE.to_string(), "none");
+        assert_eq!(Confirmations::default().to_string(), "1 block");
+        assert_eq!(Confirmations(6).to_string(), "6 blocks");
+    }
+}
@@ -0,0 +1,155 @@
+//! Explaining why bitcoind failed to start.
+//!
+//! bitcoind reports init errors in `debug.log` and on stderr, worded for
+//! node operators. Common failures are turned into errors that say which
+//! flag or file to change; anything else is passed on as bitcoind wrote it.
+
+use crate::BitcoinConfig;
+
+/// An actionable message for a bitcoind that exited during startup
+///
+/// `log` is the output bitcoind wrote before exiting. Returns `None` if it
+/// holds no error lines.
+pub fn startup_failure(log: &str, config: &BitcoinConfig) -> Option<String> {
+    let find = |needles: &[&str]| {
+        log.lines()
+            .find(|line| needles.iter().any(|needle| line.contains(needle)))
+            .map(message)
+    };
+    let data_dir = config.data_dir.display();
+
+    if find(&["Cannot obtain a lock on data directory"]).is_some() {
+        return Some(format!(
+            "Another bitcoind is already using {}. Stop it with 'cf-bitcoin stop --instance {}' and start again",
+            data_dir, config.instance_id
+        ));
+    }
+
+    if find(&[
+        "Unable to bind any endpoint for RPC server",
+        "Binding RPC on address",
+    ])
+    .is_some()
+    {
+        return Some(format!(
+            "RPC port {} is already in use. Pick another with --rpc-port, or stop the process using it",
+            config.rpc_port
+        ));
+    }
+
+    if find(&["Unable to bind to"]).is_some() {
+        return Some(format!(
+            "P2P port {} is already in use. Pick another with --p2p-port, or stop the process using it",
+            config.p2p_port
+        ));
+    }
+
+    if let Some(detail) = find(&[
+        "requires newer version",
+        "Unsupported chainstate database format",
+        "Unknown wallet version",
+        "incompatible with this version",
+    ]) {
+        return Some(format!(
+            "The data in {} was written by an incompatible Bitcoin Core version ({}). \
+             Restore a snapshot created with the installed bitcoind, or start without --snapshot",
+            data_dir, detail
+        ));
+    }
+
+    if let Some(detail) = find(&[
+        "Corrupted block database",
+        "Error opening block database",
+        "Error loading block database",
+        "Error initializing block database",
+        "Please restart with -reindex",
+    ]) {
+        return Some(format!(
+            "The chain data in {} is corrupted or incomplete ({}). \
+             If it came from a snapshot, create the snapshot again; otherwise start without --snapshot",
+            data_dir, detail
+        ));
+    }
+
+    let errors: Vec<String> = log
+        .lines()
+        .filter(|line| line.contains("Error") || line.contains("EXCEPTION"))
+        .map(message)
+        .collect();
+    if errors.is_empty() {
+        None
+    } else {
+        Some(format!("bitcoind failed to start: {}", errors.join("\n")))
+    }
+}
+
+/// A log line without its leading timestamp
+fn message(line: &str) -> String {
+    let line = line.trim();
+    match line.split_once(' ') {
+        Some((timestamp, rest)) if timestamp.ends_with('Z') && timestamp.contains('T') => {
+            rest.to_string()
+        }
+        _ => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BitcoinConfig {
+        BitcoinConfig::with_instance("diag")
+    }
+
+    #[test]
+    fn test_port_in_use() {
+        let log = "2024-01-15T10:30:00Z Binding RPC on address 127.0.0.1 port 18443 failed.\n\
+                   2024-01-15T10:30:00Z Unable to bind any endpoint for RPC server\n";
+        let message = startup_failure(log, &config()).unwrap();
+        assert!(message.contains("RPC port 18443"));
+        assert!(message.contains("--rpc-port"));
+
+        let log = "2024-01-15T10:30:00Z Error: Unable to bind to 0.0.0.0:18444 on this computer. \
+                   Bitcoin Core is probably already running.\n";
+        let message = startup_failure(log, &config()).unwrap();
+        assert!(message.contains("P2P port 18444"));
+    }
+
+    #[test]
+    fn test_datadir_locked() {
+        let log = "Error: Cannot obtain a lock on data directory /tmp/x. \
+                   Bitcoin Core is probably already running.\n";
+        let message = startup_failure(log, &config()).unwrap();
+        assert!(message.contains("cf-bitcoin stop --instance diag"));
+    }
+
+    #[test]
+    fn test_incompatible_version() {
+        let log = "2024-01-15T10:30:00Z Unsupported chainstate database format found. \
+                   Please restart with -reindex-chainstate.\n";
+        let message = startup_failure(log, &config()).unwrap();
+        assert!(message.contains("incompatible Bitcoin Core version"));
+        assert!(message.contains("(Unsupported chainstate database format found."));
+    }
+
+    #[test]
+    fn test_corrupted_chainstate() {
+        let log = "2024-01-15T10:30:00Z Fatal LevelDB error: Corruption: block checksum mismatch\n\
+                   2024-01-15T10:30:00Z Error opening block database\n";
+        let message = startup_failure(log, &config()).unwrap();
+        assert!(message.contains("corrupted or incomplete (Error opening block database)"));
+    }
+
+    #[test]
+    fn test_other_errors() {
+        let log = "2024-01-15T10:30:00Z Bitcoin Core version v27.0.0\n\
+                   2024-01-15T10:30:00Z Error: Invalid -fallbackfee\n";
+        assert_eq!(
+            startup_failure(log, &config()).unwrap(),
+            "bitcoind failed to start: Error: Invalid -fallbackfee"
+        );
+
+        assert!(startup_failure("2024-01-15T10:30:00Z Shutdown: done\n", &config()).is_none());
+    }
+}
@@ -1,16 +1,50 @@
-use chain_forge_bitcoin_accounts::{AccountGenerator, AccountsStorage, BitcoinAccount};
-use chain_forge_bitcoin_rpc::BitcoinRpcClient;
+use bitcoin::address::NetworkUnchecked;
+use bitcoin::{Address, Network};
+use chain_forge_bitcoin_accounts::{
+    AccountGenerator, AccountsStorage, AddressType, BitcoinAccount,
+};
+use chain_forge_bitcoin_rpc::{BitcoinRpcClient, EsploraClient};
+use chain_forge_common::schema::{self, Schema};
 use chain_forge_common::{
-    ChainError, ChainProvider, ChainType, NodeInfo, NodeRegistry, NodeStatus, Result,
+    ChainError, ChainProvider, ChainType, Heartbeat, HookContext, LifecycleHooks, LifecyclePhase,
+    NodeInfo, NodeRegistry, NodeStatus, Result, SignerKeystore, StartupPhase, StartupProfile,
+    StartupTimer, StorageBackend, HEARTBEAT_INTERVAL,
 };
-use chain_forge_config::Config;
+use chain_forge_config::{CleanupPolicy, Config};
+use chain_forge_events::ChainEvent;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
+use tracing::{info, warn};
+
+pub mod analyze;
+pub mod bip21;
+pub mod confirm;
+pub mod diagnose;
+pub mod public;
+pub mod reconcile;
+pub mod reset;
+pub mod snapshot;
+
+pub use analyze::{analyze_instance, WalletAnalysis};
+pub use bip21::PaymentUri;
+pub use confirm::Confirmations;
+pub use public::PublicNetwork;
+pub use reconcile::{reconcile_instance, BalanceReport};
+pub use reset::reset_instance;
+pub use snapshot::BitcoinSnapshot;
+
+/// Format of `instance.json`
+///
+/// - 1: `version` added
+const INSTANCE_SCHEMA: Schema = Schema::new(&[schema::unversioned]);
 
 /// Instance information saved to disk for CLI discovery
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +67,48 @@ pub struct InstanceInfo {
     pub accounts_count: u32,
     /// Whether the instance is currently running (may be stale)
     pub running: bool,
+    /// Clock override set with `cf-bitcoin time` (Unix seconds)
+    #[serde(default)]
+    pub mock_time: Option<i64>,
+    /// Number of times the instance has been reset in place with `cf reset`
+    #[serde(default)]
+    pub epoch: u64,
+    /// Address that funding transactions are sent from, if not the node wallet
+    #[serde(default)]
+    pub treasury: Option<String>,
+    /// Passphrase the node wallet is encrypted with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_passphrase: Option<String>,
+    /// Chain the node runs
+    #[serde(default)]
+    pub chain: BitcoinChain,
+    /// Nodes connected to this one in multi-node mode, numbered from 1
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<PeerNode>,
+    /// Public test network the instance tracks instead of running a node;
+    /// `rpc_url` is then an Esplora API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<PublicNetwork>,
+    /// Blocks send operations mine unless told otherwise
+    #[serde(default)]
+    pub confirmations: Confirmations,
+    /// Script type of the instance's accounts
+    #[serde(default)]
+    pub address_type: AddressType,
+    /// How long each phase of the last start took
+    #[serde(default, skip_serializing_if = "StartupProfile::is_empty")]
+    pub startup: StartupProfile,
+}
+
+/// A node of a multi-node instance besides the wallet node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerNode {
+    /// RPC URL of the node
+    pub rpc_url: String,
+    /// RPC port
+    pub rpc_port: u16,
+    /// P2P port
+    pub p2p_port: u16,
 }
 
 impl InstanceInfo {
@@ -51,9 +127,7 @@ impl InstanceInfo {
             )));
         }
 
-        let json = std::fs::read_to_string(&path)?;
-        let info: InstanceInfo = serde_json::from_str(&json)?;
-        Ok(info)
+        INSTANCE_SCHEMA.read(&path)
     }
 
     /// Save instance info to disk
@@ -64,13 +138,7 @@ impl InstanceInfo {
             .join(&self.instance_id)
             .join("instance.json");
 
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(&path, json)?;
-        Ok(())
+        INSTANCE_SCHEMA.write(&path, self)
     }
 
     /// Mark instance as stopped
@@ -78,9 +146,79 @@ impl InstanceInfo {
         self.running = false;
         self.save()
     }
+
+    /// RPC client for the instance's `chain-forge` wallet
+    ///
+    /// The client unlocks the wallet before signing if it is encrypted.
+    pub fn wallet_client(&self) -> Result<BitcoinRpcClient> {
+        if let Some(network) = self.network {
+            return Err(ChainError::Config(format!(
+                "Instance '{}' is attached to {} and has no node to send RPC calls to",
+                self.instance_id, network
+            )));
+        }
+        Ok(BitcoinRpcClient::new_with_wallet(
+            self.rpc_url.clone(),
+            self.rpc_user.clone(),
+            self.rpc_password.clone(),
+            "chain-forge",
+        )?
+        .with_wallet_passphrase(self.wallet_passphrase.clone())
+        .with_block_signer(self.chain.block_signer()?))
+    }
+
+    /// Refresh account balances from the node wallet, or from the explorer
+    /// API of an attached public network
+    pub fn update_balances(&self, accounts: &mut [BitcoinAccount]) -> Result<()> {
+        match self.network {
+            Some(_) => EsploraClient::new(self.rpc_url.clone()).update_balances(accounts),
+            None => self.wallet_client()?.update_balances(accounts),
+        }
+    }
+
+    /// Whether the node, or the explorer API of an attached public network,
+    /// answers
+    pub fn is_reachable(&self) -> bool {
+        match self.network {
+            Some(_) => EsploraClient::new(self.rpc_url.clone()).is_reachable(),
+            None => self
+                .wallet_client()
+                .map(|client| client.is_node_running())
+                .unwrap_or(false),
+        }
+    }
+
+    /// Number of nodes the instance runs
+    pub fn node_count(&self) -> usize {
+        1 + self.peers.len()
+    }
+
+    /// RPC client for one node of the instance
+    ///
+    /// Node 0 holds the wallet and gets the wallet client; the others get a
+    /// client without a wallet.
+    pub fn node_client(&self, index: usize) -> Result<BitcoinRpcClient> {
+        if index == 0 {
+            return self.wallet_client();
+        }
+        let peer = self.peers.get(index - 1).ok_or_else(|| {
+            ChainError::Config(format!(
+                "Node {} doesn't exist: instance '{}' runs {} node(s), numbered from 0",
+                index,
+                self.instance_id,
+                self.node_count()
+            ))
+        })?;
+        Ok(BitcoinRpcClient::new(
+            peer.rpc_url.clone(),
+            self.rpc_user.clone(),
+            self.rpc_password.clone(),
+        )?
+        .with_block_signer(self.chain.block_signer()?))
+    }
 }
 
-/// Configuration for starting a Bitcoin regtest node
+/// Configuration for starting a Bitcoin node
 #[derive(Debug, Clone)]
 pub struct BitcoinConfig {
     /// RPC URL for connecting to bitcoind
@@ -95,6 +233,8 @@ pub struct BitcoinConfig {
     pub initial_balance: f64,
     /// Optional mnemonic for deterministic account generation
     pub mnemonic: Option<String>,
+    /// Derivation index of the first account
+    pub start_index: u32,
     /// RPC username
     pub rpc_user: String,
     /// RPC password
@@ -107,6 +247,166 @@ pub struct BitcoinConfig {
     pub instance_id: String,
     /// Human-readable name for the instance
     pub name: Option<String>,
+    /// Build and serve BIP157/158 compact block filters
+    pub block_filter_index: bool,
+    /// Account indices whose keys are held by an emulated external signer
+    pub external_signers: Vec<u32>,
+    /// Labels given to the first accounts, in order
+    pub account_labels: Vec<String>,
+    /// Fund accounts from this key instead of the wallet's mining address
+    pub treasury: Option<Treasury>,
+    /// Encrypt the node wallet with this passphrase
+    pub wallet_passphrase: Option<String>,
+    /// Boot from a named chain snapshot instead of a fresh chain
+    pub snapshot: Option<String>,
+    /// Chain to run: regtest or a private signet
+    pub chain: BitcoinChain,
+    /// Extra chain parameters passed to bitcoind as `-<name>=<value>`
+    /// (e.g. `testactivationheight=segwit@1` or `vbparams=...` on regtest)
+    pub chain_params: Vec<String>,
+    /// Number of connected nodes to run; node 0 holds the wallet
+    pub nodes: u32,
+    /// Mine a block every this many seconds while the node runs
+    pub block_interval: Option<u64>,
+    /// Blocks send operations mine by default
+    pub confirmations: Confirmations,
+    /// Script type of generated accounts
+    pub address_type: AddressType,
+}
+
+/// Port offset between consecutive nodes of a multi-node instance
+pub const NODE_PORT_STRIDE: u16 = 10;
+
+/// Blocks premined per RPC call, so startup can report its progress
+const PREMINE_BATCH: u32 = 25;
+
+/// How long `stop` waits for bitcoind to shut down before killing it
+///
+/// Shorter than the 30 seconds `cf-bitcoin stop` gives a detached CLI, so
+/// the CLI is never killed while bitcoind is still running.
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Key that account funding transactions are sent from
+///
+/// Block rewards are mined to the treasury address and every funding
+/// transaction spends from it, so indexers can whitelist a single address.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Treasury {
+    /// One of the generated accounts, by index
+    Account(u32),
+    /// An external private key in WIF
+    Wif(String),
+}
+
+/// Chain a node runs
+///
+/// Signets here are private: the challenge is always given, so the node never
+/// joins the public signet. A managed signet's challenge is a key Chain Forge
+/// holds, so it can sign and mine blocks like on any other signet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinChain {
+    /// Local regtest chain
+    #[default]
+    Regtest,
+    /// Signet whose blocks must satisfy `challenge`, a hex-encoded script
+    Signet {
+        challenge: String,
+        /// WIF of the block-signing key, if Chain Forge manages it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signer: Option<String>,
+    },
+}
+
+impl BitcoinChain {
+    /// `OP_TRUE`: blocks need no signature, so the node can mine them itself
+    pub const OPEN_SIGNET_CHALLENGE: &'static str = "51";
+
+    /// A signet with the given challenge, or an open one if none is given
+    pub fn signet(challenge: Option<String>) -> Result<Self> {
+        let challenge = challenge.unwrap_or_else(|| Self::OPEN_SIGNET_CHALLENGE.to_string());
+        if challenge.is_empty()
+            || challenge.len() % 2 != 0
+            || !challenge.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(ChainError::Config(format!(
+                "Signet challenge must be a hex-encoded script, got '{}'",
+                challenge
+            )));
+        }
+        Ok(Self::Signet {
+            challenge: challenge.to_lowercase(),
+            signer: None,
+        })
+    }
+
+    /// A signet whose challenge is a freshly generated key's P2WPKH script
+    pub fn managed_signet() -> Result<Self> {
+        let signer = AccountGenerator::new_with_network(Network::Signet)?
+            .generate_accounts(1)?
+            .remove(0);
+        let challenge = signer
+            .address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| ChainError::Other(format!("Invalid signer address: {}", e)))?
+            .assume_checked()
+            .script_pubkey()
+            .to_hex_string();
+        Ok(Self::Signet {
+            challenge,
+            signer: Some(signer.wif),
+        })
+    }
+
+    /// The managed block-signing key, if any
+    pub fn block_signer(&self) -> Result<Option<BitcoinAccount>> {
+        match self {
+            Self::Signet {
+                signer: Some(wif), ..
+            } => Ok(Some(BitcoinAccount::from_wif(wif, Network::Signet)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Chain name, as bitcoind uses for its data subdirectory
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Regtest => "regtest",
+            Self::Signet { .. } => "signet",
+        }
+    }
+
+    /// Network addresses and keys are encoded for
+    pub fn network(&self) -> Network {
+        match self {
+            Self::Regtest => Network::Regtest,
+            Self::Signet { .. } => Network::Signet,
+        }
+    }
+
+    /// Whether Chain Forge can mine blocks
+    ///
+    /// Blocks on a signet with any challenge but `OP_TRUE` have to be signed
+    /// by the challenge key, which only a managed signet holds.
+    pub fn can_mine(&self) -> bool {
+        match self {
+            Self::Regtest => true,
+            Self::Signet { challenge, signer } => {
+                signer.is_some() || challenge == Self::OPEN_SIGNET_CHALLENGE
+            }
+        }
+    }
+
+    /// bitcoind arguments selecting the chain
+    fn args(&self) -> Vec<String> {
+        match self {
+            Self::Regtest => vec!["-regtest".to_string()],
+            Self::Signet { challenge, .. } => vec![
+                "-signet".to_string(),
+                format!("-signetchallenge={}", challenge),
+            ],
+        }
+    }
 }
 
 impl Default for BitcoinConfig {
@@ -131,12 +431,25 @@ impl BitcoinConfig {
             accounts: 10,
             initial_balance: 10.0,
             mnemonic: None,
+            start_index: 0,
             rpc_user: "chainforge".to_string(),
             rpc_password: "chainforge".to_string(),
             data_dir,
             verbose: false,
             instance_id: instance_id.to_string(),
             name: None,
+            block_filter_index: false,
+            external_signers: Vec::new(),
+            account_labels: Vec::new(),
+            treasury: None,
+            wallet_passphrase: None,
+            snapshot: None,
+            chain: BitcoinChain::Regtest,
+            chain_params: Vec::new(),
+            nodes: 1,
+            block_interval: None,
+            confirmations: Confirmations::default(),
+            address_type: AddressType::default(),
         }
     }
 
@@ -157,6 +470,118 @@ impl BitcoinConfig {
     pub fn instance_info_file(&self) -> PathBuf {
         self.instance_dir().join("instance.json")
     }
+
+    /// Get the keystore file backing emulated external signers
+    pub fn signer_keystore_file(&self) -> PathBuf {
+        self.instance_dir().join("signer-keystore.json")
+    }
+
+    /// Get bitcoind's debug log file
+    pub fn log_file(&self) -> PathBuf {
+        self.data_dir.join(self.chain.name()).join("debug.log")
+    }
+
+    /// Get the file bitcoind's stderr is written to when not verbose
+    ///
+    /// It sits next to the data directory, so each node has its own.
+    pub fn stderr_log_file(&self) -> PathBuf {
+        self.data_dir.with_file_name("bitcoind_stderr.log")
+    }
+
+    /// Config for one node of a multi-node instance
+    ///
+    /// Node `index` uses the configured ports plus `index * NODE_PORT_STRIDE`
+    /// and its own data directory under `node<index>/`. Node 0 is the
+    /// configured node itself.
+    pub fn node(&self, index: u32) -> Result<BitcoinConfig> {
+        if index == 0 {
+            return Ok(self.clone());
+        }
+        let offset = u16::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_mul(NODE_PORT_STRIDE));
+        let port = |base: u16| {
+            offset
+                .and_then(|offset| base.checked_add(offset))
+                .ok_or_else(|| {
+                    ChainError::Config(format!(
+                        "Not enough ports above {} for {} nodes",
+                        base, self.nodes
+                    ))
+                })
+        };
+
+        let mut node = self.clone();
+        node.rpc_port = port(self.rpc_port)?;
+        node.p2p_port = port(self.p2p_port)?;
+        node.rpc_url = format!("http://127.0.0.1:{}", node.rpc_port);
+        node.data_dir = self
+            .instance_dir()
+            .join(format!("node{}", index))
+            .join("regtest-data");
+        node.nodes = 1;
+        // The console belongs to node 0
+        node.verbose = false;
+        Ok(node)
+    }
+}
+
+/// Fund every account except the treasury itself with `balance` BTC
+///
+/// Uses a single transaction: sequential sends from one address would each
+/// have to wait for the previous send's change to confirm.
+pub(crate) fn fund_from_treasury(
+    client: &BitcoinRpcClient,
+    treasury: &str,
+    accounts: &[BitcoinAccount],
+    balance: f64,
+) -> Result<String> {
+    let payments: Vec<(String, f64)> = accounts
+        .iter()
+        .filter(|a| a.address != treasury)
+        .map(|a| (a.address.clone(), balance))
+        .collect();
+    client.send_many_from_address(treasury, &payments)
+}
+
+/// Import account keys into the node wallet so their balances are tracked
+///
+/// External signer accounts have no key to import; their balances are still
+/// tracked via the UTXO set. The treasury is imported separately.
+fn import_accounts(
+    wallet_client: &BitcoinRpcClient,
+    accounts: &[BitcoinAccount],
+    treasury: Option<&BitcoinAccount>,
+) -> Result<()> {
+    for (i, account) in accounts.iter().enumerate() {
+        let is_treasury = treasury.is_some_and(|t| t.address == account.address);
+        if account.external_signer || is_treasury {
+            continue;
+        }
+        wallet_client.import_address(&account.address, &account.wif, &format!("account-{}", i))?;
+    }
+    Ok(())
+}
+
+/// Number of blocks to mine from genesis so the wallet can spend `total_btc`
+///
+/// Each coinbase needs 100 confirmations to be spendable. On regtest the
+/// block reward halves every 150 blocks (50 -> 25 -> 12.5...); signet halves
+/// far later, so the count is an overestimate there.
+pub fn blocks_to_fund(total_btc: f64) -> u32 {
+    let mut accumulated = 0.0;
+    let mut coinbase_blocks = 0u32;
+    while accumulated < total_btc {
+        let era = coinbase_blocks / 150;
+        let reward = 50.0 / (1u64 << era) as f64;
+        if reward < 1e-8 {
+            break;
+        }
+        accumulated += reward;
+        coinbase_blocks += 1;
+    }
+    // 100 extra blocks so the earliest coinbase reaches maturity
+    100 + coinbase_blocks.max(1)
 }
 
 /// Bitcoin chain provider implementation
@@ -165,9 +590,20 @@ pub struct BitcoinProvider {
     rpc_client: Option<BitcoinRpcClient>,
     accounts: Vec<BitcoinAccount>,
     bitcoind_process: Arc<Mutex<Option<Child>>>,
+    /// bitcoind processes of nodes 1.. in multi-node mode
+    peer_processes: Arc<Mutex<Vec<Child>>>,
+    /// Stop flag and thread of the interval block producer
+    block_producer: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
     storage: AccountsStorage,
     /// Whether to keep instance data on stop (default: false)
     keep_data: bool,
+    /// Whether to snapshot the instance on stop, before clearing it
+    snapshot_on_stop: bool,
+    /// Trash retention for this instance's data; `None` uses the config's
+    trash_retention_days: Option<u32>,
+    /// Refreshes the node's registry heartbeat while it runs
+    heartbeat: Option<Heartbeat>,
+    hooks: LifecycleHooks,
 }
 
 impl BitcoinProvider {
@@ -192,8 +628,14 @@ impl BitcoinProvider {
             rpc_client: None,
             accounts: Vec::new(),
             bitcoind_process: Arc::new(Mutex::new(None)),
+            peer_processes: Arc::new(Mutex::new(Vec::new())),
+            block_producer: None,
             storage,
             keep_data: false,
+            snapshot_on_stop: false,
+            trash_retention_days: None,
+            heartbeat: None,
+            hooks: LifecycleHooks::new(),
         }
     }
 
@@ -202,20 +644,30 @@ impl BitcoinProvider {
         self.keep_data = keep;
     }
 
+    /// Apply a profile's cleanup settings: what stop does with instance data
+    pub fn set_cleanup(&mut self, cleanup: CleanupPolicy) {
+        self.keep_data = cleanup.keep_data;
+        self.snapshot_on_stop = cleanup.snapshot_on_stop;
+        self.trash_retention_days = cleanup.trash_retention_days;
+    }
+
     /// Generate accounts for this instance
     ///
     /// Each node start gets fresh accounts. If a mnemonic is provided, the same
     /// addresses are generated but balances start fresh (blockchain is cleared).
     fn generate_accounts(&mut self) -> Result<()> {
         let generator = if let Some(mnemonic) = &self.config.mnemonic {
-            AccountGenerator::from_mnemonic(mnemonic)?
+            AccountGenerator::from_mnemonic_with_network(mnemonic, self.config.chain.network())?
         } else {
-            AccountGenerator::new()?
-        };
+            AccountGenerator::new_with_network(self.config.chain.network())?
+        }
+        .with_address_type(self.config.address_type)?
+        .with_start_index(self.config.start_index);
 
-        println!("🔑 Mnemonic: {}", generator.mnemonic_phrase());
-        println!("   Save this mnemonic to recover your accounts!");
-        println!();
+        info!(
+            mnemonic = %generator.mnemonic_phrase(),
+            "Generated accounts; save this mnemonic to recover them"
+        );
 
         self.accounts = generator.generate_accounts(self.config.accounts)?;
 
@@ -224,27 +676,137 @@ impl BitcoinProvider {
             account.balance = self.config.initial_balance;
         }
 
+        for (account, label) in self.accounts.iter_mut().zip(&self.config.account_labels) {
+            account.label = Some(label.clone());
+        }
+
+        self.detach_external_signers()?;
+
         self.storage.save(&self.accounts)?;
 
         Ok(())
     }
 
-    /// Clear all instance data (blockchain and accounts)
-    fn clear_instance_data(&self) -> Result<()> {
-        let instance_dir = self.config.instance_dir();
+    /// Restore chain, wallet, and accounts from the configured snapshot
+    fn restore_snapshot(&mut self, name: &str) -> Result<()> {
+        let snapshot = BitcoinSnapshot::load(name)?;
+
+        info!(
+            snapshot = %snapshot.name,
+            height = snapshot.height,
+            source_instance = %snapshot.source_instance,
+            "Restoring snapshot"
+        );
+        snapshot.restore(&self.config.instance_dir())?;
+
+        // The chain on disk decides what bitcoind has to run
+        self.config.chain = snapshot.chain.clone();
+
+        self.accounts = self.storage.load()?;
+        self.config.accounts = self.accounts.len() as u32;
+
+        Ok(())
+    }
+
+    /// Move the keys of accounts marked as external signers into the signer keystore
+    fn detach_external_signers(&mut self) -> Result<()> {
+        if self.config.external_signers.is_empty() {
+            return Ok(());
+        }
 
-        // Remove the entire instance directory if it exists
-        if instance_dir.exists() {
-            std::fs::remove_dir_all(&instance_dir).map_err(|e| {
-                ChainError::NodeManagement(format!("Failed to clear instance data: {}", e))
+        let keystore = SignerKeystore::with_path(self.config.signer_keystore_file());
+        for &index in &self.config.external_signers {
+            let account = self.accounts.get_mut(index as usize).ok_or_else(|| {
+                ChainError::Config(format!(
+                    "External signer index {} is out of range (only {} accounts)",
+                    index, self.config.accounts
+                ))
             })?;
+            account.detach_key(&keystore)?;
+            info!(account = %account.address, "Account uses an emulated external signer");
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the configured treasury to an account with a spendable key
+    fn resolve_treasury(&self) -> Result<Option<BitcoinAccount>> {
+        let account = match &self.config.treasury {
+            None => return Ok(None),
+            Some(Treasury::Account(index)) => {
+                let account = self.accounts.get(*index as usize).ok_or_else(|| {
+                    ChainError::Config(format!(
+                        "Treasury account index {} is out of range (only {} accounts)",
+                        index, self.config.accounts
+                    ))
+                })?;
+                if account.external_signer {
+                    return Err(ChainError::Config(format!(
+                        "Treasury account {} uses an external signer",
+                        index
+                    )));
+                }
+                account.clone()
+            }
+            Some(Treasury::Wif(wif)) => BitcoinAccount::from_wif(wif, self.config.chain.network())?,
+        };
+
+        info!(address = %account.address, "Using treasury");
+        Ok(Some(account))
+    }
+
+    /// Snapshot the stopped node as `<instance>-stop-<timestamp>`
+    ///
+    /// Failing only logs a warning, so the data is still cleared as usual.
+    fn save_stop_snapshot(&self, height: u64) {
+        let instance = &self.config.instance_id;
+        let name = format!("{}-stop-{}", instance, Utc::now().format("%Y%m%d-%H%M%S"));
+        let instance_dir = self.config.instance_dir();
+        match BitcoinSnapshot::create(
+            &name,
+            instance,
+            height,
+            self.config.chain.clone(),
+            &instance_dir,
+        ) {
+            Ok(snapshot) => info!(snapshot = %snapshot.name, height, "Saved snapshot on stop"),
+            Err(e) => warn!(error = %e, "Failed to save snapshot on stop"),
+        }
+    }
+
+    /// Move instance data to the trash
+    ///
+    /// Accounts kept in the OS keyring or in memory can't be moved with the
+    /// directory, so they are deleted.
+    fn clear_instance_data(&self) -> Result<()> {
+        if self.storage.backend() != StorageBackend::File {
+            self.storage.delete()?;
+        }
+
+        let trashed = Config::trash_with_retention(self.trash_retention_days).move_instance(
+            ChainType::Bitcoin,
+            &self.config.instance_id,
+            &self.config.instance_dir(),
+        )?;
+        if let Some(entry) = trashed {
+            info!(trash = %entry.id, "Moved instance data to the trash");
         }
 
         Ok(())
     }
 
     /// Save instance info for CLI discovery
-    fn save_instance_info(&self) -> Result<()> {
+    fn save_instance_info(&self, treasury: Option<String>) -> Result<()> {
+        let peers = (1..self.config.nodes)
+            .map(|index| {
+                let node = self.config.node(index)?;
+                Ok(PeerNode {
+                    rpc_url: node.rpc_url,
+                    rpc_port: node.rpc_port,
+                    p2p_port: node.p2p_port,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
         let info = InstanceInfo {
             instance_id: self.config.instance_id.clone(),
             name: self.config.name.clone(),
@@ -255,14 +817,32 @@ impl BitcoinProvider {
             rpc_password: self.config.rpc_password.clone(),
             accounts_count: self.config.accounts,
             running: true,
+            mock_time: None,
+            epoch: 0,
+            treasury,
+            wallet_passphrase: self.config.wallet_passphrase.clone(),
+            chain: self.config.chain.clone(),
+            peers,
+            network: None,
+            confirmations: self.config.confirmations,
+            address_type: self.config.address_type,
+            startup: StartupProfile::default(),
         };
         info.save()
     }
 
+    /// Record how long each phase of this start took in the instance info
+    fn save_startup_profile(&self, startup: StartupProfile) -> Result<()> {
+        let mut info = InstanceInfo::load(&self.config.instance_id)?;
+        info!(total_ms = startup.total_ms(), "Startup phases recorded");
+        info.startup = startup;
+        info.save()
+    }
+
     /// Register this node with the global registry
     fn register_with_registry(&self) -> Result<()> {
         let registry = NodeRegistry::new();
-        let node = NodeInfo::new(
+        let mut node = NodeInfo::new(
             ChainType::Bitcoin,
             &self.config.instance_id,
             self.config.name.clone(),
@@ -270,6 +850,12 @@ impl BitcoinProvider {
             self.config.rpc_port,
             self.config.accounts,
         );
+        node.node_pid = self
+            .bitcoind_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|child| child.id());
         registry.register(node)
     }
 
@@ -280,7 +866,24 @@ impl BitcoinProvider {
         registry.update_status(&node_id, NodeStatus::Stopped)
     }
 
-    /// Start the bitcoind process in regtest mode
+    /// Context passed to lifecycle hooks
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            chain: ChainType::Bitcoin,
+            instance_id: self.config.instance_id.clone(),
+            rpc_url: self.config.rpc_url.clone(),
+            accounts: self.accounts.iter().map(|a| a.address.clone()).collect(),
+        }
+    }
+
+    /// Publish an event for this node
+    fn publish(instance_id: &str, event: ChainEvent) {
+        chain_forge_events::publish(ChainType::Bitcoin, instance_id, event);
+    }
+
+    /// Start the bitcoind processes of every node on the configured chain
+    ///
+    /// Each peer node is told to connect to all nodes started before it.
     fn start_bitcoind(&mut self) -> Result<()> {
         // Check if bitcoind is available
         let bitcoind_check = Command::new("bitcoind").arg("--version").output();
@@ -291,31 +894,65 @@ impl BitcoinProvider {
             ));
         }
 
+        // Work out every node's ports before starting any of them
+        let peers = (1..self.config.nodes)
+            .map(|index| self.config.node(index))
+            .collect::<Result<Vec<_>>>()?;
+
+        info!(
+            chain = %self.config.chain.name(),
+            port = self.config.rpc_port,
+            "Starting Bitcoin node"
+        );
+        let child = Self::spawn_bitcoind(&self.config, &[])?;
+        *self.bitcoind_process.lock().unwrap() = Some(child);
+
+        let mut p2p_ports = vec![self.config.p2p_port];
+        for (index, peer) in peers.iter().enumerate() {
+            info!(node = index + 1, port = peer.rpc_port, "Starting peer node");
+            let child = Self::spawn_bitcoind(peer, &p2p_ports)?;
+            self.peer_processes.lock().unwrap().push(child);
+            p2p_ports.push(peer.p2p_port);
+        }
+
+        Ok(())
+    }
+
+    /// Spawn bitcoind for one node, connected to the local nodes on `peer_ports`
+    fn spawn_bitcoind(config: &BitcoinConfig, peer_ports: &[u16]) -> Result<Child> {
         // Ensure data directory exists
-        std::fs::create_dir_all(&self.config.data_dir).map_err(|e| {
+        std::fs::create_dir_all(&config.data_dir).map_err(|e| {
             ChainError::NodeManagement(format!("Failed to create data directory: {}", e))
         })?;
 
-        println!(
-            "🚀 Starting Bitcoin regtest node on port {}...",
-            self.config.rpc_port
-        );
-
-        // Start bitcoind in regtest mode
+        // Start bitcoind on the configured chain
         let mut cmd = Command::new("bitcoind");
-        cmd.arg("-regtest")
-            .arg(format!("-rpcport={}", self.config.rpc_port))
-            .arg(format!("-port={}", self.config.p2p_port))
-            .arg(format!("-datadir={}", self.config.data_dir.display()))
-            .arg(format!("-rpcuser={}", self.config.rpc_user))
-            .arg(format!("-rpcpassword={}", self.config.rpc_password))
+        cmd.args(config.chain.args())
+            .arg(format!("-rpcport={}", config.rpc_port))
+            .arg(format!("-port={}", config.p2p_port))
+            .arg(format!("-datadir={}", config.data_dir.display()))
+            .arg(format!("-rpcuser={}", config.rpc_user))
+            .arg(format!("-rpcpassword={}", config.rpc_password))
             .arg("-server=1")
             .arg("-txindex=1")
             .arg("-fallbackfee=0.0001")
             .arg("-daemon=0"); // Run in foreground so we can manage the process
 
+        for port in peer_ports {
+            cmd.arg(format!("-addnode=127.0.0.1:{}", port));
+        }
+
+        // Compact block filters for Neutrino-style light clients
+        if config.block_filter_index {
+            cmd.arg("-blockfilterindex=1").arg("-peerblockfilters=1");
+        }
+
+        for param in &config.chain_params {
+            cmd.arg(format!("-{}", param.trim_start_matches('-')));
+        }
+
         // Only enable console output in verbose mode
-        if self.config.verbose {
+        if config.verbose {
             cmd.arg("-printtoconsole=1");
         } else {
             cmd.arg("-printtoconsole=0");
@@ -332,7 +969,8 @@ impl BitcoinProvider {
                     rlim_max: 10240,
                 };
                 if libc::setrlimit(libc::RLIMIT_NOFILE, &limit) != 0 {
-                    // If setrlimit fails, try to continue anyway
+                    // If setrlimit fails, try to continue anyway. This runs in the
+                    // forked child, where taking the log subscriber's locks isn't safe.
                     eprintln!("Warning: Failed to set file descriptor limit");
                 }
                 Ok(())
@@ -340,25 +978,143 @@ impl BitcoinProvider {
         }
 
         // Configure stdio based on verbose mode
-        let child = if self.config.verbose {
+        let child = if config.verbose {
             cmd.stdin(Stdio::null())
                 .stdout(Stdio::inherit()) // Forward output to parent
                 .stderr(Stdio::inherit()) // Forward errors to parent
                 .spawn()
         } else {
+            // Init errors that happen before debug.log is opened only go to stderr
+            let stderr_file = std::fs::File::create(config.stderr_log_file()).map_err(|e| {
+                ChainError::NodeManagement(format!("Failed to create stderr log: {}", e))
+            })?;
             cmd.stdin(Stdio::null())
                 .stdout(Stdio::null()) // Suppress output
-                .stderr(Stdio::null()) // Suppress errors
+                .stderr(stderr_file)
                 .spawn()
         }
         .map_err(|e| ChainError::NodeManagement(format!("Failed to start bitcoind: {}", e)))?;
 
+        Ok(child)
+    }
+
+    /// Explain why bitcoind exited, if any node's has
+    ///
+    /// Known failures in its logs (port in use, locked or corrupted data,
+    /// incompatible versions) are turned into actionable messages.
+    fn startup_failure(&self) -> Option<ChainError> {
         let mut process_guard = self.bitcoind_process.lock().unwrap();
-        *process_guard = Some(child);
+        if let Some(status) = process_guard
+            .as_mut()
+            .and_then(|child| child.try_wait().ok().flatten())
+        {
+            process_guard.take();
+            return Some(ChainError::NodeManagement(Self::exit_reason(
+                &self.config,
+                status,
+            )));
+        }
+        drop(process_guard);
+
+        let mut peers = self.peer_processes.lock().unwrap();
+        peers.iter_mut().enumerate().find_map(|(index, child)| {
+            let status = child.try_wait().ok()??;
+            let node = self.config.node(index as u32 + 1).ok()?;
+            Some(ChainError::NodeManagement(format!(
+                "Node {}: {}",
+                index + 1,
+                Self::exit_reason(&node, status)
+            )))
+        })
+    }
+
+    /// Why a node's bitcoind exited, from its logs
+    fn exit_reason(config: &BitcoinConfig, status: ExitStatus) -> String {
+        let log_path = config.log_file();
+        let log: String = [log_path.clone(), config.stderr_log_file()]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        diagnose::startup_failure(&log, config).unwrap_or_else(|| {
+            format!(
+                "bitcoind exited unexpectedly (exit code: {}). Check logs at: {}",
+                status,
+                log_path.display()
+            )
+        })
+    }
+
+    /// Wait until every peer node has caught up with node 0's chain
+    fn wait_for_peers(&self) -> Result<()> {
+        if self.config.nodes <= 1 {
+            return Ok(());
+        }
+
+        let height = self.rpc_client()?.get_block_count()?;
+        info!(
+            peers = self.config.nodes - 1,
+            height, "Waiting for peer nodes to sync"
+        );
+        for index in 1..self.config.nodes {
+            let node = self.config.node(index)?;
+            let client = BitcoinRpcClient::new(node.rpc_url, node.rpc_user, node.rpc_password)?;
+            let mut attempts = 0;
+            loop {
+                if matches!(client.get_block_count(), Ok(count) if count >= height) {
+                    break;
+                }
+                attempts += 1;
+                if attempts == 120 {
+                    return Err(self.startup_failure().unwrap_or_else(|| {
+                        ChainError::Timeout(format!(
+                            "Node {} did not sync to height {} in time",
+                            index, height
+                        ))
+                    }));
+                }
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+        }
+        info!(nodes = self.config.nodes, "Nodes in sync");
 
         Ok(())
     }
 
+    /// Mine a block every `interval` seconds until the node stops
+    fn start_block_producer(&mut self, interval: u64) -> Result<()> {
+        let client = InstanceInfo::load(&self.config.instance_id)?.wallet_client()?;
+        let address = client.get_new_address(Some("mining"))?;
+        let instance_id = self.config.instance_id.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = std::thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let tick = std::time::Duration::from_millis(200);
+                let interval = std::time::Duration::from_secs(interval);
+                let mut waited = std::time::Duration::ZERO;
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(tick);
+                    waited += tick;
+                    if waited < interval {
+                        continue;
+                    }
+                    waited = std::time::Duration::ZERO;
+                    match client.mine_blocks(1, &address) {
+                        Ok(_) => Self::publish(&instance_id, ChainEvent::blocks_mined(1)),
+                        Err(e) => warn!(error = %e, "Interval mining failed"),
+                    }
+                }
+            }
+        });
+        self.block_producer = Some((stop, handle));
+
+        info!(interval_secs = interval, "Mining a block every interval");
+        Ok(())
+    }
+
     /// Get a reference to the RPC client
     pub fn rpc_client(&self) -> Result<&BitcoinRpcClient> {
         self.rpc_client.as_ref().ok_or(ChainError::NotRunning)
@@ -403,25 +1159,56 @@ impl ChainProvider for BitcoinProvider {
 
         self.config = config.clone();
 
+        if self.config.block_interval == Some(0) {
+            return Err(ChainError::Config(
+                "Block interval must be at least 1 second".to_string(),
+            ));
+        }
+        if self.config.block_interval.is_some() && !self.config.chain.can_mine() {
+            return Err(ChainError::Config(
+                "Blocks on this signet can't be mined, so it can't have a block interval"
+                    .to_string(),
+            ));
+        }
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::BeforeStart, &ctx)?;
+
         // Update storage to use instance-specific path
         self.storage = AccountsStorage::with_path(self.config.accounts_file());
 
-        println!(
-            "🧹 Clearing previous instance data for '{}'...",
-            self.config.instance_id
+        info!(
+            instance = %self.config.instance_id,
+            "Clearing previous instance data"
         );
 
         // Clear all previous instance data (blockchain + accounts) for clean slate
         self.clear_instance_data()?;
 
-        // Generate fresh accounts
-        self.generate_accounts()?;
+        // Restore from a snapshot, or generate fresh accounts
+        match self.config.snapshot.clone() {
+            Some(name) => self.restore_snapshot(&name)?,
+            None => self.generate_accounts()?,
+        }
+        let treasury = self.resolve_treasury()?;
+        let block_signer = self.config.chain.block_signer()?;
+        if let Some(signer) = &block_signer {
+            info!(signer = %signer.address, "Blocks are signed by the challenge key");
+        }
 
         // Start bitcoind
+        let mut timer = StartupTimer::new();
         self.start_bitcoind()?;
 
+        // Brief pause to detect early startup failures (e.g., port already in use)
+        std::thread::sleep(std::time::Duration::from_millis(1000));
+        if let Some(e) = self.startup_failure() {
+            return Err(e);
+        }
+        timer.lap(StartupPhase::Spawn);
+
         // Save instance info for CLI discovery
-        self.save_instance_info()?;
+        self.save_instance_info(treasury.as_ref().map(|t| t.address.clone()))?;
 
         // Initialize RPC and fund accounts using a separate thread
         let accounts_file = self.config.accounts_file();
@@ -431,6 +1218,12 @@ impl ChainProvider for BitcoinProvider {
             let rpc_password = self.config.rpc_password.clone();
             let initial_balance = self.config.initial_balance;
             let mut accounts_vec = self.accounts.clone();
+            let instance_id = self.config.instance_id.clone();
+            let wallet_passphrase = self.config.wallet_passphrase.clone();
+            let restored = self.config.snapshot.is_some();
+            let can_mine = self.config.chain.can_mine();
+            let retry = Config::load()?.funding_retry(ChainType::Bitcoin);
+            let mut timer = timer;
 
             move || {
                 // Create a new runtime in this thread
@@ -445,15 +1238,14 @@ impl ChainProvider for BitcoinProvider {
                         rpc_password.clone(),
                     )?;
 
-                    println!("⏳ Waiting for Bitcoin node to be ready...");
+                    info!("Waiting for Bitcoin node to be ready");
                     base_client.wait_for_node(60).await?;
-
-                    println!("✅ Bitcoin node is ready!");
-                    println!();
+                    info!("Bitcoin node is ready");
+                    timer.lap(StartupPhase::RpcReady);
 
                     // Create wallet
-                    println!("📦 Creating wallet...");
-                    base_client.create_wallet("chain-forge")?;
+                    info!(encrypted = wallet_passphrase.is_some(), "Creating wallet");
+                    base_client.create_wallet("chain-forge", wallet_passphrase.as_deref())?;
 
                     // Small delay to ensure wallet is fully initialized
                     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -464,55 +1256,92 @@ impl ChainProvider for BitcoinProvider {
                         rpc_user,
                         rpc_password,
                         "chain-forge",
-                    )?;
+                    )?
+                    .with_wallet_passphrase(wallet_passphrase)
+                    .with_block_signer(block_signer);
+                    timer.lap(StartupPhase::Wallet);
+
+                    // Restored snapshots keep their chain, wallet, and balances
+                    if restored {
+                        wallet_client.update_balances(&mut accounts_vec)?;
+                        AccountsStorage::with_path(accounts_file).save(&accounts_vec)?;
+                        info!("Snapshot restored");
+                        return Ok((wallet_client, accounts_vec, timer));
+                    }
+
+                    // Without a way to mine there are no coins to fund accounts from
+                    if !can_mine {
+                        warn!(
+                            "Blocks on this signet must be signed with its challenge key, \
+                             so accounts are not funded; mine to them with that key"
+                        );
+                        info!("Importing accounts into wallet");
+                        import_accounts(&wallet_client, &accounts_vec, treasury.as_ref())?;
+                        wallet_client.update_balances(&mut accounts_vec)?;
+                        AccountsStorage::with_path(accounts_file).save(&accounts_vec)?;
+                        timer.lap(StartupPhase::Import);
+                        return Ok((wallet_client, accounts_vec, timer));
+                    }
 
                     // Get a wallet address for mining (not one of our accounts)
                     // IMPORTANT: We import accounts AFTER funding to prevent the wallet
-                    // from spending the newly created UTXOs in subsequent transactions
-                    let mining_address = wallet_client.get_new_address(Some("mining"))?;
-                    println!("📍 Mining address: {}", &mining_address[..20]);
-
-                    // Calculate how many blocks to mine for sufficient funds
-                    // Each coinbase needs 100 confirmations to be spendable
-                    // Plus a fee buffer (~0.001 BTC per transaction) for sendtoaddress fees
-                    // On regtest, block reward halves every 150 blocks (50 -> 25 -> 12.5...)
+                    // from spending the newly created UTXOs in subsequent transactions.
+                    // A treasury is the exception: it is imported first so the wallet
+                    // can sign for the block rewards mined to it.
+                    let mining_address = match &treasury {
+                        Some(treasury) => {
+                            wallet_client.import_address(
+                                &treasury.address,
+                                &treasury.wif,
+                                "treasury",
+                            )?;
+                            treasury.address.clone()
+                        }
+                        None => wallet_client.get_new_address(Some("mining"))?,
+                    };
+                    info!(address = %mining_address, "Mining address");
+
+                    // Calculate how many blocks to mine for sufficient funds,
+                    // plus a fee buffer (~0.001 BTC per transaction) for sendtoaddress fees
                     let fee_buffer = accounts_vec.len() as f64 * 0.001;
                     let total_btc_needed =
                         accounts_vec.len() as f64 * initial_balance + fee_buffer;
-
-                    let mut accumulated = 0.0;
-                    let mut coinbase_blocks = 0u32;
-                    while accumulated < total_btc_needed {
-                        let era = coinbase_blocks / 150;
-                        let reward = 50.0 / (1u64 << era) as f64;
-                        if reward < 1e-8 {
-                            break;
-                        }
-                        accumulated += reward;
-                        coinbase_blocks += 1;
+                    let blocks_to_mine = blocks_to_fund(total_btc_needed);
+
+                    info!(blocks = blocks_to_mine, "Mining initial blocks");
+                    let mut mined = 0;
+                    while mined < blocks_to_mine {
+                        let batch = (blocks_to_mine - mined).min(PREMINE_BATCH);
+                        wallet_client.mine_blocks(batch, &mining_address)?;
+                        mined += batch;
+                        Self::publish(
+                            &instance_id,
+                            ChainEvent::StartupProgress {
+                                phase: StartupPhase::Premine,
+                                done: mined as u64,
+                                total: blocks_to_mine as u64,
+                            },
+                        );
                     }
-                    // 100 extra blocks so the earliest coinbase reaches maturity
-                    let blocks_to_mine = 100 + coinbase_blocks.max(1);
-
-                    println!(
-                        "⛏️  Mining {} initial blocks (this may take a moment)...",
-                        blocks_to_mine
+                    Self::publish(
+                        &instance_id,
+                        ChainEvent::blocks_mined(blocks_to_mine as u64),
                     );
-                    wallet_client.mine_blocks(blocks_to_mine, &mining_address)?;
 
                     // Wait for UTXO set to stabilize
                     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    timer.lap(StartupPhase::Premine);
 
                     // Check wallet balance before funding
                     // Account for transaction fees (~0.001 BTC per sendtoaddress call)
                     let wallet_balance = wallet_client.get_wallet_balance()?;
                     let needed = accounts_vec.len() as f64 * initial_balance;
                     let needed_with_fees = needed + fee_buffer;
-                    println!(
-                        "   Wallet balance: {} BTC (need {} BTC for {} accounts)",
-                        wallet_balance,
+                    info!(
+                        balance = wallet_balance,
                         needed,
-                        accounts_vec.len()
+                        accounts = accounts_vec.len(),
+                        "Wallet balance (BTC)"
                     );
 
                     if wallet_balance < needed_with_fees {
@@ -524,10 +1353,10 @@ impl ChainProvider for BitcoinProvider {
 
                     // Fund ALL accounts with initial balance
                     // We do this BEFORE importing so the wallet doesn't spend from them
-                    println!(
-                        "💰 Funding {} accounts with {} BTC each...",
-                        accounts_vec.len(),
-                        initial_balance
+                    info!(
+                        accounts = accounts_vec.len(),
+                        balance = initial_balance,
+                        "Funding accounts (BTC)"
                     );
 
                     // Set amount to send for each account
@@ -535,28 +1364,77 @@ impl ChainProvider for BitcoinProvider {
                         account.balance = initial_balance;
                     }
 
-                    // Fund accounts (may fail partially, will error if any fail)
-                    if let Err(e) = wallet_client.fund_accounts(&mut accounts_vec).await {
-                        eprintln!("Warning: Some accounts failed to fund: {}", e);
-                        eprintln!("         Continuing with partially funded accounts...");
+                    // Fund accounts (may fail partially, will error if any fail),
+                    // counting only what was actually sent
+                    let dispensed = match &treasury {
+                        Some(treasury) => match fund_from_treasury(
+                            &wallet_client,
+                            &treasury.address,
+                            &accounts_vec,
+                            initial_balance,
+                        ) {
+                            Ok(txid) => {
+                                info!(%txid, "Sent from the treasury");
+                                needed
+                            }
+                            Err(e) => {
+                                warn!(
+                                    error = %e,
+                                    "Funding from the treasury failed; accounts left unfunded"
+                                );
+                                0.0
+                            }
+                        },
+                        None => {
+                            let total = accounts_vec.len() as u64;
+                            let progress = |done: usize| {
+                                Self::publish(
+                                    &instance_id,
+                                    ChainEvent::StartupProgress {
+                                        phase: StartupPhase::Funding,
+                                        done: done as u64,
+                                        total,
+                                    },
+                                )
+                            };
+                            match wallet_client
+                                .fund_accounts(&mut accounts_vec, &retry, progress)
+                                .await
+                            {
+                                Ok(()) => needed,
+                                Err(e) => {
+                                    warn!(
+                                        error = %e,
+                                        "Some accounts failed to fund; continuing"
+                                    );
+                                    // Accounts that failed were reset to 0
+                                    accounts_vec.iter().map(|a| a.balance).sum()
+                                }
+                            }
+                        }
+                    };
+                    if dispensed > 0.0 {
+                        Self::publish(
+                            &instance_id,
+                            ChainEvent::FundsDispensed {
+                                address: None,
+                                amount: dispensed,
+                            },
+                        );
                     }
 
                     // Mine blocks to confirm all transactions
-                    println!("⛏️  Mining 6 blocks to confirm transactions...");
+                    info!(blocks = 6, "Mining blocks to confirm transactions");
                     wallet_client.mine_blocks(6, &mining_address)?;
+                    Self::publish(&instance_id, ChainEvent::blocks_mined(6));
 
                     // Wait for UTXO set to update after mining
                     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    timer.lap(StartupPhase::Funding);
 
                     // NOW import accounts into the wallet so we can track their balances
-                    println!("📥 Importing accounts into wallet...");
-                    for (i, account) in accounts_vec.iter().enumerate() {
-                        wallet_client.import_address(
-                            &account.address,
-                            &account.wif,
-                            &format!("account-{}", i),
-                        )?;
-                    }
+                    info!("Importing accounts into wallet");
+                    import_accounts(&wallet_client, &accounts_vec, treasury.as_ref())?;
 
                     // Wait for wallet to process imports
                     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -567,68 +1445,136 @@ impl ChainProvider for BitcoinProvider {
                     // Save updated accounts to instance-specific location
                     let storage = AccountsStorage::with_path(accounts_file);
                     storage.save(&accounts_vec)?;
+                    timer.lap(StartupPhase::Import);
 
-                    println!("✅ All accounts funded!");
-                    println!();
+                    info!("All accounts funded");
 
-                    Ok::<(BitcoinRpcClient, Vec<BitcoinAccount>), ChainError>((
+                    Ok::<(BitcoinRpcClient, Vec<BitcoinAccount>, StartupTimer), ChainError>((
                         wallet_client,
                         accounts_vec,
+                        timer,
                     ))
                 })
             }
         })
         .join()
-        .map_err(|_| ChainError::Other("Initialization thread panicked".to_string()))??;
+        .map_err(|_| ChainError::Other("Initialization thread panicked".to_string()))?
+        // bitcoind can also fail while loading its chain, after the first check
+        .map_err(|e| self.startup_failure().unwrap_or(e))?;
 
         self.rpc_client = Some(result.0);
         self.accounts = result.1;
+        if let Err(e) = self.save_startup_profile(result.2.finish()) {
+            warn!(error = %e, "Failed to save the startup profile");
+        }
+
+        self.wait_for_peers()?;
+
+        if let Some(interval) = self.config.block_interval {
+            self.start_block_producer(interval)?;
+        }
 
         // Register with global node registry
         if let Err(e) = self.register_with_registry() {
-            eprintln!("Warning: Failed to register with node registry: {}", e);
+            warn!(error = %e, "Failed to register with node registry");
         }
+        self.heartbeat = Some(Heartbeat::start(
+            NodeRegistry::node_id(ChainType::Bitcoin, &self.config.instance_id),
+            HEARTBEAT_INTERVAL,
+        ));
+        Self::publish(&self.config.instance_id, ChainEvent::NodeStarted);
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::AfterStart, &ctx)?;
 
         let instance_name = self
             .config
             .name
             .as_ref()
             .unwrap_or(&self.config.instance_id);
-        println!("🎉 Bitcoin regtest node '{}' is running!", instance_name);
-        println!("   RPC URL: {}", self.config.rpc_url);
-        println!();
+        info!(
+            chain = %self.config.chain.name(),
+            instance = %instance_name,
+            rpc_url = %self.config.rpc_url,
+            "Bitcoin node is running"
+        );
+        for index in 1..self.config.nodes {
+            let node = self.config.node(index)?;
+            info!(node = index, rpc_url = %node.rpc_url, "Peer node is running");
+        }
 
         Ok(())
     }
 
     fn stop(&mut self) -> Result<()> {
+        if self.is_running() {
+            let ctx = self.hook_context();
+            self.hooks.run_before_stop(&ctx);
+        }
+
+        if let Some(mut heartbeat) = self.heartbeat.take() {
+            heartbeat.stop();
+        }
+
+        if let Some((stop, handle)) = self.block_producer.take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+
+        // Peer nodes have no wallet or registry entry, so they just go away
+        let mut peers: Vec<Child> = self.peer_processes.lock().unwrap().drain(..).collect();
+        for peer in &mut peers {
+            terminate(peer);
+        }
+        for peer in &mut peers {
+            let _ = wait_for_shutdown(peer);
+        }
+
         let mut process_guard = self.bitcoind_process.lock().unwrap();
 
         if let Some(mut child) = process_guard.take() {
-            // Kill the process
-            let _ = child.kill();
-            child.wait().map_err(|e| {
-                ChainError::NodeManagement(format!("Failed to wait for bitcoind: {}", e))
-            })?;
+            // The height is only known while bitcoind still answers
+            let height = self
+                .rpc_client
+                .as_ref()
+                .filter(|_| self.snapshot_on_stop)
+                .and_then(|client| client.get_block_count().ok());
+
+            // Killing bitcoind can leave the chainstate half-written, which
+            // matters when the data is kept, so ask it to shut down first
+            let stopping = self
+                .rpc_client
+                .as_ref()
+                .is_some_and(|client| client.stop_node().is_ok());
+            if !stopping {
+                terminate(&mut child);
+            }
+            wait_for_shutdown(&mut child)?;
 
             // Unregister from global node registry
             if let Err(e) = self.unregister_from_registry() {
-                eprintln!("Warning: Failed to unregister from node registry: {}", e);
+                warn!(error = %e, "Failed to unregister from node registry");
             }
+            Self::publish(&self.config.instance_id, ChainEvent::NodeStopped);
 
             // Mark instance as stopped
             if let Ok(mut info) = InstanceInfo::load(&self.config.instance_id) {
                 let _ = info.mark_stopped();
             }
 
+            if self.snapshot_on_stop {
+                self.save_stop_snapshot(height.unwrap_or(0));
+            }
+
             // Clean up instance data unless keep_data is set
             if !self.keep_data {
                 let _ = self.clear_instance_data();
             }
 
-            println!(
-                "🛑 Bitcoin regtest node stopped (instance: {})",
-                self.config.instance_id
+            info!(
+                chain = %self.config.chain.name(),
+                instance = %self.config.instance_id,
+                "Bitcoin node stopped"
             );
         }
 
@@ -664,6 +1610,45 @@ impl ChainProvider for BitcoinProvider {
     fn get_rpc_url(&self) -> String {
         self.config.rpc_url.clone()
     }
+
+    fn hooks(&mut self) -> &mut LifecycleHooks {
+        &mut self.hooks
+    }
+}
+
+/// Ask bitcoind to shut down cleanly with SIGTERM
+///
+/// There is no such signal on Windows, so the process is killed there.
+fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+}
+
+/// Wait for bitcoind to exit, killing it if it takes over [`SHUTDOWN_TIMEOUT`]
+fn wait_for_shutdown(child: &mut Child) -> Result<ExitStatus> {
+    let wait_error = |e: std::io::Error| {
+        ChainError::NodeManagement(format!("Failed to wait for bitcoind: {}", e))
+    };
+    let deadline = std::time::Instant::now() + SHUTDOWN_TIMEOUT;
+
+    while std::time::Instant::now() < deadline {
+        if let Some(status) = child.try_wait().map_err(wait_error)? {
+            return Ok(status);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    warn!(
+        pid = child.id(),
+        timeout_secs = SHUTDOWN_TIMEOUT.as_secs(),
+        "bitcoind did not shut down in time, killing it"
+    );
+    let _ = child.kill();
+    child.wait().map_err(wait_error)
 }
 
 impl Drop for BitcoinProvider {
@@ -704,12 +1689,25 @@ mod tests {
             accounts: 5,
             initial_balance: 50.0,
             mnemonic: None,
+            start_index: 0,
             rpc_user: "test".to_string(),
             rpc_password: "test".to_string(),
             data_dir: PathBuf::from("/tmp/bitcoin-test"),
             verbose: false,
             instance_id: "test".to_string(),
             name: None,
+            block_filter_index: false,
+            external_signers: Vec::new(),
+            account_labels: Vec::new(),
+            treasury: None,
+            wallet_passphrase: None,
+            snapshot: None,
+            chain: BitcoinChain::Regtest,
+            chain_params: Vec::new(),
+            nodes: 1,
+            block_interval: None,
+            confirmations: Confirmations::default(),
+            address_type: AddressType::default(),
         };
 
         let provider = BitcoinProvider::with_config(config);
@@ -717,6 +1715,12 @@ mod tests {
         assert_eq!(provider.get_rpc_url(), "http://localhost:19000");
     }
 
+    #[test]
+    fn test_block_filter_index_disabled_by_default() {
+        let config = BitcoinConfig::default();
+        assert!(!config.block_filter_index);
+    }
+
     #[test]
     fn test_provider_not_running_initially() {
         let provider = BitcoinProvider::new();
@@ -805,6 +1809,16 @@ mod tests {
             rpc_password: "chainforge".to_string(),
             accounts_count: 10,
             running: true,
+            mock_time: None,
+            epoch: 0,
+            treasury: None,
+            wallet_passphrase: None,
+            chain: BitcoinChain::Regtest,
+            peers: Vec::new(),
+            network: None,
+            confirmations: Confirmations::default(),
+            address_type: AddressType::default(),
+            startup: StartupProfile::default(),
         };
 
         // Serialize to JSON
@@ -825,6 +1839,167 @@ mod tests {
         assert!(deserialized.running);
     }
 
+    #[test]
+    fn test_instance_info_without_mock_time() {
+        // Instance files written before clock overrides existed
+        let json = r#"{
+            "instance_id": "default",
+            "name": null,
+            "rpc_url": "http://127.0.0.1:18443",
+            "rpc_port": 18443,
+            "p2p_port": 18444,
+            "rpc_user": "chainforge",
+            "rpc_password": "chainforge",
+            "accounts_count": 10,
+            "running": true
+        }"#;
+
+        let info: InstanceInfo = serde_json::from_str(json).unwrap();
+        assert!(info.mock_time.is_none());
+        assert_eq!(info.epoch, 0);
+        assert!(info.treasury.is_none());
+        assert!(info.wallet_passphrase.is_none());
+        assert_eq!(info.chain, BitcoinChain::Regtest);
+        assert_eq!(info.confirmations, Confirmations::default());
+        assert_eq!(info.address_type, AddressType::Bech32);
+
+        // Gets a version when it is next read through the schema
+        let (upgraded, changed) = INSTANCE_SCHEMA
+            .upgrade(serde_json::from_str(json).unwrap())
+            .unwrap();
+        assert!(changed);
+        assert_eq!(upgraded["version"], 1);
+        let info: InstanceInfo = serde_json::from_value(upgraded).unwrap();
+        assert_eq!(info.instance_id, "default");
+    }
+
+    #[test]
+    fn test_blocks_to_fund() {
+        // One block's reward, plus 100 for maturity
+        assert_eq!(blocks_to_fund(0.0), 101);
+        assert_eq!(blocks_to_fund(50.0), 101);
+        assert_eq!(blocks_to_fund(100.1), 103);
+        // Rewards halve after 150 blocks: 150 * 50 + 25
+        assert_eq!(blocks_to_fund(7510.0), 251);
+    }
+
+    #[test]
+    fn test_signet_chain() {
+        let open = BitcoinChain::signet(None).unwrap();
+        assert_eq!(
+            open,
+            BitcoinChain::Signet {
+                challenge: "51".to_string(),
+                signer: None,
+            }
+        );
+        assert!(open.can_mine());
+        assert_eq!(open.network(), Network::Signet);
+        assert_eq!(open.args(), vec!["-signet", "-signetchallenge=51"]);
+
+        let challenge = "5121AB".to_string() + &"cd".repeat(32) + "51ae";
+        let signed = BitcoinChain::signet(Some(challenge)).unwrap();
+        assert!(!signed.can_mine());
+
+        assert!(BitcoinChain::signet(Some("5".to_string())).is_err());
+        assert!(BitcoinChain::signet(Some("zz".to_string())).is_err());
+        assert!(BitcoinChain::signet(Some(String::new())).is_err());
+    }
+
+    #[test]
+    fn test_managed_signet() {
+        let managed = BitcoinChain::managed_signet().unwrap();
+        assert!(managed.can_mine());
+
+        let signer = managed.block_signer().unwrap().unwrap();
+        assert!(signer.address.starts_with("tb1q"));
+        let BitcoinChain::Signet { challenge, .. } = &managed else {
+            panic!("managed signet is a signet");
+        };
+        // P2WPKH: OP_0 <20-byte key hash>
+        assert!(challenge.starts_with("0014"));
+        assert_eq!(challenge.len(), 44);
+
+        // The key survives a round trip through instance.json
+        let json = serde_json::to_string(&managed).unwrap();
+        let restored: BitcoinChain = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, managed);
+
+        assert!(BitcoinChain::signet(None)
+            .unwrap()
+            .block_signer()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_log_file_follows_chain() {
+        let mut config = BitcoinConfig::with_instance("chain-test");
+        assert!(config.log_file().ends_with("regtest/debug.log"));
+
+        config.chain = BitcoinChain::signet(None).unwrap();
+        assert!(config.log_file().ends_with("signet/debug.log"));
+    }
+
+    #[test]
+    fn test_node_configs() {
+        let mut config = BitcoinConfig::with_instance("multi");
+        config.nodes = 3;
+
+        let node0 = config.node(0).unwrap();
+        assert_eq!(node0.rpc_port, 18443);
+        assert_eq!(node0.data_dir, config.data_dir);
+        assert_eq!(
+            node0.stderr_log_file(),
+            config.instance_dir().join("bitcoind_stderr.log")
+        );
+
+        let node2 = config.node(2).unwrap();
+        assert_eq!(node2.rpc_port, 18463);
+        assert_eq!(node2.p2p_port, 18464);
+        assert_eq!(node2.rpc_url, "http://127.0.0.1:18463");
+        assert_eq!(
+            node2.data_dir,
+            config.instance_dir().join("node2").join("regtest-data")
+        );
+        assert_eq!(
+            node2.stderr_log_file(),
+            config
+                .instance_dir()
+                .join("node2")
+                .join("bitcoind_stderr.log")
+        );
+
+        config.rpc_port = 65530;
+        assert!(config.node(1).is_err());
+    }
+
+    #[test]
+    fn test_instance_info_nodes() {
+        let json = r#"{
+            "instance_id": "multi",
+            "name": null,
+            "rpc_url": "http://127.0.0.1:18443",
+            "rpc_port": 18443,
+            "p2p_port": 18444,
+            "rpc_user": "user",
+            "rpc_password": "pass",
+            "accounts_count": 5,
+            "running": true
+        }"#;
+        let mut info: InstanceInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.node_count(), 1);
+        assert!(info.node_client(1).is_err());
+
+        info.peers.push(PeerNode {
+            rpc_url: "http://127.0.0.1:18453".to_string(),
+            rpc_port: 18453,
+            p2p_port: 18454,
+        });
+        assert_eq!(info.node_count(), 2);
+        assert_eq!(info.node_client(1).unwrap().url(), "http://127.0.0.1:18453");
+    }
+
     #[test]
     fn test_instance_info_without_name() {
         let info = InstanceInfo {
@@ -837,6 +2012,16 @@ mod tests {
             rpc_password: "pass".to_string(),
             accounts_count: 5,
             running: false,
+            mock_time: None,
+            epoch: 0,
+            treasury: None,
+            wallet_passphrase: None,
+            chain: BitcoinChain::Regtest,
+            peers: Vec::new(),
+            network: None,
+            confirmations: Confirmations::default(),
+            address_type: AddressType::default(),
+            startup: StartupProfile::default(),
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -0,0 +1,168 @@
+//! Passthrough instances that track accounts on a public test network.
+//!
+//! Attaching derives accounts for the network and records an instance
+//! without spawning bitcoind. There is no node wallet to ask, so balances
+//! come from an Esplora-style explorer API, which is what the instance's
+//! RPC URL points at. Funding happens through the network's faucets.
+
+use crate::{BitcoinConfig, Confirmations, InstanceInfo};
+use bitcoin::Network;
+use chain_forge_bitcoin_accounts::{AccountGenerator, AccountsStorage, BitcoinAccount};
+use chain_forge_bitcoin_rpc::EsploraClient;
+use chain_forge_common::{
+    ChainError, ChainType, NodeInfo, NodeRegistry, NodeStatus, Result, StartupProfile,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::info;
+
+/// Public Bitcoin test network an instance can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicNetwork {
+    Testnet4,
+    Testnet,
+    Signet,
+}
+
+impl PublicNetwork {
+    /// A public Esplora API for the network
+    pub fn default_api_url(&self) -> &'static str {
+        match self {
+            PublicNetwork::Testnet4 => "https://mempool.space/testnet4/api",
+            PublicNetwork::Testnet => "https://mempool.space/testnet/api",
+            PublicNetwork::Signet => "https://mempool.space/signet/api",
+        }
+    }
+
+    /// Network addresses and keys are encoded for
+    ///
+    /// Testnet4 shares testnet3's address and key encodings.
+    pub fn network(&self) -> Network {
+        match self {
+            PublicNetwork::Testnet4 | PublicNetwork::Testnet => Network::Testnet,
+            PublicNetwork::Signet => Network::Signet,
+        }
+    }
+}
+
+impl fmt::Display for PublicNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicNetwork::Testnet4 => write!(f, "testnet4"),
+            PublicNetwork::Testnet => write!(f, "testnet"),
+            PublicNetwork::Signet => write!(f, "signet"),
+        }
+    }
+}
+
+/// Attach an instance to a public test network
+///
+/// Uses the instance ID, name, account count, mnemonic and labels from
+/// `config`; its `rpc_url` is the Esplora API balances are read from. An
+/// existing local node instance with the same ID is left alone; re-attaching
+/// a passthrough instance replaces its accounts. Returns the accounts with
+/// their current balances.
+pub fn attach(config: &BitcoinConfig, network: PublicNetwork) -> Result<Vec<BitcoinAccount>> {
+    if let Ok(info) = InstanceInfo::load(&config.instance_id) {
+        if info.running && info.network.is_none() {
+            return Err(ChainError::AlreadyRunning);
+        }
+    }
+
+    let client = EsploraClient::new(config.rpc_url.clone());
+    if !client.is_reachable() {
+        return Err(ChainError::Rpc(format!(
+            "Bitcoin {} API {} is not reachable",
+            network,
+            client.url()
+        )));
+    }
+
+    let generator = if let Some(mnemonic) = &config.mnemonic {
+        AccountGenerator::from_mnemonic_with_network(mnemonic, network.network())?
+    } else {
+        AccountGenerator::new_with_network(network.network())?
+    }
+    .with_address_type(config.address_type)?;
+
+    info!(
+        mnemonic = %generator.mnemonic_phrase(),
+        "Generated accounts; save this mnemonic to recover them"
+    );
+
+    let mut accounts = generator.generate_accounts(config.accounts)?;
+    for (account, label) in accounts.iter_mut().zip(&config.account_labels) {
+        account.label = Some(label.clone());
+    }
+    client.update_balances(&mut accounts)?;
+    AccountsStorage::with_path(config.accounts_file()).save(&accounts)?;
+
+    let info = InstanceInfo {
+        instance_id: config.instance_id.clone(),
+        name: config.name.clone(),
+        rpc_url: client.url().to_string(),
+        rpc_port: 0,
+        p2p_port: 0,
+        rpc_user: String::new(),
+        rpc_password: String::new(),
+        accounts_count: config.accounts,
+        running: true,
+        mock_time: None,
+        epoch: 0,
+        treasury: None,
+        wallet_passphrase: None,
+        chain: Default::default(),
+        peers: Vec::new(),
+        network: Some(network),
+        confirmations: Confirmations::default(),
+        address_type: config.address_type,
+        startup: StartupProfile::default(),
+    };
+    info.save()?;
+
+    NodeRegistry::new().register(NodeInfo::new(
+        ChainType::Bitcoin,
+        &config.instance_id,
+        config.name.clone(),
+        info.rpc_url,
+        0,
+        config.accounts,
+    ))?;
+
+    Ok(accounts)
+}
+
+/// Detach a passthrough instance, keeping its accounts on disk
+pub fn detach(instance_id: &str) -> Result<()> {
+    let mut info = InstanceInfo::load(instance_id)?;
+    if info.network.is_none() {
+        return Err(ChainError::Config(format!(
+            "Instance '{}' runs a local node; stop it instead",
+            instance_id
+        )));
+    }
+
+    info.mark_stopped()?;
+    NodeRegistry::new().update_status(
+        &NodeRegistry::node_id(ChainType::Bitcoin, instance_id),
+        NodeStatus::Stopped,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_encoding() {
+        assert_eq!(PublicNetwork::Testnet4.network(), Network::Testnet);
+        assert_eq!(PublicNetwork::Signet.network(), Network::Signet);
+
+        let json = serde_json::to_string(&PublicNetwork::Testnet4).unwrap();
+        assert_eq!(json, "\"testnet4\"");
+        let network: PublicNetwork = serde_json::from_str("\"signet\"").unwrap();
+        assert_eq!(network, PublicNetwork::Signet);
+        assert_eq!(network.to_string(), "signet");
+    }
+}
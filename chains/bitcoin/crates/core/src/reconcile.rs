@@ -0,0 +1,197 @@
+//! Reconciling the balances an instance reports for its accounts.
+//!
+//! An account's balance can be read three ways: from the node wallet
+//! (`listunspent`), from the UTXO set (`scantxoutset`), and from the value
+//! cached in `accounts.json`, which the dashboard shows. They should agree;
+//! when they don't, the usual cause is a descriptor imported after the
+//! account was funded without a rescan, or a cache that wasn't refreshed
+//! after a transaction.
+//!
+//! `scantxoutset` only sees confirmed outputs, so unconfirmed ones are left
+//! out of the wallet balance too.
+
+use crate::{BitcoinConfig, InstanceInfo};
+use chain_forge_bitcoin_accounts::{AccountsStorage, BitcoinAccount};
+use chain_forge_bitcoin_rpc::BitcoinRpcClient;
+use chain_forge_common::{ChainError, Result};
+use serde::{Deserialize, Serialize};
+
+/// A way an account's balances disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// The wallet is missing confirmed outputs that are in the UTXO set
+    MissingFromWallet,
+    /// The wallet counts outputs that aren't in the UTXO set
+    WalletAhead,
+    /// `accounts.json` doesn't match the UTXO set
+    StaleCache,
+}
+
+impl Discrepancy {
+    /// What usually causes it and how to fix it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::MissingFromWallet => {
+                "the address was likely imported after it was funded; rescan the wallet with 'bitcoin-cli rescanblockchain'"
+            }
+            Self::WalletAhead => {
+                "the wallet still counts spent or reorged outputs; rescan the wallet with 'bitcoin-cli rescanblockchain'"
+            }
+            Self::StaleCache => {
+                "the cached balance is out of date; 'cf-bitcoin accounts' refreshes it"
+            }
+        }
+    }
+}
+
+/// The balances of one account, in BTC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalances {
+    pub index: usize,
+    pub address: String,
+    pub label: Option<String>,
+    /// Balance cached in `accounts.json`
+    pub cached: f64,
+    /// Confirmed balance the node wallet tracks
+    pub wallet: f64,
+    /// Balance of the address in the UTXO set
+    pub utxo_set: f64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Balances of an instance's accounts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceReport {
+    pub accounts: Vec<AccountBalances>,
+}
+
+impl BalanceReport {
+    /// Number of accounts whose balances disagree
+    pub fn mismatch_count(&self) -> usize {
+        self.accounts
+            .iter()
+            .filter(|account| !account.discrepancies.is_empty())
+            .count()
+    }
+
+    /// Whether every account's balances agree
+    pub fn is_clean(&self) -> bool {
+        self.mismatch_count() == 0
+    }
+}
+
+/// Compare the balances of the accounts of a running instance
+pub fn reconcile_instance(instance_id: &str) -> Result<BalanceReport> {
+    let info = InstanceInfo::load(instance_id)?;
+    let client = info.wallet_client()?;
+    if !client.is_node_running() {
+        return Err(ChainError::NotRunning);
+    }
+
+    let storage =
+        AccountsStorage::with_path(BitcoinConfig::with_instance(instance_id).accounts_file());
+    reconcile_accounts(&client, &storage.load()?)
+}
+
+/// Compare the cached balances of `accounts` with the node's wallet and
+/// UTXO set
+pub fn reconcile_accounts(
+    client: &BitcoinRpcClient,
+    accounts: &[BitcoinAccount],
+) -> Result<BalanceReport> {
+    let mut report = BalanceReport::default();
+
+    for (index, account) in accounts.iter().enumerate() {
+        let wallet = client
+            .list_unspent(&account.address)?
+            .iter()
+            .filter(|utxo| utxo.confirmations > 0)
+            .map(|utxo| utxo.amount)
+            .sum();
+        let utxo_set = client.get_balance(&account.address)?;
+
+        report.accounts.push(AccountBalances {
+            index,
+            address: account.address.clone(),
+            label: account.label.clone(),
+            cached: account.balance,
+            wallet,
+            utxo_set,
+            discrepancies: compare(account.balance, wallet, utxo_set),
+        });
+    }
+
+    Ok(report)
+}
+
+/// How balances read from the cache and the wallet differ from the UTXO set
+fn compare(cached: f64, wallet: f64, utxo_set: f64) -> Vec<Discrepancy> {
+    let utxo_set = to_sats(utxo_set);
+    let mut discrepancies = Vec::new();
+
+    match to_sats(wallet).cmp(&utxo_set) {
+        std::cmp::Ordering::Less => discrepancies.push(Discrepancy::MissingFromWallet),
+        std::cmp::Ordering::Greater => discrepancies.push(Discrepancy::WalletAhead),
+        std::cmp::Ordering::Equal => {}
+    }
+    if to_sats(cached) != utxo_set {
+        discrepancies.push(Discrepancy::StaleCache);
+    }
+    discrepancies
+}
+
+/// A BTC amount in satoshis, so float rounding doesn't count as a mismatch
+fn to_sats(btc: f64) -> i64 {
+    (btc * 1e8).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_agreeing_balances() {
+        assert!(compare(0.1 + 0.2, 0.3, 0.3).is_empty());
+        assert!(compare(0.0, 0.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_compare_finds_discrepancies() {
+        // Imported after funding without a rescan: the wallet sees nothing
+        assert_eq!(
+            compare(10.0, 0.0, 10.0),
+            vec![Discrepancy::MissingFromWallet]
+        );
+        assert_eq!(compare(10.0, 12.0, 10.0), vec![Discrepancy::WalletAhead]);
+        assert_eq!(compare(10.0, 5.0, 5.0), vec![Discrepancy::StaleCache]);
+        assert_eq!(
+            compare(10.0, 0.0, 5.0),
+            vec![Discrepancy::MissingFromWallet, Discrepancy::StaleCache]
+        );
+    }
+
+    #[test]
+    fn test_mismatch_count() {
+        let account = |discrepancies| AccountBalances {
+            index: 0,
+            address: "bcrt1q".to_string(),
+            label: None,
+            cached: 1.0,
+            wallet: 1.0,
+            utxo_set: 1.0,
+            discrepancies,
+        };
+        let mut report = BalanceReport::default();
+        report.accounts.push(account(Vec::new()));
+        assert!(report.is_clean());
+
+        report.accounts.push(account(vec![
+            Discrepancy::WalletAhead,
+            Discrepancy::StaleCache,
+        ]));
+        assert_eq!(report.mismatch_count(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+//! Reset a running instance back to a clean chain without restarting bitcoind.
+//!
+//! The chain is rolled back to genesis with `invalidateblock`, the wallet is
+//! re-funded by mining a fresh chain, and every account is funded again, just
+//! as on startup. Accounts keep their keys, and an instance with a treasury
+//! is mined to and funded from it again.
+
+use crate::{blocks_to_fund, fund_from_treasury, BitcoinConfig, InstanceInfo};
+use chain_forge_bitcoin_accounts::{AccountsStorage, BitcoinAccount};
+use chain_forge_common::{ChainError, ChainType, Result};
+use chain_forge_events::ChainEvent;
+use std::time::Duration;
+use tracing::info;
+
+/// Roll an instance back to genesis and fund each account with `balance` BTC
+///
+/// Bumps the instance's `epoch` and clears any clock override. Returns the
+/// accounts with their new balances.
+pub fn reset_instance(instance_id: &str, balance: f64) -> Result<Vec<BitcoinAccount>> {
+    let mut info = InstanceInfo::load(instance_id)?;
+    let client = info.wallet_client()?;
+    if !client.is_node_running() {
+        return Err(ChainError::NotRunning);
+    }
+
+    let storage =
+        AccountsStorage::with_path(BitcoinConfig::with_instance(instance_id).accounts_file());
+    let mut accounts = storage.load()?;
+
+    if info.mock_time.take().is_some() {
+        client.set_mock_time(0)?;
+    }
+
+    let height = client.invalidate_to_genesis()?;
+    info!(blocks = height, "Rolled back to genesis");
+
+    let fee_buffer = accounts.len() as f64 * 0.001;
+    let blocks = blocks_to_fund(accounts.len() as f64 * balance + fee_buffer);
+    let mining_address = match &info.treasury {
+        Some(treasury) => treasury.clone(),
+        None => client.get_new_address(Some("mining"))?,
+    };
+    info!(blocks, "Mining blocks");
+    client.mine_blocks(blocks, &mining_address)?;
+    publish(instance_id, ChainEvent::blocks_mined(blocks as u64));
+
+    info!(accounts = accounts.len(), balance, "Funding accounts (BTC)");
+    match &info.treasury {
+        Some(treasury) => {
+            fund_from_treasury(&client, treasury, &accounts, balance)?;
+        }
+        None => {
+            for account in &accounts {
+                client.send_to_address(&account.address, balance)?;
+                // Let the wallet see its change output before the next send
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+    publish(
+        instance_id,
+        ChainEvent::FundsDispensed {
+            address: None,
+            amount: accounts.len() as f64 * balance,
+        },
+    );
+
+    client.mine_blocks(6, &mining_address)?;
+    publish(instance_id, ChainEvent::blocks_mined(6));
+
+    client.update_balances(&mut accounts)?;
+    storage.save(&accounts)?;
+    for account in &accounts {
+        publish(
+            instance_id,
+            ChainEvent::BalanceChanged {
+                address: account.address.clone(),
+                balance: account.balance,
+            },
+        );
+    }
+
+    info.epoch += 1;
+    info.save()?;
+
+    Ok(accounts)
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Bitcoin, instance_id, event);
+}
@@ -0,0 +1,154 @@
+//! Chain snapshots for resetting to a known state.
+//!
+//! A snapshot is a `tar.gz` of an instance's regtest data directory (chain
+//! and wallet) and `accounts.json`, taken after bitcoind has flushed its
+//! chain state to disk. Snapshots live under
+//! `~/.chain-forge/bitcoin/snapshots/<name>/`.
+
+use crate::BitcoinChain;
+use chain_forge_common::{ChainError, Result};
+use chain_forge_config::Config;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ARCHIVE_FILE: &str = "chain.tar.gz";
+const METADATA_FILE: &str = "snapshot.json";
+
+/// Metadata stored next to a snapshot archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinSnapshot {
+    /// Snapshot name
+    pub name: String,
+    /// Instance the snapshot was taken from
+    pub source_instance: String,
+    /// Block height when the snapshot was taken
+    pub height: u64,
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+    /// Chain the snapshot belongs to
+    #[serde(default)]
+    pub chain: BitcoinChain,
+}
+
+impl BitcoinSnapshot {
+    /// Directory holding all snapshots
+    pub fn snapshots_dir() -> PathBuf {
+        Config::data_dir().join("bitcoin").join("snapshots")
+    }
+
+    /// Directory for a named snapshot
+    pub fn dir(name: &str) -> PathBuf {
+        Self::snapshots_dir().join(name)
+    }
+
+    /// Path of the chain archive
+    pub fn archive_path(&self) -> PathBuf {
+        Self::dir(&self.name).join(ARCHIVE_FILE)
+    }
+
+    /// Load snapshot metadata by name
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::dir(name).join(METADATA_FILE);
+        if !path.exists() {
+            return Err(ChainError::Other(format!("Snapshot '{}' not found", name)));
+        }
+
+        let json = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// All saved snapshots, oldest first
+    pub fn list() -> Result<Vec<Self>> {
+        let dir = Self::snapshots_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if let Some(name) = entry.file_name().to_str() {
+                // Skip directories left behind by a failed create
+                if let Ok(snapshot) = Self::load(name) {
+                    snapshots.push(snapshot);
+                }
+            }
+        }
+        snapshots.sort_by_key(|s| s.created_at);
+        Ok(snapshots)
+    }
+
+    /// Archive an instance directory's chain, wallet, and accounts into a new snapshot
+    pub fn create(
+        name: &str,
+        source_instance: &str,
+        height: u64,
+        chain: BitcoinChain,
+        instance_dir: &Path,
+    ) -> Result<Self> {
+        let dir = Self::dir(name);
+        if dir.exists() {
+            return Err(ChainError::Other(format!(
+                "Snapshot '{}' already exists",
+                name
+            )));
+        }
+        std::fs::create_dir_all(&dir)?;
+
+        let snapshot = Self {
+            name: name.to_string(),
+            source_instance: source_instance.to_string(),
+            height,
+            created_at: Utc::now(),
+            chain,
+        };
+
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(snapshot.archive_path())
+            .arg("--exclude=debug.log")
+            .arg("-C")
+            .arg(instance_dir)
+            .arg("regtest-data")
+            .arg("accounts.json")
+            .status()
+            .map_err(|e| ChainError::Other(format!("Failed to run tar: {}", e)))?;
+
+        if !status.success() {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(ChainError::Other(format!(
+                "Failed to archive chain (tar exited with {})",
+                status
+            )));
+        }
+
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        std::fs::write(dir.join(METADATA_FILE), json)?;
+
+        Ok(snapshot)
+    }
+
+    /// Extract the snapshot's chain, wallet, and accounts into an instance directory
+    pub fn restore(&self, instance_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(instance_dir)?;
+
+        let status = Command::new("tar")
+            .arg("-xzf")
+            .arg(self.archive_path())
+            .arg("-C")
+            .arg(instance_dir)
+            .status()
+            .map_err(|e| ChainError::Other(format!("Failed to run tar: {}", e)))?;
+
+        if !status.success() {
+            return Err(ChainError::Other(format!(
+                "Failed to restore snapshot '{}' (tar exited with {})",
+                self.name, status
+            )));
+        }
+
+        Ok(())
+    }
+}
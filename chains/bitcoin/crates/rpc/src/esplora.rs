@@ -0,0 +1,140 @@
+//! Read-only client for Esplora-style block explorer APIs.
+//!
+//! Public test networks have no node Chain Forge controls, so balances of
+//! attached instances come from an explorer's REST API instead
+//! (e.g. `https://mempool.space/testnet4/api`).
+
+use chain_forge_bitcoin_accounts::BitcoinAccount;
+use chain_forge_common::{rpc_metrics, ChainError, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// Satoshis per bitcoin
+const SATS_PER_BTC: f64 = 100_000_000.0;
+
+/// Funded and spent totals of an address (from `GET /address/:address`)
+#[derive(Debug, Clone, Default, Deserialize)]
+struct AddressStats {
+    funded_txo_sum: u64,
+    spent_txo_sum: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AddressInfo {
+    chain_stats: AddressStats,
+    #[serde(default)]
+    mempool_stats: AddressStats,
+}
+
+impl AddressInfo {
+    /// Balance in satoshis, unconfirmed transactions included
+    fn balance(&self) -> i64 {
+        let funded = self.chain_stats.funded_txo_sum + self.mempool_stats.funded_txo_sum;
+        let spent = self.chain_stats.spent_txo_sum + self.mempool_stats.spent_txo_sum;
+        funded as i64 - spent as i64
+    }
+}
+
+/// Client for an Esplora REST API
+pub struct EsploraClient {
+    base_url: String,
+}
+
+impl EsploraClient {
+    /// Create a client for an API base URL
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Base URL of the API
+    pub fn url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Send a GET request for a path under the base URL, recorded in the
+    /// RPC metrics as `method`
+    fn get(&self, method: &str, path: &str) -> Result<ureq::Response> {
+        let started = Instant::now();
+        let response = ureq::get(&format!("{}{}", self.base_url, path))
+            .timeout(Duration::from_secs(30))
+            .call()
+            .map_err(|e| ChainError::Rpc(format!("{} failed: {}", method, e)));
+        rpc_metrics::global().record(&self.base_url, method, started.elapsed(), response.is_ok());
+        response
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, method: &str, path: &str) -> Result<T> {
+        self.get(method, path)?
+            .into_json()
+            .map_err(|e| ChainError::Rpc(format!("Invalid {} response: {}", method, e)))
+    }
+
+    /// Height of the chain tip
+    pub fn get_tip_height(&self) -> Result<u64> {
+        let body = self
+            .get("tip-height", "/blocks/tip/height")?
+            .into_string()
+            .map_err(|e| ChainError::Rpc(format!("Invalid tip height response: {}", e)))?;
+        body.trim()
+            .parse()
+            .map_err(|_| ChainError::Rpc(format!("Invalid tip height: {}", body.trim())))
+    }
+
+    /// Whether the API answers
+    pub fn is_reachable(&self) -> bool {
+        self.get_tip_height().is_ok()
+    }
+
+    /// Balance of an address in BTC, unconfirmed transactions included
+    pub fn get_balance(&self, address: &str) -> Result<f64> {
+        let info: AddressInfo = self.get_json("address", &format!("/address/{}", address))?;
+        Ok(info.balance() as f64 / SATS_PER_BTC)
+    }
+
+    /// Refresh the balances of accounts
+    pub fn update_balances(&self, accounts: &mut [BitcoinAccount]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for account in accounts.iter_mut() {
+            match self.get_balance(&account.address) {
+                Ok(balance) => account.balance = balance,
+                Err(e) => errors.push(format!("{}...: {}", &account.address[..20], e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ChainError::Rpc(format!(
+                "Failed to get balance for {} account(s): {}",
+                errors.len(),
+                errors.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_balance() {
+        let json = r#"{
+            "address": "tb1qexample",
+            "chain_stats": {"funded_txo_count": 2, "funded_txo_sum": 150000, "spent_txo_count": 1, "spent_txo_sum": 50000, "tx_count": 3},
+            "mempool_stats": {"funded_txo_count": 0, "funded_txo_sum": 0, "spent_txo_count": 1, "spent_txo_sum": 25000, "tx_count": 1}
+        }"#;
+        let info: AddressInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.balance(), 75000);
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash() {
+        let client = EsploraClient::new("https://mempool.space/testnet4/api/");
+        assert_eq!(client.url(), "https://mempool.space/testnet4/api");
+    }
+}
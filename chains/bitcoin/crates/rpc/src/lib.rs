@@ -1,10 +1,27 @@
 use bitcoincore_rpc::bitcoin::address::NetworkUnchecked;
-use bitcoincore_rpc::bitcoin::{Address, Amount};
-use bitcoincore_rpc::{Auth, Client, RpcApi};
-use chain_forge_bitcoin_accounts::BitcoinAccount;
-use chain_forge_common::{ChainError, Result};
+use bitcoincore_rpc::bitcoin::consensus::encode;
+use bitcoincore_rpc::bitcoin::hashes::Hash;
+use bitcoincore_rpc::bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoincore_rpc::bitcoin::{Address, Amount, PublicKey, ScriptBuf, Transaction, Witness};
+use bitcoincore_rpc::jsonrpc;
+use bitcoincore_rpc::jsonrpc::simple_http::SimpleHttpTransport;
+use bitcoincore_rpc::{Client, RpcApi};
+use chain_forge_bitcoin_accounts::{AddressType, BitcoinAccount};
+use chain_forge_common::rpc_cassette::{self, Cassette, RecordedError};
+use chain_forge_common::{rpc_metrics, ChainError, Result, RetryPolicy, Signer};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+mod esplora;
+mod signet;
+
+pub use esplora::EsploraClient;
+
+/// Bitcoin Core's default `-mempoolexpiry` (two weeks), in seconds
+const MEMPOOL_EXPIRY_SECS: i64 = 336 * 60 * 60;
 
 /// Transaction info from Bitcoin wallet (from `listtransactions`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,24 +57,349 @@ pub struct BitcoinTransactionDetail {
     pub details: Vec<BitcoinTxDetailEntry>,
 }
 
+/// Input of a decoded transaction, with the output it spends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinTxInput {
+    /// Transaction and output index spent; `None` for a coinbase input
+    pub prevout: Option<BitcoinOutpoint>,
+    /// Address of the spent output, if its script has one
+    pub address: Option<String>,
+    /// Value of the spent output in BTC (0 for a coinbase input)
+    pub amount: f64,
+}
+
+/// Output of a decoded transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinTxOutput {
+    pub vout: u32,
+    /// Address the output pays, if its script has one
+    pub address: Option<String>,
+    pub amount: f64,
+}
+
+/// Decoded transaction with the outputs its inputs spend
+/// (from `getrawtransaction`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinRawTransaction {
+    pub txid: String,
+    /// Fee in BTC (`None` for a coinbase transaction)
+    pub fee: Option<f64>,
+    pub confirmations: i64,
+    /// Height of the containing block (`None` while unconfirmed)
+    pub block_height: Option<u64>,
+    pub block_time: Option<i64>,
+    pub inputs: Vec<BitcoinTxInput>,
+    pub outputs: Vec<BitcoinTxOutput>,
+}
+
+impl BitcoinRawTransaction {
+    /// Net satoshis each address gains (or loses, if negative) from the
+    /// transaction, in the order addresses first appear, inputs first
+    pub fn net_flows(&self) -> Vec<(String, i64)> {
+        let spent = self
+            .inputs
+            .iter()
+            .map(|input| (&input.address, -to_sats(input.amount)));
+        let received = self
+            .outputs
+            .iter()
+            .map(|output| (&output.address, to_sats(output.amount)));
+
+        let mut flows: Vec<(String, i64)> = Vec::new();
+        for (address, sats) in spent.chain(received) {
+            let Some(address) = address else { continue };
+            match flows.iter_mut().find(|(a, _)| a == address) {
+                Some((_, net)) => *net += sats,
+                None => flows.push((address.clone(), sats)),
+            }
+        }
+        flows
+    }
+}
+
+/// Balance of an address just before and after a transaction, in BTC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinBalanceChange {
+    pub address: String,
+    pub before: f64,
+    pub after: f64,
+    pub change: f64,
+}
+
+/// Block header (from `getblockheader`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinBlockHeader {
+    pub hash: String,
+    pub height: u64,
+    pub time: i64,
+    pub previous_block_hash: Option<String>,
+}
+
+/// Block with its transaction IDs (from `getblock`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinBlock {
+    pub hash: String,
+    pub height: u64,
+    pub time: i64,
+    pub previous_block_hash: Option<String>,
+    /// -1 if the block is no longer in the best chain
+    pub confirmations: i64,
+    /// Serialized size in bytes
+    pub size: u64,
+    pub weight: u64,
+    pub tx_count: u64,
+    /// Transaction IDs in block order, coinbase first
+    pub txids: Vec<String>,
+}
+
+/// BIP158 basic block filter (from `getblockfilter`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinBlockFilter {
+    pub height: u64,
+    pub block_hash: String,
+    /// Hex-encoded filter
+    pub filter: String,
+    /// Hex-encoded filter header
+    pub header: String,
+}
+
+/// Reference to a transaction output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitcoinOutpoint {
+    pub txid: String,
+    pub vout: u32,
+}
+
+/// Unspent output of an address, including whether the wallet has it locked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinUtxo {
+    pub txid: String,
+    pub vout: u32,
+    pub amount: f64,
+    pub confirmations: i64,
+    /// Locked outputs are skipped by wallet coin selection (`lockunspent`)
+    pub locked: bool,
+}
+
+/// A signed parent/child pair whose child pays for its parent (CPFP)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinCpfpPackage {
+    pub parent_txid: String,
+    pub parent_hex: String,
+    pub child_txid: String,
+    pub child_hex: String,
+}
+
+/// Outcome of one transaction in a `submitpackage` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinPackageTxResult {
+    pub wtxid: String,
+    pub txid: String,
+    pub vsize: Option<u64>,
+    /// Base fee in BTC
+    pub fee: Option<f64>,
+    /// Why the transaction was rejected, if it was
+    pub error: Option<String>,
+}
+
+/// Result of `submitpackage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinPackageResult {
+    /// `"success"` when the whole package was accepted
+    pub message: String,
+    pub transactions: Vec<BitcoinPackageTxResult>,
+    /// Transactions evicted from the mempool by the package
+    pub replaced: Vec<String>,
+}
+
+impl BitcoinPackageResult {
+    /// Whether every transaction in the package was accepted
+    pub fn is_success(&self) -> bool {
+        self.message == "success"
+    }
+}
+
+/// JSON-RPC transport that records every call in the RPC metrics, and
+/// records it to or replays it from the active RPC cassette
+///
+/// Wallet calls are recorded under the node's base URL, so a node's stats
+/// cover every wallet.
+struct MeteredTransport {
+    inner: SimpleHttpTransport,
+    endpoint: String,
+}
+
+impl MeteredTransport {
+    /// Record a request and what it got back
+    fn record(
+        &self,
+        cassette: &Cassette,
+        request: &jsonrpc::Request,
+        response: std::result::Result<&jsonrpc::Response, &jsonrpc::Error>,
+    ) {
+        let outcome = match response {
+            Ok(response) => match &response.error {
+                Some(error) => Err(RecordedError {
+                    code: Some(error.code.into()),
+                    message: error.message.clone(),
+                }),
+                None => Ok(response
+                    .result
+                    .as_ref()
+                    .and_then(|raw| serde_json::from_str(raw.get()).ok())
+                    .unwrap_or(serde_json::Value::Null)),
+            },
+            Err(e) => Err(RecordedError {
+                code: None,
+                message: e.to_string(),
+            }),
+        };
+        cassette.record_call(
+            &self.endpoint,
+            request.method,
+            &request_params(request),
+            &outcome,
+        );
+    }
+}
+
+/// Answer a request from a replaying cassette
+fn replay(
+    cassette: &Cassette,
+    request: &jsonrpc::Request,
+) -> std::result::Result<jsonrpc::Response, jsonrpc::Error> {
+    let (result, error) = match cassette.replay_call(request.method, &request_params(request)) {
+        Ok(result) => (Some(serde_json::value::to_raw_value(&result)?), None),
+        Err(RecordedError {
+            code: Some(code),
+            message,
+        }) => (
+            None,
+            Some(jsonrpc::error::RpcError {
+                code: code as i32,
+                message,
+                data: None,
+            }),
+        ),
+        Err(RecordedError {
+            code: None,
+            message,
+        }) => return Err(jsonrpc::Error::Transport(message.into())),
+    };
+    Ok(jsonrpc::Response {
+        result,
+        error,
+        id: request.id.clone(),
+        jsonrpc: Some("2.0".to_string()),
+    })
+}
+
+/// A request's params as JSON (`null` if it has none)
+fn request_params(request: &jsonrpc::Request) -> serde_json::Value {
+    request
+        .params
+        .and_then(|raw| serde_json::from_str(raw.get()).ok())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+impl jsonrpc::Transport for MeteredTransport {
+    fn send_request(
+        &self,
+        request: jsonrpc::Request,
+    ) -> std::result::Result<jsonrpc::Response, jsonrpc::Error> {
+        let cassette = rpc_cassette::active();
+        if let Some(cassette) = cassette.as_ref().filter(|c| c.is_replaying()) {
+            return replay(cassette, &request);
+        }
+
+        let method = request.method.to_string();
+        let started = Instant::now();
+        let response = self.inner.send_request(request.clone());
+        let success = matches!(&response, Ok(r) if r.error.is_none());
+        rpc_metrics::global().record(&self.endpoint, &method, started.elapsed(), success);
+        if let Some(cassette) = cassette {
+            self.record(&cassette, &request, response.as_ref());
+        }
+        response
+    }
+
+    fn send_batch(
+        &self,
+        requests: &[jsonrpc::Request],
+    ) -> std::result::Result<Vec<jsonrpc::Response>, jsonrpc::Error> {
+        // Batched requests are recorded and replayed one by one
+        let cassette = rpc_cassette::active();
+        if let Some(cassette) = cassette.as_ref().filter(|c| c.is_replaying()) {
+            return requests
+                .iter()
+                .map(|request| replay(cassette, request))
+                .collect();
+        }
+
+        let started = Instant::now();
+        let responses = self.inner.send_batch(requests);
+        let success = matches!(&responses, Ok(r) if r.iter().all(|r| r.error.is_none()));
+        rpc_metrics::global().record(&self.endpoint, "batch", started.elapsed(), success);
+        if let (Some(cassette), Ok(responses)) = (cassette, &responses) {
+            // Responses can come back in any order
+            for request in requests {
+                if let Some(response) = responses.iter().find(|r| r.id == request.id) {
+                    self.record(&cassette, request, Ok(response));
+                }
+            }
+        }
+        responses
+    }
+
+    fn fmt_target(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.inner.fmt_target(f)
+    }
+}
+
+/// Connect to `url`, recording calls under `endpoint`
+fn connect(
+    url: &str,
+    endpoint: &str,
+    user: String,
+    password: String,
+) -> std::result::Result<Client, jsonrpc::simple_http::Error> {
+    let inner = SimpleHttpTransport::builder()
+        .url(url)?
+        .auth(user, Some(password))
+        .build();
+    let transport = MeteredTransport {
+        inner,
+        endpoint: endpoint.to_string(),
+    };
+    Ok(Client::from_jsonrpc(jsonrpc::Client::with_transport(
+        transport,
+    )))
+}
+
+/// How long the wallet stays unlocked after an operation that needs its keys
+pub const WALLET_UNLOCK_SECS: u64 = 60;
+
 /// Bitcoin Core RPC client wrapper
 pub struct BitcoinRpcClient {
     client: Client,
     rpc_url: String,
     wallet_name: String,
+    wallet_passphrase: Option<String>,
+    block_signer: Option<BitcoinAccount>,
 }
 
 impl BitcoinRpcClient {
     /// Create a new RPC client with authentication
     pub fn new(rpc_url: String, user: String, password: String) -> Result<Self> {
-        let auth = Auth::UserPass(user, password);
-        let client = Client::new(&rpc_url, auth)
+        let client = connect(&rpc_url, &rpc_url, user, password)
             .map_err(|e| ChainError::Rpc(format!("Failed to create RPC client: {}", e)))?;
 
         Ok(Self {
             client,
             rpc_url,
             wallet_name: "chain-forge".to_string(),
+            wallet_passphrase: None,
+            block_signer: None,
         })
     }
 
@@ -68,18 +410,40 @@ impl BitcoinRpcClient {
         password: String,
         wallet_name: &str,
     ) -> Result<Self> {
-        let auth = Auth::UserPass(user, password);
         let wallet_url = format!("{}/wallet/{}", rpc_url, wallet_name);
-        let client = Client::new(&wallet_url, auth)
+        let client = connect(&wallet_url, &rpc_url, user, password)
             .map_err(|e| ChainError::Rpc(format!("Failed to create wallet RPC client: {}", e)))?;
 
         Ok(Self {
             client,
             rpc_url,
             wallet_name: wallet_name.to_string(),
+            wallet_passphrase: None,
+            block_signer: None,
         })
     }
 
+    /// Unlock the wallet with `passphrase` before operations that need its keys
+    ///
+    /// Signing, sending, and importing keys call `walletpassphrase` first and
+    /// leave the wallet unlocked for [`WALLET_UNLOCK_SECS`], after which the
+    /// node locks it again. Without a passphrase the wallet is assumed to be
+    /// unencrypted.
+    pub fn with_wallet_passphrase(mut self, passphrase: Option<String>) -> Self {
+        self.wallet_passphrase = passphrase;
+        self
+    }
+
+    /// Sign mined blocks with `signer`'s key, for a signet whose challenge is
+    /// its P2WPKH script
+    ///
+    /// `mine_blocks` then assembles and signs blocks itself instead of using
+    /// `generatetoaddress`.
+    pub fn with_block_signer(mut self, signer: Option<BitcoinAccount>) -> Self {
+        self.block_signer = signer;
+        self
+    }
+
     /// Get the RPC URL
     pub fn url(&self) -> &str {
         &self.rpc_url
@@ -95,6 +459,17 @@ impl BitcoinRpcClient {
         self.client.get_blockchain_info().is_ok()
     }
 
+    /// Ask the node to shut down cleanly (`stop`)
+    ///
+    /// Returns once the request is accepted; the node flushes its chainstate
+    /// and wallets before it exits.
+    pub fn stop_node(&self) -> Result<()> {
+        self.client
+            .stop()
+            .map(|_| ())
+            .map_err(|e| ChainError::Rpc(format!("Failed to stop node: {}", e)))
+    }
+
     /// Wait for the node to be ready
     pub async fn wait_for_node(&self, max_attempts: u32) -> Result<()> {
         for attempt in 1..=max_attempts {
@@ -105,7 +480,7 @@ impl BitcoinRpcClient {
             tokio::time::sleep(Duration::from_millis(500)).await;
 
             if attempt == max_attempts {
-                return Err(ChainError::NodeManagement(
+                return Err(ChainError::Timeout(
                     "Bitcoin node did not start in time".to_string(),
                 ));
             }
@@ -115,7 +490,10 @@ impl BitcoinRpcClient {
     }
 
     /// Create a new wallet (descriptor wallet with default keys for change addresses)
-    pub fn create_wallet(&self, wallet_name: &str) -> Result<()> {
+    ///
+    /// With a `passphrase` the wallet is encrypted. An existing wallet is
+    /// loaded as is.
+    pub fn create_wallet(&self, wallet_name: &str, passphrase: Option<&str>) -> Result<()> {
         // Check if wallet already exists
         let wallets = self
             .client
@@ -133,7 +511,7 @@ impl BitcoinRpcClient {
                 // Wallet doesn't exist, create a descriptor wallet with default keys
                 // We need the default keys for change addresses when sending
                 self.client
-                    .create_wallet(wallet_name, None, None, None, None)
+                    .create_wallet(wallet_name, None, None, passphrase, None)
                     .map_err(|e| ChainError::Rpc(format!("Failed to create wallet: {}", e)))?;
             }
         }
@@ -141,12 +519,31 @@ impl BitcoinRpcClient {
         Ok(())
     }
 
+    /// Unlock the wallet if a passphrase is set
+    fn unlock_wallet(&self) -> Result<()> {
+        let Some(passphrase) = &self.wallet_passphrase else {
+            return Ok(());
+        };
+        self.client
+            .call::<serde_json::Value>(
+                "walletpassphrase",
+                &[
+                    serde_json::json!(passphrase),
+                    serde_json::json!(WALLET_UNLOCK_SECS),
+                ],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to unlock wallet: {}", e)))?;
+        Ok(())
+    }
+
     /// Import an account with its private key for spending (uses importdescriptors)
     ///
-    /// For P2WPKH (bech32) addresses, uses wpkh(WIF) descriptor format.
+    /// The descriptor matches the address type: `pkh(WIF)`, `sh(wpkh(WIF))`,
+    /// `wpkh(WIF)`, or `tr(WIF)`.
     pub fn import_address(&self, address: &str, wif: &str, label: &str) -> Result<()> {
-        // For descriptor wallets with P2WPKH addresses, use wpkh(WIF) descriptor
-        let raw_desc = format!("wpkh({})", wif);
+        self.unlock_wallet()?;
+
+        let raw_desc = AddressType::of_address(address)?.descriptor(wif);
 
         // Get the checksum for the descriptor
         let desc_info: serde_json::Value = self
@@ -234,6 +631,10 @@ impl BitcoinRpcClient {
 
     /// Mine blocks to a specific address
     pub fn mine_blocks(&self, count: u32, address: &str) -> Result<Vec<String>> {
+        if let Some(signer) = &self.block_signer {
+            return self.mine_signed_blocks(count, address, signer);
+        }
+
         let addr: Address<NetworkUnchecked> = address
             .parse()
             .map_err(|e| ChainError::Rpc(format!("Invalid address: {}", e)))?;
@@ -248,6 +649,71 @@ impl BitcoinRpcClient {
         Ok(block_hashes.iter().map(|h| h.to_string()).collect())
     }
 
+    /// Override the node's clock (`setmocktime`)
+    ///
+    /// New blocks and time-based checks use `timestamp` (Unix seconds) instead
+    /// of the system clock. Pass 0 to restore the system clock.
+    pub fn set_mock_time(&self, timestamp: i64) -> Result<()> {
+        let _: serde_json::Value = self
+            .client
+            .call("setmocktime", &[serde_json::json!(timestamp)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to set mock time: {}", e)))?;
+        Ok(())
+    }
+
+    /// Write the node's cached chain state to disk
+    ///
+    /// `gettxoutsetinfo` flushes the block index and UTXO set before
+    /// scanning, so the data directory can be copied while the node runs.
+    /// Returns the height the flushed state is at.
+    pub fn flush_chain_state(&self) -> Result<u64> {
+        let info: serde_json::Value = self
+            .client
+            .call("gettxoutsetinfo", &[serde_json::json!("none")])
+            .map_err(|e| ChainError::Rpc(format!("Failed to flush chain state: {}", e)))?;
+        info["height"]
+            .as_u64()
+            .ok_or_else(|| ChainError::Rpc("gettxoutsetinfo returned no height".to_string()))
+    }
+
+    /// Get the median time past of the chain tip (BIP113)
+    pub fn get_median_time_past(&self) -> Result<i64> {
+        Ok(self.get_blockchain_info()?.median_time as i64)
+    }
+
+    /// Roll the chain back to the genesis block (`invalidateblock` on block 1)
+    ///
+    /// The node keeps running; every block after genesis is marked invalid
+    /// and newly mined blocks build a fresh chain. Returns the height before
+    /// the rollback.
+    pub fn invalidate_to_genesis(&self) -> Result<u64> {
+        let height = self.get_block_count()?;
+        if height == 0 {
+            return Ok(0);
+        }
+
+        let hash = self.get_block_hash(1)?;
+        let _: serde_json::Value = self
+            .client
+            .call("invalidateblock", &[serde_json::json!(hash)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to invalidate block {}: {}", hash, e)))?;
+        Ok(height)
+    }
+
+    /// Rescan blocks `start..=stop` for wallet transactions
+    pub fn rescan_blockchain(&self, start: u64, stop: u64) -> Result<()> {
+        let _: serde_json::Value = self
+            .client
+            .call(
+                "rescanblockchain",
+                &[serde_json::json!(start), serde_json::json!(stop)],
+            )
+            .map_err(|e| {
+                ChainError::Rpc(format!("Failed to rescan blocks {}-{}: {}", start, stop, e))
+            })?;
+        Ok(())
+    }
+
     /// Send BTC to an address (from wallet funds, not a specific account)
     ///
     /// This sends from the wallet's available UTXOs. For sending from a specific
@@ -262,6 +728,7 @@ impl BitcoinRpcClient {
         let amount = Amount::from_btc(amount_btc)
             .map_err(|e| ChainError::Rpc(format!("Invalid amount: {}", e)))?;
 
+        self.unlock_wallet()?;
         let txid = self
             .client
             .send_to_address(&addr, amount, None, None, None, None, None, None)
@@ -279,6 +746,18 @@ impl BitcoinRpcClient {
         from_address: &str,
         to_address: &str,
         amount_btc: f64,
+    ) -> Result<String> {
+        self.send_many_from_address(from_address, &[(to_address.to_string(), amount_btc)])
+    }
+
+    /// Send BTC from a specific address to several addresses in one transaction
+    ///
+    /// Like [`send_from_address`](Self::send_from_address), only UTXOs owned by
+    /// `from_address` are spent and change goes back to it.
+    pub fn send_many_from_address(
+        &self,
+        from_address: &str,
+        payments: &[(String, f64)],
     ) -> Result<String> {
         // Get UTXOs for the source address
         let utxos: Vec<serde_json::Value> = self
@@ -293,91 +772,271 @@ impl BitcoinRpcClient {
             )
             .map_err(|e| ChainError::Rpc(format!("Failed to list UTXOs: {}", e)))?;
 
-        if utxos.is_empty() {
+        let (inputs, outputs) = plan_spend_many(from_address, payments, &utxos)?;
+        let raw_tx = self.create_raw_transaction(&inputs, &outputs)?;
+        let signed_hex = self.sign_with_wallet(&raw_tx, &[])?;
+
+        // Broadcast the transaction
+        let txid: String = self
+            .client
+            .call("sendrawtransaction", &[serde_json::json!(signed_hex)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to broadcast transaction: {}", e)))?;
+
+        Ok(txid)
+    }
+
+    /// Create an unsigned transaction with `createrawtransaction`
+    fn create_raw_transaction(
+        &self,
+        inputs: &[serde_json::Value],
+        outputs: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<String> {
+        self.client
+            .call(
+                "createrawtransaction",
+                &[serde_json::json!(inputs), serde_json::json!(outputs)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to create raw transaction: {}", e)))
+    }
+
+    /// Sign a raw transaction with the wallet's keys
+    ///
+    /// `prevtxs` describes inputs the node doesn't know about yet, such as
+    /// outputs of an unbroadcast parent.
+    fn sign_with_wallet(&self, raw_tx: &str, prevtxs: &[serde_json::Value]) -> Result<String> {
+        self.unlock_wallet()?;
+
+        let mut params = vec![serde_json::json!(raw_tx)];
+        if !prevtxs.is_empty() {
+            params.push(serde_json::json!(prevtxs));
+        }
+        let signed: serde_json::Value =
+            self.client
+                .call("signrawtransactionwithwallet", &params)
+                .map_err(|e| ChainError::Rpc(format!("Failed to sign transaction: {}", e)))?;
+
+        if signed["complete"].as_bool() != Some(true) {
             return Err(ChainError::Rpc(format!(
-                "No UTXOs found for address {}",
-                from_address
+                "Transaction signing incomplete: {:?}",
+                signed["errors"]
             )));
         }
 
-        // Calculate total available
-        let total_available: f64 = utxos
-            .iter()
-            .map(|u| u["amount"].as_f64().unwrap_or(0.0))
-            .sum();
+        signed["hex"]
+            .as_str()
+            .map(|hex| hex.to_string())
+            .ok_or_else(|| ChainError::Rpc("No signed transaction hex".to_string()))
+    }
+
+    /// Build a parent/child pair where the child pays for its parent
+    ///
+    /// The parent pays `amount_btc` from `from_address` to `to_address` with a
+    /// fee of `parent_fee_btc`; use 0 for a parent that can only be relayed
+    /// as part of a package. The child spends that output back to
+    /// `to_address` with a fee of `child_fee_btc`. Both addresses must be
+    /// imported in the wallet, which generated accounts are. Nothing is
+    /// broadcast; pass the result to [`Self::submit_package`].
+    pub fn build_cpfp_package(
+        &self,
+        from_address: &str,
+        to_address: &str,
+        amount_btc: f64,
+        parent_fee_btc: f64,
+        child_fee_btc: f64,
+    ) -> Result<BitcoinCpfpPackage> {
+        let to_script = parse_address(to_address)?.script_pubkey();
+
+        let utxos: Vec<serde_json::Value> = self
+            .client
+            .call(
+                "listunspent",
+                &[
+                    serde_json::json!(1),
+                    serde_json::json!(9_999_999),
+                    serde_json::json!([from_address]),
+                ],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to list UTXOs: {}", e)))?;
 
-        // Estimate fee (simple: 0.0001 BTC per KB, ~250 bytes per input)
-        let estimated_fee = 0.0001 * (utxos.len() as f64 * 0.25).max(0.25);
-        let amount_with_fee = amount_btc + estimated_fee;
+        let (inputs, outputs) = plan_spend_with_fee(
+            from_address,
+            &[(to_address.to_string(), amount_btc)],
+            &utxos,
+            parent_fee_btc,
+        )?;
+        let parent_hex =
+            self.sign_with_wallet(&self.create_raw_transaction(&inputs, &outputs)?, &[])?;
+        let parent: Transaction = encode::deserialize_hex(&parent_hex)
+            .map_err(|e| ChainError::Rpc(format!("Failed to decode parent transaction: {}", e)))?;
+        let parent_txid = parent.compute_txid().to_string();
+
+        let (vout, value) = find_output(&parent, &to_script).ok_or_else(|| {
+            ChainError::Rpc(format!(
+                "Parent transaction has no output to {}",
+                to_address
+            ))
+        })?;
+        let child_fee = Amount::from_btc(child_fee_btc)
+            .map_err(|e| ChainError::Rpc(format!("Invalid child fee: {}", e)))?;
+        let child_value = value.checked_sub(child_fee).ok_or_else(|| {
+            ChainError::Rpc(format!(
+                "Child fee {} BTC is more than the {} BTC it spends",
+                child_fee_btc,
+                value.to_btc()
+            ))
+        })?;
+
+        let child_inputs = vec![serde_json::json!({ "txid": parent_txid, "vout": vout })];
+        let mut child_outputs = serde_json::Map::new();
+        child_outputs.insert(
+            to_address.to_string(),
+            serde_json::json!(child_value.to_btc()),
+        );
+        // The parent isn't in the mempool yet, so describe the spent output
+        let prevtx = serde_json::json!({
+            "txid": parent_txid,
+            "vout": vout,
+            "scriptPubKey": to_script.to_hex_string(),
+            "amount": value.to_btc(),
+        });
+        let child_hex = self.sign_with_wallet(
+            &self.create_raw_transaction(&child_inputs, &child_outputs)?,
+            &[prevtx],
+        )?;
+        let child: Transaction = encode::deserialize_hex(&child_hex)
+            .map_err(|e| ChainError::Rpc(format!("Failed to decode child transaction: {}", e)))?;
+
+        Ok(BitcoinCpfpPackage {
+            parent_txid,
+            parent_hex,
+            child_txid: child.compute_txid().to_string(),
+            child_hex,
+        })
+    }
 
-        if total_available < amount_with_fee {
-            return Err(ChainError::Rpc(format!(
-                "Insufficient funds in {}: {} BTC available, {} BTC needed (including ~{} BTC fee)",
-                &from_address[..20],
-                total_available,
-                amount_with_fee,
-                estimated_fee
-            )));
+    /// Submit related transactions to the mempool together (`submitpackage`)
+    ///
+    /// Transactions must be ordered parents first, and the package must be a
+    /// child with its unconfirmed parents. Requires Bitcoin Core 26 or later.
+    pub fn submit_package(&self, raw_txs: &[String]) -> Result<BitcoinPackageResult> {
+        let result: serde_json::Value = self
+            .client
+            .call("submitpackage", &[serde_json::json!(raw_txs)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to submit package: {}", e)))?;
+        Ok(parse_package_result(&result))
+    }
+
+    /// Fund an address from the treasury address if one is set, or from the
+    /// wallet otherwise
+    pub fn fund_address(
+        &self,
+        treasury: Option<&str>,
+        address: &str,
+        amount_btc: f64,
+    ) -> Result<String> {
+        match treasury {
+            Some(treasury) => self.send_from_address(treasury, address, amount_btc),
+            None => self.send_to_address(address, amount_btc),
         }
+    }
 
-        // Select UTXOs (simple: use all until we have enough)
-        let mut selected_utxos = Vec::new();
-        let mut selected_total = 0.0;
-        for utxo in &utxos {
-            selected_utxos.push(serde_json::json!({
-                "txid": utxo["txid"],
-                "vout": utxo["vout"],
-            }));
-            selected_total += utxo["amount"].as_f64().unwrap_or(0.0);
-            if selected_total >= amount_with_fee {
-                break;
-            }
+    /// Send BTC from an account whose key is held by a [`Signer`]
+    ///
+    /// The source account does not need to be in the node wallet: UTXOs are
+    /// found with `scantxoutset`, each P2WPKH input's sighash is signed by
+    /// `signer`, and the witness is assembled locally before broadcasting.
+    pub fn send_from_address_with_signer(
+        &self,
+        from: &BitcoinAccount,
+        to_address: &str,
+        amount_btc: f64,
+        signer: &dyn Signer,
+    ) -> Result<String> {
+        if signer.address() != from.address {
+            return Err(ChainError::Other(format!(
+                "Signer is for {}, not {}",
+                signer.address(),
+                from.address
+            )));
+        }
+        // Inputs are signed with the BIP143 sighash, which only P2WPKH uses
+        if from.address_type()? != AddressType::Bech32 {
+            return Err(ChainError::Other(format!(
+                "Account {} is not a bech32 (P2WPKH) account; external signers only support those",
+                from.address
+            )));
         }
 
-        // Calculate change
-        let change = selected_total - amount_btc - estimated_fee;
+        let scan_result: serde_json::Value = self
+            .client
+            .call(
+                "scantxoutset",
+                &[
+                    serde_json::json!("start"),
+                    serde_json::json!([format!("addr({})", from.address)]),
+                ],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to scan UTXO set: {}", e)))?;
+        let utxos = scan_result["unspents"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
 
-        // Build outputs
-        let mut outputs = serde_json::Map::new();
-        outputs.insert(to_address.to_string(), serde_json::json!(amount_btc));
-        if change > 0.00001 {
-            // Send change back to source
-            outputs.insert(from_address.to_string(), serde_json::json!(change));
-        }
+        let (inputs, outputs) = plan_spend(&from.address, to_address, amount_btc, &utxos)?;
 
-        // Create raw transaction
         let raw_tx: String = self
             .client
             .call(
                 "createrawtransaction",
-                &[
-                    serde_json::json!(selected_utxos),
-                    serde_json::json!(outputs),
-                ],
+                &[serde_json::json!(inputs), serde_json::json!(outputs)],
             )
             .map_err(|e| ChainError::Rpc(format!("Failed to create raw transaction: {}", e)))?;
 
-        // Sign the transaction
-        let signed: serde_json::Value = self
-            .client
-            .call("signrawtransactionwithwallet", &[serde_json::json!(raw_tx)])
-            .map_err(|e| ChainError::Rpc(format!("Failed to sign transaction: {}", e)))?;
+        let mut tx: Transaction = encode::deserialize_hex(&raw_tx)
+            .map_err(|e| ChainError::Rpc(format!("Failed to decode raw transaction: {}", e)))?;
+
+        let public_key = PublicKey::from_str(&from.public_key)
+            .map_err(|e| ChainError::Other(format!("Invalid public key: {}", e)))?
+            .to_bytes();
+
+        // Compute and sign every input's sighash before attaching witnesses
+        let mut witnesses = Vec::with_capacity(tx.input.len());
+        {
+            let mut cache = SighashCache::new(&tx);
+            for (index, input) in tx.input.iter().enumerate() {
+                let utxo = utxos
+                    .iter()
+                    .find(|u| {
+                        u["txid"].as_str() == Some(&input.previous_output.txid.to_string())
+                            && u["vout"].as_u64() == Some(input.previous_output.vout as u64)
+                    })
+                    .ok_or_else(|| ChainError::Rpc("Selected UTXO not found".to_string()))?;
 
-        if signed["complete"].as_bool() != Some(true) {
-            return Err(ChainError::Rpc(format!(
-                "Transaction signing incomplete: {:?}",
-                signed["errors"]
-            )));
-        }
+                let script_pubkey =
+                    ScriptBuf::from_hex(utxo["scriptPubKey"].as_str().unwrap_or(""))
+                        .map_err(|e| ChainError::Rpc(format!("Invalid scriptPubKey: {}", e)))?;
+                let value = Amount::from_btc(utxo["amount"].as_f64().unwrap_or(0.0))
+                    .map_err(|e| ChainError::Rpc(format!("Invalid UTXO amount: {}", e)))?;
 
-        let signed_hex = signed["hex"]
-            .as_str()
-            .ok_or_else(|| ChainError::Rpc("No signed transaction hex".to_string()))?;
+                let sighash = cache
+                    .p2wpkh_signature_hash(index, &script_pubkey, value, EcdsaSighashType::All)
+                    .map_err(|e| ChainError::Other(format!("Failed to compute sighash: {}", e)))?;
+
+                let mut signature = signer.sign(&sighash.to_byte_array())?;
+                signature.push(EcdsaSighashType::All as u8);
+                witnesses.push(Witness::from_slice(&[signature, public_key.clone()]));
+            }
+        }
+        for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+            input.witness = witness;
+        }
 
-        // Broadcast the transaction
         let txid: String = self
             .client
-            .call("sendrawtransaction", &[serde_json::json!(signed_hex)])
+            .call(
+                "sendrawtransaction",
+                &[serde_json::json!(encode::serialize_hex(&tx))],
+            )
             .map_err(|e| ChainError::Rpc(format!("Failed to broadcast transaction: {}", e)))?;
 
         Ok(txid)
@@ -417,8 +1076,15 @@ impl BitcoinRpcClient {
     /// 1. Sends the exact target amount to each account (assuming they start at 0)
     /// 2. Does NOT mine blocks (caller should mine for confirmation after all sends)
     ///
-    /// Returns an error if any account fails to fund.
-    pub async fn fund_accounts(&self, accounts: &mut [BitcoinAccount]) -> Result<()> {
+    /// Failed sends are retried per `retry`, and each account's outcome is
+    /// logged. `progress` is called with the number of accounts handled so
+    /// far after each one. Returns an error if any account fails to fund.
+    pub async fn fund_accounts(
+        &self,
+        accounts: &mut [BitcoinAccount],
+        retry: &RetryPolicy,
+        mut progress: impl FnMut(usize),
+    ) -> Result<()> {
         let mut errors = Vec::new();
 
         for (i, account) in accounts.iter_mut().enumerate() {
@@ -426,27 +1092,53 @@ impl BitcoinRpcClient {
 
             // Skip if target is 0
             if target_balance <= 0.0 {
+                progress(i + 1);
                 continue;
             }
 
             // Send the target amount directly
-            match self.send_to_address(&account.address, target_balance) {
-                Ok(txid) => {
-                    println!(
-                        "   Sent {} BTC to account {} {} (txid: {}...)",
-                        target_balance,
-                        i,
-                        &account.address[..20],
-                        &txid[..16]
-                    );
-                }
-                Err(e) => {
-                    errors.push(format!("account {}: {}", i, e));
-                    // Set balance to 0 since funding failed
-                    account.balance = 0.0;
+            for attempt in 1..=retry.attempts() {
+                match self.send_to_address(&account.address, target_balance) {
+                    Ok(txid) => {
+                        debug!(
+                            account = i,
+                            address = %account.address,
+                            amount = target_balance,
+                            %txid,
+                            attempts = attempt,
+                            status = "funded",
+                            "Funded account (BTC)"
+                        );
+                        break;
+                    }
+                    Err(e) if attempt < retry.attempts() => {
+                        let delay = retry.delay(attempt);
+                        warn!(
+                            account = i,
+                            attempt,
+                            error = %e,
+                            delay_ms = delay.as_millis() as u64,
+                            "Failed to fund account, retrying"
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    Err(e) => {
+                        warn!(
+                            account = i,
+                            attempts = attempt,
+                            status = "failed",
+                            error = %e,
+                            "Failed to fund account"
+                        );
+                        errors.push(format!("account {}: {}", i, e));
+                        // Set balance to 0 since funding failed
+                        account.balance = 0.0;
+                    }
                 }
             }
 
+            progress(i + 1);
+
             // Small delay between transactions to allow UTXO set to update
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
@@ -490,20 +1182,389 @@ impl BitcoinRpcClient {
         Ok(())
     }
 
-    /// Get blockchain info
-    pub fn get_blockchain_info(&self) -> Result<bitcoincore_rpc::json::GetBlockchainInfoResult> {
-        self.client
-            .get_blockchain_info()
-            .map_err(|e| ChainError::Rpc(format!("Failed to get blockchain info: {}", e)))
-    }
-
-    /// Get the current block count
+    /// List the unspent outputs of an address, locked ones included
+    ///
+    /// `listunspent` hides locked outputs, so they are looked up separately
+    /// from `listlockunspent`.
+    pub fn list_unspent(&self, address: &str) -> Result<Vec<BitcoinUtxo>> {
+        let unspent: Vec<serde_json::Value> = self
+            .client
+            .call(
+                "listunspent",
+                &[
+                    serde_json::json!(0),
+                    serde_json::json!(9_999_999),
+                    serde_json::json!([address]),
+                ],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to list unspent outputs: {}", e)))?;
+
+        let mut utxos: Vec<BitcoinUtxo> = unspent
+            .iter()
+            .map(|u| BitcoinUtxo {
+                txid: u["txid"].as_str().unwrap_or("").to_string(),
+                vout: u["vout"].as_u64().unwrap_or(0) as u32,
+                amount: u["amount"].as_f64().unwrap_or(0.0),
+                confirmations: u["confirmations"].as_i64().unwrap_or(0),
+                locked: false,
+            })
+            .collect();
+
+        for outpoint in self.list_locked_unspent()? {
+            let txout: serde_json::Value = self
+                .client
+                .call(
+                    "gettxout",
+                    &[
+                        serde_json::json!(outpoint.txid),
+                        serde_json::json!(outpoint.vout),
+                        serde_json::json!(true),
+                    ],
+                )
+                .map_err(|e| ChainError::Rpc(format!("Failed to get output: {}", e)))?;
+
+            // Null if the locked output has since been spent
+            if txout["scriptPubKey"]["address"].as_str() == Some(address) {
+                utxos.push(BitcoinUtxo {
+                    txid: outpoint.txid,
+                    vout: outpoint.vout,
+                    amount: txout["value"].as_f64().unwrap_or(0.0),
+                    confirmations: txout["confirmations"].as_i64().unwrap_or(0),
+                    locked: true,
+                });
+            }
+        }
+
+        Ok(utxos)
+    }
+
+    /// IDs of the transactions that paid an address, unconfirmed ones included
+    /// (`listreceivedbyaddress`)
+    pub fn list_received_txids(&self, address: &str) -> Result<Vec<String>> {
+        let received: Vec<serde_json::Value> = self
+            .client
+            .call(
+                "listreceivedbyaddress",
+                &[
+                    serde_json::json!(0),    // minconf
+                    serde_json::json!(true), // include_empty
+                    serde_json::json!(true), // include_watchonly
+                    serde_json::json!(address),
+                ],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to list received payments: {}", e)))?;
+
+        Ok(received
+            .iter()
+            .filter_map(|entry| entry["txids"].as_array())
+            .flatten()
+            .filter_map(|txid| txid.as_str().map(|s| s.to_string()))
+            .collect())
+    }
+
+    /// Ancestor count of every mempool transaction, keyed by txid
+    ///
+    /// The count includes the transaction itself, so anything above 1 spends
+    /// another unconfirmed transaction (`getrawmempool true`).
+    pub fn get_mempool_ancestor_counts(&self) -> Result<HashMap<String, u64>> {
+        let mempool: serde_json::Map<String, serde_json::Value> = self
+            .client
+            .call("getrawmempool", &[serde_json::json!(true)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to get mempool: {}", e)))?;
+
+        Ok(mempool
+            .into_iter()
+            .map(|(txid, entry)| {
+                let count = entry["ancestorcount"].as_u64().unwrap_or(1);
+                (txid, count)
+            })
+            .collect())
+    }
+
+    /// Number of transactions in the mempool
+    pub fn get_mempool_size(&self) -> Result<u64> {
+        let info: serde_json::Value = self
+            .client
+            .call("getmempoolinfo", &[])
+            .map_err(|e| ChainError::Rpc(format!("Failed to get mempool info: {}", e)))?;
+        Ok(info["size"].as_u64().unwrap_or(0))
+    }
+
+    /// Txids of the transactions in the mempool
+    pub fn get_mempool_txids(&self) -> Result<Vec<String>> {
+        self.client
+            .call("getrawmempool", &[])
+            .map_err(|e| ChainError::Rpc(format!("Failed to get mempool: {}", e)))
+    }
+
+    /// Drop a transaction and anything spending it from the mempool
+    ///
+    /// Bitcoin Core has no RPC for this, so the node's clock is moved past
+    /// the transaction's mempool expiry and the tip block is disconnected and
+    /// reconnected, which makes the node expire old entries. The clock is
+    /// then set to `restore_time` (the instance's time override, or 0 for the
+    /// system clock). The wallet keeps the transaction as unconfirmed and
+    /// rebroadcasts it on restart unless it is abandoned. Returns the txids
+    /// that were evicted.
+    pub fn evict_from_mempool(&self, txid: &str, restore_time: i64) -> Result<Vec<String>> {
+        let entry: serde_json::Value = self
+            .client
+            .call("getmempoolentry", &[serde_json::json!(txid)])
+            .map_err(|e| ChainError::Rpc(format!("{} is not in the mempool: {}", txid, e)))?;
+        let entry_time = entry["time"]
+            .as_i64()
+            .ok_or_else(|| ChainError::Rpc("getmempoolentry returned no time".to_string()))?;
+
+        let height = self.get_block_count()?;
+        if height == 0 {
+            return Err(ChainError::Rpc(
+                "Eviction needs at least one mined block".to_string(),
+            ));
+        }
+        let tip = self.get_block_hash(height)?;
+        let before = self.get_mempool_txids()?;
+
+        self.set_mock_time(entry_time + MEMPOOL_EXPIRY_SECS + 3600)?;
+        let reconnected = self.reconnect_block(&tip);
+        let restored = self.set_mock_time(restore_time);
+        reconnected?;
+        restored?;
+
+        let after = self.get_mempool_txids()?;
+        if after.iter().any(|t| t == txid) {
+            return Err(ChainError::Rpc(format!(
+                "{} is still in the mempool; was the node started with a longer -mempoolexpiry?",
+                txid
+            )));
+        }
+        Ok(before.into_iter().filter(|t| !after.contains(t)).collect())
+    }
+
+    /// Disconnect a tip block and connect it again
+    fn reconnect_block(&self, hash: &str) -> Result<()> {
+        for method in ["invalidateblock", "reconsiderblock"] {
+            let _: serde_json::Value = self
+                .client
+                .call(method, &[serde_json::json!(hash)])
+                .map_err(|e| ChainError::Rpc(format!("{} {} failed: {}", method, hash, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Mark a wallet transaction as abandoned (`abandontransaction`)
+    ///
+    /// Its inputs become spendable again and its descendants are abandoned
+    /// too. Bitcoin Core only allows this for transactions that are neither
+    /// confirmed nor in the mempool, so evict it first with
+    /// [`Self::evict_from_mempool`].
+    pub fn abandon_transaction(&self, txid: &str) -> Result<()> {
+        let _: serde_json::Value = self
+            .client
+            .call("abandontransaction", &[serde_json::json!(txid)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to abandon {}: {}", txid, e)))?;
+        Ok(())
+    }
+
+    /// Whether this client's wallet is loaded in the node (`listwallets`)
+    pub fn is_wallet_loaded(&self) -> Result<bool> {
+        let wallets = self
+            .client
+            .list_wallets()
+            .map_err(|e| ChainError::Rpc(format!("Failed to list wallets: {}", e)))?;
+        Ok(wallets.contains(&self.wallet_name))
+    }
+
+    /// Number of peers the node is connected to
+    pub fn get_connection_count(&self) -> Result<usize> {
+        self.client
+            .get_connection_count()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get connection count: {}", e)))
+    }
+
+    /// Enable or disable all P2P networking (`setnetworkactive`)
+    ///
+    /// A disabled node drops its connections and keeps its own chain and
+    /// mempool; re-enabling reconnects to its `-addnode` peers.
+    pub fn set_network_active(&self, active: bool) -> Result<()> {
+        let _: serde_json::Value = self
+            .client
+            .call("setnetworkactive", &[serde_json::json!(active)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to set network state: {}", e)))?;
+        Ok(())
+    }
+
+    /// Try once to connect to a peer (`addnode <address> onetry`)
+    pub fn connect_peer(&self, address: &str) -> Result<()> {
+        let _: serde_json::Value = self
+            .client
+            .call(
+                "addnode",
+                &[serde_json::json!(address), serde_json::json!("onetry")],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to connect to {}: {}", address, e)))?;
+        Ok(())
+    }
+
+    /// Decode a raw transaction (`decoderawtransaction`)
+    pub fn decode_raw_transaction(&self, hex: &str) -> Result<serde_json::Value> {
+        self.client
+            .call("decoderawtransaction", &[serde_json::json!(hex.trim())])
+            .map_err(|e| ChainError::Rpc(format!("Failed to decode transaction: {}", e)))
+    }
+
+    /// Decode a script (`decodescript`)
+    pub fn decode_script(&self, hex: &str) -> Result<serde_json::Value> {
+        self.client
+            .call("decodescript", &[serde_json::json!(hex.trim())])
+            .map_err(|e| ChainError::Rpc(format!("Failed to decode script: {}", e)))
+    }
+
+    /// Outputs currently locked in the wallet (`listlockunspent`)
+    pub fn list_locked_unspent(&self) -> Result<Vec<BitcoinOutpoint>> {
+        self.client
+            .call("listlockunspent", &[])
+            .map_err(|e| ChainError::Rpc(format!("Failed to list locked outputs: {}", e)))
+    }
+
+    /// Lock outputs so wallet coin selection will not spend them
+    pub fn lock_unspent(&self, outpoints: &[BitcoinOutpoint]) -> Result<()> {
+        self.set_unspent_lock(false, outpoints)
+    }
+
+    /// Unlock outputs previously locked with [`Self::lock_unspent`]
+    pub fn unlock_unspent(&self, outpoints: &[BitcoinOutpoint]) -> Result<()> {
+        self.set_unspent_lock(true, outpoints)
+    }
+
+    fn set_unspent_lock(&self, unlock: bool, outpoints: &[BitcoinOutpoint]) -> Result<()> {
+        let ok: bool = self
+            .client
+            .call(
+                "lockunspent",
+                &[serde_json::json!(unlock), serde_json::json!(outpoints)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to update output locks: {}", e)))?;
+        if !ok {
+            return Err(ChainError::Rpc(
+                "Bitcoin Core refused to update output locks".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get blockchain info
+    pub fn get_blockchain_info(&self) -> Result<bitcoincore_rpc::json::GetBlockchainInfoResult> {
+        self.client
+            .get_blockchain_info()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get blockchain info: {}", e)))
+    }
+
+    /// Get the current block count
     pub fn get_block_count(&self) -> Result<u64> {
         self.client
             .get_block_count()
             .map_err(|e| ChainError::Rpc(format!("Failed to get block count: {}", e)))
     }
 
+    /// Get the hash of the block at a given height
+    pub fn get_block_hash(&self, height: u64) -> Result<String> {
+        self.client
+            .call("getblockhash", &[serde_json::json!(height)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to get block hash at {}: {}", height, e)))
+    }
+
+    /// Get a block header by hash
+    pub fn get_block_header(&self, hash: &str) -> Result<BitcoinBlockHeader> {
+        let result: serde_json::Value = self
+            .client
+            .call(
+                "getblockheader",
+                &[serde_json::json!(hash), serde_json::json!(true)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to get block header {}: {}", hash, e)))?;
+
+        Ok(BitcoinBlockHeader {
+            hash: result["hash"].as_str().unwrap_or(hash).to_string(),
+            height: result["height"].as_u64().unwrap_or(0),
+            time: result["time"].as_i64().unwrap_or(0),
+            previous_block_hash: result["previousblockhash"].as_str().map(|s| s.to_string()),
+        })
+    }
+
+    /// Get a block and its transaction IDs by hash
+    pub fn get_block(&self, hash: &str) -> Result<BitcoinBlock> {
+        let result: serde_json::Value = self
+            .client
+            .call("getblock", &[serde_json::json!(hash), serde_json::json!(1)])
+            .map_err(|e| ChainError::Rpc(format!("Failed to get block {}: {}", hash, e)))?;
+
+        Ok(parse_block(&result))
+    }
+
+    /// Get the block at a given height in the best chain
+    pub fn get_block_at(&self, height: u64) -> Result<BitcoinBlock> {
+        let hash = self.get_block_hash(height)?;
+        self.get_block(&hash)
+    }
+
+    /// Get up to `limit` blocks below `before_height` (or from the tip),
+    /// newest first
+    pub fn get_recent_blocks(
+        &self,
+        before_height: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<BitcoinBlock>> {
+        let top = match before_height {
+            Some(0) => return Ok(Vec::new()),
+            Some(height) => (height - 1).min(self.get_block_count()?),
+            None => self.get_block_count()?,
+        };
+        (0..=top)
+            .rev()
+            .take(limit)
+            .map(|height| self.get_block_at(height))
+            .collect()
+    }
+
+    /// Get the BIP158 basic filter for the block at a given height
+    ///
+    /// Requires the node to run with `-blockfilterindex`.
+    pub fn get_block_filter(&self, height: u64) -> Result<BitcoinBlockFilter> {
+        let block_hash = self.get_block_hash(height)?;
+        let result: serde_json::Value = self
+            .client
+            .call(
+                "getblockfilter",
+                &[serde_json::json!(block_hash), serde_json::json!("basic")],
+            )
+            .map_err(|e| {
+                ChainError::Rpc(format!("Failed to get block filter at {}: {}", height, e))
+            })?;
+
+        Ok(BitcoinBlockFilter {
+            height,
+            block_hash,
+            filter: result["filter"].as_str().unwrap_or("").to_string(),
+            header: result["header"].as_str().unwrap_or("").to_string(),
+        })
+    }
+
+    /// Get headers for blocks above `since_height`, oldest first, up to `limit`
+    pub fn get_headers_since(
+        &self,
+        since_height: u64,
+        limit: usize,
+    ) -> Result<Vec<BitcoinBlockHeader>> {
+        let tip = self.get_block_count()?;
+        (since_height + 1..=tip)
+            .take(limit)
+            .map(|height| {
+                let hash = self.get_block_hash(height)?;
+                self.get_block_header(&hash)
+            })
+            .collect()
+    }
+
     /// Get a new address from the wallet for receiving funds
     /// This is useful for mining rewards where we don't want to use user accounts
     pub fn get_new_address(&self, label: Option<&str>) -> Result<String> {
@@ -604,16 +1665,587 @@ impl BitcoinRpcClient {
         })
     }
 
+    /// Decode any transaction, with the address and amount each input spends
+    ///
+    /// Uses `getrawtransaction` with verbosity 2, which includes input
+    /// prevouts on Bitcoin Core 25 and later. Older nodes return plain
+    /// verbose output, and each spent output is then looked up with a second
+    /// `getrawtransaction`. Nodes run with `-txindex`, so this works for
+    /// transactions outside the wallet too.
+    pub fn get_raw_transaction_verbose(&self, txid: &str) -> Result<BitcoinRawTransaction> {
+        let raw: serde_json::Value = self
+            .client
+            .call(
+                "getrawtransaction",
+                &[serde_json::json!(txid), serde_json::json!(2)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to get transaction {}: {}", txid, e)))?;
+
+        let mut transaction =
+            parse_raw_transaction(&raw, |prev_txid, vout| self.get_output(prev_txid, vout))?;
+
+        if transaction.confirmations > 0 {
+            let tip = self.get_block_count()?;
+            transaction.block_height = Some(tip + 1 - transaction.confirmations as u64);
+        }
+        Ok(transaction)
+    }
+
+    /// Look up output `vout` of transaction `txid`
+    fn get_output(&self, txid: &str, vout: u32) -> Result<BitcoinTxOutput> {
+        let raw: serde_json::Value = self
+            .client
+            .call(
+                "getrawtransaction",
+                &[serde_json::json!(txid), serde_json::json!(true)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to get transaction {}: {}", txid, e)))?;
+        raw["vout"]
+            .as_array()
+            .and_then(|outputs| outputs.get(vout as usize))
+            .map(parse_output)
+            .ok_or_else(|| ChainError::Rpc(format!("{} has no output {}", txid, vout)))
+    }
+
+    /// Balances of the addresses a transaction touches, just before and
+    /// after it
+    ///
+    /// The node keeps no address history, so balances are taken from the
+    /// current UTXO set and rewound: every transaction confirmed after `tx`,
+    /// including those later in its own block, is undone. That walks the
+    /// blocks from the tip down to `tx`'s block, which is quick on a local
+    /// chain. An unconfirmed `tx` is measured against confirmed balances,
+    /// ignoring other mempool transactions.
+    pub fn get_balance_changes(
+        &self,
+        tx: &BitcoinRawTransaction,
+    ) -> Result<Vec<BitcoinBalanceChange>> {
+        let flows = tx.net_flows();
+        if flows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let addresses: Vec<&str> = flows.iter().map(|(address, _)| address.as_str()).collect();
+        let (scan_height, mut balances) = self.scan_balances(&addresses)?;
+
+        if let Some(tx_height) = tx.block_height {
+            // Undo everything after `tx`, leaving the balances right after it
+            let mut found = false;
+            for height in (tx_height..=scan_height).rev() {
+                let hash = self.get_block_hash(height)?;
+                // Verbosity 3 includes prevouts (Bitcoin Core 25+); older
+                // nodes treat it as 2
+                let block: serde_json::Value = self
+                    .client
+                    .call("getblock", &[serde_json::json!(hash), serde_json::json!(3)])
+                    .map_err(|e| {
+                        ChainError::Rpc(format!("Failed to get block {}: {}", height, e))
+                    })?;
+                for raw in block["tx"].as_array().into_iter().flatten().rev() {
+                    if raw["txid"].as_str() == Some(tx.txid.as_str()) {
+                        found = true;
+                        break;
+                    }
+                    let later = parse_raw_transaction(raw, |prev_txid, vout| {
+                        self.get_output(prev_txid, vout)
+                    })?;
+                    rewind(&mut balances, &later);
+                }
+            }
+            if !found {
+                return Err(ChainError::Rpc(format!(
+                    "Transaction {} is not in block {}",
+                    tx.txid, tx_height
+                )));
+            }
+        }
+
+        Ok(flows
+            .into_iter()
+            .map(|(address, net)| {
+                let balance = balances.get(&address).copied().unwrap_or(0);
+                let (before, after) = if tx.block_height.is_some() {
+                    (balance - net, balance)
+                } else {
+                    (balance, balance + net)
+                };
+                BitcoinBalanceChange {
+                    address,
+                    before: from_sats(before),
+                    after: from_sats(after),
+                    change: from_sats(net),
+                }
+            })
+            .collect())
+    }
+
+    /// Confirmed balances of `addresses` in BTC, in order
+    ///
+    /// Balances come from the UTXO set rather than the wallet, so addresses
+    /// need not be imported.
+    pub fn get_address_balances(&self, addresses: &[&str]) -> Result<Vec<f64>> {
+        let (_, balances) = self.scan_balances(addresses)?;
+        Ok(addresses
+            .iter()
+            .map(|address| from_sats(balances.get(*address).copied().unwrap_or(0)))
+            .collect())
+    }
+
+    /// Confirmed balances of `addresses` in satoshis, from a single UTXO set
+    /// scan, and the height the scan was taken at
+    fn scan_balances(&self, addresses: &[&str]) -> Result<(u64, HashMap<String, i64>)> {
+        let mut scripts = HashMap::new();
+        for address in addresses {
+            let script = parse_address(address)?.script_pubkey().to_hex_string();
+            scripts.insert(script, address.to_string());
+        }
+        let descriptors: Vec<String> = addresses
+            .iter()
+            .map(|address| format!("addr({})", address))
+            .collect();
+
+        let scan: serde_json::Value = self
+            .client
+            .call(
+                "scantxoutset",
+                &[serde_json::json!("start"), serde_json::json!(descriptors)],
+            )
+            .map_err(|e| ChainError::Rpc(format!("Failed to scan UTXO set: {}", e)))?;
+
+        let mut balances: HashMap<String, i64> = addresses
+            .iter()
+            .map(|address| (address.to_string(), 0))
+            .collect();
+        for utxo in scan["unspents"].as_array().into_iter().flatten() {
+            let address = utxo["scriptPubKey"]
+                .as_str()
+                .and_then(|script| scripts.get(script));
+            if let Some(address) = address {
+                *balances.entry(address.clone()).or_default() +=
+                    to_sats(utxo["amount"].as_f64().unwrap_or(0.0));
+            }
+        }
+        Ok((scan["height"].as_u64().unwrap_or(0), balances))
+    }
+
     /// Get the inner RPC client for advanced operations
     pub fn inner(&self) -> &Client {
         &self.client
     }
 }
 
+/// Read an output of verbose `getrawtransaction` output
+fn parse_output(output: &serde_json::Value) -> BitcoinTxOutput {
+    BitcoinTxOutput {
+        vout: output["n"].as_u64().unwrap_or(0) as u32,
+        address: output["scriptPubKey"]["address"]
+            .as_str()
+            .map(|s| s.to_string()),
+        amount: output["value"].as_f64().unwrap_or(0.0),
+    }
+}
+
+/// Build a [`BitcoinRawTransaction`] from verbose `getrawtransaction` output
+///
+/// Inputs without a `prevout` (verbosity 1) are resolved with `lookup`.
+/// The fee is reported by verbosity 2, or else computed from the inputs.
+/// `block_height` is left for the caller, which knows the tip.
+fn parse_raw_transaction(
+    raw: &serde_json::Value,
+    mut lookup: impl FnMut(&str, u32) -> Result<BitcoinTxOutput>,
+) -> Result<BitcoinRawTransaction> {
+    let mut inputs = Vec::new();
+    for input in raw["vin"].as_array().into_iter().flatten() {
+        let Some(prev_txid) = input["txid"].as_str() else {
+            // Coinbase
+            inputs.push(BitcoinTxInput {
+                prevout: None,
+                address: None,
+                amount: 0.0,
+            });
+            continue;
+        };
+        let vout = input["vout"].as_u64().unwrap_or(0) as u32;
+        let spent = match input.get("prevout") {
+            Some(prevout) => parse_output(prevout),
+            None => lookup(prev_txid, vout)?,
+        };
+        inputs.push(BitcoinTxInput {
+            prevout: Some(BitcoinOutpoint {
+                txid: prev_txid.to_string(),
+                vout,
+            }),
+            address: spent.address,
+            amount: spent.amount,
+        });
+    }
+
+    let outputs: Vec<BitcoinTxOutput> = raw["vout"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(parse_output)
+        .collect();
+
+    let is_coinbase = inputs.iter().any(|input| input.prevout.is_none());
+    let fee = if is_coinbase {
+        None
+    } else {
+        raw["fee"].as_f64().or_else(|| {
+            let spent: f64 = inputs.iter().map(|input| input.amount).sum();
+            let paid: f64 = outputs.iter().map(|output| output.amount).sum();
+            // Round away float noise to whole satoshis
+            Some(((spent - paid) * 1e8).round() / 1e8)
+        })
+    };
+
+    Ok(BitcoinRawTransaction {
+        txid: raw["txid"].as_str().unwrap_or_default().to_string(),
+        fee,
+        confirmations: raw["confirmations"].as_i64().unwrap_or(0),
+        block_height: None,
+        block_time: raw["blocktime"].as_i64(),
+        inputs,
+        outputs,
+    })
+}
+
+/// Select inputs and build outputs for spending `amount_btc` from `from_address`
+///
+/// Returns `(inputs, outputs)` ready for `createrawtransaction`. Change goes
+/// back to the source address.
+fn plan_spend(
+    from_address: &str,
+    to_address: &str,
+    amount_btc: f64,
+    utxos: &[serde_json::Value],
+) -> Result<(
+    Vec<serde_json::Value>,
+    serde_json::Map<String, serde_json::Value>,
+)> {
+    plan_spend_many(from_address, &[(to_address.to_string(), amount_btc)], utxos)
+}
+
+/// Select inputs and build outputs for paying several addresses from
+/// `from_address`
+///
+/// Payments to the same address are combined into one output. Change goes
+/// back to the source address.
+fn plan_spend_many(
+    from_address: &str,
+    payments: &[(String, f64)],
+    utxos: &[serde_json::Value],
+) -> Result<(
+    Vec<serde_json::Value>,
+    serde_json::Map<String, serde_json::Value>,
+)> {
+    // Estimate fee (simple: 0.0001 BTC per KB, ~250 bytes per input,
+    // ~35 bytes per extra output)
+    let estimated_fee = 0.0001
+        * (utxos.len() as f64 * 0.25 + payments.len().saturating_sub(1) as f64 * 0.035).max(0.25);
+    plan_spend_with_fee(from_address, payments, utxos, estimated_fee)
+}
+
+/// Like [`plan_spend_many`], with a fixed fee instead of an estimate
+fn plan_spend_with_fee(
+    from_address: &str,
+    payments: &[(String, f64)],
+    utxos: &[serde_json::Value],
+    fee_btc: f64,
+) -> Result<(
+    Vec<serde_json::Value>,
+    serde_json::Map<String, serde_json::Value>,
+)> {
+    if utxos.is_empty() {
+        return Err(ChainError::Rpc(format!(
+            "No UTXOs found for address {}",
+            from_address
+        )));
+    }
+
+    // Calculate total available
+    let total_available: f64 = utxos
+        .iter()
+        .map(|u| u["amount"].as_f64().unwrap_or(0.0))
+        .sum();
+
+    let amount_btc: f64 = payments.iter().map(|(_, amount)| amount).sum();
+    let amount_with_fee = amount_btc + fee_btc;
+
+    if total_available < amount_with_fee {
+        return Err(ChainError::Rpc(format!(
+            "Insufficient funds in {}: {} BTC available, {} BTC needed (including ~{} BTC fee)",
+            &from_address[..20.min(from_address.len())],
+            total_available,
+            amount_with_fee,
+            fee_btc
+        )));
+    }
+
+    // Select UTXOs (simple: use all until we have enough)
+    let mut inputs = Vec::new();
+    let mut selected_total = 0.0;
+    for utxo in utxos {
+        inputs.push(serde_json::json!({
+            "txid": utxo["txid"],
+            "vout": utxo["vout"],
+        }));
+        selected_total += utxo["amount"].as_f64().unwrap_or(0.0);
+        if selected_total >= amount_with_fee {
+            break;
+        }
+    }
+
+    // Calculate change, rounded to whole satoshis
+    let change = ((selected_total - amount_btc - fee_btc) * 1e8).round() / 1e8;
+
+    // Build outputs
+    let mut outputs = serde_json::Map::new();
+    for (address, amount) in payments {
+        let total = outputs.get(address).and_then(|v| v.as_f64()).unwrap_or(0.0) + amount;
+        outputs.insert(address.clone(), serde_json::json!(total));
+    }
+    if change > 0.00001 {
+        // Send change back to source
+        let total = outputs
+            .get(from_address)
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0)
+            + change;
+        outputs.insert(from_address.to_string(), serde_json::json!(total));
+    }
+
+    Ok((inputs, outputs))
+}
+
+/// Undo a transaction's effect on the tracked balances
+fn rewind(balances: &mut HashMap<String, i64>, tx: &BitcoinRawTransaction) {
+    for (address, net) in tx.net_flows() {
+        if let Some(balance) = balances.get_mut(&address) {
+            *balance -= net;
+        }
+    }
+}
+
+fn to_sats(btc: f64) -> i64 {
+    (btc * 1e8).round() as i64
+}
+
+fn from_sats(sats: i64) -> f64 {
+    sats as f64 / 1e8
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    let addr: Address<NetworkUnchecked> = address
+        .parse()
+        .map_err(|e| ChainError::Rpc(format!("Invalid address: {}", e)))?;
+    Ok(addr.assume_checked())
+}
+
+/// Index and value of the first output of `tx` paying to `script_pubkey`
+fn find_output(tx: &Transaction, script_pubkey: &ScriptBuf) -> Option<(u32, Amount)> {
+    tx.output
+        .iter()
+        .enumerate()
+        .find(|(_, output)| &output.script_pubkey == script_pubkey)
+        .map(|(vout, output)| (vout as u32, output.value))
+}
+
+/// Read a `getblock` response (verbosity 1)
+fn parse_block(result: &serde_json::Value) -> BitcoinBlock {
+    let txids: Vec<String> = result["tx"]
+        .as_array()
+        .map(|txids| {
+            txids
+                .iter()
+                .filter_map(|txid| txid.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    BitcoinBlock {
+        hash: result["hash"].as_str().unwrap_or("").to_string(),
+        height: result["height"].as_u64().unwrap_or(0),
+        time: result["time"].as_i64().unwrap_or(0),
+        previous_block_hash: result["previousblockhash"].as_str().map(|s| s.to_string()),
+        confirmations: result["confirmations"].as_i64().unwrap_or(0),
+        size: result["size"].as_u64().unwrap_or(0),
+        weight: result["weight"].as_u64().unwrap_or(0),
+        tx_count: result["nTx"].as_u64().unwrap_or(txids.len() as u64),
+        txids,
+    }
+}
+
+/// Flatten a `submitpackage` response
+fn parse_package_result(result: &serde_json::Value) -> BitcoinPackageResult {
+    let transactions = result["tx-results"]
+        .as_object()
+        .map(|results| {
+            results
+                .iter()
+                .map(|(wtxid, tx)| BitcoinPackageTxResult {
+                    wtxid: wtxid.clone(),
+                    txid: tx["txid"].as_str().unwrap_or("").to_string(),
+                    vsize: tx["vsize"].as_u64(),
+                    fee: tx["fees"]["base"].as_f64(),
+                    error: tx["error"].as_str().map(|s| s.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    BitcoinPackageResult {
+        message: result["package_msg"].as_str().unwrap_or("").to_string(),
+        transactions,
+        replaced: result["replaced-transactions"]
+            .as_array()
+            .map(|txids| {
+                txids
+                    .iter()
+                    .filter_map(|txid| txid.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const FROM: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+    const TO: &str = "bcrt1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qzf4jry";
+
+    #[test]
+    fn test_plan_spend_with_change() {
+        let utxos = vec![
+            serde_json::json!({"txid": "aa", "vout": 0, "amount": 1.0}),
+            serde_json::json!({"txid": "bb", "vout": 1, "amount": 1.0}),
+        ];
+
+        let (inputs, outputs) = plan_spend(FROM, TO, 0.5, &utxos).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0]["txid"], "aa");
+        assert_eq!(outputs[TO], 0.5);
+        assert!(outputs.contains_key(FROM));
+    }
+
+    #[test]
+    fn test_plan_spend_many() {
+        let utxos = vec![
+            serde_json::json!({"txid": "aa", "vout": 0, "amount": 1.0}),
+            serde_json::json!({"txid": "bb", "vout": 1, "amount": 1.0}),
+        ];
+        let payments = vec![
+            (TO.to_string(), 0.75),
+            (TO.to_string(), 0.25),
+            ("bcrt1qother".to_string(), 0.5),
+        ];
+
+        let (inputs, outputs) = plan_spend_many(FROM, &payments, &utxos).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(outputs[TO], 1.0);
+        assert_eq!(outputs["bcrt1qother"], 0.5);
+        assert!(outputs.contains_key(FROM));
+    }
+
+    #[test]
+    fn test_plan_spend_insufficient_funds() {
+        let utxos = vec![serde_json::json!({"txid": "aa", "vout": 0, "amount": 0.1})];
+        assert!(plan_spend(FROM, TO, 1.0, &utxos).is_err());
+        assert!(plan_spend(FROM, TO, 1.0, &[]).is_err());
+    }
+
+    #[test]
+    fn test_plan_spend_with_fixed_fee() {
+        let utxos = vec![serde_json::json!({"txid": "aa", "vout": 0, "amount": 1.0})];
+
+        // A zero-fee parent returns everything but the payment as change
+        let (_, outputs) =
+            plan_spend_with_fee(FROM, &[(TO.to_string(), 0.3)], &utxos, 0.0).unwrap();
+        assert_eq!(outputs[TO], 0.3);
+        assert_eq!(outputs[FROM], 0.7);
+
+        let (_, outputs) =
+            plan_spend_with_fee(FROM, &[(TO.to_string(), 0.3)], &utxos, 0.0002).unwrap();
+        assert_eq!(outputs[FROM], 0.6998);
+    }
+
+    #[test]
+    fn test_find_output() {
+        use bitcoincore_rpc::bitcoin::{absolute, transaction, TxOut};
+
+        let script = |byte: u8| {
+            let mut bytes = vec![0x00, 0x14];
+            bytes.extend([byte; 20]);
+            ScriptBuf::from_bytes(bytes)
+        };
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1_000),
+                    script_pubkey: script(1),
+                },
+                TxOut {
+                    value: Amount::from_sat(2_000),
+                    script_pubkey: script(2),
+                },
+            ],
+        };
+
+        assert_eq!(
+            find_output(&tx, &script(2)),
+            Some((1, Amount::from_sat(2_000)))
+        );
+        assert_eq!(find_output(&tx, &script(3)), None);
+    }
+
+    #[test]
+    fn test_parse_package_result() {
+        let result = serde_json::json!({
+            "package_msg": "transaction failed",
+            "tx-results": {
+                "w1": {"txid": "t1", "vsize": 110, "fees": {"base": 0.0}},
+                "w2": {"txid": "t2", "error": "min relay fee not met"}
+            },
+            "replaced-transactions": ["old"]
+        });
+
+        let parsed = parse_package_result(&result);
+        assert!(!parsed.is_success());
+        assert_eq!(parsed.transactions.len(), 2);
+        assert_eq!(parsed.transactions[0].wtxid, "w1");
+        assert_eq!(parsed.transactions[0].vsize, Some(110));
+        assert_eq!(parsed.transactions[0].fee, Some(0.0));
+        assert_eq!(
+            parsed.transactions[1].error.as_deref(),
+            Some("min relay fee not met")
+        );
+        assert_eq!(parsed.replaced, vec!["old".to_string()]);
+
+        let accepted = parse_package_result(&serde_json::json!({"package_msg": "success"}));
+        assert!(accepted.is_success());
+        assert!(accepted.transactions.is_empty());
+    }
+
+    #[test]
+    fn test_outpoint_json() {
+        // Shape expected by lockunspent and returned by listlockunspent
+        let outpoint = BitcoinOutpoint {
+            txid: "aa".to_string(),
+            vout: 1,
+        };
+        let json = serde_json::to_value(&outpoint).unwrap();
+        assert_eq!(json, serde_json::json!({"txid": "aa", "vout": 1}));
+        assert_eq!(
+            serde_json::from_value::<BitcoinOutpoint>(json).unwrap(),
+            outpoint
+        );
+    }
+
     #[test]
     fn test_rpc_client_url() {
         let client = BitcoinRpcClient::new(
@@ -648,6 +2280,87 @@ mod tests {
         assert!(!client.is_node_running());
     }
 
+    #[test]
+    fn test_signer_rejects_non_p2wpkh_accounts() {
+        struct FixedSigner(String);
+        impl Signer for FixedSigner {
+            fn address(&self) -> &str {
+                &self.0
+            }
+            fn sign(&self, _payload: &[u8]) -> Result<Vec<u8>> {
+                unreachable!("nothing is signed for unsupported accounts")
+            }
+        }
+
+        // No node is listening: the account is rejected before any RPC call
+        let client = BitcoinRpcClient::new(
+            "http://localhost:19999".to_string(),
+            "user".to_string(),
+            "pass".to_string(),
+        )
+        .unwrap();
+        for address_type in [AddressType::Taproot, AddressType::Legacy] {
+            let from = chain_forge_bitcoin_accounts::AccountGenerator::new()
+                .unwrap()
+                .with_address_type(address_type)
+                .unwrap()
+                .derive_account(0)
+                .unwrap();
+            let signer = FixedSigner(from.address.clone());
+            let err = client
+                .send_from_address_with_signer(&from, TO, 0.1, &signer)
+                .unwrap_err();
+            assert!(err.to_string().contains("not a bech32"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_block_header_serialization() {
+        let header = BitcoinBlockHeader {
+            hash: "00aa".to_string(),
+            height: 102,
+            time: 1700000000,
+            previous_block_hash: Some("00bb".to_string()),
+        };
+
+        let json = serde_json::to_value(&header).unwrap();
+        assert_eq!(json["hash"], "00aa");
+        assert_eq!(json["height"], 102);
+        assert_eq!(json["previous_block_hash"], "00bb");
+
+        let genesis = BitcoinBlockHeader {
+            previous_block_hash: None,
+            ..header
+        };
+        assert!(serde_json::to_value(&genesis).unwrap()["previous_block_hash"].is_null());
+    }
+
+    #[test]
+    fn test_parse_block() {
+        let result = serde_json::json!({
+            "hash": "00aa",
+            "confirmations": 3,
+            "height": 102,
+            "size": 420,
+            "weight": 1356,
+            "time": 1700000000,
+            "nTx": 2,
+            "tx": ["c0ffee", "beef"],
+            "previousblockhash": "00bb",
+        });
+        let block = parse_block(&result);
+        assert_eq!(block.height, 102);
+        assert_eq!(block.tx_count, 2);
+        assert_eq!(block.txids, vec!["c0ffee", "beef"]);
+        assert_eq!(block.previous_block_hash.as_deref(), Some("00bb"));
+
+        // The genesis block has no parent
+        let genesis =
+            parse_block(&serde_json::json!({ "hash": "0f9188", "height": 0, "tx": ["4a5e1e"] }));
+        assert!(genesis.previous_block_hash.is_none());
+        assert_eq!(genesis.tx_count, 1);
+    }
+
     #[test]
     fn test_transaction_info_serialization() {
         let tx = BitcoinTransactionInfo {
@@ -810,4 +2523,103 @@ mod tests {
         assert_eq!(detail.details[0].address, "bcrt1qa");
         assert_eq!(detail.details[0].label, Some("acc-0".to_string()));
     }
+
+    #[test]
+    fn test_parse_raw_transaction_with_prevouts() {
+        let raw = serde_json::json!({
+            "txid": "cc",
+            "confirmations": 2,
+            "blocktime": 1700000000,
+            "fee": 0.0001,
+            "vin": [{
+                "txid": "aa",
+                "vout": 1,
+                "prevout": {"value": 1.0, "scriptPubKey": {"address": FROM}}
+            }],
+            "vout": [
+                {"n": 0, "value": 0.4, "scriptPubKey": {"address": TO}},
+                {"n": 1, "value": 0.5999, "scriptPubKey": {"address": FROM}}
+            ]
+        });
+
+        let tx = parse_raw_transaction(&raw, |_, _| panic!("prevout was included")).unwrap();
+        assert_eq!(tx.fee, Some(0.0001));
+        assert_eq!(tx.inputs[0].address.as_deref(), Some(FROM));
+        assert_eq!(tx.inputs[0].amount, 1.0);
+        assert_eq!(tx.inputs[0].prevout.as_ref().unwrap().vout, 1);
+        assert_eq!(tx.outputs[0].address.as_deref(), Some(TO));
+        assert_eq!(tx.outputs.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_raw_transaction_looks_up_prevouts() {
+        let raw = serde_json::json!({
+            "txid": "cc",
+            "vin": [{"txid": "aa", "vout": 0}, {"txid": "bb", "vout": 3}],
+            "vout": [{"n": 0, "value": 1.4999, "scriptPubKey": {"address": TO}}]
+        });
+
+        let mut looked_up = Vec::new();
+        let tx = parse_raw_transaction(&raw, |txid, vout| {
+            looked_up.push((txid.to_string(), vout));
+            Ok(BitcoinTxOutput {
+                vout,
+                address: Some(FROM.to_string()),
+                amount: if txid == "aa" { 1.0 } else { 0.5 },
+            })
+        })
+        .unwrap();
+
+        assert_eq!(looked_up, [("aa".to_string(), 0), ("bb".to_string(), 3)]);
+        assert_eq!(tx.fee, Some(0.0001));
+        assert_eq!(tx.confirmations, 0);
+        assert!(tx.block_time.is_none());
+    }
+
+    #[test]
+    fn test_parse_coinbase_transaction() {
+        let raw = serde_json::json!({
+            "txid": "cc",
+            "vin": [{"coinbase": "51"}],
+            "vout": [{"n": 0, "value": 50.0, "scriptPubKey": {"address": TO}}]
+        });
+
+        let tx = parse_raw_transaction(&raw, |_, _| panic!("coinbase has no prevout")).unwrap();
+        assert!(tx.inputs[0].prevout.is_none());
+        assert!(tx.fee.is_none());
+    }
+
+    #[test]
+    fn test_net_flows_and_rewind() {
+        let raw = serde_json::json!({
+            "txid": "cc",
+            "fee": 0.0001,
+            "vin": [{
+                "txid": "aa",
+                "vout": 1,
+                "prevout": {"value": 1.0, "scriptPubKey": {"address": FROM}}
+            }],
+            "vout": [
+                {"n": 0, "value": 0.4, "scriptPubKey": {"address": TO}},
+                {"n": 1, "value": 0.5999, "scriptPubKey": {"address": FROM}},
+                {"n": 2, "value": 0.0, "scriptPubKey": {"type": "nulldata"}}
+            ]
+        });
+        let tx = parse_raw_transaction(&raw, |_, _| panic!("prevout was included")).unwrap();
+
+        // The sender nets out its change and the fee
+        let flows = tx.net_flows();
+        assert_eq!(
+            flows,
+            vec![
+                (FROM.to_string(), -40_010_000),
+                (TO.to_string(), 40_000_000)
+            ]
+        );
+
+        let mut balances = HashMap::from([(TO.to_string(), 150_000_000)]);
+        rewind(&mut balances, &tx);
+        assert_eq!(balances[TO], 110_000_000);
+        assert!(!balances.contains_key(FROM));
+    }
 }
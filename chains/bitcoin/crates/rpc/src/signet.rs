@@ -0,0 +1,525 @@
+//! Mining on a signet whose block-signing key Chain Forge holds (BIP325).
+//!
+//! `generatetoaddress` can't sign signet blocks, so they are assembled from
+//! `getblocktemplate` instead: the coinbase's witness commitment carries a
+//! signature over the block, and the header is ground to the signet's
+//! proof-of-work target before it is submitted.
+
+use crate::BitcoinRpcClient;
+use bitcoincore_rpc::bitcoin::address::NetworkUnchecked;
+use bitcoincore_rpc::bitcoin::block::{Header, Version as BlockVersion};
+use bitcoincore_rpc::bitcoin::consensus::encode;
+use bitcoincore_rpc::bitcoin::hashes::Hash;
+use bitcoincore_rpc::bitcoin::opcodes::all::OP_RETURN;
+use bitcoincore_rpc::bitcoin::opcodes::OP_0;
+use bitcoincore_rpc::bitcoin::script::{Builder, PushBytesBuf};
+use bitcoincore_rpc::bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoincore_rpc::bitcoin::transaction::Version;
+use bitcoincore_rpc::bitcoin::{
+    absolute, Address, Amount, Block, BlockHash, CompactTarget, OutPoint, PublicKey, Script,
+    ScriptBuf, Sequence, Transaction, TxIn, TxMerkleNode, TxOut, Witness,
+};
+use bitcoincore_rpc::RpcApi;
+use chain_forge_bitcoin_accounts::{BitcoinAccount, LocalSigner};
+use chain_forge_common::{ChainError, Result, Signer};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Marks the signet solution among the witness commitment's pushes
+const SIGNET_HEADER: [u8; 4] = [0xec, 0xc7, 0xda, 0xa2];
+
+/// The parts of a `getblocktemplate` result a block is built from
+#[derive(Clone)]
+struct BlockTemplate {
+    version: i32,
+    previous_block_hash: BlockHash,
+    height: i64,
+    time: u32,
+    bits: CompactTarget,
+    coinbase_value: Amount,
+    witness_commitment: ScriptBuf,
+    transactions: Vec<Transaction>,
+}
+
+impl BlockTemplate {
+    fn from_json(template: &serde_json::Value) -> Result<Self> {
+        let field = |name: &str| {
+            template
+                .get(name)
+                .ok_or_else(|| ChainError::Rpc(format!("Block template is missing '{}'", name)))
+        };
+        let invalid =
+            |name: &str| ChainError::Rpc(format!("Block template has an invalid '{}'", name));
+
+        let transactions = field("transactions")?
+            .as_array()
+            .ok_or_else(|| invalid("transactions"))?
+            .iter()
+            .map(|tx| {
+                encode::deserialize_hex(tx["data"].as_str().unwrap_or_default())
+                    .map_err(|e| ChainError::Rpc(format!("Invalid template transaction: {}", e)))
+            })
+            .collect::<Result<Vec<Transaction>>>()?;
+        let time = field("curtime")?
+            .as_u64()
+            .ok_or_else(|| invalid("curtime"))?;
+        let min_time = field("mintime")?
+            .as_u64()
+            .ok_or_else(|| invalid("mintime"))?;
+
+        Ok(Self {
+            version: field("version")?
+                .as_i64()
+                .ok_or_else(|| invalid("version"))? as i32,
+            previous_block_hash: BlockHash::from_str(
+                field("previousblockhash")?.as_str().unwrap_or_default(),
+            )
+            .map_err(|_| invalid("previousblockhash"))?,
+            height: field("height")?.as_i64().ok_or_else(|| invalid("height"))?,
+            time: time.max(min_time) as u32,
+            bits: field("bits")?
+                .as_str()
+                .and_then(|bits| u32::from_str_radix(bits, 16).ok())
+                .map(CompactTarget::from_consensus)
+                .ok_or_else(|| invalid("bits"))?,
+            coinbase_value: Amount::from_sat(
+                field("coinbasevalue")?
+                    .as_u64()
+                    .ok_or_else(|| invalid("coinbasevalue"))?,
+            ),
+            witness_commitment: ScriptBuf::from_hex(
+                field("default_witness_commitment")?
+                    .as_str()
+                    .unwrap_or_default(),
+            )
+            .map_err(|_| invalid("default_witness_commitment"))?,
+            transactions,
+        })
+    }
+}
+
+/// Append a push of `data` to a script
+fn push(script: &Script, data: Vec<u8>) -> Result<ScriptBuf> {
+    let data = PushBytesBuf::try_from(data)
+        .map_err(|e| ChainError::Other(format!("Push too large: {}", e)))?;
+    Ok(Builder::from(script.to_bytes())
+        .push_slice(data)
+        .into_script())
+}
+
+/// Build a block from `template` paying `payout`, with `commitment` as its
+/// witness commitment output
+fn assemble(template: &BlockTemplate, payout: &Script, commitment: ScriptBuf) -> Block {
+    let coinbase = Transaction {
+        version: Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            // BIP34 height, padded to the two-byte minimum
+            script_sig: Builder::new()
+                .push_int(template.height)
+                .push_opcode(OP_0)
+                .into_script(),
+            sequence: Sequence::MAX,
+            // Witness reserved value
+            witness: Witness::from_slice(&[[0u8; 32]]),
+        }],
+        output: vec![
+            TxOut {
+                value: template.coinbase_value,
+                script_pubkey: payout.to_owned(),
+            },
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: commitment,
+            },
+        ],
+    };
+
+    let mut txdata = vec![coinbase];
+    txdata.extend(template.transactions.iter().cloned());
+    let mut block = Block {
+        header: Header {
+            version: BlockVersion::from_consensus(template.version),
+            prev_blockhash: template.previous_block_hash,
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: template.time,
+            bits: template.bits,
+            nonce: 0,
+        },
+        txdata,
+    };
+    if let Some(root) = block.compute_merkle_root() {
+        block.header.merkle_root = root;
+    }
+    block
+}
+
+/// The virtual transaction the signet challenge is spent from
+///
+/// It commits to the block header fields that exist before the solution is
+/// known: the merkle root is that of the block without the solution.
+fn to_spend(unsolved: &Block, challenge: &Script) -> Result<Transaction> {
+    let mut block_data = encode::serialize(&unsolved.header.version);
+    block_data.extend(encode::serialize(&unsolved.header.prev_blockhash));
+    block_data.extend(encode::serialize(&unsolved.header.merkle_root));
+    block_data.extend(unsolved.header.time.to_le_bytes());
+
+    Ok(Transaction {
+        version: Version(0),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: push(&Builder::new().push_opcode(OP_0).into_script(), block_data)?,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: challenge.to_owned(),
+        }],
+    })
+}
+
+/// The virtual transaction whose input signature is the signet solution
+fn to_sign(to_spend: &Transaction) -> Transaction {
+    Transaction {
+        version: Version(0),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.compute_txid(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// Build a block from `template` signed for a P2WPKH `challenge`
+///
+/// The solution goes into the witness commitment after the signet header;
+/// the signature covers the block with only the header there.
+fn signed_block(
+    template: &BlockTemplate,
+    payout: &Script,
+    challenge: &Script,
+    signer: &dyn Signer,
+    public_key: Vec<u8>,
+) -> Result<Block> {
+    let unsolved_commitment = push(&template.witness_commitment, SIGNET_HEADER.to_vec())?;
+    let unsolved = assemble(template, payout, unsolved_commitment);
+
+    let to_sign = to_sign(&to_spend(&unsolved, challenge)?);
+    let sighash = SighashCache::new(&to_sign)
+        .p2wpkh_signature_hash(0, challenge, Amount::ZERO, EcdsaSighashType::All)
+        .map_err(|e| ChainError::Other(format!("Failed to compute block sighash: {}", e)))?;
+    let mut signature = signer.sign(&sighash.to_byte_array())?;
+    signature.push(EcdsaSighashType::All as u8);
+
+    // Serialized scriptSig (empty for P2WPKH) followed by the witness stack
+    let mut solution = SIGNET_HEADER.to_vec();
+    solution.extend(encode::serialize(&ScriptBuf::new()));
+    solution.extend(encode::serialize(&Witness::from_slice(&[
+        signature, public_key,
+    ])));
+
+    let commitment = push(&template.witness_commitment, solution)?;
+    Ok(assemble(template, payout, commitment))
+}
+
+/// Find a nonce up to `max_nonce` for which the header meets its
+/// proof-of-work target
+///
+/// Signet blocks need real work, so the nonces are split between one thread
+/// per core. Returns false, leaving the header as it was, if none works.
+fn grind(header: &mut Header, max_nonce: u32) -> bool {
+    let target = header.target();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get()) as u32;
+    let found = AtomicBool::new(false);
+    let solved = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .filter(|first_nonce| *first_nonce <= max_nonce)
+            .map(|first_nonce| {
+                let found = &found;
+                let mut candidate = *header;
+                candidate.nonce = first_nonce;
+                scope.spawn(move || loop {
+                    if found.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    if candidate.validate_pow(target).is_ok() {
+                        found.store(true, Ordering::Relaxed);
+                        return Some(candidate);
+                    }
+                    candidate.nonce = candidate
+                        .nonce
+                        .checked_add(threads)
+                        .filter(|nonce| *nonce <= max_nonce)?;
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .filter_map(|worker| worker.join().ok().flatten())
+            .next()
+    });
+
+    match solved {
+        Some(solved) => {
+            *header = solved;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Build a block from `template` signed for `challenge`, and grind it
+///
+/// The solution signs the header time, so if no nonce up to `max_nonce`
+/// works, the block is signed again a second later and ground once more.
+fn solve_block(
+    mut template: BlockTemplate,
+    payout: &Script,
+    challenge: &Script,
+    signer: &dyn Signer,
+    public_key: &[u8],
+    max_nonce: u32,
+) -> Result<Block> {
+    loop {
+        let mut block = signed_block(&template, payout, challenge, signer, public_key.to_vec())?;
+        if grind(&mut block.header, max_nonce) {
+            return Ok(block);
+        }
+        template.time += 1;
+    }
+}
+
+impl BitcoinRpcClient {
+    /// Mine blocks signed with `signer`'s key, paying their rewards to `address`
+    ///
+    /// The signet's challenge must be `signer`'s P2WPKH script.
+    pub(crate) fn mine_signed_blocks(
+        &self,
+        count: u32,
+        address: &str,
+        signer: &BitcoinAccount,
+    ) -> Result<Vec<String>> {
+        let payout = address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| ChainError::Rpc(format!("Invalid address: {}", e)))?
+            .assume_checked()
+            .script_pubkey();
+        let challenge = signer
+            .address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| ChainError::Other(format!("Invalid signer address: {}", e)))?
+            .assume_checked()
+            .script_pubkey();
+        let public_key = PublicKey::from_str(&signer.public_key)
+            .map_err(|e| ChainError::Other(format!("Invalid signer public key: {}", e)))?
+            .to_bytes();
+        let local_signer = LocalSigner::from_account(signer)?;
+
+        let mut hashes = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let template: serde_json::Value = self
+                .client
+                .call(
+                    "getblocktemplate",
+                    &[serde_json::json!({"rules": ["segwit", "signet"]})],
+                )
+                .map_err(|e| ChainError::Rpc(format!("Failed to get block template: {}", e)))?;
+            let template = BlockTemplate::from_json(&template)?;
+
+            let block = solve_block(
+                template,
+                &payout,
+                &challenge,
+                &local_signer,
+                &public_key,
+                u32::MAX,
+            )?;
+
+            let rejection: Option<String> = self
+                .client
+                .call(
+                    "submitblock",
+                    &[serde_json::json!(encode::serialize_hex(&block))],
+                )
+                .map_err(|e| ChainError::Rpc(format!("Failed to submit block: {}", e)))?;
+            if let Some(reason) = rejection {
+                return Err(ChainError::Rpc(format!("Block rejected: {}", reason)));
+            }
+            hashes.push(block.block_hash().to_string());
+        }
+
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoincore_rpc::bitcoin::secp256k1::{ecdsa, Message, Secp256k1};
+    use bitcoincore_rpc::bitcoin::Network;
+    use chain_forge_bitcoin_accounts::AccountGenerator;
+
+    fn template() -> BlockTemplate {
+        BlockTemplate::from_json(&serde_json::json!({
+            "version": 536870912,
+            "previousblockhash": "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+            "height": 1,
+            "curtime": 1700000000,
+            "mintime": 1600000000,
+            "bits": "1e0377ae",
+            "coinbasevalue": 5000000000u64,
+            "default_witness_commitment":
+                "6a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9",
+            "transactions": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_block_template() {
+        let template = template();
+        assert_eq!(template.height, 1);
+        assert_eq!(template.time, 1700000000);
+        assert_eq!(template.bits.to_consensus(), 0x1e0377ae);
+        assert_eq!(template.coinbase_value, Amount::from_int_btc(50));
+
+        assert!(BlockTemplate::from_json(&serde_json::json!({"height": 1})).is_err());
+    }
+
+    /// A signer with its P2WPKH challenge script and public key
+    fn signet_signer() -> (LocalSigner, ScriptBuf, PublicKey) {
+        let account = AccountGenerator::new_with_network(Network::Signet)
+            .unwrap()
+            .generate_accounts(1)
+            .unwrap()
+            .remove(0);
+        let challenge = account
+            .address
+            .parse::<Address<NetworkUnchecked>>()
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        let public_key = PublicKey::from_str(&account.public_key).unwrap();
+        (
+            LocalSigner::from_account(&account).unwrap(),
+            challenge,
+            public_key,
+        )
+    }
+
+    /// Check that `block`, built from `template` paying `challenge`, carries
+    /// a solution signing it as it is without the solution
+    fn assert_solution_verifies(
+        block: &Block,
+        template: &BlockTemplate,
+        challenge: &Script,
+        public_key: &PublicKey,
+    ) {
+        // The commitment keeps the default commitment and adds the solution
+        let commitment = block.txdata[0].output[1].script_pubkey.as_bytes();
+        let default = template.witness_commitment.as_bytes();
+        assert_eq!(&commitment[..default.len()], default);
+        let solution = &commitment[default.len() + 2..];
+        assert_eq!(solution[..4], SIGNET_HEADER);
+
+        let witness: Witness = encode::deserialize(&solution[5..]).unwrap();
+        let mut unsolved = assemble(
+            template,
+            challenge,
+            push(&template.witness_commitment, SIGNET_HEADER.to_vec()).unwrap(),
+        );
+        unsolved.header.time = block.header.time;
+        let to_sign = to_sign(&to_spend(&unsolved, challenge).unwrap());
+        let sighash = SighashCache::new(&to_sign)
+            .p2wpkh_signature_hash(0, challenge, Amount::ZERO, EcdsaSighashType::All)
+            .unwrap();
+        let signature = witness.nth(0).unwrap();
+        let signature = ecdsa::Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+        assert!(Secp256k1::verification_only()
+            .verify_ecdsa(
+                &Message::from_digest(sighash.to_byte_array()),
+                &signature,
+                &public_key.inner,
+            )
+            .is_ok());
+        assert_eq!(witness.nth(1).unwrap(), public_key.to_bytes());
+    }
+
+    #[test]
+    fn test_signed_block_solution() {
+        let (signer, challenge, public_key) = signet_signer();
+        let template = template();
+
+        let block = signed_block(
+            &template,
+            &challenge,
+            &challenge,
+            &signer,
+            public_key.to_bytes(),
+        )
+        .unwrap();
+        assert_solution_verifies(&block, &template, &challenge, &public_key);
+    }
+
+    #[test]
+    fn test_grind() {
+        let mut block = assemble(&template(), &ScriptBuf::new(), ScriptBuf::new());
+        // Easiest possible target, so this test doesn't spend time hashing
+        block.header.bits = CompactTarget::from_consensus(0x207fffff);
+        assert!(grind(&mut block.header, u32::MAX));
+        assert!(block.header.validate_pow(block.header.target()).is_ok());
+
+        // Difficulty 1 needs about 2^32 hashes, so 1000 nonces run out
+        block.header.bits = CompactTarget::from_consensus(0x1d00ffff);
+        let before = block.header;
+        assert!(!grind(&mut block.header, 1000));
+        assert_eq!(block.header, before);
+    }
+
+    #[test]
+    fn test_solve_block_signs_the_new_time() {
+        let (signer, challenge, public_key) = signet_signer();
+        let mut template = template();
+        // Met by about every other hash
+        template.bits = CompactTarget::from_consensus(0x207fffff);
+
+        // Start at a time where nonce 0 doesn't meet the target, so with
+        // only that nonce the block has to be signed again at a later time
+        let unsolvable = |template: &BlockTemplate| {
+            let block = signed_block(
+                template,
+                &challenge,
+                &challenge,
+                &signer,
+                public_key.to_bytes(),
+            )
+            .unwrap();
+            block.header.validate_pow(block.header.target()).is_err()
+        };
+        while !unsolvable(&template) {
+            template.time += 1;
+        }
+
+        let block = solve_block(
+            template.clone(),
+            &challenge,
+            &challenge,
+            &signer,
+            &public_key.to_bytes(),
+            0,
+        )
+        .unwrap();
+        assert!(block.header.time > template.time);
+        assert!(block.header.validate_pow(block.header.target()).is_ok());
+        assert_solution_verifies(&block, &template, &challenge, &public_key);
+    }
+}
@@ -0,0 +1,332 @@
+use bip39::Mnemonic;
+use bitcoin::bip32::{ChildNumber, DerivationPath, Xpriv};
+use bitcoin::secp256k1::{All, Secp256k1, SecretKey};
+use bitcoin::Network;
+use chain_forge_common::{AccountStore, ChainError, Protection, Result, StorageBackend};
+use chain_forge_config::Config;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// Ethereum account with keypair and metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthereumAccount {
+    /// EIP-55 checksummed address (0x...)
+    pub address: String,
+    /// Hex-encoded uncompressed public key, without the 0x04 prefix
+    pub public_key: String,
+    /// 0x-prefixed hex private key, as accepted by wallets and `cast`
+    pub private_key: String,
+    /// BIP39 mnemonic phrase used to derive this account
+    pub mnemonic: Option<String>,
+    /// BIP44 derivation path
+    pub derivation_path: Option<String>,
+    /// Balance in ETH
+    pub balance: f64,
+    /// Optional human-readable label (e.g. "alice")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl EthereumAccount {
+    /// Create a new account from a secret key
+    pub fn from_secret_key(
+        secret_key: SecretKey,
+        mnemonic: Option<String>,
+        path: Option<String>,
+    ) -> Self {
+        Self::from_secret_key_in(&Secp256k1::new(), secret_key, mnemonic, path)
+    }
+
+    /// [`Self::from_secret_key`] with an existing secp256k1 context
+    fn from_secret_key_in(
+        secp: &Secp256k1<All>,
+        secret_key: SecretKey,
+        mnemonic: Option<String>,
+        path: Option<String>,
+    ) -> Self {
+        let public_key = secret_key.public_key(secp).serialize_uncompressed();
+
+        // The address is the last 20 bytes of the hash of the 64-byte key
+        let hash = Keccak256::digest(&public_key[1..]);
+        let address = to_checksum_address(&hash[12..]);
+
+        Self {
+            address,
+            public_key: to_hex(&public_key[1..]),
+            private_key: format!("0x{}", to_hex(&secret_key.secret_bytes())),
+            mnemonic,
+            derivation_path: path,
+            balance: 0.0,
+            label: None,
+        }
+    }
+
+    /// Create an account from a hex-encoded private key (with or without 0x)
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        let bytes = from_hex(private_key.trim_start_matches("0x"))
+            .ok_or_else(|| ChainError::AccountGeneration("Invalid private key hex".to_string()))?;
+        let secret_key = SecretKey::from_slice(&bytes)
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid private key: {}", e)))?;
+        Ok(Self::from_secret_key(secret_key, None, None))
+    }
+
+    /// Get the secret key for this account
+    pub fn secret_key(&self) -> Result<SecretKey> {
+        let bytes = from_hex(self.private_key.trim_start_matches("0x"))
+            .ok_or_else(|| ChainError::AccountGeneration("Invalid private key hex".to_string()))?;
+        SecretKey::from_slice(&bytes)
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid secret key bytes: {}", e)))
+    }
+}
+
+/// Validate an address and return it in EIP-55 checksummed form
+///
+/// All-lowercase and all-uppercase addresses are accepted as-is; mixed-case
+/// addresses must carry a valid checksum.
+pub fn checksum_address(address: &str) -> Result<String> {
+    let invalid = |reason: &str| {
+        ChainError::AccountGeneration(format!("Invalid address '{}': {}", address, reason))
+    };
+
+    let hex = address
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid("missing 0x prefix"))?;
+    if hex.len() != 40 {
+        return Err(invalid("expected 20 bytes"));
+    }
+    let bytes = from_hex(hex).ok_or_else(|| invalid("not hex"))?;
+
+    let checksummed = to_checksum_address(&bytes);
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && checksummed != address {
+        return Err(invalid("checksum mismatch"));
+    }
+    Ok(checksummed)
+}
+
+/// Format 20 address bytes with an EIP-55 mixed-case checksum
+fn to_checksum_address(bytes: &[u8]) -> String {
+    let lower = to_hex(bytes);
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// BIP44 path of the external chain accounts are derived from
+///
+/// This matches the default of `anvil`, Hardhat, and MetaMask, so the same
+/// mnemonic yields the same addresses everywhere.
+pub const ACCOUNT_CHAIN_PATH: &str = "m/44'/60'/0'/0";
+
+/// Account generator for Ethereum using BIP39/BIP44
+pub struct AccountGenerator {
+    phrase: String,
+    secp: Secp256k1<All>,
+    chain_key: Xpriv,
+}
+
+impl AccountGenerator {
+    /// Create a new generator with a random mnemonic
+    pub fn new() -> Result<Self> {
+        use rand::RngCore;
+        let mut entropy = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut entropy);
+
+        let mnemonic = Mnemonic::from_entropy(&entropy).map_err(|e| {
+            ChainError::AccountGeneration(format!("Failed to generate mnemonic: {}", e))
+        })?;
+        Self::with_mnemonic(mnemonic)
+    }
+
+    /// Create a generator from an existing mnemonic phrase
+    pub fn from_mnemonic(phrase: &str) -> Result<Self> {
+        let mnemonic = Mnemonic::parse(phrase)
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid mnemonic: {}", e)))?;
+        Self::with_mnemonic(mnemonic)
+    }
+
+    fn with_mnemonic(mnemonic: Mnemonic) -> Result<Self> {
+        let seed = mnemonic.to_seed("");
+
+        // The network only affects extended key serialization, which is unused
+        let secp = Secp256k1::new();
+        let master_key = Xpriv::new_master(Network::Bitcoin, &seed).map_err(|e| {
+            ChainError::AccountGeneration(format!("Failed to create master key: {}", e))
+        })?;
+
+        let path = DerivationPath::from_str(ACCOUNT_CHAIN_PATH).map_err(|e| {
+            ChainError::AccountGeneration(format!("Invalid derivation path: {}", e))
+        })?;
+        let chain_key = master_key
+            .derive_priv(&secp, &path)
+            .map_err(|e| ChainError::AccountGeneration(format!("Failed to derive key: {}", e)))?;
+
+        Ok(Self {
+            phrase: mnemonic.to_string(),
+            secp,
+            chain_key,
+        })
+    }
+
+    /// Get the mnemonic phrase
+    pub fn mnemonic_phrase(&self) -> String {
+        self.phrase.clone()
+    }
+
+    /// Generate multiple accounts from the mnemonic
+    pub fn generate_accounts(&self, count: u32) -> Result<Vec<EthereumAccount>> {
+        (0..count).map(|index| self.derive_account(index)).collect()
+    }
+
+    /// Derive a single account at the given index
+    /// Uses Ethereum's standard BIP44 derivation path: m/44'/60'/0'/0/{index}
+    pub fn derive_account(&self, index: u32) -> Result<EthereumAccount> {
+        let child = ChildNumber::from_normal_idx(index).map_err(|e| {
+            ChainError::AccountGeneration(format!("Invalid account index {}: {}", index, e))
+        })?;
+
+        let derived_key = self
+            .chain_key
+            .derive_priv(&self.secp, &[child])
+            .map_err(|e| ChainError::AccountGeneration(format!("Failed to derive key: {}", e)))?;
+
+        Ok(EthereumAccount::from_secret_key_in(
+            &self.secp,
+            derived_key.private_key,
+            Some(self.mnemonic_phrase()),
+            Some(format!("{}/{}", ACCOUNT_CHAIN_PATH, index)),
+        ))
+    }
+}
+
+impl Default for AccountGenerator {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default account generator")
+    }
+}
+
+/// Accounts storage manager for Ethereum
+pub struct AccountsStorage {
+    accounts_file: std::path::PathBuf,
+    protection: Protection,
+    backend: StorageBackend,
+}
+
+/// Serialized account fields that are encrypted at rest: the private key and mnemonic
+const SECRET_FIELDS: &[&str] = &["privateKey", "mnemonic"];
+
+impl AccountsStorage {
+    /// Create a new storage manager (uses default path under data_dir/ethereum/accounts.json)
+    pub fn new(data_dir: &Path) -> Self {
+        let accounts_file = data_dir.join("ethereum").join("accounts.json");
+        Self::with_path(accounts_file)
+    }
+
+    /// Create a storage manager with a specific file path
+    ///
+    /// Accounts are kept by the backend set with `accounts_storage` in
+    /// `chain-forge.toml`. Secrets are encrypted if `CHAIN_FORGE_PASSPHRASE`
+    /// is set.
+    pub fn with_path(accounts_file: std::path::PathBuf) -> Self {
+        Self {
+            accounts_file,
+            protection: Protection::from_env(),
+            backend: Config::load()
+                .map(|config| config.accounts_storage)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Use `protection` instead of the one from the environment
+    pub fn with_protection(mut self, protection: Protection) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Use `backend` instead of the configured one
+    pub fn with_backend(mut self, backend: StorageBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    fn store(&self) -> Box<dyn AccountStore> {
+        self.backend
+            .open(&self.accounts_file, self.protection.clone())
+    }
+
+    /// Get the accounts file path
+    pub fn accounts_file(&self) -> &Path {
+        &self.accounts_file
+    }
+
+    /// Backend the accounts are kept by
+    pub fn backend(&self) -> StorageBackend {
+        self.backend
+    }
+
+    /// Save accounts
+    ///
+    /// With the file backend the file is replaced atomically, so a crash
+    /// mid-save leaves the previous accounts in place.
+    pub fn save(&self, accounts: &[EthereumAccount]) -> Result<()> {
+        let accounts = accounts
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        self.store().save(&accounts, SECRET_FIELDS)
+    }
+
+    /// Load accounts, upgrading an older file format and decrypting secrets
+    /// if they are encrypted
+    pub fn load(&self) -> Result<Vec<EthereumAccount>> {
+        Ok(self
+            .store()
+            .load()?
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<serde_json::Result<_>>()?)
+    }
+
+    /// Check if any accounts are stored
+    pub fn exists(&self) -> bool {
+        self.store().exists()
+    }
+
+    /// Delete the stored accounts
+    pub fn delete(&self) -> Result<()> {
+        self.store().delete()
+    }
+}
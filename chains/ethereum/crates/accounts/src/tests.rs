@@ -0,0 +1,140 @@
+use super::*;
+use tempfile::tempdir;
+
+/// Default mnemonic of anvil and Hardhat, with well-known accounts
+const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+#[test]
+fn test_account_generation() {
+    let generator = AccountGenerator::new().unwrap();
+    let accounts = generator.generate_accounts(5).unwrap();
+
+    assert_eq!(accounts.len(), 5);
+
+    let mut addresses = std::collections::HashSet::new();
+    for account in &accounts {
+        assert!(addresses.insert(account.address.clone()));
+        assert!(account.address.starts_with("0x"));
+        assert_eq!(account.address.len(), 42);
+    }
+}
+
+#[test]
+fn test_matches_anvil_accounts() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let accounts = generator.generate_accounts(2).unwrap();
+
+    assert_eq!(
+        accounts[0].address,
+        "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+    );
+    assert_eq!(
+        accounts[0].private_key,
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+    );
+    assert_eq!(
+        accounts[1].address,
+        "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+    );
+}
+
+#[test]
+fn test_derivation_paths() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let accounts = generator.generate_accounts(3).unwrap();
+
+    for (i, account) in accounts.iter().enumerate() {
+        assert_eq!(
+            account.derivation_path,
+            Some(format!("m/44'/60'/0'/0/{}", i))
+        );
+        assert_eq!(account.mnemonic.as_deref(), Some(TEST_MNEMONIC));
+    }
+}
+
+#[test]
+fn test_invalid_mnemonic() {
+    assert!(AccountGenerator::from_mnemonic("not a valid mnemonic").is_err());
+}
+
+#[test]
+fn test_account_from_private_key() {
+    let account = EthereumAccount::from_private_key(
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    )
+    .unwrap();
+    assert_eq!(
+        account.address,
+        "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+    );
+    assert!(account.mnemonic.is_none());
+
+    let secret = account.secret_key().unwrap();
+    assert_eq!(
+        format!("0x{}", to_hex(&secret.secret_bytes())),
+        account.private_key
+    );
+
+    assert!(EthereumAccount::from_private_key("0x1234").is_err());
+}
+
+#[test]
+fn test_checksum_address() {
+    let checksummed = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    assert_eq!(checksum_address(checksummed).unwrap(), checksummed);
+    assert_eq!(
+        checksum_address(&checksummed.to_lowercase()).unwrap(),
+        checksummed
+    );
+    assert_eq!(
+        checksum_address("0xF39FD6E51AAD88F6F4CE6AB8827279CFFFB92266").unwrap(),
+        checksummed
+    );
+
+    // Mixed case with a wrong checksum
+    assert!(checksum_address("0xF39fd6e51aad88F6F4ce6aB8827279cffFb92266").is_err());
+    assert!(checksum_address("f39fd6e51aad88f6f4ce6ab8827279cfffb92266").is_err());
+    assert!(checksum_address("0x1234").is_err());
+    assert!(checksum_address("0xzz9fd6e51aad88f6f4ce6ab8827279cfffb92266").is_err());
+}
+
+#[test]
+fn test_storage_save_load() {
+    let dir = tempdir().unwrap();
+    let storage = AccountsStorage::new(dir.path());
+
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let mut accounts = generator.generate_accounts(3).unwrap();
+    accounts[0].balance = 100.0;
+    accounts[1].label = Some("alice".to_string());
+
+    storage.save(&accounts).unwrap();
+    assert!(storage.exists());
+    assert!(storage.accounts_file().ends_with("ethereum/accounts.json"));
+
+    let loaded = storage.load().unwrap();
+    assert_eq!(loaded.len(), 3);
+    assert_eq!(loaded[0].address, accounts[0].address);
+    assert_eq!(loaded[0].balance, 100.0);
+    assert_eq!(loaded[1].label.as_deref(), Some("alice"));
+
+    storage.delete().unwrap();
+    assert!(!storage.exists());
+    assert!(storage.load().unwrap().is_empty());
+}
+
+#[test]
+fn test_account_serialization() {
+    let generator = AccountGenerator::from_mnemonic(TEST_MNEMONIC).unwrap();
+    let account = generator.derive_account(0).unwrap();
+
+    let json = serde_json::to_string(&account).unwrap();
+    assert!(json.contains("\"privateKey\""));
+    assert!(json.contains("\"derivationPath\""));
+    assert!(!json.contains("\"label\""));
+
+    let deserialized: EthereumAccount = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.address, account.address);
+    assert_eq!(deserialized.public_key, account.public_key);
+}
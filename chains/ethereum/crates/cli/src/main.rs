@@ -0,0 +1,333 @@
+use chain_forge_cli_utils::{
+    data_dir, format_eth, logging, AmountArgs, DataDirArgs, ExitCode, LogArgs, OutputFormat,
+    ETH_DECIMALS,
+};
+use chain_forge_common::{validate_name, ChainProvider, ChainType, Message};
+use chain_forge_config::Config;
+use chain_forge_ethereum_accounts::{checksum_address, AccountsStorage};
+use chain_forge_ethereum_core::{EthereumConfig, EthereumInstanceInfo, EthereumProvider};
+use chain_forge_events::ChainEvent;
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use tabled::{Table, Tabled};
+
+#[derive(Parser)]
+#[command(name = "cf-ethereum")]
+#[command(about = "Chain Forge - Ethereum local development tool", long_about = None)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Start a local anvil node with pre-funded accounts
+    Start {
+        /// Instance ID for isolation (allows multiple nodes with separate state)
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Human-readable name for the instance
+        #[arg(short = 'n', long)]
+        name: Option<String>,
+
+        /// Number of accounts to generate
+        #[arg(short, long, default_value = "10")]
+        accounts: u32,
+
+        /// Initial balance for each account in ETH
+        #[arg(short, long, default_value = "10000.0")]
+        balance: f64,
+
+        /// RPC port for the node (default: 8545, or derived from the instance
+        /// ID with `port_strategy = "hashed"`)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Chain ID reported by the node
+        #[arg(long, default_value = "31337")]
+        chain_id: u64,
+
+        /// Seconds between blocks (default: mine a block per transaction)
+        #[arg(long)]
+        block_time: Option<u64>,
+
+        /// Optional mnemonic phrase to use for account generation
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+
+        /// Keep instance data on stop (default: move it to the trash)
+        #[arg(long, default_value = "false")]
+        keep_data: bool,
+
+        /// Store account secrets in plaintext even if `encrypt_accounts` is
+        /// set and no passphrase is
+        #[arg(long, default_value = "false")]
+        insecure_plaintext: bool,
+    },
+
+    /// List all generated accounts with their balances
+    Accounts {
+        /// Instance ID to query
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        amount: AmountArgs,
+    },
+
+    /// Add ETH to an account's balance
+    Fund {
+        /// Account address to fund
+        address: String,
+
+        /// Amount of ETH to add
+        amount: f64,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+}
+
+#[derive(Tabled)]
+struct AccountDisplay {
+    #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Balance (ETH)")]
+    balance: String,
+}
+
+/// Format an account index, with its label if it has one
+fn account_index(index: usize, label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("{} ({})", index, label),
+        None => index.to_string(),
+    }
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Ethereum, instance_id, event);
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    data_dir::init(&cli.data);
+    logging::init(&cli.log);
+
+    if let Err(e) = run(cli).await {
+        eprintln!("Error: {:?}", e);
+        ExitCode::from_report(&e).exit();
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Commands::Start {
+            instance,
+            name,
+            accounts,
+            balance,
+            port,
+            chain_id,
+            block_time,
+            mnemonic,
+            keep_data,
+            insecure_plaintext,
+        } => {
+            if let Err(e) = validate_name(&instance) {
+                eprintln!("❌ {}", Message::InvalidInstanceName(e.to_string()));
+                ExitCode::Validation.exit();
+            }
+
+            if let Some(ref n) = name {
+                if let Err(e) = validate_name(n) {
+                    eprintln!("❌ {}", Message::InvalidDisplayName(e.to_string()));
+                    ExitCode::Validation.exit();
+                }
+            }
+
+            // Account secrets are encrypted with CHAIN_FORGE_PASSPHRASE when
+            // set; `encrypt_accounts` makes that mandatory
+            if let Err(e) =
+                Config::load().and_then(|c| c.check_accounts_protection(insecure_plaintext))
+            {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+
+            // A port not given explicitly follows the configured port strategy
+            let port = match port {
+                Some(port) => port,
+                None => match Config::load()
+                    .and_then(|c| c.instance_port(ChainType::Ethereum, &instance, 8545, 1))
+                {
+                    Ok(port) => port,
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        ExitCode::Validation.exit();
+                    }
+                },
+            };
+
+            let mut config = EthereumConfig::with_instance(&instance);
+            config.rpc_url = format!("http://localhost:{}", port);
+            config.port = port;
+            config.accounts = accounts;
+            config.initial_balance = balance;
+            config.chain_id = chain_id;
+            config.block_time = block_time;
+            config.mnemonic = mnemonic;
+            config.name = name;
+
+            logging::log_to_instance(&config.instance_dir());
+            let mut provider = EthereumProvider::with_config(config.clone());
+            provider.set_keep_data(keep_data);
+            provider.start(config)?;
+
+            println!("💡 Tip: Keep this terminal open to keep the node running");
+            println!(
+                "   Run 'cf-ethereum accounts --instance {}' in another terminal to see your accounts",
+                instance
+            );
+            println!();
+
+            // Keep the process alive
+            tokio::signal::ctrl_c().await?;
+            println!();
+            provider.stop()?;
+        }
+
+        Commands::Accounts {
+            instance,
+            format,
+            amount,
+        } => {
+            let storage = AccountsStorage::with_path(
+                EthereumConfig::with_instance(&instance).accounts_file(),
+            );
+            let mut accounts = storage.load()?;
+
+            if accounts.is_empty() {
+                println!(
+                    "No accounts found for instance '{}'. Run 'cf-ethereum start --instance {}' first.",
+                    instance, instance
+                );
+                return Ok(());
+            }
+
+            // Update balances from the chain
+            let balances_updated = match EthereumInstanceInfo::load(&instance) {
+                Ok(info) => match info.client().update_balances(&mut accounts) {
+                    Ok(_) => {
+                        if let Err(e) = storage.save(&accounts) {
+                            eprintln!("Warning: Could not save updated balances: {}", e);
+                        }
+                        true
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Could not update balances from chain: {}", e);
+                        eprintln!("Showing cached balances from startup.");
+                        false
+                    }
+                },
+                Err(e) => {
+                    eprintln!("Warning: Could not connect to Ethereum node: {}", e);
+                    eprintln!("Showing cached balances from startup.");
+                    false
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&accounts)?);
+                }
+                OutputFormat::Table => {
+                    let display_accounts: Vec<AccountDisplay> = accounts
+                        .iter()
+                        .enumerate()
+                        .map(|(i, acc)| AccountDisplay {
+                            index: account_index(i, acc.label.as_deref()),
+                            address: acc.address.clone(),
+                            balance: amount.format(acc.balance, ETH_DECIMALS),
+                        })
+                        .collect();
+
+                    println!("{}", Table::new(display_accounts));
+
+                    if !balances_updated {
+                        println!();
+                        println!(
+                            "Note: Balances shown are from startup cache (node not reachable)"
+                        );
+                    }
+                }
+            }
+        }
+
+        Commands::Fund {
+            address,
+            amount,
+            instance,
+        } => {
+            let address = match checksum_address(&address) {
+                Ok(address) => address,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+            if !amount.is_finite() || amount <= 0.0 {
+                eprintln!("❌ Amount must be a positive number, got {}", amount);
+                ExitCode::Validation.exit();
+            }
+
+            let client = EthereumInstanceInfo::load(&instance)?.client();
+            if !client.is_node_running() {
+                eprintln!(
+                    "❌ Error: Ethereum node is not running. Start it with 'cf-ethereum start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            println!("💰 Adding {} ETH to {}...", amount, address);
+
+            match client.add_balance(&address, amount) {
+                Ok(balance) => {
+                    println!("✅ Account funded!");
+                    publish(
+                        &instance,
+                        ChainEvent::FundsDispensed {
+                            address: Some(address.clone()),
+                            amount,
+                        },
+                    );
+
+                    println!("   New balance: {} ETH", format_eth(balance));
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", Message::FundingFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,681 @@
+use chain_forge_common::schema::{self, Schema};
+use chain_forge_common::{
+    ChainError, ChainProvider, ChainType, Heartbeat, HookContext, LifecycleHooks, LifecyclePhase,
+    NodeInfo, NodeRegistry, NodeStatus, Result, StartupPhase, StartupProfile, StartupTimer,
+    StorageBackend, HEARTBEAT_INTERVAL,
+};
+use chain_forge_config::{Config, EthereumProfile};
+use chain_forge_ethereum_accounts::{AccountGenerator, AccountsStorage, EthereumAccount};
+use chain_forge_ethereum_rpc::EthereumRpcClient;
+use chain_forge_events::ChainEvent;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+pub mod reset;
+
+pub use reset::reset_instance;
+
+/// Format of `instance.json`
+///
+/// - 1: `version` added
+const INSTANCE_SCHEMA: Schema = Schema::new(&[schema::unversioned]);
+
+/// Instance information saved to disk for CLI discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthereumInstanceInfo {
+    /// Instance ID
+    pub instance_id: String,
+    /// Human-readable name for the instance
+    pub name: Option<String>,
+    /// RPC URL for this instance
+    pub rpc_url: String,
+    /// RPC port
+    pub rpc_port: u16,
+    /// Chain ID reported by the node
+    pub chain_id: u64,
+    /// Number of accounts
+    pub accounts_count: u32,
+    /// Whether the instance is currently running (may be stale)
+    pub running: bool,
+    /// Process ID of anvil, if known
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Number of times the instance has been reset in place with `cf reset`
+    #[serde(default)]
+    pub epoch: u64,
+    /// How long each phase of the last start took
+    #[serde(default, skip_serializing_if = "StartupProfile::is_empty")]
+    pub startup: StartupProfile,
+}
+
+impl EthereumInstanceInfo {
+    /// Load instance info from the default location for an instance ID
+    pub fn load(instance_id: &str) -> Result<Self> {
+        let path = EthereumConfig::with_instance(instance_id).instance_info_file();
+
+        if !path.exists() {
+            return Err(ChainError::Other(format!(
+                "Instance '{}' not found. Run 'cf-ethereum start --instance {}' first.",
+                instance_id, instance_id
+            )));
+        }
+
+        INSTANCE_SCHEMA.read(&path)
+    }
+
+    /// Save instance info to disk
+    pub fn save(&self) -> Result<()> {
+        let path = EthereumConfig::with_instance(&self.instance_id).instance_info_file();
+        INSTANCE_SCHEMA.write(&path, self)
+    }
+
+    /// Mark instance as stopped
+    pub fn mark_stopped(&mut self) -> Result<()> {
+        self.running = false;
+        self.save()
+    }
+
+    /// RPC client for this instance
+    pub fn client(&self) -> EthereumRpcClient {
+        EthereumRpcClient::new(self.rpc_url.clone())
+    }
+}
+
+/// Configuration for starting an anvil node
+#[derive(Debug, Clone)]
+pub struct EthereumConfig {
+    pub rpc_url: String,
+    pub port: u16,
+    pub accounts: u32,
+    pub initial_balance: f64,
+    pub mnemonic: Option<String>,
+    /// Chain ID the node reports (anvil's default is 31337)
+    pub chain_id: u64,
+    /// Seconds between blocks; `None` mines a block per transaction
+    pub block_time: Option<u64>,
+    /// Instance ID for isolation (allows multiple nodes with separate state)
+    pub instance_id: String,
+    /// Human-readable name for the instance
+    pub name: Option<String>,
+    /// Labels given to the first accounts, in order
+    pub account_labels: Vec<String>,
+}
+
+impl Default for EthereumConfig {
+    fn default() -> Self {
+        Self::with_instance("default")
+    }
+}
+
+impl EthereumConfig {
+    /// Create a config with a specific instance ID
+    pub fn with_instance(instance_id: &str) -> Self {
+        Self {
+            rpc_url: "http://localhost:8545".to_string(),
+            port: 8545,
+            accounts: 10,
+            initial_balance: 10000.0,
+            mnemonic: None,
+            chain_id: 31337,
+            block_time: None,
+            instance_id: instance_id.to_string(),
+            name: None,
+            account_labels: Vec::new(),
+        }
+    }
+
+    /// Get the instance directory path
+    pub fn instance_dir(&self) -> PathBuf {
+        Config::data_dir()
+            .join("ethereum")
+            .join("instances")
+            .join(&self.instance_id)
+    }
+
+    /// Get the accounts file path for this instance
+    pub fn accounts_file(&self) -> PathBuf {
+        self.instance_dir().join("accounts.json")
+    }
+
+    /// Get the instance info file path
+    pub fn instance_info_file(&self) -> PathBuf {
+        self.instance_dir().join("instance.json")
+    }
+
+    /// Get anvil's log file (its stdout)
+    pub fn log_file(&self) -> PathBuf {
+        self.instance_dir().join("anvil_stdout.log")
+    }
+}
+
+impl From<EthereumProfile> for EthereumConfig {
+    fn from(profile: EthereumProfile) -> Self {
+        Self {
+            rpc_url: profile.rpc_url,
+            port: profile.port,
+            accounts: profile.accounts,
+            initial_balance: profile.initial_balance,
+            chain_id: profile.chain_id,
+            ..Self::with_instance("default")
+        }
+    }
+}
+
+/// Ethereum chain provider backed by `anvil`
+pub struct EthereumProvider {
+    config: EthereumConfig,
+    rpc_client: Option<EthereumRpcClient>,
+    accounts: Vec<EthereumAccount>,
+    node_process: Arc<Mutex<Option<Child>>>,
+    storage: AccountsStorage,
+    /// Whether to keep instance data on stop (default: false)
+    keep_data: bool,
+    /// Refreshes the node's registry heartbeat while it runs
+    heartbeat: Option<Heartbeat>,
+    hooks: LifecycleHooks,
+}
+
+impl EthereumProvider {
+    /// Create a new Ethereum provider with default instance
+    pub fn new() -> Self {
+        Self::with_instance("default")
+    }
+
+    /// Create a provider for a specific instance ID
+    pub fn with_instance(instance_id: &str) -> Self {
+        Self::with_config(EthereumConfig::with_instance(instance_id))
+    }
+
+    /// Create a provider with a specific configuration
+    pub fn with_config(config: EthereumConfig) -> Self {
+        let storage = AccountsStorage::with_path(config.accounts_file());
+
+        Self {
+            config,
+            rpc_client: None,
+            accounts: Vec::new(),
+            node_process: Arc::new(Mutex::new(None)),
+            storage,
+            keep_data: false,
+            heartbeat: None,
+            hooks: LifecycleHooks::new(),
+        }
+    }
+
+    /// Set whether to keep instance data on stop
+    pub fn set_keep_data(&mut self, keep: bool) {
+        self.keep_data = keep;
+    }
+
+    /// Generate accounts for this instance
+    ///
+    /// Returns the mnemonic, which anvil is started with so that it unlocks
+    /// the same accounts for `eth_sendTransaction`.
+    fn generate_accounts(&mut self) -> Result<String> {
+        let generator = match &self.config.mnemonic {
+            Some(mnemonic) => AccountGenerator::from_mnemonic(mnemonic)?,
+            None => AccountGenerator::new()?,
+        };
+
+        info!(
+            mnemonic = %generator.mnemonic_phrase(),
+            "Generated accounts; save this mnemonic to recover them"
+        );
+
+        self.accounts = generator.generate_accounts(self.config.accounts)?;
+
+        // Set initial balance targets (applied once anvil is up)
+        for account in &mut self.accounts {
+            account.balance = self.config.initial_balance;
+        }
+
+        for (account, label) in self.accounts.iter_mut().zip(&self.config.account_labels) {
+            account.label = Some(label.clone());
+        }
+
+        self.storage.save(&self.accounts)?;
+
+        Ok(generator.mnemonic_phrase())
+    }
+
+    /// Move instance data to the trash
+    ///
+    /// Accounts kept in the OS keyring or in memory can't be moved with the
+    /// directory, so they are deleted.
+    fn clear_instance_data(&self) -> Result<()> {
+        if self.storage.backend() != StorageBackend::File {
+            self.storage.delete()?;
+        }
+
+        let trashed = Config::trash().move_instance(
+            ChainType::Ethereum,
+            &self.config.instance_id,
+            &self.config.instance_dir(),
+        )?;
+        if let Some(entry) = trashed {
+            info!(trash = %entry.id, "Moved instance data to the trash");
+        }
+
+        Ok(())
+    }
+
+    /// Save instance info for CLI discovery
+    fn save_instance_info(&self) -> Result<()> {
+        let pid = self
+            .node_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|child| child.id());
+        let info = EthereumInstanceInfo {
+            instance_id: self.config.instance_id.clone(),
+            name: self.config.name.clone(),
+            rpc_url: self.config.rpc_url.clone(),
+            rpc_port: self.config.port,
+            chain_id: self.config.chain_id,
+            accounts_count: self.config.accounts,
+            running: true,
+            pid,
+            epoch: 0,
+            startup: StartupProfile::default(),
+        };
+        info.save()
+    }
+
+    /// Record how long each phase of this start took in the instance info
+    fn save_startup_profile(&self, startup: StartupProfile) -> Result<()> {
+        let mut info = EthereumInstanceInfo::load(&self.config.instance_id)?;
+        info!(total_ms = startup.total_ms(), "Startup phases recorded");
+        info.startup = startup;
+        info.save()
+    }
+
+    /// Register this node with the global registry
+    fn register_with_registry(&self) -> Result<()> {
+        let registry = NodeRegistry::new();
+        let mut node = NodeInfo::new(
+            ChainType::Ethereum,
+            &self.config.instance_id,
+            self.config.name.clone(),
+            self.config.rpc_url.clone(),
+            self.config.port,
+            self.config.accounts,
+        );
+        node.node_pid = self
+            .node_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|child| child.id());
+        registry.register(node)
+    }
+
+    /// Unregister this node from the global registry
+    fn unregister_from_registry(&self) -> Result<()> {
+        let registry = NodeRegistry::new();
+        let node_id = NodeRegistry::node_id(ChainType::Ethereum, &self.config.instance_id);
+        registry.update_status(&node_id, NodeStatus::Stopped)
+    }
+
+    /// Context passed to lifecycle hooks
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            chain: ChainType::Ethereum,
+            instance_id: self.config.instance_id.clone(),
+            rpc_url: self.config.rpc_url.clone(),
+            accounts: self.accounts.iter().map(|a| a.address.clone()).collect(),
+        }
+    }
+
+    /// Publish an event for this node
+    fn publish(&self, event: ChainEvent) {
+        chain_forge_events::publish(ChainType::Ethereum, &self.config.instance_id, event);
+    }
+
+    /// Start the anvil process
+    fn start_node(&mut self, mnemonic: &str) -> Result<()> {
+        if Command::new("anvil").arg("--version").output().is_err() {
+            return Err(ChainError::NodeManagement(
+                "anvil not found. Please install Foundry (https://getfoundry.sh).".to_string(),
+            ));
+        }
+
+        std::net::TcpListener::bind(("0.0.0.0", self.config.port)).map_err(|_| {
+            ChainError::NodeManagement(format!(
+                "RPC port {} is already in use. Check for other running nodes or services.",
+                self.config.port
+            ))
+        })?;
+
+        let instance_name = self
+            .config
+            .name
+            .as_ref()
+            .unwrap_or(&self.config.instance_id);
+        info!(
+            instance = %instance_name,
+            port = self.config.port,
+            "Starting anvil"
+        );
+
+        let log_dir = self.config.instance_dir();
+        std::fs::create_dir_all(&log_dir).ok();
+        let stdout_file = std::fs::File::create(self.config.log_file()).map_err(|e| {
+            ChainError::NodeManagement(format!("Failed to create stdout log: {}", e))
+        })?;
+        let stderr_file = std::fs::File::create(log_dir.join("anvil_stderr.log")).map_err(|e| {
+            ChainError::NodeManagement(format!("Failed to create stderr log: {}", e))
+        })?;
+
+        // Balances are set exactly with anvil_setBalance once the node is up,
+        // since --balance only takes whole ETH
+        let mut cmd = Command::new("anvil");
+        cmd.arg("--port")
+            .arg(self.config.port.to_string())
+            .arg("--chain-id")
+            .arg(self.config.chain_id.to_string())
+            .arg("--mnemonic")
+            .arg(mnemonic)
+            .arg("--accounts")
+            .arg(self.config.accounts.to_string())
+            .stdout(stdout_file)
+            .stderr(stderr_file);
+
+        if let Some(block_time) = self.config.block_time {
+            cmd.arg("--block-time").arg(block_time.to_string());
+        }
+
+        let child = cmd
+            .spawn()
+            .map_err(|e| ChainError::NodeManagement(format!("Failed to start anvil: {}", e)))?;
+
+        *self.node_process.lock().unwrap() = Some(child);
+
+        Ok(())
+    }
+
+    /// Get a reference to the RPC client
+    pub fn rpc_client(&self) -> Result<&EthereumRpcClient> {
+        self.rpc_client.as_ref().ok_or(ChainError::NotRunning)
+    }
+}
+
+impl Default for EthereumProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChainProvider for EthereumProvider {
+    type Account = EthereumAccount;
+    type Transaction = ();
+    type Config = EthereumConfig;
+
+    fn start(&mut self, config: Self::Config) -> Result<()> {
+        if self.is_running() {
+            return Err(ChainError::AlreadyRunning);
+        }
+
+        self.config = config;
+
+        // Check if this instance is already running in the registry
+        let registry = NodeRegistry::new();
+        let node_id = NodeRegistry::node_id(ChainType::Ethereum, &self.config.instance_id);
+        if let Ok(Some(existing)) = registry.get(&node_id) {
+            if existing.status == NodeStatus::Running {
+                return Err(ChainError::NodeManagement(format!(
+                    "Instance '{}' is already running on port {}. Stop it first or use a different instance name.",
+                    self.config.instance_id, existing.rpc_port
+                )));
+            }
+        }
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::BeforeStart, &ctx)?;
+
+        self.storage = AccountsStorage::with_path(self.config.accounts_file());
+
+        info!(
+            instance = %self.config.instance_id,
+            "Clearing previous instance data"
+        );
+        self.clear_instance_data()?;
+
+        let retry = Config::load()?.funding_retry(ChainType::Ethereum);
+        let mnemonic = self.generate_accounts()?;
+        let mut timer = StartupTimer::new();
+        self.start_node(&mnemonic)?;
+
+        // Brief pause to detect early startup failures (e.g., bad arguments)
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        {
+            let mut process_guard = self.node_process.lock().unwrap();
+            if let Some(ref mut child) = *process_guard {
+                if let Ok(Some(status)) = child.try_wait() {
+                    process_guard.take();
+                    return Err(ChainError::NodeManagement(format!(
+                        "anvil exited unexpectedly (exit code: {}). Check logs at: {}",
+                        status,
+                        self.config
+                            .instance_dir()
+                            .join("anvil_stderr.log")
+                            .display()
+                    )));
+                }
+            }
+        }
+        timer.lap(StartupPhase::Spawn);
+
+        self.save_instance_info()?;
+
+        let rpc_client = EthereumRpcClient::new(self.config.rpc_url.clone());
+        info!("Waiting for anvil to be ready");
+        rpc_client.wait_for_node(60)?;
+        info!("anvil is ready");
+        timer.lap(StartupPhase::RpcReady);
+
+        info!(
+            accounts = self.config.accounts,
+            balance = self.config.initial_balance,
+            "Setting account balances (ETH)"
+        );
+        rpc_client.set_balances(&self.accounts, &retry)?;
+        rpc_client.update_balances(&mut self.accounts)?;
+        self.storage.save(&self.accounts)?;
+        info!("All accounts funded");
+        timer.lap(StartupPhase::Funding);
+
+        self.rpc_client = Some(rpc_client);
+
+        if let Err(e) = self.save_startup_profile(timer.finish()) {
+            warn!(error = %e, "Failed to save the startup profile");
+        }
+
+        if let Err(e) = self.register_with_registry() {
+            warn!(error = %e, "Failed to register with node registry");
+        }
+        self.heartbeat = Some(Heartbeat::start(
+            NodeRegistry::node_id(ChainType::Ethereum, &self.config.instance_id),
+            HEARTBEAT_INTERVAL,
+        ));
+        self.publish(ChainEvent::NodeStarted);
+        self.publish(ChainEvent::FundsDispensed {
+            address: None,
+            amount: self.config.accounts as f64 * self.config.initial_balance,
+        });
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::AfterStart, &ctx)?;
+
+        let instance_name = self
+            .config
+            .name
+            .as_ref()
+            .unwrap_or(&self.config.instance_id);
+        info!(
+            instance = %instance_name,
+            rpc_url = %self.config.rpc_url,
+            chain_id = self.config.chain_id,
+            "anvil is running"
+        );
+
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        if self.is_running() {
+            let ctx = self.hook_context();
+            self.hooks.run_before_stop(&ctx);
+        }
+
+        if let Some(mut heartbeat) = self.heartbeat.take() {
+            heartbeat.stop();
+        }
+
+        let mut process_guard = self.node_process.lock().unwrap();
+
+        if let Some(mut child) = process_guard.take() {
+            child
+                .kill()
+                .map_err(|e| ChainError::NodeManagement(format!("Failed to stop anvil: {}", e)))?;
+
+            if let Err(e) = self.unregister_from_registry() {
+                warn!(error = %e, "Failed to unregister from node registry");
+            }
+            self.publish(ChainEvent::NodeStopped);
+
+            child.wait().map_err(|e| {
+                ChainError::NodeManagement(format!("Failed to wait for anvil: {}", e))
+            })?;
+
+            if let Ok(mut info) = EthereumInstanceInfo::load(&self.config.instance_id) {
+                let _ = info.mark_stopped();
+            }
+
+            if !self.keep_data {
+                let _ = self.clear_instance_data();
+            }
+
+            info!(instance = %self.config.instance_id, "anvil stopped");
+        }
+
+        self.rpc_client = None;
+
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.node_process.lock().unwrap().is_some()
+    }
+
+    fn get_accounts(&self) -> Result<Vec<Self::Account>> {
+        Ok(self.accounts.clone())
+    }
+
+    fn set_balance(&self, address: &str, amount: f64) -> Result<String> {
+        self.rpc_client()?.set_balance(address, amount)
+    }
+
+    fn get_balance(&self, address: &str) -> Result<f64> {
+        self.rpc_client()?.get_balance(address)
+    }
+
+    fn get_rpc_url(&self) -> String {
+        self.config.rpc_url.clone()
+    }
+
+    fn hooks(&mut self) -> &mut LifecycleHooks {
+        &mut self.hooks
+    }
+}
+
+impl Drop for EthereumProvider {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults() {
+        let config = EthereumConfig::default();
+        assert_eq!(config.port, 8545);
+        assert_eq!(config.accounts, 10);
+        assert_eq!(config.initial_balance, 10000.0);
+        assert_eq!(config.chain_id, 31337);
+        assert_eq!(config.rpc_url, "http://localhost:8545");
+        assert!(config.block_time.is_none());
+        assert_eq!(config.instance_id, "default");
+    }
+
+    #[test]
+    fn test_config_from_profile() {
+        let profile = EthereumProfile {
+            rpc_url: "http://localhost:9545".to_string(),
+            accounts: 3,
+            initial_balance: 50.0,
+            port: 9545,
+            chain_id: 1337,
+        };
+
+        let config: EthereumConfig = profile.into();
+        assert_eq!(config.rpc_url, "http://localhost:9545");
+        assert_eq!(config.accounts, 3);
+        assert_eq!(config.initial_balance, 50.0);
+        assert_eq!(config.port, 9545);
+        assert_eq!(config.chain_id, 1337);
+        assert_eq!(config.instance_id, "default");
+    }
+
+    #[test]
+    fn test_instance_paths() {
+        let config = EthereumConfig::with_instance("my-instance");
+        assert!(config
+            .instance_dir()
+            .ends_with("ethereum/instances/my-instance"));
+        assert!(config
+            .accounts_file()
+            .ends_with("ethereum/instances/my-instance/accounts.json"));
+        assert!(config
+            .instance_info_file()
+            .ends_with("ethereum/instances/my-instance/instance.json"));
+    }
+
+    #[test]
+    fn test_provider_not_running_initially() {
+        let provider = EthereumProvider::with_instance("test");
+        assert!(!provider.is_running());
+        assert!(provider.get_accounts().unwrap().is_empty());
+        assert!(matches!(provider.rpc_client(), Err(ChainError::NotRunning)));
+        assert_eq!(provider.get_rpc_url(), "http://localhost:8545");
+    }
+
+    #[test]
+    fn test_instance_info_serialization() {
+        let info = EthereumInstanceInfo {
+            instance_id: "test".to_string(),
+            name: Some("Test Node".to_string()),
+            rpc_url: "http://localhost:8545".to_string(),
+            rpc_port: 8545,
+            chain_id: 31337,
+            accounts_count: 10,
+            running: true,
+            pid: Some(4242),
+            epoch: 0,
+            startup: StartupProfile::default(),
+        };
+
+        let json = serde_json::to_string(&info).unwrap();
+        let deserialized: EthereumInstanceInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.instance_id, "test");
+        assert_eq!(deserialized.chain_id, 31337);
+        assert_eq!(deserialized.pid, Some(4242));
+        assert_eq!(deserialized.client().url(), "http://localhost:8545");
+    }
+}
@@ -0,0 +1,70 @@
+//! Reset a running instance back to a clean chain without restarting anvil.
+//!
+//! `anvil_reset` drops all chain state (deployed contracts, nonces, and
+//! balances) and returns to genesis, after which every account is set to the
+//! requested balance again. Accounts keep their keys.
+
+use crate::{EthereumConfig, EthereumInstanceInfo};
+use chain_forge_common::{ChainError, ChainType, Result};
+use chain_forge_config::Config;
+use chain_forge_ethereum_accounts::{AccountsStorage, EthereumAccount};
+use chain_forge_events::ChainEvent;
+use tracing::info;
+
+/// Roll an instance back to genesis and set each account to `balance` ETH
+///
+/// Bumps the instance's `epoch`. Returns the accounts with their new balances.
+pub fn reset_instance(instance_id: &str, balance: f64) -> Result<Vec<EthereumAccount>> {
+    let mut info = EthereumInstanceInfo::load(instance_id)?;
+    let client = info.client();
+    if !client.is_node_running() {
+        return Err(ChainError::NotRunning);
+    }
+
+    let storage =
+        AccountsStorage::with_path(EthereumConfig::with_instance(instance_id).accounts_file());
+    let mut accounts = storage.load()?;
+
+    let height = client.get_block_number()?;
+    client.reset()?;
+    info!(blocks = height, "Rolled back to genesis");
+
+    info!(
+        accounts = accounts.len(),
+        balance, "Setting account balances (ETH)"
+    );
+    for account in accounts.iter_mut() {
+        account.balance = balance;
+    }
+    let retry = Config::load()?.funding_retry(ChainType::Ethereum);
+    client.set_balances(&accounts, &retry)?;
+    publish(
+        instance_id,
+        ChainEvent::FundsDispensed {
+            address: None,
+            amount: accounts.len() as f64 * balance,
+        },
+    );
+
+    client.update_balances(&mut accounts)?;
+    storage.save(&accounts)?;
+    for account in &accounts {
+        publish(
+            instance_id,
+            ChainEvent::BalanceChanged {
+                address: account.address.clone(),
+                balance: account.balance,
+            },
+        );
+    }
+
+    info.epoch += 1;
+    info.save()?;
+
+    Ok(accounts)
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Ethereum, instance_id, event);
+}
@@ -0,0 +1,419 @@
+use chain_forge_common::rpc_cassette::{self, RecordedError};
+use chain_forge_common::{rpc_metrics, ChainError, Result, RetryPolicy};
+use chain_forge_ethereum_accounts::EthereumAccount;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Wei per ETH
+const WEI_PER_ETH: u128 = 1_000_000_000_000_000_000;
+
+/// Convert an amount in ETH to wei
+///
+/// The amount is converted from its shortest decimal representation, so
+/// `0.1` becomes exactly 10^17 wei. Digits past the 18th decimal are dropped.
+pub fn eth_to_wei(eth: f64) -> Result<u128> {
+    if !eth.is_finite() || eth < 0.0 {
+        return Err(ChainError::Other(format!(
+            "Amount must be a non-negative number, got {}",
+            eth
+        )));
+    }
+
+    let decimal = eth.to_string();
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((&decimal, ""));
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(18)
+        .collect();
+
+    let too_large = || ChainError::Other(format!("Amount {} ETH is too large", eth));
+    let whole: u128 = whole.parse().map_err(|_| too_large())?;
+    let fraction: u128 = fraction.parse().map_err(|_| too_large())?;
+    whole
+        .checked_mul(WEI_PER_ETH)
+        .and_then(|wei| wei.checked_add(fraction))
+        .ok_or_else(too_large)
+}
+
+/// Convert an amount in wei to ETH
+pub fn wei_to_eth(wei: u128) -> f64 {
+    (wei / WEI_PER_ETH) as f64 + (wei % WEI_PER_ETH) as f64 / WEI_PER_ETH as f64
+}
+
+/// Parse a 0x-prefixed hex quantity from a JSON-RPC result
+fn parse_quantity(quantity: &str) -> Result<u128> {
+    u128::from_str_radix(quantity.trim_start_matches("0x"), 16)
+        .map_err(|e| ChainError::Rpc(format!("Invalid quantity '{}': {}", quantity, e)))
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// Ethereum JSON-RPC client for an anvil node
+///
+/// Besides the standard `eth_*` methods, this uses anvil's `anvil_*` test
+/// methods to set balances and mine blocks.
+pub struct EthereumRpcClient {
+    rpc_url: String,
+}
+
+impl EthereumRpcClient {
+    /// Create a new RPC client
+    pub fn new(rpc_url: String) -> Self {
+        Self { rpc_url }
+    }
+
+    /// Get the RPC URL
+    pub fn url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// Send a JSON-RPC request and decode its result
+    ///
+    /// The request is recorded to, or answered from, the active RPC
+    /// cassette, if any.
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let cassette = rpc_cassette::active();
+        let response = match cassette.as_ref().filter(|c| c.is_replaying()) {
+            Some(cassette) => match cassette.replay_call(method, &params) {
+                Ok(result) => Ok(RpcResponse {
+                    result: Some(result),
+                    error: None,
+                }),
+                Err(RecordedError {
+                    code: Some(code),
+                    message,
+                }) => Ok(RpcResponse {
+                    result: None,
+                    error: Some(RpcError { code, message }),
+                }),
+                Err(RecordedError {
+                    code: None,
+                    message,
+                }) => Err(ChainError::Rpc(message)),
+            },
+            None => {
+                let response = self.send(method, &params);
+                if let Some(cassette) = cassette {
+                    let outcome = match &response {
+                        Ok(RpcResponse {
+                            error: Some(error), ..
+                        }) => Err(RecordedError {
+                            code: Some(error.code),
+                            message: error.message.clone(),
+                        }),
+                        Ok(response) => Ok(response.result.clone().unwrap_or(Value::Null)),
+                        Err(ChainError::Rpc(message)) => Err(RecordedError {
+                            code: None,
+                            message: message.clone(),
+                        }),
+                        Err(e) => Err(RecordedError {
+                            code: None,
+                            message: e.to_string(),
+                        }),
+                    };
+                    cassette.record_call(&self.rpc_url, method, &params, &outcome);
+                }
+                response
+            }
+        };
+
+        let response = response?;
+        if let Some(error) = response.error {
+            return Err(ChainError::Rpc(format!(
+                "{} failed ({}): {}",
+                method, error.code, error.message
+            )));
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .map_err(|e| ChainError::Rpc(format!("Invalid {} result: {}", method, e)))
+    }
+
+    /// Send a JSON-RPC request to the node
+    fn send(&self, method: &str, params: &Value) -> Result<RpcResponse> {
+        let started = Instant::now();
+        let response = ureq::post(&self.rpc_url)
+            .timeout(Duration::from_secs(30))
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .map_err(|e| ChainError::Rpc(format!("{} failed: {}", method, e)))
+            .and_then(|response| {
+                response
+                    .into_json::<RpcResponse>()
+                    .map_err(|e| ChainError::Rpc(format!("Invalid {} response: {}", method, e)))
+            });
+
+        let success = matches!(&response, Ok(r) if r.error.is_none());
+        rpc_metrics::global().record(&self.rpc_url, method, started.elapsed(), success);
+        response
+    }
+
+    /// Check if the node is running by asking for its chain ID
+    pub fn is_node_running(&self) -> bool {
+        self.chain_id().is_ok()
+    }
+
+    /// Wait for the node to be ready, polling every 500ms
+    pub fn wait_for_node(&self, max_attempts: u32) -> Result<()> {
+        for attempt in 1..=max_attempts {
+            if self.is_node_running() {
+                return Ok(());
+            }
+
+            std::thread::sleep(Duration::from_millis(500));
+
+            if attempt == max_attempts {
+                return Err(ChainError::Timeout(
+                    "Ethereum node did not start in time".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the chain ID
+    pub fn chain_id(&self) -> Result<u64> {
+        let id: String = self.call("eth_chainId", json!([]))?;
+        Ok(parse_quantity(&id)? as u64)
+    }
+
+    /// Get the current block number
+    pub fn get_block_number(&self) -> Result<u64> {
+        let number: String = self.call("eth_blockNumber", json!([]))?;
+        Ok(parse_quantity(&number)? as u64)
+    }
+
+    /// Get the hash of the block at `number`
+    pub fn get_block_hash(&self, number: u64) -> Result<String> {
+        self.get_block(number)?["hash"]
+            .as_str()
+            .map(|hash| hash.to_string())
+            .ok_or_else(|| ChainError::Rpc(format!("Block {} has no hash", number)))
+    }
+
+    /// Get the Unix time of the block at `number`
+    pub fn get_block_time(&self, number: u64) -> Result<i64> {
+        let block = self.get_block(number)?;
+        let timestamp = block["timestamp"]
+            .as_str()
+            .ok_or_else(|| ChainError::Rpc(format!("Block {} has no timestamp", number)))?;
+        Ok(parse_quantity(timestamp)? as i64)
+    }
+
+    /// Get the block at `number`, without its transactions
+    fn get_block(&self, number: u64) -> Result<Value> {
+        let block: Value = self.call(
+            "eth_getBlockByNumber",
+            json!([format!("{:#x}", number), false]),
+        )?;
+        if block.is_null() {
+            return Err(ChainError::Rpc(format!("Block {} not found", number)));
+        }
+        Ok(block)
+    }
+
+    /// Number of peers the node is connected to (`net_peerCount`)
+    pub fn peer_count(&self) -> Result<usize> {
+        let count: String = self.call("net_peerCount", json!([]))?;
+        Ok(parse_quantity(&count)? as usize)
+    }
+
+    /// Get the balance of an address in wei
+    pub fn get_balance_wei(&self, address: &str) -> Result<u128> {
+        let balance: String = self.call("eth_getBalance", json!([address, "latest"]))?;
+        parse_quantity(&balance)
+    }
+
+    /// Get the balance of an address in ETH
+    pub fn get_balance(&self, address: &str) -> Result<f64> {
+        Ok(wei_to_eth(self.get_balance_wei(address)?))
+    }
+
+    /// Set an address's balance to exactly `target_eth`
+    ///
+    /// Uses `anvil_setBalance`, so balances can be lowered as well as raised
+    /// and no transaction is created.
+    pub fn set_balance(&self, address: &str, target_eth: f64) -> Result<String> {
+        let current = self.get_balance(address)?;
+        let wei = eth_to_wei(target_eth)?;
+
+        let _: Value = self.call("anvil_setBalance", json!([address, format!("{:#x}", wei)]))?;
+
+        Ok(format!(
+            "Set balance to {} ETH (was {} ETH)",
+            target_eth, current
+        ))
+    }
+
+    /// Add `amount_eth` to an address's balance, returning the new balance
+    pub fn add_balance(&self, address: &str, amount_eth: f64) -> Result<f64> {
+        let wei = self
+            .get_balance_wei(address)?
+            .checked_add(eth_to_wei(amount_eth)?)
+            .ok_or_else(|| ChainError::Other(format!("Balance of {} would overflow", address)))?;
+
+        let _: Value = self.call("anvil_setBalance", json!([address, format!("{:#x}", wei)]))?;
+
+        Ok(wei_to_eth(wei))
+    }
+
+    /// Set every account to its target balance
+    ///
+    /// Failed calls are retried per `retry`, and each account's outcome is
+    /// logged.
+    pub fn set_balances(&self, accounts: &[EthereumAccount], retry: &RetryPolicy) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for (i, account) in accounts.iter().enumerate() {
+            for attempt in 1..=retry.attempts() {
+                match self.set_balance(&account.address, account.balance) {
+                    Ok(_) => {
+                        info!(
+                            account = i,
+                            address = %account.address,
+                            attempts = attempt,
+                            status = "funded",
+                            "Account balance set"
+                        );
+                        break;
+                    }
+                    Err(e) if attempt < retry.attempts() => {
+                        let delay = retry.delay(attempt);
+                        warn!(
+                            account = i,
+                            attempt,
+                            error = %e,
+                            delay_ms = delay.as_millis() as u64,
+                            "Failed to set balance, retrying"
+                        );
+                        std::thread::sleep(delay);
+                    }
+                    Err(e) => {
+                        warn!(
+                            account = i,
+                            attempts = attempt,
+                            status = "failed",
+                            error = %e,
+                            "Failed to set balance"
+                        );
+                        errors.push(format!("account {}: {}", i, e));
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ChainError::Rpc(format!(
+                "Failed to fund {} account(s): {}",
+                errors.len(),
+                errors.join("; ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Update account balances from the chain
+    ///
+    /// Returns an error if any balance queries fail, listing which accounts failed.
+    pub fn update_balances(&self, accounts: &mut [EthereumAccount]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for account in accounts.iter_mut() {
+            match self.get_balance(&account.address) {
+                Ok(balance) => {
+                    account.balance = balance;
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", account.address, e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ChainError::Rpc(format!(
+                "Failed to get balance for {} account(s): {}",
+                errors.len(),
+                errors.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Roll the chain back to genesis, dropping all state
+    pub fn reset(&self) -> Result<()> {
+        let _: Value = self.call("anvil_reset", json!([]))?;
+        Ok(())
+    }
+
+    /// Mine `count` blocks immediately
+    pub fn mine_blocks(&self, count: u64) -> Result<()> {
+        let _: Value = self.call("anvil_mine", json!([format!("{:#x}", count)]))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eth_to_wei() {
+        assert_eq!(eth_to_wei(0.0).unwrap(), 0);
+        assert_eq!(eth_to_wei(1.0).unwrap(), WEI_PER_ETH);
+        assert_eq!(eth_to_wei(0.1).unwrap(), 100_000_000_000_000_000);
+        assert_eq!(eth_to_wei(10000.5).unwrap(), 10_000_500_000_000_000_000_000);
+        assert_eq!(eth_to_wei(0.000000000000000001).unwrap(), 1);
+        assert_eq!(eth_to_wei(1e-20).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_eth_to_wei_rejects_invalid_amounts() {
+        assert!(eth_to_wei(-1.0).is_err());
+        assert!(eth_to_wei(f64::NAN).is_err());
+        assert!(eth_to_wei(f64::INFINITY).is_err());
+        assert!(eth_to_wei(1e30).is_err());
+    }
+
+    #[test]
+    fn test_wei_to_eth() {
+        assert_eq!(wei_to_eth(0), 0.0);
+        assert_eq!(wei_to_eth(WEI_PER_ETH), 1.0);
+        assert_eq!(wei_to_eth(1_500_000_000_000_000_000), 1.5);
+        assert_eq!(wei_to_eth(eth_to_wei(100.25).unwrap()), 100.25);
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("0x0").unwrap(), 0);
+        assert_eq!(parse_quantity("0x7a69").unwrap(), 31337);
+        assert!(parse_quantity("0xnope").is_err());
+    }
+
+    #[test]
+    fn test_client_url() {
+        let client = EthereumRpcClient::new("http://localhost:8545".to_string());
+        assert_eq!(client.url(), "http://localhost:8545");
+    }
+}
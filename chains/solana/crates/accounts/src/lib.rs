@@ -1,8 +1,11 @@
 use bip39::Mnemonic;
-use chain_forge_common::{ChainError, Result};
+use chain_forge_common::{
+    AccountStore, ChainError, Protection, Result, SignerKeystore, StorageBackend,
+};
+use chain_forge_config::Config;
 use ed25519_dalek::SigningKey;
 use serde::{Deserialize, Serialize};
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
 use std::path::Path;
 
 #[cfg(test)]
@@ -17,6 +20,12 @@ pub struct SolanaAccount {
     pub mnemonic: Option<String>,
     pub derivation_path: Option<String>,
     pub balance: f64,
+    /// Key is held by an external signer rather than stored with the account
+    #[serde(default)]
+    pub external_signer: bool,
+    /// Optional human-readable label (e.g. "alice")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 impl SolanaAccount {
@@ -28,11 +37,31 @@ impl SolanaAccount {
             mnemonic,
             derivation_path: path,
             balance: 0.0,
+            external_signer: false,
+            label: None,
         }
     }
 
+    /// Load an account from a Solana CLI keypair file (JSON byte array)
+    pub fn from_keypair_file(path: &Path) -> Result<Self> {
+        let keypair = read_keypair_file(path).map_err(|e| {
+            ChainError::AccountGeneration(format!(
+                "Failed to read keypair file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self::from_keypair(keypair, None, None))
+    }
+
     /// Get the keypair for this account
     pub fn keypair(&self) -> Result<Keypair> {
+        if self.external_signer {
+            return Err(ChainError::AccountGeneration(format!(
+                "Account {} uses an external signer",
+                self.public_key
+            )));
+        }
         Keypair::try_from(&self.secret_key[..])
             .map_err(|e| ChainError::AccountGeneration(format!("Invalid keypair bytes: {}", e)))
     }
@@ -41,11 +70,68 @@ impl SolanaAccount {
     pub fn address(&self) -> String {
         self.public_key.clone()
     }
+
+    /// Move this account's key into an external signer keystore
+    ///
+    /// The secret key and mnemonic are removed from the account; signing
+    /// must then go through [`EmulatedSigner`] or another
+    /// [`chain_forge_common::Signer`].
+    pub fn detach_key(&mut self, keystore: &SignerKeystore) -> Result<()> {
+        keystore.insert(&self.public_key, std::mem::take(&mut self.secret_key))?;
+        self.mnemonic = None;
+        self.external_signer = true;
+        Ok(())
+    }
+}
+
+/// Signer holding an ed25519 keypair in process
+pub struct LocalSigner {
+    address: String,
+    keypair: Keypair,
+}
+
+/// Software emulator of a hardware wallet for an external signer account
+///
+/// The emulator is a [`LocalSigner`] whose key lives in the signer keystore
+/// rather than in `accounts.json`.
+pub type EmulatedSigner = LocalSigner;
+
+impl LocalSigner {
+    /// Create a signer from an account's own keypair
+    pub fn from_account(account: &SolanaAccount) -> Result<Self> {
+        Ok(Self {
+            address: account.public_key.clone(),
+            keypair: account.keypair()?,
+        })
+    }
+
+    /// Load the signer for an address from a keystore
+    pub fn load(keystore: &SignerKeystore, address: &str) -> Result<Self> {
+        let secret = keystore.get(address)?;
+        let keypair = Keypair::try_from(&secret[..])
+            .map_err(|e| ChainError::AccountGeneration(format!("Invalid keypair bytes: {}", e)))?;
+        Ok(Self {
+            address: address.to_string(),
+            keypair,
+        })
+    }
+}
+
+impl chain_forge_common::Signer for LocalSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.keypair.sign_message(payload).as_ref().to_vec())
+    }
 }
 
 /// Account generator for Solana
 pub struct AccountGenerator {
     mnemonic: Mnemonic,
+    /// Index of the first account generated
+    start_index: u32,
 }
 
 impl AccountGenerator {
@@ -59,14 +145,26 @@ impl AccountGenerator {
         let mnemonic = Mnemonic::from_entropy(&entropy).map_err(|e| {
             ChainError::AccountGeneration(format!("Failed to generate mnemonic: {}", e))
         })?;
-        Ok(Self { mnemonic })
+        Ok(Self {
+            mnemonic,
+            start_index: 0,
+        })
     }
 
     /// Create a generator from an existing mnemonic phrase
     pub fn from_mnemonic(phrase: &str) -> Result<Self> {
         let mnemonic = Mnemonic::parse(phrase)
             .map_err(|e| ChainError::AccountGeneration(format!("Invalid mnemonic: {}", e)))?;
-        Ok(Self { mnemonic })
+        Ok(Self {
+            mnemonic,
+            start_index: 0,
+        })
+    }
+
+    /// Generate accounts from `index` on instead of from 0
+    pub fn with_start_index(mut self, index: u32) -> Self {
+        self.start_index = index;
+        self
     }
 
     /// Get the mnemonic phrase
@@ -74,11 +172,17 @@ impl AccountGenerator {
         self.mnemonic.to_string()
     }
 
-    /// Generate multiple accounts from the mnemonic
+    /// Generate multiple accounts from the mnemonic, from the start index on
     pub fn generate_accounts(&self, count: u32) -> Result<Vec<SolanaAccount>> {
+        let end = self.start_index.checked_add(count).ok_or_else(|| {
+            ChainError::AccountGeneration(format!(
+                "Cannot generate {} accounts from index {}",
+                count, self.start_index
+            ))
+        })?;
         let mut accounts = Vec::new();
 
-        for index in 0..count {
+        for index in self.start_index..end {
             let account = self.derive_account(index)?;
             accounts.push(account);
         }
@@ -194,18 +298,50 @@ fn derive_child_key(
 /// Accounts storage manager
 pub struct AccountsStorage {
     accounts_file: std::path::PathBuf,
+    protection: Protection,
+    backend: StorageBackend,
 }
 
+/// Serialized account fields that are encrypted at rest: the secret key and mnemonic
+const SECRET_FIELDS: &[&str] = &["secretKey", "mnemonic"];
+
 impl AccountsStorage {
     /// Create a new storage manager (uses default path under data_dir/solana/accounts.json)
     pub fn new(data_dir: &Path) -> Self {
         let accounts_file = data_dir.join("solana").join("accounts.json");
-        Self { accounts_file }
+        Self::with_path(accounts_file)
     }
 
     /// Create a storage manager with a specific file path
+    ///
+    /// Accounts are kept by the backend set with `accounts_storage` in
+    /// `chain-forge.toml`. Secrets are encrypted if `CHAIN_FORGE_PASSPHRASE`
+    /// is set.
     pub fn with_path(accounts_file: std::path::PathBuf) -> Self {
-        Self { accounts_file }
+        Self {
+            accounts_file,
+            protection: Protection::from_env(),
+            backend: Config::load()
+                .map(|config| config.accounts_storage)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Use `protection` instead of the one from the environment
+    pub fn with_protection(mut self, protection: Protection) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Use `backend` instead of the configured one
+    pub fn with_backend(mut self, backend: StorageBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    fn store(&self) -> Box<dyn AccountStore> {
+        self.backend
+            .open(&self.accounts_file, self.protection.clone())
     }
 
     /// Get the accounts file path
@@ -213,39 +349,41 @@ impl AccountsStorage {
         &self.accounts_file
     }
 
-    /// Save accounts to file
-    pub fn save(&self, accounts: &[SolanaAccount]) -> Result<()> {
-        // Ensure directory exists
-        if let Some(parent) = self.accounts_file.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    /// Backend the accounts are kept by
+    pub fn backend(&self) -> StorageBackend {
+        self.backend
+    }
 
-        let json = serde_json::to_string_pretty(accounts)?;
-        std::fs::write(&self.accounts_file, json)?;
-        Ok(())
+    /// Save accounts
+    ///
+    /// With the file backend the file is replaced atomically, so a crash
+    /// mid-save leaves the previous accounts in place.
+    pub fn save(&self, accounts: &[SolanaAccount]) -> Result<()> {
+        let accounts = accounts
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        self.store().save(&accounts, SECRET_FIELDS)
     }
 
-    /// Load accounts from file
+    /// Load accounts, upgrading an older file format and decrypting secrets
+    /// if they are encrypted
     pub fn load(&self) -> Result<Vec<SolanaAccount>> {
-        if !self.accounts_file.exists() {
-            return Ok(Vec::new());
-        }
-
-        let json = std::fs::read_to_string(&self.accounts_file)?;
-        let accounts: Vec<SolanaAccount> = serde_json::from_str(&json)?;
-        Ok(accounts)
+        Ok(self
+            .store()
+            .load()?
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<serde_json::Result<_>>()?)
     }
 
-    /// Check if accounts file exists
+    /// Check if any accounts are stored
     pub fn exists(&self) -> bool {
-        self.accounts_file.exists()
+        self.store().exists()
     }
 
-    /// Delete the accounts file
+    /// Delete the stored accounts
     pub fn delete(&self) -> Result<()> {
-        if self.accounts_file.exists() {
-            std::fs::remove_file(&self.accounts_file)?;
-        }
-        Ok(())
+        self.store().delete()
     }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use chain_forge_common::Signer as _;
 use tempfile::TempDir;
 
 #[test]
@@ -108,6 +109,50 @@ fn test_accounts_storage_save_load() {
     }
 }
 
+#[test]
+fn test_accounts_storage_encrypted_save_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let protection = Protection::Passphrase("test passphrase".to_string());
+    let storage = AccountsStorage::new(temp_dir.path()).with_protection(protection);
+
+    let generator = AccountGenerator::new().unwrap();
+    let accounts = generator.generate_accounts(2).unwrap();
+    storage.save(&accounts).unwrap();
+
+    let on_disk = std::fs::read_to_string(storage.accounts_file()).unwrap();
+    assert!(on_disk.contains(&accounts[0].public_key));
+    assert!(!on_disk.contains("secretKey"));
+    assert!(!on_disk.contains(&generator.mnemonic_phrase()));
+
+    let loaded = storage.load().unwrap();
+    assert_eq!(loaded[0].secret_key, accounts[0].secret_key);
+    assert_eq!(loaded[0].mnemonic, accounts[0].mnemonic);
+
+    let locked = AccountsStorage::new(temp_dir.path()).with_protection(Protection::Plaintext);
+    assert!(locked.load().is_err());
+}
+
+#[test]
+fn test_accounts_storage_memory_backend() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = AccountsStorage::new(temp_dir.path()).with_backend(StorageBackend::Memory);
+
+    let generator = AccountGenerator::new().unwrap();
+    let accounts = generator.generate_accounts(2).unwrap();
+    storage.save(&accounts).unwrap();
+
+    assert!(storage.exists());
+    assert!(!storage.accounts_file().exists());
+    let loaded = AccountsStorage::new(temp_dir.path())
+        .with_backend(StorageBackend::Memory)
+        .load()
+        .unwrap();
+    assert_eq!(loaded[1].secret_key, accounts[1].secret_key);
+
+    storage.delete().unwrap();
+    assert!(!storage.exists());
+}
+
 #[test]
 fn test_accounts_storage_delete() {
     let temp_dir = TempDir::new().unwrap();
@@ -177,6 +222,25 @@ fn test_multiple_account_indices() {
     assert_ne!(account_5.public_key, account_100.public_key);
 }
 
+#[test]
+fn test_start_index() {
+    let generator = AccountGenerator::new().unwrap().with_start_index(5);
+    let accounts = generator.generate_accounts(2).unwrap();
+
+    assert_eq!(
+        accounts[0].public_key,
+        generator.derive_account(5).unwrap().public_key
+    );
+    assert_eq!(
+        accounts[1].derivation_path.as_deref(),
+        Some("m/44'/501'/6'/0'")
+    );
+    assert!(generator
+        .with_start_index(u32::MAX)
+        .generate_accounts(2)
+        .is_err());
+}
+
 #[test]
 fn test_accounts_storage_with_path() {
     let temp_dir = TempDir::new().unwrap();
@@ -213,3 +277,56 @@ fn test_accounts_storage_with_nested_path() {
     storage.save(&accounts).unwrap();
     assert!(nested_path.exists());
 }
+
+#[test]
+fn test_detach_key_to_external_signer() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore = SignerKeystore::with_path(temp_dir.path().join("signers.json"));
+
+    let generator = AccountGenerator::new().unwrap();
+    let mut account = generator.derive_account(0).unwrap();
+    let expected = account.keypair().unwrap();
+
+    account.detach_key(&keystore).unwrap();
+
+    assert!(account.external_signer);
+    assert!(account.secret_key.is_empty());
+    assert!(account.mnemonic.is_none());
+    assert!(account.keypair().is_err());
+
+    // Signatures from the emulator verify against the account's public key
+    let signer = EmulatedSigner::load(&keystore, &account.public_key).unwrap();
+    assert_eq!(signer.address(), account.public_key);
+    let signature = signer.sign(b"hello").unwrap();
+    assert_eq!(signature, expected.sign_message(b"hello").as_ref().to_vec());
+}
+
+#[test]
+fn test_external_signer_defaults_to_false() {
+    let json = r#"{
+        "publicKey": "11111111111111111111111111111111",
+        "secretKey": [],
+        "mnemonic": null,
+        "derivationPath": null,
+        "balance": 0.0
+    }"#;
+
+    let account: SolanaAccount = serde_json::from_str(json).unwrap();
+    assert!(!account.external_signer);
+}
+
+#[test]
+fn test_local_signer_from_account() {
+    let generator = AccountGenerator::new().unwrap();
+    let account = generator.derive_account(0).unwrap();
+
+    let signer = LocalSigner::from_account(&account).unwrap();
+    assert_eq!(signer.address(), account.public_key);
+
+    let signature = signer.sign(b"message").unwrap();
+    let keypair = account.keypair().unwrap();
+    assert_eq!(
+        signature,
+        keypair.sign_message(b"message").as_ref().to_vec()
+    );
+}
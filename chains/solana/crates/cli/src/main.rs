@@ -1,13 +1,36 @@
-use chain_forge_cli_utils::OutputFormat;
-use chain_forge_common::{validate_name, ChainProvider};
-use chain_forge_config::Config;
-use chain_forge_solana_accounts::AccountsStorage;
-use chain_forge_solana_core::{SolanaConfig, SolanaInstanceInfo, SolanaProvider};
-use chain_forge_solana_rpc::SolanaRpcClient;
-use clap::{Parser, Subcommand};
-use eyre::Result;
+use chain_forge_cli_utils::{
+    daemon, data_dir, format_sol, logging, progress, write_secret_file, AmountArgs, DataDirArgs,
+    ExitCode, LogArgs, OutputFormat, SOL_DECIMALS,
+};
+use chain_forge_common::{
+    gap_scan, validate_name, ChainError, ChainProvider, ChainType, Message, NodeRegistry,
+    NodeStatus, RemoteSigner, Signer, SignerKeystore, DEFAULT_GAP_LIMIT,
+};
+use chain_forge_config::{Config, PortChoice, Preset};
+use chain_forge_events::ChainEvent;
+use chain_forge_solana_accounts::{
+    AccountGenerator, AccountsStorage, EmulatedSigner, LocalSigner, SolanaAccount,
+};
+use chain_forge_solana_core::snapshot::{pause_process, resume_process};
+use chain_forge_solana_core::{
+    auto_port, public, warp_instance, PublicNetwork, SolanaConfig, SolanaInstanceInfo,
+    SolanaProvider, SolanaSnapshot, WarpTarget, MINIMUM_SLOTS_PER_EPOCH, PORT_SPAN,
+};
+use chain_forge_solana_rpc::{fee_percentile, pay, EpochProgress, PriorityFee, SolanaRpcClient};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use eyre::{eyre, Result, WrapErr};
+use std::path::PathBuf;
+use std::time::Duration;
 use tabled::{Table, Tabled};
 
+mod wizard;
+
+/// How long `start --detach` waits for the node to come up
+const DETACH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long `stop` waits for a detached node before killing it
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Parser)]
 #[command(name = "cf-solana")]
 #[command(about = "Chain Forge - Solana local development tool", long_about = None)]
@@ -15,13 +38,30 @@ use tabled::{Table, Tabled};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    data: DataDirArgs,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Start local Solana test validator with pre-funded accounts
-    Start {
-        /// Instance ID for isolation (allows multiple nodes with separate state)
+    Start(StartArgs),
+
+    /// Track accounts on a public cluster instead of running a validator
+    Attach {
+        /// Public cluster to attach to
+        #[arg(long, default_value = "devnet", value_parser = ["devnet", "testnet"])]
+        network: String,
+
+        /// RPC endpoint to use instead of the cluster's public one
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// Instance ID to record the accounts under
         #[arg(short, long, default_value = "default")]
         instance: String,
 
@@ -29,29 +69,71 @@ enum Commands {
         #[arg(short = 'n', long)]
         name: Option<String>,
 
-        /// Number of accounts to generate
+        /// Number of accounts to derive
         #[arg(short, long, default_value = "10")]
         accounts: u32,
 
-        /// Initial balance for each account in SOL
-        #[arg(short, long, default_value = "100.0")]
-        balance: f64,
-
-        /// RPC port for the validator
-        #[arg(short, long, default_value = "8899")]
-        port: u16,
-
-        /// Optional mnemonic phrase to use for account generation
+        /// Optional mnemonic phrase to derive the accounts from
         #[arg(short, long)]
         mnemonic: Option<String>,
+    },
 
-        /// Keep instance data on stop (default: clean up)
-        #[arg(long, default_value = "false")]
-        keep_data: bool,
+    /// Save, restore, or list ledger snapshots
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// Show the current epoch, or wait for a later one
+    Epoch {
+        /// Instance ID to query
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Wait until the validator enters this epoch
+        #[arg(long)]
+        wait_for: Option<u64>,
+
+        /// Wait until the next epoch boundary
+        #[arg(long, default_value = "false", conflicts_with = "wait_for")]
+        next: bool,
+
+        /// Seconds to wait for the epoch
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Fast-forward a running validator to a later slot or epoch
+    ///
+    /// The validator restarts on its ledger at the new slot, keeping
+    /// accounts, programs, and balances.
+    Warp {
+        /// Instance ID to warp
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Slot to warp to
+        #[arg(long, required_unless_present = "epoch", conflicts_with = "epoch")]
+        slot: Option<u64>,
+
+        /// Warp to the first slot of this epoch
+        #[arg(long)]
+        epoch: Option<u64>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
     },
 
     /// List all generated accounts with their balances
     Accounts {
+        #[command(subcommand)]
+        command: Option<AccountsCommands>,
+
         /// Instance ID to query
         #[arg(short, long, default_value = "default")]
         instance: String,
@@ -59,6 +141,32 @@ enum Commands {
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
+
+        #[command(flatten)]
+        amount: AmountArgs,
+    },
+
+    /// Find funded accounts derived from a mnemonic, beyond the generated ones
+    Scan {
+        /// Instance ID whose validator (and mnemonic) to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Mnemonic to scan instead of the instance's
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+
+        /// Derivation index to start scanning from
+        #[arg(long, default_value = "0")]
+        start_index: u32,
+
+        /// Stop after this many empty accounts in a row
+        #[arg(long, default_value_t = DEFAULT_GAP_LIMIT)]
+        gap_limit: u32,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
     },
 
     /// Fund an account with SOL
@@ -74,6 +182,82 @@ enum Commands {
         instance: String,
     },
 
+    /// Transfer SOL between accounts
+    Transfer {
+        /// Source account address
+        from: String,
+
+        /// Destination account address
+        to: String,
+
+        /// Amount of SOL to send
+        amount: f64,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Sign through a remote signing service (HSM/KMS gateway) instead of a local key
+        #[arg(long, env = "CF_SIGNER_URL")]
+        signer_url: Option<String>,
+
+        /// Bearer token for the remote signing service
+        #[arg(long, env = "CF_SIGNER_TOKEN", hide_env_values = true)]
+        signer_token: Option<String>,
+
+        /// Compute unit price in micro-lamports (attaches a priority fee)
+        #[arg(long)]
+        priority_fee: Option<u64>,
+
+        /// Compute unit limit for the transaction
+        #[arg(long)]
+        compute_unit_limit: Option<u32>,
+
+        /// Use this durable nonce account instead of a recent blockhash
+        /// (the source account must be its authority)
+        #[arg(long)]
+        nonce_account: Option<String>,
+    },
+
+    /// Manage durable nonce accounts for offline-signed transactions
+    Nonce {
+        #[command(subcommand)]
+        command: NonceCommands,
+    },
+
+    /// Manage address lookup tables for v0 transactions
+    Alt {
+        #[command(subcommand)]
+        command: AltCommands,
+    },
+
+    /// Create SPL token mints and mint or transfer tokens between accounts
+    Token {
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+
+    /// Create, pay, and detect Solana Pay transfer requests
+    Pay {
+        #[command(subcommand)]
+        command: PayCommands,
+    },
+
+    /// Show prioritization fees paid in recent slots
+    Fees {
+        /// Only consider transactions that write-lock these accounts (repeatable)
+        #[arg(short, long)]
+        account: Vec<String>,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
     /// Show current configuration
     Config {
         /// Instance ID to show config for
@@ -89,206 +273,1008 @@ enum Commands {
     },
 }
 
-#[derive(Tabled)]
-struct AccountDisplay {
-    #[tabled(rename = "Index")]
-    index: usize,
-    #[tabled(rename = "Public Key")]
-    public_key: String,
-    #[tabled(rename = "Balance (SOL)")]
-    balance: String,
-}
+#[derive(Subcommand)]
+enum AccountsCommands {
+    /// Export account keys for solana-cli or wallets such as Phantom
+    Export {
+        /// Export format
+        #[arg(short, long, value_enum)]
+        format: KeyExportFormat,
 
-/// Get RPC client for a specific instance
-fn get_rpc_client_for_instance(instance_id: &str) -> Result<SolanaRpcClient> {
-    let info = SolanaInstanceInfo::load(instance_id).map_err(|e| eyre::eyre!("{}", e))?;
-    Ok(SolanaRpcClient::new(info.rpc_url))
+        /// Directory to write keypair files to (keypair-json only)
+        #[arg(short, long, default_value = "keypairs")]
+        output: PathBuf,
+
+        /// Instance ID to export
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 }
 
-/// Get accounts storage for a specific instance
-fn get_storage_for_instance(instance_id: &str) -> AccountsStorage {
-    let accounts_file = Config::data_dir()
-        .join("solana")
-        .join("instances")
-        .join(instance_id)
-        .join("accounts.json");
-    AccountsStorage::with_path(accounts_file)
+#[derive(Clone, Copy, ValueEnum)]
+enum KeyExportFormat {
+    /// One Solana CLI keypair file (JSON byte array) per account
+    KeypairJson,
+    /// The recovery phrase and each account's derivation path
+    Seed,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[derive(Subcommand)]
+enum NonceCommands {
+    /// Create a nonce account, funded by and under the authority of an account
+    Create {
+        /// Authority account address (pays the rent-exempt deposit)
+        authority: String,
 
-    match cli.command {
-        Commands::Start {
-            instance,
-            name,
-            accounts,
-            balance,
-            port,
-            mnemonic,
-            keep_data,
-        } => {
-            // Validate instance name
-            if let Err(e) = validate_name(&instance) {
-                eprintln!("❌ Invalid instance name: {}", e);
-                std::process::exit(1);
-            }
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 
-            // Validate display name if provided
-            if let Some(ref n) = name {
-                if let Err(e) = validate_name(n) {
-                    eprintln!("❌ Invalid display name: {}", e);
-                    std::process::exit(1);
-                }
-            }
+    /// Show the current nonce value
+    Show {
+        /// Nonce account address
+        nonce_account: String,
 
-            // Create instance-specific config
-            let mut config = SolanaConfig::with_instance(&instance);
-            config.rpc_url = format!("http://localhost:{}", port);
-            config.port = port;
-            config.accounts = accounts;
-            config.initial_balance = balance;
-            config.mnemonic = mnemonic;
-            config.name = name;
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 
-            let mut provider = SolanaProvider::with_config(config.clone());
-            provider.set_keep_data(keep_data);
-            provider.start(config)?;
+    /// Advance the nonce, invalidating transactions signed against it
+    Advance {
+        /// Nonce account address
+        nonce_account: String,
 
-            println!("💡 Tip: Keep this terminal open to keep the validator running");
-            println!(
-                "   Run 'cf-solana accounts --instance {}' in another terminal to see your accounts",
-                instance
-            );
-            println!();
+        /// Authority account address
+        authority: String,
 
-            // Keep the process alive
-            tokio::signal::ctrl_c().await?;
-            println!();
-            provider.stop()?;
-        }
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 
-        Commands::Accounts { instance, format } => {
-            let storage = get_storage_for_instance(&instance);
+    /// Withdraw SOL from a nonce account (withdrawing everything closes it)
+    Withdraw {
+        /// Nonce account address
+        nonce_account: String,
 
-            let mut accounts = storage.load()?;
+        /// Authority account address
+        authority: String,
 
-            if accounts.is_empty() {
-                println!(
-                    "No accounts found for instance '{}'. Run 'cf-solana start --instance {}' first.",
-                    instance, instance
-                );
-                return Ok(());
-            }
+        /// Destination address
+        to: String,
 
-            // Update balances from validator
-            let balances_updated = match get_rpc_client_for_instance(&instance) {
-                Ok(rpc_client) => {
-                    if rpc_client.is_validator_running() {
-                        match rpc_client.update_balances(&mut accounts) {
-                            Ok(_) => {
-                                // Save updated balances back to storage
-                                if let Err(e) = storage.save(&accounts) {
-                                    eprintln!("Warning: Could not save updated balances: {}", e);
-                                }
-                                true
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Warning: Could not update balances from validator: {}",
-                                    e
-                                );
-                                false
-                            }
-                        }
-                    } else {
-                        eprintln!("Warning: Validator is not running. Showing cached balances.");
-                        false
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Could not connect to instance: {}", e);
-                    eprintln!("Showing cached balances from startup.");
-                    false
-                }
-            };
+        /// Amount of SOL to withdraw
+        amount: f64,
 
-            match format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&accounts)?);
-                }
-                OutputFormat::Table => {
-                    let display_accounts: Vec<AccountDisplay> = accounts
-                        .iter()
-                        .enumerate()
-                        .map(|(i, acc)| AccountDisplay {
-                            index: i,
-                            public_key: acc.public_key.clone(),
-                            balance: format!("{:.2}", acc.balance),
-                        })
-                        .collect();
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+}
 
-                    let table = Table::new(display_accounts).to_string();
-                    println!("{}", table);
+#[derive(Subcommand)]
+enum AltCommands {
+    /// Create a lookup table, paid for by and under the authority of an account
+    Create {
+        /// Authority account address (pays rent)
+        authority: String,
 
-                    if !balances_updated {
-                        println!();
-                        println!(
-                            "Note: Balances shown are from startup cache (validator not reachable)"
-                        );
-                    }
-                }
-            }
-        }
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 
-        Commands::Fund {
-            address,
-            amount,
-            instance,
-        } => {
-            let rpc_client = get_rpc_client_for_instance(&instance)?;
+    /// Add addresses to a lookup table
+    Extend {
+        /// Lookup table address
+        table: String,
 
-            if !rpc_client.is_validator_running() {
-                eprintln!(
-                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
-                    instance
-                );
-                std::process::exit(1);
-            }
+        /// Authority account address (pays for the added space)
+        authority: String,
 
-            println!("💰 Requesting airdrop of {} SOL to {}...", amount, address);
+        /// Addresses to add
+        addresses: Vec<String>,
 
-            match rpc_client.request_airdrop(&address, amount) {
-                Ok(signature) => {
-                    println!("✅ Airdrop successful!");
-                    println!("   Signature: {}", signature);
+        /// Also add every account of the instance
+        #[arg(long, default_value = "false")]
+        all_accounts: bool,
 
-                    // Show updated balance
-                    if let Ok(balance) = rpc_client.get_balance(&address) {
-                        println!("   New balance: {} SOL", balance);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("❌ Airdrop failed: {}", e);
-                    std::process::exit(1);
-                }
-            }
-        }
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
 
-        Commands::Config { instance } => {
-            println!("Chain Forge Solana Configuration");
-            println!("=================================");
-            println!();
-            println!("Instance: {}", instance);
+    /// Show a lookup table's authority, state, and addresses
+    Show {
+        /// Lookup table address
+        table: String,
 
-            // Try to load instance info
-            match SolanaInstanceInfo::load(&instance) {
-                Ok(info) => {
-                    println!(
-                        "  Status: {}",
-                        if info.running {
-                            "Running (may be stale)"
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Deactivate a lookup table so it can be closed after the cooldown
+    Deactivate {
+        /// Lookup table address
+        table: String,
+
+        /// Authority account address
+        authority: String,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Close a deactivated lookup table and reclaim its rent
+    Close {
+        /// Lookup table address
+        table: String,
+
+        /// Authority account address
+        authority: String,
+
+        /// Address that receives the rent (default: the authority)
+        #[arg(long)]
+        recipient: Option<String>,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenCommands {
+    /// Create a mint, paid for by and with an account as mint authority
+    Create {
+        /// Mint authority account address (pays rent)
+        authority: String,
+
+        /// Number of decimals of the token
+        #[arg(short, long, default_value = "9")]
+        decimals: u8,
+
+        /// Also create a token account for every account of the instance
+        #[arg(long, default_value = "false")]
+        all_accounts: bool,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Mint tokens to accounts, creating their token accounts if needed
+    Mint {
+        /// Mint address
+        mint: String,
+
+        /// Mint authority account address
+        authority: String,
+
+        /// Amount of tokens to mint to each recipient
+        amount: f64,
+
+        /// Recipient addresses
+        to: Vec<String>,
+
+        /// Mint to every account of the instance
+        #[arg(long, default_value = "false")]
+        all_accounts: bool,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Transfer tokens between accounts
+    Transfer {
+        /// Mint address
+        mint: String,
+
+        /// Source account address
+        from: String,
+
+        /// Destination address
+        to: String,
+
+        /// Amount of tokens to send
+        amount: f64,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Show each account's balance of a token
+    Balances {
+        /// Mint address
+        mint: String,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum PayCommands {
+    /// Create a transfer request URL with a fresh reference key
+    Request {
+        /// Recipient address
+        recipient: String,
+
+        /// Amount of SOL requested
+        amount: f64,
+
+        /// Merchant name shown by wallets
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Description shown by wallets
+        #[arg(long)]
+        message: Option<String>,
+
+        /// Memo the payment must include
+        #[arg(long)]
+        memo: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Pay a transfer request from an account, as a wallet would
+    Send {
+        /// Transfer request URL (solana:...)
+        url: String,
+
+        /// Paying account address
+        #[arg(long)]
+        from: String,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+    },
+
+    /// Find the transaction paying a request by its reference and check it
+    Find {
+        /// Transfer request URL (solana:...)
+        url: String,
+
+        /// Seconds to keep looking for the payment (default: look once)
+        #[arg(short, long, default_value = "0")]
+        timeout: u64,
+
+        /// Instance ID to use
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
+// Options for starting a validator, shared by `start` and `snapshot restore`
+#[derive(Args)]
+struct StartArgs {
+    /// Instance ID for isolation (allows multiple nodes with separate state)
+    #[arg(short, long, default_value = "default")]
+    instance: String,
+
+    /// Human-readable name for the instance
+    #[arg(short = 'n', long)]
+    name: Option<String>,
+
+    /// Number of accounts to generate (default: 10)
+    #[arg(short, long)]
+    accounts: Option<u32>,
+
+    /// Initial balance for each account in SOL (default: 100.0)
+    #[arg(short, long)]
+    balance: Option<f64>,
+
+    /// Built-in preset to start from: ci, demo, or load-test (explicit flags take precedence)
+    #[arg(long)]
+    preset: Option<String>,
+
+    /// Profile from chain-forge.toml (`[solana.<NAME>]`) to start from; explicit
+    /// flags and presets take precedence
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// RPC port for the validator, or `auto` to pick free ports (default: 8899,
+    /// or derived from the instance ID with `port_strategy = "hashed"`)
+    #[arg(short, long)]
+    port: Option<PortChoice>,
+
+    /// Optional mnemonic phrase to use for account generation
+    #[arg(short, long)]
+    mnemonic: Option<String>,
+
+    /// Derivation index of the first account (default: 0)
+    #[arg(long, default_value = "0")]
+    start_index: u32,
+
+    /// Keep instance data on stop (default: move it to the trash)
+    #[arg(long, default_value = "false")]
+    keep_data: bool,
+
+    /// Prompt for instance settings instead of reading them from flags
+    #[arg(long, default_value = "false")]
+    interactive: bool,
+
+    /// Run the validator in the background and return once it's ready
+    #[arg(long, default_value = "false", conflicts_with = "interactive")]
+    detach: bool,
+
+    /// Boot from a ledger snapshot created with 'cf-solana snapshot'
+    #[arg(long)]
+    snapshot: Option<String>,
+
+    /// Warp the ledger to this slot after starting
+    #[arg(long)]
+    warp_slot: Option<u64>,
+
+    /// Warp the ledger to the first slot of this epoch after starting
+    #[arg(long, conflicts_with = "warp_slot")]
+    warp_epoch: Option<u64>,
+
+    /// Slots in each epoch (minimum 32); small values reach epoch
+    /// boundaries in seconds
+    #[arg(long)]
+    slots_per_epoch: Option<u64>,
+
+    /// Fixed annual inflation rate paid at epoch boundaries (e.g. 0.08)
+    #[arg(long)]
+    inflation: Option<f64>,
+
+    /// Account indices whose keys are held by an emulated external signer (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    external_signer: Vec<u32>,
+
+    /// Copy this account from the clone URL into the ledger (repeatable)
+    #[arg(long = "clone", value_name = "PUBKEY")]
+    clone_accounts: Vec<String>,
+
+    /// Copy this upgradeable program from the clone URL into the ledger
+    /// (repeatable)
+    #[arg(long = "clone-program", value_name = "PROGRAM_ID")]
+    clone_programs: Vec<String>,
+
+    /// RPC URL of the cluster to clone from
+    #[arg(long, default_value = chain_forge_solana_core::DEFAULT_CLONE_URL)]
+    clone_url: String,
+
+    /// Store account secrets in plaintext even if `encrypt_accounts` is set
+    /// and no passphrase is
+    #[arg(long, default_value = "false")]
+    insecure_plaintext: bool,
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Archive an instance's ledger and accounts once it reaches a slot
+    Create {
+        /// Instance ID to snapshot
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Slot to wait for before pausing the validator and archiving
+        /// (default: the current slot)
+        #[arg(long)]
+        at_slot: Option<u64>,
+
+        /// Snapshot name (default: <instance>-slot-<N>)
+        #[arg(short = 'n', long)]
+        name: Option<String>,
+
+        /// Seconds to wait for the slot
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+    },
+
+    /// Start an instance from a snapshot
+    Restore {
+        /// Snapshot to restore
+        #[arg(value_name = "NAME")]
+        from: String,
+
+        #[command(flatten)]
+        start: StartArgs,
+    },
+
+    /// List saved snapshots
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Tabled)]
+struct LookupAddressDisplay {
+    #[tabled(rename = "Index")]
+    index: usize,
+    #[tabled(rename = "Address")]
+    address: String,
+}
+
+#[derive(Tabled)]
+struct TokenBalanceDisplay {
+    #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Public Key")]
+    public_key: String,
+    #[tabled(rename = "Balance")]
+    balance: f64,
+}
+
+#[derive(Tabled)]
+struct SnapshotDisplay {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Instance")]
+    source_instance: String,
+    #[tabled(rename = "Slot")]
+    slot: u64,
+    #[tabled(rename = "Created")]
+    created_at: String,
+}
+
+#[derive(Tabled)]
+struct FeeDisplay {
+    #[tabled(rename = "Slot")]
+    slot: u64,
+    #[tabled(rename = "Fee (micro-lamports/CU)")]
+    fee: u64,
+}
+
+#[derive(Tabled)]
+struct DerivedAccountDisplay {
+    #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Path")]
+    path: String,
+    #[tabled(rename = "Public Key")]
+    public_key: String,
+}
+
+#[derive(Tabled)]
+struct ScanDisplay {
+    #[tabled(rename = "Index")]
+    index: u32,
+    #[tabled(rename = "Path")]
+    path: String,
+    #[tabled(rename = "Public Key")]
+    public_key: String,
+    #[tabled(rename = "Balance (SOL)")]
+    balance: String,
+    #[tabled(rename = "In Instance")]
+    tracked: String,
+}
+
+#[derive(Tabled)]
+struct AccountDisplay {
+    #[tabled(rename = "Index")]
+    index: String,
+    #[tabled(rename = "Public Key")]
+    public_key: String,
+    #[tabled(rename = "Balance (SOL)")]
+    balance: String,
+}
+
+/// Get RPC client for a specific instance
+fn get_rpc_client_for_instance(instance_id: &str) -> Result<SolanaRpcClient> {
+    let info = SolanaInstanceInfo::load(instance_id)?;
+    Ok(SolanaRpcClient::new(info.rpc_url))
+}
+
+/// Format an account index, with its label if it has one
+fn account_index(index: usize, label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("{} ({})", index, label),
+        None => index.to_string(),
+    }
+}
+
+/// Get accounts storage for a specific instance
+fn get_storage_for_instance(instance_id: &str) -> AccountsStorage {
+    let accounts_file = Config::data_dir()
+        .join("solana")
+        .join("instances")
+        .join(instance_id)
+        .join("accounts.json");
+    AccountsStorage::with_path(accounts_file)
+}
+
+/// Pick the signer for a transfer source
+///
+/// A remote signing service takes precedence; otherwise external signer
+/// accounts sign through the instance's emulated signer and other accounts
+/// with their local keypair.
+fn signer_for(
+    instance_id: &str,
+    from: &str,
+    signer_url: Option<String>,
+    signer_token: Option<String>,
+) -> Result<Box<dyn Signer>> {
+    if let Some(url) = signer_url {
+        return Ok(Box::new(
+            RemoteSigner::new(&url, from).with_token(signer_token),
+        ));
+    }
+
+    let account = get_storage_for_instance(instance_id)
+        .load()?
+        .into_iter()
+        .find(|a| a.public_key == from)
+        .ok_or_else(|| {
+            eyre!(
+                "Source address is not an account of instance '{}'",
+                instance_id
+            )
+        })?;
+
+    if account.external_signer {
+        let keystore = SignerKeystore::with_path(
+            SolanaConfig::with_instance(instance_id).signer_keystore_file(),
+        );
+        Ok(Box::new(EmulatedSigner::load(&keystore, from)?))
+    } else {
+        Ok(Box::new(LocalSigner::from_account(&account)?))
+    }
+}
+
+/// Print the validator's position in the epoch schedule
+fn print_epoch(
+    progress: &EpochProgress,
+    inflation: Option<f64>,
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "epoch": progress.epoch,
+                "slotIndex": progress.slot_index,
+                "slotsInEpoch": progress.slots_in_epoch,
+                "absoluteSlot": progress.absolute_slot,
+                "inflationRate": inflation,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Table => {
+            println!("📅 Epoch {}", progress.epoch);
+            println!(
+                "   Slot:      {} ({} of {} in epoch)",
+                progress.absolute_slot, progress.slot_index, progress.slots_in_epoch
+            );
+            println!("   Remaining: {} slots", progress.slots_remaining());
+            if let Some(rate) = inflation {
+                println!("   Inflation: {:.2}%", rate * 100.0);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Solana, instance_id, event);
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    data_dir::init(&cli.data);
+    logging::init(&cli.log);
+    progress::show_startup_progress();
+
+    if let Err(e) = run(cli).await {
+        eprintln!("Error: {:?}", e);
+        ExitCode::from_report(&e).exit();
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Commands::Start(args) => start(args).await?,
+
+        Commands::Attach {
+            network,
+            rpc_url,
+            instance,
+            name,
+            accounts,
+            mnemonic,
+        } => attach(network, rpc_url, instance, name, accounts, mnemonic)?,
+
+        Commands::Snapshot { command } => run_snapshot(command).await?,
+
+        Commands::Epoch {
+            instance,
+            wait_for,
+            next,
+            timeout,
+            format,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_validator_running() {
+                eprintln!(
+                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let mut progress = rpc_client.get_epoch_info()?;
+            let target = wait_for.or(next.then_some(progress.epoch + 1));
+            if let Some(epoch) = target.filter(|epoch| *epoch > progress.epoch) {
+                if matches!(format, OutputFormat::Table) {
+                    println!(
+                        "⏳ Waiting for epoch {} ({} slots left in epoch {})...",
+                        epoch,
+                        progress.slots_remaining(),
+                        progress.epoch
+                    );
+                }
+                progress = rpc_client
+                    .wait_for_epoch(epoch, Duration::from_secs(timeout))
+                    .await?;
+            }
+
+            // Not every validator exposes inflation (e.g. before the first epoch)
+            let inflation = rpc_client.get_inflation_rate().ok();
+            print_epoch(&progress, inflation, format)?;
+        }
+
+        Commands::Warp {
+            instance,
+            slot,
+            epoch,
+            format,
+        } => {
+            let target = match (slot, epoch) {
+                (Some(slot), _) => WarpTarget::Slot(slot),
+                (None, Some(epoch)) => WarpTarget::Epoch(epoch),
+                (None, None) => unreachable!("clap requires --slot or --epoch"),
+            };
+
+            let spinner = progress::spinner(format!(
+                "Restarting instance '{}' at the new slot",
+                instance
+            ));
+            let result = warp_instance(&instance, target);
+            spinner.finish_and_clear();
+
+            let progress = match result {
+                Ok(progress) => progress,
+                Err(ChainError::NotRunning) => {
+                    eprintln!(
+                        "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                        instance
+                    );
+                    ExitCode::NodeUnreachable.exit();
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            };
+
+            if matches!(format, OutputFormat::Table) {
+                println!(
+                    "✅ Warped instance '{}' to slot {}",
+                    instance, progress.absolute_slot
+                );
+            }
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+            let inflation = rpc_client.get_inflation_rate().ok();
+            print_epoch(&progress, inflation, format)?;
+        }
+
+        Commands::Accounts {
+            command: Some(command),
+            ..
+        } => run_accounts(command)?,
+
+        Commands::Accounts {
+            command: None,
+            instance,
+            format,
+            amount,
+        } => {
+            let storage = get_storage_for_instance(&instance);
+
+            let mut accounts = storage.load()?;
+
+            if accounts.is_empty() {
+                println!(
+                    "No accounts found for instance '{}'. Run 'cf-solana start --instance {}' first.",
+                    instance, instance
+                );
+                return Ok(());
+            }
+
+            // Update balances from validator
+            let balances_updated = match get_rpc_client_for_instance(&instance) {
+                Ok(rpc_client) => {
+                    if rpc_client.is_validator_running() {
+                        match rpc_client.update_balances(&mut accounts) {
+                            Ok(_) => {
+                                // Save updated balances back to storage
+                                if let Err(e) = storage.save(&accounts) {
+                                    eprintln!("Warning: Could not save updated balances: {}", e);
+                                }
+                                true
+                            }
+                            Err(e) => {
+                                eprintln!(
+                                    "Warning: Could not update balances from validator: {}",
+                                    e
+                                );
+                                false
+                            }
+                        }
+                    } else {
+                        eprintln!("Warning: Validator is not running. Showing cached balances.");
+                        false
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Warning: Could not connect to instance: {}", e);
+                    eprintln!("Showing cached balances from startup.");
+                    false
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&accounts)?);
+                }
+                OutputFormat::Table => {
+                    let display_accounts: Vec<AccountDisplay> = accounts
+                        .iter()
+                        .enumerate()
+                        .map(|(i, acc)| AccountDisplay {
+                            index: account_index(i, acc.label.as_deref()),
+                            public_key: acc.public_key.clone(),
+                            balance: amount.format(acc.balance, SOL_DECIMALS),
+                        })
+                        .collect();
+
+                    let table = Table::new(display_accounts).to_string();
+                    println!("{}", table);
+
+                    if !balances_updated {
+                        println!();
+                        println!(
+                            "Note: Balances shown are from startup cache (validator not reachable)"
+                        );
+                    }
+                }
+            }
+        }
+
+        Commands::Scan {
+            instance,
+            mnemonic,
+            start_index,
+            gap_limit,
+            format,
+        } => scan(instance, mnemonic, start_index, gap_limit, format)?,
+
+        Commands::Fund {
+            address,
+            amount,
+            instance,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_validator_running() {
+                eprintln!(
+                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            println!("💰 Requesting airdrop of {} SOL to {}...", amount, address);
+
+            match rpc_client.request_airdrop(&address, amount) {
+                Ok(signature) => {
+                    println!("✅ Airdrop successful!");
+                    println!("   Signature: {}", signature);
+                    publish(
+                        &instance,
+                        ChainEvent::FundsDispensed {
+                            address: Some(address.clone()),
+                            amount,
+                        },
+                    );
+
+                    // Show updated balance
+                    if let Ok(balance) = rpc_client.get_balance(&address) {
+                        println!("   New balance: {} SOL", format_sol(balance));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", Message::AirdropFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+
+        Commands::Transfer {
+            from,
+            to,
+            amount,
+            instance,
+            signer_url,
+            signer_token,
+            priority_fee,
+            compute_unit_limit,
+            nonce_account,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_validator_running() {
+                eprintln!(
+                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            println!("💸 Transferring {} SOL", amount);
+            println!("   From: {}", from);
+            println!("   To:   {}", to);
+            if let Some(nonce_account) = &nonce_account {
+                println!("   Nonce account: {}", nonce_account);
+            }
+            println!();
+
+            let signer = signer_for(&instance, &from, signer_url, signer_token)?;
+
+            let priority_fee = if priority_fee.is_some() || compute_unit_limit.is_some() {
+                Some(PriorityFee {
+                    compute_unit_price: priority_fee.unwrap_or(0),
+                    compute_unit_limit,
+                })
+            } else {
+                None
+            };
+
+            match rpc_client.transfer_with_signer(
+                &to,
+                amount,
+                signer.as_ref(),
+                priority_fee,
+                nonce_account.as_deref(),
+            ) {
+                Ok(signature) => {
+                    println!("✅ Transaction sent!");
+                    println!("   Signature: {}", signature);
+
+                    // Show updated balances
+                    println!();
+                    println!("Updated balances:");
+                    if let Ok(from_balance) = rpc_client.get_balance(&from) {
+                        println!("   From: {} SOL", format_sol(from_balance));
+                    }
+                    if let Ok(to_balance) = rpc_client.get_balance(&to) {
+                        println!("   To:   {} SOL", format_sol(to_balance));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", Message::TransferFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+
+        Commands::Nonce { command } => run_nonce(command)?,
+
+        Commands::Alt { command } => run_alt(command)?,
+
+        Commands::Token { command } => run_token(command)?,
+
+        Commands::Pay { command } => run_pay(command).await?,
+
+        Commands::Fees {
+            account,
+            instance,
+            format,
+        } => {
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            if !rpc_client.is_validator_running() {
+                eprintln!(
+                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let fees = rpc_client.get_recent_prioritization_fees(&account)?;
+
+            match format {
+                OutputFormat::Json => {
+                    let json = serde_json::json!({
+                        "fees": fees
+                            .iter()
+                            .map(|f| serde_json::json!({
+                                "slot": f.slot,
+                                "prioritizationFee": f.prioritization_fee,
+                            }))
+                            .collect::<Vec<_>>(),
+                        "min": fee_percentile(&fees, 0),
+                        "median": fee_percentile(&fees, 50),
+                        "p75": fee_percentile(&fees, 75),
+                        "max": fee_percentile(&fees, 100),
+                    });
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                OutputFormat::Table => {
+                    if fees.is_empty() {
+                        println!("No prioritization fees recorded in recent slots.");
+                        return Ok(());
+                    }
+
+                    // Only list slots that actually paid a priority fee
+                    let display: Vec<FeeDisplay> = fees
+                        .iter()
+                        .filter(|f| f.prioritization_fee > 0)
+                        .map(|f| FeeDisplay {
+                            slot: f.slot,
+                            fee: f.prioritization_fee,
+                        })
+                        .collect();
+                    if !display.is_empty() {
+                        println!("{}", Table::new(display));
+                        println!();
+                    }
+
+                    println!("📊 Recent prioritization fees over {} slots:", fees.len());
+                    println!("   Min:    {}", fee_percentile(&fees, 0));
+                    println!("   Median: {}", fee_percentile(&fees, 50));
+                    println!("   P75:    {}", fee_percentile(&fees, 75));
+                    println!("   Max:    {}", fee_percentile(&fees, 100));
+                }
+            }
+        }
+
+        Commands::Config { instance } => {
+            println!("Chain Forge Solana Configuration");
+            println!("=================================");
+            println!();
+            println!("Instance: {}", instance);
+
+            // Try to load instance info
+            match SolanaInstanceInfo::load(&instance) {
+                Ok(info) => {
+                    println!(
+                        "  Status: {}",
+                        if info.running {
+                            "Running (may be stale)"
                         } else {
                             "Stopped"
                         }
@@ -296,56 +1282,1147 @@ async fn main() -> Result<()> {
                     if let Some(name) = &info.name {
                         println!("  Name: {}", name);
                     }
-                    println!("  RPC URL: {}", info.rpc_url);
-                    println!("  RPC Port: {}", info.rpc_port);
-                    println!("  Accounts: {}", info.accounts_count);
+                    println!("  RPC URL: {}", info.rpc_url);
+                    println!("  RPC Port: {}", info.rpc_port);
+                    if let Some(ports) = info.ports {
+                        println!("  Ports: {}", ports);
+                    }
+                    println!("  Accounts: {}", info.accounts_count);
+                }
+                Err(_) => {
+                    println!("  Status: Not initialized");
+                    println!(
+                        "  Run 'cf-solana start --instance {}' to create this instance",
+                        instance
+                    );
+                }
+            }
+
+            println!();
+            println!(
+                "Instance Directory: {:?}",
+                Config::data_dir()
+                    .join("solana")
+                    .join("instances")
+                    .join(&instance)
+            );
+
+            // Also show global config if available
+            let config = Config::load()?;
+            if let Some(solana_config) = config.solana {
+                println!();
+                println!("Global Config (chain-forge.toml):");
+                println!("  Default Profile:");
+                println!("    RPC URL: {}", solana_config.default.rpc_url);
+                println!("    Accounts: {}", solana_config.default.accounts);
+                println!(
+                    "    Initial Balance: {} SOL",
+                    solana_config.default.initial_balance
+                );
+                println!("    Port: {}", solana_config.default.port);
+            }
+        }
+
+        Commands::Stop { instance } => {
+            if let Some(network) = SolanaInstanceInfo::load(&instance)
+                .ok()
+                .and_then(|info| info.network)
+            {
+                public::detach(&instance)?;
+                println!("✅ Detached instance '{}' from {}", instance, network);
+                return Ok(());
+            }
+
+            let instance_dir = SolanaConfig::with_instance(&instance).instance_dir();
+            let stopped = match daemon::stop(&instance_dir, STOP_TIMEOUT) {
+                Ok(stopped) => stopped,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Failure.exit();
+                }
+            };
+
+            if let Some(pid) = stopped {
+                println!("✅ Stopped instance '{}' (pid {})", instance, pid);
+                // A node that had to be killed couldn't update the registry
+                let _ = NodeRegistry::new().update_status(
+                    &NodeRegistry::node_id(ChainType::Solana, &instance),
+                    NodeStatus::Stopped,
+                );
+            } else {
+                println!("Note: Use Ctrl+C to stop the validator running in 'start' mode");
+                println!(
+                    "      Instance '{}' should be stopped from its terminal",
+                    instance
+                );
+            }
+
+            // Mark instance as stopped if info exists
+            if let Ok(mut info) = SolanaInstanceInfo::load(&instance) {
+                info.running = false;
+                let _ = info.save();
+                if stopped.is_none() {
+                    println!("      Marked instance '{}' as stopped", instance);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Start a validator and keep it running until Ctrl+C
+async fn start(args: StartArgs) -> Result<()> {
+    let StartArgs {
+        instance,
+        name,
+        accounts,
+        balance,
+        port,
+        mnemonic,
+        start_index,
+        keep_data,
+        interactive,
+        detach,
+        snapshot,
+        warp_slot,
+        warp_epoch,
+        slots_per_epoch,
+        inflation,
+        external_signer,
+        clone_accounts,
+        clone_programs,
+        clone_url,
+        preset,
+        profile,
+        insecure_plaintext,
+    } = args;
+
+    // Presets, then the profile, fill in anything not given explicitly
+    let preset = match Preset::resolve(preset.as_deref()) {
+        Ok(preset) => preset,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::Validation.exit();
+        }
+    };
+    let profile = match profile.map(|name| Config::load().and_then(|c| c.solana_profile(&name))) {
+        Some(Ok(profile)) => Some(profile),
+        Some(Err(e)) => {
+            eprintln!("❌ {}", e);
+            ExitCode::Validation.exit();
+        }
+        None => None,
+    };
+    let accounts = accounts
+        .or(preset.map(|p| p.accounts))
+        .or(profile.as_ref().map(|p| p.accounts))
+        .unwrap_or(10);
+    let balance = balance
+        .or(preset.map(|p| p.solana_balance))
+        .or(profile.as_ref().map(|p| p.initial_balance))
+        .unwrap_or(100.0);
+    let port = port.or(profile.as_ref().map(|p| PortChoice::Port(p.port)));
+    // Without --profile, `[solana.default]` still decides what stop does
+    // with the instance data
+    let cleanup = match &profile {
+        Some(profile) => profile.cleanup(),
+        None => Config::load()
+            .and_then(|c| c.solana_profile("default"))
+            .map(|p| p.cleanup())
+            .unwrap_or_default(),
+    };
+
+    // Account secrets are encrypted with CHAIN_FORGE_PASSPHRASE when set;
+    // `encrypt_accounts` makes that mandatory
+    if let Err(e) = Config::load().and_then(|c| c.check_accounts_protection(insecure_plaintext)) {
+        eprintln!("❌ {}", e);
+        ExitCode::Validation.exit();
+    }
+
+    // A port not given explicitly follows the configured port strategy
+    let port = match port {
+        Some(PortChoice::Port(port)) => port,
+        // Free ports are scanned for and reserved so concurrent starts don't collide
+        Some(PortChoice::Auto) => match auto_port(&instance) {
+            Ok(port) => port,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+        },
+        None => match Config::load()
+            .and_then(|c| c.instance_port(ChainType::Solana, &instance, 8899, PORT_SPAN))
+        {
+            Ok(port) => port,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+        },
+    };
+
+    let (instance, accounts, balance, port, mnemonic) = if interactive {
+        let options = wizard::run(wizard::StartOptions {
+            instance,
+            port,
+            accounts,
+            balance,
+            mnemonic,
+        })?;
+
+        println!();
+        println!("💡 Equivalent command:");
+        println!("   {}", options.to_command());
+        println!();
+
+        (
+            options.instance,
+            options.accounts,
+            options.balance,
+            options.port,
+            options.mnemonic,
+        )
+    } else {
+        (instance, accounts, balance, port, mnemonic)
+    };
+
+    // Validate instance name
+    if let Err(e) = validate_name(&instance) {
+        eprintln!("❌ {}", Message::InvalidInstanceName(e.to_string()));
+        ExitCode::Validation.exit();
+    }
+
+    // Validate display name if provided
+    if let Some(ref n) = name {
+        if let Err(e) = validate_name(n) {
+            eprintln!("❌ {}", Message::InvalidDisplayName(e.to_string()));
+            ExitCode::Validation.exit();
+        }
+    }
+
+    if let Some(slots) = slots_per_epoch {
+        if slots < MINIMUM_SLOTS_PER_EPOCH {
+            eprintln!(
+                "❌ Slots per epoch must be at least {}",
+                MINIMUM_SLOTS_PER_EPOCH
+            );
+            ExitCode::Validation.exit();
+        }
+    }
+
+    if let Some(rate) = inflation {
+        if !(0.0..=1.0).contains(&rate) {
+            eprintln!("❌ Inflation must be a rate between 0 and 1 (e.g. 0.08)");
+            ExitCode::Validation.exit();
+        }
+    }
+
+    if detach {
+        let instance_dir = SolanaConfig::with_instance(&instance).instance_dir();
+        let node_id = NodeRegistry::node_id(ChainType::Solana, &instance);
+        println!("⏳ Starting instance '{}' in the background...", instance);
+        match daemon::detach(&instance_dir, &node_id, DETACH_TIMEOUT) {
+            Ok(pid) => {
+                println!("✅ Validator running in the background (pid {})", pid);
+                println!("   Logs: {}", daemon::log_path(&instance_dir).display());
+                println!("   Run 'cf-solana stop --instance {}' to stop it", instance);
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Failure.exit();
+            }
+        }
+        return Ok(());
+    }
+
+    // Create instance-specific config
+    let mut config = SolanaConfig::with_instance(&instance);
+    config.rpc_url = format!("http://localhost:{}", port);
+    config.port = port;
+    config.accounts = accounts;
+    config.initial_balance = balance;
+    config.mnemonic = mnemonic;
+    config.start_index = start_index;
+    config.name = name;
+    config.snapshot = snapshot;
+    config.slots_per_epoch = slots_per_epoch;
+    config.inflation_fixed = inflation;
+    config.warp_slot = warp_epoch
+        .map(|epoch| config.epoch_start_slot(epoch))
+        .or(warp_slot);
+    config.external_signers = external_signer;
+    config.clone_accounts = clone_accounts;
+    config.clone_programs = clone_programs;
+    config.clone_url = clone_url;
+    config.account_labels = preset.map(|p| p.labels()).unwrap_or_default();
+
+    let instance_dir = config.instance_dir();
+    let ports = config.ports();
+    logging::log_to_instance(&instance_dir);
+    let mut provider = SolanaProvider::with_config(config.clone());
+    provider.set_cleanup(cleanup);
+    if keep_data {
+        provider.set_keep_data(true);
+    }
+    provider.start(config)?;
+
+    println!("🔌 Ports: {}", ports);
+
+    println!("💡 Tip: Keep this terminal open to keep the validator running");
+    println!(
+        "   Run 'cf-solana accounts --instance {}' in another terminal to see your accounts",
+        instance
+    );
+    println!();
+
+    // Keep the process alive
+    tokio::signal::ctrl_c().await?;
+    println!();
+    provider.stop()?;
+    daemon::clear_own_pid(&instance_dir);
+
+    Ok(())
+}
+
+/// Derive accounts for a public cluster and record them as an instance
+fn attach(
+    network: String,
+    rpc_url: Option<String>,
+    instance: String,
+    name: Option<String>,
+    accounts: u32,
+    mnemonic: Option<String>,
+) -> Result<()> {
+    let network = match network.as_str() {
+        "testnet" => PublicNetwork::Testnet,
+        _ => PublicNetwork::Devnet,
+    };
+
+    if let Err(e) = validate_name(&instance) {
+        eprintln!("❌ {}", Message::InvalidInstanceName(e.to_string()));
+        ExitCode::Validation.exit();
+    }
+    if let Some(ref n) = name {
+        if let Err(e) = validate_name(n) {
+            eprintln!("❌ {}", Message::InvalidDisplayName(e.to_string()));
+            ExitCode::Validation.exit();
+        }
+    }
+
+    let mut config = SolanaConfig::with_instance(&instance);
+    config.rpc_url = rpc_url.unwrap_or_else(|| network.default_rpc_url().to_string());
+    config.accounts = accounts;
+    config.mnemonic = mnemonic;
+    config.name = name;
+
+    println!("🌐 Attaching instance '{}' to {}...", instance, network);
+    println!("   RPC URL: {}", config.rpc_url);
+    println!();
+
+    let accounts = public::attach(&config, network)?;
+
+    let display_accounts: Vec<AccountDisplay> = accounts
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| AccountDisplay {
+            index: account_index(i, acc.label.as_deref()),
+            public_key: acc.public_key.clone(),
+            balance: format_sol(acc.balance),
+        })
+        .collect();
+    println!("{}", Table::new(display_accounts));
+    println!();
+    println!(
+        "✅ Instance '{}' tracks {} accounts on {}",
+        instance,
+        accounts.len(),
+        network
+    );
+    println!(
+        "   Run 'cf-solana fund <ADDRESS> <AMOUNT> --instance {}' to request a {} airdrop",
+        instance, network
+    );
+    println!("   Run 'cf-solana stop --instance {}' to detach", instance);
+
+    Ok(())
+}
+
+/// Check derived accounts for funds until `gap_limit` in a row are empty
+fn scan(
+    instance: String,
+    mnemonic: Option<String>,
+    start_index: u32,
+    gap_limit: u32,
+    format: OutputFormat,
+) -> Result<()> {
+    let rpc_client = get_rpc_client_for_instance(&instance)?;
+    if !rpc_client.is_validator_running() {
+        eprintln!(
+            "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+            instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    // Accounts the instance already has are marked in the output
+    let known = get_storage_for_instance(&instance)
+        .load()
+        .unwrap_or_default();
+    let mnemonic = mnemonic.or_else(|| known.iter().find_map(|acc| acc.mnemonic.clone()));
+    let Some(mnemonic) = mnemonic else {
+        eprintln!(
+            "❌ Instance '{}' has no mnemonic to scan. Pass one with --mnemonic",
+            instance
+        );
+        ExitCode::Validation.exit();
+    };
+    let generator = AccountGenerator::from_mnemonic(&mnemonic)?;
+
+    let funded = gap_scan(
+        start_index,
+        gap_limit,
+        |index| generator.derive_account(index),
+        |batch: &[SolanaAccount]| {
+            batch
+                .iter()
+                .map(|acc| rpc_client.get_balance(&acc.public_key))
+                .collect()
+        },
+    )?;
+    let tracked = |acc: &SolanaAccount| known.iter().any(|k| k.public_key == acc.public_key);
+
+    match format {
+        OutputFormat::Json => {
+            let json: Vec<_> = funded
+                .iter()
+                .map(|found| {
+                    serde_json::json!({
+                        "index": found.index,
+                        "derivationPath": found.account.derivation_path,
+                        "publicKey": found.account.public_key,
+                        "balance": found.balance,
+                        "tracked": tracked(&found.account),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Table => {
+            if funded.is_empty() {
+                println!(
+                    "No funded accounts found from index {} (gap limit {})",
+                    start_index, gap_limit
+                );
+                return Ok(());
+            }
+            let display: Vec<ScanDisplay> = funded
+                .iter()
+                .map(|found| ScanDisplay {
+                    index: found.index,
+                    path: found.account.derivation_path.clone().unwrap_or_default(),
+                    public_key: found.account.public_key.clone(),
+                    balance: format_sol(found.balance),
+                    tracked: if tracked(&found.account) { "yes" } else { "no" }.to_string(),
+                })
+                .collect();
+            println!("{}", Table::new(display));
+            println!();
+            println!("✅ Found {} funded accounts", funded.len());
+            if let Some(last) = funded.iter().rev().find(|f| !tracked(&f.account)) {
+                println!(
+                    "   Restart with '--mnemonic <PHRASE> --accounts {}' to track them all",
+                    last.index + 1
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_accounts(command: AccountsCommands) -> Result<()> {
+    match command {
+        AccountsCommands::Export {
+            format,
+            output,
+            instance,
+        } => {
+            let accounts = get_storage_for_instance(&instance).load()?;
+            if accounts.is_empty() {
+                eprintln!(
+                    "❌ No accounts found for instance '{}'. Run 'cf-solana start --instance {}' first.",
+                    instance, instance
+                );
+                ExitCode::Validation.exit();
+            }
+
+            // Keys held by an external signer aren't in the accounts file
+            for account in accounts.iter().filter(|acc| acc.external_signer) {
+                eprintln!(
+                    "⚠️  Skipping {}: its key is held by an external signer",
+                    account.public_key
+                );
+            }
+            let accounts = accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, acc)| !acc.external_signer);
+
+            match format {
+                KeyExportFormat::KeypairJson => {
+                    let mut written = 0;
+                    for (i, account) in accounts {
+                        let file = output.join(format!("account-{}.json", i));
+                        let keypair = serde_json::to_string(&account.secret_key)?;
+                        write_secret_file(&file, keypair.as_bytes())
+                            .wrap_err_with(|| format!("Failed to write {}", file.display()))?;
+                        println!("🔑 {} -> {}", account.public_key, file.display());
+                        written += 1;
+                    }
+                    println!();
+                    println!("✅ Wrote {} keypair files to {}", written, output.display());
+                    println!("   Use them with e.g. 'solana balance --keypair <FILE>'");
                 }
-                Err(_) => {
-                    println!("  Status: Not initialized");
+                KeyExportFormat::Seed => {
+                    let Some(mnemonic) = accounts.clone().find_map(|(_, acc)| acc.mnemonic.clone())
+                    else {
+                        eprintln!(
+                            "❌ Instance '{}' has no recovery phrase; export its keys with --format keypair-json",
+                            instance
+                        );
+                        ExitCode::Validation.exit();
+                    };
+
+                    println!("🔑 Recovery phrase: {}", mnemonic);
+                    println!();
+                    let display: Vec<DerivedAccountDisplay> = accounts
+                        .filter(|(_, acc)| acc.mnemonic.as_ref() == Some(&mnemonic))
+                        .map(|(i, acc)| DerivedAccountDisplay {
+                            index: account_index(i, acc.label.as_deref()),
+                            path: acc.derivation_path.clone().unwrap_or_default(),
+                            public_key: acc.public_key.clone(),
+                        })
+                        .collect();
+                    println!("{}", Table::new(display));
+                    println!();
                     println!(
-                        "  Run 'cf-solana start --instance {}' to create this instance",
-                        instance
+                        "💡 Wallets such as Phantom derive the same accounts from this phrase"
                     );
                 }
             }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_snapshot(command: SnapshotCommands) -> Result<()> {
+    match command {
+        SnapshotCommands::Create {
+            instance,
+            at_slot,
+            name,
+            timeout,
+        } => {
+            if let Some(Err(e)) = name.as_deref().map(validate_name) {
+                eprintln!("❌ {}", Message::InvalidSnapshotName(e.to_string()));
+                ExitCode::Validation.exit();
+            }
+
+            let info = SolanaInstanceInfo::load(&instance)?;
+            let pid = info.pid.ok_or_else(|| {
+                eyre!(
+                    "Validator process ID is unknown for instance '{}'. Restart it with 'cf-solana start --instance {}'",
+                    instance,
+                    instance
+                )
+            })?;
+
+            let rpc_client = SolanaRpcClient::new(info.rpc_url.clone());
+            if !rpc_client.is_validator_running() {
+                eprintln!(
+                    "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+                    instance
+                );
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let slot = match at_slot {
+                Some(at_slot) => {
+                    println!("⏳ Waiting for slot {}...", at_slot);
+                    rpc_client
+                        .wait_for_slot(at_slot, Duration::from_secs(timeout))
+                        .await?
+                }
+                None => rpc_client.get_slot()?,
+            };
+            let name = name.unwrap_or_else(|| format!("{}-slot-{}", instance, slot));
+
+            // Pause the validator so the ledger is not written while archiving
+            pause_process(pid)?;
+            let spinner = progress::spinner(format!(
+                "Archiving the ledger at slot {} (validator paused)",
+                slot
+            ));
+
+            let instance_dir = SolanaConfig::with_instance(&instance).instance_dir();
+            let result = SolanaSnapshot::create(&name, &instance, slot, &instance_dir);
+
+            spinner.finish_and_clear();
+            resume_process(pid)?;
 
+            let snapshot = result?;
+            println!("✅ Snapshot '{}' saved", snapshot.name);
+            println!("   Archive: {}", snapshot.archive_path().display());
             println!();
+            println!("💡 Start an instance from it with:");
             println!(
-                "Instance Directory: {:?}",
-                Config::data_dir()
-                    .join("solana")
-                    .join("instances")
-                    .join(&instance)
+                "   cf-solana snapshot restore {} --instance <instance> --port <port>",
+                snapshot.name
             );
+        }
 
-            // Also show global config if available
-            let config = Config::load()?;
-            if let Some(solana_config) = config.solana {
-                println!();
-                println!("Global Config (chain-forge.toml):");
-                println!("  Default Profile:");
-                println!("    RPC URL: {}", solana_config.default.rpc_url);
-                println!("    Accounts: {}", solana_config.default.accounts);
-                println!(
-                    "    Initial Balance: {} SOL",
-                    solana_config.default.initial_balance
-                );
-                println!("    Port: {}", solana_config.default.port);
+        SnapshotCommands::Restore { from, start: args } => {
+            if let Err(e) = SolanaSnapshot::load(&from) {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
             }
+            start(StartArgs {
+                snapshot: Some(from),
+                ..args
+            })
+            .await?;
         }
 
-        Commands::Stop { instance } => {
-            println!("Note: Use Ctrl+C to stop the validator running in 'start' mode");
+        SnapshotCommands::List { format } => {
+            let snapshots = SolanaSnapshot::list()?;
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&snapshots)?);
+                }
+                OutputFormat::Table => {
+                    if snapshots.is_empty() {
+                        println!("No snapshots found. Create one with 'cf-solana snapshot create'");
+                        return Ok(());
+                    }
+                    let display: Vec<SnapshotDisplay> = snapshots
+                        .into_iter()
+                        .map(|s| SnapshotDisplay {
+                            name: s.name,
+                            source_instance: s.source_instance,
+                            slot: s.slot,
+                            created_at: s.created_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                        })
+                        .collect();
+                    println!("{}", Table::new(display));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_nonce(command: NonceCommands) -> Result<()> {
+    let instance = match &command {
+        NonceCommands::Create { instance, .. }
+        | NonceCommands::Show { instance, .. }
+        | NonceCommands::Advance { instance, .. }
+        | NonceCommands::Withdraw { instance, .. } => instance.clone(),
+    };
+    let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+    if !rpc_client.is_validator_running() {
+        eprintln!(
+            "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+            instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    let result = match command {
+        NonceCommands::Create { authority, .. } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            rpc_client
+                .create_nonce_account(signer.as_ref())
+                .map(|nonce_account| {
+                    println!("✅ Nonce account created: {}", nonce_account);
+                    println!("   Authority: {}", authority);
+                })
+        }
+        NonceCommands::Show { nonce_account, .. } => {
+            rpc_client.get_nonce(&nonce_account).map(|nonce| {
+                println!("🔢 Nonce account: {}", nonce_account);
+                println!("   Nonce: {}", nonce);
+            })
+        }
+        NonceCommands::Advance {
+            nonce_account,
+            authority,
+            ..
+        } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            rpc_client
+                .advance_nonce(&nonce_account, signer.as_ref())
+                .and_then(|signature| {
+                    println!("✅ Nonce advanced!");
+                    println!("   Signature: {}", signature);
+                    println!("   New nonce: {}", rpc_client.get_nonce(&nonce_account)?);
+                    Ok(())
+                })
+        }
+        NonceCommands::Withdraw {
+            nonce_account,
+            authority,
+            to,
+            amount,
+            ..
+        } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            rpc_client
+                .withdraw_nonce(&nonce_account, signer.as_ref(), &to, amount)
+                .map(|signature| {
+                    println!("✅ Withdrew {} SOL from {}", amount, nonce_account);
+                    println!("   Signature: {}", signature);
+                })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("❌ Nonce operation failed: {}", e);
+        ExitCode::from_chain_error(&e).exit();
+    }
+
+    Ok(())
+}
+
+fn run_alt(command: AltCommands) -> Result<()> {
+    let instance = match &command {
+        AltCommands::Create { instance, .. }
+        | AltCommands::Extend { instance, .. }
+        | AltCommands::Show { instance, .. }
+        | AltCommands::Deactivate { instance, .. }
+        | AltCommands::Close { instance, .. } => instance.clone(),
+    };
+    let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+    if !rpc_client.is_validator_running() {
+        eprintln!(
+            "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+            instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    let result = match command {
+        AltCommands::Create { authority, .. } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            rpc_client
+                .create_lookup_table(signer.as_ref())
+                .map(|table| {
+                    println!("✅ Lookup table created: {}", table);
+                    println!("   Authority: {}", authority);
+                })
+        }
+        AltCommands::Extend {
+            table,
+            authority,
+            mut addresses,
+            all_accounts,
+            ..
+        } => {
+            if all_accounts {
+                for account in get_storage_for_instance(&instance).load()? {
+                    if !addresses.contains(&account.public_key) {
+                        addresses.push(account.public_key);
+                    }
+                }
+            }
+            if addresses.is_empty() {
+                eprintln!("❌ Error: No addresses given. Pass addresses or --all-accounts");
+                ExitCode::Validation.exit();
+            }
+
+            let signer = signer_for(&instance, &authority, None, None)?;
+            println!("📇 Adding {} address(es) to {}...", addresses.len(), table);
+            rpc_client
+                .extend_lookup_table(&table, signer.as_ref(), &addresses)
+                .map(|signatures| {
+                    println!("✅ Lookup table extended!");
+                    for signature in signatures {
+                        println!("   Signature: {}", signature);
+                    }
+                })
+        }
+        AltCommands::Show { table, format, .. } => {
+            rpc_client.get_lookup_table(&table).and_then(|info| {
+                match format {
+                    OutputFormat::Json => {
+                        let json = serde_json::json!({
+                            "address": info.address,
+                            "authority": info.authority,
+                            "deactivationSlot": info.deactivation_slot,
+                            "lastExtendedSlot": info.last_extended_slot,
+                            "addresses": info.addresses,
+                        });
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    }
+                    OutputFormat::Table => {
+                        println!("📇 Lookup table: {}", info.address);
+                        println!(
+                            "   Authority: {}",
+                            info.authority.as_deref().unwrap_or("none (frozen)")
+                        );
+                        match info.deactivation_slot {
+                            Some(slot) => println!("   Status: deactivated in slot {}", slot),
+                            None => println!("   Status: active"),
+                        }
+                        println!("   Last extended in slot: {}", info.last_extended_slot);
+                        println!("   Addresses: {}", info.addresses.len());
+                        if !info.addresses.is_empty() {
+                            let display: Vec<LookupAddressDisplay> = info
+                                .addresses
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, address)| LookupAddressDisplay { index, address })
+                                .collect();
+                            println!();
+                            println!("{}", Table::new(display));
+                        }
+                    }
+                }
+                Ok(())
+            })
+        }
+        AltCommands::Deactivate {
+            table, authority, ..
+        } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            rpc_client
+                .deactivate_lookup_table(&table, signer.as_ref())
+                .map(|signature| {
+                    println!("✅ Lookup table deactivated!");
+                    println!("   Signature: {}", signature);
+                    println!("   It can be closed once the deactivation slot is ~513 slots old");
+                })
+        }
+        AltCommands::Close {
+            table,
+            authority,
+            recipient,
+            ..
+        } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            let recipient = recipient.unwrap_or_else(|| authority.clone());
+            rpc_client
+                .close_lookup_table(&table, signer.as_ref(), &recipient)
+                .map(|signature| {
+                    println!("✅ Lookup table closed, rent sent to {}", recipient);
+                    println!("   Signature: {}", signature);
+                })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("❌ Lookup table operation failed: {}", e);
+        ExitCode::from_chain_error(&e).exit();
+    }
+
+    Ok(())
+}
+
+fn run_token(command: TokenCommands) -> Result<()> {
+    let instance = match &command {
+        TokenCommands::Create { instance, .. }
+        | TokenCommands::Mint { instance, .. }
+        | TokenCommands::Transfer { instance, .. }
+        | TokenCommands::Balances { instance, .. } => instance.clone(),
+    };
+    let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+    if !rpc_client.is_validator_running() {
+        eprintln!(
+            "❌ Error: Validator is not running. Start it with 'cf-solana start --instance {}'",
+            instance
+        );
+        ExitCode::NodeUnreachable.exit();
+    }
+
+    let result = match command {
+        TokenCommands::Create {
+            authority,
+            decimals,
+            all_accounts,
+            format,
+            ..
+        } => {
+            let signer = signer_for(&instance, &authority, None, None)?;
+            let table = matches!(format, OutputFormat::Table);
+            rpc_client
+                .create_mint(signer.as_ref(), decimals)
+                .and_then(|mint| {
+                    if table {
+                        println!("✅ Mint created: {}", mint);
+                        println!("   Authority: {}", authority);
+                        println!("   Decimals: {}", decimals);
+                    }
+
+                    if all_accounts {
+                        let owners: Vec<String> = get_storage_for_instance(&instance)
+                            .load()?
+                            .into_iter()
+                            .map(|account| account.public_key)
+                            .collect();
+                        if table {
+                            println!("🪙 Creating {} token account(s)...", owners.len());
+                        }
+                        rpc_client.create_token_accounts(&mint, signer.as_ref(), &owners)?;
+                        if table {
+                            println!("✅ Token accounts created!");
+                        }
+                    }
+
+                    if !table {
+                        let json = serde_json::json!({
+                            "mint": mint,
+                            "authority": authority,
+                            "decimals": decimals,
+                        });
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    }
+                    Ok(())
+                })
+        }
+        TokenCommands::Mint {
+            mint,
+            authority,
+            amount,
+            mut to,
+            all_accounts,
+            ..
+        } => {
+            if all_accounts {
+                for account in get_storage_for_instance(&instance).load()? {
+                    if !to.contains(&account.public_key) {
+                        to.push(account.public_key);
+                    }
+                }
+            }
+            if to.is_empty() {
+                eprintln!("❌ Error: No recipients given. Pass addresses or --all-accounts");
+                ExitCode::Validation.exit();
+            }
+
+            let signer = signer_for(&instance, &authority, None, None)?;
             println!(
-                "      Instance '{}' should be stopped from its terminal",
-                instance
+                "🪙 Minting {} token(s) to {} account(s)...",
+                amount,
+                to.len()
+            );
+            to.iter().try_for_each(|owner| {
+                rpc_client
+                    .mint_tokens(&mint, signer.as_ref(), owner, amount)
+                    .map(|signature| {
+                        println!("   Minted to {} (signature: {})", owner, signature);
+                    })
+            })
+        }
+        TokenCommands::Transfer {
+            mint,
+            from,
+            to,
+            amount,
+            ..
+        } => {
+            let signer = signer_for(&instance, &from, None, None)?;
+            rpc_client
+                .transfer_tokens(&mint, signer.as_ref(), &to, amount)
+                .map(|signature| {
+                    println!("✅ Transferred {} token(s) to {}", amount, to);
+                    println!("   Signature: {}", signature);
+                })
+        }
+        TokenCommands::Balances { mint, format, .. } => {
+            let accounts = get_storage_for_instance(&instance).load()?;
+            accounts
+                .iter()
+                .map(|account| rpc_client.get_token_balance(&mint, &account.public_key))
+                .collect::<chain_forge_common::Result<Vec<f64>>>()
+                .and_then(|balances| {
+                    match format {
+                        OutputFormat::Json => {
+                            let json: Vec<_> = accounts
+                                .iter()
+                                .zip(&balances)
+                                .map(|(account, balance)| {
+                                    serde_json::json!({
+                                        "publicKey": account.public_key,
+                                        "balance": balance,
+                                    })
+                                })
+                                .collect();
+                            println!("{}", serde_json::to_string_pretty(&json)?);
+                        }
+                        OutputFormat::Table => {
+                            println!("🪙 Token: {}", mint);
+                            let display: Vec<TokenBalanceDisplay> = accounts
+                                .iter()
+                                .zip(balances)
+                                .enumerate()
+                                .map(|(i, (account, balance))| TokenBalanceDisplay {
+                                    index: account_index(i, account.label.as_deref()),
+                                    public_key: account.public_key.clone(),
+                                    balance,
+                                })
+                                .collect();
+                            println!("{}", Table::new(display));
+                        }
+                    }
+                    Ok(())
+                })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("❌ Token operation failed: {}", e);
+        ExitCode::from_chain_error(&e).exit();
+    }
+
+    Ok(())
+}
+
+async fn run_pay(command: PayCommands) -> Result<()> {
+    match command {
+        PayCommands::Request {
+            recipient,
+            amount,
+            label,
+            message,
+            memo,
+            format,
+        } => {
+            if !amount.is_finite() || amount <= 0.0 {
+                eprintln!("❌ Error: Amount must be positive");
+                ExitCode::Validation.exit();
+            }
+            let mut request = pay::TransferRequest::new(&recipient, amount);
+            request.label = label;
+            request.message = message;
+            request.memo = memo;
+            let url = request.url();
+            if let Err(e) = pay::TransferRequest::parse(&url) {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+
+            match format {
+                OutputFormat::Json => {
+                    let json = serde_json::json!({
+                        "url": url,
+                        "recipient": request.recipient,
+                        "amount": request.amount,
+                        "reference": request.reference(),
+                    });
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                OutputFormat::Table => {
+                    println!("🧾 Transfer request for {} SOL", amount);
+                    println!("   URL:       {}", url);
+                    println!("   Reference: {}", request.reference().unwrap_or_default());
+                    println!();
+                    println!("💡 Check for payment with: cf-solana pay find '{}'", url);
+                }
+            }
+        }
+
+        PayCommands::Send {
+            url,
+            from,
+            instance,
+        } => {
+            let request = match pay::TransferRequest::parse(&url) {
+                Ok(request) => request,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+            let signer = signer_for(&instance, &from, None, None)?;
+
+            println!(
+                "💸 Paying {} SOL to {}...",
+                request.amount, request.recipient
             );
+            match rpc_client.pay(&request, signer.as_ref()) {
+                Ok(signature) => {
+                    println!("✅ Paid!");
+                    println!("   Signature: {}", signature);
+                }
+                Err(e) => {
+                    eprintln!("❌ {}", Message::PaymentFailed(e.to_string()));
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
 
-            // Mark instance as stopped if info exists
-            if let Ok(mut info) = SolanaInstanceInfo::load(&instance) {
-                info.running = false;
-                let _ = info.save();
-                println!("      Marked instance '{}' as stopped", instance);
+        PayCommands::Find {
+            url,
+            timeout,
+            instance,
+            format,
+        } => {
+            let request = match pay::TransferRequest::parse(&url) {
+                Ok(request) => request,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+            let Some(reference) = request.reference() else {
+                eprintln!("❌ Error: The request has no reference to look the payment up by");
+                ExitCode::Validation.exit();
+            };
+            let rpc_client = get_rpc_client_for_instance(&instance)?;
+
+            let deadline = std::time::Instant::now() + Duration::from_secs(timeout);
+            let found = loop {
+                match rpc_client.find_reference(reference)? {
+                    Some(found) => break found,
+                    None if std::time::Instant::now() >= deadline => {
+                        eprintln!("❌ No payment references {}", reference);
+                        ExitCode::Timeout.exit();
+                    }
+                    None => tokio::time::sleep(Duration::from_secs(1)).await,
+                }
+            };
+            let validation = rpc_client.validate_transfer(&found.signature, &request);
+
+            match format {
+                OutputFormat::Json => {
+                    let json = serde_json::json!({
+                        "reference": reference,
+                        "signature": found.signature,
+                        "slot": found.slot,
+                        "blockTime": found.block_time,
+                        "valid": validation.is_ok(),
+                        "error": validation.as_ref().err().map(|e| e.to_string()),
+                    });
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                OutputFormat::Table => {
+                    println!("🔎 Payment found for {}", reference);
+                    println!("   Signature: {}", found.signature);
+                    println!("   Slot:      {}", found.slot);
+                }
+            }
+            if let Err(e) = validation {
+                eprintln!("❌ {}", e);
+                ExitCode::from_chain_error(&e).exit();
+            }
+            if matches!(format, OutputFormat::Table) {
+                println!("✅ Payment is valid");
             }
         }
     }
@@ -0,0 +1,156 @@
+//! Interactive wizard for `cf-solana start --interactive`
+
+use chain_forge_common::validate_name;
+use chain_forge_solana_accounts::AccountGenerator;
+use chain_forge_solana_core::{SolanaConfig, PORT_SPAN};
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use eyre::Result;
+use std::net::TcpListener;
+
+/// Options collected by the wizard, mirroring the `start` flags
+pub struct StartOptions {
+    pub instance: String,
+    pub port: u16,
+    pub accounts: u32,
+    pub balance: f64,
+    pub mnemonic: Option<String>,
+}
+
+impl StartOptions {
+    /// Build the equivalent non-interactive command line
+    pub fn to_command(&self) -> String {
+        let mut command = format!(
+            "cf-solana start --instance {} --port {} --accounts {} --balance {}",
+            self.instance, self.port, self.accounts, self.balance
+        );
+        if let Some(mnemonic) = &self.mnemonic {
+            command.push_str(&format!(" --mnemonic \"{}\"", mnemonic));
+        }
+        command
+    }
+}
+
+/// Prompt for start options, using the given values as defaults
+pub fn run(defaults: StartOptions) -> Result<StartOptions> {
+    let theme = ColorfulTheme::default();
+
+    let instance: String = Input::with_theme(&theme)
+        .with_prompt("Instance name")
+        .default(defaults.instance)
+        .validate_with(|input: &String| validate_name(input).map_err(|e| e.to_string()))
+        .interact_text()?;
+
+    let port: u16 = Input::with_theme(&theme)
+        .with_prompt("RPC port")
+        .default(defaults.port)
+        .validate_with(|port: &u16| check_ports(*port))
+        .interact_text()?;
+
+    let accounts: u32 = Input::with_theme(&theme)
+        .with_prompt("Number of accounts")
+        .default(defaults.accounts)
+        .validate_with(|count: &u32| {
+            if *count == 0 {
+                Err("must generate at least one account")
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+
+    let balance: f64 = Input::with_theme(&theme)
+        .with_prompt("Initial balance per account (SOL)")
+        .default(defaults.balance)
+        .validate_with(|balance: &f64| {
+            if *balance < 0.0 {
+                Err("balance cannot be negative")
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+
+    let choice = Select::with_theme(&theme)
+        .with_prompt("Mnemonic")
+        .items(&["Generate a new mnemonic", "Use an existing mnemonic"])
+        .default(if defaults.mnemonic.is_some() { 1 } else { 0 })
+        .interact()?;
+
+    let mnemonic = if choice == 1 {
+        let mut input = Input::<String>::with_theme(&theme).with_prompt("Mnemonic phrase");
+        if let Some(existing) = defaults.mnemonic {
+            input = input.default(existing);
+        }
+        let phrase = input
+            .validate_with(|phrase: &String| {
+                AccountGenerator::from_mnemonic(phrase.trim())
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            })
+            .interact_text()?;
+        Some(phrase.trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(StartOptions {
+        instance,
+        port,
+        accounts,
+        balance,
+        mnemonic,
+    })
+}
+
+/// Highest RPC port whose derived ports all fit in a `u16`
+const MAX_PORT: u16 = u16::MAX - (PORT_SPAN - 1);
+
+/// Check that the RPC port and the ports derived from it are free
+fn check_ports(port: u16) -> std::result::Result<(), String> {
+    if port > MAX_PORT {
+        return Err(format!(
+            "port must be at most {} (faucet, gossip, and validator ports are derived from it)",
+            MAX_PORT
+        ));
+    }
+
+    let config = SolanaConfig {
+        port,
+        ..SolanaConfig::default()
+    };
+
+    for (label, port) in [
+        ("RPC", config.port),
+        ("Faucet", config.faucet_port()),
+        ("Gossip", config.gossip_port()),
+    ] {
+        if TcpListener::bind(("0.0.0.0", port)).is_err() {
+            return Err(format!("{} port {} is already in use", label, port));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_ports_bound() {
+        assert_eq!(MAX_PORT, 64031);
+        let too_high = check_ports(64032).unwrap_err();
+        assert!(too_high.contains("at most 64031"), "{}", too_high);
+
+        // The highest allowed port builds its config; it may only fail
+        // because something else is listening
+        SolanaConfig {
+            port: MAX_PORT,
+            ..SolanaConfig::default()
+        }
+        .ports();
+        if let Err(e) = check_ports(MAX_PORT) {
+            assert!(e.contains("already in use"), "{}", e);
+        }
+    }
+}
@@ -1,13 +1,34 @@
+use chain_forge_common::schema::{self, Schema};
 use chain_forge_common::{
-    ChainError, ChainProvider, ChainType, NodeInfo, NodeRegistry, NodeStatus, Result,
+    ChainError, ChainProvider, ChainType, Heartbeat, HookContext, LifecycleHooks, LifecyclePhase,
+    NodeInfo, NodeRegistry, NodeStatus, Result, SignerKeystore, StartupPhase, StartupProfile,
+    StartupTimer, StorageBackend, HEARTBEAT_INTERVAL,
 };
-use chain_forge_config::{Config, SolanaProfile};
+use chain_forge_config::{CleanupPolicy, Config, SolanaProfile};
+use chain_forge_events::ChainEvent;
 use chain_forge_solana_accounts::{AccountGenerator, AccountsStorage, SolanaAccount};
 use chain_forge_solana_rpc::SolanaRpcClient;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command};
 use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+pub mod public;
+pub mod reset;
+pub mod snapshot;
+pub mod warp;
+
+pub use public::PublicNetwork;
+pub use reset::reset_instance;
+pub use snapshot::SolanaSnapshot;
+pub use warp::{warp_instance, WarpTarget};
+
+/// Format of `instance.json`
+///
+/// - 1: `version` added
+const INSTANCE_SCHEMA: Schema = Schema::new(&[schema::unversioned]);
 
 /// Instance information saved to disk for CLI discovery
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +45,42 @@ pub struct SolanaInstanceInfo {
     pub accounts_count: u32,
     /// Whether the instance is currently running (may be stale)
     pub running: bool,
+    /// Process ID of the validator, if known
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Number of times the instance has been reset in place with `cf reset`
+    #[serde(default)]
+    pub epoch: u64,
+    /// Public cluster the instance tracks instead of running a validator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<PublicNetwork>,
+    /// Every port the validator uses; absent for instances saved before
+    /// they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ports: Option<ValidatorPorts>,
+    /// How long each phase of the last start took
+    #[serde(default, skip_serializing_if = "StartupProfile::is_empty")]
+    pub startup: StartupProfile,
+}
+
+/// Ports a validator listens on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorPorts {
+    pub rpc: u16,
+    pub faucet: u16,
+    pub gossip: u16,
+    /// Inclusive range for TPU, TVU, and repair sockets
+    pub dynamic_range: [u16; 2],
+}
+
+impl std::fmt::Display for ValidatorPorts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RPC {}, faucet {}, gossip {}, dynamic {}-{}",
+            self.rpc, self.faucet, self.gossip, self.dynamic_range[0], self.dynamic_range[1]
+        )
+    }
 }
 
 impl SolanaInstanceInfo {
@@ -42,9 +99,7 @@ impl SolanaInstanceInfo {
             )));
         }
 
-        let json = std::fs::read_to_string(&path)?;
-        let info: SolanaInstanceInfo = serde_json::from_str(&json)?;
-        Ok(info)
+        INSTANCE_SCHEMA.read(&path)
     }
 
     /// Save instance info to disk
@@ -55,13 +110,7 @@ impl SolanaInstanceInfo {
             .join(&self.instance_id)
             .join("instance.json");
 
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(&path, json)?;
-        Ok(())
+        INSTANCE_SCHEMA.write(&path, self)
     }
 
     /// Mark instance as stopped
@@ -71,6 +120,34 @@ impl SolanaInstanceInfo {
     }
 }
 
+/// Slots per epoch when none is configured (mainnet's epoch length)
+pub const DEFAULT_SLOTS_PER_EPOCH: u64 = 432_000;
+
+/// Shortest epoch the runtime accepts
+pub const MINIMUM_SLOTS_PER_EPOCH: u64 = 32;
+
+/// Cluster accounts and programs are cloned from when none is configured
+pub const DEFAULT_CLONE_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Ports a validator uses from its RPC port on: the faucet and gossip ports
+/// and the 501-port dynamic range after them
+pub const PORT_SPAN: u16 = 1505;
+
+/// Offset of the faucet port from the RPC port
+const FAUCET_PORT_OFFSET: u16 = 1002;
+
+/// Free RPC port for an instance started with `--port auto`
+///
+/// The RPC port and every port from the faucet port to the end of the
+/// dynamic range must be free; the block stays reserved for the instance
+/// while the validator starts.
+pub fn auto_port(instance_id: &str) -> Result<u16> {
+    let listen: Vec<u16> = std::iter::once(0)
+        .chain(FAUCET_PORT_OFFSET..PORT_SPAN)
+        .collect();
+    Config::load()?.auto_port(ChainType::Solana, instance_id, PORT_SPAN, &listen)
+}
+
 /// Configuration for starting a Solana validator
 #[derive(Debug, Clone)]
 pub struct SolanaConfig {
@@ -79,10 +156,33 @@ pub struct SolanaConfig {
     pub accounts: u32,
     pub initial_balance: f64,
     pub mnemonic: Option<String>,
+    /// Derivation index of the first account
+    pub start_index: u32,
     /// Instance ID for isolation (allows multiple nodes with separate state)
     pub instance_id: String,
     /// Human-readable name for the instance
     pub name: Option<String>,
+    /// Warp the ledger to this slot after starting
+    pub warp_slot: Option<u64>,
+    /// Slots in each epoch (default: [`DEFAULT_SLOTS_PER_EPOCH`]); only
+    /// applies to a fresh ledger
+    pub slots_per_epoch: Option<u64>,
+    /// Fixed annual inflation rate paid out at epoch boundaries; only
+    /// applies to a fresh ledger
+    pub inflation_fixed: Option<f64>,
+    /// Boot from a named ledger snapshot instead of a fresh ledger
+    pub snapshot: Option<String>,
+    /// Account indices whose keys are held by an emulated external signer
+    pub external_signers: Vec<u32>,
+    /// Labels given to the first accounts, in order
+    pub account_labels: Vec<String>,
+    /// Accounts copied from `clone_url` into a fresh ledger
+    pub clone_accounts: Vec<String>,
+    /// Upgradeable programs copied from `clone_url` into a fresh ledger,
+    /// keeping their upgrade authority
+    pub clone_programs: Vec<String>,
+    /// RPC URL of the cluster to clone from (default: [`DEFAULT_CLONE_URL`])
+    pub clone_url: String,
 }
 
 impl Default for SolanaConfig {
@@ -100,8 +200,18 @@ impl SolanaConfig {
             accounts: 10,
             initial_balance: 100.0,
             mnemonic: None,
+            start_index: 0,
             instance_id: instance_id.to_string(),
             name: None,
+            warp_slot: None,
+            slots_per_epoch: None,
+            inflation_fixed: None,
+            snapshot: None,
+            external_signers: Vec::new(),
+            account_labels: Vec::new(),
+            clone_accounts: Vec::new(),
+            clone_programs: Vec::new(),
+            clone_url: DEFAULT_CLONE_URL.to_string(),
         }
     }
 
@@ -122,6 +232,48 @@ impl SolanaConfig {
     pub fn instance_info_file(&self) -> PathBuf {
         self.instance_dir().join("instance.json")
     }
+
+    /// Get the keystore file backing emulated external signers
+    pub fn signer_keystore_file(&self) -> PathBuf {
+        self.instance_dir().join("signer-keystore.json")
+    }
+
+    /// Get the validator's own log file
+    pub fn log_file(&self) -> PathBuf {
+        self.instance_dir()
+            .join("test-ledger")
+            .join("validator.log")
+    }
+
+    /// Faucet port, derived from the RPC port to avoid conflicts
+    /// (RPC port + 1002, e.g., 8899 -> 9901, 9000 -> 10002)
+    pub fn faucet_port(&self) -> u16 {
+        self.port + FAUCET_PORT_OFFSET
+    }
+
+    /// Gossip port, placed right after the faucet port
+    pub fn gossip_port(&self) -> u16 {
+        self.faucet_port() + 1
+    }
+
+    /// Every port the validator uses, derived from the RPC port
+    pub fn ports(&self) -> ValidatorPorts {
+        let dynamic_base = self.gossip_port() + 1;
+        ValidatorPorts {
+            rpc: self.port,
+            faucet: self.faucet_port(),
+            gossip: self.gossip_port(),
+            dynamic_range: [dynamic_base, dynamic_base + 500],
+        }
+    }
+
+    /// First slot of `epoch`
+    ///
+    /// solana-test-validator disables warmup epochs, so every epoch is the
+    /// same length.
+    pub fn epoch_start_slot(&self, epoch: u64) -> u64 {
+        epoch.saturating_mul(self.slots_per_epoch.unwrap_or(DEFAULT_SLOTS_PER_EPOCH))
+    }
 }
 
 impl From<SolanaProfile> for SolanaConfig {
@@ -132,8 +284,18 @@ impl From<SolanaProfile> for SolanaConfig {
             accounts: profile.accounts,
             initial_balance: profile.initial_balance,
             mnemonic: None,
+            start_index: 0,
             instance_id: "default".to_string(),
             name: None,
+            warp_slot: None,
+            slots_per_epoch: None,
+            inflation_fixed: None,
+            snapshot: None,
+            external_signers: Vec::new(),
+            account_labels: Vec::new(),
+            clone_accounts: Vec::new(),
+            clone_programs: Vec::new(),
+            clone_url: DEFAULT_CLONE_URL.to_string(),
         }
     }
 }
@@ -147,6 +309,13 @@ pub struct SolanaProvider {
     storage: AccountsStorage,
     /// Whether to keep instance data on stop (default: false)
     keep_data: bool,
+    /// Whether to snapshot the instance on stop, before clearing it
+    snapshot_on_stop: bool,
+    /// Trash retention for this instance's data; `None` uses the config's
+    trash_retention_days: Option<u32>,
+    /// Refreshes the node's registry heartbeat while it runs
+    heartbeat: Option<Heartbeat>,
+    hooks: LifecycleHooks,
 }
 
 impl SolanaProvider {
@@ -173,6 +342,10 @@ impl SolanaProvider {
             validator_process: Arc::new(Mutex::new(None)),
             storage,
             keep_data: false,
+            snapshot_on_stop: false,
+            trash_retention_days: None,
+            heartbeat: None,
+            hooks: LifecycleHooks::new(),
         }
     }
 
@@ -181,6 +354,13 @@ impl SolanaProvider {
         self.keep_data = keep;
     }
 
+    /// Apply a profile's cleanup settings: what stop does with instance data
+    pub fn set_cleanup(&mut self, cleanup: CleanupPolicy) {
+        self.keep_data = cleanup.keep_data;
+        self.snapshot_on_stop = cleanup.snapshot_on_stop;
+        self.trash_retention_days = cleanup.trash_retention_days;
+    }
+
     /// Generate accounts for this instance
     ///
     /// Each node start gets fresh accounts. If a mnemonic is provided, the same
@@ -190,11 +370,13 @@ impl SolanaProvider {
             AccountGenerator::from_mnemonic(mnemonic)?
         } else {
             AccountGenerator::new()?
-        };
+        }
+        .with_start_index(self.config.start_index);
 
-        println!("🔑 Mnemonic: {}", generator.mnemonic_phrase());
-        println!("   Save this mnemonic to recover your accounts!");
-        println!();
+        info!(
+            mnemonic = %generator.mnemonic_phrase(),
+            "Generated accounts; save this mnemonic to recover them"
+        );
 
         self.accounts = generator.generate_accounts(self.config.accounts)?;
 
@@ -203,27 +385,97 @@ impl SolanaProvider {
             account.balance = self.config.initial_balance;
         }
 
+        for (account, label) in self.accounts.iter_mut().zip(&self.config.account_labels) {
+            account.label = Some(label.clone());
+        }
+
+        self.detach_external_signers()?;
+
         self.storage.save(&self.accounts)?;
 
         Ok(())
     }
 
-    /// Clear all instance data
-    fn clear_instance_data(&self) -> Result<()> {
-        let instance_dir = self.config.instance_dir();
+    /// Move the keys of accounts marked as external signers into the signer keystore
+    fn detach_external_signers(&mut self) -> Result<()> {
+        if self.config.external_signers.is_empty() {
+            return Ok(());
+        }
 
-        // Remove the entire instance directory if it exists
-        if instance_dir.exists() {
-            std::fs::remove_dir_all(&instance_dir).map_err(|e| {
-                ChainError::NodeManagement(format!("Failed to clear instance data: {}", e))
+        let keystore = SignerKeystore::with_path(self.config.signer_keystore_file());
+        for &index in &self.config.external_signers {
+            let account = self.accounts.get_mut(index as usize).ok_or_else(|| {
+                ChainError::Config(format!(
+                    "External signer index {} is out of range (only {} accounts)",
+                    index, self.config.accounts
+                ))
             })?;
+            account.detach_key(&keystore)?;
+            info!(account = %account.public_key, "Account uses an emulated external signer");
+        }
+
+        Ok(())
+    }
+
+    /// Move instance data to the trash
+    ///
+    /// Accounts kept in the OS keyring or in memory can't be moved with the
+    /// directory, so they are deleted.
+    fn clear_instance_data(&self) -> Result<()> {
+        if self.storage.backend() != StorageBackend::File {
+            self.storage.delete()?;
+        }
+
+        let trashed = Config::trash_with_retention(self.trash_retention_days).move_instance(
+            ChainType::Solana,
+            &self.config.instance_id,
+            &self.config.instance_dir(),
+        )?;
+        if let Some(entry) = trashed {
+            info!(trash = %entry.id, "Moved instance data to the trash");
+        }
+
+        Ok(())
+    }
+
+    /// Snapshot the stopped instance as `<instance>-stop-<timestamp>`
+    ///
+    /// Failing only logs a warning, so the data is still cleared as usual.
+    fn save_stop_snapshot(&self, slot: u64) {
+        let instance = &self.config.instance_id;
+        let name = format!("{}-stop-{}", instance, Utc::now().format("%Y%m%d-%H%M%S"));
+        match SolanaSnapshot::create(&name, instance, slot, &self.config.instance_dir()) {
+            Ok(snapshot) => info!(snapshot = %snapshot.name, slot, "Saved snapshot on stop"),
+            Err(e) => warn!(error = %e, "Failed to save snapshot on stop"),
         }
+    }
+
+    /// Restore ledger and accounts from the configured snapshot
+    fn restore_snapshot(&mut self, name: &str) -> Result<()> {
+        let snapshot = SolanaSnapshot::load(name)?;
+
+        info!(
+            snapshot = %snapshot.name,
+            slot = snapshot.slot,
+            source_instance = %snapshot.source_instance,
+            "Restoring snapshot"
+        );
+        snapshot.restore(&self.config.instance_dir())?;
+
+        self.accounts = self.storage.load()?;
+        self.config.accounts = self.accounts.len() as u32;
 
         Ok(())
     }
 
     /// Save instance info for CLI discovery
     fn save_instance_info(&self) -> Result<()> {
+        let pid = self
+            .validator_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|child| child.id());
         let info = SolanaInstanceInfo {
             instance_id: self.config.instance_id.clone(),
             name: self.config.name.clone(),
@@ -231,14 +483,27 @@ impl SolanaProvider {
             rpc_port: self.config.port,
             accounts_count: self.config.accounts,
             running: true,
+            pid,
+            epoch: 0,
+            network: None,
+            ports: Some(self.config.ports()),
+            startup: StartupProfile::default(),
         };
         info.save()
     }
 
+    /// Record how long each phase of this start took in the instance info
+    fn save_startup_profile(&self, startup: StartupProfile) -> Result<()> {
+        let mut info = SolanaInstanceInfo::load(&self.config.instance_id)?;
+        info!(total_ms = startup.total_ms(), "Startup phases recorded");
+        info.startup = startup;
+        info.save()
+    }
+
     /// Register this node with the global registry
     fn register_with_registry(&self) -> Result<()> {
         let registry = NodeRegistry::new();
-        let node = NodeInfo::new(
+        let mut node = NodeInfo::new(
             ChainType::Solana,
             &self.config.instance_id,
             self.config.name.clone(),
@@ -246,6 +511,12 @@ impl SolanaProvider {
             self.config.port,
             self.config.accounts,
         );
+        node.node_pid = self
+            .validator_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|child| child.id());
         registry.register(node)
     }
 
@@ -256,6 +527,21 @@ impl SolanaProvider {
         registry.update_status(&node_id, NodeStatus::Stopped)
     }
 
+    /// Context passed to lifecycle hooks
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            chain: ChainType::Solana,
+            instance_id: self.config.instance_id.clone(),
+            rpc_url: self.config.rpc_url.clone(),
+            accounts: self.accounts.iter().map(|a| a.public_key.clone()).collect(),
+        }
+    }
+
+    /// Publish an event for this node
+    fn publish(&self, event: ChainEvent) {
+        chain_forge_events::publish(ChainType::Solana, &self.config.instance_id, event);
+    }
+
     /// Check if a port is available for binding
     fn check_port_available(port: u16, description: &str) -> Result<()> {
         std::net::TcpListener::bind(("0.0.0.0", port)).map_err(|_| {
@@ -281,8 +567,7 @@ impl SolanaProvider {
         }
 
         // Calculate faucet port based on RPC port to avoid conflicts
-        // Use RPC port + 1002 (e.g., 8899 -> 9901, 9000 -> 10002)
-        let faucet_port = self.config.port + 1002;
+        let faucet_port = self.config.faucet_port();
 
         // Check if required ports are available before starting
         Self::check_port_available(self.config.port, "RPC")?;
@@ -293,54 +578,44 @@ impl SolanaProvider {
             .name
             .as_ref()
             .unwrap_or(&self.config.instance_id);
-        println!(
-            "🚀 Starting Solana test validator '{}' on port {}...",
-            instance_name, self.config.port
+        info!(
+            instance = %instance_name,
+            port = self.config.port,
+            "Starting Solana test validator"
         );
 
-        // Create log files for capturing startup output and errors
-        let log_dir = self.config.instance_dir();
-        std::fs::create_dir_all(&log_dir).ok();
-        let stdout_file =
-            std::fs::File::create(log_dir.join("validator_stdout.log")).map_err(|e| {
-                ChainError::NodeManagement(format!("Failed to create stdout log: {}", e))
-            })?;
-        let stderr_file =
-            std::fs::File::create(log_dir.join("validator_stderr.log")).map_err(|e| {
-                ChainError::NodeManagement(format!("Failed to create stderr log: {}", e))
-            })?;
+        // Pre-check gossip port availability
+        Self::check_port_available(self.config.gossip_port(), "Gossip")?;
+
+        let mut cmd = validator_command(&self.config.ports(), &self.config.instance_dir())?;
 
-        // Use instance-specific ledger directory to allow multiple concurrent validators
-        let ledger_dir = self.config.instance_dir().join("test-ledger");
+        // A restored snapshot must keep its ledger; otherwise start clean
+        if self.config.snapshot.is_none() {
+            cmd.arg("--reset");
+        }
 
-        // Each instance needs its own gossip port and dynamic port range to avoid
-        // conflicts when running multiple validators concurrently.
-        // Gossip port defaults to 8000 and is NOT covered by --dynamic-port-range,
-        // so it must be set explicitly via --gossip-port.
-        let gossip_port = faucet_port + 1;
-        let dynamic_base = faucet_port + 2;
-        let dynamic_end = dynamic_base + 500;
+        if let Some(slot) = self.config.warp_slot {
+            cmd.arg("--warp-slot").arg(slot.to_string());
+        }
 
-        // Pre-check gossip port availability
-        Self::check_port_available(gossip_port, "Gossip")?;
-
-        // Start the validator
-        // Note: --quiet is omitted because output is redirected to log files anyway,
-        // and --quiet can suppress error messages we need to diagnose startup failures.
-        let mut cmd = Command::new("solana-test-validator");
-        cmd.arg("--rpc-port")
-            .arg(self.config.port.to_string())
-            .arg("--faucet-port")
-            .arg(faucet_port.to_string())
-            .arg("--gossip-port")
-            .arg(gossip_port.to_string())
-            .arg("--dynamic-port-range")
-            .arg(format!("{}-{}", dynamic_base, dynamic_end))
-            .arg("--ledger")
-            .arg(&ledger_dir)
-            .arg("--reset")
-            .stdout(stdout_file)
-            .stderr(stderr_file);
+        if let Some(slots) = self.config.slots_per_epoch {
+            cmd.arg("--slots-per-epoch").arg(slots.to_string());
+        }
+
+        if let Some(rate) = self.config.inflation_fixed {
+            cmd.arg("--inflation-fixed").arg(rate.to_string());
+        }
+
+        // Clones are fetched once, into genesis
+        if !self.config.clone_accounts.is_empty() || !self.config.clone_programs.is_empty() {
+            cmd.arg("--url").arg(&self.config.clone_url);
+            for address in &self.config.clone_accounts {
+                cmd.arg("--clone").arg(address);
+            }
+            for program in &self.config.clone_programs {
+                cmd.arg("--clone-upgradeable-program").arg(program);
+            }
+        }
 
         let child = cmd
             .spawn()
@@ -358,6 +633,44 @@ impl SolanaProvider {
     }
 }
 
+/// `solana-test-validator` command for the ledger in `instance_dir`
+///
+/// Output goes to log files in `instance_dir`; flags that only apply to a
+/// new ledger are left to the caller.
+pub(crate) fn validator_command(ports: &ValidatorPorts, instance_dir: &Path) -> Result<Command> {
+    // Create log files for capturing startup output and errors
+    std::fs::create_dir_all(instance_dir).ok();
+    let stdout_file = std::fs::File::create(instance_dir.join("validator_stdout.log"))
+        .map_err(|e| ChainError::NodeManagement(format!("Failed to create stdout log: {}", e)))?;
+    let stderr_file = std::fs::File::create(instance_dir.join("validator_stderr.log"))
+        .map_err(|e| ChainError::NodeManagement(format!("Failed to create stderr log: {}", e)))?;
+
+    // Each instance needs its own gossip port and dynamic port range to avoid
+    // conflicts when running multiple validators concurrently.
+    // Gossip port defaults to 8000 and is NOT covered by --dynamic-port-range,
+    // so it must be set explicitly via --gossip-port.
+    // Note: --quiet is omitted because output is redirected to log files anyway,
+    // and --quiet can suppress error messages we need to diagnose startup failures.
+    let mut cmd = Command::new("solana-test-validator");
+    cmd.arg("--rpc-port")
+        .arg(ports.rpc.to_string())
+        .arg("--faucet-port")
+        .arg(ports.faucet.to_string())
+        .arg("--gossip-port")
+        .arg(ports.gossip.to_string())
+        .arg("--dynamic-port-range")
+        .arg(format!(
+            "{}-{}",
+            ports.dynamic_range[0], ports.dynamic_range[1]
+        ))
+        // Instance-specific ledger directory allows multiple concurrent validators
+        .arg("--ledger")
+        .arg(instance_dir.join("test-ledger"))
+        .stdout(stdout_file)
+        .stderr(stderr_file);
+    Ok(cmd)
+}
+
 impl Default for SolanaProvider {
     fn default() -> Self {
         Self::new()
@@ -388,21 +701,28 @@ impl ChainProvider for SolanaProvider {
             }
         }
 
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::BeforeStart, &ctx)?;
+
         // Update storage to use instance-specific path
         self.storage = AccountsStorage::with_path(self.config.accounts_file());
 
-        println!(
-            "🧹 Clearing previous instance data for '{}'...",
-            self.config.instance_id
+        info!(
+            instance = %self.config.instance_id,
+            "Clearing previous instance data"
         );
 
         // Clear all previous instance data for clean slate
         self.clear_instance_data()?;
 
-        // Generate fresh accounts
-        self.generate_accounts()?;
+        // Restore from a snapshot, or generate fresh accounts
+        match self.config.snapshot.clone() {
+            Some(name) => self.restore_snapshot(&name)?,
+            None => self.generate_accounts()?,
+        }
 
         // Start validator
+        let mut timer = StartupTimer::new();
         self.start_validator()?;
 
         // Brief pause to detect early startup failures (e.g., port already in use)
@@ -414,11 +734,9 @@ impl ChainProvider for SolanaProvider {
             if let Some(ref mut child) = *process_guard {
                 if let Ok(Some(status)) = child.try_wait() {
                     process_guard.take();
-                    let log_dir = self.config.instance_dir();
-
                     // Check the validator's own log for the real error
                     // (panics and internal errors go there, not to stdout/stderr)
-                    let validator_log_path = log_dir.join("test-ledger").join("validator.log");
+                    let validator_log_path = self.config.log_file();
                     let error_detail =
                         std::fs::read_to_string(&validator_log_path)
                             .ok()
@@ -455,6 +773,7 @@ impl ChainProvider for SolanaProvider {
                 }
             }
         }
+        timer.lap(StartupPhase::Spawn);
 
         // Save instance info for CLI discovery
         self.save_instance_info()?;
@@ -466,6 +785,10 @@ impl ChainProvider for SolanaProvider {
             let accounts = self.config.accounts;
             let initial_balance = self.config.initial_balance;
             let mut accounts_vec = self.accounts.clone();
+            let restored = self.config.snapshot.is_some();
+            let retry = Config::load()?.funding_retry(ChainType::Solana);
+            let instance_id = self.config.instance_id.clone();
+            let mut timer = timer;
 
             move || {
                 // Create a new runtime in this thread
@@ -475,34 +798,52 @@ impl ChainProvider for SolanaProvider {
                 rt.block_on(async {
                     let rpc_client = SolanaRpcClient::new(config_url);
 
-                    println!("⏳ Waiting for validator to be ready...");
+                    info!("Waiting for validator to be ready");
                     rpc_client.wait_for_validator(60).await?;
+                    info!("Validator is ready");
+                    timer.lap(StartupPhase::RpcReady);
+
+                    // Restored snapshots keep their balances as recorded in the ledger
+                    if !restored {
+                        // Initialize account balances to the target amount before funding
+                        for account in accounts_vec.iter_mut() {
+                            account.balance = initial_balance;
+                        }
 
-                    println!("✅ Validator is ready!");
-                    println!();
-
-                    // Initialize account balances to the target amount before funding
-                    for account in accounts_vec.iter_mut() {
-                        account.balance = initial_balance;
+                        info!(
+                            accounts,
+                            balance = initial_balance,
+                            "Setting account balances (SOL)"
+                        );
+                        let total = accounts_vec.len() as u64;
+                        let progress = |done: usize| {
+                            chain_forge_events::publish(
+                                ChainType::Solana,
+                                &instance_id,
+                                ChainEvent::StartupProgress {
+                                    phase: StartupPhase::Funding,
+                                    done: done as u64,
+                                    total,
+                                },
+                            );
+                        };
+                        rpc_client
+                            .set_balances(&mut accounts_vec, &retry, progress)
+                            .await?;
+                        timer.lap(StartupPhase::Funding);
                     }
-
-                    println!(
-                        "💰 Setting {} accounts to {} SOL each...",
-                        accounts, initial_balance
-                    );
-                    rpc_client.set_balances(&mut accounts_vec).await?;
                     rpc_client.update_balances(&mut accounts_vec)?;
 
                     // Save updated accounts to instance-specific location
                     let storage = AccountsStorage::with_path(accounts_file);
                     storage.save(&accounts_vec)?;
 
-                    println!("✅ All accounts funded!");
-                    println!();
+                    info!("All accounts funded");
 
-                    Ok::<(SolanaRpcClient, Vec<SolanaAccount>), ChainError>((
+                    Ok::<(SolanaRpcClient, Vec<SolanaAccount>, StartupTimer), ChainError>((
                         rpc_client,
                         accounts_vec,
+                        timer,
                     ))
                 })
             }
@@ -512,54 +853,101 @@ impl ChainProvider for SolanaProvider {
 
         self.rpc_client = Some(result.0);
         self.accounts = result.1;
+        if let Err(e) = self.save_startup_profile(result.2.finish()) {
+            warn!(error = %e, "Failed to save the startup profile");
+        }
 
         // Register with global node registry
         if let Err(e) = self.register_with_registry() {
-            eprintln!("Warning: Failed to register with node registry: {}", e);
+            warn!(error = %e, "Failed to register with node registry");
+        }
+        self.heartbeat = Some(Heartbeat::start(
+            NodeRegistry::node_id(ChainType::Solana, &self.config.instance_id),
+            HEARTBEAT_INTERVAL,
+        ));
+        self.publish(ChainEvent::NodeStarted);
+        if self.config.snapshot.is_none() {
+            self.publish(ChainEvent::FundsDispensed {
+                address: None,
+                amount: self.config.accounts as f64 * self.config.initial_balance,
+            });
         }
 
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::AfterStart, &ctx)?;
+
         let instance_name = self
             .config
             .name
             .as_ref()
             .unwrap_or(&self.config.instance_id);
-        println!("🎉 Solana test validator '{}' is running!", instance_name);
-        println!("   RPC URL: {}", self.config.rpc_url);
-        println!();
+        info!(
+            instance = %instance_name,
+            rpc_url = %self.config.rpc_url,
+            "Solana test validator is running"
+        );
 
         Ok(())
     }
 
     fn stop(&mut self) -> Result<()> {
+        if self.is_running() {
+            let ctx = self.hook_context();
+            self.hooks.run_before_stop(&ctx);
+        }
+
+        if let Some(mut heartbeat) = self.heartbeat.take() {
+            heartbeat.stop();
+        }
+
         let mut process_guard = self.validator_process.lock().unwrap();
 
         if let Some(mut child) = process_guard.take() {
+            // The slot is only known while the validator still answers
+            let slot = self
+                .rpc_client
+                .as_ref()
+                .filter(|_| self.snapshot_on_stop)
+                .and_then(|client| client.get_slot().ok());
+
             child.kill().map_err(|e| {
                 ChainError::NodeManagement(format!("Failed to stop validator: {}", e))
             })?;
 
             // Unregister from global node registry
             if let Err(e) = self.unregister_from_registry() {
-                eprintln!("Warning: Failed to unregister from node registry: {}", e);
+                warn!(error = %e, "Failed to unregister from node registry");
             }
+            self.publish(ChainEvent::NodeStopped);
 
             child.wait().map_err(|e| {
                 ChainError::NodeManagement(format!("Failed to wait for validator: {}", e))
             })?;
 
-            // Mark instance as stopped
             if let Ok(mut info) = SolanaInstanceInfo::load(&self.config.instance_id) {
+                // A warp replaces the validator with one this process didn't start
+                if let Some(pid) = info.pid.filter(|pid| *pid != child.id()) {
+                    if let Err(e) = warp::stop_validator(pid, &self.config.ports()) {
+                        warn!(pid, error = %e, "Failed to stop the warped validator");
+                    }
+                }
+
+                // Mark instance as stopped
                 let _ = info.mark_stopped();
             }
 
+            if self.snapshot_on_stop {
+                self.save_stop_snapshot(slot.unwrap_or(0));
+            }
+
             // Clean up instance data unless keep_data is set
             if !self.keep_data {
                 let _ = self.clear_instance_data();
             }
 
-            println!(
-                "🛑 Solana test validator stopped (instance: {})",
-                self.config.instance_id
+            info!(
+                instance = %self.config.instance_id,
+                "Solana test validator stopped"
             );
         }
 
@@ -595,6 +983,10 @@ impl ChainProvider for SolanaProvider {
     fn get_rpc_url(&self) -> String {
         self.config.rpc_url.clone()
     }
+
+    fn hooks(&mut self) -> &mut LifecycleHooks {
+        &mut self.hooks
+    }
 }
 
 impl Drop for SolanaProvider {
@@ -632,6 +1024,16 @@ mod tests {
         assert!(config.name.is_none());
     }
 
+    #[test]
+    fn test_epoch_start_slot() {
+        let mut config = SolanaConfig::default();
+        assert_eq!(config.epoch_start_slot(2), 2 * DEFAULT_SLOTS_PER_EPOCH);
+
+        config.slots_per_epoch = Some(MINIMUM_SLOTS_PER_EPOCH);
+        assert_eq!(config.epoch_start_slot(0), 0);
+        assert_eq!(config.epoch_start_slot(10), 320);
+    }
+
     #[test]
     fn test_provider_with_config() {
         let mut config = SolanaConfig::with_instance("test");
@@ -654,6 +1056,7 @@ mod tests {
             accounts: 15,
             initial_balance: 200.0,
             port: 8900,
+            ..SolanaProfile::default()
         };
 
         let config: SolanaConfig = profile.into();
@@ -739,6 +1142,11 @@ mod tests {
             rpc_port: 8899,
             accounts_count: 10,
             running: true,
+            pid: Some(4242),
+            epoch: 0,
+            network: None,
+            ports: None,
+            startup: StartupProfile::default(),
         };
 
         // Serialize to JSON
@@ -755,6 +1163,25 @@ mod tests {
         assert_eq!(deserialized.rpc_port, 8899);
         assert_eq!(deserialized.accounts_count, 10);
         assert!(deserialized.running);
+        assert_eq!(deserialized.pid, Some(4242));
+    }
+
+    #[test]
+    fn test_instance_info_without_pid() {
+        // Instance files written before PIDs were recorded still load
+        let json = r#"{
+            "instance_id": "old",
+            "name": null,
+            "rpc_url": "http://localhost:8899",
+            "rpc_port": 8899,
+            "accounts_count": 10,
+            "running": true
+        }"#;
+
+        let info: SolanaInstanceInfo = serde_json::from_str(json).unwrap();
+        assert!(info.pid.is_none());
+        assert_eq!(info.epoch, 0);
+        assert!(info.network.is_none());
     }
 
     #[test]
@@ -766,6 +1193,11 @@ mod tests {
             rpc_port: 8899,
             accounts_count: 5,
             running: false,
+            pid: None,
+            epoch: 0,
+            network: None,
+            ports: None,
+            startup: StartupProfile::default(),
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -776,6 +1208,23 @@ mod tests {
         assert!(!deserialized.running);
     }
 
+    #[test]
+    fn test_derived_ports() {
+        let mut config = SolanaConfig::default();
+        assert_eq!(config.faucet_port(), 9901);
+        assert_eq!(config.gossip_port(), 9902);
+
+        config.port = 9000;
+        assert_eq!(config.faucet_port(), 10002);
+        assert_eq!(config.gossip_port(), 10003);
+
+        let ports = config.ports();
+        assert_eq!(ports.rpc, 9000);
+        assert_eq!(ports.dynamic_range, [10004, 10504]);
+        // The dynamic range ends on the last port of the span
+        assert_eq!(ports.dynamic_range[1], config.port + PORT_SPAN - 1);
+    }
+
     #[test]
     fn test_different_instance_configs() {
         let config1 = SolanaConfig::with_instance("dev");
@@ -0,0 +1,157 @@
+//! Passthrough instances that track accounts on a public cluster.
+//!
+//! Attaching derives accounts the same way `start` does but spawns no
+//! validator: the instance's RPC URL points at the cluster's public
+//! endpoint, so balance lookups, airdrops and the accounts API work on it
+//! like on a local node. Teams that stage on devnet or testnet get the same
+//! tooling without a separate set of scripts.
+
+use crate::{SolanaConfig, SolanaInstanceInfo};
+use chain_forge_common::{
+    ChainError, ChainType, NodeInfo, NodeRegistry, NodeStatus, Result, StartupProfile,
+};
+use chain_forge_solana_accounts::{AccountGenerator, AccountsStorage, SolanaAccount};
+use chain_forge_solana_rpc::SolanaRpcClient;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::info;
+
+/// Public Solana cluster an instance can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicNetwork {
+    Devnet,
+    Testnet,
+}
+
+impl PublicNetwork {
+    /// The cluster's public RPC endpoint
+    pub fn default_rpc_url(&self) -> &'static str {
+        match self {
+            PublicNetwork::Devnet => "https://api.devnet.solana.com",
+            PublicNetwork::Testnet => "https://api.testnet.solana.com",
+        }
+    }
+}
+
+impl fmt::Display for PublicNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicNetwork::Devnet => write!(f, "devnet"),
+            PublicNetwork::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+/// Attach an instance to a public cluster
+///
+/// Uses the instance ID, name, RPC URL, account count, mnemonic and labels
+/// from `config`. An existing local validator instance with the same ID is
+/// left alone; re-attaching a passthrough instance replaces its accounts.
+/// Returns the accounts with their current on-chain balances.
+pub fn attach(config: &SolanaConfig, network: PublicNetwork) -> Result<Vec<SolanaAccount>> {
+    if let Ok(info) = SolanaInstanceInfo::load(&config.instance_id) {
+        if info.running && info.network.is_none() {
+            return Err(ChainError::AlreadyRunning);
+        }
+    }
+
+    let client = SolanaRpcClient::new(config.rpc_url.clone());
+    if !client.is_validator_running() {
+        return Err(ChainError::Rpc(format!(
+            "Solana {} RPC endpoint {} is not reachable",
+            network, config.rpc_url
+        )));
+    }
+
+    let generator = if let Some(mnemonic) = &config.mnemonic {
+        AccountGenerator::from_mnemonic(mnemonic)?
+    } else {
+        AccountGenerator::new()?
+    };
+
+    info!(
+        mnemonic = %generator.mnemonic_phrase(),
+        "Generated accounts; save this mnemonic to recover them"
+    );
+
+    let mut accounts = generator.generate_accounts(config.accounts)?;
+    for (account, label) in accounts.iter_mut().zip(&config.account_labels) {
+        account.label = Some(label.clone());
+    }
+    client.update_balances(&mut accounts)?;
+    AccountsStorage::with_path(config.accounts_file()).save(&accounts)?;
+
+    let info = SolanaInstanceInfo {
+        instance_id: config.instance_id.clone(),
+        name: config.name.clone(),
+        rpc_url: config.rpc_url.clone(),
+        rpc_port: url_port(&config.rpc_url),
+        accounts_count: config.accounts,
+        running: true,
+        pid: None,
+        epoch: 0,
+        network: Some(network),
+        ports: None,
+        startup: StartupProfile::default(),
+    };
+    info.save()?;
+
+    NodeRegistry::new().register(NodeInfo::new(
+        ChainType::Solana,
+        &config.instance_id,
+        config.name.clone(),
+        info.rpc_url,
+        info.rpc_port,
+        config.accounts,
+    ))?;
+
+    Ok(accounts)
+}
+
+/// Detach a passthrough instance, keeping its accounts on disk
+pub fn detach(instance_id: &str) -> Result<()> {
+    let mut info = SolanaInstanceInfo::load(instance_id)?;
+    if info.network.is_none() {
+        return Err(ChainError::Config(format!(
+            "Instance '{}' runs a local validator; stop it instead",
+            instance_id
+        )));
+    }
+
+    info.mark_stopped()?;
+    NodeRegistry::new().update_status(
+        &NodeRegistry::node_id(ChainType::Solana, instance_id),
+        NodeStatus::Stopped,
+    )
+}
+
+/// Port of an RPC URL, falling back to the scheme's default
+fn url_port(url: &str) -> u16 {
+    let authority = url.split("://").nth(1).unwrap_or(url);
+    let host = authority.split('/').next().unwrap_or(authority);
+    host.rsplit_once(':')
+        .and_then(|(_, port)| port.parse().ok())
+        .unwrap_or(if url.starts_with("http://") { 80 } else { 443 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_serialization() {
+        let json = serde_json::to_string(&PublicNetwork::Devnet).unwrap();
+        assert_eq!(json, "\"devnet\"");
+        let network: PublicNetwork = serde_json::from_str("\"testnet\"").unwrap();
+        assert_eq!(network, PublicNetwork::Testnet);
+        assert_eq!(network.to_string(), "testnet");
+    }
+
+    #[test]
+    fn test_url_port() {
+        assert_eq!(url_port("https://api.devnet.solana.com"), 443);
+        assert_eq!(url_port("http://rpc.example.com/path"), 80);
+        assert_eq!(url_port("https://rpc.example.com:8899/key"), 8899);
+    }
+}
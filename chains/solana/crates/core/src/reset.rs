@@ -0,0 +1,84 @@
+//! Reset a running instance's accounts without restarting the validator.
+//!
+//! `solana-test-validator` cannot roll its ledger back while it runs, so a
+//! reset restores account balances instead: funds above the initial balance
+//! go back to the faucet and accounts below it are airdropped the difference.
+//! Other ledger state (programs, token accounts) is kept; restart with a
+//! fresh ledger or restore a snapshot to clear it.
+
+use crate::{SolanaConfig, SolanaInstanceInfo};
+use chain_forge_common::{ChainError, ChainType, Result};
+use chain_forge_events::ChainEvent;
+use chain_forge_solana_accounts::{AccountsStorage, SolanaAccount};
+use chain_forge_solana_rpc::SolanaRpcClient;
+use tracing::info;
+
+/// Set every account of an instance back to `balance` SOL
+///
+/// External signer accounts can only be topped up, since their keys are not
+/// available to move funds out. Bumps the instance's `epoch` and returns the
+/// accounts with their new balances.
+pub fn reset_instance(instance_id: &str, balance: f64) -> Result<Vec<SolanaAccount>> {
+    let mut info = SolanaInstanceInfo::load(instance_id)?;
+    if let Some(network) = info.network {
+        return Err(ChainError::Config(format!(
+            "Instance '{}' is attached to {} and cannot be reset",
+            instance_id, network
+        )));
+    }
+    let client = SolanaRpcClient::new(info.rpc_url.clone());
+    if !client.is_validator_running() {
+        return Err(ChainError::NotRunning);
+    }
+
+    let config = SolanaConfig::with_instance(instance_id);
+    let faucet = SolanaAccount::from_keypair_file(
+        &config
+            .instance_dir()
+            .join("test-ledger")
+            .join("faucet-keypair.json"),
+    )?;
+
+    let storage = AccountsStorage::with_path(config.accounts_file());
+    let mut accounts = storage.load()?;
+
+    info!(
+        accounts = accounts.len(),
+        balance, "Resetting account balances (SOL)"
+    );
+    for account in &accounts {
+        if !account.external_signer {
+            client.drain_excess(account, balance, &faucet.public_key)?;
+        }
+        client.set_balance(&account.public_key, balance)?;
+    }
+    publish(
+        instance_id,
+        ChainEvent::FundsDispensed {
+            address: None,
+            amount: accounts.len() as f64 * balance,
+        },
+    );
+
+    client.update_balances(&mut accounts)?;
+    storage.save(&accounts)?;
+    for account in &accounts {
+        publish(
+            instance_id,
+            ChainEvent::BalanceChanged {
+                address: account.public_key.clone(),
+                balance: account.balance,
+            },
+        );
+    }
+
+    info.epoch += 1;
+    info.save()?;
+
+    Ok(accounts)
+}
+
+/// Publish an event for an instance
+fn publish(instance_id: &str, event: ChainEvent) {
+    chain_forge_events::publish(ChainType::Solana, instance_id, event);
+}
@@ -0,0 +1,203 @@
+//! Ledger snapshots for deterministic test replays.
+//!
+//! A snapshot is a `tar.gz` of an instance's `test-ledger` directory and
+//! `accounts.json`, taken while the validator is paused at a known slot.
+//! Snapshots live under `~/.chain-forge/solana/snapshots/<name>/`.
+
+use chain_forge_common::{ChainError, Result};
+use chain_forge_config::Config;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ARCHIVE_FILE: &str = "ledger.tar.gz";
+const METADATA_FILE: &str = "snapshot.json";
+
+/// Metadata stored next to a snapshot archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaSnapshot {
+    /// Snapshot name
+    pub name: String,
+    /// Instance the snapshot was taken from
+    pub source_instance: String,
+    /// Slot the validator had reached when it was paused
+    pub slot: u64,
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+}
+
+impl SolanaSnapshot {
+    /// Directory holding all snapshots
+    pub fn snapshots_dir() -> PathBuf {
+        Config::data_dir().join("solana").join("snapshots")
+    }
+
+    /// Directory for a named snapshot
+    pub fn dir(name: &str) -> PathBuf {
+        Self::snapshots_dir().join(name)
+    }
+
+    /// Path of the ledger archive
+    pub fn archive_path(&self) -> PathBuf {
+        Self::dir(&self.name).join(ARCHIVE_FILE)
+    }
+
+    /// Load snapshot metadata by name
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::dir(name).join(METADATA_FILE);
+        if !path.exists() {
+            return Err(ChainError::Other(format!("Snapshot '{}' not found", name)));
+        }
+
+        let json = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// All saved snapshots, oldest first
+    pub fn list() -> Result<Vec<Self>> {
+        let dir = Self::snapshots_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if let Some(name) = entry.file_name().to_str() {
+                // Skip directories left behind by a failed create
+                if let Ok(snapshot) = Self::load(name) {
+                    snapshots.push(snapshot);
+                }
+            }
+        }
+        snapshots.sort_by_key(|s| s.created_at);
+        Ok(snapshots)
+    }
+
+    /// Archive an instance directory's ledger and accounts into a new snapshot
+    pub fn create(
+        name: &str,
+        source_instance: &str,
+        slot: u64,
+        instance_dir: &Path,
+    ) -> Result<Self> {
+        let dir = Self::dir(name);
+        if dir.exists() {
+            return Err(ChainError::Other(format!(
+                "Snapshot '{}' already exists",
+                name
+            )));
+        }
+        std::fs::create_dir_all(&dir)?;
+
+        let snapshot = Self {
+            name: name.to_string(),
+            source_instance: source_instance.to_string(),
+            slot,
+            created_at: Utc::now(),
+        };
+
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(snapshot.archive_path())
+            .arg("-C")
+            .arg(instance_dir)
+            .arg("test-ledger")
+            .arg("accounts.json")
+            .status()
+            .map_err(|e| ChainError::Other(format!("Failed to run tar: {}", e)))?;
+
+        if !status.success() {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(ChainError::Other(format!(
+                "Failed to archive ledger (tar exited with {})",
+                status
+            )));
+        }
+
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        std::fs::write(dir.join(METADATA_FILE), json)?;
+
+        Ok(snapshot)
+    }
+
+    /// Extract the snapshot's ledger and accounts into an instance directory
+    pub fn restore(&self, instance_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(instance_dir)?;
+
+        let status = Command::new("tar")
+            .arg("-xzf")
+            .arg(self.archive_path())
+            .arg("-C")
+            .arg(instance_dir)
+            .status()
+            .map_err(|e| ChainError::Other(format!("Failed to run tar: {}", e)))?;
+
+        if !status.success() {
+            return Err(ChainError::Other(format!(
+                "Failed to restore snapshot '{}' (tar exited with {})",
+                self.name, status
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Pause a process (SIGSTOP) so its on-disk state stops changing
+#[cfg(unix)]
+pub fn pause_process(pid: u32) -> Result<()> {
+    signal_process(pid, "-STOP")
+}
+
+/// Resume a process paused with [`pause_process`] (SIGCONT)
+#[cfg(unix)]
+pub fn resume_process(pid: u32) -> Result<()> {
+    signal_process(pid, "-CONT")
+}
+
+/// Ask a process to exit (SIGTERM), or kill it (SIGKILL) if `force`
+#[cfg(unix)]
+pub fn stop_process(pid: u32, force: bool) -> Result<()> {
+    signal_process(pid, if force { "-KILL" } else { "-TERM" })
+}
+
+#[cfg(unix)]
+fn signal_process(pid: u32, signal: &str) -> Result<()> {
+    let status = Command::new("kill")
+        .arg(signal)
+        .arg(pid.to_string())
+        .status()
+        .map_err(|e| ChainError::NodeManagement(format!("Failed to signal validator: {}", e)))?;
+
+    if !status.success() {
+        return Err(ChainError::NodeManagement(format!(
+            "Failed to send {} to validator process {}",
+            signal, pid
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn pause_process(_pid: u32) -> Result<()> {
+    Err(ChainError::NodeManagement(
+        "Pausing the validator is only supported on Unix".to_string(),
+    ))
+}
+
+#[cfg(not(unix))]
+pub fn resume_process(_pid: u32) -> Result<()> {
+    Err(ChainError::NodeManagement(
+        "Resuming the validator is only supported on Unix".to_string(),
+    ))
+}
+
+#[cfg(not(unix))]
+pub fn stop_process(_pid: u32, _force: bool) -> Result<()> {
+    Err(ChainError::NodeManagement(
+        "Stopping the validator by process ID is only supported on Unix".to_string(),
+    ))
+}
@@ -0,0 +1,161 @@
+//! Fast-forward a running instance to a later slot.
+//!
+//! `solana-test-validator` can only warp its ledger as it starts, so a warp
+//! restarts the validator on the same ledger with `--warp-slot`. Accounts,
+//! programs, and balances are kept, while the slot (and with it the epoch
+//! and the `Clock` sysvar) jumps ahead. Settings fixed when the ledger was
+//! created, like the epoch length and inflation, stay as they were.
+//!
+//! The new validator is not a child of the process that started the
+//! instance. Its PID replaces the old one in `instance.json`, which is how
+//! stopping the instance finds it.
+
+use crate::snapshot::stop_process;
+use crate::{validator_command, SolanaConfig, SolanaInstanceInfo, ValidatorPorts};
+use chain_forge_common::{ChainError, ChainType, NodeRegistry, NodeStatus, Result};
+use chain_forge_solana_rpc::{EpochProgress, SolanaRpcClient};
+use std::net::TcpListener;
+use std::process::Child;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How long the old validator gets to exit, and the new one to reach the slot
+const WARP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where to warp an instance to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarpTarget {
+    Slot(u64),
+    /// First slot of the epoch
+    Epoch(u64),
+}
+
+/// Restart an instance's validator at a later slot
+///
+/// Returns the validator's position in the epoch schedule once it is
+/// serving RPC at the new slot.
+pub fn warp_instance(instance_id: &str, target: WarpTarget) -> Result<EpochProgress> {
+    let mut info = SolanaInstanceInfo::load(instance_id)?;
+    if let Some(network) = info.network {
+        return Err(ChainError::Config(format!(
+            "Instance '{}' is attached to {} and cannot be warped",
+            instance_id, network
+        )));
+    }
+    let client = SolanaRpcClient::new(info.rpc_url.clone());
+    if !client.is_validator_running() {
+        return Err(ChainError::NotRunning);
+    }
+    let (Some(pid), Some(ports)) = (info.pid, info.ports) else {
+        return Err(ChainError::NodeManagement(format!(
+            "Validator process of instance '{}' is unknown. Restart it with 'cf-solana start --instance {}'",
+            instance_id, instance_id
+        )));
+    };
+
+    let current = client.get_epoch_info()?;
+    let slot = match target {
+        WarpTarget::Slot(slot) => slot,
+        WarpTarget::Epoch(epoch) => client.get_first_slot_in_epoch(epoch)?,
+    };
+    if slot <= current.absolute_slot {
+        return Err(ChainError::Config(format!(
+            "Instance '{}' is already at slot {}; it can only be warped forward",
+            instance_id, current.absolute_slot
+        )));
+    }
+
+    info!(
+        instance = %instance_id,
+        from = current.absolute_slot,
+        to = slot,
+        "Warping validator"
+    );
+    stop_validator(pid, &ports)?;
+
+    let config = SolanaConfig::with_instance(instance_id);
+    let mut cmd = validator_command(&ports, &config.instance_dir())?;
+    cmd.arg("--warp-slot").arg(slot.to_string());
+    // A new process group keeps Ctrl+C in the caller's terminal away from it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| ChainError::NodeManagement(format!("Failed to start validator: {}", e)))?;
+
+    info.pid = Some(child.id());
+    info.save()?;
+    // The old validator's exit may already have marked the node stopped
+    let registry = NodeRegistry::new();
+    let node_id = NodeRegistry::node_id(ChainType::Solana, instance_id);
+    if let Err(e) = registry
+        .set_node_pid(&node_id, child.id())
+        .and_then(|()| registry.update_status(&node_id, NodeStatus::Running))
+    {
+        warn!(error = %e, "Failed to record the new validator in the node registry");
+    }
+
+    wait_for_warp(&client, &mut child, slot, &config)
+}
+
+/// Stop a validator started outside this process, waiting for its ports
+/// to be released
+pub(crate) fn stop_validator(pid: u32, ports: &ValidatorPorts) -> Result<()> {
+    stop_process(pid, false)?;
+
+    let deadline = Instant::now() + WARP_TIMEOUT;
+    while !ports_free(ports) {
+        if Instant::now() >= deadline {
+            warn!(pid, "Validator did not exit in time, killing it");
+            stop_process(pid, true)?;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Ok(())
+}
+
+/// Whether the validator's TCP ports can be bound again
+fn ports_free(ports: &ValidatorPorts) -> bool {
+    [ports.rpc, ports.faucet]
+        .into_iter()
+        .all(|port| TcpListener::bind(("0.0.0.0", port)).is_ok())
+}
+
+/// Wait until the restarted validator serves RPC at `slot` or later
+fn wait_for_warp(
+    client: &SolanaRpcClient,
+    child: &mut Child,
+    slot: u64,
+    config: &SolanaConfig,
+) -> Result<EpochProgress> {
+    let deadline = Instant::now() + WARP_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Err(ChainError::NodeManagement(format!(
+                "Validator exited while warping ({}). Check logs at: {}",
+                status,
+                config.log_file().display()
+            )));
+        }
+
+        if let Ok(progress) = client.get_epoch_info() {
+            if progress.absolute_slot >= slot {
+                return Ok(progress);
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(ChainError::NodeManagement(format!(
+                "Validator did not reach slot {} within {}s. Check logs at: {}",
+                slot,
+                WARP_TIMEOUT.as_secs(),
+                config.log_file().display()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,240 @@
+//! Human-readable breakdowns of serialized transactions and messages.
+//!
+//! Accepts the base64 or base58 wire format that wallets and
+//! `simulateTransaction` use, so payloads can be pasted in as they are.
+//! Decoding is offline; nothing is looked up on the validator, and accounts
+//! loaded from address lookup tables are shown by their table position.
+
+use base64::Engine;
+use chain_forge_common::{ChainError, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::transaction::VersionedTransaction;
+
+/// An account key of a message and how the message uses it
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedAccount {
+    pub pubkey: String,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+/// An instruction with its program and accounts resolved
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    /// Name of well-known programs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<&'static str>,
+    /// Account keys, or `lookup:<n>` for the n-th account loaded from the
+    /// message's address lookup tables
+    pub accounts: Vec<String>,
+    /// Instruction data as hex
+    pub data: String,
+}
+
+/// Accounts a v0 message loads from an address lookup table
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedLookup {
+    pub table: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// A decoded message
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedMessage {
+    /// `legacy` or `v0`
+    pub version: &'static str,
+    pub num_required_signatures: u8,
+    pub account_keys: Vec<DecodedAccount>,
+    pub recent_blockhash: String,
+    pub instructions: Vec<DecodedInstruction>,
+    pub address_table_lookups: Vec<DecodedLookup>,
+}
+
+/// A decoded transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedTransaction {
+    /// Signatures in signer order; unsigned slots are zero (`1111...`)
+    pub signatures: Vec<String>,
+    pub message: DecodedMessage,
+}
+
+/// Decode a serialized transaction (base64 or base58)
+pub fn decode_transaction(data: &str) -> Result<DecodedTransaction> {
+    let transaction: VersionedTransaction = deserialize(data, "transaction")?;
+    Ok(DecodedTransaction {
+        signatures: transaction
+            .signatures
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        message: describe(&transaction.message),
+    })
+}
+
+/// Decode a serialized message (base64 or base58)
+pub fn decode_message(data: &str) -> Result<DecodedMessage> {
+    let message: VersionedMessage = deserialize(data, "message")?;
+    Ok(describe(&message))
+}
+
+/// Decode `data` as base64, then base58, keeping the first that parses
+///
+/// The base58 alphabet is a subset of base64's, so a base58 payload can
+/// also decode as base64; it then fails to deserialize and base58 is tried.
+fn deserialize<T: DeserializeOwned>(data: &str, what: &str) -> Result<T> {
+    let data = data.trim();
+    let candidates = [
+        base64::engine::general_purpose::STANDARD.decode(data).ok(),
+        bs58::decode(data).into_vec().ok(),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|bytes| bincode::deserialize(&bytes).ok())
+        .ok_or_else(|| ChainError::Other(format!("Not a base64 or base58 encoded {}", what)))
+}
+
+fn describe(message: &VersionedMessage) -> DecodedMessage {
+    let header = message.header();
+    let keys = message.static_account_keys();
+    let signers = header.num_required_signatures as usize;
+    let writable_signers = signers.saturating_sub(header.num_readonly_signed_accounts as usize);
+    let writable_unsigned = keys
+        .len()
+        .saturating_sub(header.num_readonly_unsigned_accounts as usize);
+
+    let account_keys = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| DecodedAccount {
+            pubkey: key.to_string(),
+            signer: i < signers,
+            writable: if i < signers {
+                i < writable_signers
+            } else {
+                i < writable_unsigned
+            },
+        })
+        .collect();
+
+    let account = |index: u8| match keys.get(index as usize) {
+        Some(key) => key.to_string(),
+        None => format!("lookup:{}", index as usize - keys.len()),
+    };
+    let instructions = message
+        .instructions()
+        .iter()
+        .map(|ix| {
+            let program_id = account(ix.program_id_index);
+            DecodedInstruction {
+                program: program_name(&program_id),
+                program_id,
+                accounts: ix.accounts.iter().map(|&i| account(i)).collect(),
+                data: ix.data.iter().map(|b| format!("{:02x}", b)).collect(),
+            }
+        })
+        .collect();
+
+    let address_table_lookups = message
+        .address_table_lookups()
+        .unwrap_or_default()
+        .iter()
+        .map(|lookup| DecodedLookup {
+            table: lookup.account_key.to_string(),
+            writable_indexes: lookup.writable_indexes.clone(),
+            readonly_indexes: lookup.readonly_indexes.clone(),
+        })
+        .collect();
+
+    DecodedMessage {
+        version: match message {
+            VersionedMessage::Legacy(_) => "legacy",
+            VersionedMessage::V0(_) => "v0",
+        },
+        num_required_signatures: header.num_required_signatures,
+        account_keys,
+        recent_blockhash: message.recent_blockhash().to_string(),
+        instructions,
+        address_table_lookups,
+    }
+}
+
+/// Name of a well-known program
+fn program_name(program_id: &str) -> Option<&'static str> {
+    match program_id {
+        "11111111111111111111111111111111" => Some("System Program"),
+        "ComputeBudget111111111111111111111111111111" => Some("Compute Budget Program"),
+        "AddressLookupTab1e1111111111111111111111111" => Some("Address Lookup Table Program"),
+        "Stake11111111111111111111111111111111111111" => Some("Stake Program"),
+        "Vote111111111111111111111111111111111111111" => Some("Vote Program"),
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => Some("Token Program"),
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PUnBqCXEpPxuEb" => Some("Token-2022 Program"),
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => Some("Associated Token Account Program"),
+        "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr" => Some("Memo Program"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::Message;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::Transaction;
+
+    fn transfer_message() -> (Message, Pubkey, Pubkey) {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&from, &to, 1_000);
+        let message = Message::new_with_blockhash(&[instruction], Some(&from), &Hash::default());
+        (message, from, to)
+    }
+
+    #[test]
+    fn test_decode_transaction_base64() {
+        let (message, from, to) = transfer_message();
+        let transaction = Transaction::new_unsigned(message);
+        let data = base64::engine::general_purpose::STANDARD
+            .encode(bincode::serialize(&transaction).unwrap());
+
+        let decoded = decode_transaction(&data).unwrap();
+        assert_eq!(decoded.signatures.len(), 1);
+        assert_eq!(decoded.message.version, "legacy");
+
+        let keys = &decoded.message.account_keys;
+        assert_eq!(keys[0].pubkey, from.to_string());
+        assert!(keys[0].signer && keys[0].writable);
+        assert_eq!(keys[1].pubkey, to.to_string());
+        assert!(!keys[1].signer && keys[1].writable);
+        assert!(!keys[2].writable); // System Program
+
+        let instruction = &decoded.message.instructions[0];
+        assert_eq!(instruction.program, Some("System Program"));
+        assert_eq!(instruction.accounts, vec![from.to_string(), to.to_string()]);
+        // Transfer discriminant 2, then 1000 lamports, little-endian
+        assert_eq!(instruction.data, "02000000e803000000000000");
+    }
+
+    #[test]
+    fn test_decode_message_base58() {
+        let (message, _, _) = transfer_message();
+        let data = bs58::encode(bincode::serialize(&message).unwrap()).into_string();
+
+        let decoded = decode_message(&data).unwrap();
+        assert_eq!(decoded.num_required_signatures, 1);
+        assert_eq!(decoded.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode_transaction("not a transaction").is_err());
+        assert!(decode_message("").is_err());
+    }
+}
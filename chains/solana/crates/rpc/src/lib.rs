@@ -1,14 +1,53 @@
-use chain_forge_common::{ChainError, Result};
-use chain_forge_solana_accounts::SolanaAccount;
-use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
-use solana_client::rpc_config::RpcTransactionConfig;
+use async_trait::async_trait;
+use chain_forge_common::rpc_cassette::{self, RecordedError};
+use chain_forge_common::{rpc_metrics, ChainError, Result, RetryPolicy, Signer};
+use chain_forge_solana_accounts::{LocalSigner, SolanaAccount};
+use solana_client::client_error::{ClientErrorKind, Result as ClientResult};
+use solana_client::http_sender::HttpSender;
+use solana_client::nonce_utils;
+use solana_client::rpc_client::{
+    GetConfirmedSignaturesForAddress2Config, RpcClient, RpcClientConfig,
+};
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_sdk::address_lookup_table::{
+    self, state::AddressLookupTable, AddressLookupTableAccount,
+};
 use solana_sdk::{
-    commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
-    signature::Signature,
+    commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction, hash::Hash,
+    instruction::Instruction, message::Message, native_token::LAMPORTS_PER_SOL, nonce,
+    pubkey::Pubkey, signature::Keypair, signature::Signature, system_instruction,
+    transaction::Transaction,
 };
-use solana_transaction_status_client_types::UiTransactionEncoding;
+use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+pub mod decode;
+pub mod pay;
+pub mod token;
+
+pub use decode::{decode_message, decode_transaction, DecodedMessage, DecodedTransaction};
+pub use token::token_account_address;
+
+/// Where the validator is within the current epoch
+#[derive(Debug, Clone)]
+pub struct EpochProgress {
+    pub epoch: u64,
+    /// Slot within the epoch
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: u64,
+}
+
+impl EpochProgress {
+    /// Slots left until the next epoch boundary
+    pub fn slots_remaining(&self) -> u64 {
+        self.slots_in_epoch.saturating_sub(self.slot_index)
+    }
+}
 
 /// A simplified transaction signature info suitable for API serialization
 #[derive(Debug, Clone)]
@@ -41,6 +80,157 @@ pub struct TransactionDetail {
     pub balance_changes: Vec<BalanceChange>,
 }
 
+/// A block produced in a slot
+#[derive(Debug, Clone)]
+pub struct SolanaBlock {
+    pub slot: u64,
+    pub blockhash: String,
+    pub parent_slot: u64,
+    pub previous_blockhash: String,
+    /// Blocks before this one; slots skipped without a block don't count
+    pub block_height: Option<u64>,
+    pub block_time: Option<i64>,
+    pub tx_count: u64,
+    /// Transaction signatures, in block order
+    pub signatures: Vec<String>,
+}
+
+/// Prioritization fee paid in a recent slot (from `getRecentPrioritizationFees`)
+#[derive(Debug, Clone)]
+pub struct PrioritizationFee {
+    pub slot: u64,
+    /// Compute unit price in micro-lamports
+    pub prioritization_fee: u64,
+}
+
+/// An address lookup table and its state
+#[derive(Debug, Clone)]
+pub struct LookupTableInfo {
+    pub address: String,
+    /// `None` once the table is frozen
+    pub authority: Option<String>,
+    pub addresses: Vec<String>,
+    /// Slot the table was deactivated in, if it was
+    pub deactivation_slot: Option<u64>,
+    pub last_extended_slot: u64,
+}
+
+/// Addresses added per extend transaction, keeping each one under the
+/// packet size limit
+pub const LOOKUP_TABLE_EXTEND_CHUNK: usize = 20;
+
+/// Priority fee settings attached to generated transactions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityFee {
+    /// Compute unit price in micro-lamports
+    pub compute_unit_price: u64,
+    /// Compute unit limit (the runtime default is used if not set)
+    pub compute_unit_limit: Option<u32>,
+}
+
+impl PriorityFee {
+    /// Compute budget instructions to prepend to a transaction
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(limit) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+        }
+        if self.compute_unit_price > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                self.compute_unit_price,
+            ));
+        }
+        instructions
+    }
+}
+
+/// Fee at the given percentile (0-100) of a set of prioritization fees
+///
+/// Returns 0 when there are no fees.
+pub fn fee_percentile(fees: &[PrioritizationFee], percentile: u8) -> u64 {
+    let mut values: Vec<u64> = fees.iter().map(|f| f.prioritization_fee).collect();
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    let rank = (percentile.min(100) as usize * (values.len() - 1)) / 100;
+    values[rank]
+}
+
+/// Instructions adding `addresses` to a lookup table, one per
+/// [`LOOKUP_TABLE_EXTEND_CHUNK`] addresses, paid for by the authority
+pub fn lookup_table_extend_instructions(
+    table: &Pubkey,
+    authority: &Pubkey,
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    addresses
+        .chunks(LOOKUP_TABLE_EXTEND_CHUNK)
+        .map(|chunk| {
+            address_lookup_table::instruction::extend_lookup_table(
+                *table,
+                *authority,
+                Some(*authority),
+                chunk.to_vec(),
+            )
+        })
+        .collect()
+}
+
+/// Parse a base58 public key
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    Pubkey::from_str(address).map_err(|e| ChainError::Rpc(format!("Invalid public key: {}", e)))
+}
+
+/// RPC sender that records every call in the RPC metrics, and records it
+/// to or replays it from the active RPC cassette
+struct MeteredSender {
+    inner: HttpSender,
+    endpoint: String,
+}
+
+#[async_trait]
+impl RpcSender for MeteredSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let method = request.to_string();
+        let cassette = rpc_cassette::active();
+        if let Some(cassette) = cassette.as_ref().filter(|c| c.is_replaying()) {
+            return cassette
+                .replay_call(&method, &params)
+                .map_err(|e| ClientErrorKind::Custom(e.message).into());
+        }
+        let recorded_params = cassette.as_ref().map(|_| params.clone());
+
+        let started = Instant::now();
+        let result = self.inner.send(request, params).await;
+        rpc_metrics::global().record(&self.endpoint, &method, started.elapsed(), result.is_ok());
+
+        if let (Some(cassette), Some(params)) = (cassette, recorded_params) {
+            let outcome = match &result {
+                Ok(value) => Ok(value.clone()),
+                Err(e) => Err(RecordedError {
+                    code: None,
+                    message: e.to_string(),
+                }),
+            };
+            cassette.record_call(&self.endpoint, &method, &params, &outcome);
+        }
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
 /// Wrapper around Solana RPC client
 pub struct SolanaRpcClient {
     client: RpcClient,
@@ -50,10 +240,13 @@ pub struct SolanaRpcClient {
 impl SolanaRpcClient {
     /// Create a new RPC client
     pub fn new(rpc_url: String) -> Self {
-        let client = RpcClient::new_with_timeout_and_commitment(
-            rpc_url.clone(),
-            Duration::from_secs(30),
-            CommitmentConfig::confirmed(),
+        let sender = MeteredSender {
+            inner: HttpSender::new_with_timeout(rpc_url.clone(), Duration::from_secs(30)),
+            endpoint: rpc_url.clone(),
+        };
+        let client = RpcClient::new_sender(
+            sender,
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
         );
 
         Self { client, rpc_url }
@@ -79,7 +272,7 @@ impl SolanaRpcClient {
             tokio::time::sleep(Duration::from_millis(500)).await;
 
             if attempt == max_attempts {
-                return Err(ChainError::NodeManagement(
+                return Err(ChainError::Timeout(
                     "Validator did not start in time".to_string(),
                 ));
             }
@@ -88,6 +281,94 @@ impl SolanaRpcClient {
         Ok(())
     }
 
+    /// Get the current slot
+    pub fn get_slot(&self) -> Result<u64> {
+        self.client
+            .get_slot()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get slot: {}", e)))
+    }
+
+    /// Get the Unix time `slot` was produced at
+    pub fn get_block_time(&self, slot: u64) -> Result<i64> {
+        self.client
+            .get_block_time(slot)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get block time: {}", e)))
+    }
+
+    /// Number of other validators in the cluster
+    pub fn get_peer_count(&self) -> Result<usize> {
+        self.client
+            .get_cluster_nodes()
+            .map(|nodes| nodes.len().saturating_sub(1))
+            .map_err(|e| ChainError::Rpc(format!("Failed to get cluster nodes: {}", e)))
+    }
+
+    /// Wait until the validator reaches `slot`, returning the slot observed
+    pub async fn wait_for_slot(&self, slot: u64, timeout: Duration) -> Result<u64> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let current = self.get_slot()?;
+            if current >= slot {
+                return Ok(current);
+            }
+
+            if std::time::Instant::now() >= deadline {
+                return Err(ChainError::Timeout(format!(
+                    "Validator did not reach slot {} in time (current slot: {})",
+                    slot, current
+                )));
+            }
+
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Get the current epoch and progress through it
+    pub fn get_epoch_info(&self) -> Result<EpochProgress> {
+        let info = self
+            .client
+            .get_epoch_info()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get epoch info: {}", e)))?;
+
+        Ok(EpochProgress {
+            epoch: info.epoch,
+            slot_index: info.slot_index,
+            slots_in_epoch: info.slots_in_epoch,
+            absolute_slot: info.absolute_slot,
+        })
+    }
+
+    /// Get the total inflation rate for the current epoch
+    pub fn get_inflation_rate(&self) -> Result<f64> {
+        let rate = self
+            .client
+            .get_inflation_rate()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get inflation rate: {}", e)))?;
+
+        Ok(rate.total)
+    }
+
+    /// First slot of `epoch` in the validator's epoch schedule
+    pub fn get_first_slot_in_epoch(&self, epoch: u64) -> Result<u64> {
+        let schedule = self
+            .client
+            .get_epoch_schedule()
+            .map_err(|e| ChainError::Rpc(format!("Failed to get epoch schedule: {}", e)))?;
+
+        Ok(schedule.get_first_slot_in_epoch(epoch))
+    }
+
+    /// Wait until the validator enters `epoch`
+    ///
+    /// This waits for the epoch's first slot as the validator produces
+    /// slots; with a short epoch schedule (`--slots-per-epoch`) that takes
+    /// seconds. Warping the instance gets there without waiting.
+    pub async fn wait_for_epoch(&self, epoch: u64, timeout: Duration) -> Result<EpochProgress> {
+        let slot = self.get_first_slot_in_epoch(epoch)?;
+        self.wait_for_slot(slot, timeout).await?;
+        self.get_epoch_info()
+    }
+
     /// Get the balance of an account in SOL
     pub fn get_balance(&self, address: &str) -> Result<f64> {
         let pubkey = Pubkey::from_str(address)
@@ -151,6 +432,62 @@ impl SolanaRpcClient {
         ))
     }
 
+    /// Move an account's balance above `target_sol` to another address
+    ///
+    /// Airdrops can only add funds, so lowering a balance needs a transfer
+    /// signed by the account itself. The excess, less the transaction fee,
+    /// goes to `to_address` so the account ends exactly at `target_sol`.
+    /// Returns the signature, or `None` if there was nothing to move.
+    pub fn drain_excess(
+        &self,
+        account: &SolanaAccount,
+        target_sol: f64,
+        to_address: &str,
+    ) -> Result<Option<String>> {
+        let keypair = account.keypair()?;
+        let from = Pubkey::from_str(&account.public_key)
+            .map_err(|e| ChainError::Rpc(format!("Invalid public key: {}", e)))?;
+        let to = Pubkey::from_str(to_address)
+            .map_err(|e| ChainError::Rpc(format!("Invalid public key: {}", e)))?;
+
+        let current_lamports = self
+            .client
+            .get_balance(&from)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get balance: {}", e)))?;
+        let target_lamports = (target_sol * LAMPORTS_PER_SOL as f64) as u64;
+        if current_lamports <= target_lamports {
+            return Ok(None);
+        }
+        let excess = current_lamports - target_lamports;
+
+        let blockhash = self.get_latest_blockhash()?;
+        let mut message = Message::new(
+            &[system_instruction::transfer(&from, &to, excess)],
+            Some(&from),
+        );
+        message.recent_blockhash = blockhash;
+        let fee = self
+            .client
+            .get_fee_for_message(&message)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get fee: {}", e)))?;
+        if excess <= fee {
+            return Ok(None);
+        }
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(&from, &to, excess - fee)],
+            Some(&from),
+            &[&keypair],
+            blockhash,
+        );
+        let signature = self
+            .client
+            .send_and_confirm_transaction(&transaction)
+            .map_err(|e| ChainError::Rpc(format!("Failed to send transaction: {}", e)))?;
+
+        Ok(Some(signature.to_string()))
+    }
+
     /// Request an airdrop to an account (adds to existing balance)
     pub fn request_airdrop(&self, address: &str, amount_sol: f64) -> Result<String> {
         let pubkey = Pubkey::from_str(address)
@@ -175,44 +512,55 @@ impl SolanaRpcClient {
     ///
     /// This is the primary method for ensuring accounts have specific balances.
     /// Similar to Foundry/Anvil where you specify target balances upfront.
-    pub async fn set_balances(&self, accounts: &mut [SolanaAccount]) -> Result<()> {
-        for account in accounts.iter_mut() {
+    /// Airdrops that fail, e.g. when rate-limited, are retried per `retry`,
+    /// and each account's outcome is logged. `progress` is called with the
+    /// number of accounts handled so far after each one.
+    pub async fn set_balances(
+        &self,
+        accounts: &mut [SolanaAccount],
+        retry: &RetryPolicy,
+        mut progress: impl FnMut(usize),
+    ) -> Result<()> {
+        for (i, account) in accounts.iter_mut().enumerate() {
             let target_balance = account.balance;
 
-            // Retry logic for rate-limited airdrops
-            let mut retries = 3;
-            let mut success = false;
-
-            while retries > 0 && !success {
+            for attempt in 1..=retry.attempts() {
                 match self.set_balance(&account.public_key, target_balance) {
                     Ok(msg) => {
-                        // Balance is now set to target
-                        if msg.contains("Already") {
-                            // Balance was already sufficient
+                        let status = if msg.contains("Already") {
+                            "already_funded"
                         } else {
-                            // Balance was adjusted
                             account.balance = target_balance;
-                        }
-                        success = true;
+                            "funded"
+                        };
+                        debug!(account = %account.public_key, attempts = attempt, status, "Account balance set");
+                        break;
+                    }
+                    Err(e) if attempt < retry.attempts() => {
+                        let delay = retry.delay(attempt);
+                        warn!(
+                            account = %account.public_key,
+                            attempt,
+                            error = %e,
+                            delay_ms = delay.as_millis() as u64,
+                            "Failed to set balance, retrying"
+                        );
+                        tokio::time::sleep(delay).await;
                     }
                     Err(e) => {
-                        if retries > 1 {
-                            eprintln!(
-                                "Warning: Failed to set balance for {}: {}. Retrying in 2s...",
-                                account.public_key, e
-                            );
-                            tokio::time::sleep(Duration::from_secs(2)).await;
-                        } else {
-                            eprintln!(
-                                "Warning: Failed to set balance for {} after 3 attempts: {}",
-                                account.public_key, e
-                            );
-                        }
-                        retries -= 1;
+                        warn!(
+                            account = %account.public_key,
+                            attempts = attempt,
+                            status = "failed",
+                            error = %e,
+                            "Failed to set balance"
+                        );
                     }
                 }
             }
 
+            progress(i + 1);
+
             // Longer delay between accounts to avoid rate limiting
             tokio::time::sleep(Duration::from_millis(500)).await;
         }
@@ -236,10 +584,7 @@ impl SolanaRpcClient {
                     account.balance = amount_sol;
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to fund account {}: {}",
-                        account.public_key, e
-                    );
+                    warn!(account = %account.public_key, error = %e, "Failed to fund account");
                 }
             }
             // Small delay between airdrops to avoid rate limiting
@@ -257,10 +602,7 @@ impl SolanaRpcClient {
                     account.balance = balance;
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to get balance for {}: {}",
-                        account.public_key, e
-                    );
+                    warn!(account = %account.public_key, error = %e, "Failed to get balance");
                 }
             }
         }
@@ -268,6 +610,339 @@ impl SolanaRpcClient {
         Ok(())
     }
 
+    /// Transfer SOL from an account whose key is held by a [`Signer`]
+    ///
+    /// The transaction message is built locally and its serialized bytes are
+    /// passed to `signer`, so the key never has to be in this process. A
+    /// priority fee, if given, is attached as compute budget instructions.
+    ///
+    /// With `nonce_account`, the transaction uses that durable nonce instead
+    /// of a recent blockhash, so it stays valid until the nonce is advanced.
+    /// The signer must be the nonce authority.
+    pub fn transfer_with_signer(
+        &self,
+        to_address: &str,
+        amount_sol: f64,
+        signer: &dyn Signer,
+        priority_fee: Option<PriorityFee>,
+        nonce_account: Option<&str>,
+    ) -> Result<String> {
+        let from = parse_pubkey(signer.address())?;
+        let to = parse_pubkey(to_address)?;
+        let lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
+
+        let mut instructions = Vec::new();
+        let blockhash = match nonce_account {
+            Some(nonce_account) => {
+                // Advancing the nonce must be the first instruction
+                instructions.push(system_instruction::advance_nonce_account(
+                    &parse_pubkey(nonce_account)?,
+                    &from,
+                ));
+                self.get_nonce(nonce_account)?
+            }
+            None => self.get_latest_blockhash()?,
+        };
+        if let Some(fee) = priority_fee {
+            instructions.extend(fee.instructions());
+        }
+        instructions.push(system_instruction::transfer(&from, &to, lamports));
+
+        self.send_signed(&instructions, &from, blockhash, &[signer])
+    }
+
+    /// Create a durable nonce account with the signer's account as authority
+    ///
+    /// The signer pays the rent-exempt deposit. Returns the nonce account
+    /// address.
+    pub fn create_nonce_account(&self, authority: &dyn Signer) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let nonce_signer =
+            LocalSigner::from_account(&SolanaAccount::from_keypair(Keypair::new(), None, None))?;
+        let nonce_pubkey = parse_pubkey(nonce_signer.address())?;
+
+        let rent = self
+            .client
+            .get_minimum_balance_for_rent_exemption(nonce::State::size())
+            .map_err(|e| ChainError::Rpc(format!("Failed to get rent exemption: {}", e)))?;
+        let instructions = system_instruction::create_nonce_account(
+            &authority_pubkey,
+            &nonce_pubkey,
+            &authority_pubkey,
+            rent,
+        );
+
+        self.send_signed(
+            &instructions,
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority, &nonce_signer],
+        )?;
+        Ok(nonce_pubkey.to_string())
+    }
+
+    /// Get the blockhash currently stored in a nonce account
+    pub fn get_nonce(&self, nonce_account: &str) -> Result<Hash> {
+        let account = nonce_utils::get_account_with_commitment(
+            &self.client,
+            &parse_pubkey(nonce_account)?,
+            CommitmentConfig::confirmed(),
+        )
+        .map_err(|e| ChainError::Rpc(format!("Failed to get nonce account: {}", e)))?;
+        let data = nonce_utils::data_from_account(&account)
+            .map_err(|e| ChainError::Rpc(format!("Invalid nonce account: {}", e)))?;
+        Ok(data.blockhash())
+    }
+
+    /// Advance a nonce account to a new value, invalidating transactions
+    /// signed against the old one
+    pub fn advance_nonce(&self, nonce_account: &str, authority: &dyn Signer) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let instruction = system_instruction::advance_nonce_account(
+            &parse_pubkey(nonce_account)?,
+            &authority_pubkey,
+        );
+
+        self.send_signed(
+            &[instruction],
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )
+    }
+
+    /// Withdraw SOL from a nonce account to an address
+    ///
+    /// Withdrawing the whole balance closes the nonce account.
+    pub fn withdraw_nonce(
+        &self,
+        nonce_account: &str,
+        authority: &dyn Signer,
+        to_address: &str,
+        amount_sol: f64,
+    ) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let instruction = system_instruction::withdraw_nonce_account(
+            &parse_pubkey(nonce_account)?,
+            &authority_pubkey,
+            &parse_pubkey(to_address)?,
+            (amount_sol * LAMPORTS_PER_SOL as f64) as u64,
+        );
+
+        self.send_signed(
+            &[instruction],
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )
+    }
+
+    /// Create an address lookup table with the signer's account as
+    /// authority and payer
+    ///
+    /// Returns the table address. Tables can be used by v0 transactions once
+    /// they have been extended, starting from the slot after the extension.
+    pub fn create_lookup_table(&self, authority: &dyn Signer) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        // The table address is derived from a slot that must be in the
+        // SlotHashes sysvar, so use one that is already finalized
+        let recent_slot = self
+            .client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .map_err(|e| ChainError::Rpc(format!("Failed to get slot: {}", e)))?;
+        let (instruction, table) = address_lookup_table::instruction::create_lookup_table(
+            authority_pubkey,
+            authority_pubkey,
+            recent_slot,
+        );
+
+        self.send_signed(
+            &[instruction],
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )?;
+        Ok(table.to_string())
+    }
+
+    /// Add addresses to a lookup table
+    ///
+    /// Addresses are sent [`LOOKUP_TABLE_EXTEND_CHUNK`] at a time; returns
+    /// one signature per transaction.
+    pub fn extend_lookup_table(
+        &self,
+        table: &str,
+        authority: &dyn Signer,
+        addresses: &[String],
+    ) -> Result<Vec<String>> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let addresses = addresses
+            .iter()
+            .map(|address| parse_pubkey(address))
+            .collect::<Result<Vec<_>>>()?;
+
+        lookup_table_extend_instructions(&parse_pubkey(table)?, &authority_pubkey, &addresses)
+            .into_iter()
+            .map(|instruction| {
+                self.send_signed(
+                    &[instruction],
+                    &authority_pubkey,
+                    self.get_latest_blockhash()?,
+                    &[authority],
+                )
+            })
+            .collect()
+    }
+
+    /// Deactivate a lookup table so it can be closed after the cooldown
+    ///
+    /// Deactivated tables can't be extended or used by new transactions.
+    pub fn deactivate_lookup_table(&self, table: &str, authority: &dyn Signer) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let instruction = address_lookup_table::instruction::deactivate_lookup_table(
+            parse_pubkey(table)?,
+            authority_pubkey,
+        );
+
+        self.send_signed(
+            &[instruction],
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )
+    }
+
+    /// Close a deactivated lookup table and reclaim its rent
+    ///
+    /// Fails until the deactivation slot has left the SlotHashes sysvar,
+    /// about 513 slots after deactivating.
+    pub fn close_lookup_table(
+        &self,
+        table: &str,
+        authority: &dyn Signer,
+        recipient: &str,
+    ) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let instruction = address_lookup_table::instruction::close_lookup_table(
+            parse_pubkey(table)?,
+            authority_pubkey,
+            parse_pubkey(recipient)?,
+        );
+
+        self.send_signed(
+            &[instruction],
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )
+    }
+
+    /// Get a lookup table's authority, addresses, and activation state
+    pub fn get_lookup_table(&self, table: &str) -> Result<LookupTableInfo> {
+        let data = self
+            .client
+            .get_account_data(&parse_pubkey(table)?)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get lookup table: {}", e)))?;
+        let state = AddressLookupTable::deserialize(&data)
+            .map_err(|e| ChainError::Rpc(format!("Invalid lookup table: {}", e)))?;
+
+        Ok(LookupTableInfo {
+            address: table.to_string(),
+            authority: state.meta.authority.map(|key| key.to_string()),
+            addresses: state.addresses.iter().map(|key| key.to_string()).collect(),
+            deactivation_slot: (state.meta.deactivation_slot != u64::MAX)
+                .then_some(state.meta.deactivation_slot),
+            last_extended_slot: state.meta.last_extended_slot,
+        })
+    }
+
+    /// Load a lookup table in the form v0 messages are compiled with
+    ///
+    /// Pass the result to `v0::Message::try_compile` to build transactions
+    /// that reference the table.
+    pub fn get_lookup_table_account(&self, table: &str) -> Result<AddressLookupTableAccount> {
+        let key = parse_pubkey(table)?;
+        let data = self
+            .client
+            .get_account_data(&key)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get lookup table: {}", e)))?;
+        let state = AddressLookupTable::deserialize(&data)
+            .map_err(|e| ChainError::Rpc(format!("Invalid lookup table: {}", e)))?;
+
+        Ok(AddressLookupTableAccount {
+            key,
+            addresses: state.addresses.to_vec(),
+        })
+    }
+
+    /// Sign a transaction with each of `signers` and send it
+    ///
+    /// Every required signer of the message must be in `signers`.
+    fn send_signed(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        blockhash: Hash,
+        signers: &[&dyn Signer],
+    ) -> Result<String> {
+        let message = Message::new_with_blockhash(instructions, Some(payer), &blockhash);
+        let mut transaction = Transaction::new_unsigned(message);
+        let message_data = transaction.message_data();
+
+        let required = transaction.message.header.num_required_signatures as usize;
+        let mut signatures = Vec::with_capacity(required);
+        for key in &transaction.message.account_keys[..required] {
+            let signer = signers
+                .iter()
+                .find(|s| s.address() == key.to_string())
+                .ok_or_else(|| ChainError::Other(format!("Missing signature for {}", key)))?;
+            let bytes = signer.sign(&message_data)?;
+            signatures.push(
+                Signature::try_from(bytes.as_slice()).map_err(|e| {
+                    ChainError::Other(format!("Invalid signature from signer: {}", e))
+                })?,
+            );
+        }
+        transaction.signatures = signatures;
+
+        let signature = self
+            .client
+            .send_and_confirm_transaction(&transaction)
+            .map_err(|e| ChainError::Rpc(format!("Failed to send transaction: {}", e)))?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Get prioritization fees paid in recent slots
+    ///
+    /// If addresses are given, only transactions that write-lock all of them
+    /// are considered.
+    pub fn get_recent_prioritization_fees(
+        &self,
+        addresses: &[String],
+    ) -> Result<Vec<PrioritizationFee>> {
+        let pubkeys = addresses
+            .iter()
+            .map(|a| {
+                Pubkey::from_str(a)
+                    .map_err(|e| ChainError::Rpc(format!("Invalid public key: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let fees = self
+            .client
+            .get_recent_prioritization_fees(&pubkeys)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get prioritization fees: {}", e)))?;
+
+        Ok(fees
+            .into_iter()
+            .map(|f| PrioritizationFee {
+                slot: f.slot,
+                prioritization_fee: f.prioritization_fee,
+            })
+            .collect())
+    }
+
     /// Get the latest blockhash
     pub fn get_latest_blockhash(&self) -> Result<solana_sdk::hash::Hash> {
         self.client
@@ -399,6 +1074,70 @@ impl SolanaRpcClient {
         })
     }
 
+    /// Get the block produced in `slot`
+    ///
+    /// Fails if the slot was skipped or isn't confirmed yet.
+    pub fn get_block(&self, slot: u64) -> Result<SolanaBlock> {
+        let config = RpcBlockConfig {
+            encoding: None,
+            transaction_details: Some(TransactionDetails::Signatures),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let block = self
+            .client
+            .get_block_with_config(slot, config)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get block {}: {}", slot, e)))?;
+
+        let signatures = block.signatures.unwrap_or_default();
+        Ok(SolanaBlock {
+            slot,
+            blockhash: block.blockhash,
+            parent_slot: block.parent_slot,
+            previous_blockhash: block.previous_blockhash,
+            block_height: block.block_height,
+            block_time: block.block_time,
+            tx_count: signatures.len() as u64,
+            signatures,
+        })
+    }
+
+    /// Get up to `limit` confirmed blocks, newest first
+    ///
+    /// Starts at the latest confirmed slot, or below `before_slot` if given.
+    /// Skipped slots are passed over.
+    pub fn get_recent_blocks(
+        &self,
+        before_slot: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<SolanaBlock>> {
+        let mut end = match before_slot {
+            Some(0) => return Ok(Vec::new()),
+            Some(slot) => slot - 1,
+            None => self.get_slot()?,
+        };
+
+        // Find slots with blocks a window at a time, walking back from `end`
+        let window = (limit as u64).max(1) * 2;
+        let mut slots = Vec::new();
+        while slots.len() < limit {
+            let start = end.saturating_sub(window - 1);
+            let found = self
+                .client
+                .get_blocks(start, Some(end))
+                .map_err(|e| ChainError::Rpc(format!("Failed to get blocks: {}", e)))?;
+            slots.extend(found.into_iter().rev());
+            if start == 0 {
+                break;
+            }
+            end = start - 1;
+        }
+        slots.truncate(limit);
+
+        slots.into_iter().map(|slot| self.get_block(slot)).collect()
+    }
+
     /// Get the inner RPC client for advanced operations
     pub fn inner(&self) -> &RpcClient {
         &self.client
@@ -428,6 +1167,70 @@ mod tests {
         // Just verify we can get the inner client
     }
 
+    #[test]
+    fn test_epoch_slots_remaining() {
+        let progress = EpochProgress {
+            epoch: 3,
+            slot_index: 20,
+            slots_in_epoch: 32,
+            absolute_slot: 116,
+        };
+        assert_eq!(progress.slots_remaining(), 12);
+    }
+
+    #[test]
+    fn test_priority_fee_instructions() {
+        assert!(PriorityFee::default().instructions().is_empty());
+
+        let fee = PriorityFee {
+            compute_unit_price: 1_000,
+            compute_unit_limit: Some(200_000),
+        };
+        let instructions = fee.instructions();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0],
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000)
+        );
+        assert_eq!(
+            instructions[1],
+            ComputeBudgetInstruction::set_compute_unit_price(1_000)
+        );
+    }
+
+    #[test]
+    fn test_lookup_table_extend_instructions() {
+        let table = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..LOOKUP_TABLE_EXTEND_CHUNK * 2 + 1)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        let instructions = lookup_table_extend_instructions(&table, &authority, &addresses);
+        assert_eq!(instructions.len(), 3);
+        assert!(instructions
+            .iter()
+            .all(|ix| ix.program_id == address_lookup_table::program::id()));
+        assert!(lookup_table_extend_instructions(&table, &authority, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_fee_percentile() {
+        let fees: Vec<PrioritizationFee> = [0, 10, 20, 30, 1_000]
+            .iter()
+            .enumerate()
+            .map(|(slot, &fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee: fee,
+            })
+            .collect();
+
+        assert_eq!(fee_percentile(&fees, 0), 0);
+        assert_eq!(fee_percentile(&fees, 50), 20);
+        assert_eq!(fee_percentile(&fees, 100), 1_000);
+        assert_eq!(fee_percentile(&[], 50), 0);
+    }
+
     #[test]
     fn test_validator_running_check_no_server() {
         let client = SolanaRpcClient::new("http://localhost:19999".to_string());
@@ -0,0 +1,237 @@
+//! Solana Pay transfer requests and reference-key payment detection.
+//!
+//! Each request carries a fresh reference key. The paying wallet adds it to
+//! the transfer instruction as a read-only account, so the payment can be
+//! found with `getSignaturesForAddress` on the reference and then checked
+//! against the request, the way a Solana Pay merchant confirms an order.
+
+use crate::{parse_pubkey, SolanaRpcClient, TransactionSignatureInfo};
+use chain_forge_common::uri::{percent_decode, percent_encode};
+use chain_forge_common::{ChainError, Result, Signer};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer as _};
+use solana_sdk::{pubkey, pubkey::Pubkey, system_instruction};
+
+/// SPL Memo program, which Solana Pay memos are written with
+const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Signatures fetched per reference when looking for a payment
+const REFERENCE_SEARCH_LIMIT: usize = 1000;
+
+/// A Solana Pay transfer request for SOL
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferRequest {
+    pub recipient: String,
+    /// Amount in SOL
+    pub amount: f64,
+    /// Keys the payment must reference
+    pub references: Vec<String>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+    pub memo: Option<String>,
+}
+
+impl TransferRequest {
+    /// Request `amount` SOL for `recipient` with a fresh reference key
+    pub fn new(recipient: &str, amount: f64) -> Self {
+        Self {
+            recipient: recipient.to_string(),
+            amount,
+            references: vec![new_reference()],
+            label: None,
+            message: None,
+            memo: None,
+        }
+    }
+
+    /// The reference to look the payment up by
+    pub fn reference(&self) -> Option<&str> {
+        self.references.first().map(String::as_str)
+    }
+
+    /// The request as a `solana:` URL
+    pub fn url(&self) -> String {
+        let mut params = vec![format!("amount={}", self.amount)];
+        params.extend(
+            self.references
+                .iter()
+                .map(|reference| format!("reference={}", reference)),
+        );
+        for (name, value) in [
+            ("label", &self.label),
+            ("message", &self.message),
+            ("memo", &self.memo),
+        ] {
+            if let Some(value) = value {
+                params.push(format!("{}={}", name, percent_encode(value)));
+            }
+        }
+        format!("solana:{}?{}", self.recipient, params.join("&"))
+    }
+
+    /// Parse a `solana:` transfer request URL
+    pub fn parse(url: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| ChainError::Config(format!("Invalid Solana Pay URL: {}", reason));
+
+        let rest = url
+            .strip_prefix("solana:")
+            .ok_or_else(|| invalid("expected the solana: scheme"))?;
+        let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+        parse_pubkey(recipient).map_err(|_| invalid("recipient is not a public key"))?;
+
+        let mut request = Self {
+            recipient: recipient.to_string(),
+            amount: 0.0,
+            references: Vec::new(),
+            label: None,
+            message: None,
+            memo: None,
+        };
+        let mut amount = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value).ok_or_else(|| invalid("bad percent-encoding"))?;
+            match name {
+                "amount" => {
+                    amount = Some(
+                        value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|amount| amount.is_finite() && *amount >= 0.0)
+                            .ok_or_else(|| invalid("amount is not a decimal"))?,
+                    )
+                }
+                "reference" => {
+                    parse_pubkey(&value).map_err(|_| invalid("reference is not a public key"))?;
+                    request.references.push(value);
+                }
+                "spl-token" => return Err(invalid("only SOL transfers are supported")),
+                "label" => request.label = Some(value),
+                "message" => request.message = Some(value),
+                "memo" => request.memo = Some(value),
+                _ => {}
+            }
+        }
+        request.amount = amount.ok_or_else(|| invalid("missing amount"))?;
+        Ok(request)
+    }
+}
+
+/// A fresh random reference key
+pub fn new_reference() -> String {
+    Keypair::new().pubkey().to_string()
+}
+
+fn to_lamports(amount_sol: f64) -> u64 {
+    (amount_sol * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+impl SolanaRpcClient {
+    /// Pay a transfer request from the signer's account, the way a wallet
+    /// would: the memo, if any, comes first and the references are attached
+    /// to the transfer instruction
+    pub fn pay(&self, request: &TransferRequest, payer: &dyn Signer) -> Result<String> {
+        let from = parse_pubkey(payer.address())?;
+        let to = parse_pubkey(&request.recipient)?;
+
+        let mut instructions = Vec::new();
+        if let Some(memo) = &request.memo {
+            instructions.push(Instruction {
+                program_id: MEMO_PROGRAM_ID,
+                accounts: Vec::new(),
+                data: memo.as_bytes().to_vec(),
+            });
+        }
+        let mut transfer = system_instruction::transfer(&from, &to, to_lamports(request.amount));
+        for reference in &request.references {
+            transfer
+                .accounts
+                .push(AccountMeta::new_readonly(parse_pubkey(reference)?, false));
+        }
+        instructions.push(transfer);
+
+        let blockhash = self.get_latest_blockhash()?;
+        self.send_signed(&instructions, &from, blockhash, &[payer])
+    }
+
+    /// Oldest confirmed transaction that references `reference`, if any
+    pub fn find_reference(&self, reference: &str) -> Result<Option<TransactionSignatureInfo>> {
+        let mut signatures =
+            self.get_signatures_for_address(reference, Some(REFERENCE_SEARCH_LIMIT))?;
+        Ok(signatures.pop())
+    }
+
+    /// Check that a transaction succeeded and paid the request's recipient
+    /// at least the requested amount
+    pub fn validate_transfer(&self, signature: &str, request: &TransferRequest) -> Result<()> {
+        let tx = self.get_transaction(signature)?;
+        if let Some(err) = tx.err {
+            return Err(ChainError::Rpc(format!(
+                "Payment transaction {} failed: {}",
+                signature, err
+            )));
+        }
+
+        let received = tx
+            .balance_changes
+            .iter()
+            .find(|change| change.account == request.recipient)
+            .map_or(0.0, |change| change.change);
+        if received < 0.0 || to_lamports(received) < to_lamports(request.amount) {
+            return Err(ChainError::Rpc(format!(
+                "Payment transaction {} sent {} SOL to {}, expected {}",
+                signature,
+                received.max(0.0),
+                request.recipient,
+                request.amount
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+    #[test]
+    fn test_url_roundtrip() {
+        let mut request = TransferRequest::new(RECIPIENT, 1.5);
+        request.label = Some("Test Shop".to_string());
+        request.memo = Some("order#42".to_string());
+
+        let url = request.url();
+        assert!(url.starts_with(&format!("solana:{}?amount=1.5&reference=", RECIPIENT)));
+        assert!(url.contains("&label=Test%20Shop"));
+        assert!(url.contains("&memo=order%2342"));
+
+        assert_eq!(TransferRequest::parse(&url).unwrap(), request);
+    }
+
+    #[test]
+    fn test_new_references_are_unique() {
+        let a = TransferRequest::new(RECIPIENT, 1.0);
+        let b = TransferRequest::new(RECIPIENT, 1.0);
+        assert_ne!(a.reference(), b.reference());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_urls() {
+        assert!(TransferRequest::parse("bitcoin:abc?amount=1").is_err());
+        assert!(TransferRequest::parse(&format!("solana:{}", RECIPIENT)).is_err());
+        assert!(TransferRequest::parse(&format!("solana:{}?amount=-1", RECIPIENT)).is_err());
+        assert!(
+            TransferRequest::parse(&format!("solana:{}?amount=1&reference=nope", RECIPIENT))
+                .is_err()
+        );
+        assert!(TransferRequest::parse(&format!(
+            "solana:{}?amount=1&spl-token={}",
+            RECIPIENT, RECIPIENT
+        ))
+        .is_err());
+    }
+}
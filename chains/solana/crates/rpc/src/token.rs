@@ -0,0 +1,267 @@
+//! SPL token fixtures: mints, associated token accounts, minting, and transfers.
+//!
+//! Amounts are whole tokens and are converted to base units with the mint's
+//! decimals. Tokens always move between owners' associated token accounts,
+//! which are created on the way when they don't exist yet.
+
+use crate::{parse_pubkey, SolanaRpcClient};
+use chain_forge_common::{ChainError, Result, Signer};
+use chain_forge_solana_accounts::{LocalSigner, SolanaAccount};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::system_instruction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::state::{Account as TokenAccount, Mint};
+
+/// Token accounts created per transaction, keeping each one under the
+/// packet size limit
+pub const TOKEN_ACCOUNT_CHUNK: usize = 8;
+
+/// Convert a whole-token amount to base units
+pub fn to_base_units(amount: f64, decimals: u8) -> u64 {
+    (amount * 10f64.powi(decimals as i32)).round() as u64
+}
+
+/// Convert base units to a whole-token amount
+pub fn from_base_units(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Address of an owner's associated token account for a mint
+pub fn token_account_address(owner: &str, mint: &str) -> Result<String> {
+    Ok(get_associated_token_address(&parse_pubkey(owner)?, &parse_pubkey(mint)?).to_string())
+}
+
+/// Instruction creating an owner's associated token account if it's missing
+fn create_token_account_instruction(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    create_associated_token_account_idempotent(payer, owner, mint, &spl_token::id())
+}
+
+impl SolanaRpcClient {
+    /// Create a mint with the signer as mint authority and payer
+    ///
+    /// Returns the mint address.
+    pub fn create_mint(&self, authority: &dyn Signer, decimals: u8) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let mint_signer =
+            LocalSigner::from_account(&SolanaAccount::from_keypair(Keypair::new(), None, None))?;
+        let mint_pubkey = parse_pubkey(mint_signer.address())?;
+
+        let rent = self
+            .client
+            .get_minimum_balance_for_rent_exemption(Mint::LEN)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get rent exemption: {}", e)))?;
+        let instructions = [
+            system_instruction::create_account(
+                &authority_pubkey,
+                &mint_pubkey,
+                rent,
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint_pubkey,
+                &authority_pubkey,
+                None,
+                decimals,
+            )
+            .map_err(|e| ChainError::Other(format!("Invalid mint instruction: {}", e)))?,
+        ];
+
+        self.send_signed(
+            &instructions,
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority, &mint_signer],
+        )?;
+        Ok(mint_pubkey.to_string())
+    }
+
+    /// Create the associated token accounts of `owners` for a mint
+    ///
+    /// `payer` pays the rent. Accounts that already exist are left as they
+    /// are. Returns the token account addresses, in the order of `owners`.
+    pub fn create_token_accounts(
+        &self,
+        mint: &str,
+        payer: &dyn Signer,
+        owners: &[String],
+    ) -> Result<Vec<String>> {
+        let payer_pubkey = parse_pubkey(payer.address())?;
+        let mint = parse_pubkey(mint)?;
+        let owners = owners
+            .iter()
+            .map(|owner| parse_pubkey(owner))
+            .collect::<Result<Vec<_>>>()?;
+
+        for chunk in owners.chunks(TOKEN_ACCOUNT_CHUNK) {
+            let instructions: Vec<Instruction> = chunk
+                .iter()
+                .map(|owner| create_token_account_instruction(&payer_pubkey, owner, &mint))
+                .collect();
+            self.send_signed(
+                &instructions,
+                &payer_pubkey,
+                self.get_latest_blockhash()?,
+                &[payer],
+            )?;
+        }
+
+        Ok(owners
+            .iter()
+            .map(|owner| get_associated_token_address(owner, &mint).to_string())
+            .collect())
+    }
+
+    /// Mint tokens to an owner's associated token account
+    ///
+    /// The mint authority pays for the token account if it has to be created.
+    pub fn mint_tokens(
+        &self,
+        mint: &str,
+        authority: &dyn Signer,
+        owner: &str,
+        amount: f64,
+    ) -> Result<String> {
+        let authority_pubkey = parse_pubkey(authority.address())?;
+        let mint_pubkey = parse_pubkey(mint)?;
+        let owner = parse_pubkey(owner)?;
+        let decimals = self.get_mint_decimals(mint)?;
+        let token_account = get_associated_token_address(&owner, &mint_pubkey);
+
+        let instructions = [
+            create_token_account_instruction(&authority_pubkey, &owner, &mint_pubkey),
+            spl_token::instruction::mint_to_checked(
+                &spl_token::id(),
+                &mint_pubkey,
+                &token_account,
+                &authority_pubkey,
+                &[],
+                to_base_units(amount, decimals),
+                decimals,
+            )
+            .map_err(|e| ChainError::Other(format!("Invalid mint instruction: {}", e)))?,
+        ];
+
+        self.send_signed(
+            &instructions,
+            &authority_pubkey,
+            self.get_latest_blockhash()?,
+            &[authority],
+        )
+    }
+
+    /// Transfer tokens from the signer's associated token account to another
+    /// owner's
+    ///
+    /// The sender pays for the recipient's token account if it has to be
+    /// created.
+    pub fn transfer_tokens(
+        &self,
+        mint: &str,
+        from: &dyn Signer,
+        to_owner: &str,
+        amount: f64,
+    ) -> Result<String> {
+        let from_pubkey = parse_pubkey(from.address())?;
+        let mint_pubkey = parse_pubkey(mint)?;
+        let to_owner = parse_pubkey(to_owner)?;
+        let decimals = self.get_mint_decimals(mint)?;
+
+        let instructions = [
+            create_token_account_instruction(&from_pubkey, &to_owner, &mint_pubkey),
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &get_associated_token_address(&from_pubkey, &mint_pubkey),
+                &mint_pubkey,
+                &get_associated_token_address(&to_owner, &mint_pubkey),
+                &from_pubkey,
+                &[],
+                to_base_units(amount, decimals),
+                decimals,
+            )
+            .map_err(|e| ChainError::Other(format!("Invalid transfer instruction: {}", e)))?,
+        ];
+
+        self.send_signed(
+            &instructions,
+            &from_pubkey,
+            self.get_latest_blockhash()?,
+            &[from],
+        )
+    }
+
+    /// Get the number of decimals of a mint
+    pub fn get_mint_decimals(&self, mint: &str) -> Result<u8> {
+        let data = self
+            .client
+            .get_account_data(&parse_pubkey(mint)?)
+            .map_err(|e| ChainError::Rpc(format!("Failed to get mint: {}", e)))?;
+        let mint = Mint::unpack(&data)
+            .map_err(|e| ChainError::Rpc(format!("Invalid mint account: {}", e)))?;
+        Ok(mint.decimals)
+    }
+
+    /// Get an owner's token balance for a mint, in whole tokens
+    ///
+    /// Owners without an associated token account have a balance of 0.
+    pub fn get_token_balance(&self, mint: &str, owner: &str) -> Result<f64> {
+        let token_account = parse_pubkey(&token_account_address(owner, mint)?)?;
+        let account = self
+            .client
+            .get_account_with_commitment(&token_account, CommitmentConfig::confirmed())
+            .map_err(|e| ChainError::Rpc(format!("Failed to get token account: {}", e)))?
+            .value;
+
+        let Some(account) = account else {
+            return Ok(0.0);
+        };
+        let token_account = TokenAccount::unpack(&account.data)
+            .map_err(|e| ChainError::Rpc(format!("Invalid token account: {}", e)))?;
+        Ok(from_base_units(
+            token_account.amount,
+            self.get_mint_decimals(mint)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_units() {
+        assert_eq!(to_base_units(1.5, 6), 1_500_000);
+        assert_eq!(to_base_units(0.1, 9), 100_000_000);
+        assert_eq!(to_base_units(42.0, 0), 42);
+        assert_eq!(from_base_units(1_500_000, 6), 1.5);
+    }
+
+    #[test]
+    fn test_token_account_address() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let address = token_account_address(&owner.to_string(), &mint.to_string()).unwrap();
+        assert_eq!(
+            address,
+            get_associated_token_address(&owner, &mint).to_string()
+        );
+        assert!(token_account_address("not-a-key", &mint.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_create_token_account_instruction() {
+        let payer = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let instruction = create_token_account_instruction(&payer, &owner, &mint);
+        assert_eq!(instruction.program_id, spl_associated_token_account::id());
+        // Idempotent creation, so existing accounts don't fail the transaction
+        assert_eq!(instruction.data, vec![1]);
+    }
+}
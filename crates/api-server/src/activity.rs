@@ -0,0 +1,209 @@
+//! Time-bucketed activity counts for dashboard sparklines.
+//!
+//! Blocks mined and funds dispensed come from the local audit log;
+//! transaction times come from the node's recent history over RPC.
+
+use chain_forge_common::{AuditEntry, AuditEvent};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Most buckets a single request may return
+pub const MAX_BUCKETS: i64 = 1440;
+
+/// Buckets returned when no `since` is given
+pub const DEFAULT_BUCKETS: i64 = 60;
+
+/// Activity within one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ActivityBucket {
+    /// Start of the bucket (Unix seconds)
+    pub start: i64,
+    /// Confirmed transactions involving node accounts
+    pub transactions: u64,
+    /// Blocks mined
+    pub blocks: u64,
+    /// Total amount dispensed to accounts (SOL or BTC)
+    pub funded: f64,
+}
+
+/// Parse a bucket width such as `30s`, `1m`, `1h`, or `1d` into seconds
+pub fn parse_bucket(bucket: &str) -> Option<i64> {
+    let unit_start = bucket.len().checked_sub(1)?;
+    if !bucket.is_char_boundary(unit_start) {
+        return None;
+    }
+    let (value, unit) = bucket.split_at(unit_start);
+    let value: i64 = value.parse().ok()?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    if value <= 0 {
+        return None;
+    }
+    value.checked_mul(unit_secs)
+}
+
+/// Start of the window ending at `now`: `since`, or enough for
+/// [`DEFAULT_BUCKETS`] buckets
+///
+/// Returns `None` if the window starts in the future, spans more than
+/// [`MAX_BUCKETS`] buckets, or can't be computed without overflowing.
+pub fn window_start(since: Option<i64>, now: i64, bucket_secs: i64) -> Option<i64> {
+    let since = match since {
+        Some(since) => since,
+        None => now.checked_sub((DEFAULT_BUCKETS - 1).checked_mul(bucket_secs)?)?,
+    };
+    let span = now.checked_sub(since)?;
+    if span < 0 || span / bucket_secs >= MAX_BUCKETS {
+        return None;
+    }
+    // Buckets are counted from the aligned start, which must fit as well
+    let first = since.checked_sub(since.rem_euclid(bucket_secs))?;
+    now.checked_sub(first)?;
+    Some(since)
+}
+
+/// Count activity in fixed-width buckets from `since` up to `until`
+///
+/// Buckets are aligned to multiples of `bucket_secs`, returned oldest first,
+/// and include empty buckets so charts can plot them directly.
+pub fn bucket_activity(
+    audit_entries: &[AuditEntry],
+    transaction_times: &[i64],
+    bucket_secs: i64,
+    since: i64,
+    until: i64,
+) -> Vec<ActivityBucket> {
+    let first = since - since.rem_euclid(bucket_secs);
+    let count = ((until - first) / bucket_secs + 1).max(0) as usize;
+    let mut buckets: Vec<ActivityBucket> = (0..count)
+        .map(|i| ActivityBucket {
+            start: first + i as i64 * bucket_secs,
+            transactions: 0,
+            blocks: 0,
+            funded: 0.0,
+        })
+        .collect();
+
+    let index = |time: i64| -> Option<usize> {
+        if time < first || time > until {
+            return None;
+        }
+        Some(((time - first) / bucket_secs) as usize)
+    };
+
+    for time in transaction_times {
+        if let Some(bucket) = index(*time).and_then(|i| buckets.get_mut(i)) {
+            bucket.transactions += 1;
+        }
+    }
+
+    for entry in audit_entries {
+        let Some(bucket) = index(entry.timestamp.timestamp()).and_then(|i| buckets.get_mut(i))
+        else {
+            continue;
+        };
+        match &entry.event {
+            AuditEvent::BlocksMined { count } => bucket.blocks += count,
+            AuditEvent::FundsDispensed { amount, .. } => bucket.funded += amount,
+            AuditEvent::NodeStarted | AuditEvent::NodeStopped => {}
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_forge_common::ChainType;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(timestamp: i64, event: AuditEvent) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc.timestamp_opt(timestamp, 0).unwrap(),
+            chain: ChainType::Bitcoin,
+            instance_id: "dev".to_string(),
+            event,
+        }
+    }
+
+    #[test]
+    fn test_parse_bucket() {
+        assert_eq!(parse_bucket("30s"), Some(30));
+        assert_eq!(parse_bucket("1m"), Some(60));
+        assert_eq!(parse_bucket("2h"), Some(7200));
+        assert_eq!(parse_bucket("1d"), Some(86400));
+        assert_eq!(parse_bucket("0m"), None);
+        assert_eq!(parse_bucket("1w"), None);
+        assert_eq!(parse_bucket("m"), None);
+        assert_eq!(parse_bucket(""), None);
+        assert_eq!(parse_bucket("1é"), None);
+        // Too wide to count in seconds
+        assert_eq!(parse_bucket("999999999999999d"), None);
+        assert_eq!(parse_bucket("9223372036854775807s"), Some(i64::MAX));
+    }
+
+    #[test]
+    fn test_window_start() {
+        let now = 1_000_000;
+        assert_eq!(window_start(None, now, 60), Some(now - 59 * 60));
+        assert_eq!(window_start(Some(now - 120), now, 60), Some(now - 120));
+        assert_eq!(window_start(Some(now + 1), now, 60), None);
+        assert_eq!(window_start(Some(now - MAX_BUCKETS * 60), now, 60), None);
+
+        // Extreme inputs are rejected rather than overflowing
+        assert_eq!(window_start(Some(i64::MIN), now, 60), None);
+        assert_eq!(window_start(None, now, i64::MAX), None);
+        assert_eq!(window_start(None, i64::MIN, 60), None);
+        assert_eq!(window_start(Some(-i64::MAX + 1), now, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_bucket_activity() {
+        let entries = vec![
+            entry(1_000_010, AuditEvent::BlocksMined { count: 101 }),
+            entry(
+                1_000_015,
+                AuditEvent::FundsDispensed {
+                    address: None,
+                    amount: 100.0,
+                },
+            ),
+            entry(1_000_130, AuditEvent::BlocksMined { count: 1 }),
+            entry(1_000_130, AuditEvent::NodeStopped),
+        ];
+        let transactions = vec![999_970, 1_000_000, 1_000_070];
+
+        // 1_000_000 is not a multiple of 60, so the first bucket starts earlier
+        // and includes the transaction at 999_970
+        let buckets = bucket_activity(&entries, &transactions, 60, 1_000_000, 1_000_150);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].start, 999_960);
+        assert_eq!(buckets[0].blocks, 101);
+        assert_eq!(buckets[0].funded, 100.0);
+        assert_eq!(buckets[0].transactions, 2);
+        assert_eq!(buckets[1].transactions, 1);
+        assert_eq!(buckets[2].blocks, 1);
+        assert_eq!(
+            buckets[3],
+            ActivityBucket {
+                start: 1_000_140,
+                transactions: 0,
+                blocks: 0,
+                funded: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_bucket_activity_ignores_events_outside_window() {
+        let entries = vec![entry(500, AuditEvent::BlocksMined { count: 5 })];
+        let buckets = bucket_activity(&entries, &[100, 10_000], 60, 1_200, 1_300);
+        assert!(buckets.iter().all(|b| b.blocks == 0 && b.transactions == 0));
+    }
+}
@@ -0,0 +1,330 @@
+//! Request/response body logging for troubleshooting dashboard/API mismatches.
+//!
+//! Bodies are logged to stderr for routes enabled with `cf-api --debug-route`,
+//! or for any request carrying `X-Debug: true` with the bearer token set by
+//! `--debug-token` or the `[api]` section of `chain-forge.toml`. Values of
+//! keys that look like secrets (private keys, mnemonics, passwords, tokens)
+//! are redacted before logging.
+//!
+//! The same token guards `POST /api/v1/admin/reload`; without one, only
+//! loopback clients may reload.
+
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::settings::LiveSettings;
+
+/// Bodies longer than this are truncated in the log
+const MAX_LOGGED_BODY: usize = 16 * 1024;
+
+/// Bodies larger than this, or of unknown size, are passed through without
+/// being read, and only their size is logged
+const MAX_BUFFERED_BODY: usize = 1024 * 1024;
+
+/// Key fragments whose values are never logged
+const SECRET_KEYS: &[&str] = &[
+    "private_key",
+    "secret_key",
+    "secret",
+    "mnemonic",
+    "seed",
+    "wif",
+    "password",
+    "passphrase",
+    "token",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Which requests get their bodies logged
+#[derive(Debug, Clone, Default)]
+pub struct DebugLogConfig {
+    /// Path prefixes that are always logged (e.g. `/api/v1/nodes`)
+    pub routes: Vec<String>,
+    /// Bearer token that allows `X-Debug: true` on any route
+    pub token: Option<String>,
+}
+
+impl DebugLogConfig {
+    /// Whether any logging is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.routes.is_empty() || self.token.is_some()
+    }
+
+    /// Whether a request should have its bodies logged
+    pub fn should_log(&self, path: &str, headers: &HeaderMap) -> bool {
+        if self
+            .routes
+            .iter()
+            .any(|route| path.starts_with(route.as_str()))
+        {
+            return true;
+        }
+
+        let debug_requested = headers
+            .get("x-debug")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        debug_requested && self.has_token(headers)
+    }
+
+    /// Whether a request carries the configured bearer token
+    pub fn has_token(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = &self.token else {
+            return false;
+        };
+        headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|v| v == token)
+    }
+
+    /// Whether a client may use the admin endpoints: it needs the token when
+    /// one is configured, and must connect over loopback otherwise
+    pub fn allows_admin(&self, peer: IpAddr, headers: &HeaderMap) -> bool {
+        match self.token {
+            Some(_) => self.has_token(headers),
+            None => peer.is_loopback(),
+        }
+    }
+}
+
+/// Middleware that logs request and response bodies for selected requests
+pub async fn log_bodies(
+    State(settings): State<Arc<LiveSettings>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !settings
+        .current()
+        .debug_log
+        .should_log(request.uri().path(), request.headers())
+    {
+        return next.run(request).await;
+    }
+
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let (parts, body) = request.into_parts();
+    let (body, description) = match buffer_body(body).await {
+        Ok(buffered) => buffered,
+        Err(e) => {
+            warn!(%method, %uri, error = %e, "Failed to read request body");
+            (Body::empty(), describe_body(&[]))
+        }
+    };
+    info!(%method, %uri, body = %description, "Request");
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let status = response.status();
+    // Streaming responses never end, so they can't be buffered
+    let is_stream = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if is_stream {
+        info!(%status, %uri, "Response (event stream)");
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let (body, description) = match buffer_body(body).await {
+        Ok(buffered) => buffered,
+        Err(e) => {
+            warn!(%method, %uri, error = %e, "Failed to read response body");
+            (Body::empty(), describe_body(&[]))
+        }
+    };
+    info!(%status, %uri, body = %description, "Response");
+
+    Response::from_parts(parts, body)
+}
+
+/// Read a body for the log if it's small enough, returning the body to pass
+/// on with its description
+///
+/// Large bodies (log downloads, snapshots) and ones of unknown size are
+/// returned untouched, described only by their size.
+async fn buffer_body(body: Body) -> Result<(Body, String), axum::Error> {
+    match body.size_hint().exact() {
+        Some(len) if len <= MAX_BUFFERED_BODY as u64 => {}
+        Some(len) => return Ok((body, format!("({} bytes, not logged)", len))),
+        None => return Ok((body, "(unknown size, not logged)".to_string())),
+    }
+
+    let bytes = to_bytes(body, MAX_BUFFERED_BODY).await?;
+    let description = describe_body(&bytes);
+    Ok((Body::from(bytes), description))
+}
+
+/// Render a body for the log: redacted JSON, or a size for anything else
+fn describe_body(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "(empty)".to_string();
+    }
+
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut json) => {
+            redact(&mut json);
+            let mut text = json.to_string();
+            if text.len() > MAX_LOGGED_BODY {
+                let mut end = MAX_LOGGED_BODY;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                text.truncate(end);
+                text.push_str("... (truncated)");
+            }
+            text
+        }
+        Err(_) => format!("({} bytes, not JSON)", bytes.len()),
+    }
+}
+
+/// Replace the values of secret-looking keys, at any depth
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    fn config() -> DebugLogConfig {
+        DebugLogConfig {
+            routes: vec!["/api/v1/nodes".to_string()],
+            token: Some("s3cret".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_redact_nested_secrets() {
+        let mut body = json!({
+            "success": true,
+            "data": [{
+                "address": "bcrt1qxyz",
+                "private_key": "abcd",
+                "Mnemonic": "word word word",
+                "wif": "cV...",
+                "balance": 10.0
+            }],
+            "rpc_password": "chainforge"
+        });
+        redact(&mut body);
+
+        assert_eq!(body["data"][0]["address"], "bcrt1qxyz");
+        assert_eq!(body["data"][0]["balance"], 10.0);
+        assert_eq!(body["data"][0]["private_key"], REDACTED);
+        assert_eq!(body["data"][0]["Mnemonic"], REDACTED);
+        assert_eq!(body["data"][0]["wif"], REDACTED);
+        assert_eq!(body["rpc_password"], REDACTED);
+    }
+
+    #[test]
+    fn test_describe_body() {
+        assert_eq!(describe_body(b""), "(empty)");
+        assert_eq!(describe_body(b"\x00\x01"), "(2 bytes, not JSON)");
+        assert_eq!(
+            describe_body(br#"{"secret_key":[1,2,3]}"#),
+            r#"{"secret_key":"[REDACTED]"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_body_skips_large_bodies() {
+        let (body, description) = buffer_body(Body::from(r#"{"password":"x"}"#))
+            .await
+            .unwrap();
+        assert_eq!(description, r#"{"password":"[REDACTED]"}"#);
+        assert_eq!(
+            to_bytes(body, usize::MAX).await.unwrap(),
+            r#"{"password":"x"}"#
+        );
+
+        let large = vec![b'a'; MAX_BUFFERED_BODY + 1];
+        let (body, description) = buffer_body(Body::from(large.clone())).await.unwrap();
+        assert_eq!(
+            description,
+            format!("({} bytes, not logged)", MAX_BUFFERED_BODY + 1)
+        );
+        // Passed through as is
+        assert_eq!(to_bytes(body, usize::MAX).await.unwrap(), large);
+    }
+
+    #[test]
+    fn test_should_log_configured_route() {
+        let headers = HeaderMap::new();
+        assert!(config().should_log("/api/v1/nodes/bitcoin:dev/accounts", &headers));
+        assert!(!config().should_log("/api/v1/health", &headers));
+    }
+
+    #[test]
+    fn test_should_log_requires_debug_header_and_token() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-debug", HeaderValue::from_static("true"));
+        assert!(!config().should_log("/api/v1/health", &headers));
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer wrong"));
+        assert!(!config().should_log("/api/v1/health", &headers));
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer s3cret"));
+        assert!(config().should_log("/api/v1/health", &headers));
+
+        // Without a configured token the header is ignored
+        let no_token = DebugLogConfig {
+            token: None,
+            ..config()
+        };
+        assert!(!no_token.should_log("/api/v1/health", &headers));
+    }
+
+    #[test]
+    fn test_allows_admin() {
+        let local = IpAddr::from([127, 0, 0, 1]);
+        let remote = IpAddr::from([192, 168, 1, 20]);
+        let mut headers = HeaderMap::new();
+
+        // With a token, the peer address doesn't matter
+        assert!(!config().allows_admin(local, &headers));
+        headers.insert("authorization", HeaderValue::from_static("Bearer wrong"));
+        assert!(!config().allows_admin(remote, &headers));
+        headers.insert("authorization", HeaderValue::from_static("Bearer s3cret"));
+        assert!(config().allows_admin(remote, &headers));
+
+        // Without one, only loopback clients are let through
+        let no_token = DebugLogConfig {
+            token: None,
+            ..config()
+        };
+        assert!(no_token.allows_admin(local, &headers));
+        assert!(no_token.allows_admin(IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]), &headers));
+        assert!(!no_token.allows_admin(remote, &headers));
+    }
+}
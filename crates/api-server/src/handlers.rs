@@ -1,14 +1,69 @@
 //! Request handlers for the Chain Forge REST API.
 
-use axum::{extract::Path, http::StatusCode, Json};
-use chain_forge_bitcoin_accounts::AccountsStorage as BitcoinAccountsStorage;
-use chain_forge_bitcoin_core::InstanceInfo as BitcoinInstanceInfo;
-use chain_forge_bitcoin_rpc::BitcoinRpcClient;
-use chain_forge_common::{ChainType, NodeInfo, NodeRegistry, NodeStatus};
-use chain_forge_config::Config;
-use chain_forge_solana_accounts::AccountsStorage as SolanaAccountsStorage;
-use chain_forge_solana_rpc::SolanaRpcClient;
+use crate::activity::{self, ActivityBucket};
+use crate::jobs::{Job, JobProgress, JobStatus, JobStore, CANCELLED};
+use crate::node_logs::{self, DEFAULT_TAIL_LINES, MAX_TAIL_LINES};
+use crate::settings::LiveSettings;
+use crate::supervisor::{NodeCommand, Supervisor};
+use axum::{
+    extract::{
+        ws::{self, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{self, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use chain_forge_bitcoin_accounts::{
+    AccountsStorage as BitcoinAccountsStorage, EmulatedSigner as BitcoinEmulatedSigner,
+};
+use chain_forge_bitcoin_core::analyze::analyze_accounts;
+use chain_forge_bitcoin_core::{
+    confirm, BitcoinConfig, Confirmations, InstanceInfo as BitcoinInstanceInfo, WalletAnalysis,
+};
+use chain_forge_bitcoin_rpc::{
+    BitcoinBlock, BitcoinBlockHeader, BitcoinOutpoint, BitcoinRpcClient, BitcoinUtxo,
+};
+use chain_forge_cli_utils::logging::{self, LogEntry};
+use chain_forge_cli_utils::{format_duration, format_relative};
+use chain_forge_common::rpc_metrics::{self, EndpointStats};
+use chain_forge_common::{
+    validate_name, AuditLog, ChainType, Message, NodeInfo, NodeRegistry, NodeStatus, Signer,
+    SignerKeystore,
+};
+use chain_forge_config::{
+    BitcoinProfile, Config, EthereumProfile, PortChoice, Preset, SolanaProfile,
+};
+use chain_forge_ethereum_accounts::AccountsStorage as EthereumAccountsStorage;
+use chain_forge_ethereum_core::EthereumConfig;
+use chain_forge_ethereum_rpc::EthereumRpcClient;
+use chain_forge_events::{ChainEvent, Event, EventBus, EventPage};
+use chain_forge_solana_accounts::{
+    AccountsStorage as SolanaAccountsStorage, EmulatedSigner, LocalSigner, SolanaAccount,
+};
+use chain_forge_solana_core::snapshot::{pause_process, resume_process};
+use chain_forge_solana_core::{
+    auto_port, warp_instance, SolanaConfig, SolanaInstanceInfo, SolanaSnapshot, ValidatorPorts,
+    WarpTarget,
+};
+use chain_forge_solana_rpc::{pay, token_account_address, SolanaBlock, SolanaRpcClient};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tracing::level_filters::LevelFilter;
+use tracing::warn;
 
 /// Response wrapper for API responses
 #[derive(Serialize)]
@@ -18,6 +73,9 @@ pub struct ApiResponse<T: Serialize> {
     pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Stable ID of the error message, for errors from the message catalog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -26,6 +84,7 @@ impl<T: Serialize> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
         }
     }
 
@@ -34,12 +93,21 @@ impl<T: Serialize> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.to_string()),
+            code: None,
+        }
+    }
+
+    /// An error from the message catalog, with its ID as `code`
+    pub fn message(message: Message) -> Self {
+        Self {
+            code: Some(message.id()),
+            ..Self::error(&message.to_string())
         }
     }
 }
 
 /// Node info for API response (includes additional runtime info)
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct NodeInfoResponse {
     pub node_id: String,
     pub name: Option<String>,
@@ -49,11 +117,20 @@ pub struct NodeInfoResponse {
     pub rpc_port: u16,
     pub accounts_count: u32,
     pub status: String,
+    /// RFC3339 start time
     pub started_at: Option<String>,
+    /// Start time relative to now, e.g. `2h ago`
+    pub started: Option<String>,
+    /// Seconds the node has been running (running nodes only)
+    pub uptime_secs: Option<i64>,
+    /// Uptime for display, e.g. `2h 14m`
+    pub uptime: Option<String>,
 }
 
 impl From<NodeInfo> for NodeInfoResponse {
     fn from(info: NodeInfo) -> Self {
+        let now = chrono::Utc::now();
+        let uptime = info.uptime(now);
         Self {
             node_id: info.node_id,
             name: info.name,
@@ -64,39 +141,131 @@ impl From<NodeInfo> for NodeInfoResponse {
             accounts_count: info.accounts_count,
             status: info.status.to_string(),
             started_at: info.started_at.map(|t| t.to_rfc3339()),
+            started: info.started_at.map(|t| format_relative(t, now)),
+            uptime_secs: uptime.map(|uptime| uptime.num_seconds().max(0)),
+            uptime: uptime.map(format_duration),
         }
     }
 }
 
 /// Account info for API response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct AccountInfo {
     pub index: usize,
     pub address: String,
     pub balance: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Output descriptor (Bitcoin)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descriptor: Option<String>,
+}
+
+/// Query parameters for the account list
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AccountsQuery {
+    /// Skip this many matching accounts
+    #[serde(default)]
+    pub offset: usize,
+    /// Return at most this many accounts
+    pub limit: Option<usize>,
+    /// Only accounts holding at least this much SOL or BTC
+    pub min_balance: Option<f64>,
+    /// Only accounts with this label
+    pub label: Option<String>,
+    /// Fetch live balances; `false` serves the balances saved in accounts.json
+    #[serde(default = "default_refresh")]
+    pub refresh: bool,
+}
+
+impl Default for AccountsQuery {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: None,
+            min_balance: None,
+            label: None,
+            refresh: default_refresh(),
+        }
+    }
+}
+
+fn default_refresh() -> bool {
+    true
+}
+
+/// Apply an [`AccountsQuery`] to the accounts of an instance
+///
+/// Returns each selected account with its index. `refresh` is called with
+/// the accounts whose balances are needed: only the requested page, unless
+/// `min_balance` has to look at every account with the label.
+pub(crate) fn select_accounts<A>(
+    accounts: Vec<A>,
+    query: &AccountsQuery,
+    label: impl Fn(&A) -> Option<&str>,
+    balance: impl Fn(&A) -> f64,
+    refresh: impl FnOnce(&mut [A]),
+) -> Vec<(usize, A)> {
+    let page = |accounts: Vec<(usize, A)>| -> Vec<(usize, A)> {
+        accounts
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect()
+    };
+
+    let matching: Vec<(usize, A)> = accounts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, account)| {
+            query
+                .label
+                .as_deref()
+                .map_or(true, |wanted| label(account) == Some(wanted))
+        })
+        .collect();
+
+    let (indices, mut selected): (Vec<usize>, Vec<A>) = match query.min_balance {
+        None => page(matching).into_iter().unzip(),
+        Some(_) => matching.into_iter().unzip(),
+    };
+    if query.refresh {
+        refresh(&mut selected);
+    }
+
+    let selected = indices.into_iter().zip(selected);
+    match query.min_balance {
+        None => selected.collect(),
+        Some(min) => page(
+            selected
+                .filter(|(_, account)| balance(account) >= min)
+                .collect(),
+        ),
+    }
 }
 
 /// Request to start a new node
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct StartNodeRequest {
     pub chain: String,
     #[serde(default = "default_instance")]
     pub instance: String,
     pub name: Option<String>,
+    /// RPC port, or `"auto"` to pick free ports (Solana only)
     #[serde(default = "default_port")]
-    pub port: u16,
-    #[serde(default = "default_accounts")]
-    pub accounts: u32,
-    #[serde(default = "default_balance")]
-    pub balance: f64,
+    pub port: PortChoice,
+    /// Built-in preset; explicit fields take precedence over it
+    pub preset: Option<String>,
+    pub accounts: Option<u32>,
+    pub balance: Option<f64>,
 }
 
 fn default_instance() -> String {
     "default".to_string()
 }
 
-fn default_port() -> u16 {
-    8899
+fn default_port() -> PortChoice {
+    PortChoice::Port(8899)
 }
 
 fn default_accounts() -> u32 {
@@ -107,15 +276,22 @@ fn default_balance() -> f64 {
     100.0
 }
 
+/// How long a node started through the API gets to register itself; Bitcoin
+/// nodes mine and fund every account first
+const NODE_START_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Request to fund an account
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct FundAccountRequest {
     pub address: String,
     pub amount: f64,
+    /// Blocks to mine to confirm a Bitcoin send, or `"none"` (default: the
+    /// instance's); ignored for bulk recipients
+    pub confirm: Option<Confirmations>,
 }
 
 /// Health check response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct HealthCheckResponse {
     pub total: usize,
     pub running: usize,
@@ -123,26 +299,130 @@ pub struct HealthCheckResponse {
     pub unknown: usize,
 }
 
+/// Live health of a single node
+#[derive(Serialize, JsonSchema)]
+pub struct NodeHealthInfo {
+    pub node_id: String,
+    pub chain: String,
+    /// Status recorded in the registry, which this check doesn't change
+    pub registry_status: String,
+    /// Status from the node's process and heartbeat, computed for this
+    /// response only
+    pub observed_status: String,
+    /// Whether the node answered the block height query
+    pub rpc_reachable: bool,
+    /// Block height, or slot for Solana
+    pub block_height: Option<u64>,
+    /// Connected peers (other cluster validators for Solana)
+    pub peer_count: Option<usize>,
+    /// Whether the node wallet is loaded (Bitcoin only)
+    pub wallet_loaded: Option<bool>,
+    /// Unix time of the latest block
+    pub last_block_time: Option<i64>,
+    /// Round-trip time of each probe, in milliseconds
+    pub latency_ms: BTreeMap<String, f64>,
+    /// Why each failed probe failed
+    pub errors: BTreeMap<String, String>,
+}
+
 /// Start node response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct StartNodeResponse {
+    /// Poll `GET /api/v1/jobs/{id}` for startup progress
+    pub job_id: String,
     pub message: String,
+    /// The chain CLI command the server runs
     pub command: String,
     pub chain: String,
     pub instance: String,
     pub port: u16,
+    /// Every port the validator uses (Solana only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports: Option<ValidatorPortsResponse>,
+    /// Where the CLI's output is written
+    pub log_file: String,
+}
+
+/// Ports a Solana validator listens on
+#[derive(Serialize, JsonSchema)]
+pub struct ValidatorPortsResponse {
+    pub rpc: u16,
+    pub faucet: u16,
+    pub gossip: u16,
+    /// Inclusive range for TPU, TVU, and repair sockets
+    pub dynamic_range: [u16; 2],
+}
+
+impl From<ValidatorPorts> for ValidatorPortsResponse {
+    fn from(ports: ValidatorPorts) -> Self {
+        Self {
+            rpc: ports.rpc,
+            faucet: ports.faucet,
+            gossip: ports.gossip,
+            dynamic_range: ports.dynamic_range,
+        }
+    }
 }
 
 /// Stop node response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct StopNodeResponse {
     pub message: String,
-    pub instruction: String,
+    /// How to stop a node the server has no process for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+    pub node_id: String,
+    /// Whether the node's process was shut down
+    pub stopped: bool,
+}
+
+/// Query parameters for stopping every node
+#[derive(Deserialize, JsonSchema)]
+pub struct StopAllQuery {
+    /// Only stop nodes of this chain
+    pub chain: Option<String>,
+}
+
+/// Outcome of a group operation for one node
+#[derive(Serialize, JsonSchema)]
+pub struct NodeOperationResult {
     pub node_id: String,
+    pub chain: String,
+    /// Whether the operation succeeded for this node
+    pub success: bool,
+    /// Registry status after the operation
+    pub status: String,
+    /// Why the operation failed, or what is left to do
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Job starting the node again (restarts only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+}
+
+/// Per-node results of an operation on several nodes
+#[derive(Serialize, JsonSchema)]
+pub struct GroupOperationResponse {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<NodeOperationResult>,
+}
+
+impl From<Vec<NodeOperationResult>> for GroupOperationResponse {
+    fn from(results: Vec<NodeOperationResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.success).count();
+        Self {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
 }
 
 /// Fund response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct FundResponse {
     pub success: bool,
     pub txid_or_signature: String,
@@ -150,8 +430,108 @@ pub struct FundResponse {
     pub amount: f64,
 }
 
+/// Request to transfer funds between addresses
+#[derive(Deserialize, JsonSchema)]
+pub struct TransferRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    /// Blocks to mine to confirm a Bitcoin transfer, or `"none"` (default:
+    /// the instance's)
+    pub confirm: Option<Confirmations>,
+}
+
+/// Transfer response
+#[derive(Serialize, JsonSchema)]
+pub struct TransferResponse {
+    pub txid_or_signature: String,
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+}
+
+/// Request to mine blocks on a Bitcoin node
+#[derive(Deserialize, JsonSchema)]
+pub struct MineRequest {
+    /// Blocks to mine
+    pub blocks: u32,
+    /// Address the block rewards go to (default: a fresh wallet address,
+    /// so account balances don't change)
+    pub address: Option<String>,
+}
+
+/// Blocks mined by a mine request
+#[derive(Serialize, JsonSchema)]
+pub struct MineResponse {
+    /// Hashes of the new blocks, oldest first
+    pub block_hashes: Vec<String>,
+    /// Height of the chain tip after mining
+    pub height: u64,
+}
+
+/// Request body for funding several addresses in one job
+#[derive(Deserialize, JsonSchema)]
+pub struct BulkFundRequest {
+    pub recipients: Vec<FundAccountRequest>,
+    /// Blocks to mine to confirm Bitcoin sends, or `"none"` (default: the
+    /// instance's)
+    pub confirm: Option<Confirmations>,
+}
+
+/// A recipient a bulk funding job couldn't fund
+#[derive(Serialize, JsonSchema)]
+pub struct BulkFundFailure {
+    pub address: String,
+    pub error: String,
+}
+
+/// Result of a bulk funding job
+#[derive(Serialize, JsonSchema)]
+pub struct BulkFundResult {
+    pub sent: Vec<FundResponse>,
+    pub failed: Vec<BulkFundFailure>,
+}
+
+/// Request body for taking a ledger snapshot (Solana)
+#[derive(Deserialize, JsonSchema)]
+pub struct SnapshotRequest {
+    /// Snapshot name (default: `<instance>-slot-<N>`)
+    pub name: Option<String>,
+    /// Slot to wait for before archiving (default: the current slot)
+    pub at_slot: Option<u64>,
+    /// Seconds to wait for `at_slot`
+    #[serde(default = "default_snapshot_timeout")]
+    pub timeout: u64,
+}
+
+fn default_snapshot_timeout() -> u64 {
+    300
+}
+
+/// Request body for warping a validator ahead (Solana); give `slot` or
+/// `epoch`
+#[derive(Deserialize, JsonSchema)]
+pub struct WarpRequest {
+    /// Slot to warp to
+    pub slot: Option<u64>,
+    /// Warp to the first slot of this epoch
+    pub epoch: Option<u64>,
+}
+
+/// Request body for a wallet rescan (Bitcoin)
+#[derive(Deserialize, JsonSchema)]
+pub struct RescanRequest {
+    /// First block height to scan
+    #[serde(default)]
+    pub start_height: u64,
+}
+
+/// Blocks rescanned per RPC call; progress and cancellation are checked
+/// between calls
+const RESCAN_CHUNK: u64 = 1000;
+
 /// Transaction info for API response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct TransactionInfo {
     pub signature: String,
     pub slot: u64,
@@ -162,8 +542,159 @@ pub struct TransactionInfo {
     pub account: String,
 }
 
-/// Balance change in a transaction
-#[derive(Serialize)]
+/// Query parameters for the transaction list
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TransactionsQuery {
+    /// Return at most this many transactions, most recent first; at least 1
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for transaction search
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TransactionSearchQuery {
+    /// Only transactions that move funds to or from this address
+    pub address: Option<String>,
+    /// Minimum amount moved, in SOL or BTC (either direction)
+    pub min: Option<f64>,
+    /// Maximum amount moved, in SOL or BTC (either direction)
+    pub max: Option<f64>,
+    /// Only transactions in blocks at or after this Unix timestamp
+    pub since: Option<i64>,
+}
+
+impl TransactionSearchQuery {
+    /// Whether a result satisfies the amount and time filters
+    ///
+    /// Unconfirmed transactions have no block time and always pass `since`.
+    fn matches(&self, tx: &TransactionSearchResult) -> bool {
+        let amount = tx.amount.abs();
+        self.min.map_or(true, |min| amount >= min)
+            && self.max.map_or(true, |max| amount <= max)
+            && match (self.since, tx.block_time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            }
+    }
+}
+
+/// A transaction matched by a search, from one account's point of view
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransactionSearchResult {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub account: String,
+    /// Net change to the account: positive if received, negative if sent
+    pub amount: f64,
+}
+
+/// Query parameters for the activity timeline
+#[derive(Deserialize, JsonSchema)]
+pub struct ActivityQuery {
+    /// Bucket width, e.g. `30s`, `1m`, `1h`, `1d` (default: 1m)
+    pub bucket: Option<String>,
+    /// Start of the timeline (Unix seconds); omit for the last 60 buckets
+    pub since: Option<i64>,
+}
+
+/// Request body for locking or unlocking an account's outputs
+#[derive(Deserialize, JsonSchema)]
+pub struct UtxoLockRequest {
+    /// Outputs to lock or unlock; when unlocking, omit to unlock all of the
+    /// account's locked outputs
+    #[serde(default)]
+    #[schemars(with = "Vec<crate::openapi::Outpoint>")]
+    pub outpoints: Vec<BitcoinOutpoint>,
+}
+
+/// Request body for creating an SPL token mint (Solana)
+#[derive(Deserialize, JsonSchema)]
+pub struct CreateTokenRequest {
+    /// Account that becomes the mint authority and pays rent
+    pub authority: String,
+    /// Number of decimals (default: 9)
+    #[serde(default = "default_token_decimals")]
+    pub decimals: u8,
+    /// Also create a token account for every account of the instance
+    #[serde(default)]
+    pub create_accounts: bool,
+}
+
+fn default_token_decimals() -> u8 {
+    9
+}
+
+/// A created SPL token mint
+#[derive(Serialize, JsonSchema)]
+pub struct TokenMintInfo {
+    pub mint: String,
+    pub authority: String,
+    pub decimals: u8,
+    /// Token accounts created for the instance's accounts
+    pub token_accounts: Vec<String>,
+}
+
+/// Request body for minting SPL tokens (Solana)
+#[derive(Deserialize, JsonSchema)]
+pub struct MintTokensRequest {
+    /// Mint authority account
+    pub authority: String,
+    /// Amount of tokens minted to each recipient
+    pub amount: f64,
+    /// Recipient addresses; omit to mint to every account of the instance
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
+/// Request body for transferring SPL tokens (Solana)
+#[derive(Deserialize, JsonSchema)]
+pub struct TransferTokensRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+}
+
+/// A confirmed token transaction
+#[derive(Serialize, JsonSchema)]
+pub struct TokenTransferInfo {
+    pub signature: String,
+    pub to: String,
+    pub amount: f64,
+}
+
+/// An account's balance of a token
+#[derive(Serialize, JsonSchema)]
+pub struct TokenBalance {
+    pub address: String,
+    pub token_account: String,
+    pub balance: f64,
+}
+
+/// Query parameters for looking up a Solana Pay payment
+#[derive(Deserialize, JsonSchema)]
+pub struct PaymentQuery {
+    /// Recipient the payment must go to; with `amount`, the payment is checked
+    pub recipient: Option<String>,
+    /// SOL the payment must send at least
+    pub amount: Option<f64>,
+}
+
+/// A payment found by its Solana Pay reference key
+#[derive(Serialize, JsonSchema)]
+pub struct PaymentInfo {
+    pub reference: String,
+    /// Paying transaction, once one references the key
+    pub signature: Option<String>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+    /// Whether the payment matches `recipient` and `amount`, when both are given
+    pub valid: Option<bool>,
+    /// Why the payment doesn't match
+    pub error: Option<String>,
+}
+
+/// Balance of an account just before and after a transaction
+#[derive(Serialize, JsonSchema)]
 pub struct BalanceChangeInfo {
     pub account: String,
     pub before: f64,
@@ -171,8 +702,16 @@ pub struct BalanceChangeInfo {
     pub change: f64,
 }
 
+/// Address and amount on one side of a Bitcoin transaction
+#[derive(Serialize, JsonSchema)]
+pub struct TransactionIoInfo {
+    /// `None` for coinbase inputs and outputs without an address
+    pub address: Option<String>,
+    pub amount: f64,
+}
+
 /// Detailed transaction info
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct TransactionDetailInfo {
     pub signature: String,
     pub slot: u64,
@@ -180,16 +719,277 @@ pub struct TransactionDetailInfo {
     pub fee: f64,
     pub err: Option<String>,
     pub balance_changes: Vec<BalanceChangeInfo>,
+    /// Outputs the transaction spends, in input order (Bitcoin only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<TransactionIoInfo>>,
+    /// Outputs the transaction creates (Bitcoin only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<TransactionIoInfo>>,
+}
+
+/// Query parameters for the block header long-poll
+#[derive(Deserialize, JsonSchema)]
+pub struct BlockHeadersQuery {
+    /// Return headers above this height; omit to get the current tip
+    pub since: Option<u64>,
+    /// Seconds to wait for a new block when none is available yet
+    pub timeout: Option<u64>,
+}
+
+/// Query parameters for tailing a node's log
+#[derive(Deserialize, JsonSchema)]
+pub struct LogTailQuery {
+    /// Lines to return from the end of the log (default: 200, max: 10000)
+    pub lines: Option<usize>,
+    /// Keep the connection open and stream new lines as SSE `line` events
+    #[serde(default)]
+    pub follow: bool,
+}
+
+/// The end of a node's log
+#[derive(Serialize, JsonSchema)]
+pub struct LogTailResponse {
+    pub node_id: String,
+    pub file: String,
+    pub lines: Vec<String>,
+}
+
+/// Query parameters for reading a node's instance log
+#[derive(Deserialize, JsonSchema)]
+pub struct InstanceLogQuery {
+    /// Entries to return from the end of the log (default: 200, max: 10000)
+    pub lines: Option<usize>,
+    /// Least severe level to return: error, warn, info, debug, or trace
+    /// (default: info)
+    pub level: Option<String>,
+}
+
+/// An event Chain Forge logged for a node
+#[derive(Serialize, JsonSchema)]
+pub struct InstanceLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// The event's message and structured fields
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<LogEntry> for InstanceLogEntry {
+    fn from(entry: LogEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            level: entry.level,
+            target: entry.target,
+            fields: entry.fields,
+        }
+    }
+}
+
+/// The end of a node's instance log
+#[derive(Serialize, JsonSchema)]
+pub struct InstanceLogResponse {
+    pub node_id: String,
+    pub file: String,
+    pub entries: Vec<InstanceLogEntry>,
+}
+
+/// Block header for API response
+#[derive(Serialize, JsonSchema)]
+pub struct BlockHeaderInfo {
+    pub hash: String,
+    pub height: u64,
+    pub time: i64,
+    pub previous_block_hash: Option<String>,
+}
+
+/// Query parameters for listing recent blocks
+#[derive(Deserialize, JsonSchema)]
+pub struct BlocksQuery {
+    /// Return blocks below this height (slot on Solana), to get the next
+    /// page; omit to start at the tip
+    pub before: Option<u64>,
+    /// Blocks to return (default: 20, max: 100)
+    pub limit: Option<usize>,
+}
+
+/// Block for API response
+#[derive(Serialize, JsonSchema)]
+pub struct BlockInfo {
+    /// Block hash (blockhash on Solana)
+    pub hash: String,
+    /// Null if a Solana node didn't record it
+    pub height: Option<u64>,
+    /// Slot the block was produced in (Solana)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    /// Unix time; null if a Solana node didn't record it
+    pub time: Option<i64>,
+    pub previous_block_hash: Option<String>,
+    /// Slot of the parent block (Solana)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_slot: Option<u64>,
+    /// -1 if the block is no longer in the best chain (Bitcoin)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<i64>,
+    /// Size in bytes (Bitcoin)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Weight in weight units (Bitcoin)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u64>,
+    pub tx_count: u64,
+    /// Transaction IDs, coinbase first (signatures on Solana); only on
+    /// single-block responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txids: Option<Vec<String>>,
+}
+
+impl BlockInfo {
+    fn from_bitcoin(block: BitcoinBlock, with_txids: bool) -> Self {
+        Self {
+            hash: block.hash,
+            height: Some(block.height),
+            slot: None,
+            time: Some(block.time),
+            previous_block_hash: block.previous_block_hash,
+            parent_slot: None,
+            confirmations: Some(block.confirmations),
+            size: Some(block.size),
+            weight: Some(block.weight),
+            tx_count: block.tx_count,
+            txids: with_txids.then_some(block.txids),
+        }
+    }
+
+    fn from_solana(block: SolanaBlock, with_signatures: bool) -> Self {
+        Self {
+            hash: block.blockhash,
+            height: block.block_height,
+            slot: Some(block.slot),
+            time: block.block_time,
+            previous_block_hash: Some(block.previous_blockhash),
+            parent_slot: Some(block.parent_slot),
+            confirmations: None,
+            size: None,
+            weight: None,
+            tx_count: block.tx_count,
+            txids: with_signatures.then_some(block.signatures),
+        }
+    }
+}
+
+/// Compact block filter for API response
+#[derive(Serialize, JsonSchema)]
+pub struct BlockFilterInfo {
+    pub height: u64,
+    pub block_hash: String,
+    pub filter: String,
+    pub header: String,
+}
+
+/// Blocks listed per request when no limit is given
+const BLOCKS_DEFAULT_LIMIT: usize = 20;
+/// Maximum number of blocks listed per request
+const BLOCKS_MAX_LIMIT: usize = 100;
+
+/// Default long-poll wait for new block headers
+const HEADERS_DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Upper bound on the long-poll wait for new block headers
+const HEADERS_MAX_TIMEOUT_SECS: u64 = 60;
+/// Maximum number of headers returned per request
+const HEADERS_MAX_BATCH: usize = 100;
+
+/// Clamp a requested long-poll timeout
+fn headers_timeout(requested: Option<u64>) -> Duration {
+    Duration::from_secs(
+        requested
+            .unwrap_or(HEADERS_DEFAULT_TIMEOUT_SECS)
+            .min(HEADERS_MAX_TIMEOUT_SECS),
+    )
+}
+
+/// Query parameters for the event long-poll
+#[derive(Deserialize, JsonSchema)]
+pub struct EventsQuery {
+    /// Return events after this cursor; omit to get the current cursor
+    pub since: Option<u64>,
+    /// Only return events for this node
+    pub node_id: Option<String>,
+    /// Seconds to wait for an event when none is available yet
+    pub timeout: Option<u64>,
+}
+
+/// What a decode request's payload is
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DecodeKind {
+    /// A raw transaction (both chains)
+    #[default]
+    Transaction,
+    /// An output or redeem script (Bitcoin)
+    Script,
+    /// A transaction message without signatures (Solana)
+    Message,
+}
+
+/// Request body for decoding a raw payload
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DecodeRequest {
+    #[serde(default)]
+    pub kind: DecodeKind,
+    /// Hex for Bitcoin; base64 or base58 for Solana
+    pub data: String,
+}
+
+/// Query parameters for the WebSocket event stream
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WsEventsQuery {
+    /// Replay buffered events after this cursor before live ones
+    pub since: Option<u64>,
+    /// Only send events for this node
+    pub node_id: Option<String>,
+}
+
+/// Profiles defined in `chain-forge.toml`
+#[derive(Serialize, JsonSchema)]
+pub struct ProfilesResponse {
+    /// Config file the profiles are read from and written to
+    pub path: String,
+    /// Whether the config file exists yet
+    pub exists: bool,
+    pub solana: BTreeMap<String, SolanaProfile>,
+    pub bitcoin: BTreeMap<String, BitcoinProfile>,
+    pub ethereum: BTreeMap<String, EthereumProfile>,
+}
+
+/// Request body for creating or replacing a profile
+#[derive(Deserialize, JsonSchema)]
+pub struct UpdateProfileRequest {
+    pub chain: String,
+    pub name: String,
+    /// Profile fields; omitted fields take their defaults
+    pub profile: serde_json::Value,
 }
 
 /// Cleanup response
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CleanupResponse {
     pub removed: usize,
     pub remaining: usize,
     pub removed_nodes: Vec<String>,
 }
 
+/// Settings in effect after a reload
+#[derive(Serialize, JsonSchema)]
+pub struct ReloadResponse {
+    /// Config file that was read, if one exists
+    pub config_path: Option<String>,
+    pub debug_routes: Vec<String>,
+    /// Whether a debug token is set; the token itself is never returned
+    pub debug_token: bool,
+    pub watch_interval_secs: u64,
+}
+
 /// List all registered nodes
 pub async fn list_nodes() -> (StatusCode, Json<ApiResponse<Vec<NodeInfoResponse>>>) {
     let registry = NodeRegistry::new();
@@ -201,7 +1001,9 @@ pub async fn list_nodes() -> (StatusCode, Json<ApiResponse<Vec<NodeInfoResponse>
         }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(&format!("Failed to list nodes: {}", e))),
+            Json(ApiResponse::message(Message::ListNodesFailed(
+                e.to_string(),
+            ))),
         ),
     }
 }
@@ -219,18 +1021,124 @@ pub async fn get_node(
         ),
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Node not found")),
+            Json(ApiResponse::message(Message::NodeNotFound)),
         ),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+            Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
         ),
     }
 }
 
+/// Timings and errors of the RPC calls a node health check makes
+#[derive(Default)]
+struct HealthProbes {
+    latency_ms: BTreeMap<String, f64>,
+    errors: BTreeMap<String, String>,
+}
+
+impl HealthProbes {
+    /// Run one probe, recording how long it took and why it failed
+    fn run<T>(
+        &mut self,
+        name: &str,
+        probe: impl FnOnce() -> chain_forge_common::Result<T>,
+    ) -> Option<T> {
+        let started = Instant::now();
+        let result = probe();
+        self.latency_ms
+            .insert(name.to_string(), started.elapsed().as_secs_f64() * 1000.0);
+        result
+            .map_err(|e| self.errors.insert(name.to_string(), e.to_string()))
+            .ok()
+    }
+}
+
+/// Probe a single node's RPC for its health
+///
+/// Unlike `POST /health`, this only reads the registry: a node that doesn't
+/// answer is reported as unreachable but keeps its registered status.
+pub async fn get_node_health(
+    Path(node_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<NodeHealthInfo>>) {
+    let node = match NodeRegistry::new().get_recorded(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+    let observed_status = node.observed_status(chrono::Utc::now());
+
+    let mut probes = HealthProbes::default();
+    let mut wallet_loaded = None;
+    let (block_height, peer_count, last_block_time) = match node.chain {
+        ChainType::Solana => {
+            let client = SolanaRpcClient::new(node.rpc_url.clone());
+            let slot = probes.run("slot", || client.get_slot());
+            let peers = probes.run("peers", || client.get_peer_count());
+            let time =
+                slot.and_then(|slot| probes.run("block_time", || client.get_block_time(slot)));
+            (slot, peers, time)
+        }
+        ChainType::Bitcoin => {
+            let client = probes.run("connect", || {
+                BitcoinInstanceInfo::load(&node.instance_id)?.wallet_client()
+            });
+            match client {
+                Some(client) => {
+                    let height = probes.run("block_height", || client.get_block_count());
+                    let peers = probes.run("peers", || client.get_connection_count());
+                    wallet_loaded = probes.run("wallet", || client.is_wallet_loaded());
+                    let time = height.and_then(|height| {
+                        probes.run("block_time", || {
+                            let hash = client.get_block_hash(height)?;
+                            Ok(client.get_block_header(&hash)?.time)
+                        })
+                    });
+                    (height, peers, time)
+                }
+                None => (None, None, None),
+            }
+        }
+        ChainType::Ethereum => {
+            let client = EthereumRpcClient::new(node.rpc_url.clone());
+            let height = probes.run("block_height", || client.get_block_number());
+            let peers = probes.run("peers", || client.peer_count());
+            let time = height
+                .and_then(|height| probes.run("block_time", || client.get_block_time(height)));
+            (height, peers, time)
+        }
+    };
+
+    let response = NodeHealthInfo {
+        chain: node.chain.to_string(),
+        registry_status: node.status.to_string(),
+        observed_status: observed_status.to_string(),
+        node_id: node.node_id,
+        rpc_reachable: block_height.is_some(),
+        block_height,
+        peer_count,
+        wallet_loaded,
+        last_block_time,
+        latency_ms: probes.latency_ms,
+        errors: probes.errors,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
 /// Get accounts for a specific node with live balances from the blockchain
 pub async fn get_node_accounts(
     Path(node_id): Path<String>,
+    Query(query): Query<AccountsQuery>,
 ) -> (StatusCode, Json<ApiResponse<Vec<AccountInfo>>>) {
     let registry = NodeRegistry::new();
 
@@ -239,13 +1147,13 @@ pub async fn get_node_accounts(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Node not found")),
+                Json(ApiResponse::message(Message::NodeNotFound)),
             );
         }
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
             );
         }
     };
@@ -260,21 +1168,26 @@ pub async fn get_node_accounts(
             let storage = SolanaAccountsStorage::with_path(accounts_file);
 
             match storage.load() {
-                Ok(mut accounts) => {
-                    // Fetch live balances from the blockchain
-                    let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
-                    let _ = rpc_client.update_balances(&mut accounts);
-
-                    accounts
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, acc)| AccountInfo {
-                            index: i,
-                            address: acc.public_key,
-                            balance: acc.balance,
-                        })
-                        .collect()
-                }
+                Ok(accounts) => select_accounts(
+                    accounts,
+                    &query,
+                    |acc| acc.label.as_deref(),
+                    |acc| acc.balance,
+                    |accounts| {
+                        // Fetch live balances from the blockchain
+                        let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+                        let _ = rpc_client.update_balances(accounts);
+                    },
+                )
+                .into_iter()
+                .map(|(i, acc)| AccountInfo {
+                    index: i,
+                    address: acc.public_key,
+                    balance: acc.balance,
+                    label: acc.label,
+                    descriptor: None,
+                })
+                .collect(),
                 Err(e) => {
                     return (
                         StatusCode::INTERNAL_SERVER_ERROR,
@@ -295,29 +1208,27 @@ pub async fn get_node_accounts(
             let storage = BitcoinAccountsStorage::with_path(accounts_file);
 
             match storage.load() {
-                Ok(mut accounts) => {
-                    // Fetch live balances from the blockchain
-                    if let Ok(info) = BitcoinInstanceInfo::load(&node.instance_id) {
-                        if let Ok(rpc_client) = BitcoinRpcClient::new_with_wallet(
-                            info.rpc_url,
-                            info.rpc_user,
-                            info.rpc_password,
-                            "chain-forge",
-                        ) {
-                            let _ = rpc_client.update_balances(&mut accounts);
+                Ok(accounts) => select_accounts(
+                    accounts,
+                    &query,
+                    |acc| acc.label.as_deref(),
+                    |acc| acc.balance,
+                    |accounts| {
+                        // Fetch live balances from the blockchain
+                        if let Ok(info) = BitcoinInstanceInfo::load(&node.instance_id) {
+                            let _ = info.update_balances(accounts);
                         }
-                    }
-
-                    accounts
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, acc)| AccountInfo {
-                            index: i,
-                            address: acc.address,
-                            balance: acc.balance,
-                        })
-                        .collect()
-                }
+                    },
+                )
+                .into_iter()
+                .map(|(i, acc)| AccountInfo {
+                    index: i,
+                    descriptor: acc.descriptor().ok(),
+                    address: acc.address,
+                    balance: acc.balance,
+                    label: acc.label,
+                })
+                .collect(),
                 Err(e) => {
                     return (
                         StatusCode::INTERNAL_SERVER_ERROR,
@@ -329,7 +1240,44 @@ pub async fn get_node_accounts(
                 }
             }
         }
-    };
+        ChainType::Ethereum => {
+            let storage = EthereumAccountsStorage::with_path(
+                EthereumConfig::with_instance(&node.instance_id).accounts_file(),
+            );
+
+            match storage.load() {
+                Ok(accounts) => select_accounts(
+                    accounts,
+                    &query,
+                    |acc| acc.label.as_deref(),
+                    |acc| acc.balance,
+                    |accounts| {
+                        // Fetch live balances from the blockchain
+                        let rpc_client = EthereumRpcClient::new(node.rpc_url.clone());
+                        let _ = rpc_client.update_balances(accounts);
+                    },
+                )
+                .into_iter()
+                .map(|(i, acc)| AccountInfo {
+                    index: i,
+                    address: acc.address,
+                    balance: acc.balance,
+                    label: acc.label,
+                    descriptor: None,
+                })
+                .collect(),
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to load accounts: {}",
+                            e
+                        ))),
+                    );
+                }
+            }
+        }
+    };
 
     (StatusCode::OK, Json(ApiResponse::success(accounts)))
 }
@@ -343,184 +1291,618 @@ pub async fn health_check() -> (StatusCode, Json<ApiResponse<HealthCheckResponse
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to list nodes: {}", e))),
+                Json(ApiResponse::message(Message::ListNodesFailed(
+                    e.to_string(),
+                ))),
             );
         }
     };
 
-    let mut running = 0;
-    let mut stopped = 0;
-    let mut unknown = 0;
+    let statuses = probe_statuses(&nodes);
+    let _ = registry.update_statuses(&statuses);
 
-    for node in &nodes {
-        let is_running = match node.chain {
-            ChainType::Solana => {
-                let client = SolanaRpcClient::new(node.rpc_url.clone());
-                client.is_validator_running()
-            }
-            ChainType::Bitcoin => {
-                if let Ok(info) = BitcoinInstanceInfo::load(&node.instance_id) {
-                    match BitcoinRpcClient::new_with_wallet(
-                        info.rpc_url,
-                        info.rpc_user,
-                        info.rpc_password,
-                        "chain-forge",
-                    ) {
-                        Ok(client) => client.is_node_running(),
-                        Err(_) => false,
-                    }
-                } else {
-                    false
-                }
-            }
-        };
+    let count = |status| statuses.iter().filter(|(_, s)| *s == status).count();
+    let response = HealthCheckResponse {
+        total: nodes.len(),
+        running: count(NodeStatus::Running),
+        stopped: count(NodeStatus::Stopped),
+        unknown: count(NodeStatus::Unknown),
+    };
 
-        // Update registry status
-        let new_status = if is_running {
-            running += 1;
-            NodeStatus::Running
-        } else {
-            // Check if it was previously running
-            match node.status {
-                NodeStatus::Running => {
-                    stopped += 1;
-                    NodeStatus::Stopped
-                }
-                NodeStatus::Stopped => {
-                    stopped += 1;
-                    NodeStatus::Stopped
-                }
-                NodeStatus::Unknown => {
-                    unknown += 1;
-                    NodeStatus::Unknown
-                }
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
+/// Check every registered node and report the result for each one
+///
+/// Statuses are probed as for `POST /api/v1/health`, then saved to the
+/// registry in a single write.
+pub async fn check_nodes_health() -> (StatusCode, Json<ApiResponse<GroupOperationResponse>>) {
+    let registry = NodeRegistry::new();
+
+    let nodes = match registry.list() {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::ListNodesFailed(
+                    e.to_string(),
+                ))),
+            );
+        }
+    };
+
+    let statuses = probe_statuses(&nodes);
+    if let Err(e) = registry.update_statuses(&statuses) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::StatusUpdateFailed(
+                e.to_string(),
+            ))),
+        );
+    }
+
+    let results = nodes
+        .iter()
+        .zip(&statuses)
+        .map(|(node, (_, status))| {
+            let running = *status == NodeStatus::Running;
+            NodeOperationResult {
+                node_id: node.node_id.clone(),
+                chain: node.chain.to_string(),
+                success: running,
+                status: status.to_string(),
+                message: (!running).then(|| match status {
+                    NodeStatus::Unknown => "Node is not sending heartbeats".to_string(),
+                    _ => "Node did not respond".to_string(),
+                }),
+                job_id: None,
             }
-        };
+        })
+        .collect::<Vec<_>>();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(GroupOperationResponse::from(results))),
+    )
+}
 
-        // Update status in registry
-        let _ = registry.update_status(&node.node_id, new_status);
+/// Whether a node's RPC endpoint answers
+fn is_node_running(node: &NodeInfo) -> bool {
+    match node.chain {
+        ChainType::Solana => {
+            let client = SolanaRpcClient::new(node.rpc_url.clone());
+            client.is_validator_running()
+        }
+        ChainType::Bitcoin => BitcoinInstanceInfo::load(&node.instance_id)
+            .map(|info| info.is_reachable())
+            .unwrap_or(false),
+        ChainType::Ethereum => EthereumRpcClient::new(node.rpc_url.clone()).is_node_running(),
     }
+}
 
-    let response = HealthCheckResponse {
-        total: nodes.len(),
-        running,
-        stopped,
-        unknown,
+/// Probe each node and work out the status to record for it
+///
+/// A node that answers while its provider has stopped sending heartbeats is
+/// no longer managed, so it's unknown. One that doesn't answer is stopped,
+/// unless its status was already unknown.
+fn probe_statuses(nodes: &[NodeInfo]) -> Vec<(String, NodeStatus)> {
+    let now = chrono::Utc::now();
+    nodes
+        .iter()
+        .map(|node| {
+            let is_running = is_node_running(node);
+            let status = if is_running && node.heartbeat_expired(now) {
+                NodeStatus::Unknown
+            } else if is_running {
+                NodeStatus::Running
+            } else if node.status == NodeStatus::Unknown {
+                NodeStatus::Unknown
+            } else {
+                NodeStatus::Stopped
+            };
+            (node.node_id.clone(), status)
+        })
+        .collect()
+}
+
+/// Concrete RPC port for a start request
+///
+/// With `"port": "auto"` free ports are picked and reserved here rather than
+/// by the chain CLI, so the response can report them.
+fn resolve_port(req: &StartNodeRequest) -> Result<u16, String> {
+    match req.port {
+        PortChoice::Port(port) => Ok(port),
+        PortChoice::Auto if req.chain.eq_ignore_ascii_case("solana") => {
+            auto_port(&req.instance).map_err(|e| e.to_string())
+        }
+        PortChoice::Auto => Err("Automatic ports are only supported for solana".to_string()),
+    }
+}
+
+/// Chain CLI command that starts the node described by `req`
+fn node_command(req: &StartNodeRequest) -> Result<(ChainType, NodeCommand), String> {
+    let chain = parse_chain(&req.chain)?;
+    if chain == ChainType::Ethereum && req.preset.is_some() {
+        return Err("Presets are not supported for ethereum".to_string());
+    }
+
+    let preset = Preset::resolve(req.preset.as_deref()).map_err(|e| e.to_string())?;
+    let accounts = req
+        .accounts
+        .or(preset.map(|p| p.accounts))
+        .unwrap_or_else(default_accounts);
+    let preset_balance = preset.map(|p| match chain {
+        ChainType::Solana => p.solana_balance,
+        _ => p.bitcoin_balance,
+    });
+    let balance = req
+        .balance
+        .or(preset_balance)
+        .unwrap_or_else(default_balance);
+
+    let port_flag = match chain {
+        ChainType::Bitcoin => "--rpc-port",
+        ChainType::Solana | ChainType::Ethereum => "--port",
+    };
+    let mut args = vec![
+        "start".to_string(),
+        "--instance".to_string(),
+        req.instance.clone(),
+        port_flag.to_string(),
+        req.port.to_string(),
+        "--accounts".to_string(),
+        accounts.to_string(),
+        "--balance".to_string(),
+        balance.to_string(),
+    ];
+    if let Some(preset) = preset {
+        args.extend(["--preset".to_string(), preset.name.to_string()]);
+    }
+    if let Some(name) = &req.name {
+        args.extend(["--name".to_string(), name.clone()]);
+    }
+
+    let command = NodeCommand {
+        program: format!("cf-{}", chain),
+        args,
     };
+    Ok((chain, command))
+}
 
-    (StatusCode::OK, Json(ApiResponse::success(response)))
+/// Chain named in a request
+fn parse_chain(chain: &str) -> Result<ChainType, String> {
+    match chain.to_lowercase().as_str() {
+        "solana" => Ok(ChainType::Solana),
+        "bitcoin" => Ok(ChainType::Bitcoin),
+        "ethereum" => Ok(ChainType::Ethereum),
+        _ => Err(Message::InvalidChain.to_string()),
+    }
 }
 
-/// Start a new node (returns immediately with instance info)
-/// Note: Actually starting nodes requires running them in the background
-/// which would need more sophisticated process management.
-/// For now, this returns instructions on how to start the node.
+/// Start a node in the background
+///
+/// The chain CLI runs as a child of the API server; poll the returned job
+/// until the node is funded and registered.
 pub async fn start_node(
-    Json(req): Json<StartNodeRequest>,
+    State(jobs): State<Arc<JobStore>>,
+    State(supervisor): State<Arc<Supervisor>>,
+    Json(mut req): Json<StartNodeRequest>,
 ) -> (StatusCode, Json<ApiResponse<StartNodeResponse>>) {
-    let chain = match req.chain.to_lowercase().as_str() {
-        "solana" => "solana",
-        "bitcoin" => "bitcoin",
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(ApiResponse::error(
-                    "Invalid chain. Use 'solana' or 'bitcoin'",
-                )),
-            );
+    let port = match resolve_port(&req) {
+        Ok(port) => port,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(&e)));
         }
     };
+    req.port = PortChoice::Port(port);
 
-    // Build the command to start the node
-    let cmd = match chain {
-        "solana" => format!(
-            "cf-solana start --instance {} --port {} --accounts {} --balance {}{}",
-            req.instance,
-            req.port,
-            req.accounts,
-            req.balance,
-            req.name
-                .as_ref()
-                .map(|n| format!(" --name \"{}\"", n))
-                .unwrap_or_default()
-        ),
-        "bitcoin" => format!(
-            "cf-bitcoin start --instance {} --rpc-port {} --accounts {} --balance {}{}",
-            req.instance,
-            req.port,
-            req.accounts,
-            req.balance,
-            req.name
-                .as_ref()
-                .map(|n| format!(" --name \"{}\"", n))
-                .unwrap_or_default()
-        ),
-        _ => unreachable!(),
+    let (chain, command) = match node_command(&req) {
+        Ok(planned) => planned,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(&e)));
+        }
+    };
+
+    let node_id = NodeRegistry::node_id(chain, &req.instance);
+    let registered_running = matches!(
+        NodeRegistry::new().get(&node_id),
+        Ok(Some(NodeInfo {
+            status: NodeStatus::Running,
+            ..
+        }))
+    );
+    if registered_running || supervisor.pid(&node_id).is_some() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(&format!(
+                "Node {} is already running",
+                node_id
+            ))),
+        );
+    }
+
+    let log_file = supervisor.log_file(&node_id);
+    let job = {
+        let node_id = node_id.clone();
+        let command = command.clone();
+        jobs.spawn("start_node", Some(node_id.clone()), move |progress| {
+            run_node(progress, &supervisor, &node_id, &command)
+        })
     };
 
     let response = StartNodeResponse {
-        message: "Node start requires running the CLI command in a separate terminal".to_string(),
-        command: cmd,
+        message: format!(
+            "Starting node; poll /api/v1/jobs/{} until it is running",
+            job.id
+        ),
+        job_id: job.id,
+        command: command.to_string(),
         chain: chain.to_string(),
+        ports: (chain == ChainType::Solana).then(|| {
+            let mut config = SolanaConfig::with_instance(&req.instance);
+            config.port = port;
+            config.ports().into()
+        }),
         instance: req.instance,
-        port: req.port,
+        port,
+        log_file: log_file.display().to_string(),
     };
 
-    (StatusCode::OK, Json(ApiResponse::success(response)))
+    (StatusCode::ACCEPTED, Json(ApiResponse::success(response)))
+}
+
+/// Run a node's CLI and wait until it registers the node
+///
+/// The CLI is stopped again if the job is cancelled or the node doesn't
+/// come up within [`NODE_START_TIMEOUT`].
+fn run_node(
+    progress: &JobProgress,
+    supervisor: &Supervisor,
+    node_id: &str,
+    command: &NodeCommand,
+) -> Result<serde_json::Value, String> {
+    progress.phase("spawning", None);
+    let pid = supervisor.spawn(node_id, command)?;
+    let log_file = supervisor.log_file(node_id);
+
+    progress.phase("starting", None);
+    let registry = NodeRegistry::new();
+    let deadline = Instant::now() + NODE_START_TIMEOUT;
+    loop {
+        if progress.is_cancelled() {
+            supervisor.stop(node_id, pid)?;
+            return Err(CANCELLED.to_string());
+        }
+
+        if supervisor.pid(node_id) != Some(pid) {
+            return Err(format!(
+                "{} exited during startup; see {}",
+                command.program,
+                log_file.display()
+            ));
+        }
+
+        if let Ok(Some(node)) = registry.get(node_id) {
+            if node.status == NodeStatus::Running {
+                registry.set_pid(node_id, pid).map_err(|e| e.to_string())?;
+                return Ok(serde_json::json!({
+                    "node_id": node_id,
+                    "pid": pid,
+                    "rpc_url": node.rpc_url,
+                    "log_file": log_file,
+                }));
+            }
+        }
+
+        if Instant::now() >= deadline {
+            supervisor.stop(node_id, pid)?;
+            return Err(format!(
+                "Node did not start within {}s; see {}",
+                NODE_START_TIMEOUT.as_secs(),
+                log_file.display()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
 }
 
-/// Stop a node (marks as stopped in registry)
-/// Note: Actually stopping nodes would require process management.
-/// For now, this just updates the registry.
+/// Stop a node
+///
+/// Nodes with a known process (started through the API) are shut down and
+/// this waits until they exit. Nodes started from a terminal are only
+/// marked as stopped, with instructions for stopping them.
 pub async fn stop_node(
+    State(supervisor): State<Arc<Supervisor>>,
     Path(node_id): Path<String>,
 ) -> (StatusCode, Json<ApiResponse<StopNodeResponse>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
     let registry = NodeRegistry::new();
 
-    // Check if node exists
-    match registry.get(&node_id) {
-        Ok(Some(node)) => {
-            // Update status to stopped
-            if let Err(e) = registry.update_status(&node_id, NodeStatus::Stopped) {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error(&format!(
-                        "Failed to update status: {}",
-                        e
-                    ))),
-                );
-            }
+    let Some(pid) = supervisor.pid(&node_id).or(node.pid) else {
+        if let Err(e) = registry.update_status(&node_id, NodeStatus::Stopped) {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::StatusUpdateFailed(
+                    e.to_string(),
+                ))),
+            );
+        }
 
-            let instruction = match node.chain {
-                ChainType::Solana => format!(
-                    "Press Ctrl+C in the terminal running 'cf-solana start --instance {}'",
-                    node.instance_id
-                ),
-                ChainType::Bitcoin => format!(
-                    "Press Ctrl+C in the terminal running 'cf-bitcoin start --instance {}'",
-                    node.instance_id
-                ),
-            };
+        let instruction = format!(
+            "Press Ctrl+C in the terminal running 'cf-{} start --instance {}'",
+            node.chain, node.instance_id
+        );
+        let response = StopNodeResponse {
+            message: "Node marked as stopped. To actually stop the node:".to_string(),
+            instruction: Some(instruction),
+            node_id,
+            stopped: false,
+        };
+        return (StatusCode::OK, Json(ApiResponse::success(response)));
+    };
 
-            let response = StopNodeResponse {
-                message: "Node marked as stopped. To actually stop the node:".to_string(),
-                instruction,
-                node_id,
-            };
+    let stopped = {
+        let node_id = node_id.clone();
+        tokio::task::spawn_blocking(move || supervisor.stop(&node_id, pid))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result)
+    };
+    if let Err(e) = stopped {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::StopNodeFailed(e.to_string()))),
+        );
+    }
 
-            (StatusCode::OK, Json(ApiResponse::success(response)))
+    // A CLI that had to be killed couldn't unregister the node itself
+    if let Ok(Some(_)) = registry.get(&node_id) {
+        let _ = registry.update_status(&node_id, NodeStatus::Stopped);
+    }
+
+    let response = StopNodeResponse {
+        message: "Node stopped".to_string(),
+        instruction: None,
+        node_id,
+        stopped: true,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
+/// Stop every running node, optionally only those of one chain
+///
+/// Nodes are stopped in parallel, as `DELETE /api/v1/nodes/{node_id}`
+/// would, and the registry is updated in a single write once they're all
+/// done.
+pub async fn stop_all_nodes(
+    State(supervisor): State<Arc<Supervisor>>,
+    Query(query): Query<StopAllQuery>,
+) -> (StatusCode, Json<ApiResponse<GroupOperationResponse>>) {
+    let chain = match query.chain.as_deref().map(parse_chain).transpose() {
+        Ok(chain) => chain,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(&e)));
         }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Node not found")),
+    };
+
+    let nodes = match NodeRegistry::new().list() {
+        Ok(nodes) => nodes
+            .into_iter()
+            .filter(|node| node.status != NodeStatus::Stopped)
+            .filter(|node| chain.map_or(true, |chain| node.chain == chain))
+            .collect(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::ListNodesFailed(
+                    e.to_string(),
+                ))),
+            );
+        }
+    };
+
+    match stop_nodes(supervisor, nodes).await {
+        Ok(results) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(GroupOperationResponse::from(results))),
         ),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+            Json(ApiResponse::error(&e)),
         ),
     }
 }
 
+/// Restart every node of an environment
+///
+/// An environment is the set of nodes sharing an instance ID across chains,
+/// such as the nodes `cf scaffold` starts for a project. Every node must
+/// have been started through the API and every start command must be valid
+/// before anything is stopped; then all nodes are stopped and each one is
+/// started again as a background job.
+pub async fn restart_environment(
+    State(jobs): State<Arc<JobStore>>,
+    State(supervisor): State<Arc<Supervisor>>,
+    Path(name): Path<String>,
+) -> (StatusCode, Json<ApiResponse<GroupOperationResponse>>) {
+    let nodes: Vec<NodeInfo> = match NodeRegistry::new().list() {
+        Ok(nodes) => nodes
+            .into_iter()
+            .filter(|node| node.instance_id == name)
+            .collect(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::ListNodesFailed(
+                    e.to_string(),
+                ))),
+            );
+        }
+    };
+    if nodes.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(&format!(
+                "No nodes in environment '{}'",
+                name
+            ))),
+        );
+    }
+
+    let unmanaged: Vec<&str> = nodes
+        .iter()
+        .filter(|node| node.status != NodeStatus::Stopped)
+        .filter(|node| supervisor.pid(&node.node_id).or(node.pid).is_none())
+        .map(|node| node.node_id.as_str())
+        .collect();
+    if !unmanaged.is_empty() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(&format!(
+                "Nodes started from a terminal can't be restarted: {}",
+                unmanaged.join(", ")
+            ))),
+        );
+    }
+
+    let mut commands = BTreeMap::new();
+    for node in &nodes {
+        match node_command(&restart_request(node)) {
+            Ok((_, command)) => {
+                commands.insert(node.node_id.clone(), command);
+            }
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::error(&format!("{}: {}", node.node_id, e))),
+                );
+            }
+        }
+    }
+
+    let mut results = match stop_nodes(supervisor.clone(), nodes).await {
+        Ok(results) => results,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&e)),
+            );
+        }
+    };
+
+    // Nodes that didn't stop keep running as they are
+    for result in results.iter_mut().filter(|result| result.success) {
+        let Some(command) = commands.remove(&result.node_id) else {
+            continue;
+        };
+        let node_id = result.node_id.clone();
+        let supervisor = supervisor.clone();
+        let job = jobs.spawn("start_node", Some(node_id.clone()), move |progress| {
+            run_node(progress, &supervisor, &node_id, &command)
+        });
+        result.message = Some(format!(
+            "Restarting; poll /api/v1/jobs/{} until it is running",
+            job.id
+        ));
+        result.job_id = Some(job.id);
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(ApiResponse::success(GroupOperationResponse::from(results))),
+    )
+}
+
+/// Request that starts a registered node again with the same settings
+fn restart_request(node: &NodeInfo) -> StartNodeRequest {
+    StartNodeRequest {
+        chain: node.chain.to_string(),
+        instance: node.instance_id.clone(),
+        name: node.name.clone(),
+        port: PortChoice::Port(node.rpc_port),
+        preset: None,
+        accounts: Some(node.accounts_count),
+        balance: None,
+    }
+}
+
+/// Stop several nodes in parallel and mark the ones that stopped as stopped
+///
+/// Nodes the server has no process for are only marked as stopped, as with
+/// `DELETE /api/v1/nodes/{node_id}`. Errs only if the registry can't be
+/// updated.
+async fn stop_nodes(
+    supervisor: Arc<Supervisor>,
+    nodes: Vec<NodeInfo>,
+) -> Result<Vec<NodeOperationResult>, String> {
+    let results = tokio::task::spawn_blocking(move || {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = nodes
+                .iter()
+                .map(|node| {
+                    let supervisor = &supervisor;
+                    scope.spawn(move || stop_process(supervisor, node))
+                })
+                .collect();
+
+            nodes
+                .iter()
+                .zip(handles)
+                .map(|(node, handle)| {
+                    let stopped = handle
+                        .join()
+                        .unwrap_or_else(|_| Err("Stopping the node panicked".to_string()));
+                    let (success, status, message) = match stopped {
+                        Ok(message) => (true, NodeStatus::Stopped, message),
+                        Err(e) => (
+                            false,
+                            node.status,
+                            Some(Message::StopNodeFailed(e).to_string()),
+                        ),
+                    };
+                    NodeOperationResult {
+                        node_id: node.node_id.clone(),
+                        chain: node.chain.to_string(),
+                        success,
+                        status: status.to_string(),
+                        message,
+                        job_id: None,
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // A CLI that had to be killed couldn't unregister the node itself
+    let stopped: Vec<(String, NodeStatus)> = results
+        .iter()
+        .filter(|result| result.success)
+        .map(|result| (result.node_id.clone(), NodeStatus::Stopped))
+        .collect();
+    NodeRegistry::new()
+        .update_statuses(&stopped)
+        .map_err(|e| Message::StatusUpdateFailed(e.to_string()).to_string())?;
+    Ok(results)
+}
+
+/// Stop the CLI running `node`, blocking until it exits
+///
+/// Returns how to stop a node the server has no process for.
+fn stop_process(supervisor: &Supervisor, node: &NodeInfo) -> Result<Option<String>, String> {
+    let Some(pid) = supervisor.pid(&node.node_id).or(node.pid) else {
+        return Ok(Some(format!(
+            "Marked as stopped; press Ctrl+C in the terminal running 'cf-{} start --instance {}'",
+            node.chain, node.instance_id
+        )));
+    };
+    supervisor.stop(&node.node_id, pid).map(|()| None)
+}
+
 /// Fund an account on a specific node
 pub async fn fund_account(
     Path(node_id): Path<String>,
@@ -533,13 +1915,13 @@ pub async fn fund_account(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Node not found")),
+                Json(ApiResponse::message(Message::NodeNotFound)),
             );
         }
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
             );
         }
     };
@@ -550,12 +1932,23 @@ pub async fn fund_account(
             if !client.is_validator_running() {
                 return (
                     StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Solana validator is not running")),
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Solana,
+                    ))),
                 );
             }
 
             match client.request_airdrop(&req.address, req.amount) {
                 Ok(signature) => {
+                    chain_forge_events::publish(
+                        ChainType::Solana,
+                        &node.instance_id,
+                        ChainEvent::FundsDispensed {
+                            address: Some(req.address.clone()),
+                            amount: req.amount,
+                        },
+                    );
+
                     let response = FundResponse {
                         success: true,
                         txid_or_signature: signature,
@@ -566,7 +1959,7 @@ pub async fn fund_account(
                 }
                 Err(e) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error(&format!("Airdrop failed: {}", e))),
+                    Json(ApiResponse::message(Message::AirdropFailed(e.to_string()))),
                 ),
             }
         }
@@ -584,12 +1977,7 @@ pub async fn fund_account(
                 }
             };
 
-            let client = match BitcoinRpcClient::new_with_wallet(
-                info.rpc_url,
-                info.rpc_user,
-                info.rpc_password,
-                "chain-forge",
-            ) {
+            let client = match info.wallet_client() {
                 Ok(client) => client,
                 Err(e) => {
                     return (
@@ -605,16 +1993,25 @@ pub async fn fund_account(
             if !client.is_node_running() {
                 return (
                     StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Bitcoin node is not running")),
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Bitcoin,
+                    ))),
                 );
             }
 
-            match client.send_to_address(&req.address, req.amount) {
+            match client.fund_address(info.treasury.as_deref(), &req.address, req.amount) {
                 Ok(txid) => {
-                    // Mine a block to confirm
-                    let _ = client
-                        .get_new_address(Some("mining"))
-                        .and_then(|addr| client.mine_blocks(1, &addr));
+                    chain_forge_events::publish(
+                        ChainType::Bitcoin,
+                        &node.instance_id,
+                        ChainEvent::FundsDispensed {
+                            address: Some(req.address.clone()),
+                            amount: req.amount,
+                        },
+                    );
+
+                    let confirmations = req.confirm.unwrap_or(info.confirmations);
+                    let _ = confirm::confirm(&client, &node.instance_id, confirmations);
 
                     let response = FundResponse {
                         success: true,
@@ -626,23 +2023,265 @@ pub async fn fund_account(
                 }
                 Err(e) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error(&format!("Transaction failed: {}", e))),
+                    Json(ApiResponse::message(Message::TransactionFailed(
+                        e.to_string(),
+                    ))),
+                ),
+            }
+        }
+        ChainType::Ethereum => {
+            let client = EthereumRpcClient::new(node.rpc_url.clone());
+            if !client.is_node_running() {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Ethereum,
+                    ))),
+                );
+            }
+
+            // anvil credits the balance directly, so there is no transaction
+            match client.add_balance(&req.address, req.amount) {
+                Ok(_) => {
+                    chain_forge_events::publish(
+                        ChainType::Ethereum,
+                        &node.instance_id,
+                        ChainEvent::FundsDispensed {
+                            address: Some(req.address.clone()),
+                            amount: req.amount,
+                        },
+                    );
+
+                    let response = FundResponse {
+                        success: true,
+                        txid_or_signature: String::new(),
+                        address: req.address,
+                        amount: req.amount,
+                    };
+                    (StatusCode::OK, Json(ApiResponse::success(response)))
+                }
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::message(Message::FundingFailed(e.to_string()))),
                 ),
             }
         }
     }
 }
 
-/// Clean up the registry by removing nodes that are not currently running
-pub async fn cleanup_registry() -> (StatusCode, Json<ApiResponse<CleanupResponse>>) {
-    let registry = NodeRegistry::new();
+/// Transfer funds from one of a node's accounts (Bitcoin and Solana)
+///
+/// Solana transfers are signed with the instance's stored keypair (or its
+/// emulated signer). Bitcoin transfers spend the source address's UTXOs and
+/// mine blocks to confirm them as `confirm` or the instance says.
+pub async fn transfer(
+    Path(node_id): Path<String>,
+    Json(req): Json<TransferRequest>,
+) -> (StatusCode, Json<ApiResponse<TransferResponse>>) {
+    if !req.amount.is_finite() || req.amount <= 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::AmountNotPositive)),
+        );
+    }
 
-    let nodes = match registry.list() {
-        Ok(nodes) => nodes,
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let result = match node.chain {
+        ChainType::Solana => {
+            let (client, instance_id) = match solana_client(&node_id, "Transfers") {
+                Ok(client) => client,
+                Err(response) => return response,
+            };
+            let signer = match solana_signer(&instance_id, &req.from) {
+                Ok(signer) => signer,
+                Err(response) => return response,
+            };
+            client.transfer_with_signer(&req.to, req.amount, signer.as_ref(), None, None)
+        }
+        ChainType::Bitcoin => {
+            let client = match bitcoin_wallet_client(&node_id, "Transfers") {
+                Ok(client) => client,
+                Err(response) => return response,
+            };
+            let config = BitcoinConfig::with_instance(&node.instance_id);
+            let from_account = BitcoinAccountsStorage::with_path(config.accounts_file())
+                .load()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|a| a.address == req.from);
+
+            let result = match from_account.filter(|a| a.external_signer) {
+                Some(account) => {
+                    let keystore = SignerKeystore::with_path(config.signer_keystore_file());
+                    BitcoinEmulatedSigner::load(&keystore, &account.address).and_then(|signer| {
+                        client.send_from_address_with_signer(&account, &req.to, req.amount, &signer)
+                    })
+                }
+                None => client.send_from_address(&req.from, &req.to, req.amount),
+            };
+
+            if result.is_ok() {
+                let confirmations = req.confirm.unwrap_or_else(|| {
+                    BitcoinInstanceInfo::load(&node.instance_id)
+                        .map(|info| info.confirmations)
+                        .unwrap_or_default()
+                });
+                let _ = confirm::confirm(&client, &node.instance_id, confirmations);
+            }
+            result
+        }
+        ChainType::Ethereum => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "Transfers are only available for Bitcoin and Solana nodes",
+                )),
+            );
+        }
+    };
+
+    match result {
+        Ok(txid_or_signature) => {
+            let response = TransferResponse {
+                txid_or_signature,
+                from: req.from,
+                to: req.to,
+                amount: req.amount,
+            };
+            (StatusCode::OK, Json(ApiResponse::success(response)))
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::TransferFailed(e.to_string()))),
+        ),
+    }
+}
+
+/// Most blocks a single mine request may ask for
+const MAX_MINE_BLOCKS: u32 = 1000;
+
+/// Mine blocks on a Bitcoin node
+///
+/// Other chains produce blocks on their own, so they get an error saying so.
+pub async fn mine_blocks(
+    Path(node_id): Path<String>,
+    Json(req): Json<MineRequest>,
+) -> (StatusCode, Json<ApiResponse<MineResponse>>) {
+    if req.blocks == 0 || req.blocks > MAX_MINE_BLOCKS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "blocks must be between 1 and {}",
+                MAX_MINE_BLOCKS
+            ))),
+        );
+    }
+
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let unsupported = match node.chain {
+        ChainType::Bitcoin => None,
+        ChainType::Solana => Some(
+            "Solana validators produce slots on their own; mining is only available for Bitcoin nodes",
+        ),
+        ChainType::Ethereum => Some(
+            "anvil mines a block per transaction, or on a timer with a block time; \
+             mining is only available for Bitcoin nodes",
+        ),
+    };
+    if let Some(message) = unsupported {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(message)));
+    }
+
+    let client = match bitcoin_wallet_client(&node_id, "Mining") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let can_mine = BitcoinInstanceInfo::load(&node.instance_id)
+        .map(|info| info.chain.can_mine())
+        .unwrap_or(true);
+    if !can_mine {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Blocks on this signet must be signed by its challenge key, which Chain Forge doesn't hold",
+            )),
+        );
+    }
+
+    let address = match req.address {
+        Some(address) => address,
+        None => match client.get_new_address(Some("mining")) {
+            Ok(address) => address,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to get mining address: {}",
+                        e
+                    ))),
+                );
+            }
+        },
+    };
+
+    let mined = client
+        .mine_blocks(req.blocks, &address)
+        .and_then(|hashes| Ok((hashes, client.get_block_count()?)));
+    match mined {
+        Ok((block_hashes, height)) => {
+            chain_forge_events::publish(
+                ChainType::Bitcoin,
+                &node.instance_id,
+                ChainEvent::BlockMined {
+                    height: Some(height),
+                    count: block_hashes.len() as u64,
+                },
+            );
+            let response = MineResponse {
+                block_hashes,
+                height,
+            };
+            (StatusCode::OK, Json(ApiResponse::success(response)))
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::MiningFailed(e.to_string()))),
+        ),
+    }
+}
+
+/// Clean up the registry by removing nodes that are not currently running
+pub async fn cleanup_registry() -> (StatusCode, Json<ApiResponse<CleanupResponse>>) {
+    let registry = NodeRegistry::new();
+
+    // Write back what reads only observe, so exited nodes are recorded as
+    // stopped and cleared below
+    if let Err(e) = registry.reconcile() {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to clean registry: {}",
+                e
+            ))),
+        );
+    }
+
+    let nodes = match registry.list() {
+        Ok(nodes) => nodes,
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to list nodes: {}", e))),
+                Json(ApiResponse::message(Message::ListNodesFailed(
+                    e.to_string(),
+                ))),
             );
         }
     };
@@ -650,29 +2289,7 @@ pub async fn cleanup_registry() -> (StatusCode, Json<ApiResponse<CleanupResponse
     let mut removed_nodes = Vec::new();
 
     for node in &nodes {
-        let is_running = match node.chain {
-            ChainType::Solana => {
-                let client = SolanaRpcClient::new(node.rpc_url.clone());
-                client.is_validator_running()
-            }
-            ChainType::Bitcoin => {
-                if let Ok(info) = BitcoinInstanceInfo::load(&node.instance_id) {
-                    match BitcoinRpcClient::new_with_wallet(
-                        info.rpc_url,
-                        info.rpc_user,
-                        info.rpc_password,
-                        "chain-forge",
-                    ) {
-                        Ok(client) => client.is_node_running(),
-                        Err(_) => false,
-                    }
-                } else {
-                    false
-                }
-            }
-        };
-
-        if !is_running {
+        if !is_node_running(node) {
             let _ = registry.update_status(&node.node_id, NodeStatus::Stopped);
             removed_nodes.push(node.node_id.clone());
         }
@@ -701,10 +2318,162 @@ pub async fn cleanup_registry() -> (StatusCode, Json<ApiResponse<CleanupResponse
     )
 }
 
+/// Re-read the `[api]` config section without restarting the server
+///
+/// Requires the debug token when one is configured; otherwise only loopback
+/// clients may reload.
+pub async fn reload_settings(
+    State(settings): State<Arc<LiveSettings>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<ReloadResponse>>) {
+    let current = settings.current();
+    let debug_log = &current.debug_log;
+    if !debug_log.allows_admin(peer.ip(), &headers) {
+        return if debug_log.token.is_some() {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ApiResponse::error("A valid debug token is required")),
+            )
+        } else {
+            (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::error(
+                    "Reloading is only allowed from localhost unless a debug token is set",
+                )),
+            )
+        };
+    }
+
+    match settings.reload() {
+        Ok(current) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(ReloadResponse {
+                config_path: Config::find_file().map(|path| path.display().to_string()),
+                debug_routes: current.debug_log.routes.clone(),
+                debug_token: current.debug_log.token.is_some(),
+                watch_interval_secs: current.watch_interval.as_secs(),
+            })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to reload configuration, keeping current settings: {}",
+                e
+            ))),
+        ),
+    }
+}
+
+/// A transaction ranked by recency, ties going to the one offered first
+struct RankedTransaction<K> {
+    key: K,
+    seq: usize,
+    tx: TransactionInfo,
+}
+
+impl<K: Ord> RankedTransaction<K> {
+    fn rank(&self) -> (&K, Reverse<usize>) {
+        (&self.key, Reverse(self.seq))
+    }
+}
+
+impl<K: Ord> PartialEq for RankedTransaction<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+}
+
+impl<K: Ord> Eq for RankedTransaction<K> {}
+
+impl<K: Ord> PartialOrd for RankedTransaction<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> Ord for RankedTransaction<K> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+/// The `limit` most recent transactions offered, each signature once
+///
+/// Only `limit` transactions are held at a time: the oldest one kept is
+/// dropped when a more recent one comes along.
+struct RecentTransactions<K> {
+    limit: usize,
+    /// Min-heap, so the oldest transaction kept is on top
+    heap: BinaryHeap<Reverse<RankedTransaction<K>>>,
+    seen: HashSet<String>,
+    offered: usize,
+}
+
+impl<K: Ord> RecentTransactions<K> {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            heap: BinaryHeap::new(),
+            seen: HashSet::new(),
+            offered: 0,
+        }
+    }
+
+    /// Keep `tx` if it's among the most recent so far, ranked by `key`
+    ///
+    /// Returns `false` if `tx` is older than every transaction kept, so a
+    /// caller offering transactions newest first can stop there.
+    fn offer(&mut self, key: K, tx: TransactionInfo) -> bool {
+        if self.seen.contains(&tx.signature) {
+            return true;
+        }
+        let entry = RankedTransaction {
+            key,
+            seq: self.offered,
+            tx,
+        };
+        self.offered += 1;
+
+        if self.heap.len() >= self.limit {
+            let newer = self
+                .heap
+                .peek()
+                .is_some_and(|Reverse(oldest)| entry > *oldest);
+            if !newer {
+                return false;
+            }
+            if let Some(Reverse(dropped)) = self.heap.pop() {
+                self.seen.remove(&dropped.tx.signature);
+            }
+        }
+        self.seen.insert(entry.tx.signature.clone());
+        self.heap.push(Reverse(entry));
+        true
+    }
+
+    /// The transactions kept, most recent first
+    fn into_vec(self) -> Vec<TransactionInfo> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(entry)| entry.tx)
+            .collect()
+    }
+}
+
 /// Get recent transactions for all accounts on a specific node
 pub async fn get_node_transactions(
     Path(node_id): Path<String>,
+    Query(query): Query<TransactionsQuery>,
 ) -> (StatusCode, Json<ApiResponse<Vec<TransactionInfo>>>) {
+    if query.limit == Some(0) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("limit must be at least 1")),
+        );
+    }
+    let limit = query.limit.unwrap_or(usize::MAX);
     let registry = NodeRegistry::new();
 
     let node = match registry.get(&node_id) {
@@ -712,13 +2481,13 @@ pub async fn get_node_transactions(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Node not found")),
+                Json(ApiResponse::message(Message::NodeNotFound)),
             );
         }
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
             );
         }
     };
@@ -749,17 +2518,22 @@ pub async fn get_node_transactions(
             if !rpc_client.is_validator_running() {
                 return (
                     StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Solana validator is not running")),
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Solana,
+                    ))),
                 );
             }
 
-            let mut all_transactions: Vec<TransactionInfo> = Vec::new();
-
+            // Signatures come newest first, so each account's list is only
+            // read until it falls behind everything kept
+            let mut recent = RecentTransactions::new(limit);
             for account in &accounts {
-                match rpc_client.get_signatures_for_address(&account.public_key, Some(10)) {
+                match rpc_client
+                    .get_signatures_for_address(&account.public_key, Some(limit.min(10)))
+                {
                     Ok(signatures) => {
                         for sig in signatures {
-                            all_transactions.push(TransactionInfo {
+                            let tx = TransactionInfo {
                                 signature: sig.signature,
                                 slot: sig.slot,
                                 err: sig.err,
@@ -767,25 +2541,26 @@ pub async fn get_node_transactions(
                                 block_time: sig.block_time,
                                 confirmation_status: sig.confirmation_status,
                                 account: account.public_key.clone(),
-                            });
+                            };
+                            if !recent.offer(sig.slot, tx) {
+                                break;
+                            }
                         }
                     }
                     Err(e) => {
-                        eprintln!(
-                            "Warning: Failed to get transactions for {}: {}",
-                            account.public_key, e
+                        warn!(
+                            account = %account.public_key,
+                            error = %e,
+                            "Failed to get transactions"
                         );
                     }
                 }
             }
 
-            // Sort by slot descending (most recent first)
-            all_transactions.sort_by(|a, b| b.slot.cmp(&a.slot));
-
-            // Deduplicate by signature (same tx could appear for multiple accounts)
-            all_transactions.dedup_by(|a, b| a.signature == b.signature);
-
-            (StatusCode::OK, Json(ApiResponse::success(all_transactions)))
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(recent.into_vec())),
+            )
         }
         ChainType::Bitcoin => {
             // Load Bitcoin instance info and create wallet RPC client
@@ -802,12 +2577,7 @@ pub async fn get_node_transactions(
                 }
             };
 
-            let rpc_client = match BitcoinRpcClient::new_with_wallet(
-                info.rpc_url,
-                info.rpc_user,
-                info.rpc_password,
-                "chain-forge",
-            ) {
+            let rpc_client = match info.wallet_client() {
                 Ok(client) => client,
                 Err(e) => {
                     return (
@@ -823,7 +2593,9 @@ pub async fn get_node_transactions(
             if !rpc_client.is_node_running() {
                 return (
                     StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Bitcoin node is not running")),
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Bitcoin,
+                    ))),
                 );
             }
 
@@ -862,194 +2634,2502 @@ pub async fn get_node_transactions(
                 }
             };
 
-            // Filter to only transactions involving known accounts
-            let mut all_transactions: Vec<TransactionInfo> = wallet_txs
+            // Keep the most recent transactions involving known accounts,
+            // once per txid (the same tx can appear for sender and receiver)
+            let mut recent = RecentTransactions::new(limit);
+            for tx in wallet_txs
                 .into_iter()
                 .filter(|tx| known_addresses.contains(&tx.address))
-                .map(|tx| {
-                    let confirmation_status = if tx.confirmations > 0 {
-                        Some(format!("{} confirmations", tx.confirmations))
-                    } else {
-                        Some("unconfirmed".to_string())
-                    };
+            {
+                let confirmation_status = if tx.confirmations > 0 {
+                    Some(format!("{} confirmations", tx.confirmations))
+                } else {
+                    Some("unconfirmed".to_string())
+                };
 
+                let block_time = tx.block_time;
+                recent.offer(
+                    block_time,
                     TransactionInfo {
                         signature: tx.txid,
                         slot: tx.block_height,
                         err: None,
                         memo: None,
-                        block_time: tx.block_time,
+                        block_time,
                         confirmation_status,
                         account: tx.address,
-                    }
-                })
-                .collect();
-
-            // Sort by block_time descending (most recent first)
-            all_transactions.sort_by(|a, b| b.block_time.cmp(&a.block_time));
-
-            // Deduplicate by txid (same tx could appear for sender and receiver)
-            all_transactions.dedup_by(|a, b| a.signature == b.signature);
+                    },
+                );
+            }
 
-            (StatusCode::OK, Json(ApiResponse::success(all_transactions)))
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(recent.into_vec())),
+            )
         }
+        ChainType::Ethereum => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Transaction history is not available for Ethereum nodes",
+            )),
+        ),
     }
 }
 
-/// Get detailed transaction info by signature for a specific node
-pub async fn get_transaction_detail(
-    Path((node_id, signature)): Path<(String, String)>,
-) -> (StatusCode, Json<ApiResponse<TransactionDetailInfo>>) {
+/// Search transactions on a node by address, amount range, and time window
+///
+/// Scans the last 100 signatures per account (Solana) or the last 1000 wallet
+/// transactions (Bitcoin) and returns one result per matching account.
+pub async fn search_node_transactions(
+    Path(node_id): Path<String>,
+    Query(query): Query<TransactionSearchQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<TransactionSearchResult>>>) {
     let registry = NodeRegistry::new();
 
-    let node = match registry.get(&node_id) {
-        Ok(Some(node)) => node,
-        Ok(None) => {
+    let node = match registry.get(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+
+    match node.chain {
+        ChainType::Solana => {
+            let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+            if !rpc_client.is_validator_running() {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Solana,
+                    ))),
+                );
+            }
+
+            let addresses = match &query.address {
+                Some(address) => vec![address.clone()],
+                None => {
+                    let accounts_file = Config::data_dir()
+                        .join("solana")
+                        .join("instances")
+                        .join(&node.instance_id)
+                        .join("accounts.json");
+                    match SolanaAccountsStorage::with_path(accounts_file).load() {
+                        Ok(accounts) => accounts.into_iter().map(|a| a.public_key).collect(),
+                        Err(e) => {
+                            return (
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                Json(ApiResponse::error(&format!(
+                                    "Failed to load accounts: {}",
+                                    e
+                                ))),
+                            );
+                        }
+                    }
+                }
+            };
+
+            let mut results = Vec::new();
+            for address in &addresses {
+                let signatures = match rpc_client.get_signatures_for_address(address, Some(100)) {
+                    Ok(signatures) => signatures,
+                    Err(e) => {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(ApiResponse::error(&format!(
+                                "Failed to get transactions for {}: {}",
+                                address, e
+                            ))),
+                        );
+                    }
+                };
+
+                for sig in signatures {
+                    // Signatures are newest first, so stop at the time window
+                    if let (Some(since), Some(time)) = (query.since, sig.block_time) {
+                        if time < since {
+                            break;
+                        }
+                    }
+
+                    let detail = match rpc_client.get_transaction(&sig.signature) {
+                        Ok(detail) => detail,
+                        Err(e) => {
+                            warn!(
+                                signature = %sig.signature,
+                                error = %e,
+                                "Failed to get transaction"
+                            );
+                            continue;
+                        }
+                    };
+
+                    let amount = detail
+                        .balance_changes
+                        .iter()
+                        .find(|c| &c.account == address)
+                        .map(|c| c.change)
+                        .unwrap_or(0.0);
+                    let result = TransactionSearchResult {
+                        signature: sig.signature,
+                        slot: sig.slot,
+                        block_time: sig.block_time,
+                        account: address.clone(),
+                        amount,
+                    };
+                    if query.matches(&result) {
+                        results.push(result);
+                    }
+                }
+            }
+
+            // Sort by slot descending (most recent first)
+            results.sort_by(|a, b| b.slot.cmp(&a.slot));
+
+            (StatusCode::OK, Json(ApiResponse::success(results)))
+        }
+        ChainType::Bitcoin => {
+            let info = match BitcoinInstanceInfo::load(&node.instance_id) {
+                Ok(info) => info,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to load instance info: {}",
+                            e
+                        ))),
+                    );
+                }
+            };
+
+            let rpc_client = match info.wallet_client() {
+                Ok(client) => client,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to create RPC client: {}",
+                            e
+                        ))),
+                    );
+                }
+            };
+
+            if !rpc_client.is_node_running() {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Bitcoin,
+                    ))),
+                );
+            }
+
+            let accounts_file = Config::data_dir()
+                .join("bitcoin")
+                .join("instances")
+                .join(&node.instance_id)
+                .join("accounts.json");
+            let known_addresses: std::collections::HashSet<String> =
+                match BitcoinAccountsStorage::with_path(accounts_file).load() {
+                    Ok(accounts) => accounts.into_iter().map(|a| a.address).collect(),
+                    Err(e) => {
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(ApiResponse::error(&format!(
+                                "Failed to load accounts: {}",
+                                e
+                            ))),
+                        );
+                    }
+                };
+
+            let wallet_txs = match rpc_client.list_transactions(1000) {
+                Ok(txs) => txs,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to list transactions: {}",
+                            e
+                        ))),
+                    );
+                }
+            };
+
+            let mut results: Vec<TransactionSearchResult> = wallet_txs
+                .into_iter()
+                .filter(|tx| match &query.address {
+                    Some(address) => &tx.address == address,
+                    None => known_addresses.contains(&tx.address),
+                })
+                .map(|tx| TransactionSearchResult {
+                    signature: tx.txid,
+                    slot: tx.block_height,
+                    block_time: tx.block_time,
+                    account: tx.address,
+                    amount: tx.amount,
+                })
+                .filter(|tx| query.matches(tx))
+                .collect();
+
+            // Sort by block_time descending (most recent first)
+            results.sort_by(|a, b| b.block_time.cmp(&a.block_time));
+
+            (StatusCode::OK, Json(ApiResponse::success(results)))
+        }
+        ChainType::Ethereum => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Transaction history is not available for Ethereum nodes",
+            )),
+        ),
+    }
+}
+
+/// Get time-bucketed counts of transactions, blocks mined, and funds dispensed
+pub async fn get_node_activity(
+    Path(node_id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ActivityBucket>>>) {
+    let bucket = query.bucket.as_deref().unwrap_or("1m");
+    let Some(bucket_secs) = activity::parse_bucket(bucket) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "Invalid bucket '{}'. Use a number followed by s, m, h, or d (e.g. 1m)",
+                bucket
+            ))),
+        );
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let Some(since) = activity::window_start(query.since, now, bucket_secs) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "Time window must be in the past and span at most {} buckets",
+                activity::MAX_BUCKETS
+            ))),
+        );
+    };
+
+    let registry = NodeRegistry::new();
+
+    let node = match registry.get(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+
+    let audit_entries: Vec<_> = match AuditLog::new().entries() {
+        Ok(entries) => entries
+            .into_iter()
+            .filter(|e| e.chain == node.chain && e.instance_id == node.instance_id)
+            .collect(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to read audit log: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    // Transaction times are best effort: a stopped node still has an
+    // audit history worth charting
+    let transaction_times = match recent_transaction_times(&node) {
+        Ok(times) => times,
+        Err(e) => {
+            warn!(node_id = %node.node_id, error = %e, "Failed to get transactions");
+            Vec::new()
+        }
+    };
+
+    let buckets =
+        activity::bucket_activity(&audit_entries, &transaction_times, bucket_secs, since, now);
+    (StatusCode::OK, Json(ApiResponse::success(buckets)))
+}
+
+/// Block times of recent confirmed transactions involving a node's accounts
+fn recent_transaction_times(node: &NodeInfo) -> Result<Vec<i64>, String> {
+    let accounts_file = Config::data_dir()
+        .join(node.chain.to_string())
+        .join("instances")
+        .join(&node.instance_id)
+        .join("accounts.json");
+
+    match node.chain {
+        ChainType::Solana => {
+            let accounts = SolanaAccountsStorage::with_path(accounts_file)
+                .load()
+                .map_err(|e| e.to_string())?;
+            let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+
+            let mut seen = std::collections::HashSet::new();
+            let mut times = Vec::new();
+            for account in &accounts {
+                let signatures = rpc_client
+                    .get_signatures_for_address(&account.public_key, Some(100))
+                    .map_err(|e| e.to_string())?;
+                for sig in signatures {
+                    if let Some(time) = sig.block_time {
+                        if seen.insert(sig.signature) {
+                            times.push(time);
+                        }
+                    }
+                }
+            }
+            Ok(times)
+        }
+        ChainType::Bitcoin => {
+            let known_addresses: std::collections::HashSet<String> =
+                BitcoinAccountsStorage::with_path(accounts_file)
+                    .load()
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|a| a.address)
+                    .collect();
+            let info = BitcoinInstanceInfo::load(&node.instance_id).map_err(|e| e.to_string())?;
+            let rpc_client = info.wallet_client().map_err(|e| e.to_string())?;
+
+            let mut seen = std::collections::HashSet::new();
+            Ok(rpc_client
+                .list_transactions(1000)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .filter(|tx| known_addresses.contains(&tx.address))
+                .filter_map(|tx| tx.block_time.filter(|_| seen.insert(tx.txid)))
+                .collect())
+        }
+        // Transactions are not indexed by address on anvil
+        ChainType::Ethereum => Ok(Vec::new()),
+    }
+}
+
+/// Get detailed transaction info by signature for a specific node
+pub async fn get_transaction_detail(
+    Path((node_id, signature)): Path<(String, String)>,
+) -> (StatusCode, Json<ApiResponse<TransactionDetailInfo>>) {
+    let registry = NodeRegistry::new();
+
+    let node = match registry.get(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+
+    match node.chain {
+        ChainType::Solana => {
+            let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+            if !rpc_client.is_validator_running() {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Solana,
+                    ))),
+                );
+            }
+
+            match rpc_client.get_transaction(&signature) {
+                Ok(detail) => {
+                    let response = TransactionDetailInfo {
+                        signature: detail.signature,
+                        slot: detail.slot,
+                        block_time: detail.block_time,
+                        fee: detail.fee,
+                        err: detail.err,
+                        balance_changes: detail
+                            .balance_changes
+                            .into_iter()
+                            .map(|bc| BalanceChangeInfo {
+                                account: bc.account,
+                                before: bc.before,
+                                after: bc.after,
+                                change: bc.change,
+                            })
+                            .collect(),
+                        inputs: None,
+                        outputs: None,
+                    };
+                    (StatusCode::OK, Json(ApiResponse::success(response)))
+                }
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to get transaction: {}",
+                        e
+                    ))),
+                ),
+            }
+        }
+        ChainType::Bitcoin => {
+            let info = match BitcoinInstanceInfo::load(&node.instance_id) {
+                Ok(info) => info,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to load instance info: {}",
+                            e
+                        ))),
+                    );
+                }
+            };
+
+            let rpc_client = match info.wallet_client() {
+                Ok(client) => client,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to create RPC client: {}",
+                            e
+                        ))),
+                    );
+                }
+            };
+
+            if !rpc_client.is_node_running() {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse::message(Message::ChainNotRunning(
+                        ChainType::Bitcoin,
+                    ))),
+                );
+            }
+
+            // Decoded from the chain rather than the wallet's view, so every
+            // input and output is shown, not just those of imported accounts
+            let detail = rpc_client
+                .get_raw_transaction_verbose(&signature)
+                .and_then(|tx| Ok((rpc_client.get_balance_changes(&tx)?, tx)));
+            match detail {
+                Ok((balance_changes, tx)) => {
+                    let io = |address: &Option<String>, amount: f64| TransactionIoInfo {
+                        address: address.clone(),
+                        amount,
+                    };
+                    let response = TransactionDetailInfo {
+                        balance_changes: balance_changes
+                            .into_iter()
+                            .map(|bc| BalanceChangeInfo {
+                                account: bc.address,
+                                before: bc.before,
+                                after: bc.after,
+                                change: bc.change,
+                            })
+                            .collect(),
+                        inputs: Some(tx.inputs.iter().map(|i| io(&i.address, i.amount)).collect()),
+                        outputs: Some(
+                            tx.outputs
+                                .iter()
+                                .map(|o| io(&o.address, o.amount))
+                                .collect(),
+                        ),
+                        signature: tx.txid,
+                        slot: tx.block_height.unwrap_or(0),
+                        block_time: tx.block_time,
+                        fee: tx.fee.unwrap_or(0.0),
+                        err: None,
+                    };
+                    (StatusCode::OK, Json(ApiResponse::success(response)))
+                }
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to get transaction: {}",
+                        e
+                    ))),
+                ),
+            }
+        }
+        ChainType::Ethereum => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Transaction history is not available for Ethereum nodes",
+            )),
+        ),
+    }
+}
+
+/// Long-poll for new block headers on a Bitcoin node
+///
+/// Without `since`, returns the current tip header immediately. With `since`,
+/// returns headers above that height as soon as at least one exists, or an
+/// empty list once `timeout` seconds pass without a new block.
+pub async fn get_block_headers(
+    Path(node_id): Path<String>,
+    Query(query): Query<BlockHeadersQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<BlockHeaderInfo>>>) {
+    let registry = NodeRegistry::new();
+
+    let node = match registry.get(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+
+    if node.chain != ChainType::Bitcoin {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Block headers are only available for Bitcoin nodes",
+            )),
+        );
+    }
+
+    let info = match BitcoinInstanceInfo::load(&node.instance_id) {
+        Ok(info) => info,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to load instance info: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let rpc_client = match BitcoinRpcClient::new(info.rpc_url, info.rpc_user, info.rpc_password) {
+        Ok(client) => client,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to create RPC client: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    if !rpc_client.is_node_running() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::message(Message::ChainNotRunning(
+                ChainType::Bitcoin,
+            ))),
+        );
+    }
+
+    let to_info = |h: BitcoinBlockHeader| BlockHeaderInfo {
+        hash: h.hash,
+        height: h.height,
+        time: h.time,
+        previous_block_hash: h.previous_block_hash,
+    };
+
+    let since = match query.since {
+        Some(since) => since,
+        None => {
+            let tip = rpc_client
+                .get_block_count()
+                .and_then(|height| rpc_client.get_block_hash(height))
+                .and_then(|hash| rpc_client.get_block_header(&hash));
+            return match tip {
+                Ok(header) => (
+                    StatusCode::OK,
+                    Json(ApiResponse::success(vec![to_info(header)])),
+                ),
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to get block header: {}",
+                        e
+                    ))),
+                ),
+            };
+        }
+    };
+
+    let deadline = Instant::now() + headers_timeout(query.timeout);
+    loop {
+        match rpc_client.get_block_count() {
+            Ok(tip) if tip > since => {
+                return match rpc_client.get_headers_since(since, HEADERS_MAX_BATCH) {
+                    Ok(headers) => (
+                        StatusCode::OK,
+                        Json(ApiResponse::success(
+                            headers.into_iter().map(to_info).collect(),
+                        )),
+                    ),
+                    Err(e) => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(&format!(
+                            "Failed to get block headers: {}",
+                            e
+                        ))),
+                    ),
+                };
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to get block count: {}",
+                        e
+                    ))),
+                );
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return (StatusCode::OK, Json(ApiResponse::success(Vec::new())));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Get the BIP158 basic block filter at a height on a Bitcoin node
+pub async fn get_block_filter(
+    Path((node_id, height)): Path<(String, u64)>,
+) -> (StatusCode, Json<ApiResponse<BlockFilterInfo>>) {
+    let registry = NodeRegistry::new();
+
+    let node = match registry.get(&node_id) {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::NodeNotFound)),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+            );
+        }
+    };
+
+    if node.chain != ChainType::Bitcoin {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Block filters are only available for Bitcoin nodes",
+            )),
+        );
+    }
+
+    let info = match BitcoinInstanceInfo::load(&node.instance_id) {
+        Ok(info) => info,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to load instance info: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let rpc_client = match BitcoinRpcClient::new(info.rpc_url, info.rpc_user, info.rpc_password) {
+        Ok(client) => client,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to create RPC client: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    if !rpc_client.is_node_running() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::message(Message::ChainNotRunning(
+                ChainType::Bitcoin,
+            ))),
+        );
+    }
+
+    match rpc_client.get_block_filter(height) {
+        Ok(filter) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(BlockFilterInfo {
+                height: filter.height,
+                block_hash: filter.block_hash,
+                filter: filter.filter,
+                header: filter.header,
+            })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to get block filter (is the node running with --block-filters?): {}",
+                e
+            ))),
+        ),
+    }
+}
+
+/// RPC call metrics for one node, as made by this server
+pub async fn get_rpc_stats(
+    Path(node_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<EndpointStats>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let stats = rpc_metrics::global()
+        .stats(&node.rpc_url)
+        .unwrap_or_else(|| EndpointStats {
+            endpoint: node.rpc_url.clone(),
+            methods: Vec::new(),
+            slow_calls: Vec::new(),
+        });
+    (StatusCode::OK, Json(ApiResponse::success(stats)))
+}
+
+/// RPC call metrics for every node, in the Prometheus text format
+pub async fn prometheus_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        rpc_metrics::global().render_prometheus(),
+    )
+}
+
+/// List recent blocks on a Bitcoin or Solana node, newest first
+pub async fn get_blocks(
+    Path(node_id): Path<String>,
+    Query(query): Query<BlocksQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<BlockInfo>>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(BLOCKS_DEFAULT_LIMIT)
+        .min(BLOCKS_MAX_LIMIT);
+    let blocks = match node.chain {
+        ChainType::Bitcoin => match bitcoin_wallet_client(&node_id, "Blocks") {
+            Ok(client) => client.get_recent_blocks(query.before, limit).map(|blocks| {
+                blocks
+                    .into_iter()
+                    .map(|block| BlockInfo::from_bitcoin(block, false))
+                    .collect()
+            }),
+            Err(response) => return response,
+        },
+        ChainType::Solana => match solana_client(&node_id, "Blocks") {
+            Ok((client, _)) => client.get_recent_blocks(query.before, limit).map(|blocks| {
+                blocks
+                    .into_iter()
+                    .map(|block| BlockInfo::from_solana(block, false))
+                    .collect()
+            }),
+            Err(response) => return response,
+        },
+        ChainType::Ethereum => return blocks_unsupported(),
+    };
+
+    match blocks {
+        Ok(blocks) => (StatusCode::OK, Json(ApiResponse::success(blocks))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::ListBlocksFailed(
+                e.to_string(),
+            ))),
+        ),
+    }
+}
+
+/// Get a block with its transaction IDs by height or hash (Bitcoin), or by
+/// slot (Solana)
+pub async fn get_block(
+    Path((node_id, block)): Path<(String, String)>,
+) -> (StatusCode, Json<ApiResponse<BlockInfo>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match node.chain {
+        ChainType::Bitcoin => bitcoin_block(&node_id, &block),
+        ChainType::Solana => solana_block(&node_id, &block),
+        ChainType::Ethereum => blocks_unsupported(),
+    }
+}
+
+fn block_not_found(block: &str) -> (StatusCode, Json<ApiResponse<BlockInfo>>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ApiResponse::message(Message::BlockNotFound(
+            block.to_string(),
+        ))),
+    )
+}
+
+/// Bitcoin block by height or hash
+fn bitcoin_block(node_id: &str, block: &str) -> (StatusCode, Json<ApiResponse<BlockInfo>>) {
+    let rpc_client = match bitcoin_wallet_client(node_id, "Blocks") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    // Hashes are 64 hex characters, which no height reaches
+    let found = if block.len() == 64 {
+        rpc_client.get_block(block)
+    } else {
+        let Ok(height) = block.parse::<u64>() else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::message(Message::InvalidBlockId)),
+            );
+        };
+        match rpc_client.get_block_count() {
+            Ok(tip) if height > tip => return block_not_found(block),
+            Ok(_) => rpc_client.get_block_at(height),
+            Err(e) => Err(e),
+        }
+    };
+
+    match found {
+        Ok(found) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(BlockInfo::from_bitcoin(found, true))),
+        ),
+        // bitcoind's error for an unknown hash
+        Err(e) if e.to_string().contains("Block not found") => block_not_found(block),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::GetBlockFailed(e.to_string()))),
+        ),
+    }
+}
+
+/// Solana block by slot
+fn solana_block(node_id: &str, block: &str) -> (StatusCode, Json<ApiResponse<BlockInfo>>) {
+    let rpc_client = match solana_client(node_id, "Blocks") {
+        Ok((client, _)) => client,
+        Err(response) => return response,
+    };
+
+    let Ok(slot) = block.parse::<u64>() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::InvalidSlot)),
+        );
+    };
+    let found = match rpc_client.get_slot() {
+        Ok(tip) if slot > tip => return block_not_found(block),
+        Ok(_) => rpc_client.get_block(slot),
+        Err(e) => Err(e),
+    };
+
+    match found {
+        Ok(found) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(BlockInfo::from_solana(found, true))),
+        ),
+        // The validator's errors for a skipped slot, or one it no longer has
+        Err(e) if e.to_string().contains("skipped") || e.to_string().contains("not available") => {
+            block_not_found(block)
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::GetBlockFailed(e.to_string()))),
+        ),
+    }
+}
+
+/// Error response for block endpoints on chains without them
+fn blocks_unsupported<T: Serialize>() -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::message(Message::BlocksUnsupported)),
+    )
+}
+
+/// Registered node by ID, or the error response to return
+fn find_node<T: Serialize>(node_id: &str) -> Result<NodeInfo, (StatusCode, Json<ApiResponse<T>>)> {
+    match NodeRegistry::new().get(node_id) {
+        Ok(Some(node)) => Ok(node),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::message(Message::NodeNotFound)),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::message(Message::GetNodeFailed(e.to_string()))),
+        )),
+    }
+}
+
+/// Wallet RPC client for a running Bitcoin node, or the error response to return
+fn bitcoin_wallet_client<T: Serialize>(
+    node_id: &str,
+    feature: &str,
+) -> Result<BitcoinRpcClient, (StatusCode, Json<ApiResponse<T>>)> {
+    let node = find_node(node_id)?;
+
+    if node.chain != ChainType::Bitcoin {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "{} are only available for Bitcoin nodes",
+                feature
+            ))),
+        ));
+    }
+
+    let info = BitcoinInstanceInfo::load(&node.instance_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to load instance info: {}",
+                e
+            ))),
+        )
+    })?;
+
+    let rpc_client = info.wallet_client().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to create RPC client: {}",
+                e
+            ))),
+        )
+    })?;
+
+    if !rpc_client.is_node_running() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::message(Message::ChainNotRunning(
+                ChainType::Bitcoin,
+            ))),
+        ));
+    }
+
+    Ok(rpc_client)
+}
+
+/// List an account's unspent outputs, locked ones included (Bitcoin)
+pub async fn get_account_utxos(
+    Path((node_id, address)): Path<(String, String)>,
+) -> (StatusCode, Json<ApiResponse<Vec<BitcoinUtxo>>>) {
+    let rpc_client = match bitcoin_wallet_client(&node_id, "UTXOs") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    match rpc_client.list_unspent(&address) {
+        Ok(utxos) => (StatusCode::OK, Json(ApiResponse::success(utxos))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Failed to list UTXOs: {}", e))),
+        ),
+    }
+}
+
+/// Report address reuse, dust outputs, and unconfirmed chains among a
+/// node's accounts (Bitcoin)
+pub async fn get_wallet_analysis(
+    Path(node_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<WalletAnalysis>>) {
+    let rpc_client = match bitcoin_wallet_client(&node_id, "Wallet analyses") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let accounts_file = Config::data_dir()
+        .join("bitcoin")
+        .join("instances")
+        .join(&node.instance_id)
+        .join("accounts.json");
+    let accounts = match BitcoinAccountsStorage::with_path(accounts_file).load() {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to load accounts: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    match analyze_accounts(&rpc_client, &accounts) {
+        Ok(analysis) => (StatusCode::OK, Json(ApiResponse::success(analysis))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to analyze accounts: {}",
+                e
+            ))),
+        ),
+    }
+}
+
+/// Lock an account's outputs so wallet coin selection skips them (Bitcoin)
+pub async fn lock_account_utxos(
+    Path((node_id, address)): Path<(String, String)>,
+    Json(req): Json<UtxoLockRequest>,
+) -> (StatusCode, Json<ApiResponse<Vec<BitcoinUtxo>>>) {
+    set_account_utxo_locks(&node_id, &address, req.outpoints, true)
+}
+
+/// Unlock an account's outputs (Bitcoin)
+pub async fn unlock_account_utxos(
+    Path((node_id, address)): Path<(String, String)>,
+    Json(req): Json<UtxoLockRequest>,
+) -> (StatusCode, Json<ApiResponse<Vec<BitcoinUtxo>>>) {
+    set_account_utxo_locks(&node_id, &address, req.outpoints, false)
+}
+
+/// Lock or unlock outputs after checking they belong to `address`, then
+/// return the account's outputs with their new lock state
+fn set_account_utxo_locks(
+    node_id: &str,
+    address: &str,
+    mut outpoints: Vec<BitcoinOutpoint>,
+    lock: bool,
+) -> (StatusCode, Json<ApiResponse<Vec<BitcoinUtxo>>>) {
+    let rpc_client = match bitcoin_wallet_client(node_id, "UTXOs") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let utxos = match rpc_client.list_unspent(address) {
+        Ok(utxos) => utxos,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!("Failed to list UTXOs: {}", e))),
+            );
+        }
+    };
+
+    if outpoints.is_empty() {
+        if lock {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error("No outpoints given to lock")),
+            );
+        }
+        outpoints = utxos
+            .iter()
+            .filter(|u| u.locked)
+            .map(|u| BitcoinOutpoint {
+                txid: u.txid.clone(),
+                vout: u.vout,
+            })
+            .collect();
+    }
+
+    if let Some(unknown) = outpoints
+        .iter()
+        .find(|o| !utxos.iter().any(|u| u.txid == o.txid && u.vout == o.vout))
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "{}:{} is not an unspent output of {}",
+                unknown.txid, unknown.vout, address
+            ))),
+        );
+    }
+
+    let result = if lock {
+        rpc_client.lock_unspent(&outpoints)
+    } else if outpoints.is_empty() {
+        Ok(())
+    } else {
+        rpc_client.unlock_unspent(&outpoints)
+    };
+    if let Err(e) = result {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to update UTXO locks: {}",
+                e
+            ))),
+        );
+    }
+
+    match rpc_client.list_unspent(address) {
+        Ok(utxos) => (StatusCode::OK, Json(ApiResponse::success(utxos))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Failed to list UTXOs: {}", e))),
+        ),
+    }
+}
+
+/// RPC client for a running Solana node and its instance ID, or the error
+/// response to return
+fn solana_client<T: Serialize>(
+    node_id: &str,
+    feature: &str,
+) -> Result<(SolanaRpcClient, String), (StatusCode, Json<ApiResponse<T>>)> {
+    let node = find_node(node_id)?;
+
+    if node.chain != ChainType::Solana {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "{} are only available for Solana nodes",
+                feature
+            ))),
+        ));
+    }
+
+    let client = SolanaRpcClient::new(node.rpc_url.clone());
+    if !client.is_validator_running() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::message(Message::ChainNotRunning(
+                ChainType::Solana,
+            ))),
+        ));
+    }
+
+    Ok((client, node.instance_id))
+}
+
+/// Accounts of a Solana instance
+fn solana_accounts<T: Serialize>(
+    instance_id: &str,
+) -> Result<Vec<SolanaAccount>, (StatusCode, Json<ApiResponse<T>>)> {
+    let accounts_file = Config::data_dir()
+        .join("solana")
+        .join("instances")
+        .join(instance_id)
+        .join("accounts.json");
+    SolanaAccountsStorage::with_path(accounts_file)
+        .load()
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to load accounts: {}",
+                    e
+                ))),
+            )
+        })
+}
+
+/// Signer for one of a Solana instance's accounts
+///
+/// External signer accounts sign through the instance's emulated signer.
+fn solana_signer<T: Serialize>(
+    instance_id: &str,
+    address: &str,
+) -> Result<Box<dyn Signer>, (StatusCode, Json<ApiResponse<T>>)> {
+    let account = solana_accounts(instance_id)?
+        .into_iter()
+        .find(|a| a.public_key == address)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(&format!(
+                    "{} is not an account of this node",
+                    address
+                ))),
+            )
+        })?;
+
+    let signer: chain_forge_common::Result<Box<dyn Signer>> = if account.external_signer {
+        let keystore = SignerKeystore::with_path(
+            SolanaConfig::with_instance(instance_id).signer_keystore_file(),
+        );
+        EmulatedSigner::load(&keystore, address).map(|s| Box::new(s) as Box<dyn Signer>)
+    } else {
+        LocalSigner::from_account(&account).map(|s| Box::new(s) as Box<dyn Signer>)
+    };
+    signer.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Failed to load signer: {}", e))),
+        )
+    })
+}
+
+/// Create an SPL token mint, optionally with token accounts for every
+/// account of the instance (Solana)
+pub async fn create_token(
+    Path(node_id): Path<String>,
+    Json(req): Json<CreateTokenRequest>,
+) -> (StatusCode, Json<ApiResponse<TokenMintInfo>>) {
+    let (client, instance_id) = match solana_client(&node_id, "Tokens") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let signer = match solana_signer(&instance_id, &req.authority) {
+        Ok(signer) => signer,
+        Err(response) => return response,
+    };
+
+    let mint = match client.create_mint(signer.as_ref(), req.decimals) {
+        Ok(mint) => mint,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!("Failed to create mint: {}", e))),
+            );
+        }
+    };
+
+    let mut token_accounts = Vec::new();
+    if req.create_accounts {
+        let owners: Vec<String> = match solana_accounts(&instance_id) {
+            Ok(accounts) => accounts.into_iter().map(|a| a.public_key).collect(),
+            Err(response) => return response,
+        };
+        token_accounts = match client.create_token_accounts(&mint, signer.as_ref(), &owners) {
+            Ok(token_accounts) => token_accounts,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Mint {} created, but creating token accounts failed: {}",
+                        mint, e
+                    ))),
+                );
+            }
+        };
+    }
+
+    let response = TokenMintInfo {
+        mint,
+        authority: req.authority,
+        decimals: req.decimals,
+        token_accounts,
+    };
+    (StatusCode::CREATED, Json(ApiResponse::success(response)))
+}
+
+/// Mint SPL tokens to addresses, or to every account of the instance (Solana)
+pub async fn mint_tokens(
+    Path((node_id, mint)): Path<(String, String)>,
+    Json(req): Json<MintTokensRequest>,
+) -> (StatusCode, Json<ApiResponse<Vec<TokenTransferInfo>>>) {
+    let (client, instance_id) = match solana_client(&node_id, "Tokens") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let signer = match solana_signer(&instance_id, &req.authority) {
+        Ok(signer) => signer,
+        Err(response) => return response,
+    };
+    let recipients = if req.to.is_empty() {
+        match solana_accounts(&instance_id) {
+            Ok(accounts) => accounts.into_iter().map(|a| a.public_key).collect(),
+            Err(response) => return response,
+        }
+    } else {
+        req.to
+    };
+
+    let mut minted = Vec::new();
+    for to in recipients {
+        match client.mint_tokens(&mint, signer.as_ref(), &to, req.amount) {
+            Ok(signature) => minted.push(TokenTransferInfo {
+                signature,
+                to,
+                amount: req.amount,
+            }),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(&format!(
+                        "Failed to mint to {} ({} recipient(s) already minted to): {}",
+                        to,
+                        minted.len(),
+                        e
+                    ))),
+                );
+            }
+        }
+    }
+    (StatusCode::OK, Json(ApiResponse::success(minted)))
+}
+
+/// Transfer SPL tokens from one of the instance's accounts (Solana)
+pub async fn transfer_tokens(
+    Path((node_id, mint)): Path<(String, String)>,
+    Json(req): Json<TransferTokensRequest>,
+) -> (StatusCode, Json<ApiResponse<TokenTransferInfo>>) {
+    let (client, instance_id) = match solana_client(&node_id, "Tokens") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let signer = match solana_signer(&instance_id, &req.from) {
+        Ok(signer) => signer,
+        Err(response) => return response,
+    };
+
+    match client.transfer_tokens(&mint, signer.as_ref(), &req.to, req.amount) {
+        Ok(signature) => {
+            let response = TokenTransferInfo {
+                signature,
+                to: req.to,
+                amount: req.amount,
+            };
+            (StatusCode::OK, Json(ApiResponse::success(response)))
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Token transfer failed: {}", e))),
+        ),
+    }
+}
+
+/// Each account's balance of a token (Solana)
+pub async fn get_token_balances(
+    Path((node_id, mint)): Path<(String, String)>,
+) -> (StatusCode, Json<ApiResponse<Vec<TokenBalance>>>) {
+    let (client, instance_id) = match solana_client(&node_id, "Tokens") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+    let accounts = match solana_accounts(&instance_id) {
+        Ok(accounts) => accounts,
+        Err(response) => return response,
+    };
+
+    let balances = accounts
+        .into_iter()
+        .map(|account| {
+            Ok(TokenBalance {
+                token_account: token_account_address(&account.public_key, &mint)?,
+                balance: client.get_token_balance(&mint, &account.public_key)?,
+                address: account.public_key,
+            })
+        })
+        .collect::<chain_forge_common::Result<Vec<_>>>();
+
+    match balances {
+        Ok(balances) => (StatusCode::OK, Json(ApiResponse::success(balances))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to get token balances: {}",
+                e
+            ))),
+        ),
+    }
+}
+
+/// Find the transaction paying a Solana Pay request by its reference key
+///
+/// With `recipient` and `amount`, the transaction is also checked against
+/// the request.
+pub async fn find_payment(
+    Path((node_id, reference)): Path<(String, String)>,
+    Query(query): Query<PaymentQuery>,
+) -> (StatusCode, Json<ApiResponse<PaymentInfo>>) {
+    let (client, _) = match solana_client(&node_id, "Solana Pay lookups") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let found = match client.find_reference(&reference) {
+        Ok(found) => found,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to find payment: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let mut payment = PaymentInfo {
+        reference: reference.clone(),
+        signature: None,
+        slot: None,
+        block_time: None,
+        valid: None,
+        error: None,
+    };
+    if let Some(found) = found {
+        if let (Some(recipient), Some(amount)) = (query.recipient, query.amount) {
+            let request = pay::TransferRequest {
+                recipient,
+                amount,
+                references: vec![reference],
+                label: None,
+                message: None,
+                memo: None,
+            };
+            let validation = client.validate_transfer(&found.signature, &request);
+            payment.valid = Some(validation.is_ok());
+            payment.error = validation.err().map(|e| e.to_string());
+        }
+        payment.signature = Some(found.signature);
+        payment.slot = Some(found.slot);
+        payment.block_time = found.block_time;
+    }
+
+    (StatusCode::OK, Json(ApiResponse::success(payment)))
+}
+
+/// List background jobs, newest first
+pub async fn list_jobs(
+    State(jobs): State<Arc<JobStore>>,
+) -> (StatusCode, Json<ApiResponse<Vec<Job>>>) {
+    (StatusCode::OK, Json(ApiResponse::success(jobs.list())))
+}
+
+/// Cancel a running background job
+pub async fn cancel_job(
+    State(jobs): State<Arc<JobStore>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    match jobs.get(&id) {
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::message(Message::JobNotFound)),
+        ),
+        Some(job) if job.status != JobStatus::Running => (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::message(Message::JobFinished)),
+        ),
+        Some(_) => match jobs.cancel(&id) {
+            Some(job) => (StatusCode::OK, Json(ApiResponse::success(job))),
+            None => (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::message(Message::JobNotFound)),
+            ),
+        },
+    }
+}
+
+/// Take a ledger snapshot as a background job (Solana)
+pub async fn create_snapshot(
+    State(jobs): State<Arc<JobStore>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<SnapshotRequest>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    if node.chain != ChainType::Solana {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Snapshots are only available for Solana nodes",
+            )),
+        );
+    }
+    if let Some(name) = &req.name {
+        if let Err(e) = validate_name(name) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(&e.to_string())),
+            );
+        }
+    }
+
+    let pid = match SolanaInstanceInfo::load(&node.instance_id).map(|info| info.pid) {
+        Ok(Some(pid)) => pid,
+        Ok(None) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::error(
+                    "Validator process ID is unknown. Restart the instance to take snapshots",
+                )),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to load instance info: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let job = jobs.spawn("snapshot", Some(node_id), move |progress| {
+        take_snapshot(progress, &node, pid, req)
+    });
+    (StatusCode::ACCEPTED, Json(ApiResponse::success(job)))
+}
+
+/// Wait for the requested slot, then pause the validator, archive its
+/// ledger, and resume it
+fn take_snapshot(
+    progress: &JobProgress,
+    node: &NodeInfo,
+    pid: u32,
+    req: SnapshotRequest,
+) -> Result<serde_json::Value, String> {
+    let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+    let mut slot = rpc_client.get_slot().map_err(|e| e.to_string())?;
+    if let Some(target) = req.at_slot {
+        progress.phase("waiting_for_slot", None);
+        let deadline = Instant::now() + Duration::from_secs(req.timeout);
+        while slot < target {
+            progress.check_cancelled()?;
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Timed out waiting for slot {} (reached {})",
+                    target, slot
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(400));
+            slot = rpc_client.get_slot().map_err(|e| e.to_string())?;
+        }
+    }
+    progress.check_cancelled()?;
+
+    let name = req
+        .name
+        .unwrap_or_else(|| format!("{}-slot-{}", node.instance_id, slot));
+    let instance_dir = Config::data_dir()
+        .join("solana")
+        .join("instances")
+        .join(&node.instance_id);
+
+    // Pause the validator so the ledger is not written while archiving
+    progress.phase("archiving", None);
+    pause_process(pid).map_err(|e| e.to_string())?;
+    let result = SolanaSnapshot::create(&name, &node.instance_id, slot, &instance_dir);
+    resume_process(pid).map_err(|e| e.to_string())?;
+
+    let snapshot = result.map_err(|e| e.to_string())?;
+    serde_json::to_value(&snapshot).map_err(|e| e.to_string())
+}
+
+/// Warp a validator to a later slot as a background job (Solana)
+pub async fn warp_node(
+    State(jobs): State<Arc<JobStore>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<WarpRequest>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    if node.chain != ChainType::Solana {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::WarpUnsupported)),
+        );
+    }
+    let target = match (req.slot, req.epoch) {
+        (Some(slot), None) => WarpTarget::Slot(slot),
+        (None, Some(epoch)) => WarpTarget::Epoch(epoch),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::message(Message::InvalidWarpTarget)),
+            );
+        }
+    };
+
+    let job = jobs.spawn("warp", Some(node_id), move |progress| {
+        progress.phase("restarting", None);
+        let reached = warp_instance(&node.instance_id, target).map_err(|e| e.to_string())?;
+        Ok(serde_json::json!({
+            "slot": reached.absolute_slot,
+            "epoch": reached.epoch,
+            "slot_index": reached.slot_index,
+            "slots_in_epoch": reached.slots_in_epoch,
+        }))
+    });
+    (StatusCode::ACCEPTED, Json(ApiResponse::success(job)))
+}
+
+/// Fund several addresses as a background job
+pub async fn bulk_fund(
+    State(jobs): State<Arc<JobStore>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<BulkFundRequest>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    if req.recipients.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::NoRecipients)),
+        );
+    }
+    if req.recipients.iter().any(|r| r.amount <= 0.0) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::AmountsNotPositive)),
+        );
+    }
+
+    let job = jobs.spawn("bulk_fund", Some(node_id), move |progress| {
+        let result = fund_recipients(progress, &node, req.recipients, req.confirm)?;
+        serde_json::to_value(&result).map_err(|e| e.to_string())
+    });
+    (StatusCode::ACCEPTED, Json(ApiResponse::success(job)))
+}
+
+/// Fund each recipient in turn, recording failures instead of stopping
+fn fund_recipients(
+    progress: &JobProgress,
+    node: &NodeInfo,
+    recipients: Vec<FundAccountRequest>,
+    confirm: Option<Confirmations>,
+) -> Result<BulkFundResult, String> {
+    let mut result = BulkFundResult {
+        sent: Vec::new(),
+        failed: Vec::new(),
+    };
+    let total = recipients.len() as f64;
+
+    // Bitcoin sends from the treasury or wallet; Solana airdrops; anvil
+    // credits balances directly
+    let bitcoin = match node.chain {
+        ChainType::Bitcoin => {
+            let info = BitcoinInstanceInfo::load(&node.instance_id).map_err(|e| e.to_string())?;
+            let client = info.wallet_client().map_err(|e| e.to_string())?;
+            let confirmations = confirm.unwrap_or(info.confirmations);
+            Some((client, info.treasury, confirmations))
+        }
+        ChainType::Solana | ChainType::Ethereum => None,
+    };
+    let solana = SolanaRpcClient::new(node.rpc_url.clone());
+    let ethereum = EthereumRpcClient::new(node.rpc_url.clone());
+
+    for (i, recipient) in recipients.into_iter().enumerate() {
+        progress.check_cancelled()?;
+        progress.phase("funding", Some(i as f64 / total));
+        let sent = match &bitcoin {
+            Some((client, treasury, _)) => {
+                client.fund_address(treasury.as_deref(), &recipient.address, recipient.amount)
+            }
+            None if node.chain == ChainType::Ethereum => ethereum
+                .add_balance(&recipient.address, recipient.amount)
+                .map(|_| String::new()),
+            None => solana.request_airdrop(&recipient.address, recipient.amount),
+        };
+        match sent {
+            Ok(txid_or_signature) => {
+                chain_forge_events::publish(
+                    node.chain,
+                    &node.instance_id,
+                    ChainEvent::FundsDispensed {
+                        address: Some(recipient.address.clone()),
+                        amount: recipient.amount,
+                    },
+                );
+                result.sent.push(FundResponse {
+                    success: true,
+                    txid_or_signature,
+                    address: recipient.address,
+                    amount: recipient.amount,
+                });
+            }
+            Err(e) => result.failed.push(BulkFundFailure {
+                address: recipient.address,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    // Confirm the Bitcoin payments together
+    if let Some((client, _, confirmations)) = &bitcoin {
+        if !result.sent.is_empty() {
+            progress.phase("confirming", None);
+            let _ = confirm::confirm(client, &node.instance_id, *confirmations);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Rescan a Bitcoin wallet for transactions as a background job
+pub async fn rescan_wallet(
+    State(jobs): State<Arc<JobStore>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<RescanRequest>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    let rpc_client = match bitcoin_wallet_client(&node_id, "Rescans") {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let job = jobs.spawn("rescan", Some(node_id), move |progress| {
+        rescan_in_chunks(progress, &rpc_client, req.start_height)
+    });
+    (StatusCode::ACCEPTED, Json(ApiResponse::success(job)))
+}
+
+/// Rescan from `start` to the tip, [`RESCAN_CHUNK`] blocks at a time
+fn rescan_in_chunks(
+    progress: &JobProgress,
+    rpc_client: &BitcoinRpcClient,
+    start: u64,
+) -> Result<serde_json::Value, String> {
+    let tip = rpc_client.get_block_count().map_err(|e| e.to_string())?;
+    if start > tip {
+        return Err(format!(
+            "start_height {} is above the chain tip ({})",
+            start, tip
+        ));
+    }
+
+    let total = (tip - start + 1) as f64;
+    let mut height = start;
+    while height <= tip {
+        progress.check_cancelled()?;
+        progress.phase("scanning", Some((height - start) as f64 / total));
+        let stop = (height + RESCAN_CHUNK - 1).min(tip);
+        rpc_client
+            .rescan_blockchain(height, stop)
+            .map_err(|e| e.to_string())?;
+        height = stop + 1;
+    }
+
+    Ok(serde_json::json!({ "start_height": start, "stop_height": tip }))
+}
+
+/// Get the status of a background job
+pub async fn get_job(
+    State(jobs): State<Arc<JobStore>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Job>>) {
+    match jobs.get(&id) {
+        Some(job) => (StatusCode::OK, Json(ApiResponse::success(job))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::message(Message::JobNotFound)),
+        ),
+    }
+}
+
+/// Read every profile from the config file at `path`
+///
+/// Each chain always has a `default` profile, from the file or built in.
+fn load_profiles(path: &std::path::Path) -> chain_forge_common::Result<ProfilesResponse> {
+    let exists = path.exists();
+    let config = if exists {
+        Config::load_from_file(path)?
+    } else {
+        Config::default()
+    };
+
+    let mut solana = BTreeMap::from([("default".to_string(), SolanaProfile::default())]);
+    if let Some(config) = config.solana {
+        solana.insert("default".to_string(), config.default);
+        solana.extend(config.profiles);
+    }
+    let mut bitcoin = BTreeMap::from([("default".to_string(), BitcoinProfile::default())]);
+    if let Some(config) = config.bitcoin {
+        bitcoin.insert("default".to_string(), config.default);
+        bitcoin.extend(config.profiles);
+    }
+    let mut ethereum = BTreeMap::from([("default".to_string(), EthereumProfile::default())]);
+    if let Some(config) = config.ethereum {
+        ethereum.insert("default".to_string(), config.default);
+        ethereum.extend(config.profiles);
+    }
+
+    Ok(ProfilesResponse {
+        path: path.display().to_string(),
+        exists,
+        solana,
+        bitcoin,
+        ethereum,
+    })
+}
+
+/// List Solana, Bitcoin, and Ethereum profiles from `chain-forge.toml`
+pub async fn get_profiles() -> (StatusCode, Json<ApiResponse<ProfilesResponse>>) {
+    match load_profiles(&Config::file_path()) {
+        Ok(profiles) => (StatusCode::OK, Json(ApiResponse::success(profiles))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Failed to read config: {}", e))),
+        ),
+    }
+}
+
+/// Create or replace a named profile in `chain-forge.toml`
+///
+/// The profile is validated before anything is written. Comments and other
+/// tables in the file are kept.
+pub async fn update_profile(
+    Json(req): Json<UpdateProfileRequest>,
+) -> (StatusCode, Json<ApiResponse<ProfilesResponse>>) {
+    if let Err(e) = validate_name(&req.name) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&e.to_string())),
+        );
+    }
+
+    let path = Config::file_path();
+    let written = match req.chain.to_lowercase().as_str() {
+        "solana" => write_profile_value::<SolanaProfile>(
+            &path,
+            ChainType::Solana,
+            &req.name,
+            req.profile,
+            SolanaProfile::validate,
+        ),
+        "bitcoin" => write_profile_value::<BitcoinProfile>(
+            &path,
+            ChainType::Bitcoin,
+            &req.name,
+            req.profile,
+            BitcoinProfile::validate,
+        ),
+        "ethereum" => write_profile_value::<EthereumProfile>(
+            &path,
+            ChainType::Ethereum,
+            &req.name,
+            req.profile,
+            EthereumProfile::validate,
+        ),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::message(Message::InvalidChain)),
+            );
+        }
+    };
+
+    match written {
+        Ok(()) => get_profiles().await,
+        Err((status, message)) => (status, Json(ApiResponse::error(&message))),
+    }
+}
+
+/// Parse and validate a profile from JSON, then write it to `path`
+fn write_profile_value<P: DeserializeOwned + Serialize>(
+    path: &std::path::Path,
+    chain: ChainType,
+    name: &str,
+    value: serde_json::Value,
+    validate: fn(&P) -> chain_forge_common::Result<()>,
+) -> Result<(), (StatusCode, String)> {
+    let profile: P = serde_json::from_value(value)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid profile: {}", e)))?;
+    validate(&profile).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    chain_forge_config::write_profile(path, chain, name, &profile).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to write config: {}", e),
+        )
+    })
+}
+
+/// Long-poll for node events
+///
+/// Without `since`, returns the current cursor immediately. With `since`,
+/// returns buffered events after that cursor as soon as at least one exists,
+/// or an empty list once `timeout` seconds pass. `truncated` is set when
+/// events after `since` have already been dropped from the buffer.
+pub async fn get_events(
+    State(events): State<Arc<EventBus>>,
+    Query(query): Query<EventsQuery>,
+) -> (StatusCode, Json<ApiResponse<EventPage>>) {
+    let Some(since) = query.since else {
+        let page = EventPage {
+            events: Vec::new(),
+            cursor: events.latest_cursor(),
+            truncated: false,
+        };
+        return (StatusCode::OK, Json(ApiResponse::success(page)));
+    };
+
+    // Same bounds as the block header long-poll
+    let page = events
+        .wait_since(
+            since,
+            query.node_id.as_deref(),
+            headers_timeout(query.timeout),
+        )
+        .await;
+    (StatusCode::OK, Json(ApiResponse::success(page)))
+}
+
+/// Stream a node's events as Server-Sent Events
+///
+/// Each SSE message carries the event type as its `event` name, the cursor
+/// as its `id`, and the event JSON as its data. Browsers that reconnect with
+/// `Last-Event-ID` get buffered events after that cursor replayed first.
+pub async fn stream_node_events(
+    State(events): State<Arc<EventBus>>,
+    Path(node_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match NodeRegistry::new().get(&node_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::message(Message::NodeNotFound)),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::message(Message::GetNodeFailed(
+                    e.to_string(),
+                ))),
+            )
+                .into_response();
+        }
+    }
+
+    // Subscribe before replaying so nothing published in between is lost
+    let receiver = events.subscribe();
+    let (replay, after) = match headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        Some(last_id) => {
+            let page = events.since(last_id, Some(&node_id));
+            (page.events, page.cursor)
+        }
+        None => (Vec::new(), events.latest_cursor()),
+    };
+
+    let live = BroadcastStream::new(receiver).filter_map(move |event| match event {
+        Ok(event) if event.node_id == node_id && event.cursor > after => Some(event),
+        // Lagged receivers skip what they missed; clients can catch up
+        // with the long-poll endpoint
+        _ => None,
+    });
+    let stream = tokio_stream::iter(replay)
+        .chain(live)
+        .map(|event| Ok::<_, Infallible>(sse_event(&event)));
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Encode a bus event as an SSE message
+fn sse_event(event: &Event) -> sse::Event {
+    sse::Event::default()
+        .id(event.cursor.to_string())
+        .event(event.event.name())
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
+
+/// Download a node's log file, gzip-compressed
+pub async fn download_node_logs(Path(node_id): Path<String>) -> Response {
+    let node = match find_node::<()>(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response.into_response(),
+    };
+    let path = node_logs::log_file(&node);
+
+    let compressed = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || node_logs::gzip(&path)).await
+    };
+    match compressed {
+        Ok(Ok(bytes)) => {
+            let disposition = format!(
+                "attachment; filename=\"{}-{}.log.gz\"",
+                node.chain, node.instance_id
+            );
+            (
+                [
+                    (header::CONTENT_TYPE, "application/gzip".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Ok(Err(e)) => log_read_error(&path, e).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(&format!(
+                "Failed to read log: {}",
+                e
+            ))),
+        )
+            .into_response(),
+    }
+}
+
+/// The last lines of a node's log file
+///
+/// With `follow=true` the lines are sent as SSE `line` events, followed by
+/// new lines as the node writes them.
+pub async fn tail_node_logs(
+    Path(node_id): Path<String>,
+    Query(query): Query<LogTailQuery>,
+) -> Response {
+    let node = match find_node::<()>(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response.into_response(),
+    };
+    let path = node_logs::log_file(&node);
+    let count = query
+        .lines
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .min(MAX_TAIL_LINES);
+
+    let tail = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || node_logs::tail(&path, count)).await
+    };
+    let (lines, offset) = match tail {
+        Ok(Ok(tail)) => tail,
+        Ok(Err(e)) => return log_read_error(&path, e).into_response(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(&format!(
+                    "Failed to read log: {}",
+                    e
+                ))),
+            )
+                .into_response()
+        }
+    };
+
+    if !query.follow {
+        let response = LogTailResponse {
+            node_id,
+            file: path.display().to_string(),
+            lines,
+        };
+        return (StatusCode::OK, Json(ApiResponse::success(response))).into_response();
+    }
+
+    let stream = tokio_stream::iter(lines)
+        .chain(node_logs::follow(path, offset))
+        .map(|line| Ok::<_, Infallible>(sse::Event::default().event("line").data(line)));
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// The last entries of a node's instance log at a level or more severe
+pub async fn get_instance_log(
+    Path(node_id): Path<String>,
+    Query(query): Query<InstanceLogQuery>,
+) -> (StatusCode, Json<ApiResponse<InstanceLogResponse>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    let level = match query
+        .level
+        .as_deref()
+        .unwrap_or("info")
+        .parse::<LevelFilter>()
+    {
+        Ok(level) => level,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "Invalid level: expected error, warn, info, debug, or trace",
+                )),
+            )
+        }
+    };
+    let count = query
+        .lines
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .min(MAX_TAIL_LINES);
+
+    let instance_dir = node_logs::instance_dir(&node);
+    let entries = {
+        let instance_dir = instance_dir.clone();
+        tokio::task::spawn_blocking(move || logging::read_instance_log(&instance_dir, level, count))
+            .await
+    };
+    match entries {
+        Ok(Ok(entries)) => {
+            let response = InstanceLogResponse {
+                node_id,
+                file: instance_dir
+                    .join(logging::INSTANCE_LOG_FILE)
+                    .display()
+                    .to_string(),
+                entries: entries.into_iter().map(InstanceLogEntry::from).collect(),
+            };
+            (StatusCode::OK, Json(ApiResponse::success(response)))
+        }
+        Ok(Err(e)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to read instance log: {}",
+                e
+            ))),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!("Failed to read log: {}", e))),
+        ),
+    }
+}
+
+fn log_read_error(
+    path: &std::path::Path,
+    e: std::io::Error,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(&format!(
+                "No log file at {}; the node may not have been started on this machine",
+                path.display()
+            ))),
+        )
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        )
+    }
+}
+
+/// Decode a raw transaction, script, or message pasted in by a user
+///
+/// Bitcoin payloads go through the node's `decoderawtransaction` or
+/// `decodescript` and the node's JSON is returned as is. Solana payloads
+/// are decoded locally.
+pub async fn decode_payload(
+    Path(node_id): Path<String>,
+    Json(req): Json<DecodeRequest>,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    let node = match find_node(&node_id) {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    let decoded = match (node.chain, req.kind) {
+        (ChainType::Bitcoin, DecodeKind::Transaction | DecodeKind::Script) => {
+            let rpc_client = match bitcoin_wallet_client(&node_id, "Bitcoin decoders") {
+                Ok(client) => client,
+                Err(response) => return response,
+            };
+            match req.kind {
+                DecodeKind::Script => rpc_client.decode_script(&req.data),
+                _ => rpc_client.decode_raw_transaction(&req.data),
+            }
+        }
+        (ChainType::Solana, DecodeKind::Transaction) => {
+            chain_forge_solana_rpc::decode_transaction(&req.data)
+                .map(|tx| serde_json::to_value(tx).unwrap_or_default())
+        }
+        (ChainType::Solana, DecodeKind::Message) => {
+            chain_forge_solana_rpc::decode_message(&req.data)
+                .map(|message| serde_json::to_value(message).unwrap_or_default())
+        }
+        (ChainType::Ethereum, _) => {
             return (
-                StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Node not found")),
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "Decoding is not available for Ethereum nodes",
+                )),
             );
         }
-        Err(e) => {
+        (ChainType::Bitcoin, DecodeKind::Message) => {
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(&format!("Failed to get node: {}", e))),
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "Messages can only be decoded on Solana nodes",
+                )),
             );
         }
-    };
-
-    match node.chain {
-        ChainType::Solana => {
-            let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
-            if !rpc_client.is_validator_running() {
-                return (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Solana validator is not running")),
-                );
-            }
-
-            match rpc_client.get_transaction(&signature) {
-                Ok(detail) => {
-                    let response = TransactionDetailInfo {
-                        signature: detail.signature,
-                        slot: detail.slot,
-                        block_time: detail.block_time,
-                        fee: detail.fee,
-                        err: detail.err,
-                        balance_changes: detail
-                            .balance_changes
-                            .into_iter()
-                            .map(|bc| BalanceChangeInfo {
-                                account: bc.account,
-                                before: bc.before,
-                                after: bc.after,
-                                change: bc.change,
-                            })
-                            .collect(),
-                    };
-                    (StatusCode::OK, Json(ApiResponse::success(response)))
-                }
-                Err(e) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error(&format!(
-                        "Failed to get transaction: {}",
-                        e
-                    ))),
-                ),
-            }
+        (ChainType::Solana, DecodeKind::Script) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "Scripts can only be decoded on Bitcoin nodes",
+                )),
+            );
         }
-        ChainType::Bitcoin => {
-            let info = match BitcoinInstanceInfo::load(&node.instance_id) {
-                Ok(info) => info,
-                Err(e) => {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::error(&format!(
-                            "Failed to load instance info: {}",
-                            e
-                        ))),
-                    );
-                }
-            };
+    };
 
-            let rpc_client = match BitcoinRpcClient::new_with_wallet(
-                info.rpc_url,
-                info.rpc_user,
-                info.rpc_password,
-                "chain-forge",
-            ) {
-                Ok(client) => client,
-                Err(e) => {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::error(&format!(
-                            "Failed to create RPC client: {}",
-                            e
-                        ))),
-                    );
-                }
-            };
+    match decoded {
+        Ok(decoded) => (StatusCode::OK, Json(ApiResponse::success(decoded))),
+        // Almost always a malformed payload rather than a node problem
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&e.to_string())),
+        ),
+    }
+}
 
-            if !rpc_client.is_node_running() {
-                return (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    Json(ApiResponse::error("Bitcoin node is not running")),
-                );
-            }
+/// Stream events for every node over a WebSocket
+///
+/// Each text message is an event as JSON, the same as `/api/v1/events`
+/// returns. With `since`, buffered events after that cursor are sent first,
+/// so a reconnecting client can pass the last cursor it saw. If events were
+/// dropped before they could be sent, a `{"type": "resync"}` message tells
+/// the client to refetch whatever it shows.
+pub async fn ws_events(
+    State(events): State<Arc<EventBus>>,
+    Query(query): Query<WsEventsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| send_events(socket, events, query))
+}
 
-            match rpc_client.get_transaction_detail(&signature) {
-                Ok(detail) => {
-                    let balance_changes: Vec<BalanceChangeInfo> = detail
-                        .details
-                        .iter()
-                        .map(|entry| {
-                            let change = entry.amount;
-                            // For "receive", amount is positive; for "send", it's negative
-                            let (before, after) = if change >= 0.0 {
-                                (0.0, change)
-                            } else {
-                                (change.abs(), 0.0)
-                            };
-                            BalanceChangeInfo {
-                                account: entry.address.clone(),
-                                before,
-                                after,
-                                change,
-                            }
-                        })
-                        .collect();
+async fn send_events(mut socket: WebSocket, events: Arc<EventBus>, query: WsEventsQuery) {
+    let node_id = query.node_id.as_deref();
 
-                    // Fee is returned as negative by Bitcoin Core; use absolute value
-                    let fee = detail.fee.map(|f| f.abs()).unwrap_or(0.0);
+    // Subscribe before replaying so nothing published in between is lost
+    let mut receiver = events.subscribe();
+    let mut after = match query.since {
+        Some(since) => match send_buffered(&mut socket, &events, since, node_id).await {
+            Some(cursor) => cursor,
+            None => return,
+        },
+        None => events.latest_cursor(),
+    };
 
-                    let response = TransactionDetailInfo {
-                        signature: detail.txid,
-                        slot: detail.block_height,
-                        block_time: detail.block_time,
-                        fee,
-                        err: None,
-                        balance_changes,
-                    };
-                    (StatusCode::OK, Json(ApiResponse::success(response)))
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Ok(event) => {
+                    if event.cursor <= after || node_id.is_some_and(|id| event.node_id != id) {
+                        continue;
+                    }
+                    after = event.cursor;
+                    if socket.send(ws_message(&event)).await.is_err() {
+                        return;
+                    }
                 }
-                Err(e) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error(&format!(
-                        "Failed to get transaction: {}",
-                        e
-                    ))),
-                ),
-            }
+                // Catch up from the buffer on what the receiver skipped
+                Err(RecvError::Lagged(_)) => {
+                    match send_buffered(&mut socket, &events, after, node_id).await {
+                        Some(cursor) => after = cursor,
+                        None => return,
+                    }
+                }
+                Err(RecvError::Closed) => return,
+            },
+            message = socket.recv() => match message {
+                // Clients have nothing to say; pings are answered by axum
+                Some(Ok(ws::Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
         }
     }
 }
 
+/// Send buffered events after `since`, returning the cursor to continue
+/// from, or `None` if the client went away
+async fn send_buffered(
+    socket: &mut WebSocket,
+    events: &EventBus,
+    since: u64,
+    node_id: Option<&str>,
+) -> Option<u64> {
+    let page = events.since(since, node_id);
+    if page.truncated {
+        let resync = serde_json::json!({ "type": "resync", "cursor": page.cursor });
+        socket
+            .send(ws::Message::Text(resync.to_string().into()))
+            .await
+            .ok()?;
+    }
+    for event in &page.events {
+        socket.send(ws_message(event)).await.ok()?;
+    }
+    Some(page.cursor)
+}
+
+/// Encode a bus event as a WebSocket text message
+fn ws_message(event: &Event) -> ws::Message {
+    ws::Message::Text(serde_json::to_string(event).unwrap_or_default().into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chain_forge_bitcoin_rpc::{
-        BitcoinTransactionDetail, BitcoinTransactionInfo, BitcoinTxDetailEntry,
-    };
+    use chain_forge_bitcoin_rpc::BitcoinTransactionInfo;
+
+    /// Accounts as (label, balance); refreshing adds 1 to every balance
+    fn select(
+        accounts: &[(Option<&str>, f64)],
+        query: AccountsQuery,
+    ) -> (Vec<(usize, f64)>, usize) {
+        let accounts: Vec<(Option<String>, f64)> = accounts
+            .iter()
+            .map(|(label, balance)| (label.map(str::to_string), *balance))
+            .collect();
+        let mut refreshed = 0;
+        let selected = select_accounts(
+            accounts,
+            &query,
+            |acc| acc.0.as_deref(),
+            |acc| acc.1,
+            |accounts| {
+                refreshed = accounts.len();
+                for acc in accounts {
+                    acc.1 += 1.0;
+                }
+            },
+        );
+        let selected = selected.into_iter().map(|(i, acc)| (i, acc.1)).collect();
+        (selected, refreshed)
+    }
+
+    #[test]
+    fn test_select_accounts_pages_before_refreshing() {
+        let accounts = [(None, 0.0); 10];
+        let (selected, refreshed) = select(
+            &accounts,
+            AccountsQuery {
+                offset: 8,
+                limit: Some(5),
+                ..AccountsQuery::default()
+            },
+        );
+        assert_eq!(selected, vec![(8, 1.0), (9, 1.0)]);
+        assert_eq!(refreshed, 2);
+    }
+
+    #[test]
+    fn test_select_accounts_filters() {
+        let accounts = [
+            (Some("alice"), 5.0),
+            (Some("bob"), 0.0),
+            (Some("alice"), 0.0),
+            (None, 9.0),
+            (Some("alice"), 3.0),
+        ];
+
+        // Label keeps the original indices
+        let (selected, _) = select(
+            &accounts,
+            AccountsQuery {
+                label: Some("alice".to_string()),
+                refresh: false,
+                ..AccountsQuery::default()
+            },
+        );
+        assert_eq!(selected, vec![(0, 5.0), (2, 0.0), (4, 3.0)]);
+
+        // The balance filter sees refreshed balances of every match, then pages
+        let (selected, refreshed) = select(
+            &accounts,
+            AccountsQuery {
+                min_balance: Some(4.0),
+                limit: Some(1),
+                offset: 1,
+                ..AccountsQuery::default()
+            },
+        );
+        assert_eq!(selected, vec![(3, 10.0)]);
+        assert_eq!(refreshed, 5);
+
+        // Cached balances are used as is
+        let (selected, refreshed) = select(
+            &accounts,
+            AccountsQuery {
+                min_balance: Some(4.0),
+                refresh: false,
+                ..AccountsQuery::default()
+            },
+        );
+        assert_eq!(selected, vec![(0, 5.0), (3, 9.0)]);
+        assert_eq!(refreshed, 0);
+    }
+
+    fn search_result(amount: f64, block_time: Option<i64>) -> TransactionSearchResult {
+        TransactionSearchResult {
+            signature: "tx1".to_string(),
+            slot: 100,
+            block_time,
+            account: "addr1".to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_search_query_amount_range() {
+        let query = TransactionSearchQuery {
+            min: Some(1.0),
+            max: Some(5.0),
+            ..Default::default()
+        };
+        assert!(query.matches(&search_result(2.5, None)));
+        assert!(query.matches(&search_result(-2.5, None)));
+        assert!(query.matches(&search_result(1.0, None)));
+        assert!(!query.matches(&search_result(0.5, None)));
+        assert!(!query.matches(&search_result(6.0, None)));
+    }
+
+    #[test]
+    fn test_search_query_time_window() {
+        let query = TransactionSearchQuery {
+            since: Some(1_700_000_000),
+            ..Default::default()
+        };
+        assert!(query.matches(&search_result(1.0, Some(1_700_000_000))));
+        assert!(!query.matches(&search_result(1.0, Some(1_699_999_999))));
+        // Unconfirmed transactions are always recent enough
+        assert!(query.matches(&search_result(1.0, None)));
+    }
 
     #[test]
     fn test_api_response_success() {
@@ -1059,6 +5139,17 @@ mod tests {
         assert!(resp.error.is_none());
     }
 
+    #[test]
+    fn test_api_response_message() {
+        let resp: ApiResponse<String> = ApiResponse::message(Message::NodeNotFound);
+        assert!(!resp.success);
+        assert_eq!(resp.error.as_deref(), Some("Node not found"));
+        assert_eq!(resp.code, Some("node.not_found"));
+
+        let json = serde_json::to_value(ApiResponse::<String>::error("plain")).unwrap();
+        assert!(json.get("code").is_none());
+    }
+
     #[test]
     fn test_api_response_error() {
         let resp: ApiResponse<String> = ApiResponse::error("something went wrong");
@@ -1067,6 +5158,126 @@ mod tests {
         assert_eq!(resp.error, Some("something went wrong".to_string()));
     }
 
+    fn start_request(json: serde_json::Value) -> StartNodeRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_node_command_with_preset() {
+        let req = start_request(serde_json::json!({"chain": "solana", "preset": "ci"}));
+        let (chain, command) = node_command(&req).unwrap();
+        assert_eq!(chain, ChainType::Solana);
+        assert_eq!(command.program, "cf-solana");
+        assert!(command
+            .to_string()
+            .contains("--accounts 3 --balance 10 --preset ci"));
+    }
+
+    #[test]
+    fn test_node_command_overrides_preset() {
+        let req = start_request(serde_json::json!({
+            "chain": "bitcoin",
+            "preset": "load-test",
+            "accounts": 7,
+            "port": 18443,
+            "name": "Load Test"
+        }));
+        let (_, command) = node_command(&req).unwrap();
+        assert_eq!(
+            command.to_string(),
+            "cf-bitcoin start --instance default --rpc-port 18443 --accounts 7 --balance 1 --preset load-test --name \"Load Test\""
+        );
+    }
+
+    #[test]
+    fn test_resolve_port() {
+        let req = start_request(serde_json::json!({"chain": "solana"}));
+        assert_eq!(resolve_port(&req).unwrap(), 8899);
+
+        let req = start_request(serde_json::json!({"chain": "bitcoin", "port": "auto"}));
+        assert!(resolve_port(&req).is_err());
+
+        let req = serde_json::json!({"chain": "solana", "port": "any"});
+        assert!(serde_json::from_value::<StartNodeRequest>(req).is_err());
+    }
+
+    #[test]
+    fn test_restart_request_keeps_node_settings() {
+        let node = NodeInfo::new(
+            ChainType::Bitcoin,
+            "shop",
+            Some("Shop".to_string()),
+            "http://localhost:18500".to_string(),
+            18500,
+            4,
+        );
+        let (chain, command) = node_command(&restart_request(&node)).unwrap();
+        assert_eq!(chain, ChainType::Bitcoin);
+        assert_eq!(
+            command.to_string(),
+            "cf-bitcoin start --instance shop --rpc-port 18500 --accounts 4 --balance 100 --name Shop"
+        );
+    }
+
+    #[test]
+    fn test_node_command_rejects_invalid_requests() {
+        let req = start_request(serde_json::json!({"chain": "dogecoin"}));
+        assert!(node_command(&req).is_err());
+
+        let req = start_request(serde_json::json!({"chain": "ethereum", "preset": "ci"}));
+        assert!(node_command(&req).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_node_unknown_preset() {
+        let req = start_request(serde_json::json!({"chain": "solana", "preset": "huge"}));
+        let (status, _) = start_node(
+            State(Arc::new(JobStore::new())),
+            State(Arc::new(Supervisor::new())),
+            Json(req),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_rejects_non_positive_amount() {
+        for amount in [0.0, -1.0, f64::NAN] {
+            let req = TransferRequest {
+                from: "from".to_string(),
+                to: "to".to_string(),
+                amount,
+                confirm: None,
+            };
+            let (status, _) = transfer(Path("solana:missing".to_string()), Json(req)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_headers_timeout_clamped() {
+        assert_eq!(headers_timeout(None), Duration::from_secs(30));
+        assert_eq!(headers_timeout(Some(5)), Duration::from_secs(5));
+        assert_eq!(headers_timeout(Some(0)), Duration::from_secs(0));
+        assert_eq!(headers_timeout(Some(3600)), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_block_header_info_serialization() {
+        let header = BlockHeaderInfo {
+            hash: "00aa".to_string(),
+            height: 102,
+            time: 1700000000,
+            previous_block_hash: Some("00bb".to_string()),
+        };
+
+        let json = serde_json::to_value(&header).unwrap();
+        assert_eq!(json["hash"], "00aa");
+        assert_eq!(json["height"], 102);
+        assert_eq!(json["time"], 1700000000);
+        assert_eq!(json["previous_block_hash"], "00bb");
+    }
+
     #[test]
     fn test_transaction_info_serialization() {
         let tx = TransactionInfo {
@@ -1110,6 +5321,8 @@ mod tests {
                     change: 1.0,
                 },
             ],
+            inputs: None,
+            outputs: None,
         };
 
         let json = serde_json::to_value(&detail).unwrap();
@@ -1193,82 +5406,9 @@ mod tests {
         assert_eq!(mapped.confirmation_status, Some("unconfirmed".to_string()));
     }
 
-    #[test]
-    fn test_bitcoin_detail_to_balance_changes_mapping() {
-        // Simulate the mapping done in get_transaction_detail for Bitcoin
-        let detail = BitcoinTransactionDetail {
-            txid: "abc123".to_string(),
-            amount: -1.0,
-            fee: Some(-0.00005),
-            confirmations: 6,
-            block_height: 110,
-            block_time: Some(1700000000),
-            details: vec![
-                BitcoinTxDetailEntry {
-                    address: "bcrt1qsender".to_string(),
-                    category: "send".to_string(),
-                    amount: -1.0,
-                    label: Some("account-0".to_string()),
-                },
-                BitcoinTxDetailEntry {
-                    address: "bcrt1qreceiver".to_string(),
-                    category: "receive".to_string(),
-                    amount: 1.0,
-                    label: Some("account-1".to_string()),
-                },
-            ],
-        };
-
-        let balance_changes: Vec<BalanceChangeInfo> = detail
-            .details
-            .iter()
-            .map(|entry| {
-                let change = entry.amount;
-                let (before, after) = if change >= 0.0 {
-                    (0.0, change)
-                } else {
-                    (change.abs(), 0.0)
-                };
-                BalanceChangeInfo {
-                    account: entry.address.clone(),
-                    before,
-                    after,
-                    change,
-                }
-            })
-            .collect();
-
-        assert_eq!(balance_changes.len(), 2);
-
-        // Sender: negative change
-        assert_eq!(balance_changes[0].account, "bcrt1qsender");
-        assert_eq!(balance_changes[0].change, -1.0);
-        assert_eq!(balance_changes[0].before, 1.0);
-        assert_eq!(balance_changes[0].after, 0.0);
-
-        // Receiver: positive change
-        assert_eq!(balance_changes[1].account, "bcrt1qreceiver");
-        assert_eq!(balance_changes[1].change, 1.0);
-        assert_eq!(balance_changes[1].before, 0.0);
-        assert_eq!(balance_changes[1].after, 1.0);
-    }
-
-    #[test]
-    fn test_bitcoin_fee_absolute_value() {
-        // Bitcoin Core returns fees as negative values; we convert to absolute
-        let btc_fee = Some(-0.00005_f64);
-        let fee = btc_fee.map(|f| f.abs()).unwrap_or(0.0);
-        assert_eq!(fee, 0.00005);
-
-        let no_fee: Option<f64> = None;
-        let fee = no_fee.map(|f| f.abs()).unwrap_or(0.0);
-        assert_eq!(fee, 0.0);
-    }
-
     #[test]
     fn test_transaction_dedup_and_sort() {
-        // Simulate dedup + sort logic from get_node_transactions
-        let mut transactions = vec![
+        let transactions = vec![
             TransactionInfo {
                 signature: "tx1".to_string(),
                 slot: 100,
@@ -1298,14 +5438,106 @@ mod tests {
             },
         ];
 
-        // Sort by block_time descending
-        transactions.sort_by(|a, b| b.block_time.cmp(&a.block_time));
-        // Deduplicate by signature
-        transactions.dedup_by(|a, b| a.signature == b.signature);
+        let mut recent = RecentTransactions::new(usize::MAX);
+        for tx in transactions {
+            recent.offer(tx.block_time, tx);
+        }
+        let transactions = recent.into_vec();
 
         assert_eq!(transactions.len(), 2);
-        // Most recent first
+        // Most recent first, each signature once for the first account seen
         assert_eq!(transactions[0].signature, "tx2");
         assert_eq!(transactions[1].signature, "tx1");
+        assert_eq!(transactions[1].account, "addr1");
+    }
+
+    #[test]
+    fn test_recent_transactions_keeps_limit() {
+        let tx = |signature: &str, slot: u64| TransactionInfo {
+            signature: signature.to_string(),
+            slot,
+            err: None,
+            memo: None,
+            block_time: None,
+            confirmation_status: None,
+            account: "addr".to_string(),
+        };
+        let mut recent = RecentTransactions::new(2);
+
+        // One account's list, newest first
+        assert!(recent.offer(30, tx("a", 30)));
+        assert!(recent.offer(10, tx("b", 10)));
+        // Another account's: newer ones push out the oldest kept
+        assert!(recent.offer(40, tx("c", 40)));
+        assert!(recent.offer(30, tx("a", 30)));
+        assert!(!recent.offer(20, tx("d", 20)));
+
+        let signatures: Vec<_> = recent
+            .into_vec()
+            .into_iter()
+            .map(|tx| tx.signature)
+            .collect();
+        assert_eq!(signatures, ["c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_node_transactions_rejects_zero_limit() {
+        let (status, Json(response)) = get_node_transactions(
+            Path("solana:unknown".to_string()),
+            Query(TransactionsQuery { limit: Some(0) }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(!response.success);
+    }
+
+    #[test]
+    fn test_load_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain-forge.toml");
+
+        // No file yet: built-in defaults only
+        let profiles = load_profiles(&path).unwrap();
+        assert!(!profiles.exists);
+        assert_eq!(profiles.solana["default"].port, 8899);
+        assert_eq!(profiles.bitcoin.len(), 1);
+
+        std::fs::write(
+            &path,
+            "[solana.ci]\naccounts = 3\n\n[bitcoin.default]\naccounts = 2\n",
+        )
+        .unwrap();
+        let profiles = load_profiles(&path).unwrap();
+        assert!(profiles.exists);
+        assert_eq!(profiles.solana["ci"].accounts, 3);
+        assert_eq!(profiles.solana["default"].accounts, 10);
+        assert_eq!(profiles.bitcoin["default"].accounts, 2);
+    }
+
+    #[test]
+    fn test_write_profile_value_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain-forge.toml");
+
+        let err = write_profile_value::<SolanaProfile>(
+            &path,
+            ChainType::Solana,
+            "ci",
+            serde_json::json!({ "accounts": 0 }),
+            SolanaProfile::validate,
+        )
+        .unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert!(!path.exists());
+
+        write_profile_value::<SolanaProfile>(
+            &path,
+            ChainType::Solana,
+            "ci",
+            serde_json::json!({ "accounts": 3 }),
+            SolanaProfile::validate,
+        )
+        .unwrap();
+        assert_eq!(load_profiles(&path).unwrap().solana["ci"].accounts, 3);
     }
 }
@@ -0,0 +1,408 @@
+//! Tracked background jobs for operations that outlive an HTTP request.
+//!
+//! A request that starts slow work (node startup, snapshots, bulk funding,
+//! rescans) creates a job and returns its ID right away; clients poll
+//! `GET /api/v1/jobs/{id}` for the phase, progress, and outcome, and can
+//! cancel with `DELETE /api/v1/jobs/{id}`.
+//!
+//! The job table is saved to `~/.chain-forge/jobs.json` on every change, so
+//! finished jobs survive an API-server restart. Jobs that were still running
+//! when the server stopped are marked as failed on the next start, since
+//! their work died with the process.
+
+use chain_forge_config::Config;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Finished jobs kept for polling; older ones are dropped first
+pub const MAX_FINISHED_JOBS: usize = 256;
+
+/// Where a job is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// A long-running operation and its progress
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Job {
+    pub id: String,
+    /// What the job does, e.g. `start_node` or `rescan`
+    pub kind: String,
+    /// Node the job acts on, if any
+    pub node_id: Option<String>,
+    pub status: JobStatus,
+    /// Current step, e.g. `mining` or `funding`
+    pub phase: String,
+    /// Fraction complete from 0.0 to 1.0, when the job can tell
+    pub progress: Option<f64>,
+    pub error: Option<String>,
+    /// Job-specific output once it succeeds
+    pub result: Option<serde_json::Value>,
+    /// Unix seconds
+    pub created_at: i64,
+    /// Unix seconds
+    pub updated_at: i64,
+}
+
+/// Error returned by job work that stopped because it was cancelled
+pub const CANCELLED: &str = "cancelled";
+
+/// Job table, optionally backed by a file
+#[derive(Default)]
+pub struct JobStore {
+    jobs: Mutex<HashMap<String, Job>>,
+    next_id: AtomicU64,
+    path: Option<PathBuf>,
+}
+
+impl JobStore {
+    /// Create an in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the store at the default location (`~/.chain-forge/jobs.json`)
+    pub fn open_default() -> Self {
+        Self::open(Config::data_dir().join("jobs.json"))
+    }
+
+    /// Open a store saved at `path`, creating it on the first change
+    ///
+    /// Jobs left running by a previous server are marked as failed.
+    pub fn open(path: PathBuf) -> Self {
+        let mut jobs: HashMap<String, Job> = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable job file");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        let mut interrupted = false;
+        for job in jobs.values_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Failed;
+                job.error = Some("Interrupted by an API server restart".to_string());
+                interrupted = true;
+            }
+        }
+
+        let store = Self {
+            jobs: Mutex::new(jobs),
+            next_id: AtomicU64::new(0),
+            path: Some(path),
+        };
+        if interrupted {
+            store.save(&store.jobs.lock().unwrap());
+        }
+        store
+    }
+
+    /// Register a new running job
+    pub fn create(&self, kind: &str, node_id: Option<String>) -> Job {
+        let now = chrono::Utc::now();
+        let sequence = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            id: format!("{:x}-{}", now.timestamp_millis(), sequence),
+            kind: kind.to_string(),
+            node_id,
+            status: JobStatus::Running,
+            phase: "queued".to_string(),
+            progress: None,
+            error: None,
+            result: None,
+            created_at: now.timestamp(),
+            updated_at: now.timestamp(),
+        };
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.insert(job.id.clone(), job.clone());
+        self.save(&jobs);
+        job
+    }
+
+    /// Look up a job by ID
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    /// All known jobs, newest first
+    pub fn list(&self) -> Vec<Job> {
+        let mut jobs: Vec<Job> = self.jobs.lock().unwrap().values().cloned().collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        jobs
+    }
+
+    /// Record the step a running job is on
+    pub fn set_phase(&self, id: &str, phase: &str, progress: Option<f64>) {
+        self.update(id, |job| {
+            job.phase = phase.to_string();
+            job.progress = progress.map(|p| p.clamp(0.0, 1.0));
+        });
+    }
+
+    /// Mark a running job as succeeded or failed
+    ///
+    /// A job that was cancelled in the meantime stays cancelled.
+    pub fn finish(&self, id: &str, outcome: Result<serde_json::Value, String>) {
+        self.update(id, |job| match outcome {
+            Ok(result) => {
+                job.status = JobStatus::Succeeded;
+                job.phase = "done".to_string();
+                job.progress = Some(1.0);
+                job.result = Some(result);
+            }
+            Err(error) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(error);
+            }
+        });
+        self.prune();
+    }
+
+    /// Ask a running job to stop
+    ///
+    /// The job is marked cancelled right away; its work stops at the next
+    /// point where it checks [`JobProgress::is_cancelled`]. Returns the job,
+    /// or `None` if it doesn't exist.
+    pub fn cancel(&self, id: &str) -> Option<Job> {
+        self.update(id, |job| job.status = JobStatus::Cancelled);
+        self.get(id)
+    }
+
+    /// Run `work` on a blocking thread as a tracked job
+    ///
+    /// `work` reports its steps through the given [`JobProgress`] and should
+    /// return early once it is cancelled; its return value becomes the job's
+    /// result or error.
+    pub fn spawn<F>(self: &Arc<Self>, kind: &str, node_id: Option<String>, work: F) -> Job
+    where
+        F: FnOnce(&JobProgress) -> Result<serde_json::Value, String> + Send + 'static,
+    {
+        let job = self.create(kind, node_id);
+        let progress = JobProgress {
+            jobs: self.clone(),
+            id: job.id.clone(),
+        };
+        tokio::task::spawn_blocking(move || {
+            progress.phase("running", None);
+            let outcome = work(&progress);
+            progress.jobs.finish(&progress.id, outcome);
+        });
+        job
+    }
+
+    /// Apply `f` to a job that is still running
+    fn update(&self, id: &str, f: impl FnOnce(&mut Job)) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.get_mut(id) {
+            if job.status != JobStatus::Running {
+                return;
+            }
+            f(job);
+            job.updated_at = chrono::Utc::now().timestamp();
+            self.save(&jobs);
+        }
+    }
+
+    /// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+    fn prune(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut finished: Vec<(i64, String)> = jobs
+            .values()
+            .filter(|job| job.status != JobStatus::Running)
+            .map(|job| (job.updated_at, job.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+        self.save(&jobs);
+    }
+
+    /// Write the table to disk (best effort)
+    fn save(&self, jobs: &HashMap<String, Job>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(jobs)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let tmp = path.with_extension("json.tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, path)
+            });
+        if let Err(e) = result {
+            warn!(path = %path.display(), error = %e, "Failed to save jobs");
+        }
+    }
+}
+
+/// Handle a running job uses to report its progress
+pub struct JobProgress {
+    jobs: Arc<JobStore>,
+    id: String,
+}
+
+impl JobProgress {
+    /// Record the step the job is on
+    pub fn phase(&self, phase: &str, progress: Option<f64>) {
+        self.jobs.set_phase(&self.id, phase, progress);
+    }
+
+    /// Whether the job was cancelled and should stop
+    pub fn is_cancelled(&self) -> bool {
+        self.jobs
+            .get(&self.id)
+            .map_or(true, |job| job.status == JobStatus::Cancelled)
+    }
+
+    /// `Err` once the job was cancelled, for use with `?` between steps
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn wait_until_done(jobs: &JobStore, id: &str) -> Job {
+        for _ in 0..100 {
+            let job = jobs.get(id).unwrap();
+            if job.status != JobStatus::Running {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        jobs.get(id).unwrap()
+    }
+
+    #[test]
+    fn test_job_lifecycle() {
+        let jobs = JobStore::new();
+        let job = jobs.create("start_node", Some("bitcoin:dev".to_string()));
+        assert_eq!(job.status, JobStatus::Running);
+        assert_eq!(job.phase, "queued");
+
+        jobs.set_phase(&job.id, "mining", Some(0.25));
+        let current = jobs.get(&job.id).unwrap();
+        assert_eq!(current.phase, "mining");
+        assert_eq!(current.progress, Some(0.25));
+
+        jobs.finish(&job.id, Err("bitcoind exited".to_string()));
+        let current = jobs.get(&job.id).unwrap();
+        assert_eq!(current.status, JobStatus::Failed);
+        assert_eq!(current.phase, "mining");
+        assert_eq!(current.error.as_deref(), Some("bitcoind exited"));
+
+        assert!(jobs.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_job_ids_are_unique() {
+        let jobs = JobStore::new();
+        let a = jobs.create("start_node", None);
+        let b = jobs.create("start_node", None);
+        assert_ne!(a.id, b.id);
+        assert_eq!(jobs.list().len(), 2);
+    }
+
+    #[test]
+    fn test_finished_jobs_are_pruned() {
+        let jobs = JobStore::new();
+        let running = jobs.create("start_node", None);
+        for _ in 0..MAX_FINISHED_JOBS + 5 {
+            let job = jobs.create("start_node", None);
+            jobs.finish(&job.id, Ok(serde_json::Value::Null));
+        }
+        assert_eq!(jobs.jobs.lock().unwrap().len(), MAX_FINISHED_JOBS + 1);
+        assert!(jobs.get(&running.id).is_some());
+    }
+
+    #[test]
+    fn test_cancelled_job_stays_cancelled() {
+        let jobs = JobStore::new();
+        let job = jobs.create("rescan", None);
+        assert_eq!(jobs.cancel(&job.id).unwrap().status, JobStatus::Cancelled);
+
+        jobs.finish(&job.id, Ok(serde_json::Value::Null));
+        jobs.set_phase(&job.id, "scanning", Some(0.5));
+        let current = jobs.get(&job.id).unwrap();
+        assert_eq!(current.status, JobStatus::Cancelled);
+        assert!(current.result.is_none());
+
+        assert!(jobs.cancel("missing").is_none());
+    }
+
+    #[test]
+    fn test_jobs_persist_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+
+        let jobs = JobStore::open(path.clone());
+        let done = jobs.create("bulk_fund", None);
+        jobs.finish(&done.id, Ok(serde_json::json!({ "sent": 2 })));
+        let running = jobs.create("rescan", None);
+        drop(jobs);
+
+        let reopened = JobStore::open(path);
+        assert_eq!(reopened.get(&done.id).unwrap().result.unwrap()["sent"], 2);
+        let interrupted = reopened.get(&running.id).unwrap();
+        assert_eq!(interrupted.status, JobStatus::Failed);
+        assert!(interrupted.error.unwrap().contains("restart"));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_reports_result() {
+        let jobs = Arc::new(JobStore::new());
+        let job = jobs.spawn("start_node", None, |progress| {
+            progress.phase("funding", Some(0.5));
+            Ok(serde_json::json!({ "accounts": 3 }))
+        });
+
+        let done = wait_until_done(&jobs, &job.id).await;
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!(done.progress, Some(1.0));
+        assert_eq!(done.result.unwrap()["accounts"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_work_sees_cancellation() {
+        let jobs = Arc::new(JobStore::new());
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let job = jobs.spawn("bulk_fund", None, move |progress| {
+            started_tx.send(()).unwrap();
+            for _ in 0..200 {
+                progress.check_cancelled()?;
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Ok(serde_json::Value::Null)
+        });
+
+        started_rx.recv().unwrap();
+        jobs.cancel(&job.id);
+        let done = wait_until_done(&jobs, &job.id).await;
+        assert_eq!(done.status, JobStatus::Cancelled);
+    }
+}
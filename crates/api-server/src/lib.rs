@@ -3,8 +3,19 @@
 //! This crate provides an HTTP REST API for monitoring and controlling
 //! blockchain nodes managed by Chain Forge. Used by the web dashboard.
 
+pub mod activity;
+pub mod debug_log;
 pub mod handlers;
+pub mod jobs;
+pub mod mock;
+pub mod node_logs;
+pub mod openapi;
 pub mod routes;
 pub mod server;
+pub mod settings;
+pub mod supervisor;
+pub mod watcher;
 
+pub use debug_log::DebugLogConfig;
+pub use routes::ServerMode;
 pub use server::start_server;
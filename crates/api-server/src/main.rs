@@ -2,9 +2,10 @@
 //!
 //! Starts the REST API server for the Chain Forge web dashboard.
 
-use chain_forge_api_server::start_server;
+use chain_forge_api_server::{start_server, DebugLogConfig, ServerMode};
+use chain_forge_cli_utils::{data_dir, logging, DataDirArgs, ExitCode, LogArgs};
 use clap::Parser;
-use eyre::Result;
+use tracing::{error, info};
 
 #[derive(Parser)]
 #[command(name = "cf-api")]
@@ -18,16 +19,38 @@ struct Cli {
     /// Open API documentation in browser after starting
     #[arg(short, long, default_value = "false")]
     open: bool,
+
+    /// Log request/response bodies for routes starting with this path (repeatable)
+    #[arg(long = "debug-route", value_name = "PATH")]
+    debug_routes: Vec<String>,
+
+    /// Token that lets clients enable body logging per request with
+    /// `X-Debug: true` and `Authorization: Bearer <token>`
+    #[arg(long, env = "CF_API_DEBUG_TOKEN", hide_env_values = true)]
+    debug_token: Option<String>,
+
+    /// Endpoints to expose: `full`, or `explorer` for read-only endpoints
+    /// that are safe to share outside the team
+    #[arg(long, value_enum, default_value = "full")]
+    mode: ServerMode,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    data: DataDirArgs,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+    data_dir::init(&cli.data);
+    logging::init(&cli.log);
 
     if cli.open {
         // Try to open the dashboard URL in the default browser
         let url = format!("http://localhost:{}", cli.port);
-        println!("🌐 Opening dashboard at {}...", url);
+        info!(%url, "Opening dashboard");
 
         #[cfg(target_os = "macos")]
         {
@@ -45,5 +68,13 @@ async fn main() -> Result<()> {
         }
     }
 
-    start_server(cli.port).await
+    let debug_log = DebugLogConfig {
+        routes: cli.debug_routes,
+        token: cli.debug_token,
+    };
+
+    if let Err(e) = start_server(cli.port, debug_log, cli.mode).await {
+        error!(error = ?e, "API server failed");
+        ExitCode::from_report(&e).exit();
+    }
 }
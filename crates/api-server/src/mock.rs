@@ -0,0 +1,283 @@
+//! Mock nodes for building the dashboard without node binaries.
+//!
+//! `cf-api --mode mock` serves the node, account, and funding endpoints the
+//! dashboard uses from one [`MockProvider`] per chain, instead of the
+//! registry and real nodes. Accounts and balances are made up the same way
+//! on every run and kept in memory until the server stops.
+//! `POST /api/v1/mock/faults` slows down or breaks later calls, to check how
+//! the dashboard copes.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chain_forge_common::{
+    ChainError, ChainProvider, ChainType, Message, MockConfig, MockProvider, NodeStatus,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::handlers::{
+    select_accounts, AccountInfo, AccountsQuery, ApiResponse, FundAccountRequest, FundResponse,
+    HealthCheckResponse, NodeInfoResponse, TransferRequest, TransferResponse,
+};
+use crate::routes::AppState;
+
+type Response<T> = (StatusCode, Json<ApiResponse<T>>);
+
+/// Latency and failures added to later calls to every mock node
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MockFaultsRequest {
+    /// Delay added to every call, in milliseconds
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Fail every Nth call; calls never fail if absent
+    pub fail_every: Option<u32>,
+}
+
+/// A running mock node per chain
+///
+/// Providers are locked while a call runs, so added latency delays other
+/// calls to the same node, like a busy node would.
+pub struct MockNodes {
+    providers: Vec<Mutex<MockProvider>>,
+}
+
+impl MockNodes {
+    /// Start a `mock` instance of every chain
+    pub fn start() -> Self {
+        let providers = [
+            (ChainType::Solana, "Mock Solana"),
+            (ChainType::Bitcoin, "Mock Bitcoin"),
+            (ChainType::Ethereum, "Mock Ethereum"),
+        ]
+        .into_iter()
+        .map(|(chain, name)| {
+            let mut config = MockConfig::new(chain, "mock");
+            config.name = Some(name.to_string());
+            let mut provider = MockProvider::new(config.clone());
+            provider
+                .start(config)
+                .expect("a new mock provider always starts");
+            Mutex::new(provider)
+        })
+        .collect();
+        Self { providers }
+    }
+
+    fn all(&self) -> impl Iterator<Item = MutexGuard<'_, MockProvider>> {
+        self.providers
+            .iter()
+            .map(|provider| provider.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn find<T>(&self, node_id: &str) -> Result<MutexGuard<'_, MockProvider>, Response<T>> {
+        self.all()
+            .find(|provider| provider.node_info().node_id == node_id)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponse::message(Message::NodeNotFound)),
+                )
+            })
+    }
+}
+
+/// Routes served in mock mode
+pub fn routes(nodes: Arc<MockNodes>) -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/nodes", get(list_nodes))
+        .route("/api/v1/nodes/{node_id}", get(get_node))
+        .route("/api/v1/nodes/{node_id}/accounts", get(get_node_accounts))
+        .route("/api/v1/nodes/{node_id}/fund", post(fund_account))
+        .route("/api/v1/nodes/{node_id}/transfer", post(transfer))
+        .route("/api/v1/health", post(health_check))
+        .route("/api/v1/mock/faults", post(set_faults))
+        .with_state(nodes)
+}
+
+/// Error response for a failed mock call
+fn chain_error<T>(chain: ChainType, e: ChainError) -> Response<T> {
+    match e {
+        ChainError::NotRunning => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::message(Message::ChainNotRunning(chain))),
+        ),
+        e => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&e.to_string())),
+        ),
+    }
+}
+
+async fn list_nodes(State(nodes): State<Arc<MockNodes>>) -> Response<Vec<NodeInfoResponse>> {
+    let response = nodes
+        .all()
+        .map(|provider| provider.node_info().into())
+        .collect();
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
+async fn get_node(
+    State(nodes): State<Arc<MockNodes>>,
+    Path(node_id): Path<String>,
+) -> Response<NodeInfoResponse> {
+    match nodes.find(&node_id) {
+        Ok(provider) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(provider.node_info().into())),
+        ),
+        Err(response) => response,
+    }
+}
+
+async fn get_node_accounts(
+    State(nodes): State<Arc<MockNodes>>,
+    Path(node_id): Path<String>,
+    Query(query): Query<AccountsQuery>,
+) -> Response<Vec<AccountInfo>> {
+    let provider = match nodes.find(&node_id) {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    let accounts = match provider.get_accounts() {
+        Ok(accounts) => accounts,
+        Err(e) => return chain_error(provider.config().chain, e),
+    };
+
+    // Balances are always current, so there is nothing to refresh
+    let accounts = select_accounts(accounts, &query, |_| None, |acc| acc.balance, |_| {})
+        .into_iter()
+        .map(|(index, acc)| AccountInfo {
+            index,
+            address: acc.address,
+            balance: acc.balance,
+            label: None,
+            descriptor: None,
+        })
+        .collect();
+    (StatusCode::OK, Json(ApiResponse::success(accounts)))
+}
+
+async fn fund_account(
+    State(nodes): State<Arc<MockNodes>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<FundAccountRequest>,
+) -> Response<FundResponse> {
+    let provider = match nodes.find(&node_id) {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    if req.amount <= 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::AmountNotPositive)),
+        );
+    }
+
+    let funded = provider
+        .get_balance(&req.address)
+        .and_then(|balance| provider.set_balance(&req.address, balance + req.amount));
+    match funded {
+        Ok(txid) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(FundResponse {
+                success: true,
+                txid_or_signature: txid,
+                address: req.address,
+                amount: req.amount,
+            })),
+        ),
+        Err(e) => chain_error(provider.config().chain, e),
+    }
+}
+
+async fn transfer(
+    State(nodes): State<Arc<MockNodes>>,
+    Path(node_id): Path<String>,
+    Json(req): Json<TransferRequest>,
+) -> Response<TransferResponse> {
+    let provider = match nodes.find(&node_id) {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    if req.amount <= 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::message(Message::AmountNotPositive)),
+        );
+    }
+
+    match provider.transfer(&req.from, &req.to, req.amount) {
+        Ok(txid) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(TransferResponse {
+                txid_or_signature: txid,
+                from: req.from,
+                to: req.to,
+                amount: req.amount,
+            })),
+        ),
+        Err(e) => chain_error(provider.config().chain, e),
+    }
+}
+
+async fn health_check(State(nodes): State<Arc<MockNodes>>) -> Response<HealthCheckResponse> {
+    let running = nodes
+        .all()
+        .filter(|provider| provider.node_info().status == NodeStatus::Running)
+        .count();
+    let response = HealthCheckResponse {
+        total: nodes.providers.len(),
+        running,
+        stopped: nodes.providers.len() - running,
+        unknown: 0,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
+async fn set_faults(
+    State(nodes): State<Arc<MockNodes>>,
+    Json(req): Json<MockFaultsRequest>,
+) -> Response<MockFaultsRequest> {
+    for provider in nodes.all() {
+        provider.set_faults(Duration::from_millis(req.latency_ms), req.fail_every);
+    }
+    (StatusCode::OK, Json(ApiResponse::success(req)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_nodes_serve_accounts() {
+        let nodes = Arc::new(MockNodes::start());
+        let (status, Json(response)) = list_nodes(State(nodes.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        let listed = response.data.unwrap();
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[1].node_id, "bitcoin:mock");
+
+        let (status, Json(response)) = get_node_accounts(
+            State(nodes.clone()),
+            Path("ethereum:mock".to_string()),
+            Query(AccountsQuery {
+                limit: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let accounts = response.data.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts[0].address.starts_with("0x"));
+
+        let (status, _) = get_node(State(nodes), Path("solana:missing".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
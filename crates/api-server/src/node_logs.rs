@@ -0,0 +1,203 @@
+//! Reading node log files for the log download and tail endpoints.
+//!
+//! Each node has one main log: the validator's `validator.log` for Solana,
+//! bitcoind's `debug.log` for Bitcoin, and anvil's output for Ethereum.
+//! Nodes started by a CLI also have an instance log of the events Chain
+//! Forge itself logged while starting and running them.
+
+use chain_forge_bitcoin_core::BitcoinConfig;
+use chain_forge_common::{ChainType, NodeInfo};
+use chain_forge_ethereum_core::EthereumConfig;
+use chain_forge_solana_core::SolanaConfig;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Lines returned by a tail when none are requested
+pub const DEFAULT_TAIL_LINES: usize = 200;
+
+/// Most lines a single tail may return
+pub const MAX_TAIL_LINES: usize = 10_000;
+
+/// How often a followed log is checked for new lines
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Bytes read at a time when searching backwards for line breaks
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Main log file of a node
+pub fn log_file(node: &NodeInfo) -> PathBuf {
+    match node.chain {
+        ChainType::Solana => SolanaConfig::with_instance(&node.instance_id).log_file(),
+        ChainType::Bitcoin => BitcoinConfig::with_instance(&node.instance_id).log_file(),
+        ChainType::Ethereum => EthereumConfig::with_instance(&node.instance_id).log_file(),
+    }
+}
+
+/// Directory holding a node's instance data, including its instance log
+pub fn instance_dir(node: &NodeInfo) -> PathBuf {
+    match node.chain {
+        ChainType::Solana => SolanaConfig::with_instance(&node.instance_id).instance_dir(),
+        ChainType::Bitcoin => BitcoinConfig::with_instance(&node.instance_id).instance_dir(),
+        ChainType::Ethereum => EthereumConfig::with_instance(&node.instance_id).instance_dir(),
+    }
+}
+
+/// Last `lines` lines of a file, and the file length they were read up to
+///
+/// Only the end of the file is read, so this stays cheap on large logs.
+pub fn tail(path: &Path, lines: usize) -> io::Result<(Vec<String>, u64)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    // Read backwards until there is a line break before the first wanted line
+    let mut start = len;
+    let mut bytes = Vec::new();
+    while start > 0 && bytes.iter().filter(|&&b| b == b'\n').count() <= lines {
+        let chunk = CHUNK_SIZE.min(start);
+        start -= chunk;
+        let mut buf = vec![0; chunk as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+        buf.extend_from_slice(&bytes);
+        bytes = buf;
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let all: Vec<&str> = text.lines().collect();
+    let skip = all.len().saturating_sub(lines);
+    Ok((all[skip..].iter().map(|l| l.to_string()).collect(), len))
+}
+
+/// Bytes appended to a file since `offset`, and the new offset
+///
+/// A file shorter than `offset` was truncated or replaced (e.g. by a node
+/// restart), so it is read from the start.
+pub fn read_from(path: &Path, offset: u64) -> io::Result<(Vec<u8>, u64)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let offset = if len < offset { 0 } else { offset };
+
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_to_end(&mut bytes)?;
+    let end = offset + bytes.len() as u64;
+    Ok((bytes, end))
+}
+
+/// Lines appended to a file after `offset`, as they are written
+///
+/// The stream ends when its receiver is dropped. A file that goes missing,
+/// as it does while a node restarts, is waited for.
+pub fn follow(path: PathBuf, offset: u64) -> ReceiverStream<String> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(256);
+
+    tokio::spawn(async move {
+        let mut offset = offset;
+        let mut partial = String::new();
+        while !sender.is_closed() {
+            tokio::time::sleep(FOLLOW_INTERVAL).await;
+            let Ok((bytes, end)) = read_from(&path, offset) else {
+                continue;
+            };
+            if end < offset {
+                partial.clear();
+            }
+            offset = end;
+
+            // Lines still being written are held back until they're complete
+            partial.push_str(&String::from_utf8_lossy(&bytes));
+            while let Some(i) = partial.find('\n') {
+                let line = partial[..i].trim_end_matches('\r').to_string();
+                partial.drain(..=i);
+                if sender.send(line).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+/// Gzip-compressed contents of a file
+pub fn gzip(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    io::copy(&mut file, &mut encoder)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Write;
+
+    fn write_log(dir: &Path, contents: &str) -> PathBuf {
+        let path = dir.join("node.log");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_log(dir.path(), "one\ntwo\nthree\n");
+
+        let (lines, len) = tail(&path, 2).unwrap();
+        assert_eq!(lines, vec!["two", "three"]);
+        assert_eq!(len, 14);
+
+        let (lines, _) = tail(&path, 10).unwrap();
+        assert_eq!(lines, vec!["one", "two", "three"]);
+
+        let (lines, _) = tail(&path, 0).unwrap();
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_tail_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents: String = (0..20_000).map(|i| format!("line {}\n", i)).collect();
+        let path = write_log(dir.path(), &contents);
+
+        let (lines, _) = tail(&path, 15_000).unwrap();
+        assert_eq!(lines.len(), 15_000);
+        assert_eq!(lines[0], "line 5000");
+        assert_eq!(lines[14_999], "line 19999");
+    }
+
+    #[test]
+    fn test_read_from() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_log(dir.path(), "one\n");
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"two\n").unwrap();
+        assert_eq!(read_from(&path, 4).unwrap(), (b"two\n".to_vec(), 8));
+
+        // A truncated log is read again from the start
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(read_from(&path, 8).unwrap(), (b"new\n".to_vec(), 4));
+    }
+
+    #[test]
+    fn test_gzip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_log(dir.path(), "one\ntwo\n");
+
+        let mut contents = String::new();
+        GzDecoder::new(gzip(&path).unwrap().as_slice())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "one\ntwo\n");
+    }
+}
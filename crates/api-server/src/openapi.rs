@@ -0,0 +1,816 @@
+//! OpenAPI 3 description of the REST API.
+//!
+//! The document is served at `/api/v1/openapi.json` and can be browsed with
+//! Swagger UI at `/docs`. Request and response schemas are generated from the
+//! handler types with schemars; the operations themselves are listed here and
+//! have to be kept in step with `routes.rs`.
+
+use crate::activity::ActivityBucket;
+use crate::handlers::{
+    AccountInfo, AccountsQuery, ActivityQuery, BlockFilterInfo, BlockHeaderInfo, BlockHeadersQuery,
+    BlockInfo, BlocksQuery, BulkFundRequest, CleanupResponse, CreateTokenRequest, DecodeRequest,
+    EventsQuery, FundAccountRequest, FundResponse, GroupOperationResponse, HealthCheckResponse,
+    InstanceLogQuery, InstanceLogResponse, LogTailQuery, LogTailResponse, MineRequest,
+    MineResponse, MintTokensRequest, NodeHealthInfo, NodeInfoResponse, PaymentInfo, PaymentQuery,
+    ProfilesResponse, ReloadResponse, RescanRequest, SnapshotRequest, StartNodeRequest,
+    StartNodeResponse, StopAllQuery, StopNodeResponse, TokenBalance, TokenMintInfo,
+    TokenTransferInfo, TransactionDetailInfo, TransactionInfo, TransactionSearchQuery,
+    TransactionSearchResult, TransactionsQuery, TransferRequest, TransferResponse,
+    TransferTokensRequest, UpdateProfileRequest, UtxoLockRequest, WarpRequest, WsEventsQuery,
+};
+use crate::jobs::Job;
+use crate::mock::MockFaultsRequest;
+use crate::routes::ServerMode;
+use axum::response::Html;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::ObjectValidation;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+/// Reference to a transaction output, as accepted by the UTXO lock endpoints
+#[derive(JsonSchema)]
+pub struct Outpoint {
+    pub txid: String,
+    pub vout: u32,
+}
+
+/// An endpoint of the API
+struct Operation {
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    /// Query parameters
+    query: Vec<Value>,
+    /// JSON request body schema
+    body: Option<Value>,
+    /// Successful response
+    response: Value,
+}
+
+impl Operation {
+    fn get(path: &'static str, tag: &'static str, summary: &'static str, response: Value) -> Self {
+        Self {
+            method: "get",
+            path,
+            tag,
+            summary,
+            query: Vec::new(),
+            body: None,
+            response,
+        }
+    }
+
+    fn post(path: &'static str, tag: &'static str, summary: &'static str, response: Value) -> Self {
+        Self {
+            method: "post",
+            ..Self::get(path, tag, summary, response)
+        }
+    }
+
+    fn put(path: &'static str, tag: &'static str, summary: &'static str, response: Value) -> Self {
+        Self {
+            method: "put",
+            ..Self::get(path, tag, summary, response)
+        }
+    }
+
+    fn delete(
+        path: &'static str,
+        tag: &'static str,
+        summary: &'static str,
+        response: Value,
+    ) -> Self {
+        Self {
+            method: "delete",
+            ..Self::get(path, tag, summary, response)
+        }
+    }
+
+    fn with_query(mut self, query: Vec<Value>) -> Self {
+        self.query = query;
+        self
+    }
+
+    fn with_body(mut self, body: Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut parameters: Vec<Value> = path_params(self.path)
+            .into_iter()
+            .map(|name| {
+                let schema = if name == "height" {
+                    json!({ "type": "integer", "format": "uint64", "minimum": 0 })
+                } else {
+                    json!({ "type": "string" })
+                };
+                json!({ "name": name, "in": "path", "required": true, "schema": schema })
+            })
+            .collect();
+        parameters.extend(self.query.iter().cloned());
+
+        let mut operation = json!({
+            "tags": [self.tag],
+            "summary": self.summary,
+            "operationId": operation_id(self.method, self.path),
+            "responses": {
+                "200": self.response,
+                "default": {
+                    "description": "Error",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/ErrorResponse" }
+                        }
+                    }
+                }
+            }
+        });
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if let Some(body) = &self.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": body } }
+            });
+        }
+        operation
+    }
+}
+
+/// Names of the `{name}` segments of a route path
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .collect()
+}
+
+/// Operation ID from the method and path, e.g. `get_nodes_node_id_accounts`
+fn operation_id(method: &str, path: &str) -> String {
+    let path = path.trim_start_matches("/api/v1");
+    let words: Vec<&str> = path
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    format!("{}_{}", method, words.join("_"))
+}
+
+/// Schemas for the document, collected as operations refer to them
+struct Schemas {
+    gen: SchemaGenerator,
+}
+
+impl Schemas {
+    fn new() -> Self {
+        Self {
+            gen: SchemaGenerator::new(SchemaSettings::openapi3()),
+        }
+    }
+
+    fn schema<T: JsonSchema>(&mut self) -> Value {
+        serde_json::to_value(self.gen.subschema_for::<T>()).unwrap_or_default()
+    }
+
+    /// Successful response with `data` of type `T`
+    fn json<T: JsonSchema>(&mut self) -> Value {
+        let data = self.schema::<T>();
+        envelope(data)
+    }
+
+    /// Query parameters from the fields of `T`
+    fn query<T: JsonSchema>(&mut self) -> Vec<Value> {
+        let root = self.gen.root_schema_for::<T>();
+        let Some(object) = root.schema.object else {
+            return Vec::new();
+        };
+        let ObjectValidation {
+            properties,
+            required,
+            ..
+        } = *object;
+
+        properties
+            .into_iter()
+            .map(|(name, schema)| {
+                let mut schema = serde_json::to_value(schema).unwrap_or_default();
+                let description = schema
+                    .as_object_mut()
+                    .and_then(|schema| schema.remove("description"));
+                let mut parameter = json!({
+                    "name": name,
+                    "in": "query",
+                    "required": required.contains(&name),
+                    "schema": schema,
+                });
+                if let Some(description) = description {
+                    parameter["description"] = description;
+                }
+                parameter
+            })
+            .collect()
+    }
+
+    fn into_components(mut self) -> Value {
+        let mut schemas: Map<String, Value> = self
+            .gen
+            .take_definitions()
+            .into_iter()
+            .map(|(name, schema)| (name, serde_json::to_value(schema).unwrap_or_default()))
+            .collect();
+        schemas.insert(
+            "ErrorResponse".to_string(),
+            json!({
+                "type": "object",
+                "required": ["success", "error"],
+                "properties": {
+                    "success": { "type": "boolean", "enum": [false] },
+                    "error": { "type": "string" }
+                }
+            }),
+        );
+        json!({ "schemas": schemas })
+    }
+}
+
+/// The `{ success, data, error }` wrapper every JSON endpoint responds with
+fn envelope(data: Value) -> Value {
+    json!({
+        "description": "Success",
+        "content": {
+            "application/json": {
+                "schema": {
+                    "type": "object",
+                    "required": ["success", "data"],
+                    "properties": {
+                        "success": { "type": "boolean", "enum": [true] },
+                        "data": data
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Successful response whose `data` is an object not described in detail
+fn object(description: &str) -> Value {
+    envelope(json!({ "type": "object", "description": description }))
+}
+
+/// Non-JSON response such as a file, a text format, or a stream
+fn raw(content_type: &str, description: &str) -> Value {
+    json!({
+        "description": description,
+        "content": { content_type: { "schema": { "type": "string" } } }
+    })
+}
+
+/// The operation serving this document, in every mode
+fn document_operation() -> Operation {
+    Operation::get(
+        "/api/v1/openapi.json",
+        "meta",
+        "This OpenAPI document",
+        json!({
+            "description": "OpenAPI 3 document",
+            "content": { "application/json": { "schema": { "type": "object" } } }
+        }),
+    )
+}
+
+/// Read-only operations, matching `explorer_routes`
+fn explorer_operations(s: &mut Schemas) -> Vec<Operation> {
+    let mut operations = vec![
+        document_operation(),
+        Operation::get(
+            "/api/v1/nodes",
+            "nodes",
+            "List all registered nodes",
+            s.json::<Vec<NodeInfoResponse>>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}",
+            "nodes",
+            "Get a node",
+            s.json::<NodeInfoResponse>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/health",
+            "nodes",
+            "Probe a node's RPC without changing its registered status",
+            s.json::<NodeHealthInfo>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/accounts",
+            "accounts",
+            "List a node's accounts with their balances",
+            s.json::<Vec<AccountInfo>>(),
+        )
+        .with_query(s.query::<AccountsQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos",
+            "accounts",
+            "List an account's unspent outputs (Bitcoin)",
+            object("Unspent outputs, including whether each is locked"),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/analysis",
+            "accounts",
+            "Analyze the node wallet's outputs (Bitcoin)",
+            object("Wallet analysis"),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/transactions",
+            "transactions",
+            "List recent transactions of the node's accounts",
+            s.json::<Vec<TransactionInfo>>(),
+        )
+        .with_query(s.query::<TransactionsQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/transactions/search",
+            "transactions",
+            "Search transactions by address, amount, and time",
+            s.json::<Vec<TransactionSearchResult>>(),
+        )
+        .with_query(s.query::<TransactionSearchQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/transactions/{signature}",
+            "transactions",
+            "Get a transaction with its balance changes",
+            s.json::<TransactionDetailInfo>(),
+        ),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/decode",
+            "transactions",
+            "Decode a raw transaction, script, or message",
+            object("Decoded payload"),
+        )
+        .with_body(s.schema::<DecodeRequest>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/activity",
+            "nodes",
+            "Activity timeline in time buckets",
+            s.json::<Vec<ActivityBucket>>(),
+        )
+        .with_query(s.query::<ActivityQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/events/stream",
+            "events",
+            "Stream the node's events",
+            raw("text/event-stream", "Server-sent events"),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/headers",
+            "blocks",
+            "Long-poll for new block headers",
+            s.json::<Vec<BlockHeaderInfo>>(),
+        )
+        .with_query(s.query::<BlockHeadersQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/filters/{height}",
+            "blocks",
+            "Get a compact block filter (Bitcoin)",
+            s.json::<BlockFilterInfo>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/blocks",
+            "blocks",
+            "List recent blocks, newest first (Bitcoin, Solana)",
+            s.json::<Vec<BlockInfo>>(),
+        )
+        .with_query(s.query::<BlocksQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/blocks/{block}",
+            "blocks",
+            "Get a block and its transaction IDs by height or hash, or by slot (Bitcoin, Solana)",
+            s.json::<BlockInfo>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/balances",
+            "tokens",
+            "Token balances of the node's accounts (Solana)",
+            s.json::<Vec<TokenBalance>>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/pay/{reference}",
+            "payments",
+            "Find a Solana Pay payment by its reference key (Solana)",
+            s.json::<PaymentInfo>(),
+        )
+        .with_query(s.query::<PaymentQuery>()),
+    ];
+    operations.extend(event_operations(s));
+    operations
+}
+
+/// Event feeds, matching `event_routes`
+fn event_operations(s: &mut Schemas) -> Vec<Operation> {
+    vec![
+        Operation::get(
+            "/api/v1/events",
+            "events",
+            "Long-poll for events",
+            object("Events after the cursor, with the next cursor"),
+        )
+        .with_query(s.query::<EventsQuery>()),
+        Operation::get(
+            "/api/v1/ws",
+            "events",
+            "Event stream over a WebSocket",
+            json!({ "description": "Switching to the WebSocket protocol" }),
+        )
+        .with_query(s.query::<WsEventsQuery>()),
+    ]
+}
+
+/// Operations of mock mode, matching `mock::routes`
+fn mock_operations(s: &mut Schemas) -> Vec<Operation> {
+    let mut operations = vec![
+        document_operation(),
+        Operation::get(
+            "/api/v1/nodes",
+            "nodes",
+            "List the mock nodes",
+            s.json::<Vec<NodeInfoResponse>>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}",
+            "nodes",
+            "Get a mock node",
+            s.json::<NodeInfoResponse>(),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/accounts",
+            "accounts",
+            "List a mock node's accounts",
+            s.json::<Vec<AccountInfo>>(),
+        )
+        .with_query(s.query::<AccountsQuery>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/fund",
+            "accounts",
+            "Add funds to an address",
+            s.json::<FundResponse>(),
+        )
+        .with_body(s.schema::<FundAccountRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/transfer",
+            "accounts",
+            "Move funds between addresses",
+            s.json::<TransferResponse>(),
+        )
+        .with_body(s.schema::<TransferRequest>()),
+        Operation::post(
+            "/api/v1/health",
+            "nodes",
+            "Count the running mock nodes",
+            s.json::<HealthCheckResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/mock/faults",
+            "mock",
+            "Add latency or failures to later calls",
+            s.json::<MockFaultsRequest>(),
+        )
+        .with_body(s.schema::<MockFaultsRequest>()),
+    ];
+    operations.extend(event_operations(s));
+    operations
+}
+
+/// Operations that change state or expose configuration, matching
+/// `control_routes`
+fn control_operations(s: &mut Schemas) -> Vec<Operation> {
+    vec![
+        Operation::get(
+            "/api/v1/nodes/{node_id}/rpc-stats",
+            "nodes",
+            "RPC call statistics per endpoint",
+            object("Call counts and latencies by RPC method"),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/logs/download",
+            "logs",
+            "Download the node's logs",
+            raw("application/gzip", "Compressed log archive"),
+        ),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/logs/tail",
+            "logs",
+            "The end of the node's log, optionally followed as SSE",
+            s.json::<LogTailResponse>(),
+        )
+        .with_query(s.query::<LogTailQuery>()),
+        Operation::get(
+            "/api/v1/nodes/{node_id}/logs/instance",
+            "logs",
+            "Events Chain Forge logged for the node, filtered by level",
+            s.json::<InstanceLogResponse>(),
+        )
+        .with_query(s.query::<InstanceLogQuery>()),
+        Operation::post(
+            "/api/v1/health",
+            "nodes",
+            "Check every registered node",
+            s.json::<HealthCheckResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/nodes/health",
+            "nodes",
+            "Check every registered node, with a result per node",
+            s.json::<GroupOperationResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/nodes",
+            "nodes",
+            "Start a node as a background job",
+            s.json::<StartNodeResponse>(),
+        )
+        .with_body(s.schema::<StartNodeRequest>()),
+        Operation::delete(
+            "/api/v1/nodes/{node_id}",
+            "nodes",
+            "Stop a node",
+            s.json::<StopNodeResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/nodes/stop-all",
+            "nodes",
+            "Stop every running node, optionally of one chain",
+            s.json::<GroupOperationResponse>(),
+        )
+        .with_query(s.query::<StopAllQuery>()),
+        Operation::post(
+            "/api/v1/environments/{name}/restart",
+            "nodes",
+            "Restart every node sharing an instance ID",
+            s.json::<GroupOperationResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/fund",
+            "accounts",
+            "Fund an address",
+            s.json::<FundResponse>(),
+        )
+        .with_body(s.schema::<FundAccountRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/fund/bulk",
+            "accounts",
+            "Fund several addresses as a background job",
+            s.json::<Job>(),
+        )
+        .with_body(s.schema::<BulkFundRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/transfer",
+            "accounts",
+            "Transfer funds between addresses",
+            s.json::<TransferResponse>(),
+        )
+        .with_body(s.schema::<TransferRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/mine",
+            "nodes",
+            "Mine blocks (Bitcoin)",
+            s.json::<MineResponse>(),
+        )
+        .with_body(s.schema::<MineRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/snapshots",
+            "nodes",
+            "Take a ledger snapshot as a background job (Solana)",
+            s.json::<Job>(),
+        )
+        .with_body(s.schema::<SnapshotRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/warp",
+            "nodes",
+            "Warp the validator to a later slot as a background job (Solana)",
+            s.json::<Job>(),
+        )
+        .with_body(s.schema::<WarpRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/rescan",
+            "accounts",
+            "Rescan the wallet as a background job (Bitcoin)",
+            s.json::<Job>(),
+        )
+        .with_body(s.schema::<RescanRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos/lock",
+            "accounts",
+            "Lock an account's outputs (Bitcoin)",
+            object("The account's unspent outputs"),
+        )
+        .with_body(s.schema::<UtxoLockRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos/unlock",
+            "accounts",
+            "Unlock an account's outputs (Bitcoin)",
+            object("The account's unspent outputs"),
+        )
+        .with_body(s.schema::<UtxoLockRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/tokens",
+            "tokens",
+            "Create an SPL token mint (Solana)",
+            s.json::<TokenMintInfo>(),
+        )
+        .with_body(s.schema::<CreateTokenRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/mint",
+            "tokens",
+            "Mint tokens (Solana)",
+            s.json::<Vec<TokenTransferInfo>>(),
+        )
+        .with_body(s.schema::<MintTokensRequest>()),
+        Operation::post(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/transfer",
+            "tokens",
+            "Transfer tokens (Solana)",
+            s.json::<TokenTransferInfo>(),
+        )
+        .with_body(s.schema::<TransferTokensRequest>()),
+        Operation::get("/api/v1/jobs", "jobs", "List jobs", s.json::<Vec<Job>>()),
+        Operation::get("/api/v1/jobs/{id}", "jobs", "Get a job", s.json::<Job>()),
+        Operation::delete("/api/v1/jobs/{id}", "jobs", "Cancel a job", s.json::<Job>()),
+        Operation::get(
+            "/api/v1/config/profiles",
+            "config",
+            "List profiles from chain-forge.toml",
+            s.json::<ProfilesResponse>(),
+        ),
+        Operation::put(
+            "/api/v1/config/profiles",
+            "config",
+            "Create or replace a profile",
+            s.json::<ProfilesResponse>(),
+        )
+        .with_body(s.schema::<UpdateProfileRequest>()),
+        Operation::get(
+            "/metrics",
+            "meta",
+            "Prometheus metrics",
+            raw("text/plain", "Metrics in the Prometheus text format"),
+        ),
+        Operation::post(
+            "/api/v1/admin/reload",
+            "config",
+            "Reload settings from chain-forge.toml",
+            s.json::<ReloadResponse>(),
+        ),
+        Operation::post(
+            "/api/v1/registry/cleanup",
+            "nodes",
+            "Remove stopped nodes from the registry",
+            s.json::<CleanupResponse>(),
+        ),
+    ]
+}
+
+/// The OpenAPI document for the endpoints served in `mode`
+pub fn document(mode: ServerMode) -> Value {
+    let mut schemas = Schemas::new();
+    let operations = match mode {
+        ServerMode::Full => {
+            let mut operations = explorer_operations(&mut schemas);
+            operations.extend(control_operations(&mut schemas));
+            operations
+        }
+        ServerMode::Explorer => explorer_operations(&mut schemas),
+        ServerMode::Mock => mock_operations(&mut schemas),
+    };
+
+    let mut paths = Map::new();
+    for operation in &operations {
+        let item = paths
+            .entry(operation.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[operation.method] = operation.to_json();
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Chain Forge API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Monitor and control local blockchain nodes managed by Chain Forge."
+        },
+        "paths": paths,
+        "components": schemas.into_components(),
+    })
+}
+
+/// Swagger UI for the document, loaded from a CDN
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI)
+}
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Chain Forge API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("$ref", Value::String(r)) => found.push(r.clone()),
+                        _ => refs(value, found),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter().for_each(|value| refs(value, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_explorer_document_is_read_only() {
+        let explorer = document(ServerMode::Explorer);
+        assert!(explorer["paths"]["/api/v1/nodes"]["get"].is_object());
+        assert!(explorer["paths"]["/api/v1/nodes"]["post"].is_null());
+        assert!(explorer["paths"]["/api/v1/jobs"].is_null());
+
+        let full = document(ServerMode::Full);
+        assert!(full["paths"]["/api/v1/nodes"]["post"].is_object());
+        assert!(full["paths"]["/api/v1/jobs/{id}"]["delete"].is_object());
+
+        let mock = document(ServerMode::Mock);
+        assert!(mock["paths"]["/api/v1/mock/faults"]["post"].is_object());
+        assert!(mock["paths"]["/api/v1/ws"]["get"].is_object());
+        assert!(mock["paths"]["/api/v1/jobs"].is_null());
+    }
+
+    #[test]
+    fn test_path_parameters_declared() {
+        let doc = document(ServerMode::Full);
+        for (path, item) in doc["paths"].as_object().unwrap() {
+            for (method, operation) in item.as_object().unwrap() {
+                for name in path_params(path) {
+                    let declared = operation["parameters"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .any(|p| p["name"] == name && p["in"] == "path");
+                    assert!(declared, "{} {} doesn't declare {}", method, path, name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_refs_resolve() {
+        let doc = document(ServerMode::Full);
+        let mut found = Vec::new();
+        refs(&doc, &mut found);
+        assert!(!found.is_empty());
+
+        for r in found {
+            let name = r.strip_prefix("#/components/schemas/").unwrap();
+            assert!(
+                doc["components"]["schemas"][name].is_object(),
+                "unresolved {}",
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_parameters() {
+        let doc = document(ServerMode::Explorer);
+        let params = doc["paths"]["/api/v1/nodes/{node_id}/accounts"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        let limit = params.iter().find(|p| p["name"] == "limit").unwrap();
+        assert_eq!(limit["in"], "query");
+        assert_eq!(limit["required"], false);
+        assert!(limit["description"].as_str().unwrap().contains("at most"));
+    }
+
+    #[test]
+    fn test_operation_id() {
+        assert_eq!(
+            operation_id("get", "/api/v1/nodes/{node_id}/accounts"),
+            "get_nodes_node_id_accounts"
+        );
+        assert_eq!(operation_id("get", "/metrics"), "get_metrics");
+    }
+}
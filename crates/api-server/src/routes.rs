@@ -1,36 +1,245 @@
 //! Route definitions for the Chain Forge REST API.
 
 use axum::{
+    extract::FromRef,
     routing::{delete, get, post},
-    Router,
+    Json, Router,
 };
+use chain_forge_events::EventBus;
+use clap::ValueEnum;
+use std::sync::Arc;
 
 use crate::handlers;
+use crate::jobs::JobStore;
+use crate::mock::{self, MockNodes};
+use crate::openapi;
+use crate::settings::LiveSettings;
+use crate::supervisor::Supervisor;
 
-/// Create the API router with all routes
-pub fn create_routes() -> Router {
+/// State shared by all handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub events: Arc<EventBus>,
+    pub jobs: Arc<JobStore>,
+    pub settings: Arc<LiveSettings>,
+    pub supervisor: Arc<Supervisor>,
+}
+
+impl FromRef<AppState> for Arc<EventBus> {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<JobStore> {
+    fn from_ref(state: &AppState) -> Self {
+        state.jobs.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<LiveSettings> {
+    fn from_ref(state: &AppState) -> Self {
+        state.settings.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Supervisor> {
+    fn from_ref(state: &AppState) -> Self {
+        state.supervisor.clone()
+    }
+}
+
+/// Which endpoints the server exposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ServerMode {
+    /// Every endpoint, including node control, funding, and administration
+    #[default]
+    Full,
+    /// Read-only explorer endpoints only: nodes, blocks, transactions, and
+    /// balances, with no secrets and no way to change anything
+    Explorer,
+    /// Made-up nodes kept in memory, for building the dashboard without any
+    /// node binaries installed
+    Mock,
+}
+
+/// Create the API router with the routes for `mode`
+pub fn create_routes(state: AppState, mode: ServerMode) -> Router {
+    let router = match mode {
+        ServerMode::Full => explorer_routes().merge(control_routes()),
+        ServerMode::Explorer => explorer_routes(),
+        ServerMode::Mock => mock::routes(Arc::new(MockNodes::start())).merge(event_routes()),
+    };
+
+    // The API description only covers the endpoints this mode serves
+    let spec = openapi::document(mode);
+    router
+        .route(
+            "/api/v1/openapi.json",
+            get(move || async move { Json(spec) }),
+        )
+        .route("/docs", get(openapi::swagger_ui))
+        .with_state(state)
+}
+
+/// Read-only routes that are safe to share outside the team
+fn explorer_routes() -> Router<AppState> {
     Router::new()
         // Node listing and info
         .route("/api/v1/nodes", get(handlers::list_nodes))
         .route("/api/v1/nodes/{node_id}", get(handlers::get_node))
+        .route(
+            "/api/v1/nodes/{node_id}/health",
+            get(handlers::get_node_health),
+        )
         .route(
             "/api/v1/nodes/{node_id}/accounts",
             get(handlers::get_node_accounts),
         )
+        .route(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos",
+            get(handlers::get_account_utxos),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/analysis",
+            get(handlers::get_wallet_analysis),
+        )
         .route(
             "/api/v1/nodes/{node_id}/transactions",
             get(handlers::get_node_transactions),
         )
+        .route(
+            "/api/v1/nodes/{node_id}/transactions/search",
+            get(handlers::search_node_transactions),
+        )
         .route(
             "/api/v1/nodes/{node_id}/transactions/{signature}",
             get(handlers::get_transaction_detail),
         )
+        .route(
+            "/api/v1/nodes/{node_id}/decode",
+            post(handlers::decode_payload),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/activity",
+            get(handlers::get_node_activity),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/events/stream",
+            get(handlers::stream_node_events),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/headers",
+            get(handlers::get_block_headers),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/filters/{height}",
+            get(handlers::get_block_filter),
+        )
+        .route("/api/v1/nodes/{node_id}/blocks", get(handlers::get_blocks))
+        .route(
+            "/api/v1/nodes/{node_id}/blocks/{block}",
+            get(handlers::get_block),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/balances",
+            get(handlers::get_token_balances),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/pay/{reference}",
+            get(handlers::find_payment),
+        )
+        .merge(event_routes())
+}
+
+/// Event feeds, which only read the event bus
+fn event_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/events", get(handlers::get_events))
+        .route("/api/v1/ws", get(handlers::ws_events))
+}
+
+/// Routes that control nodes, move funds, or expose configuration
+fn control_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/v1/nodes/{node_id}/rpc-stats",
+            get(handlers::get_rpc_stats),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/logs/download",
+            get(handlers::download_node_logs),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/logs/tail",
+            get(handlers::tail_node_logs),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/logs/instance",
+            get(handlers::get_instance_log),
+        )
         // Health check
         .route("/api/v1/health", post(handlers::health_check))
+        .route("/api/v1/nodes/health", post(handlers::check_nodes_health))
         // Node control
         .route("/api/v1/nodes", post(handlers::start_node))
         .route("/api/v1/nodes/{node_id}", delete(handlers::stop_node))
+        .route("/api/v1/nodes/stop-all", post(handlers::stop_all_nodes))
+        .route(
+            "/api/v1/environments/{name}/restart",
+            post(handlers::restart_environment),
+        )
         .route("/api/v1/nodes/{node_id}/fund", post(handlers::fund_account))
+        .route(
+            "/api/v1/nodes/{node_id}/fund/bulk",
+            post(handlers::bulk_fund),
+        )
+        .route("/api/v1/nodes/{node_id}/transfer", post(handlers::transfer))
+        .route("/api/v1/nodes/{node_id}/mine", post(handlers::mine_blocks))
+        .route(
+            "/api/v1/nodes/{node_id}/snapshots",
+            post(handlers::create_snapshot),
+        )
+        .route("/api/v1/nodes/{node_id}/warp", post(handlers::warp_node))
+        .route(
+            "/api/v1/nodes/{node_id}/rescan",
+            post(handlers::rescan_wallet),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos/lock",
+            post(handlers::lock_account_utxos),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/accounts/{address}/utxos/unlock",
+            post(handlers::unlock_account_utxos),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/tokens",
+            post(handlers::create_token),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/mint",
+            post(handlers::mint_tokens),
+        )
+        .route(
+            "/api/v1/nodes/{node_id}/tokens/{mint}/transfer",
+            post(handlers::transfer_tokens),
+        )
+        // Jobs
+        .route("/api/v1/jobs", get(handlers::list_jobs))
+        .route(
+            "/api/v1/jobs/{id}",
+            get(handlers::get_job).delete(handlers::cancel_job),
+        )
+        // Config profiles
+        .route(
+            "/api/v1/config/profiles",
+            get(handlers::get_profiles).put(handlers::update_profile),
+        )
+        // Metrics
+        .route("/metrics", get(handlers::prometheus_metrics))
+        // Administration
+        .route("/api/v1/admin/reload", post(handlers::reload_settings))
         // Registry management
         .route("/api/v1/registry/cleanup", post(handlers::cleanup_registry))
 }
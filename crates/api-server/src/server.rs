@@ -1,38 +1,85 @@
 //! HTTP server setup for the Chain Forge REST API.
 
+use axum::middleware;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
+use tracing::info;
 
-use crate::routes::create_routes;
+use crate::debug_log::{self, DebugLogConfig};
+use crate::jobs::JobStore;
+use crate::routes::{create_routes, AppState, ServerMode};
+use crate::settings::LiveSettings;
+use crate::supervisor::Supervisor;
+use crate::watcher;
 
 /// Start the API server on the specified port
-pub async fn start_server(port: u16) -> eyre::Result<()> {
+pub async fn start_server(
+    port: u16,
+    debug_log: DebugLogConfig,
+    mode: ServerMode,
+) -> eyre::Result<()> {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let app = create_routes().layer(cors);
+    // Flags plus the [api] config section; re-read on SIGHUP or
+    // POST /api/v1/admin/reload
+    let settings = Arc::new(LiveSettings::load(debug_log));
+    #[cfg(unix)]
+    crate::settings::spawn_sighup_reload(settings.clone());
+
+    // Share the process-wide bus, so events published by handlers reach
+    // long-poll and SSE clients alongside what the watcher observes
+    let events = chain_forge_events::global();
+    // Mock nodes aren't in the registry, so there is nothing to watch
+    if mode != ServerMode::Mock {
+        watcher::spawn_watcher(events.clone(), settings.clone());
+    }
+
+    // RPC clients made by handlers and the watcher record into the global
+    // metrics; report slow calls as they happen
+    chain_forge_common::rpc_metrics::global().set_log_slow_calls(true);
+
+    for route in &settings.current().debug_log.routes {
+        info!(%route, "Logging request/response bodies");
+    }
+
+    let state = AppState {
+        events,
+        jobs: Arc::new(JobStore::open_default()),
+        settings: settings.clone(),
+        supervisor: Arc::new(Supervisor::new()),
+    };
+
+    // Always installed, since a reload can turn body logging on
+    let app = create_routes(state, mode)
+        .layer(middleware::from_fn_with_state(
+            settings,
+            debug_log::log_bodies,
+        ))
+        .layer(cors);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    println!(
-        "🚀 Chain Forge API Server starting on http://localhost:{}",
-        port
+    info!(
+        url = %format!("http://localhost:{}", port),
+        ?mode,
+        "Chain Forge API Server starting"
+    );
+    // The endpoint list lives in the OpenAPI document for this mode
+    info!(
+        docs = %format!("http://localhost:{}/docs", port),
+        "API documentation"
     );
-    println!("   API endpoints:");
-    println!("   - GET    /api/v1/nodes                   - List all nodes");
-    println!("   - GET    /api/v1/nodes/{{node_id}}         - Get specific node");
-    println!("   - GET    /api/v1/nodes/{{node_id}}/accounts     - Get node accounts");
-    println!("   - GET    /api/v1/nodes/{{node_id}}/transactions - Get node transactions");
-    println!("   - POST   /api/v1/health                        - Health check all nodes");
-    println!("   - POST   /api/v1/nodes                   - Start a new node");
-    println!("   - DELETE /api/v1/nodes/{{node_id}}         - Stop a node");
-    println!("   - POST   /api/v1/nodes/{{node_id}}/fund    - Fund an account");
-    println!("   - POST   /api/v1/registry/cleanup         - Remove non-running nodes");
-    println!();
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // Admin endpoints check the peer address when no debug token is set
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
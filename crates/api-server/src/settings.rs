@@ -0,0 +1,200 @@
+//! Server settings that can change without a restart.
+//!
+//! Settings come from `cf-api` flags and the `[api]` section of
+//! `chain-forge.toml`. The config file is re-read on SIGHUP or
+//! `POST /api/v1/admin/reload`; flags always apply on top of it. A reload that
+//! fails keeps the current settings.
+
+use chain_forge_common::{ChainError, Result};
+use chain_forge_config::{ApiConfig, Config};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::debug_log::DebugLogConfig;
+
+/// Settings in effect for the running server
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub debug_log: DebugLogConfig,
+    /// How often the watcher polls nodes
+    pub watch_interval: Duration,
+}
+
+impl Settings {
+    /// Combine command-line flags with the `[api]` config section
+    ///
+    /// Debug routes from both are logged; the `--debug-token` flag wins over
+    /// `debug_token` in the file.
+    pub fn resolve(flags: &DebugLogConfig, api: Option<&ApiConfig>) -> Result<Self> {
+        let api = api.cloned().unwrap_or_default();
+        if api.watch_interval_secs == 0 {
+            return Err(ChainError::Config(
+                "api.watch_interval_secs must be at least 1".to_string(),
+            ));
+        }
+
+        let mut routes = flags.routes.clone();
+        for route in api.debug_routes {
+            if !routes.contains(&route) {
+                routes.push(route);
+            }
+        }
+
+        Ok(Self {
+            debug_log: DebugLogConfig {
+                routes,
+                token: flags.token.clone().or(api.debug_token),
+            },
+            watch_interval: Duration::from_secs(api.watch_interval_secs),
+        })
+    }
+}
+
+/// Current settings, shared by handlers, middleware, and the watcher
+#[derive(Debug)]
+pub struct LiveSettings {
+    flags: DebugLogConfig,
+    current: RwLock<Arc<Settings>>,
+}
+
+impl LiveSettings {
+    /// Load settings from the flags and the config file
+    ///
+    /// A config file that can't be read is reported and ignored, so the server
+    /// still starts with the flags alone.
+    pub fn load(flags: DebugLogConfig) -> Self {
+        let settings = Config::load()
+            .and_then(|config| Settings::resolve(&flags, config.api.as_ref()))
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Ignoring [api] config");
+                Settings::resolve(&flags, None).expect("default settings are valid")
+            });
+        Self {
+            flags,
+            current: RwLock::new(Arc::new(settings)),
+        }
+    }
+
+    /// The settings in effect right now
+    pub fn current(&self) -> Arc<Settings> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Re-read the config file and apply it
+    pub fn reload(&self) -> Result<Arc<Settings>> {
+        self.apply(&Config::load()?)
+    }
+
+    /// Apply `config` on top of the flags
+    pub fn apply(&self, config: &Config) -> Result<Arc<Settings>> {
+        let settings = Arc::new(Settings::resolve(&self.flags, config.api.as_ref())?);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = settings.clone();
+        Ok(settings)
+    }
+}
+
+/// Reload settings whenever the process receives SIGHUP
+#[cfg(unix)]
+pub fn spawn_sighup_reload(settings: Arc<LiveSettings>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                warn!(error = %e, "Failed to listen for SIGHUP");
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            match settings.reload() {
+                Ok(_) => info!("Reloaded configuration"),
+                Err(e) => warn!(
+                    error = %e,
+                    "Failed to reload configuration, keeping current settings"
+                ),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags() -> DebugLogConfig {
+        DebugLogConfig {
+            routes: vec!["/api/v1/jobs".to_string()],
+            token: None,
+        }
+    }
+
+    fn live() -> LiveSettings {
+        LiveSettings {
+            flags: flags(),
+            current: RwLock::new(Arc::new(Settings::resolve(&flags(), None).unwrap())),
+        }
+    }
+
+    #[test]
+    fn test_resolve_merges_flags_and_config() {
+        let api = ApiConfig {
+            debug_routes: vec!["/api/v1/jobs".to_string(), "/api/v1/nodes".to_string()],
+            debug_token: Some("from-file".to_string()),
+            watch_interval_secs: 5,
+        };
+        let settings = Settings::resolve(&flags(), Some(&api)).unwrap();
+        assert_eq!(
+            settings.debug_log.routes,
+            vec!["/api/v1/jobs", "/api/v1/nodes"]
+        );
+        assert_eq!(settings.debug_log.token.as_deref(), Some("from-file"));
+        assert_eq!(settings.watch_interval, Duration::from_secs(5));
+
+        // The flag token wins
+        let with_token = DebugLogConfig {
+            token: Some("from-flag".to_string()),
+            ..flags()
+        };
+        let settings = Settings::resolve(&with_token, Some(&api)).unwrap();
+        assert_eq!(settings.debug_log.token.as_deref(), Some("from-flag"));
+    }
+
+    #[test]
+    fn test_apply_replaces_settings() {
+        let settings = live();
+        assert_eq!(settings.current().watch_interval, Duration::from_secs(2));
+
+        let config = Config {
+            api: Some(ApiConfig {
+                watch_interval_secs: 10,
+                ..ApiConfig::default()
+            }),
+            ..Config::default()
+        };
+        settings.apply(&config).unwrap();
+        assert_eq!(settings.current().watch_interval, Duration::from_secs(10));
+
+        // Removing the section goes back to the defaults
+        settings.apply(&Config::default()).unwrap();
+        assert_eq!(settings.current().watch_interval, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_invalid_config_keeps_current_settings() {
+        let settings = live();
+        let config = Config {
+            api: Some(ApiConfig {
+                watch_interval_secs: 0,
+                ..ApiConfig::default()
+            }),
+            ..Config::default()
+        };
+        assert!(settings.apply(&config).is_err());
+        assert_eq!(settings.current().watch_interval, Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,295 @@
+//! Node processes started through the API.
+//!
+//! `POST /api/v1/nodes` runs the chain CLI (`cf-solana start`,
+//! `cf-bitcoin start`, `cf-ethereum start`) as a child of the API server,
+//! the same way `cf pool fill` does. The CLI's provider owns the node
+//! process, registers it once it is funded, and cleans up after it when
+//! interrupted. Stopping sends the CLI SIGINT, as Ctrl+C would, and falls
+//! back to SIGKILL for the CLI and the node it started if it doesn't exit in
+//! time.
+//!
+//! Each CLI runs in its own process group, so nodes outlive an API server
+//! restart and can still be stopped by the PID recorded in the registry.
+//! Output goes to `~/.chain-forge/api-logs/<chain>-<instance>.log`.
+
+use chain_forge_common::{NodeRegistry, NodeStatus};
+use chain_forge_config::Config;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+/// How long a node's CLI gets to shut down cleanly after SIGINT
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for processes to go away after SIGKILL
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A chain CLI invocation that starts a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl std::fmt::Display for NodeCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            if arg.contains(' ') {
+                write!(f, " \"{}\"", arg)?;
+            } else {
+                write!(f, " {}", arg)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Chain CLIs run by the API server, keyed by node ID
+pub struct Supervisor {
+    children: Arc<Mutex<HashMap<String, u32>>>,
+    log_dir: PathBuf,
+}
+
+impl Supervisor {
+    /// Create a supervisor logging to the default directory
+    pub fn new() -> Self {
+        Self::with_log_dir(Config::data_dir().join("api-logs"))
+    }
+
+    /// Create a supervisor logging to a custom directory (mainly for testing)
+    pub fn with_log_dir(log_dir: PathBuf) -> Self {
+        Self {
+            children: Arc::new(Mutex::new(HashMap::new())),
+            log_dir,
+        }
+    }
+
+    /// Where the output of a node's CLI is written
+    pub fn log_file(&self, node_id: &str) -> PathBuf {
+        self.log_dir
+            .join(format!("{}.log", node_id.replace(':', "-")))
+    }
+
+    /// PID of the CLI running a node, if this server started it
+    pub fn pid(&self, node_id: &str) -> Option<u32> {
+        self.children.lock().unwrap().get(node_id).copied()
+    }
+
+    /// Run `command` for a node and return the CLI's PID
+    ///
+    /// A background thread reaps the CLI when it exits. A node whose CLI
+    /// died without unregistering it is marked as stopped.
+    pub fn spawn(&self, node_id: &str, command: &NodeCommand) -> Result<u32, String> {
+        let mut children = self.children.lock().unwrap();
+        if children.contains_key(node_id) {
+            return Err(format!("{} is already running", node_id));
+        }
+
+        fs::create_dir_all(&self.log_dir)
+            .map_err(|e| format!("Failed to create {:?}: {}", self.log_dir, e))?;
+        let log_file = self.log_file(node_id);
+        let log = File::create(&log_file)
+            .map_err(|e| format!("Failed to create {:?}: {}", log_file, e))?;
+        let log_err = log
+            .try_clone()
+            .map_err(|e| format!("Failed to open {:?}: {}", log_file, e))?;
+
+        let mut cmd = Command::new(&command.program);
+        cmd.args(&command.args)
+            .stdin(Stdio::null())
+            .stdout(log)
+            .stderr(log_err);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", command.program, e))?;
+        let pid = child.id();
+        children.insert(node_id.to_string(), pid);
+        drop(children);
+
+        let children = self.children.clone();
+        let node_id = node_id.to_string();
+        std::thread::spawn(move || {
+            let _ = child.wait();
+
+            let mut children = children.lock().unwrap();
+            if children.get(&node_id) == Some(&pid) {
+                children.remove(&node_id);
+            }
+            drop(children);
+
+            let registry = NodeRegistry::new();
+            if let Ok(Some(node)) = registry.get(&node_id) {
+                if node.status == NodeStatus::Running && node.pid == Some(pid) {
+                    let _ = registry.update_status(&node_id, NodeStatus::Stopped);
+                }
+            }
+        });
+
+        Ok(pid)
+    }
+
+    /// Whether the CLI with `pid` is still running a node
+    ///
+    /// CLIs this server started are tracked directly, since an exited child
+    /// lingers as a zombie until reaped; others are probed with signal 0.
+    pub fn is_running(&self, node_id: &str, pid: u32) -> bool {
+        match self.pid(node_id) {
+            Some(child) => child == pid,
+            None => signal_process(pid, "-0").is_ok(),
+        }
+    }
+
+    /// Stop a node's CLI, blocking until it exits
+    ///
+    /// Sends SIGINT so the CLI stops the node and unregisters it, then
+    /// SIGKILLs the CLI's process group after [`STOP_TIMEOUT`].
+    pub fn stop(&self, node_id: &str, pid: u32) -> Result<(), String> {
+        if !self.is_running(node_id, pid) {
+            return Ok(());
+        }
+
+        signal_process(pid, "-INT")?;
+        if self.wait_for_exit(node_id, pid, STOP_TIMEOUT) {
+            return Ok(());
+        }
+
+        warn!(
+            node_id,
+            pid,
+            timeout_secs = STOP_TIMEOUT.as_secs(),
+            "Node did not stop in time, killing it"
+        );
+        signal_process_group(pid, "-KILL")?;
+        if self.wait_for_exit(node_id, pid, KILL_TIMEOUT) {
+            return Ok(());
+        }
+
+        Err(format!("{} (pid {}) is still running", node_id, pid))
+    }
+
+    fn wait_for_exit(&self, node_id: &str, pid: u32, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if !self.is_running(node_id, pid) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        !self.is_running(node_id, pid)
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(unix)]
+fn signal_process(pid: u32, signal: &str) -> Result<(), String> {
+    send_signal(signal, &pid.to_string())
+}
+
+/// Signal every process in the group led by `pid`
+#[cfg(unix)]
+fn signal_process_group(pid: u32, signal: &str) -> Result<(), String> {
+    send_signal(signal, &format!("-{}", pid))
+}
+
+#[cfg(unix)]
+fn send_signal(signal: &str, target: &str) -> Result<(), String> {
+    let status = Command::new("kill")
+        .args([signal, "--", target])
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run kill: {}", e))?;
+    if !status.success() {
+        return Err(format!("Failed to send {} to {}", signal, target));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn signal_process(pid: u32, _signal: &str) -> Result<(), String> {
+    Err(format!(
+        "Stopping nodes is only supported on Unix; stop pid {} manually",
+        pid
+    ))
+}
+
+#[cfg(not(unix))]
+fn signal_process_group(pid: u32, signal: &str) -> Result<(), String> {
+    signal_process(pid, signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_command_display() {
+        let command = NodeCommand {
+            program: "cf-solana".to_string(),
+            args: vec![
+                "start".to_string(),
+                "--name".to_string(),
+                "My Node".to_string(),
+            ],
+        };
+        assert_eq!(command.to_string(), "cf-solana start --name \"My Node\"");
+    }
+
+    #[test]
+    fn test_log_file() {
+        let dir = TempDir::new().unwrap();
+        let supervisor = Supervisor::with_log_dir(dir.path().to_path_buf());
+        assert_eq!(
+            supervisor.log_file("bitcoin:dev"),
+            dir.path().join("bitcoin-dev.log")
+        );
+    }
+
+    #[test]
+    fn test_spawn_missing_program() {
+        let dir = TempDir::new().unwrap();
+        let supervisor = Supervisor::with_log_dir(dir.path().to_path_buf());
+        let command = NodeCommand {
+            program: "cf-does-not-exist".to_string(),
+            args: vec![],
+        };
+
+        assert!(supervisor.spawn("solana:test", &command).is_err());
+        assert!(supervisor.pid("solana:test").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_and_stop() {
+        let dir = TempDir::new().unwrap();
+        let supervisor = Supervisor::with_log_dir(dir.path().to_path_buf());
+        let command = NodeCommand {
+            program: "sleep".to_string(),
+            args: vec!["30".to_string()],
+        };
+
+        let pid = supervisor.spawn("solana:sleepy", &command).unwrap();
+        assert_eq!(supervisor.pid("solana:sleepy"), Some(pid));
+        assert!(supervisor.spawn("solana:sleepy", &command).is_err());
+
+        supervisor.stop("solana:sleepy", pid).unwrap();
+        assert!(supervisor.pid("solana:sleepy").is_none());
+        assert!(!supervisor.is_running("solana:sleepy", pid));
+    }
+}
@@ -0,0 +1,312 @@
+//! Background watcher that turns node state into events.
+//!
+//! Registered nodes are polled for liveness and height, and status changes,
+//! new blocks, and newly confirmed transactions involving node accounts are
+//! published to the event bus as observations. Actions taken through the API
+//! publish their own events where they happen.
+
+use chain_forge_bitcoin_accounts::AccountsStorage as BitcoinAccountsStorage;
+use chain_forge_bitcoin_core::{BitcoinConfig, InstanceInfo as BitcoinInstanceInfo};
+use chain_forge_bitcoin_rpc::{BitcoinRpcClient, EsploraClient};
+use chain_forge_common::{ChainType, NodeInfo, NodeRegistry, NodeStatus};
+use chain_forge_ethereum_rpc::EthereumRpcClient;
+use chain_forge_events::{ChainEvent, EventBus};
+use chain_forge_solana_accounts::AccountsStorage as SolanaAccountsStorage;
+use chain_forge_solana_core::SolanaConfig;
+use chain_forge_solana_rpc::SolanaRpcClient;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::settings::LiveSettings;
+
+/// Recent transactions fetched per account (Solana) or from the wallet
+/// (Bitcoin) each time a node's height moves
+const RECENT_TRANSACTIONS: usize = 20;
+
+/// Last observed state of a node
+#[derive(Debug, Clone, PartialEq)]
+struct NodeState {
+    chain: ChainType,
+    instance_id: String,
+    status: NodeStatus,
+    height: Option<u64>,
+    /// Recent confirmed transaction IDs; only fetched when the height moved
+    transactions: Option<Vec<String>>,
+}
+
+/// What the watcher remembers between polls
+#[derive(Default)]
+struct WatchState {
+    nodes: HashMap<String, NodeState>,
+    /// Transaction IDs already seen per node, from the latest fetch
+    transactions: HashMap<String, HashSet<String>>,
+}
+
+/// Poll registered nodes in the background and publish what changed
+///
+/// The poll interval is read from `settings` each time, so reloads apply
+/// from the next poll.
+pub fn spawn_watcher(bus: Arc<EventBus>, settings: Arc<LiveSettings>) {
+    tokio::spawn(async move {
+        let mut state = WatchState::default();
+        loop {
+            let heights: HashMap<String, Option<u64>> = state
+                .nodes
+                .iter()
+                .map(|(id, node)| (id.clone(), node.height))
+                .collect();
+            match tokio::task::spawn_blocking(move || poll_nodes(&heights)).await {
+                Ok(Ok(current)) => publish_changes(&bus, &mut state, current),
+                Ok(Err(e)) => warn!(error = %e, "Failed to read node registry"),
+                Err(e) => warn!(error = %e, "Node watcher failed"),
+            }
+            tokio::time::sleep(settings.current().watch_interval).await;
+        }
+    });
+}
+
+/// Probe every registered node for liveness and height
+///
+/// Recent transactions are fetched only for nodes whose height differs from
+/// `previous_heights`.
+fn poll_nodes(
+    previous_heights: &HashMap<String, Option<u64>>,
+) -> chain_forge_common::Result<HashMap<String, NodeState>> {
+    Ok(NodeRegistry::new()
+        .list()?
+        .into_iter()
+        .map(|node| {
+            let height = probe_height(&node);
+            let status = if height.is_some() {
+                NodeStatus::Running
+            } else {
+                NodeStatus::Stopped
+            };
+            let moved = height.is_some() && previous_heights.get(&node.node_id) != Some(&height);
+            let transactions = if moved {
+                recent_transaction_ids(&node).ok()
+            } else {
+                None
+            };
+            (
+                node.node_id,
+                NodeState {
+                    chain: node.chain,
+                    instance_id: node.instance_id,
+                    status,
+                    height,
+                    transactions,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Current block height (Bitcoin, Ethereum) or slot (Solana), if the node answers
+fn probe_height(node: &NodeInfo) -> Option<u64> {
+    match node.chain {
+        ChainType::Solana => SolanaRpcClient::new(node.rpc_url.clone()).get_slot().ok(),
+        ChainType::Bitcoin => {
+            let info = BitcoinInstanceInfo::load(&node.instance_id).ok()?;
+            if info.network.is_some() {
+                return EsploraClient::new(info.rpc_url).get_tip_height().ok();
+            }
+            BitcoinRpcClient::new(info.rpc_url, info.rpc_user, info.rpc_password)
+                .ok()?
+                .get_block_count()
+                .ok()
+        }
+        ChainType::Ethereum => EthereumRpcClient::new(node.rpc_url.clone())
+            .get_block_number()
+            .ok(),
+    }
+}
+
+/// IDs of recent confirmed transactions involving the node's accounts
+fn recent_transaction_ids(node: &NodeInfo) -> chain_forge_common::Result<Vec<String>> {
+    match node.chain {
+        ChainType::Solana => {
+            let accounts = SolanaAccountsStorage::with_path(
+                SolanaConfig::with_instance(&node.instance_id).accounts_file(),
+            )
+            .load()?;
+            let rpc_client = SolanaRpcClient::new(node.rpc_url.clone());
+            let mut ids = Vec::new();
+            for account in &accounts {
+                for sig in rpc_client
+                    .get_signatures_for_address(&account.public_key, Some(RECENT_TRANSACTIONS))?
+                {
+                    if !ids.contains(&sig.signature) {
+                        ids.push(sig.signature);
+                    }
+                }
+            }
+            Ok(ids)
+        }
+        ChainType::Bitcoin => {
+            let addresses: HashSet<String> = BitcoinAccountsStorage::with_path(
+                BitcoinConfig::with_instance(&node.instance_id).accounts_file(),
+            )
+            .load()?
+            .into_iter()
+            .map(|a| a.address)
+            .collect();
+            let info = BitcoinInstanceInfo::load(&node.instance_id)?;
+            let rpc_client = info.wallet_client()?;
+            let mut ids = Vec::new();
+            for tx in rpc_client.list_transactions(RECENT_TRANSACTIONS)? {
+                if tx.confirmations > 0
+                    && addresses.contains(&tx.address)
+                    && !ids.contains(&tx.txid)
+                {
+                    ids.push(tx.txid);
+                }
+            }
+            Ok(ids)
+        }
+        // anvil has no address index to look transactions up by, and
+        // scanning blocks on every poll would not scale
+        ChainType::Ethereum => Ok(Vec::new()),
+    }
+}
+
+/// Compare a poll against the last one and publish the differences
+fn publish_changes(bus: &EventBus, state: &mut WatchState, current: HashMap<String, NodeState>) {
+    for (node_id, node) in &current {
+        let previous = state.nodes.get(node_id);
+        let observe = |event| bus.observe(node.chain, &node.instance_id, event);
+        if previous.map(|p| p.status) != Some(node.status) {
+            observe(match node.status {
+                NodeStatus::Running => ChainEvent::NodeStarted,
+                _ => ChainEvent::NodeStopped,
+            });
+        }
+        if let (Some(old), Some(new)) = (previous.and_then(|p| p.height), node.height) {
+            if new > old {
+                observe(ChainEvent::BlockMined {
+                    height: Some(new),
+                    count: new - old,
+                });
+            }
+        }
+
+        // The first fetch only records what already exists
+        if let Some(ids) = &node.transactions {
+            let seen = state.transactions.get(node_id);
+            if let Some(seen) = seen {
+                for id in ids.iter().filter(|id| !seen.contains(*id)) {
+                    observe(ChainEvent::TxConfirmed { id: id.clone() });
+                }
+            }
+            state
+                .transactions
+                .insert(node_id.clone(), ids.iter().cloned().collect());
+        }
+    }
+
+    for (node_id, node) in &state.nodes {
+        if !current.contains_key(node_id) {
+            bus.observe(node.chain, &node.instance_id, ChainEvent::NodeRemoved);
+        }
+    }
+    state
+        .transactions
+        .retain(|node_id, _| current.contains_key(node_id));
+    state.nodes = current;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(status: NodeStatus, height: Option<u64>) -> NodeState {
+        NodeState {
+            chain: ChainType::Bitcoin,
+            instance_id: "dev".to_string(),
+            status,
+            height,
+            transactions: None,
+        }
+    }
+
+    #[test]
+    fn test_publish_changes() {
+        let bus = EventBus::new(10);
+        let mut known = WatchState::default();
+
+        // First sighting: status only, no block event
+        let current = HashMap::from([(
+            "bitcoin:dev".to_string(),
+            state(NodeStatus::Running, Some(101)),
+        )]);
+        publish_changes(&bus, &mut known, current);
+        let page = bus.since(0, None);
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].event, ChainEvent::NodeStarted);
+        assert_eq!(page.events[0].node_id, "bitcoin:dev");
+
+        let current = HashMap::from([(
+            "bitcoin:dev".to_string(),
+            state(NodeStatus::Running, Some(107)),
+        )]);
+        publish_changes(&bus, &mut known, current);
+        assert_eq!(
+            bus.since(1, None).events[0].event,
+            ChainEvent::BlockMined {
+                height: Some(107),
+                count: 6
+            }
+        );
+
+        let current =
+            HashMap::from([("bitcoin:dev".to_string(), state(NodeStatus::Stopped, None))]);
+        publish_changes(&bus, &mut known, current);
+        assert_eq!(bus.since(2, None).events[0].event, ChainEvent::NodeStopped);
+
+        publish_changes(&bus, &mut known, HashMap::new());
+        assert_eq!(bus.since(3, None).events[0].event, ChainEvent::NodeRemoved);
+    }
+
+    #[test]
+    fn test_publish_new_transactions() {
+        let bus = EventBus::new(10);
+        let mut known = WatchState::default();
+        let with_transactions = |height: u64, ids: &[&str]| {
+            HashMap::from([(
+                "solana:default".to_string(),
+                NodeState {
+                    chain: ChainType::Solana,
+                    instance_id: "default".to_string(),
+                    status: NodeStatus::Running,
+                    height: Some(height),
+                    transactions: Some(ids.iter().map(|id| id.to_string()).collect()),
+                },
+            )])
+        };
+
+        // Transactions that exist when the node is first seen are not events
+        publish_changes(&bus, &mut known, with_transactions(10, &["a", "b"]));
+        assert_eq!(bus.latest_cursor(), 1);
+
+        publish_changes(&bus, &mut known, with_transactions(12, &["c", "a", "b"]));
+        let events: Vec<ChainEvent> = bus
+            .since(1, None)
+            .events
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ChainEvent::BlockMined {
+                    height: Some(12),
+                    count: 2
+                },
+                ChainEvent::TxConfirmed {
+                    id: "c".to_string()
+                },
+            ]
+        );
+    }
+}
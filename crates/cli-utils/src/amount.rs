@@ -0,0 +1,139 @@
+use clap::Args;
+
+/// Number of decimal places in one BTC (satoshi precision)
+pub const BTC_DECIMALS: usize = 8;
+
+/// Number of decimal places in one SOL (lamport precision)
+pub const SOL_DECIMALS: usize = 9;
+
+/// Number of decimal places shown for ETH (gwei precision)
+///
+/// Wei precision (18 places) is beyond what an `f64` balance can represent.
+pub const ETH_DECIMALS: usize = 9;
+
+/// Shared CLI flags controlling how amounts are rendered
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct AmountArgs {
+    /// Number of decimal places to show (defaults to the chain's native precision)
+    #[arg(long)]
+    pub precision: Option<usize>,
+
+    /// Group the integer part with thousands separators
+    #[arg(long, default_value = "false")]
+    pub thousands_separator: bool,
+}
+
+impl AmountArgs {
+    /// Format an amount, falling back to `default_precision` when no `--precision` is given
+    pub fn format(&self, value: f64, default_precision: usize) -> String {
+        format_amount(
+            value,
+            self.precision.unwrap_or(default_precision),
+            self.thousands_separator,
+        )
+    }
+}
+
+/// Format an amount with a fixed number of decimals
+///
+/// Output is locale-independent: the decimal separator is always `.` and the
+/// optional thousands separator is always `,`.
+pub fn format_amount(value: f64, precision: usize, thousands_separator: bool) -> String {
+    let formatted = format!("{:.*}", precision, value.abs());
+    let (int_part, frac_part) = match formatted.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut out = String::with_capacity(formatted.len() + formatted.len() / 3 + 1);
+
+    // Avoid rendering "-0.00" when a tiny negative value rounds to zero
+    let is_zero = formatted.chars().all(|c| c == '0' || c == '.');
+    if value.is_sign_negative() && !is_zero {
+        out.push('-');
+    }
+
+    if thousands_separator {
+        out.push_str(&group_thousands(int_part));
+    } else {
+        out.push_str(int_part);
+    }
+
+    if let Some(frac_part) = frac_part {
+        out.push('.');
+        out.push_str(frac_part);
+    }
+
+    out
+}
+
+/// Format a BTC amount with satoshi precision
+pub fn format_btc(value: f64) -> String {
+    format_amount(value, BTC_DECIMALS, false)
+}
+
+/// Format a SOL amount with lamport precision
+pub fn format_sol(value: f64) -> String {
+    format_amount(value, SOL_DECIMALS, false)
+}
+
+/// Format an ETH amount with gwei precision
+pub fn format_eth(value: f64) -> String {
+    format_amount(value, ETH_DECIMALS, false)
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_precision() {
+        assert_eq!(format_btc(10.0), "10.00000000");
+        assert_eq!(format_btc(0.00000001), "0.00000001");
+        assert_eq!(format_sol(100.0), "100.000000000");
+        assert_eq!(format_sol(0.000000001), "0.000000001");
+        assert_eq!(format_eth(10000.0), "10000.000000000");
+    }
+
+    #[test]
+    fn test_explicit_precision() {
+        assert_eq!(format_amount(1.23456789, 2, false), "1.23");
+        assert_eq!(format_amount(1.5, 0, false), "2");
+    }
+
+    #[test]
+    fn test_thousands_separator() {
+        assert_eq!(format_amount(1234567.5, 2, true), "1,234,567.50");
+        assert_eq!(format_amount(123.0, 1, true), "123.0");
+        assert_eq!(format_amount(1000.0, 0, true), "1,000");
+    }
+
+    #[test]
+    fn test_negative_values() {
+        assert_eq!(format_amount(-1234.5, 1, true), "-1,234.5");
+        assert_eq!(format_amount(-0.000000001, 2, false), "0.00");
+    }
+
+    #[test]
+    fn test_amount_args_default_precision() {
+        let args = AmountArgs::default();
+        assert_eq!(args.format(1.0, BTC_DECIMALS), "1.00000000");
+
+        let args = AmountArgs {
+            precision: Some(3),
+            thousands_separator: true,
+        };
+        assert_eq!(args.format(12345.0, BTC_DECIMALS), "12,345.000");
+    }
+}
@@ -0,0 +1,216 @@
+//! Running `start` in the background with `--detach`
+//!
+//! The detached node is the same CLI re-run without `--detach`. Its PID is
+//! kept in `cli.pid` in the instance directory, and its output goes to
+//! `<instance>.log` beside that directory, since starting a node clears it.
+//! `stop` signals the PID the way Ctrl+C would, so the node shuts down and
+//! cleans up like one started in a terminal.
+
+use chain_forge_common::{NodeRegistry, NodeStatus};
+use eyre::{bail, Result, WrapErr};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Flag that asks `start` to run in the background
+pub const DETACH_FLAG: &str = "--detach";
+
+/// File in the instance directory holding the detached CLI's PID
+pub const PID_FILE: &str = "cli.pid";
+
+/// Path of the PID file for an instance directory
+pub fn pid_path(instance_dir: &Path) -> PathBuf {
+    instance_dir.join(PID_FILE)
+}
+
+/// Path of the detached CLI's log for an instance directory
+pub fn log_path(instance_dir: &Path) -> PathBuf {
+    instance_dir.with_extension("log")
+}
+
+/// PID of the detached CLI for an instance, if one was recorded
+pub fn read_pid(instance_dir: &Path) -> Option<u32> {
+    fs::read_to_string(pid_path(instance_dir))
+        .ok()
+        .and_then(|pid| pid.trim().parse().ok())
+}
+
+/// Remove the PID file if it belongs to this process
+///
+/// Called by a detached CLI on its way out so a stale PID isn't left behind.
+pub fn clear_own_pid(instance_dir: &Path) {
+    if read_pid(instance_dir) == Some(std::process::id()) {
+        let _ = fs::remove_file(pid_path(instance_dir));
+    }
+}
+
+/// Re-run the current command in the background without `--detach`
+///
+/// Returns the PID of the background CLI once the node shows up as running
+/// in the registry. Fails if the CLI exits first or `timeout` passes; the
+/// log file then has the reason.
+pub fn detach(instance_dir: &Path, node_id: &str, timeout: Duration) -> Result<u32> {
+    if let Some(parent) = instance_dir.parent() {
+        fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let log_path = log_path(instance_dir);
+    let log = File::create(&log_path)
+        .wrap_err_with(|| format!("Failed to create {}", log_path.display()))?;
+
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != DETACH_FLAG)
+        .collect();
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // A new process group keeps Ctrl+C in this terminal away from the node
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let started = chrono::Utc::now();
+    let mut child = command
+        .spawn()
+        .wrap_err("Failed to start background process")?;
+    let pid = child.id();
+
+    let registry = NodeRegistry::new();
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            bail!(
+                "Node exited during startup ({}); see {}",
+                status,
+                log_path.display()
+            );
+        }
+
+        let ready = registry.get(node_id)?.is_some_and(|node| {
+            node.status == NodeStatus::Running && node.started_at.is_some_and(|t| t >= started)
+        });
+        // The PID file is written once the node has cleared the instance
+        // directory, or on timeout so `stop` can still find the process
+        let timed_out = Instant::now() >= deadline;
+        if ready || timed_out {
+            fs::create_dir_all(instance_dir)?;
+            fs::write(pid_path(instance_dir), pid.to_string())
+                .wrap_err("Failed to write PID file")?;
+        }
+        if ready {
+            // Lets `cf` and the API server stop the node by PID as well
+            registry.set_pid(node_id, pid)?;
+            return Ok(pid);
+        }
+        if timed_out {
+            bail!(
+                "Node did not start within {}s (pid {}); see {}",
+                timeout.as_secs(),
+                pid,
+                log_path.display()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Stop the detached CLI for an instance, if there is one
+///
+/// Sends SIGINT and waits up to `timeout` for the node to shut down, then
+/// kills its process group. Returns the PID that was stopped, or `None` if
+/// the instance wasn't started with `--detach` or has already exited.
+#[cfg(unix)]
+pub fn stop(instance_dir: &Path, timeout: Duration) -> Result<Option<u32>> {
+    let Some(pid) = read_pid(instance_dir) else {
+        return Ok(None);
+    };
+    if !is_alive(pid) {
+        let _ = fs::remove_file(pid_path(instance_dir));
+        return Ok(None);
+    }
+
+    signal("-INT", &pid.to_string())?;
+    let deadline = Instant::now() + timeout;
+    while is_alive(pid) {
+        if Instant::now() >= deadline {
+            // The negative PID addresses the group, so the node goes too
+            signal("-KILL", &format!("-{}", pid))?;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+
+    let _ = fs::remove_file(pid_path(instance_dir));
+    Ok(Some(pid))
+}
+
+#[cfg(not(unix))]
+pub fn stop(instance_dir: &Path, _timeout: Duration) -> Result<Option<u32>> {
+    match read_pid(instance_dir) {
+        Some(pid) => bail!(
+            "Stopping detached nodes is only supported on Unix; stop pid {} manually",
+            pid
+        ),
+        None => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .arg("-0")
+        .arg(pid.to_string())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(unix)]
+fn signal(signal: &str, target: &str) -> Result<()> {
+    let status = Command::new("kill")
+        .arg(signal)
+        .arg("--")
+        .arg(target)
+        .status()
+        .wrap_err("Failed to run kill")?;
+    if !status.success() {
+        bail!("Failed to signal {}", target.trim_start_matches('-'));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_pid() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_pid(dir.path()), None);
+
+        fs::write(pid_path(dir.path()), "4242\n").unwrap();
+        assert_eq!(read_pid(dir.path()), Some(4242));
+
+        fs::write(pid_path(dir.path()), "not a pid").unwrap();
+        assert_eq!(read_pid(dir.path()), None);
+    }
+
+    #[test]
+    fn test_clear_own_pid() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(pid_path(dir.path()), "1").unwrap();
+        clear_own_pid(dir.path());
+        assert!(pid_path(dir.path()).exists());
+
+        fs::write(pid_path(dir.path()), std::process::id().to_string()).unwrap();
+        clear_own_pid(dir.path());
+        assert!(!pid_path(dir.path()).exists());
+    }
+}
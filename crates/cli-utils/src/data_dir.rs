@@ -0,0 +1,29 @@
+//! The `--local` flag shared by every command.
+//!
+//! By default chain state lives in `~/.chain-forge`. With `--local`, or a
+//! `data_dir` key in `chain-forge.toml`, it is kept in the project instead,
+//! so projects don't share instances and a monorepo can keep its chain state
+//! next to the code. The choice is passed on to the processes a command
+//! starts through `CHAIN_FORGE_DATA_DIR`.
+
+use crate::ExitCode;
+use chain_forge_config::Config;
+use clap::Args;
+
+/// Data directory flags shared by every command
+#[derive(Debug, Clone, Default, Args)]
+pub struct DataDirArgs {
+    /// Keep chain state in `./.chain-forge` instead of `~/.chain-forge`
+    #[arg(long, global = true)]
+    pub local: bool,
+}
+
+/// Pick the data directory for the rest of the process
+///
+/// Exits if `chain-forge.toml` has to be read and can't be.
+pub fn init(args: &DataDirArgs) {
+    if let Err(e) = Config::select_data_dir(args.local) {
+        eprintln!("❌ Failed to pick the data directory: {}", e);
+        ExitCode::from_chain_error(&e).exit();
+    }
+}
@@ -0,0 +1,107 @@
+use chain_forge_common::ChainError;
+
+/// Process exit codes shared by all Chain Forge CLIs
+///
+/// Scripts can branch on these to distinguish failure types without parsing
+/// error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Command completed successfully
+    Success = 0,
+    /// Unclassified failure
+    Failure = 1,
+    /// Invalid input (arguments, names, config, mnemonic)
+    Validation = 2,
+    /// The target node is not running or cannot be reached
+    NodeUnreachable = 3,
+    /// The node was reachable but an RPC call failed
+    RpcFailure = 4,
+    /// An operation did not complete in time
+    Timeout = 5,
+}
+
+impl ExitCode {
+    /// Numeric code passed to `std::process::exit`
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Classify a `ChainError` into an exit code
+    pub fn from_chain_error(error: &ChainError) -> Self {
+        match error {
+            ChainError::Config(_)
+            | ChainError::TomlParsing(_)
+            | ChainError::AccountGeneration(_) => ExitCode::Validation,
+            ChainError::NotRunning => ExitCode::NodeUnreachable,
+            ChainError::Rpc(_) => ExitCode::RpcFailure,
+            ChainError::Timeout(_) => ExitCode::Timeout,
+            ChainError::NodeManagement(_)
+            | ChainError::Io(_)
+            | ChainError::Serialization(_)
+            | ChainError::AlreadyRunning
+            | ChainError::Other(_) => ExitCode::Failure,
+        }
+    }
+
+    /// Classify an `eyre::Report`, looking for a `ChainError` in its chain
+    pub fn from_report(report: &eyre::Report) -> Self {
+        report
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ChainError>())
+            .map(Self::from_chain_error)
+            .unwrap_or(ExitCode::Failure)
+    }
+
+    /// Exit the process with this code
+    pub fn exit(self) -> ! {
+        std::process::exit(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_values() {
+        assert_eq!(ExitCode::Success.code(), 0);
+        assert_eq!(ExitCode::Failure.code(), 1);
+        assert_eq!(ExitCode::Validation.code(), 2);
+        assert_eq!(ExitCode::NodeUnreachable.code(), 3);
+        assert_eq!(ExitCode::RpcFailure.code(), 4);
+        assert_eq!(ExitCode::Timeout.code(), 5);
+    }
+
+    #[test]
+    fn test_from_chain_error() {
+        assert_eq!(
+            ExitCode::from_chain_error(&ChainError::Config("bad".to_string())),
+            ExitCode::Validation
+        );
+        assert_eq!(
+            ExitCode::from_chain_error(&ChainError::NotRunning),
+            ExitCode::NodeUnreachable
+        );
+        assert_eq!(
+            ExitCode::from_chain_error(&ChainError::Rpc("boom".to_string())),
+            ExitCode::RpcFailure
+        );
+        assert_eq!(
+            ExitCode::from_chain_error(&ChainError::Timeout("slow".to_string())),
+            ExitCode::Timeout
+        );
+        assert_eq!(
+            ExitCode::from_chain_error(&ChainError::Other("other".to_string())),
+            ExitCode::Failure
+        );
+    }
+
+    #[test]
+    fn test_from_report() {
+        let report = eyre::Report::new(ChainError::Rpc("boom".to_string()));
+        assert_eq!(ExitCode::from_report(&report), ExitCode::RpcFailure);
+
+        let report = eyre::eyre!("plain error");
+        assert_eq!(ExitCode::from_report(&report), ExitCode::Failure);
+    }
+}
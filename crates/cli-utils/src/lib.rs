@@ -1,3 +1,20 @@
+pub mod amount;
+pub mod daemon;
+pub mod data_dir;
+pub mod exit;
 pub mod format;
+pub mod logging;
+pub mod progress;
+pub mod secret;
+pub mod time;
 
+pub use amount::{
+    format_amount, format_btc, format_eth, format_sol, AmountArgs, BTC_DECIMALS, ETH_DECIMALS,
+    SOL_DECIMALS,
+};
+pub use data_dir::DataDirArgs;
+pub use exit::ExitCode;
 pub use format::{format_accounts, OutputFormat};
+pub use logging::{LogArgs, LogFormat};
+pub use secret::write_secret_file;
+pub use time::{format_duration, format_relative};
@@ -0,0 +1,221 @@
+//! Log output for the chain CLIs and the API server.
+//!
+//! Providers report progress and problems as `tracing` events rather than
+//! printing them, so stdout only carries command output. Events go to stderr
+//! as text, or as JSON lines with `--log-format json`. Once a node starts,
+//! its events are also appended to `chain-forge.log` in the instance
+//! directory as JSON lines, whatever the console format.
+//!
+//! Text lines are printed above any progress bars; with JSON logs, progress
+//! bars are not drawn at all.
+
+use crate::progress::{self, ConsoleWriter};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing::Level;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// File name of the per-instance log, within the instance directory
+pub const INSTANCE_LOG_FILE: &str = "chain-forge.log";
+
+/// How log events are written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event
+    Json,
+}
+
+/// Logging flags shared by every command
+#[derive(Debug, Clone, Args)]
+pub struct LogArgs {
+    /// Format of log events on stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "text",
+        env = "CF_LOG_FORMAT"
+    )]
+    pub log_format: LogFormat,
+
+    /// Least severe level to log: error, warn, info, debug, or trace
+    /// (`RUST_LOG` directives take precedence)
+    #[arg(long, global = true, default_value = "info", env = "CF_LOG_LEVEL")]
+    pub log_level: LevelFilter,
+}
+
+impl Default for LogArgs {
+    fn default() -> Self {
+        Self {
+            log_format: LogFormat::Text,
+            log_level: LevelFilter::INFO,
+        }
+    }
+}
+
+/// Path of the instance log events are appended to, if any
+static INSTANCE_LOG: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Writer that appends to the instance log, or discards until one is set
+///
+/// The file is opened for each event, because starting and stopping a node
+/// clear its instance directory. Events are dropped while the directory
+/// doesn't exist rather than recreating it.
+struct InstanceLog;
+
+impl Write for InstanceLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let path = INSTANCE_LOG
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(path) = path.filter(|path| path.parent().is_some_and(Path::exists)) {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Install the global subscriber
+///
+/// Call once at startup, before any events are emitted. Later calls are
+/// ignored.
+pub fn init(args: &LogArgs) {
+    let filter = || {
+        EnvFilter::builder()
+            .with_default_directive(args.log_level.into())
+            .from_env_lossy()
+    };
+
+    let console = match args.log_format {
+        LogFormat::Text => fmt::layer()
+            .with_writer(|| ConsoleWriter)
+            .with_target(false)
+            .without_time()
+            .boxed(),
+        LogFormat::Json => {
+            progress::hide();
+            fmt::layer().json().with_writer(io::stderr).boxed()
+        }
+    };
+    let instance = fmt::layer()
+        .json()
+        .with_ansi(false)
+        .with_writer(|| InstanceLog);
+
+    let _ = tracing_subscriber::registry()
+        .with(console.with_filter(filter()))
+        .with(instance.with_filter(filter()))
+        .try_init();
+}
+
+/// Append this process's events to the log of the instance in `instance_dir`
+pub fn log_to_instance(instance_dir: &Path) {
+    *INSTANCE_LOG.lock().unwrap_or_else(|e| e.into_inner()) =
+        Some(instance_dir.join(INSTANCE_LOG_FILE));
+}
+
+/// A line of an instance log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// The event's message and structured fields
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogEntry {
+    fn level(&self) -> Option<Level> {
+        self.level.parse().ok()
+    }
+}
+
+/// Read the last `lines` entries of an instance log at `level` or more severe
+///
+/// Lines that aren't log entries are skipped. A missing log reads as empty.
+pub fn read_instance_log(
+    instance_dir: &Path,
+    level: LevelFilter,
+    lines: usize,
+) -> io::Result<Vec<LogEntry>> {
+    let file = match File::open(instance_dir.join(INSTANCE_LOG_FILE)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let Ok(entry) = serde_json::from_str::<LogEntry>(&line?) else {
+            continue;
+        };
+        if entry.level().is_some_and(|l| level >= l) {
+            entries.push(entry);
+        }
+    }
+    let skip = entries.len().saturating_sub(lines);
+    entries.drain(..skip);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_instance_log_filters_levels() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = |level: &str, message: &str| {
+            format!(
+                r#"{{"timestamp":"2024-01-01T00:00:00Z","level":"{}","target":"test","fields":{{"message":"{}"}}}}"#,
+                level, message
+            )
+        };
+        let log = [
+            line("INFO", "starting"),
+            "not json".to_string(),
+            line("WARN", "slow"),
+            line("DEBUG", "detail"),
+            line("ERROR", "failed"),
+        ]
+        .join("\n");
+        fs::write(dir.path().join(INSTANCE_LOG_FILE), log).unwrap();
+
+        let all = read_instance_log(dir.path(), LevelFilter::TRACE, 100).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let warnings = read_instance_log(dir.path(), LevelFilter::WARN, 100).unwrap();
+        let messages: Vec<_> = warnings.iter().map(|e| &e.fields["message"]).collect();
+        assert_eq!(messages, ["slow", "failed"]);
+
+        let last = read_instance_log(dir.path(), LevelFilter::INFO, 1).unwrap();
+        assert_eq!(last[0].fields["message"], "failed");
+    }
+
+    #[test]
+    fn test_read_missing_instance_log() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read_instance_log(dir.path(), LevelFilter::INFO, 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Progress bars for long-running CLI work.
+//!
+//! Bars are drawn on stderr, and only when it is a terminal, so piped output
+//! and JSON logs are unchanged. Log lines written while a bar is shown go
+//! through [`ConsoleWriter`], which prints them above the bars instead of
+//! through them.
+//!
+//! Providers report startup progress (blocks premined, accounts funded) as
+//! [`ChainEvent::StartupProgress`] events; [`show_startup_progress`] draws
+//! them for nodes this process starts.
+
+use chain_forge_common::StartupPhase;
+use chain_forge_events::{ChainEvent, Event, EventSink};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+static BARS: OnceLock<MultiProgress> = OnceLock::new();
+
+/// Every bar and spinner this process shows
+pub fn bars() -> &'static MultiProgress {
+    BARS.get_or_init(|| MultiProgress::with_draw_target(ProgressDrawTarget::stderr()))
+}
+
+/// Never draw progress, e.g. when stderr carries JSON logs
+pub fn hide() {
+    bars().set_draw_target(ProgressDrawTarget::hidden());
+}
+
+/// A bar for work of `len` steps
+pub fn bar(len: u64, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let style = ProgressStyle::with_template("{spinner} {msg} [{bar:30}] {pos}/{len} ({elapsed})")
+        .expect("valid progress template")
+        .progress_chars("=> ");
+    let bar = bars().add(ProgressBar::new(len).with_style(style));
+    bar.set_message(message);
+    bar.enable_steady_tick(Duration::from_millis(120));
+    bar
+}
+
+/// A spinner for work of unknown length
+pub fn spinner(message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let style = ProgressStyle::with_template("{spinner} {msg} ({elapsed})")
+        .expect("valid progress template");
+    let spinner = bars().add(ProgressBar::new_spinner().with_style(style));
+    spinner.set_message(message);
+    spinner.enable_steady_tick(Duration::from_millis(120));
+    spinner
+}
+
+/// Writes log lines to stderr without breaking progress bars
+pub struct ConsoleWriter;
+
+impl Write for ConsoleWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        bars().suspend(|| io::stderr().write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// What a startup phase's bar is labelled with
+fn phase_label(phase: StartupPhase) -> &'static str {
+    match phase {
+        StartupPhase::Premine => "Mining initial blocks",
+        StartupPhase::Funding => "Funding accounts",
+        StartupPhase::Import => "Importing accounts",
+        StartupPhase::Wallet => "Creating wallet",
+        StartupPhase::RpcReady => "Waiting for RPC",
+        StartupPhase::Spawn => "Starting node",
+    }
+}
+
+/// Draws startup progress events as bars, one per node and phase
+#[derive(Default)]
+pub struct StartupProgressSink {
+    bars: Mutex<HashMap<(String, StartupPhase), ProgressBar>>,
+}
+
+impl EventSink for StartupProgressSink {
+    fn handle(&self, event: &Event) {
+        let ChainEvent::StartupProgress { phase, done, total } = event.event else {
+            return;
+        };
+        let mut bars = self.bars.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (event.node_id.clone(), phase);
+        let bar = bars
+            .entry(key.clone())
+            .or_insert_with(|| bar(total, phase_label(phase)));
+        bar.set_length(total);
+        bar.set_position(done);
+        if done >= total {
+            bar.finish();
+            bars.remove(&key);
+        }
+    }
+}
+
+/// Show the startup progress of nodes this process starts
+pub fn show_startup_progress() {
+    chain_forge_events::global().add_sink(StartupProgressSink::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_forge_common::ChainType;
+    use chain_forge_events::EventBus;
+
+    #[test]
+    fn test_startup_sink_drops_finished_bars() {
+        hide();
+        let bus = EventBus::new(10);
+        let progress = |done| ChainEvent::StartupProgress {
+            phase: StartupPhase::Funding,
+            done,
+            total: 3,
+        };
+        let sink = StartupProgressSink::default();
+
+        sink.handle(&bus.observe(ChainType::Solana, "dev", progress(1)));
+        assert_eq!(sink.bars.lock().unwrap().len(), 1);
+        sink.handle(&bus.observe(ChainType::Solana, "dev", ChainEvent::NodeStarted));
+        assert_eq!(sink.bars.lock().unwrap().len(), 1);
+        sink.handle(&bus.observe(ChainType::Solana, "dev", progress(3)));
+        assert!(sink.bars.lock().unwrap().is_empty());
+    }
+}
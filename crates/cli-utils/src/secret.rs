@@ -0,0 +1,44 @@
+//! Files holding exported private keys.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Write `contents` to a file only the current user can read
+///
+/// An existing file is replaced. On Unix the file is created with mode
+/// `0600`, so the keys are never readable by others, even briefly.
+pub fn write_secret_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("account-0.json");
+        write_secret_file(&path, b"[1,2,3]").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"[1,2,3]");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Format a duration with its two largest units, e.g. `2h 14m` or `45s`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (
+        secs / 86_400,
+        secs % 86_400 / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+    );
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Format a past time relative to `now`, e.g. `3h ago` or `just now`
+pub fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - time;
+    if elapsed < Duration::seconds(10) {
+        return "just now".to_string();
+    }
+
+    let secs = elapsed.num_seconds();
+    let amount = if secs >= 86_400 {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3_600 {
+        format!("{}h", secs / 3_600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    };
+    format!("{} ago", amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::seconds(45)), "45s");
+        assert_eq!(format_duration(Duration::seconds(125)), "2m 5s");
+        assert_eq!(
+            format_duration(Duration::seconds(2 * 3_600 + 14 * 60)),
+            "2h 14m"
+        );
+        assert_eq!(
+            format_duration(Duration::seconds(3 * 86_400 + 7_200)),
+            "3d 2h"
+        );
+        // Clock skew never shows a negative uptime
+        assert_eq!(format_duration(Duration::seconds(-5)), "0s");
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = Utc::now();
+        assert_eq!(format_relative(now, now), "just now");
+        assert_eq!(
+            format_relative(now + Duration::seconds(30), now),
+            "just now"
+        );
+        assert_eq!(format_relative(now - Duration::seconds(42), now), "42s ago");
+        assert_eq!(format_relative(now - Duration::minutes(90), now), "1h ago");
+        assert_eq!(format_relative(now - Duration::days(2), now), "2d ago");
+    }
+}
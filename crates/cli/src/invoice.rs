@@ -0,0 +1,419 @@
+//! Payment requests for `cf invoice`
+//!
+//! `cf invoice create` derives a fresh account on an instance and returns a
+//! BIP21 URI (Bitcoin) or Solana Pay URL paying it, so checkout flows can be
+//! tested end to end. An invoice is paid once its account holds the requested
+//! amount; `cf invoice list` and `cf invoice watch` check for that.
+//! Invoices are kept in `~/.chain-forge/invoices.json`.
+
+// Allow fs2 trait methods that have the same name as std methods stabilized in Rust 1.89
+#![allow(clippy::incompatible_msrv)]
+
+use chain_forge_bitcoin_core::{BitcoinConfig, InstanceInfo};
+use chain_forge_bitcoin_rpc::EsploraClient;
+use chain_forge_common::uri::percent_encode;
+use chain_forge_common::ChainType;
+use chain_forge_config::Config;
+use chain_forge_solana_core::{SolanaConfig, SolanaInstanceInfo};
+use chain_forge_solana_rpc::SolanaRpcClient;
+use chrono::{DateTime, Utc};
+use eyre::{bail, eyre, Result};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+use tabled::Tabled;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceStatus {
+    Pending,
+    Paid,
+}
+
+impl fmt::Display for InvoiceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Paid => write!(f, "paid"),
+        }
+    }
+}
+
+/// A request for payment to a fresh account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invoice {
+    pub id: String,
+    pub chain: ChainType,
+    pub instance_id: String,
+    /// Account generated for this invoice
+    pub address: String,
+    /// Requested amount in BTC or SOL
+    pub amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// BIP21 URI or Solana Pay URL
+    pub uri: String,
+    pub status: InvoiceStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+impl Invoice {
+    /// Whether the invoice's account holds the requested amount
+    ///
+    /// Bitcoin balances only count confirmed outputs, so on regtest a block
+    /// has to be mined after the payment.
+    fn is_funded(&self) -> Result<bool> {
+        let balance = match self.chain {
+            ChainType::Solana => {
+                let info = SolanaInstanceInfo::load(&self.instance_id)?;
+                SolanaRpcClient::new(info.rpc_url).get_balance(&self.address)?
+            }
+            ChainType::Bitcoin => {
+                let info = InstanceInfo::load(&self.instance_id)?;
+                match info.network {
+                    Some(_) => EsploraClient::new(info.rpc_url).get_balance(&self.address)?,
+                    None => info.wallet_client()?.get_balance(&self.address)?,
+                }
+            }
+            ChainType::Ethereum => bail!("Invoices are not supported for Ethereum"),
+        };
+        Ok(balance >= self.amount)
+    }
+}
+
+#[derive(Tabled)]
+pub struct InvoiceDisplay {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Instance")]
+    instance: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+impl InvoiceDisplay {
+    pub fn new(invoice: &Invoice) -> Self {
+        let unit = match invoice.chain {
+            ChainType::Bitcoin => "BTC",
+            ChainType::Solana => "SOL",
+            ChainType::Ethereum => "ETH",
+        };
+        Self {
+            id: invoice.id.clone(),
+            instance: format!("{}:{}", invoice.chain, invoice.instance_id),
+            amount: format!("{} {}", invoice.amount, unit),
+            address: invoice.address.clone(),
+            status: invoice.status.to_string(),
+        }
+    }
+}
+
+/// Payment URI for `amount` to `address`: BIP21 for Bitcoin, Solana Pay for
+/// Solana
+pub fn payment_uri(
+    chain: ChainType,
+    address: &str,
+    amount: f64,
+    label: Option<&str>,
+    message: &str,
+) -> Result<String> {
+    let scheme = match chain {
+        ChainType::Bitcoin => "bitcoin",
+        ChainType::Solana => "solana",
+        ChainType::Ethereum => bail!("Invoices are not supported for Ethereum"),
+    };
+
+    let mut uri = format!("{}:{}?amount={}", scheme, address, amount);
+    if let Some(label) = label {
+        uri.push_str(&format!("&label={}", percent_encode(label)));
+    }
+    uri.push_str(&format!("&message={}", percent_encode(message)));
+    Ok(uri)
+}
+
+/// Derive the instance's next unused account from its mnemonic and save it
+/// under `label`, returning its address
+fn new_account(chain: ChainType, instance_id: &str, label: &str) -> Result<String> {
+    match chain {
+        ChainType::Solana => {
+            SolanaInstanceInfo::load(instance_id)?;
+            let storage = chain_forge_solana_accounts::AccountsStorage::with_path(
+                SolanaConfig::with_instance(instance_id).accounts_file(),
+            );
+            let mut accounts = storage.load()?;
+            let phrase = accounts
+                .iter()
+                .find_map(|a| a.mnemonic.clone())
+                .ok_or_else(|| no_mnemonic(chain, instance_id))?;
+            let generator = chain_forge_solana_accounts::AccountGenerator::from_mnemonic(&phrase)?;
+
+            let mut index = accounts.len() as u32;
+            let mut account = loop {
+                let account = generator.derive_account(index)?;
+                if !accounts.iter().any(|a| a.public_key == account.public_key) {
+                    break account;
+                }
+                index += 1;
+            };
+            account.label = Some(label.to_string());
+            let address = account.public_key.clone();
+            accounts.push(account);
+            storage.save(&accounts)?;
+            Ok(address)
+        }
+        ChainType::Bitcoin => {
+            let info = InstanceInfo::load(instance_id)?;
+            let network = match info.network {
+                Some(network) => network.network(),
+                None => info.chain.network(),
+            };
+            let storage = chain_forge_bitcoin_accounts::AccountsStorage::with_path(
+                BitcoinConfig::with_instance(instance_id).accounts_file(),
+            );
+            let mut accounts = storage.load()?;
+            let phrase = accounts
+                .iter()
+                .find_map(|a| a.mnemonic.clone())
+                .ok_or_else(|| no_mnemonic(chain, instance_id))?;
+            let generator =
+                chain_forge_bitcoin_accounts::AccountGenerator::from_mnemonic_with_network(
+                    &phrase, network,
+                )?;
+
+            let mut index = accounts.len() as u32;
+            let mut account = loop {
+                let account = generator.derive_account(index)?;
+                if !accounts.iter().any(|a| a.address == account.address) {
+                    break account;
+                }
+                index += 1;
+            };
+            // The node wallet has to know the address for it to be spendable
+            // like the other accounts
+            if info.network.is_none() {
+                info.wallet_client()?
+                    .import_address(&account.address, &account.wif, label)?;
+            }
+            account.label = Some(label.to_string());
+            let address = account.address.clone();
+            accounts.push(account);
+            storage.save(&accounts)?;
+            Ok(address)
+        }
+        ChainType::Ethereum => bail!("Invoices are not supported for Ethereum"),
+    }
+}
+
+fn no_mnemonic(chain: ChainType, instance_id: &str) -> eyre::Report {
+    eyre!(
+        "{} instance '{}' has no mnemonic to derive invoice accounts from",
+        chain,
+        instance_id
+    )
+}
+
+/// Persistent invoice list
+pub struct InvoiceStore {
+    path: PathBuf,
+}
+
+impl InvoiceStore {
+    /// Create an InvoiceStore using the default data directory
+    pub fn new() -> Self {
+        Self::with_path(Config::data_dir().join("invoices.json"))
+    }
+
+    /// Create an InvoiceStore with a custom path (mainly for testing)
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Run `f` on the invoice list while holding an exclusive lock, then save it
+    fn update<T>(&self, f: impl FnOnce(&mut Vec<Invoice>) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        lock.lock_exclusive()
+            .map_err(|e| eyre!("Failed to acquire lock on invoices: {}", e))?;
+
+        let mut invoices = self.invoices()?;
+        let result = f(&mut invoices);
+        if result.is_ok() {
+            fs::write(&self.path, serde_json::to_string_pretty(&invoices)?)?;
+        }
+
+        lock.unlock()
+            .map_err(|e| eyre!("Failed to release lock on invoices: {}", e))?;
+        result
+    }
+
+    /// All invoices, oldest first
+    pub fn invoices(&self) -> Result<Vec<Invoice>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let json = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Create an invoice for `amount` paid to a new account on an instance
+    pub fn create(
+        &self,
+        chain: ChainType,
+        instance_id: &str,
+        amount: f64,
+        label: Option<String>,
+    ) -> Result<Invoice> {
+        if !amount.is_finite() || amount <= 0.0 {
+            bail!("Invoice amount must be positive, got {}", amount);
+        }
+
+        self.update(|invoices| {
+            let id = next_id(invoices);
+            let address = new_account(chain, instance_id, &format!("invoice-{}", id))?;
+            let uri = payment_uri(
+                chain,
+                &address,
+                amount,
+                label.as_deref(),
+                &format!("Invoice {}", id),
+            )?;
+
+            let invoice = Invoice {
+                id,
+                chain,
+                instance_id: instance_id.to_string(),
+                address,
+                amount,
+                label,
+                uri,
+                status: InvoiceStatus::Pending,
+                created_at: Utc::now(),
+                paid_at: None,
+            };
+            invoices.push(invoice.clone());
+            Ok(invoice)
+        })
+    }
+
+    /// Mark pending invoices whose account has been funded as paid, and
+    /// return all invoices
+    ///
+    /// Invoices whose node can't be reached stay pending.
+    pub fn refresh(&self) -> Result<Vec<Invoice>> {
+        let funded: Vec<String> = self
+            .invoices()?
+            .into_iter()
+            .filter(|invoice| invoice.status == InvoiceStatus::Pending)
+            .filter(|invoice| invoice.is_funded().unwrap_or(false))
+            .map(|invoice| invoice.id)
+            .collect();
+
+        if funded.is_empty() {
+            return self.invoices();
+        }
+        self.update(|invoices| {
+            mark_paid(invoices, &funded, Utc::now());
+            Ok(invoices.clone())
+        })
+    }
+}
+
+/// Next sequential invoice ID, e.g. `inv-3`
+fn next_id(invoices: &[Invoice]) -> String {
+    let last = invoices
+        .iter()
+        .filter_map(|invoice| invoice.id.strip_prefix("inv-")?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("inv-{}", last + 1)
+}
+
+fn mark_paid(invoices: &mut [Invoice], ids: &[String], now: DateTime<Utc>) {
+    for invoice in invoices {
+        if invoice.status == InvoiceStatus::Pending && ids.contains(&invoice.id) {
+            invoice.status = InvoiceStatus::Paid;
+            invoice.paid_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoice(id: &str) -> Invoice {
+        Invoice {
+            id: id.to_string(),
+            chain: ChainType::Bitcoin,
+            instance_id: "shop".to_string(),
+            address: "bcrt1qexample".to_string(),
+            amount: 0.5,
+            label: None,
+            uri: String::new(),
+            status: InvoiceStatus::Pending,
+            created_at: Utc::now(),
+            paid_at: None,
+        }
+    }
+
+    #[test]
+    fn test_payment_uri() {
+        let uri = payment_uri(
+            ChainType::Bitcoin,
+            "bcrt1qexample",
+            0.5,
+            Some("Test Shop"),
+            "Invoice inv-1",
+        )
+        .unwrap();
+        assert_eq!(
+            uri,
+            "bitcoin:bcrt1qexample?amount=0.5&label=Test%20Shop&message=Invoice%20inv-1"
+        );
+
+        let uri = payment_uri(ChainType::Solana, "9xQeWv", 0.00000001, None, "x").unwrap();
+        assert_eq!(uri, "solana:9xQeWv?amount=0.00000001&message=x");
+
+        assert!(payment_uri(ChainType::Ethereum, "0xabc", 1.0, None, "x").is_err());
+    }
+
+    #[test]
+    fn test_next_id() {
+        assert_eq!(next_id(&[]), "inv-1");
+        assert_eq!(next_id(&[invoice("inv-1"), invoice("inv-7")]), "inv-8");
+    }
+
+    #[test]
+    fn test_mark_paid() {
+        let now = Utc::now();
+        let mut invoices = vec![invoice("inv-1"), invoice("inv-2")];
+        mark_paid(&mut invoices, &["inv-2".to_string()], now);
+
+        assert_eq!(invoices[0].status, InvoiceStatus::Pending);
+        assert_eq!(invoices[1].status, InvoiceStatus::Paid);
+        assert_eq!(invoices[1].paid_at, Some(now));
+    }
+
+    #[test]
+    fn test_create_rejects_bad_amount() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = InvoiceStore::with_path(dir.path().join("invoices.json"));
+        assert!(store.create(ChainType::Bitcoin, "shop", 0.0, None).is_err());
+        assert!(store.invoices().unwrap().is_empty());
+    }
+}
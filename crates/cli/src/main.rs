@@ -0,0 +1,1154 @@
+//! Chain Forge umbrella CLI
+//!
+//! Chain-agnostic commands that don't belong to a single chain's CLI.
+
+use chain_forge_cli_utils::{data_dir, progress, DataDirArgs, ExitCode, OutputFormat};
+use chain_forge_common::{AuditLog, ChainType, Message, NodeRegistry, NodeStatus};
+use chain_forge_config::Config;
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::{Result, WrapErr};
+use invoice::{InvoiceDisplay, InvoiceStatus, InvoiceStore};
+use nodes::{NodeDisplay, NodeJson, PhaseDisplay, ProfileJson};
+use pool::{Pool, PoolMemberDisplay};
+use proxy::RouteDisplay;
+use scaffold::Template;
+use scenario::{Action, NodeState, Scenario};
+use stats::InstanceStatsDisplay;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use tabled::Table;
+use trash::TrashDisplay;
+use tx_template::{TemplateDisplay, TemplateStore};
+use vectors::NetworkArg;
+
+mod invoice;
+mod nodes;
+mod pool;
+mod proxy;
+mod scaffold;
+mod scenario;
+mod stats;
+mod trash;
+mod tx_template;
+mod vectors;
+
+#[derive(Parser)]
+#[command(name = "cf")]
+#[command(about = "Chain Forge - multi-chain local development tool", long_about = None)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Inspect chain-forge.toml configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Scaffold a new project wired to a local Chain Forge instance
+    New {
+        /// Project name (also used as the Chain Forge instance ID)
+        name: String,
+
+        /// Project template
+        #[arg(short, long, value_enum, default_value = "solana-counter")]
+        template: Template,
+
+        /// Directory to create the project in
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Create payment requests and watch for them to be paid
+    Invoice {
+        #[command(subcommand)]
+        command: InvoiceCommands,
+    },
+
+    /// Inspect nodes in the node registry
+    Nodes {
+        #[command(subcommand)]
+        command: NodesCommands,
+    },
+
+    /// Manage a warm pool of pre-started nodes for fast test startup
+    Pool {
+        #[command(subcommand)]
+        command: PoolCommands,
+    },
+
+    /// Serve node RPC endpoints at stable local URLs
+    Proxy {
+        #[command(subcommand)]
+        command: ProxyCommands,
+    },
+
+    /// Reset a running node to a clean state without restarting it
+    Reset {
+        /// Node ID from the node registry (e.g. bitcoin:dev, solana:default, ethereum:default)
+        #[arg(long)]
+        node: String,
+
+        /// Balance to fund each account with (default: 100 SOL, 10 BTC, or 10000 ETH)
+        #[arg(short, long)]
+        balance: Option<f64>,
+    },
+
+    /// Start nodes and set up fixtures described in a scenario file
+    Run {
+        /// Scenario file (YAML)
+        file: PathBuf,
+
+        /// Print the commands without running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Summarize local usage from the audit log
+    Stats {
+        /// Number of days to include
+        #[arg(short, long, default_value = "7")]
+        days: u32,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Restore or delete instance data cleared by stopping a node
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
+    /// Work with transactions across chains
+    Tx {
+        #[command(subcommand)]
+        command: TxCommands,
+    },
+
+    /// Generate wallet test vectors
+    Vectors {
+        #[command(subcommand)]
+        command: VectorsCommands,
+    },
+}
+
+/// Chain selector for commands that take `--chain`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ChainArg {
+    Solana,
+    Bitcoin,
+    Ethereum,
+}
+
+impl From<ChainArg> for ChainType {
+    fn from(chain: ChainArg) -> Self {
+        match chain {
+            ChainArg::Solana => ChainType::Solana,
+            ChainArg::Bitcoin => ChainType::Bitcoin,
+            ChainArg::Ethereum => ChainType::Ethereum,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum InvoiceCommands {
+    /// Request a payment to a new account as a BIP21 URI or Solana Pay URL
+    Create {
+        /// Chain to request payment on (bitcoin or solana)
+        #[arg(short, long, value_enum)]
+        chain: ChainArg,
+
+        /// Amount in BTC or SOL
+        #[arg(short, long)]
+        amount: f64,
+
+        /// Instance ID to create the account on
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Payee label shown by wallets
+        #[arg(short, long)]
+        label: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// List invoices, marking funded ones as paid
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Wait until an invoice is paid
+    Watch {
+        /// Invoice ID (e.g. inv-1)
+        id: String,
+
+        /// Seconds to wait before giving up
+        #[arg(short, long, default_value = "300")]
+        timeout: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum NodesCommands {
+    /// List registered nodes with their uptime
+    List {
+        /// Only list nodes of this chain
+        #[arg(short, long, value_enum)]
+        chain: Option<ChainArg>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show how long each phase of a node's last start took
+    Profile {
+        /// Node ID (e.g. bitcoin:default)
+        node: String,
+
+        /// Fail if startup took longer than this many seconds
+        #[arg(long)]
+        budget: Option<f64>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum PoolCommands {
+    /// Start more pre-warmed nodes in the background
+    Fill {
+        /// Chain to start nodes for
+        #[arg(short, long, value_enum)]
+        chain: ChainArg,
+
+        /// Number of nodes to add
+        #[arg(short, long, default_value = "2")]
+        size: u32,
+
+        /// Initial balance for each account (default: 100 SOL, 10 BTC, or 10000 ETH)
+        #[arg(short, long)]
+        balance: Option<f64>,
+    },
+
+    /// Lease an idle node, resetting it in place first
+    Acquire {
+        /// Chain of the node to lease
+        #[arg(short, long, value_enum)]
+        chain: ChainArg,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Return a leased node to the pool
+    Release {
+        /// Node ID returned by 'cf pool acquire' (e.g. solana:pool-solana-0)
+        node_id: String,
+    },
+
+    /// Show pool members and their state
+    Status {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Stop idle pooled nodes and remove them from the pool
+    Drain {
+        /// Only drain nodes of this chain
+        #[arg(short, long, value_enum)]
+        chain: Option<ChainArg>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProxyCommands {
+    /// Forward http://localhost:<port>/<alias> to each node's current RPC URL
+    Start {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = proxy::DEFAULT_PORT)]
+        port: u16,
+
+        /// Extra alias as NAME=NODE_ID, e.g. frontend=solana:dev (repeatable)
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommands {
+    /// List trashed instance data, newest first
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Move trashed data back to its instance directory
+    Restore {
+        /// Trash entry ID, or a node ID (e.g. solana:dev) for its newest entry
+        id: String,
+    },
+
+    /// Delete trashed data for good (default: only entries past retention)
+    Purge {
+        /// Trash entry ID, or a node ID (e.g. solana:dev) for its newest entry
+        #[arg(conflicts_with = "all")]
+        id: Option<String>,
+
+        /// Delete every entry
+        #[arg(long, default_value = "false")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TxCommands {
+    /// Save and apply named transaction templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// Check a template file and save it under a name
+    Save {
+        /// TOML template file
+        file: PathBuf,
+
+        /// Template name (default: the file name without extension)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Replace an existing template with the same name
+        #[arg(long, default_value = "false")]
+        force: bool,
+    },
+
+    /// Run a saved template's steps against an instance
+    Apply {
+        /// Template name
+        name: String,
+
+        /// Instance ID to run against
+        #[arg(short, long, default_value = "default")]
+        instance: String,
+
+        /// Variable value as NAME=VALUE (repeatable)
+        #[arg(long = "var")]
+        vars: Vec<String>,
+
+        /// Print the commands without running them
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+
+    /// List saved templates
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Delete a saved template
+    Remove {
+        /// Template name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum VectorsCommands {
+    /// Write mnemonic -> path -> address/key tuples as a JSON file
+    Generate {
+        /// Chain to derive keys for
+        #[arg(short, long, value_enum)]
+        chain: ChainArg,
+
+        /// Number of keys to derive
+        #[arg(short = 'n', long, default_value = "10")]
+        count: u32,
+
+        /// Mnemonic to derive from (a random one is generated if omitted)
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+
+        /// Bitcoin network to encode addresses and WIFs for
+        #[arg(long, value_enum, default_value = "regtest")]
+        network: NetworkArg,
+
+        /// Output file (default: <chain>-vectors.json)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the JSON Schema for chain-forge.toml
+    Schema,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    data_dir::init(&cli.data);
+
+    match cli.command {
+        Commands::Config { command } => match command {
+            ConfigCommands::Schema => {
+                println!("{}", serde_json::to_string_pretty(&Config::json_schema())?);
+            }
+        },
+
+        Commands::New {
+            name,
+            template,
+            path,
+        } => {
+            let root = scaffold::scaffold(&path, &name, template)?;
+
+            println!("✅ Created project '{}' at {}", name, root.display());
+            println!();
+            println!("Next steps:");
+            println!("   cd {}", root.display());
+            println!("   ./scripts/test.sh");
+        }
+
+        Commands::Invoice { command } => run_invoice(command)?,
+
+        Commands::Nodes {
+            command: NodesCommands::List { chain, format },
+        } => {
+            let registry = NodeRegistry::new();
+            // Record nodes whose process has exited as stopped
+            registry.reconcile()?;
+            let mut nodes = match chain {
+                Some(chain) => registry.list_by_chain(chain.into())?,
+                None => registry.list()?,
+            };
+            nodes::sort_by_start(&mut nodes);
+            let now = chrono::Utc::now();
+
+            match format {
+                OutputFormat::Json => {
+                    let nodes: Vec<NodeJson> =
+                        nodes.iter().map(|node| NodeJson::new(node, now)).collect();
+                    println!("{}", serde_json::to_string_pretty(&nodes)?);
+                }
+                OutputFormat::Table => {
+                    if nodes.is_empty() {
+                        println!("No nodes registered.");
+                    } else {
+                        let rows: Vec<NodeDisplay> = nodes
+                            .iter()
+                            .map(|node| NodeDisplay::new(node, now))
+                            .collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        Commands::Nodes {
+            command:
+                NodesCommands::Profile {
+                    node,
+                    budget,
+                    format,
+                },
+        } => {
+            let Some(info) = NodeRegistry::new().get(&node)? else {
+                eprintln!("❌ {}", Message::NodeNotRegistered(node.to_string()));
+                ExitCode::Validation.exit();
+            };
+            let profile = nodes::startup_profile(&info)?;
+
+            match format {
+                OutputFormat::Json => {
+                    let profile = ProfileJson::new(&info.node_id, &profile);
+                    println!("{}", serde_json::to_string_pretty(&profile)?);
+                }
+                OutputFormat::Table => {
+                    if profile.is_empty() {
+                        println!(
+                            "No startup profile recorded for {}; it is written when the node starts.",
+                            info.display_name()
+                        );
+                    } else {
+                        println!("⏱️  Startup of {}", info.display_name());
+                        println!("{}", Table::new(PhaseDisplay::rows(&profile)));
+                        println!("   Total: {}", nodes::format_millis(profile.total_ms()));
+                    }
+                }
+            }
+
+            if let Some(budget) = budget {
+                if profile.total_ms() as f64 > budget * 1000.0 {
+                    eprintln!(
+                        "❌ Startup took {}, over the {}s budget",
+                        nodes::format_millis(profile.total_ms()),
+                        budget
+                    );
+                    ExitCode::Timeout.exit();
+                }
+            }
+        }
+
+        Commands::Pool { command } => run_pool(command)?,
+
+        Commands::Proxy {
+            command: ProxyCommands::Start { port, aliases },
+        } => {
+            let aliases = match proxy::parse_aliases(&aliases) {
+                Ok(aliases) => aliases,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+
+            let nodes = NodeRegistry::new().list()?;
+            let routes = proxy::routes(&nodes, &aliases);
+            for (alias, node_id) in &aliases {
+                if !routes.iter().any(|(name, _)| name == alias) {
+                    println!(
+                        "⚠️  {} is not registered yet; /{} will route to it once it is",
+                        node_id, alias
+                    );
+                }
+            }
+
+            println!("🔀 Proxy listening on http://localhost:{}", port);
+            if routes.is_empty() {
+                println!("   No nodes registered yet; new nodes are routed as /<chain>-<instance>");
+            } else {
+                let rows: Vec<RouteDisplay> = routes
+                    .iter()
+                    .map(|(alias, node)| RouteDisplay::new(port, alias, node))
+                    .collect();
+                println!("{}", Table::new(rows));
+            }
+            println!();
+            println!("💡 Press Ctrl+C to stop the proxy");
+
+            tokio::runtime::Runtime::new()?.block_on(proxy::serve(port, aliases))?;
+        }
+
+        Commands::Reset { node, balance } => {
+            let Some(info) = NodeRegistry::new().get(&node)? else {
+                eprintln!("❌ {}", Message::NodeNotRegistered(node.to_string()));
+                ExitCode::Validation.exit();
+            };
+            if info.status != NodeStatus::Running {
+                eprintln!("❌ {}", Message::NodeNotRunning(node.to_string()));
+                ExitCode::NodeUnreachable.exit();
+            }
+
+            let balance = balance.unwrap_or(default_balance(info.chain));
+            println!("🔄 Resetting {}...", info.display_name());
+            let result = match info.chain {
+                ChainType::Solana => {
+                    chain_forge_solana_core::reset_instance(&info.instance_id, balance)
+                        .map(|accounts| accounts.len())
+                }
+                ChainType::Bitcoin => {
+                    chain_forge_bitcoin_core::reset_instance(&info.instance_id, balance)
+                        .map(|accounts| accounts.len())
+                }
+                ChainType::Ethereum => {
+                    chain_forge_ethereum_core::reset_instance(&info.instance_id, balance)
+                        .map(|accounts| accounts.len())
+                }
+            };
+
+            match result {
+                Ok(count) => {
+                    println!("✅ Reset {} ({} accounts funded)", node, count);
+                }
+                Err(e) => {
+                    eprintln!("❌ Reset failed: {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            }
+        }
+
+        Commands::Run { file, dry_run } => run_scenario(&file, dry_run)?,
+
+        Commands::Stats { days, format } => {
+            let entries = AuditLog::new().entries()?;
+            let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+            let report = stats::summarize(&entries, since);
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+                OutputFormat::Table => {
+                    println!(
+                        "Usage since {} ({} days)",
+                        report.since.format("%Y-%m-%d %H:%M UTC"),
+                        days
+                    );
+                    println!("  Nodes started: {}", report.nodes_started);
+                    println!("  Blocks mined:  {}", report.blocks_mined);
+                    println!();
+
+                    if report.instances.is_empty() {
+                        println!("No activity recorded.");
+                    } else {
+                        let rows: Vec<InstanceStatsDisplay> =
+                            report.instances.iter().map(Into::into).collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        Commands::Trash { command } => run_trash(command)?,
+
+        Commands::Tx {
+            command: TxCommands::Template { command },
+        } => run_template(command)?,
+
+        Commands::Vectors {
+            command:
+                VectorsCommands::Generate {
+                    chain,
+                    count,
+                    mnemonic,
+                    network,
+                    output,
+                },
+        } => {
+            let chain = ChainType::from(chain);
+            let set = match chain {
+                ChainType::Solana => vectors::solana_vectors(mnemonic.as_deref(), count),
+                ChainType::Bitcoin => {
+                    vectors::bitcoin_vectors(mnemonic.as_deref(), network.into(), count)
+                }
+                ChainType::Ethereum => vectors::ethereum_vectors(mnemonic.as_deref(), count),
+            };
+            let set = match set {
+                Ok(set) => set,
+                Err(e) => {
+                    eprintln!("❌ Failed to generate vectors: {}", e);
+                    ExitCode::from_chain_error(&e).exit();
+                }
+            };
+
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{}-vectors.json", chain)));
+            std::fs::write(&output, serde_json::to_string_pretty(&set)?)?;
+            println!(
+                "✅ Wrote {} {} vectors to {}",
+                set.vectors.len(),
+                chain,
+                output.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Default initial balance per account, matching the chain CLIs
+fn default_balance(chain: ChainType) -> f64 {
+    match chain {
+        ChainType::Solana => 100.0,
+        ChainType::Bitcoin => 10.0,
+        ChainType::Ethereum => 10000.0,
+    }
+}
+
+fn run_trash(command: TrashCommands) -> Result<()> {
+    let trash = Config::trash();
+
+    match command {
+        TrashCommands::List { format } => {
+            let entries = trash.list()?;
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+                OutputFormat::Table => {
+                    if entries.is_empty() {
+                        println!("The trash is empty.");
+                    } else {
+                        let now = chrono::Utc::now();
+                        let rows: Vec<TrashDisplay> = entries
+                            .iter()
+                            .map(|entry| TrashDisplay::new(entry, now))
+                            .collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        TrashCommands::Restore { id } => match trash.restore(&id) {
+            Ok(entry) => {
+                println!(
+                    "♻️  Restored {} to {}",
+                    entry.id,
+                    entry.original_path.display()
+                );
+                println!();
+                println!("💡 Starting the instance again moves this data back to the trash");
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+        },
+
+        TrashCommands::Purge { id, all } => {
+            let purged = match (id, all) {
+                (Some(id), _) => trash.purge(&id).map(|entry| vec![entry]),
+                (None, true) => trash.purge_all(),
+                (None, false) => trash.prune(),
+            };
+            let purged = match purged {
+                Ok(purged) => purged,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+            for entry in &purged {
+                println!("🗑️  Deleted {}", entry.id);
+            }
+            if purged.is_empty() {
+                println!("Nothing to purge.");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_template(command: TemplateCommands) -> Result<()> {
+    let store = TemplateStore::new();
+
+    match command {
+        TemplateCommands::Save { file, name, force } => {
+            let name = match name {
+                Some(name) => name,
+                None => file
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(str::to_string)
+                    .ok_or_else(|| eyre::eyre!("Cannot derive a name from {}", file.display()))?,
+            };
+
+            let template = match store.save(&name, &file, force) {
+                Ok(template) => template,
+                Err(e) => {
+                    eprintln!("❌ {:#}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+
+            println!(
+                "✅ Saved template '{}' ({} steps on {})",
+                name,
+                template.steps.len(),
+                template.chain
+            );
+            let variables = template.variable_names();
+            if !variables.is_empty() {
+                println!(
+                    "   Variables: {}",
+                    variables.into_iter().collect::<Vec<_>>().join(", ")
+                );
+            }
+            println!();
+            println!(
+                "💡 Run it with: cf tx template apply {} --instance <id>",
+                name
+            );
+        }
+
+        TemplateCommands::Apply {
+            name,
+            instance,
+            vars,
+            dry_run,
+        } => {
+            let template = store.load(&name)?;
+            let steps = tx_template::parse_vars(&vars).and_then(|vars| {
+                template.render(&instance, &vars, || {
+                    tx_template::instance_accounts(template.chain, &instance)
+                })
+            });
+            let steps = match steps {
+                Ok(steps) => steps,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+
+            let program = template.program();
+            for (i, args) in steps.iter().enumerate() {
+                println!(
+                    "▶️  Step {}/{}: {} {}",
+                    i + 1,
+                    steps.len(),
+                    program,
+                    args.join(" ")
+                );
+                if dry_run {
+                    continue;
+                }
+
+                let status = Command::new(program)
+                    .args(args)
+                    .status()
+                    .map_err(|e| eyre::eyre!("Failed to run {}: {}", program, e))?;
+                if !status.success() {
+                    eprintln!("❌ Step {} failed; later steps were not run", i + 1);
+                    // Pass on the chain CLI's exit code (see ExitCode)
+                    std::process::exit(status.code().unwrap_or(1));
+                }
+                println!();
+            }
+
+            if !dry_run {
+                println!("✅ Applied template '{}' to {}", name, instance);
+            }
+        }
+
+        TemplateCommands::List { format } => {
+            let templates = store.list()?;
+            match format {
+                OutputFormat::Json => {
+                    let json: serde_json::Map<_, _> = templates
+                        .into_iter()
+                        .map(|(name, template)| Ok((name, serde_json::to_value(template)?)))
+                        .collect::<Result<_>>()?;
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                OutputFormat::Table => {
+                    if templates.is_empty() {
+                        println!("No templates saved. Add one with 'cf tx template save <file>'.");
+                    } else {
+                        let rows: Vec<TemplateDisplay> = templates
+                            .iter()
+                            .map(|(name, template)| TemplateDisplay::new(name, template))
+                            .collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        TemplateCommands::Remove { name } => {
+            store.remove(&name)?;
+            println!("🗑️  Removed template '{}'", name);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_scenario(file: &Path, dry_run: bool) -> Result<()> {
+    let scenario = std::fs::read_to_string(file)
+        .wrap_err_with(|| format!("Failed to read {}", file.display()))
+        .and_then(|yaml| Scenario::parse(&yaml));
+    let scenario = match scenario {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("❌ {:#}", e);
+            ExitCode::Validation.exit();
+        }
+    };
+
+    let registry = NodeRegistry::new();
+    for node in &scenario.nodes {
+        let program = node.program();
+        println!("📦 {}", node.node_id());
+
+        let running = node.is_running(&registry)?;
+        if running {
+            if let Err(e) = node.check_running() {
+                eprintln!("❌ {}", e);
+                ExitCode::Validation.exit();
+            }
+            println!("   Already running");
+        } else {
+            println!("▶️  {} {}", program, node.start_args().join(" "));
+            if !dry_run {
+                node.start()?;
+            }
+        }
+
+        // A node that isn't running yet has nothing applied; starting it
+        // clears any earlier record
+        let instance_dir = node.instance_dir();
+        let mut state = if running || !dry_run {
+            NodeState::load(&instance_dir)?
+        } else {
+            NodeState::default()
+        };
+        let actions = node.actions();
+        let pending = match state.pending(&actions) {
+            Ok(pending) => pending,
+            Err(e) => {
+                eprintln!("❌ {}: {}", node.node_id(), e);
+                ExitCode::Validation.exit();
+            }
+        };
+        if pending.is_empty() {
+            println!("   Nothing to apply");
+        }
+        if dry_run {
+            // Accounts and token mints may not exist yet
+            for action in pending {
+                println!("▶️  {} {}", program, action.args().join(" "));
+            }
+            println!();
+            continue;
+        }
+
+        // The bar shows between steps; each step's own output replaces it
+        // while the step runs
+        let steps = progress::bar(pending.len() as u64, node.node_id());
+        for action in pending {
+            let args = node.render(action, &state, || {
+                scenario::account_values(node.chain, &node.instance)
+            })?;
+
+            let mut command = Command::new(program);
+            command.args(&args);
+            let status = steps.suspend(|| -> Result<ExitStatus> {
+                println!("▶️  {} {}", program, args.join(" "));
+                if let Action::CreateToken { name, .. } = action {
+                    // Keep the mint address so later actions can refer to it
+                    let output = command
+                        .stderr(Stdio::inherit())
+                        .output()
+                        .map_err(|e| eyre::eyre!("Failed to run {}: {}", program, e))?;
+                    if output.status.success() {
+                        let created: serde_json::Value = serde_json::from_slice(&output.stdout)
+                            .wrap_err("Unexpected output from token create")?;
+                        let mint = created["mint"]
+                            .as_str()
+                            .ok_or_else(|| eyre::eyre!("token create did not report a mint"))?;
+                        println!("   Token {}: {}", name, mint);
+                        state.tokens.insert(name.clone(), mint.to_string());
+                    }
+                    Ok(output.status)
+                } else {
+                    command
+                        .status()
+                        .map_err(|e| eyre::eyre!("Failed to run {}: {}", program, e))
+                }
+            })?;
+            if !status.success() {
+                steps.abandon();
+                eprintln!("❌ Step failed; later steps were not run");
+                // Pass on the chain CLI's exit code (see ExitCode)
+                std::process::exit(status.code().unwrap_or(1));
+            }
+
+            state.applied.push(action.clone());
+            state.save(&instance_dir)?;
+            steps.inc(1);
+        }
+        steps.finish_and_clear();
+        println!();
+    }
+
+    if !dry_run {
+        println!("✅ Applied scenario {}", file.display());
+    }
+    Ok(())
+}
+
+fn run_invoice(command: InvoiceCommands) -> Result<()> {
+    let store = InvoiceStore::new();
+
+    match command {
+        InvoiceCommands::Create {
+            chain,
+            amount,
+            instance,
+            label,
+            format,
+        } => {
+            let invoice = match store.create(chain.into(), &instance, amount, label) {
+                Ok(invoice) => invoice,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    ExitCode::Validation.exit();
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&invoice)?);
+                }
+                OutputFormat::Table => {
+                    println!("🧾 Created invoice {}", invoice.id);
+                    println!("   Pay to: {}", invoice.address);
+                    println!("   URI:    {}", invoice.uri);
+                    println!();
+                    println!("💡 Wait for payment with: cf invoice watch {}", invoice.id);
+                }
+            }
+        }
+
+        InvoiceCommands::List { format } => {
+            let invoices = store.refresh()?;
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&invoices)?);
+                }
+                OutputFormat::Table => {
+                    if invoices.is_empty() {
+                        println!("No invoices. Create one with 'cf invoice create'.");
+                    } else {
+                        let rows: Vec<InvoiceDisplay> =
+                            invoices.iter().map(InvoiceDisplay::new).collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        InvoiceCommands::Watch { id, timeout } => {
+            if !store.invoices()?.iter().any(|invoice| invoice.id == id) {
+                eprintln!("❌ Invoice '{}' not found", id);
+                ExitCode::Validation.exit();
+            }
+
+            println!("⏳ Waiting for {} to be paid...", id);
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout);
+            loop {
+                let invoices = store.refresh()?;
+                if let Some(invoice) = invoices
+                    .iter()
+                    .find(|invoice| invoice.id == id && invoice.status == InvoiceStatus::Paid)
+                {
+                    println!("✅ {} paid ({} to {})", id, invoice.amount, invoice.address);
+                    break;
+                }
+                if std::time::Instant::now() >= deadline {
+                    eprintln!("❌ {} was not paid within {}s", id, timeout);
+                    ExitCode::Timeout.exit();
+                }
+                std::thread::sleep(std::time::Duration::from_secs(2));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_pool(command: PoolCommands) -> Result<()> {
+    let pool = Pool::new();
+    let registry = NodeRegistry::new();
+
+    match command {
+        PoolCommands::Fill {
+            chain,
+            size,
+            balance,
+        } => {
+            let chain = ChainType::from(chain);
+            let balance = balance.unwrap_or(default_balance(chain));
+
+            for member in pool.fill(chain, size, balance)? {
+                println!(
+                    "🚀 Starting {} on port {} (pid {})",
+                    member.node_id, member.port, member.pid
+                );
+            }
+            println!();
+            println!("💡 Nodes become available once funded; check with 'cf pool status'");
+        }
+
+        PoolCommands::Acquire { chain, format } => {
+            let chain = ChainType::from(chain);
+            let Some(member) = pool.acquire(chain, |m| pool::is_ready(&registry, m))? else {
+                eprintln!(
+                    "❌ No idle {} nodes in the pool. Add some with 'cf pool fill --chain {}'",
+                    chain, chain
+                );
+                ExitCode::NodeUnreachable.exit();
+            };
+
+            if let Err(e) = pool::reset_member(&member) {
+                // Hand the node back so it isn't stuck as leased
+                let _ = pool.release(&member.node_id);
+                return Err(e.wrap_err(format!("Failed to reset {}", member.node_id)));
+            }
+
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&member)?),
+                OutputFormat::Table => {
+                    println!("✅ Leased {}", member.node_id);
+                    println!("   Instance: {}", member.instance_id);
+                    println!("   Port:     {}", member.port);
+                    println!();
+                    println!("   Release with: cf pool release {}", member.node_id);
+                }
+            }
+        }
+
+        PoolCommands::Release { node_id } => {
+            let member = pool.release(&node_id)?;
+            println!("✅ Returned {} to the pool", member.node_id);
+        }
+
+        PoolCommands::Status { format } => {
+            let members = pool.members()?;
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&members)?),
+                OutputFormat::Table => {
+                    if members.is_empty() {
+                        println!("The pool is empty. Add nodes with 'cf pool fill'.");
+                    } else {
+                        let rows: Vec<PoolMemberDisplay> = members
+                            .iter()
+                            .map(|m| PoolMemberDisplay::new(m, pool::is_ready(&registry, m)))
+                            .collect();
+                        println!("{}", Table::new(rows));
+                    }
+                }
+            }
+        }
+
+        PoolCommands::Drain { chain } => {
+            let drained = pool.drain(chain.map(ChainType::from))?;
+            for member in &drained {
+                match pool::stop_member(member) {
+                    Ok(()) => println!("🛑 Stopping {}", member.node_id),
+                    Err(e) => eprintln!("⚠️  {}", e),
+                }
+            }
+            if drained.is_empty() {
+                println!("No idle nodes to drain.");
+            }
+        }
+    }
+
+    Ok(())
+}
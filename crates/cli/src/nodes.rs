@@ -0,0 +1,213 @@
+//! Registered node listing for `cf nodes list`, and startup timings for
+//! `cf nodes profile`
+
+use chain_forge_bitcoin_core::InstanceInfo as BitcoinInstanceInfo;
+use chain_forge_cli_utils::{format_duration, format_relative};
+use chain_forge_common::{ChainType, NodeInfo, StartupProfile};
+use chain_forge_ethereum_core::EthereumInstanceInfo;
+use chain_forge_solana_core::SolanaInstanceInfo;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tabled::Tabled;
+
+/// A node with its uptime, for JSON output
+///
+/// `started_at` stays RFC3339; `started` and `uptime` are for display.
+#[derive(Serialize)]
+pub struct NodeJson<'a> {
+    #[serde(flatten)]
+    node: &'a NodeInfo,
+    started: Option<String>,
+    uptime_secs: Option<i64>,
+    uptime: Option<String>,
+}
+
+impl<'a> NodeJson<'a> {
+    pub fn new(node: &'a NodeInfo, now: DateTime<Utc>) -> Self {
+        let uptime = node.uptime(now);
+        Self {
+            node,
+            started: node.started_at.map(|t| format_relative(t, now)),
+            uptime_secs: uptime.map(|uptime| uptime.num_seconds().max(0)),
+            uptime: uptime.map(format_duration),
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub struct NodeDisplay {
+    #[tabled(rename = "Node")]
+    node_id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "RPC URL")]
+    rpc_url: String,
+    #[tabled(rename = "Started")]
+    started: String,
+    #[tabled(rename = "Uptime")]
+    uptime: String,
+}
+
+impl NodeDisplay {
+    pub fn new(node: &NodeInfo, now: DateTime<Utc>) -> Self {
+        Self {
+            node_id: node.node_id.clone(),
+            name: node.display_name().to_string(),
+            status: node.status.to_string(),
+            rpc_url: node.rpc_url.clone(),
+            started: node
+                .started_at
+                .map(|t| format_relative(t, now))
+                .unwrap_or_else(|| "-".to_string()),
+            uptime: node
+                .uptime(now)
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+/// Sort nodes most recently started first, nodes without a start time last
+pub fn sort_by_start(nodes: &mut [NodeInfo]) {
+    nodes.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+}
+
+/// Startup phases recorded for a node's last start
+pub fn startup_profile(node: &NodeInfo) -> chain_forge_common::Result<StartupProfile> {
+    Ok(match node.chain {
+        ChainType::Solana => SolanaInstanceInfo::load(&node.instance_id)?.startup,
+        ChainType::Bitcoin => BitcoinInstanceInfo::load(&node.instance_id)?.startup,
+        ChainType::Ethereum => EthereumInstanceInfo::load(&node.instance_id)?.startup,
+    })
+}
+
+/// A node's startup phases, for JSON output
+#[derive(Serialize)]
+pub struct ProfileJson<'a> {
+    node_id: &'a str,
+    total_ms: u64,
+    #[serde(flatten)]
+    profile: &'a StartupProfile,
+}
+
+impl<'a> ProfileJson<'a> {
+    pub fn new(node_id: &'a str, profile: &'a StartupProfile) -> Self {
+        Self {
+            node_id,
+            total_ms: profile.total_ms(),
+            profile,
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub struct PhaseDisplay {
+    #[tabled(rename = "Phase")]
+    phase: String,
+    #[tabled(rename = "Duration")]
+    duration: String,
+    #[tabled(rename = "Share")]
+    share: String,
+}
+
+impl PhaseDisplay {
+    /// One row per phase
+    pub fn rows(profile: &StartupProfile) -> Vec<Self> {
+        let total = profile.total_ms().max(1) as f64;
+        profile
+            .phases
+            .iter()
+            .map(|timing| Self {
+                phase: timing.phase.to_string(),
+                duration: format_millis(timing.duration_ms),
+                share: format!("{:.0}%", timing.duration_ms as f64 * 100.0 / total),
+            })
+            .collect()
+    }
+}
+
+/// Milliseconds as seconds for display, e.g. `1.25s`
+pub fn format_millis(ms: u64) -> String {
+    format!("{:.2}s", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_forge_common::{NodeStatus, StartupPhase};
+    use chrono::Duration;
+
+    fn node(instance: &str) -> NodeInfo {
+        NodeInfo::new(
+            ChainType::Solana,
+            instance,
+            None,
+            "http://localhost:8899".to_string(),
+            8899,
+            10,
+        )
+    }
+
+    #[test]
+    fn test_node_display() {
+        let node = node("dev");
+        let now = node.started_at.unwrap() + Duration::minutes(134);
+
+        let row = NodeDisplay::new(&node, now);
+        assert_eq!(row.name, "dev");
+        assert_eq!(row.started, "2h ago");
+        assert_eq!(row.uptime, "2h 14m");
+
+        let mut stopped = node.clone();
+        stopped.status = NodeStatus::Stopped;
+        assert_eq!(NodeDisplay::new(&stopped, now).uptime, "-");
+    }
+
+    #[test]
+    fn test_node_json_keeps_rfc3339() {
+        let node = node("dev");
+        let started_at = node.started_at.unwrap();
+        let json =
+            serde_json::to_value(NodeJson::new(&node, started_at + Duration::seconds(90))).unwrap();
+
+        assert_eq!(json["node_id"], "solana:dev");
+        assert!(DateTime::parse_from_rfc3339(json["started_at"].as_str().unwrap()).is_ok());
+        assert_eq!(json["uptime_secs"], 90);
+        assert_eq!(json["uptime"], "1m 30s");
+        assert_eq!(json["started"], "1m ago");
+    }
+
+    #[test]
+    fn test_phase_display() {
+        let mut profile = StartupProfile::default();
+        profile.record(StartupPhase::Spawn, std::time::Duration::from_millis(1000));
+        profile.record(
+            StartupPhase::Funding,
+            std::time::Duration::from_millis(3000),
+        );
+
+        let rows = PhaseDisplay::rows(&profile);
+        assert_eq!(rows[0].phase, "spawn");
+        assert_eq!(rows[0].duration, "1.00s");
+        assert_eq!(rows[1].share, "75%");
+
+        let json = serde_json::to_value(ProfileJson::new("solana:dev", &profile)).unwrap();
+        assert_eq!(json["total_ms"], 4000);
+        assert_eq!(json["phases"][1]["phase"], "funding");
+    }
+
+    #[test]
+    fn test_sort_by_start() {
+        let mut older = node("older");
+        older.started_at = older.started_at.map(|t| t - Duration::hours(1));
+        let mut unknown = node("unknown");
+        unknown.started_at = None;
+        let mut nodes = vec![unknown, older, node("newer")];
+
+        sort_by_start(&mut nodes);
+        let order: Vec<&str> = nodes.iter().map(|n| n.instance_id.as_str()).collect();
+        assert_eq!(order, ["newer", "older", "unknown"]);
+    }
+}
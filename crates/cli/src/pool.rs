@@ -0,0 +1,389 @@
+//! Warm pool of pre-started nodes for `cf pool`
+//!
+//! `cf pool fill` starts idle instances in the background with the chain
+//! CLIs. Tests lease one with `cf pool acquire`, which resets it in place
+//! like `cf reset`, and hand it back with `cf pool release`.
+//! Pool membership is kept in `~/.chain-forge/pool.json`.
+
+// Allow fs2 trait methods that have the same name as std methods stabilized in Rust 1.89
+#![allow(clippy::incompatible_msrv)]
+
+use chain_forge_common::{ChainType, NodeInfo, NodeRegistry, NodeStatus};
+use chain_forge_config::Config;
+use chrono::{DateTime, Utc};
+use eyre::{bail, eyre, Result, WrapErr};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tabled::Tabled;
+
+/// First RPC port handed to pooled Solana validators
+pub const SOLANA_BASE_PORT: u16 = 9100;
+/// Port spacing between pooled Solana validators (each uses RPC + 1002..1504)
+const SOLANA_PORT_STEP: u16 = 600;
+/// First RPC port handed to pooled Bitcoin nodes (P2P uses the next port)
+pub const BITCOIN_BASE_PORT: u16 = 18500;
+const BITCOIN_PORT_STEP: u16 = 2;
+/// First RPC port handed to pooled anvil nodes
+pub const ETHEREUM_BASE_PORT: u16 = 8600;
+const ETHEREUM_PORT_STEP: u16 = 1;
+
+/// A node owned by the pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolMember {
+    pub node_id: String,
+    pub chain: ChainType,
+    pub instance_id: String,
+    pub port: u16,
+    /// Initial balance each account is reset to on acquire
+    pub balance: f64,
+    /// Process ID of the chain CLI running the node
+    pub pid: u32,
+    /// When the current lease started, if leased
+    pub leased_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Tabled)]
+pub struct PoolMemberDisplay {
+    #[tabled(rename = "Node")]
+    node_id: String,
+    #[tabled(rename = "Port")]
+    port: u16,
+    #[tabled(rename = "State")]
+    state: String,
+}
+
+impl PoolMemberDisplay {
+    pub fn new(member: &PoolMember, ready: bool) -> Self {
+        let state = match (member.leased_at, ready) {
+            (Some(at), _) => format!("leased since {}", at.format("%H:%M:%S")),
+            (None, true) => "idle".to_string(),
+            (None, false) => "starting".to_string(),
+        };
+        Self {
+            node_id: member.node_id.clone(),
+            port: member.port,
+            state,
+        }
+    }
+}
+
+/// Persistent pool membership
+pub struct Pool {
+    path: PathBuf,
+}
+
+impl Pool {
+    /// Create a Pool using the default data directory
+    pub fn new() -> Self {
+        Self::with_path(Config::data_dir().join("pool.json"))
+    }
+
+    /// Create a Pool with a custom path (mainly for testing)
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Directory for pooled node logs
+    fn log_dir(&self) -> PathBuf {
+        self.path.with_file_name("pool-logs")
+    }
+
+    /// Run `f` on the member list while holding an exclusive lock, then save it
+    ///
+    /// Concurrent `cf pool acquire` calls from parallel tests must never hand
+    /// out the same member twice.
+    fn update<T>(&self, f: impl FnOnce(&mut Vec<PoolMember>) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        lock.lock_exclusive()
+            .map_err(|e| eyre!("Failed to acquire lock on pool: {}", e))?;
+
+        let mut members = self.members()?;
+        let result = f(&mut members);
+        if result.is_ok() {
+            fs::write(&self.path, serde_json::to_string_pretty(&members)?)?;
+        }
+
+        lock.unlock()
+            .map_err(|e| eyre!("Failed to release lock on pool: {}", e))?;
+        result
+    }
+
+    /// All pool members
+    pub fn members(&self) -> Result<Vec<PoolMember>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let json = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Start `count` more pooled nodes for a chain in the background
+    pub fn fill(&self, chain: ChainType, count: u32, balance: f64) -> Result<Vec<PoolMember>> {
+        let log_dir = self.log_dir();
+        fs::create_dir_all(&log_dir)?;
+
+        self.update(|members| {
+            let mut started = Vec::new();
+            let mut slot = 0u16;
+
+            while started.len() < count as usize {
+                let instance_id = format!("pool-{}-{}", chain, slot);
+                let port = match chain {
+                    ChainType::Solana => SOLANA_BASE_PORT + slot * SOLANA_PORT_STEP,
+                    ChainType::Bitcoin => BITCOIN_BASE_PORT + slot * BITCOIN_PORT_STEP,
+                    ChainType::Ethereum => ETHEREUM_BASE_PORT + slot * ETHEREUM_PORT_STEP,
+                };
+                slot += 1;
+
+                if members.iter().any(|m| m.instance_id == instance_id) {
+                    continue;
+                }
+
+                let log = File::create(log_dir.join(format!("{}.log", instance_id)))?;
+                let mut cmd = match chain {
+                    ChainType::Solana => {
+                        let mut cmd = Command::new("cf-solana");
+                        cmd.args(["start", "--port", &port.to_string()]);
+                        cmd
+                    }
+                    ChainType::Bitcoin => {
+                        let mut cmd = Command::new("cf-bitcoin");
+                        cmd.args([
+                            "start",
+                            "--rpc-port",
+                            &port.to_string(),
+                            "--p2p-port",
+                            &(port + 1).to_string(),
+                        ]);
+                        cmd
+                    }
+                    ChainType::Ethereum => {
+                        let mut cmd = Command::new("cf-ethereum");
+                        cmd.args(["start", "--port", &port.to_string()]);
+                        cmd
+                    }
+                };
+                let child = cmd
+                    .args([
+                        "--instance",
+                        &instance_id,
+                        "--balance",
+                        &balance.to_string(),
+                    ])
+                    .stdin(Stdio::null())
+                    .stdout(log.try_clone()?)
+                    .stderr(log)
+                    .spawn()
+                    .wrap_err_with(|| format!("Failed to start {}", instance_id))?;
+
+                let member = PoolMember {
+                    node_id: NodeRegistry::node_id(chain, &instance_id),
+                    chain,
+                    instance_id,
+                    port,
+                    balance,
+                    pid: child.id(),
+                    leased_at: None,
+                };
+                members.push(member.clone());
+                started.push(member);
+            }
+
+            Ok(started)
+        })
+    }
+
+    /// Lease an idle, ready member of a chain
+    pub fn acquire(
+        &self,
+        chain: ChainType,
+        is_ready: impl Fn(&PoolMember) -> bool,
+    ) -> Result<Option<PoolMember>> {
+        self.update(|members| {
+            let member = members
+                .iter_mut()
+                .find(|m| m.chain == chain && m.leased_at.is_none() && is_ready(m));
+            Ok(member.map(|m| {
+                m.leased_at = Some(Utc::now());
+                m.clone()
+            }))
+        })
+    }
+
+    /// Return a leased member to the pool
+    pub fn release(&self, node_id: &str) -> Result<PoolMember> {
+        self.update(|members| {
+            let member = members
+                .iter_mut()
+                .find(|m| m.node_id == node_id)
+                .ok_or_else(|| eyre!("'{}' is not a pool member", node_id))?;
+            if member.leased_at.is_none() {
+                bail!("'{}' is not leased", node_id);
+            }
+            member.leased_at = None;
+            Ok(member.clone())
+        })
+    }
+
+    /// Remove idle members (all chains, or one) and return them
+    ///
+    /// Leased members are left in place until released.
+    pub fn drain(&self, chain: Option<ChainType>) -> Result<Vec<PoolMember>> {
+        self.update(|members| {
+            let (drained, kept): (Vec<_>, Vec<_>) = members
+                .drain(..)
+                .partition(|m| m.leased_at.is_none() && chain.unwrap_or(m.chain) == m.chain);
+            *members = kept;
+            Ok(drained)
+        })
+    }
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a pooled node has finished starting (it registers once funded)
+pub fn is_ready(registry: &NodeRegistry, member: &PoolMember) -> bool {
+    matches!(
+        registry.get(&member.node_id),
+        Ok(Some(NodeInfo {
+            status: NodeStatus::Running,
+            ..
+        }))
+    )
+}
+
+/// Reset a leased node in place (see `cf reset`) so each lease starts clean
+pub fn reset_member(member: &PoolMember) -> Result<()> {
+    match member.chain {
+        ChainType::Solana => {
+            chain_forge_solana_core::reset_instance(&member.instance_id, member.balance)?;
+        }
+        ChainType::Bitcoin => {
+            chain_forge_bitcoin_core::reset_instance(&member.instance_id, member.balance)?;
+        }
+        ChainType::Ethereum => {
+            chain_forge_ethereum_core::reset_instance(&member.instance_id, member.balance)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ask a pooled node's CLI to shut down as if Ctrl+C was pressed
+#[cfg(unix)]
+pub fn stop_member(member: &PoolMember) -> Result<()> {
+    let status = Command::new("kill")
+        .arg("-INT")
+        .arg(member.pid.to_string())
+        .status()?;
+    if !status.success() {
+        bail!("Failed to stop {} (pid {})", member.node_id, member.pid);
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn stop_member(member: &PoolMember) -> Result<()> {
+    bail!(
+        "Stopping pooled nodes is only supported on Unix; stop {} (pid {}) manually",
+        member.node_id,
+        member.pid
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn member(chain: ChainType, instance_id: &str) -> PoolMember {
+        PoolMember {
+            node_id: NodeRegistry::node_id(chain, instance_id),
+            chain,
+            instance_id: instance_id.to_string(),
+            port: 9100,
+            balance: 10.0,
+            pid: 1,
+            leased_at: None,
+        }
+    }
+
+    fn pool_with(members: Vec<PoolMember>) -> (Pool, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let pool = Pool::with_path(dir.path().join("pool.json"));
+        pool.update(|m| {
+            *m = members;
+            Ok(())
+        })
+        .unwrap();
+        (pool, dir)
+    }
+
+    #[test]
+    fn test_acquire_and_release() {
+        let (pool, _dir) = pool_with(vec![
+            member(ChainType::Solana, "pool-solana-0"),
+            member(ChainType::Bitcoin, "pool-bitcoin-0"),
+        ]);
+
+        let leased = pool.acquire(ChainType::Bitcoin, |_| true).unwrap().unwrap();
+        assert_eq!(leased.node_id, "bitcoin:pool-bitcoin-0");
+        assert!(leased.leased_at.is_some());
+
+        // The only Bitcoin member is taken
+        assert!(pool
+            .acquire(ChainType::Bitcoin, |_| true)
+            .unwrap()
+            .is_none());
+
+        pool.release(&leased.node_id).unwrap();
+        assert!(pool.release(&leased.node_id).is_err());
+        assert!(pool
+            .acquire(ChainType::Bitcoin, |_| true)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_acquire_skips_members_not_ready() {
+        let (pool, _dir) = pool_with(vec![
+            member(ChainType::Solana, "pool-solana-0"),
+            member(ChainType::Solana, "pool-solana-1"),
+        ]);
+
+        let leased = pool
+            .acquire(ChainType::Solana, |m| m.instance_id == "pool-solana-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(leased.instance_id, "pool-solana-1");
+    }
+
+    #[test]
+    fn test_drain_keeps_leased_members() {
+        let (pool, _dir) = pool_with(vec![
+            member(ChainType::Solana, "pool-solana-0"),
+            member(ChainType::Solana, "pool-solana-1"),
+            member(ChainType::Bitcoin, "pool-bitcoin-0"),
+        ]);
+        pool.acquire(ChainType::Solana, |_| true).unwrap();
+
+        let drained = pool.drain(Some(ChainType::Solana)).unwrap();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(drained[0].instance_id, "pool-solana-1");
+        assert_eq!(pool.members().unwrap().len(), 2);
+    }
+}
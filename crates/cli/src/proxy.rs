@@ -0,0 +1,293 @@
+//! Stable local URLs for node RPC endpoints, for `cf proxy start`
+//!
+//! Serves `http://localhost:<port>/<alias>` and forwards each request to the
+//! RPC URL the node registry has for that alias at the time, so frontend
+//! `.env` files can point at one address no matter which port an instance
+//! got. Every registered node is reachable as `<chain>-<instance>` (e.g.
+//! `solana-dev`); `--alias` adds names of your own.
+
+use axum::{
+    body::Bytes,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use chain_forge_common::{validate_name, NodeInfo, NodeRegistry, NodeStatus};
+use eyre::{eyre, Result};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Arc;
+use tabled::Tabled;
+use tower_http::cors::CorsLayer;
+
+/// Port the proxy listens on by default
+pub const DEFAULT_PORT: u16 = 7000;
+
+/// Request headers passed on to the node
+const FORWARDED_HEADERS: [&str; 3] = ["content-type", "accept", "authorization"];
+
+/// Alias every node gets: `<chain>-<instance>`
+pub fn default_alias(node: &NodeInfo) -> String {
+    format!("{}-{}", node.chain, node.instance_id)
+}
+
+/// Parse `NAME=NODE_ID` pairs given with `--alias`
+pub fn parse_aliases(pairs: &[String]) -> Result<BTreeMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (name, node_id) = pair
+                .split_once('=')
+                .ok_or_else(|| eyre!("Invalid --alias '{}'; expected NAME=NODE_ID", pair))?;
+            validate_name(name).map_err(|e| eyre!("Invalid alias '{}': {}", name, e))?;
+            Ok((name.to_string(), node_id.to_string()))
+        })
+        .collect()
+}
+
+/// Every alias and the node it routes to, sorted by alias
+///
+/// Custom aliases for nodes that aren't registered are left out.
+pub fn routes(nodes: &[NodeInfo], aliases: &BTreeMap<String, String>) -> Vec<(String, NodeInfo)> {
+    let mut routes: BTreeMap<String, NodeInfo> = nodes
+        .iter()
+        .map(|node| (default_alias(node), node.clone()))
+        .collect();
+    for (alias, node_id) in aliases {
+        if let Some(node) = nodes.iter().find(|n| &n.node_id == node_id) {
+            routes.insert(alias.clone(), node.clone());
+        }
+    }
+    routes.into_iter().collect()
+}
+
+/// Split a request path into its alias and the path to forward
+///
+/// `/solana-dev` and `/solana-dev/` forward to the RPC root; anything after
+/// the alias is kept (`/bitcoin-dev/wallet/x` -> `/wallet/x`).
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    let path = path.strip_prefix('/')?;
+    let (alias, rest) = match path.find('/') {
+        Some(i) => (&path[..i], &path[i..]),
+        None => (path, ""),
+    };
+    (!alias.is_empty()).then_some((alias, rest))
+}
+
+#[derive(Tabled)]
+pub struct RouteDisplay {
+    #[tabled(rename = "URL")]
+    url: String,
+    #[tabled(rename = "Node")]
+    node_id: String,
+    #[tabled(rename = "Target")]
+    target: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+impl RouteDisplay {
+    pub fn new(port: u16, alias: &str, node: &NodeInfo) -> Self {
+        Self {
+            url: format!("http://localhost:{}/{}", port, alias),
+            node_id: node.node_id.clone(),
+            target: node.rpc_url.clone(),
+            status: node.status.to_string(),
+        }
+    }
+}
+
+/// Serve the proxy on `port` until interrupted
+pub async fn serve(port: u16, aliases: BTreeMap<String, String>) -> Result<()> {
+    let aliases = Arc::new(aliases);
+    let app = Router::new()
+        .fallback(
+            move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+                forward(aliases.clone(), method, uri, headers, body)
+            },
+        )
+        // Frontends on other ports call the proxy straight from the browser
+        .layer(CorsLayer::permissive());
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| eyre!("Failed to listen on port {}: {}", port, e))?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+async fn forward(
+    aliases: Arc<BTreeMap<String, String>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    // The registry is read on every request so restarted nodes are picked up
+    match tokio::task::spawn_blocking(move || {
+        forward_blocking(&aliases, method, &uri, &headers, &body)
+    })
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &format!("Proxy failed: {}", e),
+        ),
+    }
+}
+
+fn forward_blocking(
+    aliases: &BTreeMap<String, String>,
+    method: Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Response {
+    let Some((alias, rest)) = split_path(uri.path()) else {
+        return error(
+            StatusCode::NOT_FOUND,
+            "Request a node as /<alias>, e.g. /solana-default",
+        );
+    };
+
+    let nodes = match NodeRegistry::new().list() {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed to read node registry: {}", e),
+            )
+        }
+    };
+    let Some((_, node)) = routes(&nodes, aliases)
+        .into_iter()
+        .find(|(name, _)| name == alias)
+    else {
+        return error(
+            StatusCode::NOT_FOUND,
+            &format!("No node with alias '{}'", alias),
+        );
+    };
+    if node.status != NodeStatus::Running {
+        return error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &format!("{} is not running", node.node_id),
+        );
+    }
+
+    let mut target = format!("{}{}", node.rpc_url.trim_end_matches('/'), rest);
+    if let Some(query) = uri.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+
+    let mut request = ureq::request(method.as_str(), &target);
+    for name in FORWARDED_HEADERS {
+        if let Some(value) = headers.get(name).and_then(|v| v.to_str().ok()) {
+            request = request.set(name, value);
+        }
+    }
+    let result = if body.is_empty() {
+        request.call()
+    } else {
+        request.send_bytes(body)
+    };
+
+    // Error statuses from the node are passed through as they are
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_GATEWAY,
+                &format!(
+                    "Failed to reach {} at {}: {}",
+                    node.node_id, node.rpc_url, e
+                ),
+            )
+        }
+    };
+
+    let status = StatusCode::from_u16(response.status()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let content_type = response
+        .header("content-type")
+        .and_then(|v| HeaderValue::from_str(v).ok());
+    let mut bytes = Vec::new();
+    if let Err(e) = response.into_reader().read_to_end(&mut bytes) {
+        return error(
+            StatusCode::BAD_GATEWAY,
+            &format!("Failed to read response from {}: {}", node.node_id, e),
+        );
+    }
+
+    let mut response = (status, bytes).into_response();
+    if let Some(content_type) = content_type {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_forge_common::ChainType;
+
+    fn node(chain: ChainType, instance_id: &str) -> NodeInfo {
+        NodeInfo::new(
+            chain,
+            instance_id,
+            None,
+            "http://localhost:8899".to_string(),
+            8899,
+            10,
+        )
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(split_path("/solana-dev"), Some(("solana-dev", "")));
+        assert_eq!(split_path("/solana-dev/"), Some(("solana-dev", "/")));
+        assert_eq!(
+            split_path("/bitcoin-dev/wallet/chain-forge"),
+            Some(("bitcoin-dev", "/wallet/chain-forge"))
+        );
+        assert_eq!(split_path("/"), None);
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        let aliases = parse_aliases(&["frontend=solana:dev".to_string()]).unwrap();
+        assert_eq!(aliases["frontend"], "solana:dev");
+
+        assert!(parse_aliases(&["frontend".to_string()]).is_err());
+        assert!(parse_aliases(&["Bad Name=solana:dev".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_routes() {
+        let nodes = vec![
+            node(ChainType::Solana, "dev"),
+            node(ChainType::Bitcoin, "dev"),
+        ];
+        let aliases = parse_aliases(&[
+            "frontend=solana:dev".to_string(),
+            "missing=ethereum:dev".to_string(),
+        ])
+        .unwrap();
+
+        let routes = routes(&nodes, &aliases);
+        let names: Vec<&str> = routes.iter().map(|(alias, _)| alias.as_str()).collect();
+        assert_eq!(names, vec!["bitcoin-dev", "frontend", "solana-dev"]);
+        assert_eq!(routes[1].1.node_id, "solana:dev");
+    }
+}
@@ -0,0 +1,135 @@
+//! Project scaffolding for `cf new`
+
+use chain_forge_common::validate_name;
+use clap::ValueEnum;
+use eyre::{bail, Result, WrapErr};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A template file: path relative to the project root, contents, and whether it is executable
+struct TemplateFile {
+    path: &'static str,
+    contents: &'static str,
+    executable: bool,
+}
+
+macro_rules! template_file {
+    ($template:literal, $path:literal) => {
+        template_file!($template, $path, false)
+    };
+    ($template:literal, $path:literal, $executable:expr) => {
+        TemplateFile {
+            path: $path,
+            contents: include_str!(concat!("../templates/", $template, "/", $path)),
+            executable: $executable,
+        }
+    };
+}
+
+const SOLANA_COUNTER: &[TemplateFile] = &[
+    template_file!("solana-counter", "README.md"),
+    template_file!("solana-counter", ".gitignore"),
+    template_file!("solana-counter", "chain-forge.toml"),
+    template_file!("solana-counter", "programs/counter/Cargo.toml"),
+    template_file!("solana-counter", "programs/counter/src/lib.rs"),
+    template_file!("solana-counter", "client/package.json"),
+    template_file!("solana-counter", "client/tsconfig.json"),
+    template_file!("solana-counter", "client/src/index.ts"),
+    template_file!("solana-counter", "scripts/test.sh", true),
+];
+
+/// Available project templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// Solana counter program with a TypeScript client and test script
+    SolanaCounter,
+}
+
+impl Template {
+    fn files(self) -> &'static [TemplateFile] {
+        match self {
+            Template::SolanaCounter => SOLANA_COUNTER,
+        }
+    }
+}
+
+/// Render template contents for a project name
+fn render(contents: &str, project_name: &str) -> String {
+    contents
+        .replace("{{project_name}}", project_name)
+        .replace("{{crate_name}}", &project_name.replace('-', "_"))
+}
+
+/// Scaffold a new project from a template into `parent/name`
+///
+/// The project name doubles as the Chain Forge instance ID, so it must pass
+/// the same validation as instance names.
+pub fn scaffold(parent: &Path, name: &str, template: Template) -> Result<PathBuf> {
+    validate_name(name).wrap_err("Invalid project name")?;
+
+    let root = parent.join(name);
+    if root.exists() {
+        bail!("Destination {:?} already exists", root);
+    }
+
+    for file in template.files() {
+        let path = root.join(file.path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("Failed to create directory {:?}", dir))?;
+        }
+        fs::write(&path, render(file.contents, name))
+            .wrap_err_with(|| format!("Failed to write {:?}", path))?;
+
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render() {
+        let rendered = render("{{project_name}} / {{crate_name}}", "my-app");
+        assert_eq!(rendered, "my-app / my_app");
+    }
+
+    #[test]
+    fn test_scaffold_solana_counter() {
+        let temp = TempDir::new().unwrap();
+        let root = scaffold(temp.path(), "my-app", Template::SolanaCounter).unwrap();
+
+        for file in SOLANA_COUNTER {
+            let contents = fs::read_to_string(root.join(file.path)).unwrap();
+            assert!(
+                !contents.contains("{{"),
+                "unrendered placeholder in {}",
+                file.path
+            );
+        }
+
+        let cargo_toml = fs::read_to_string(root.join("programs/counter/Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("name = \"my_app\""));
+    }
+
+    #[test]
+    fn test_scaffold_rejects_invalid_name() {
+        let temp = TempDir::new().unwrap();
+        assert!(scaffold(temp.path(), "My App", Template::SolanaCounter).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_rejects_existing_directory() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("my-app")).unwrap();
+        assert!(scaffold(temp.path(), "my-app", Template::SolanaCounter).is_err());
+    }
+}
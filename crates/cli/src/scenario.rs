@@ -0,0 +1,721 @@
+//! Declarative multi-chain environments for `cf run`
+//!
+//! A scenario is a YAML file listing the nodes to start and what to set up
+//! on each of them, in order: tokens, transfers, then blocks to mine.
+//!
+//! ```yaml
+//! nodes:
+//!   - chain: solana
+//!     instance: shop
+//!     accounts: 3
+//!     balance: 50
+//!     tokens:
+//!       - name: usdc
+//!         decimals: 6
+//!         mint:
+//!           - to: "{{accounts.1}}"
+//!             amount: 1000
+//!     transfers:
+//!       - from: "{{accounts.1}}"
+//!         to: "{{accounts.2}}"
+//!         amount: 25
+//!         token: usdc
+//!   - chain: bitcoin
+//!     instance: shop
+//!     transfers:
+//!       - from: "{{accounts.0}}"
+//!         to: "{{accounts.1}}"
+//!         amount: 0.5
+//!     mine: 6
+//! ```
+//!
+//! Running a scenario is idempotent. Nodes that are already running are
+//! reused, and the actions applied to each node are recorded in
+//! `scenario.json` in its instance directory, so running the file again only
+//! applies actions added since. Restarting a node clears the record along
+//! with the rest of its instance data. Like transaction templates, every
+//! step runs a chain CLI against the instance.
+
+use crate::tx_template::{chain_cli, instance_accounts, placeholders, substitute};
+use chain_forge_cli_utils::daemon;
+use chain_forge_common::{validate_name, ChainType, NodeInfo, NodeRegistry, NodeStatus};
+use eyre::{bail, eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// File in the instance directory recording what a scenario applied
+const STATE_FILE: &str = "scenario.json";
+
+/// How long to wait for a node started in the background to come up
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Nodes to start and set up
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub nodes: Vec<NodeSpec>,
+}
+
+/// A node and the fixtures to create on it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeSpec {
+    pub chain: ChainType,
+    #[serde(default = "default_instance")]
+    pub instance: String,
+    /// Number of accounts (default: the chain CLI's default)
+    pub accounts: Option<u32>,
+    /// Initial balance of each account
+    pub balance: Option<f64>,
+    /// RPC port
+    pub port: Option<u16>,
+    /// SPL tokens to create (Solana only)
+    #[serde(default)]
+    pub tokens: Vec<TokenSpec>,
+    #[serde(default)]
+    pub transfers: Vec<TransferSpec>,
+    /// Blocks to mine after the transfers (Bitcoin only)
+    #[serde(default)]
+    pub mine: u32,
+}
+
+fn default_instance() -> String {
+    "default".to_string()
+}
+
+/// An SPL token mint and its initial supply
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenSpec {
+    /// Name transfers refer to the token by
+    pub name: String,
+    /// Mint authority, an account of the instance
+    #[serde(default = "default_authority")]
+    pub authority: String,
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    #[serde(default)]
+    pub mint: Vec<MintSpec>,
+}
+
+fn default_authority() -> String {
+    "{{accounts.0}}".to_string()
+}
+
+fn default_decimals() -> u8 {
+    9
+}
+
+/// Tokens minted to an address
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MintSpec {
+    pub to: String,
+    pub amount: f64,
+}
+
+/// A transfer of the chain's native coin, or of a scenario token
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransferSpec {
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    /// Name of a token of the same node to transfer instead of the native coin
+    pub token: Option<String>,
+}
+
+/// One setup step, run as a chain CLI command
+///
+/// Addresses may hold `{{accounts.<index or label>}}` placeholders, and
+/// tokens are referred to by name until their mint exists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum Action {
+    CreateToken {
+        name: String,
+        authority: String,
+        decimals: u8,
+    },
+    MintToken {
+        token: String,
+        authority: String,
+        to: String,
+        amount: f64,
+    },
+    Transfer {
+        from: String,
+        to: String,
+        amount: f64,
+        token: Option<String>,
+    },
+    Mine {
+        blocks: u32,
+    },
+}
+
+impl Action {
+    /// Chain CLI arguments, before substitution
+    ///
+    /// Token mints are referred to as `{{tokens.<name>}}`.
+    pub fn args(&self) -> Vec<String> {
+        let mint = |name: &str| format!("{{{{tokens.{}}}}}", name);
+        match self {
+            Action::CreateToken {
+                authority,
+                decimals,
+                ..
+            } => vec![
+                "token".to_string(),
+                "create".to_string(),
+                authority.clone(),
+                "--decimals".to_string(),
+                decimals.to_string(),
+                "--format".to_string(),
+                "json".to_string(),
+            ],
+            Action::MintToken {
+                token,
+                authority,
+                to,
+                amount,
+            } => vec![
+                "token".to_string(),
+                "mint".to_string(),
+                mint(token),
+                authority.clone(),
+                amount.to_string(),
+                to.clone(),
+            ],
+            Action::Transfer {
+                from,
+                to,
+                amount,
+                token: Some(token),
+            } => vec![
+                "token".to_string(),
+                "transfer".to_string(),
+                mint(token),
+                from.clone(),
+                to.clone(),
+                amount.to_string(),
+            ],
+            Action::Transfer {
+                from,
+                to,
+                amount,
+                token: None,
+            } => vec![
+                "transfer".to_string(),
+                from.clone(),
+                to.clone(),
+                amount.to_string(),
+            ],
+            Action::Mine { blocks } => {
+                vec![
+                    "mine".to_string(),
+                    "--blocks".to_string(),
+                    blocks.to_string(),
+                ]
+            }
+        }
+    }
+}
+
+impl Scenario {
+    /// Parse and check a scenario
+    pub fn parse(yaml: &str) -> Result<Self> {
+        let scenario: Scenario = serde_norway::from_str(yaml)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.nodes.is_empty() {
+            bail!("Scenario has no nodes");
+        }
+
+        let mut node_ids = BTreeSet::new();
+        for node in &self.nodes {
+            let node_id = node.node_id();
+            validate_name(&node.instance)
+                .map_err(|e| eyre!("{}: invalid instance name: {}", node_id, e))?;
+            if !node_ids.insert(node_id.clone()) {
+                bail!("{} is listed more than once", node_id);
+            }
+            node.validate()
+                .wrap_err_with(|| format!("Invalid node {}", node_id))?;
+        }
+        Ok(())
+    }
+}
+
+impl NodeSpec {
+    pub fn node_id(&self) -> String {
+        NodeRegistry::node_id(self.chain, &self.instance)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.tokens.is_empty() && self.chain != ChainType::Solana {
+            bail!("tokens are only supported on Solana");
+        }
+        if self.mine > 0 && self.chain != ChainType::Bitcoin {
+            bail!("mine is only supported on Bitcoin");
+        }
+
+        let mut names = BTreeSet::new();
+        for token in &self.tokens {
+            if !names.insert(token.name.as_str()) {
+                bail!("token {} is defined more than once", token.name);
+            }
+        }
+        for (i, transfer) in self.transfers.iter().enumerate() {
+            match &transfer.token {
+                Some(token) if !names.contains(token.as_str()) => {
+                    bail!("transfer {}: unknown token {}", i + 1, token);
+                }
+                None if self.chain == ChainType::Ethereum => {
+                    bail!("transfer {}: cf-ethereum has no transfer command", i + 1);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Chain CLI that starts and sets up the node
+    pub fn program(&self) -> &'static str {
+        chain_cli(self.chain)
+    }
+
+    /// Chain CLI arguments that start the node
+    ///
+    /// Solana and Bitcoin nodes are started with `--detach`; cf-ethereum
+    /// can't detach, so its caller runs it in the background instead.
+    pub fn start_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "start".to_string(),
+            "--instance".to_string(),
+            self.instance.clone(),
+        ];
+        if let Some(accounts) = self.accounts {
+            args.extend(["--accounts".to_string(), accounts.to_string()]);
+        }
+        if let Some(balance) = self.balance {
+            args.extend(["--balance".to_string(), balance.to_string()]);
+        }
+        if let Some(port) = self.port {
+            let flag = match self.chain {
+                ChainType::Bitcoin => "--rpc-port",
+                ChainType::Solana | ChainType::Ethereum => "--port",
+            };
+            args.extend([flag.to_string(), port.to_string()]);
+        }
+        if self.chain != ChainType::Ethereum {
+            args.push(daemon::DETACH_FLAG.to_string());
+        }
+        args
+    }
+
+    /// Setup actions in the order they are applied
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for token in &self.tokens {
+            actions.push(Action::CreateToken {
+                name: token.name.clone(),
+                authority: token.authority.clone(),
+                decimals: token.decimals,
+            });
+            actions.extend(token.mint.iter().map(|mint| Action::MintToken {
+                token: token.name.clone(),
+                authority: token.authority.clone(),
+                to: mint.to.clone(),
+                amount: mint.amount,
+            }));
+        }
+        actions.extend(self.transfers.iter().map(|transfer| Action::Transfer {
+            from: transfer.from.clone(),
+            to: transfer.to.clone(),
+            amount: transfer.amount,
+            token: transfer.token.clone(),
+        }));
+        if self.mine > 0 {
+            actions.push(Action::Mine { blocks: self.mine });
+        }
+        actions
+    }
+
+    /// Start the node and wait for it to be running
+    pub fn start(&self) -> Result<()> {
+        let program = self.program();
+        let args = self.start_args();
+        if self.chain != ChainType::Ethereum {
+            let status = Command::new(program)
+                .args(&args)
+                .status()
+                .map_err(|e| eyre!("Failed to run {}: {}", program, e))?;
+            if !status.success() {
+                bail!("{} {} failed ({})", program, args.join(" "), status);
+            }
+            return Ok(());
+        }
+
+        // Run in the background the way `--detach` would, logging beside
+        // the instance directory
+        let instance_dir = self.instance_dir();
+        if let Some(parent) = instance_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let log_path = daemon::log_path(&instance_dir);
+        let log = File::create(&log_path)
+            .wrap_err_with(|| format!("Failed to create {}", log_path.display()))?;
+        let started = chrono::Utc::now();
+        let mut child = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .map_err(|e| eyre!("Failed to run {}: {}", program, e))?;
+
+        let registry = NodeRegistry::new();
+        let node_id = self.node_id();
+        let deadline = Instant::now() + START_TIMEOUT;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                bail!(
+                    "{} exited during startup ({}); see {}",
+                    node_id,
+                    status,
+                    log_path.display()
+                );
+            }
+            let ready = registry.get(&node_id)?.is_some_and(|node| {
+                node.status == NodeStatus::Running && node.started_at.is_some_and(|t| t >= started)
+            });
+            if ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                bail!(
+                    "{} did not start within {}s; see {}",
+                    node_id,
+                    START_TIMEOUT.as_secs(),
+                    log_path.display()
+                );
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    /// Directory holding the node's instance data
+    pub fn instance_dir(&self) -> PathBuf {
+        match self.chain {
+            ChainType::Solana => {
+                chain_forge_solana_core::SolanaConfig::with_instance(&self.instance).instance_dir()
+            }
+            ChainType::Bitcoin => {
+                chain_forge_bitcoin_core::BitcoinConfig::with_instance(&self.instance)
+                    .instance_dir()
+            }
+            ChainType::Ethereum => {
+                chain_forge_ethereum_core::EthereumConfig::with_instance(&self.instance)
+                    .instance_dir()
+            }
+        }
+    }
+
+    /// Whether the node is registered as running
+    pub fn is_running(&self, registry: &NodeRegistry) -> Result<bool> {
+        Ok(matches!(
+            registry.get(&self.node_id())?,
+            Some(NodeInfo {
+                status: NodeStatus::Running,
+                ..
+            })
+        ))
+    }
+
+    /// Check that a running node matches the scenario
+    ///
+    /// Only the account count can be compared; balances change with use.
+    pub fn check_running(&self) -> Result<()> {
+        if let Some(expected) = self.accounts {
+            let actual = instance_accounts(self.chain, &self.instance)?.len();
+            if actual != expected as usize {
+                bail!(
+                    "{} is running with {} accounts but the scenario asks for {}; \
+                     stop it and run the scenario again",
+                    self.node_id(),
+                    actual,
+                    expected
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Substitute placeholders into an action's arguments and target the instance
+    ///
+    /// `accounts` is only called if the action refers to an account.
+    pub fn render(
+        &self,
+        action: &Action,
+        state: &NodeState,
+        accounts: impl FnOnce() -> Result<BTreeMap<String, String>>,
+    ) -> Result<Vec<String>> {
+        let args = action.args();
+        let mut values: BTreeMap<String, String> = state
+            .tokens
+            .iter()
+            .map(|(name, mint)| (format!("tokens.{}", name), mint.clone()))
+            .collect();
+        if args
+            .iter()
+            .flat_map(|arg| placeholders(arg))
+            .any(|name| name.starts_with("accounts."))
+        {
+            values.extend(accounts()?);
+        }
+
+        let missing: BTreeSet<String> = args
+            .iter()
+            .flat_map(|arg| placeholders(arg))
+            .filter(|name| !values.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Unknown placeholders: {}",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        Ok(args
+            .iter()
+            .map(|arg| substitute(arg, &values))
+            .chain(["--instance".to_string(), self.instance.clone()])
+            .collect())
+    }
+}
+
+/// `accounts.<index>` and `accounts.<label>` values for an instance
+pub fn account_values(chain: ChainType, instance_id: &str) -> Result<BTreeMap<String, String>> {
+    let mut values = BTreeMap::new();
+    for (index, account) in instance_accounts(chain, instance_id)?
+        .into_iter()
+        .enumerate()
+    {
+        if let Some(label) = account.label {
+            values.insert(format!("accounts.{}", label), account.address.clone());
+        }
+        values.insert(format!("accounts.{}", index), account.address);
+    }
+    Ok(values)
+}
+
+/// What a scenario has applied to a running node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeState {
+    /// Actions applied so far, in order
+    pub applied: Vec<Action>,
+    /// Mint addresses of the tokens created, by name
+    pub tokens: BTreeMap<String, String>,
+}
+
+impl NodeState {
+    /// Load the record in `instance_dir`, or an empty one
+    pub fn load(instance_dir: &Path) -> Result<Self> {
+        match fs::read_to_string(instance_dir.join(STATE_FILE)) {
+            Ok(json) => serde_json::from_str(&json).wrap_err("Invalid scenario record"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, instance_dir: &Path) -> Result<()> {
+        fs::write(
+            instance_dir.join(STATE_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Actions still to apply
+    ///
+    /// What was applied must be where `actions` starts, since actions that
+    /// already ran can't be undone. Anything else means the scenario changed
+    /// under a running node.
+    pub fn pending<'a>(&self, actions: &'a [Action]) -> Result<&'a [Action]> {
+        if !actions.starts_with(&self.applied) {
+            bail!(
+                "The scenario changed since it was applied; only actions added at \
+                 the end can be applied to a running node. Stop it and run the scenario again"
+            );
+        }
+        Ok(&actions[self.applied.len()..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SHOP: &str = r#"
+nodes:
+  - chain: solana
+    instance: shop
+    accounts: 3
+    tokens:
+      - name: usdc
+        decimals: 6
+        mint:
+          - to: "{{accounts.1}}"
+            amount: 1000
+    transfers:
+      - from: "{{accounts.1}}"
+        to: "{{accounts.buyer}}"
+        amount: 25
+        token: usdc
+  - chain: bitcoin
+    instance: shop
+    port: 18600
+    mine: 6
+"#;
+
+    fn accounts() -> Result<BTreeMap<String, String>> {
+        Ok(BTreeMap::from([
+            ("accounts.0".to_string(), "Auth111".to_string()),
+            ("accounts.1".to_string(), "Seller222".to_string()),
+            ("accounts.buyer".to_string(), "Buyer333".to_string()),
+        ]))
+    }
+
+    #[test]
+    fn test_parse_and_actions() {
+        let scenario = Scenario::parse(SHOP).unwrap();
+        assert_eq!(scenario.nodes.len(), 2);
+
+        let solana = &scenario.nodes[0];
+        let actions = solana.actions();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions[0],
+            Action::CreateToken {
+                name: "usdc".to_string(),
+                authority: "{{accounts.0}}".to_string(),
+                decimals: 6,
+            }
+        );
+        assert!(matches!(actions[2], Action::Transfer { .. }));
+
+        let bitcoin = &scenario.nodes[1];
+        assert_eq!(bitcoin.actions(), vec![Action::Mine { blocks: 6 }]);
+        assert_eq!(
+            bitcoin.start_args(),
+            vec![
+                "start",
+                "--instance",
+                "shop",
+                "--rpc-port",
+                "18600",
+                "--detach"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_scenarios() {
+        let cases = [
+            "nodes: []",
+            "nodes:\n  - chain: solana\n  - chain: solana",
+            "nodes:\n  - chain: bitcoin\n    tokens:\n      - name: usdc",
+            "nodes:\n  - chain: solana\n    mine: 1",
+            "nodes:\n  - chain: ethereum\n    transfers:\n      - {from: a, to: b, amount: 1}",
+            "nodes:\n  - chain: solana\n    transfers:\n      - {from: a, to: b, amount: 1, token: usdc}",
+            "nodes:\n  - chain: solana\n    balances: 1",
+        ];
+        for yaml in cases {
+            assert!(Scenario::parse(yaml).is_err(), "accepted {}", yaml);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let scenario = Scenario::parse(SHOP).unwrap();
+        let solana = &scenario.nodes[0];
+        let actions = solana.actions();
+        let mut state = NodeState::default();
+
+        let create = solana.render(&actions[0], &state, accounts).unwrap();
+        assert_eq!(
+            create,
+            vec![
+                "token",
+                "create",
+                "Auth111",
+                "--decimals",
+                "6",
+                "--format",
+                "json",
+                "--instance",
+                "shop"
+            ]
+        );
+
+        // The mint only exists once the token is created
+        assert!(solana.render(&actions[2], &state, accounts).is_err());
+        state
+            .tokens
+            .insert("usdc".to_string(), "Mint444".to_string());
+        let transfer = solana.render(&actions[2], &state, accounts).unwrap();
+        assert_eq!(
+            transfer,
+            vec![
+                "token",
+                "transfer",
+                "Mint444",
+                "Seller222",
+                "Buyer333",
+                "25",
+                "--instance",
+                "shop"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pending() {
+        let scenario = Scenario::parse(SHOP).unwrap();
+        let actions = scenario.nodes[0].actions();
+
+        let mut state = NodeState::default();
+        assert_eq!(state.pending(&actions).unwrap().len(), 3);
+
+        state.applied = actions[..2].to_vec();
+        assert_eq!(state.pending(&actions).unwrap(), &actions[2..]);
+
+        state.applied = vec![Action::Mine { blocks: 1 }];
+        assert!(state.pending(&actions).is_err());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(NodeState::load(dir.path()).unwrap().applied.is_empty());
+
+        let state = NodeState {
+            applied: vec![Action::Mine { blocks: 6 }],
+            tokens: BTreeMap::from([("usdc".to_string(), "Mint444".to_string())]),
+        };
+        state.save(dir.path()).unwrap();
+
+        let loaded = NodeState::load(dir.path()).unwrap();
+        assert_eq!(loaded.applied, state.applied);
+        assert_eq!(loaded.tokens, state.tokens);
+    }
+}
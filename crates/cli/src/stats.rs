@@ -0,0 +1,155 @@
+//! Local usage statistics for `cf stats`, built from the audit log
+
+use chain_forge_cli_utils::{format_btc, format_eth, format_sol};
+use chain_forge_common::{AuditEntry, AuditEvent, ChainType};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tabled::Tabled;
+
+/// Usage for a single instance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstanceStats {
+    pub chain: ChainType,
+    pub instance_id: String,
+    pub nodes_started: u64,
+    pub blocks_mined: u64,
+    pub funds_dispensed: f64,
+}
+
+/// Usage summary over a time window
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsReport {
+    pub since: DateTime<Utc>,
+    pub nodes_started: u64,
+    pub blocks_mined: u64,
+    pub instances: Vec<InstanceStats>,
+}
+
+#[derive(Tabled)]
+pub struct InstanceStatsDisplay {
+    #[tabled(rename = "Chain")]
+    chain: String,
+    #[tabled(rename = "Instance")]
+    instance: String,
+    #[tabled(rename = "Starts")]
+    nodes_started: u64,
+    #[tabled(rename = "Blocks Mined")]
+    blocks_mined: u64,
+    #[tabled(rename = "Funds Dispensed")]
+    funds_dispensed: String,
+}
+
+impl From<&InstanceStats> for InstanceStatsDisplay {
+    fn from(stats: &InstanceStats) -> Self {
+        let funds_dispensed = match stats.chain {
+            ChainType::Solana => format!("{} SOL", format_sol(stats.funds_dispensed)),
+            ChainType::Bitcoin => format!("{} BTC", format_btc(stats.funds_dispensed)),
+            ChainType::Ethereum => format!("{} ETH", format_eth(stats.funds_dispensed)),
+        };
+
+        Self {
+            chain: stats.chain.to_string(),
+            instance: stats.instance_id.clone(),
+            nodes_started: stats.nodes_started,
+            blocks_mined: stats.blocks_mined,
+            funds_dispensed,
+        }
+    }
+}
+
+/// Summarize audit entries recorded at or after `since`
+pub fn summarize(entries: &[AuditEntry], since: DateTime<Utc>) -> StatsReport {
+    let mut by_instance: BTreeMap<(String, String), InstanceStats> = BTreeMap::new();
+
+    for entry in entries.iter().filter(|e| e.timestamp >= since) {
+        let stats = by_instance
+            .entry((entry.chain.to_string(), entry.instance_id.clone()))
+            .or_insert_with(|| InstanceStats {
+                chain: entry.chain,
+                instance_id: entry.instance_id.clone(),
+                nodes_started: 0,
+                blocks_mined: 0,
+                funds_dispensed: 0.0,
+            });
+
+        match &entry.event {
+            AuditEvent::NodeStarted => stats.nodes_started += 1,
+            AuditEvent::NodeStopped => {}
+            AuditEvent::BlocksMined { count } => stats.blocks_mined += count,
+            AuditEvent::FundsDispensed { amount, .. } => stats.funds_dispensed += amount,
+        }
+    }
+
+    let instances: Vec<InstanceStats> = by_instance.into_values().collect();
+
+    StatsReport {
+        since,
+        nodes_started: instances.iter().map(|s| s.nodes_started).sum(),
+        blocks_mined: instances.iter().map(|s| s.blocks_mined).sum(),
+        instances,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(chain: ChainType, instance: &str, age_days: i64, event: AuditEvent) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now() - Duration::days(age_days),
+            chain,
+            instance_id: instance.to_string(),
+            event,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let entries = vec![
+            entry(ChainType::Bitcoin, "dev", 1, AuditEvent::NodeStarted),
+            entry(
+                ChainType::Bitcoin,
+                "dev",
+                1,
+                AuditEvent::BlocksMined { count: 101 },
+            ),
+            entry(
+                ChainType::Bitcoin,
+                "dev",
+                1,
+                AuditEvent::FundsDispensed {
+                    address: None,
+                    amount: 10.0,
+                },
+            ),
+            entry(ChainType::Solana, "default", 2, AuditEvent::NodeStarted),
+            entry(ChainType::Solana, "default", 2, AuditEvent::NodeStopped),
+            entry(ChainType::Solana, "default", 3, AuditEvent::NodeStarted),
+        ];
+
+        let report = summarize(&entries, Utc::now() - Duration::days(7));
+        assert_eq!(report.nodes_started, 3);
+        assert_eq!(report.blocks_mined, 101);
+        assert_eq!(report.instances.len(), 2);
+
+        let bitcoin = &report.instances[0];
+        assert_eq!(bitcoin.chain, ChainType::Bitcoin);
+        assert_eq!(bitcoin.funds_dispensed, 10.0);
+
+        let solana = &report.instances[1];
+        assert_eq!(solana.nodes_started, 2);
+    }
+
+    #[test]
+    fn test_summarize_excludes_old_entries() {
+        let entries = vec![
+            entry(ChainType::Solana, "default", 30, AuditEvent::NodeStarted),
+            entry(ChainType::Solana, "default", 1, AuditEvent::NodeStarted),
+        ];
+
+        let report = summarize(&entries, Utc::now() - Duration::days(7));
+        assert_eq!(report.nodes_started, 1);
+    }
+}
@@ -0,0 +1,29 @@
+//! Trashed instance data listing for `cf trash list`
+
+use chain_forge_cli_utils::{format_duration, format_relative};
+use chain_forge_common::TrashEntry;
+use chrono::{DateTime, Utc};
+use tabled::Tabled;
+
+#[derive(Tabled)]
+pub struct TrashDisplay {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Node")]
+    node_id: String,
+    #[tabled(rename = "Trashed")]
+    trashed: String,
+    #[tabled(rename = "Deleted In")]
+    expires_in: String,
+}
+
+impl TrashDisplay {
+    pub fn new(entry: &TrashEntry, now: DateTime<Utc>) -> Self {
+        Self {
+            id: entry.id.clone(),
+            node_id: entry.node_id(),
+            trashed: format_relative(entry.trashed_at, now),
+            expires_in: format_duration(entry.expires_at - now),
+        }
+    }
+}
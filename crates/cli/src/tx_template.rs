@@ -0,0 +1,524 @@
+//! Named transaction templates for `cf tx template`
+//!
+//! A template is a TOML file describing a sequence of steps against one
+//! chain, with `{{variable}}` placeholders filled in when it is applied:
+//!
+//! ```toml
+//! chain = "solana"
+//! description = "Pay a merchant from the first account"
+//!
+//! [variables]
+//! amount = "1.5"
+//!
+//! [[steps]]
+//! action = "transfer"
+//! from = "{{accounts.0}}"
+//! to = "{{merchant}}"
+//! amount = "{{amount}}"
+//! ```
+//!
+//! Each step runs the chain CLI (`cf-solana`, `cf-bitcoin`, `cf-ethereum`)
+//! against the target instance, so templates can use anything the CLIs
+//! can do. Saved templates live in `~/.chain-forge/templates/<name>.toml`.
+
+use chain_forge_common::{validate_name, ChainType};
+use chain_forge_config::Config;
+use eyre::{bail, eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tabled::Tabled;
+
+/// A step of a template, run as one chain CLI command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum TemplateStep {
+    /// Transfer between accounts (`<cli> transfer <from> <to> <amount>`)
+    Transfer {
+        from: String,
+        to: String,
+        amount: String,
+    },
+    /// Fund an address (`<cli> fund <address> <amount>`)
+    Fund { address: String, amount: String },
+    /// Any other chain CLI command, e.g. a program or contract interaction
+    Cli { args: Vec<String> },
+}
+
+impl TemplateStep {
+    /// Chain CLI arguments for this step, before substitution
+    fn args(&self) -> Vec<String> {
+        match self {
+            TemplateStep::Transfer { from, to, amount } => {
+                vec![
+                    "transfer".to_string(),
+                    from.clone(),
+                    to.clone(),
+                    amount.clone(),
+                ]
+            }
+            TemplateStep::Fund { address, amount } => {
+                vec!["fund".to_string(), address.clone(), amount.clone()]
+            }
+            TemplateStep::Cli { args } => args.clone(),
+        }
+    }
+}
+
+/// A parameterized sequence of steps against one chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxTemplate {
+    pub chain: ChainType,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Default values for variables
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    pub steps: Vec<TemplateStep>,
+}
+
+impl TxTemplate {
+    /// Parse and check a template
+    pub fn parse(toml: &str) -> Result<Self> {
+        let template: TxTemplate = toml::from_str(toml)?;
+        template.validate()?;
+        Ok(template)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            bail!("Template has no steps");
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            match step {
+                TemplateStep::Transfer { .. } if self.chain == ChainType::Ethereum => {
+                    bail!(
+                        "Step {}: cf-ethereum has no transfer command; use a fund or cli step",
+                        i + 1
+                    );
+                }
+                TemplateStep::Cli { args } if args.is_empty() => {
+                    bail!("Step {}: cli step has no arguments", i + 1);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Chain CLI that runs the steps
+    pub fn program(&self) -> &'static str {
+        chain_cli(self.chain)
+    }
+
+    /// Variables the steps refer to, excluding `instance` and `accounts.*`
+    pub fn variable_names(&self) -> BTreeSet<String> {
+        self.steps
+            .iter()
+            .flat_map(|step| step.args())
+            .flat_map(|arg| placeholders(&arg))
+            .filter(|name| name != "instance" && !name.starts_with("accounts."))
+            .collect()
+    }
+
+    /// Substitute variables into every step, returning each step's arguments
+    ///
+    /// `vars` take precedence over the template's defaults. `accounts` is
+    /// only called if a step refers to `{{accounts.<index or label>}}`.
+    pub fn render(
+        &self,
+        instance_id: &str,
+        vars: &BTreeMap<String, String>,
+        accounts: impl FnOnce() -> Result<Vec<AccountRef>>,
+    ) -> Result<Vec<Vec<String>>> {
+        let steps: Vec<Vec<String>> = self.steps.iter().map(TemplateStep::args).collect();
+
+        let mut values = self.variables.clone();
+        values.extend(vars.clone());
+        values.insert("instance".to_string(), instance_id.to_string());
+
+        let uses_accounts = steps
+            .iter()
+            .flatten()
+            .flat_map(|arg| placeholders(arg))
+            .any(|name| name.starts_with("accounts."));
+        if uses_accounts {
+            for (index, account) in accounts()?.into_iter().enumerate() {
+                if let Some(label) = account.label {
+                    values.insert(format!("accounts.{}", label), account.address.clone());
+                }
+                values.insert(format!("accounts.{}", index), account.address);
+            }
+        }
+
+        let missing: BTreeSet<String> = steps
+            .iter()
+            .flatten()
+            .flat_map(|arg| placeholders(arg))
+            .filter(|name| !values.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Missing values for: {} (pass them with --var NAME=VALUE)",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        Ok(steps
+            .into_iter()
+            .map(|args| {
+                args.iter()
+                    .map(|arg| substitute(arg, &values))
+                    .chain(["--instance".to_string(), instance_id.to_string()])
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// Name of a chain's CLI
+pub fn chain_cli(chain: ChainType) -> &'static str {
+    match chain {
+        ChainType::Solana => "cf-solana",
+        ChainType::Bitcoin => "cf-bitcoin",
+        ChainType::Ethereum => "cf-ethereum",
+    }
+}
+
+/// An instance account that templates can refer to
+pub struct AccountRef {
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// Addresses of an instance's accounts, in index order
+pub fn instance_accounts(chain: ChainType, instance_id: &str) -> Result<Vec<AccountRef>> {
+    let accounts: Vec<AccountRef> = match chain {
+        ChainType::Solana => chain_forge_solana_accounts::AccountsStorage::with_path(
+            chain_forge_solana_core::SolanaConfig::with_instance(instance_id).accounts_file(),
+        )
+        .load()?
+        .into_iter()
+        .map(|a| AccountRef {
+            address: a.public_key,
+            label: a.label,
+        })
+        .collect(),
+        ChainType::Bitcoin => chain_forge_bitcoin_accounts::AccountsStorage::with_path(
+            chain_forge_bitcoin_core::BitcoinConfig::with_instance(instance_id).accounts_file(),
+        )
+        .load()?
+        .into_iter()
+        .map(|a| AccountRef {
+            address: a.address,
+            label: a.label,
+        })
+        .collect(),
+        ChainType::Ethereum => chain_forge_ethereum_accounts::AccountsStorage::with_path(
+            chain_forge_ethereum_core::EthereumConfig::with_instance(instance_id).accounts_file(),
+        )
+        .load()?
+        .into_iter()
+        .map(|a| AccountRef {
+            address: a.address,
+            label: a.label,
+        })
+        .collect(),
+    };
+
+    if accounts.is_empty() {
+        bail!(
+            "No accounts found for {} instance '{}'; is it started?",
+            chain,
+            instance_id
+        );
+    }
+    Ok(accounts)
+}
+
+/// Names of the `{{...}}` placeholders in `s`
+pub fn placeholders(s: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        names.push(rest[start + 2..start + 2 + end].trim().to_string());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+/// Replace placeholders in `s` with their values
+pub fn substitute(s: &str, values: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+        out.push_str(&rest[..start]);
+        out.push_str(values.get(name).map(String::as_str).unwrap_or_default());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Tabled)]
+pub struct TemplateDisplay {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Chain")]
+    chain: String,
+    #[tabled(rename = "Steps")]
+    steps: usize,
+    #[tabled(rename = "Variables")]
+    variables: String,
+    #[tabled(rename = "Description")]
+    description: String,
+}
+
+impl TemplateDisplay {
+    pub fn new(name: &str, template: &TxTemplate) -> Self {
+        Self {
+            name: name.to_string(),
+            chain: template.chain.to_string(),
+            steps: template.steps.len(),
+            variables: template
+                .variable_names()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", "),
+            description: template.description.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Saved templates, one TOML file each
+pub struct TemplateStore {
+    dir: PathBuf,
+}
+
+impl TemplateStore {
+    /// Create a store using the default data directory
+    pub fn new() -> Self {
+        Self::with_dir(Config::data_dir().join("templates"))
+    }
+
+    /// Create a store with a custom directory (mainly for testing)
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.toml", name))
+    }
+
+    /// Check `file` and save it as template `name`
+    pub fn save(&self, name: &str, file: &Path, force: bool) -> Result<TxTemplate> {
+        validate_name(name).map_err(|e| eyre!("Invalid template name: {}", e))?;
+
+        let toml = fs::read_to_string(file)
+            .wrap_err_with(|| format!("Failed to read {}", file.display()))?;
+        let template = TxTemplate::parse(&toml)
+            .wrap_err_with(|| format!("Invalid template {}", file.display()))?;
+
+        let path = self.path(name);
+        if path.exists() && !force {
+            bail!(
+                "Template '{}' already exists; pass --force to replace it",
+                name
+            );
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, toml)?;
+        Ok(template)
+    }
+
+    /// Load a saved template
+    pub fn load(&self, name: &str) -> Result<TxTemplate> {
+        let path = self.path(name);
+        if !path.exists() {
+            bail!("Template '{}' not found", name);
+        }
+        TxTemplate::parse(&fs::read_to_string(&path)?)
+            .wrap_err_with(|| format!("Invalid template {}", path.display()))
+    }
+
+    /// Saved templates by name; unreadable files are skipped
+    pub fn list(&self) -> Result<Vec<(String, TxTemplate)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Ok(template) = self.load(name) {
+                templates.push((name.to_string(), template));
+            }
+        }
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(templates)
+    }
+
+    /// Delete a saved template
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let path = self.path(name);
+        if !path.exists() {
+            bail!("Template '{}' not found", name);
+        }
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
+
+impl Default for TemplateStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse `NAME=VALUE` pairs given with `--var`
+pub fn parse_vars(pairs: &[String]) -> Result<BTreeMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| eyre!("Invalid --var '{}'; expected NAME=VALUE", pair))?;
+            Ok((name.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PAYMENT: &str = r#"
+chain = "solana"
+description = "Pay a merchant"
+
+[variables]
+amount = "1.5"
+
+[[steps]]
+action = "transfer"
+from = "{{accounts.0}}"
+to = "{{merchant}}"
+amount = "{{amount}}"
+
+[[steps]]
+action = "cli"
+args = ["alt", "extend", "{{table}}", "{{accounts.alice}}", "--all-accounts"]
+"#;
+
+    fn accounts() -> Result<Vec<AccountRef>> {
+        Ok(vec![AccountRef {
+            address: "Payer111".to_string(),
+            label: Some("alice".to_string()),
+        }])
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(placeholders("{{a}}-{{ b }}"), vec!["a", "b"]);
+        assert!(placeholders("no {{placeholder").is_empty());
+    }
+
+    #[test]
+    fn test_parse_and_variable_names() {
+        let template = TxTemplate::parse(PAYMENT).unwrap();
+        assert_eq!(template.chain, ChainType::Solana);
+        assert_eq!(template.steps.len(), 2);
+        assert_eq!(
+            template.variable_names().into_iter().collect::<Vec<_>>(),
+            vec!["amount", "merchant", "table"]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let template = TxTemplate::parse(PAYMENT).unwrap();
+        let vars =
+            parse_vars(&["merchant=Shop222".to_string(), "table=Table333".to_string()]).unwrap();
+
+        let steps = template.render("qa", &vars, accounts).unwrap();
+        assert_eq!(
+            steps[0],
+            vec!["transfer", "Payer111", "Shop222", "1.5", "--instance", "qa"]
+        );
+        assert_eq!(
+            steps[1],
+            vec![
+                "alt",
+                "extend",
+                "Table333",
+                "Payer111",
+                "--all-accounts",
+                "--instance",
+                "qa"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_reports_missing_variables() {
+        let template = TxTemplate::parse(PAYMENT).unwrap();
+        let err = template
+            .render("qa", &BTreeMap::new(), accounts)
+            .unwrap_err();
+        assert!(err.to_string().contains("merchant, table"));
+    }
+
+    #[test]
+    fn test_parse_rejects_ethereum_transfer() {
+        let toml = r#"
+chain = "ethereum"
+
+[[steps]]
+action = "transfer"
+from = "a"
+to = "b"
+amount = "1"
+"#;
+        assert!(TxTemplate::parse(toml).is_err());
+    }
+
+    #[test]
+    fn test_parse_vars_rejects_missing_value() {
+        assert!(parse_vars(&["amount".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_store_save_load_remove() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("payment.toml");
+        fs::write(&file, PAYMENT).unwrap();
+
+        let store = TemplateStore::with_dir(dir.path().join("templates"));
+        store.save("payment", &file, false).unwrap();
+        assert!(store.save("payment", &file, false).is_err());
+        store.save("payment", &file, true).unwrap();
+
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(store.load("payment").unwrap().steps.len(), 2);
+
+        store.remove("payment").unwrap();
+        assert!(store.load("payment").is_err());
+    }
+}
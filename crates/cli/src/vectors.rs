@@ -0,0 +1,207 @@
+//! Wallet test vectors for `cf vectors generate`
+//!
+//! Vectors are derived with the same accounts crates the chain CLIs use, so
+//! wallet implementations can check their derivation against Chain Forge's.
+
+use bitcoin::Network;
+use chain_forge_bitcoin_accounts::AccountGenerator as BitcoinAccountGenerator;
+use chain_forge_common::{ChainType, Result};
+use chain_forge_ethereum_accounts::AccountGenerator as EthereumAccountGenerator;
+use chain_forge_solana_accounts::AccountGenerator as SolanaAccountGenerator;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Bitcoin network to encode addresses and WIFs for
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum NetworkArg {
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl From<NetworkArg> for Network {
+    fn from(network: NetworkArg) -> Self {
+        match network {
+            NetworkArg::Mainnet => Network::Bitcoin,
+            NetworkArg::Testnet => Network::Testnet,
+            NetworkArg::Signet => Network::Signet,
+            NetworkArg::Regtest => Network::Regtest,
+        }
+    }
+}
+
+/// A set of vectors derived from one mnemonic
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorSet {
+    pub chain: ChainType,
+    pub mnemonic: String,
+    /// Bitcoin network the addresses are encoded for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    pub vectors: Vec<Vector>,
+}
+
+/// One derived key
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vector {
+    pub index: u32,
+    pub path: String,
+    pub address: String,
+    /// Hex compressed public key (Bitcoin), base58 public key (Solana), or
+    /// hex uncompressed public key (Ethereum)
+    pub public_key: String,
+    /// Private key in WIF (Bitcoin only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wif: Option<String>,
+    /// Base58 64-byte keypair, as used by Solana wallets, or 0x-prefixed hex
+    /// private key (Ethereum)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+}
+
+/// Derive `count` Bitcoin vectors, from `mnemonic` or a fresh one
+pub fn bitcoin_vectors(mnemonic: Option<&str>, network: Network, count: u32) -> Result<VectorSet> {
+    let generator = match mnemonic {
+        Some(phrase) => BitcoinAccountGenerator::from_mnemonic_with_network(phrase, network)?,
+        None => BitcoinAccountGenerator::new_with_network(network)?,
+    };
+
+    let vectors = generator
+        .generate_accounts(count)?
+        .into_iter()
+        .zip(0..)
+        .map(|(account, index)| Vector {
+            index,
+            path: account.derivation_path.unwrap_or_default(),
+            address: account.address,
+            public_key: account.public_key,
+            wif: Some(account.wif),
+            secret_key: None,
+        })
+        .collect();
+
+    Ok(VectorSet {
+        chain: ChainType::Bitcoin,
+        mnemonic: generator.mnemonic_phrase(),
+        network: Some(network.to_string()),
+        vectors,
+    })
+}
+
+/// Derive `count` Solana vectors, from `mnemonic` or a fresh one
+pub fn solana_vectors(mnemonic: Option<&str>, count: u32) -> Result<VectorSet> {
+    let generator = match mnemonic {
+        Some(phrase) => SolanaAccountGenerator::from_mnemonic(phrase)?,
+        None => SolanaAccountGenerator::new()?,
+    };
+
+    let mut vectors = Vec::new();
+    for (account, index) in generator.generate_accounts(count)?.into_iter().zip(0..) {
+        let secret_key = account.keypair()?.to_base58_string();
+        vectors.push(Vector {
+            index,
+            path: account.derivation_path.unwrap_or_default(),
+            address: account.public_key.clone(),
+            public_key: account.public_key,
+            wif: None,
+            secret_key: Some(secret_key),
+        });
+    }
+
+    Ok(VectorSet {
+        chain: ChainType::Solana,
+        mnemonic: generator.mnemonic_phrase(),
+        network: None,
+        vectors,
+    })
+}
+
+/// Derive `count` Ethereum vectors, from `mnemonic` or a fresh one
+pub fn ethereum_vectors(mnemonic: Option<&str>, count: u32) -> Result<VectorSet> {
+    let generator = match mnemonic {
+        Some(phrase) => EthereumAccountGenerator::from_mnemonic(phrase)?,
+        None => EthereumAccountGenerator::new()?,
+    };
+
+    let vectors = generator
+        .generate_accounts(count)?
+        .into_iter()
+        .zip(0..)
+        .map(|(account, index)| Vector {
+            index,
+            path: account.derivation_path.unwrap_or_default(),
+            address: account.address,
+            public_key: account.public_key,
+            wif: None,
+            secret_key: Some(account.private_key),
+        })
+        .collect();
+
+    Ok(VectorSet {
+        chain: ChainType::Ethereum,
+        mnemonic: generator.mnemonic_phrase(),
+        network: None,
+        vectors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_bitcoin_vectors() {
+        let set = bitcoin_vectors(Some(MNEMONIC), Network::Regtest, 3).unwrap();
+        assert_eq!(set.mnemonic, MNEMONIC);
+        assert_eq!(set.network.as_deref(), Some("regtest"));
+        assert_eq!(set.vectors.len(), 3);
+        assert_eq!(set.vectors[2].index, 2);
+        assert_eq!(set.vectors[2].path, "m/44'/0'/0'/0/2");
+        assert!(set.vectors[0].address.starts_with("bcrt1"));
+        assert!(set.vectors[0].wif.is_some());
+        assert!(set.vectors[0].secret_key.is_none());
+
+        // Same mnemonic, same vectors
+        let again = bitcoin_vectors(Some(MNEMONIC), Network::Regtest, 1).unwrap();
+        assert_eq!(again.vectors[0].address, set.vectors[0].address);
+    }
+
+    #[test]
+    fn test_solana_vectors() {
+        let set = solana_vectors(Some(MNEMONIC), 2).unwrap();
+        assert!(set.network.is_none());
+        assert_eq!(set.vectors[1].path, "m/44'/501'/1'/0'");
+        assert_eq!(set.vectors[0].address, set.vectors[0].public_key);
+        assert!(set.vectors[0].wif.is_none());
+        assert!(set.vectors[0].secret_key.is_some());
+    }
+
+    #[test]
+    fn test_ethereum_vectors() {
+        let set = ethereum_vectors(Some(MNEMONIC), 2).unwrap();
+        assert!(set.network.is_none());
+        assert_eq!(set.vectors[1].path, "m/44'/60'/0'/0/1");
+        assert_eq!(
+            set.vectors[0].address,
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+        );
+        assert!(set.vectors[0].wif.is_none());
+        assert!(set.vectors[0].secret_key.is_some());
+    }
+
+    #[test]
+    fn test_vectors_json_omits_other_chain_fields() {
+        let set = solana_vectors(Some(MNEMONIC), 1).unwrap();
+        let json = serde_json::to_value(&set).unwrap();
+        assert_eq!(json["chain"], "solana");
+        assert!(json.get("network").is_none());
+        assert!(json["vectors"][0].get("wif").is_none());
+        assert!(json["vectors"][0]["secretKey"].is_string());
+    }
+}
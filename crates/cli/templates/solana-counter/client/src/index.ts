@@ -0,0 +1,88 @@
+/**
+ * {{project_name}} client
+ *
+ * Creates a counter account owned by the deployed program, initializes it,
+ * increments it twice, and checks the stored value.
+ *
+ * Environment:
+ *   PROGRAM_ID  - deployed program id (required)
+ *   RPC_URL     - validator RPC URL (default: http://localhost:8899)
+ *   CF_INSTANCE - Chain Forge instance holding the payer account (default: {{project_name}})
+ */
+
+import {
+  Connection,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+  sendAndConfirmTransaction,
+} from '@solana/web3.js';
+import { readFileSync } from 'fs';
+import { homedir } from 'os';
+import { join } from 'path';
+
+const COUNTER_SIZE = 8;
+
+function loadPayer(instance: string): Keypair {
+  const accountsFile = join(homedir(), '.chain-forge', 'solana', 'instances', instance, 'accounts.json');
+  const parsed = JSON.parse(readFileSync(accountsFile, 'utf-8'));
+  const accounts = Array.isArray(parsed) ? parsed : parsed.accounts;
+  return Keypair.fromSecretKey(new Uint8Array(accounts[0].secretKey));
+}
+
+function counterInstruction(programId: PublicKey, counter: PublicKey, op: number): TransactionInstruction {
+  return new TransactionInstruction({
+    programId,
+    keys: [{ pubkey: counter, isSigner: false, isWritable: true }],
+    data: Buffer.from([op]),
+  });
+}
+
+async function main() {
+  if (!process.env.PROGRAM_ID) {
+    throw new Error('PROGRAM_ID is not set');
+  }
+
+  const programId = new PublicKey(process.env.PROGRAM_ID);
+  const connection = new Connection(process.env.RPC_URL ?? 'http://localhost:8899', 'confirmed');
+  const payer = loadPayer(process.env.CF_INSTANCE ?? '{{project_name}}');
+  const counter = Keypair.generate();
+
+  console.log(`Program: ${programId.toBase58()}`);
+  console.log(`Payer:   ${payer.publicKey.toBase58()}`);
+  console.log(`Counter: ${counter.publicKey.toBase58()}`);
+
+  const lamports = await connection.getMinimumBalanceForRentExemption(COUNTER_SIZE);
+  const transaction = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: counter.publicKey,
+      lamports,
+      space: COUNTER_SIZE,
+      programId,
+    }),
+    counterInstruction(programId, counter.publicKey, 0),
+    counterInstruction(programId, counter.publicKey, 1),
+    counterInstruction(programId, counter.publicKey, 1),
+  );
+  await sendAndConfirmTransaction(connection, transaction, [payer, counter]);
+
+  const info = await connection.getAccountInfo(counter.publicKey);
+  if (!info) {
+    throw new Error('Counter account not found');
+  }
+  const value = info.data.readBigUInt64LE(0);
+  console.log(`Counter value: ${value}`);
+
+  if (value !== 2n) {
+    throw new Error(`Expected counter value 2, got ${value}`);
+  }
+  console.log('OK');
+}
+
+main().catch((err) => {
+  console.error('Error:', err);
+  process.exit(1);
+});
@@ -0,0 +1,69 @@
+//! {{project_name}} - counter program
+//!
+//! Instructions:
+//! - 0: Initialize counter to 0
+//! - 1: Increment counter by 1
+//! - 2: Log current counter value
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+entrypoint!(process_instruction);
+
+/// Program entrypoint
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = *instruction_data
+        .first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+
+    // Verify the account is owned by this program
+    if counter_account.owner != program_id {
+        msg!("Counter account not owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if counter_account.data_len() < 8 {
+        msg!("Counter account must hold at least 8 bytes");
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    match instruction {
+        0 => {
+            let mut data = counter_account.try_borrow_mut_data()?;
+            data[..8].copy_from_slice(&0u64.to_le_bytes());
+            msg!("Counter initialized to 0");
+        }
+        1 => {
+            let mut data = counter_account.try_borrow_mut_data()?;
+            let value = read_counter(&data).saturating_add(1);
+            data[..8].copy_from_slice(&value.to_le_bytes());
+            msg!("Counter incremented to: {}", value);
+        }
+        2 => {
+            let data = counter_account.try_borrow_data()?;
+            msg!("Current counter value: {}", read_counter(&data));
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+
+    Ok(())
+}
+
+fn read_counter(data: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[..8]);
+    u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+# Start a local validator, deploy the counter program, and run the client against it.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+INSTANCE="${CF_INSTANCE:-{{project_name}}}"
+PORT="${PORT:-8899}"
+RPC_URL="http://localhost:${PORT}"
+
+echo "Starting Chain Forge instance '${INSTANCE}' on port ${PORT}..."
+cf-solana start --instance "${INSTANCE}" --port "${PORT}" --accounts 3 &
+VALIDATOR_PID=$!
+trap 'kill -INT "${VALIDATOR_PID}" 2>/dev/null || true; wait "${VALIDATOR_PID}" 2>/dev/null || true' EXIT
+
+ACCOUNTS_FILE="${HOME}/.chain-forge/solana/instances/${INSTANCE}/accounts.json"
+for _ in $(seq 1 120); do
+    if [ -f "${ACCOUNTS_FILE}" ] && solana --url "${RPC_URL}" cluster-version >/dev/null 2>&1; then
+        break
+    fi
+    sleep 1
+done
+
+echo "Building program..."
+(cd programs/counter && cargo build-sbf)
+
+mkdir -p target
+jq -c '(.accounts // .)[0].secretKey' "${ACCOUNTS_FILE}" > target/payer.json
+
+echo "Deploying program..."
+PROGRAM_ID=$(solana program deploy \
+    --url "${RPC_URL}" \
+    --keypair target/payer.json \
+    --output json \
+    programs/counter/target/deploy/{{crate_name}}.so | jq -r '.programId')
+echo "Program deployed: ${PROGRAM_ID}"
+
+echo "Running client..."
+(cd client && yarn install --silent && PROGRAM_ID="${PROGRAM_ID}" RPC_URL="${RPC_URL}" CF_INSTANCE="${INSTANCE}" yarn start)
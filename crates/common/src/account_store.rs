@@ -0,0 +1,314 @@
+//! Backends that keep each chain's accounts.
+//!
+//! A chain's `AccountsStorage` serializes its accounts and hands them to an
+//! [`AccountStore`], picked with `accounts_storage` in `chain-forge.toml`:
+//!
+//! - `file` (the default): everything in `accounts.json`, with secrets
+//!   encrypted when a passphrase is set
+//! - `keyring`: `accounts.json` without secrets; each account's secret
+//!   fields are kept in the OS keyring (Keychain, Credential Manager, or the
+//!   Secret Service)
+//! - `memory`: nothing on disk; accounts live as long as the process, which
+//!   suits tests
+
+use crate::schema;
+use crate::{ChainError, Protection, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Where accounts are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// `accounts.json`, secrets included
+    #[default]
+    File,
+    /// `accounts.json` for public fields, the OS keyring for secrets
+    Keyring,
+    /// Process memory only
+    Memory,
+}
+
+impl StorageBackend {
+    /// Store for the accounts file at `path`
+    ///
+    /// `protection` encrypts secrets written by the file backend, and lets
+    /// the keyring backend read a file the file backend encrypted.
+    pub fn open(self, path: &Path, protection: Protection) -> Box<dyn AccountStore> {
+        let path = path.to_path_buf();
+        match self {
+            Self::File => Box::new(FileStore { path, protection }),
+            Self::Keyring => Box::new(KeyringStore { path, protection }),
+            Self::Memory => Box::new(MemoryStore { path }),
+        }
+    }
+}
+
+/// Keeps a list of serialized accounts
+pub trait AccountStore: Send + Sync {
+    /// Replace the stored accounts
+    ///
+    /// `secret_fields` are the serialized fields that hold key material.
+    fn save(&self, accounts: &[Value], secret_fields: &[&str]) -> Result<()>;
+
+    /// The stored accounts, with their secrets; empty if none are stored
+    fn load(&self) -> Result<Vec<Value>>;
+
+    fn exists(&self) -> bool;
+
+    fn delete(&self) -> Result<()>;
+}
+
+/// Accounts in a versioned `accounts.json`
+pub struct FileStore {
+    path: PathBuf,
+    protection: Protection,
+}
+
+impl AccountStore for FileStore {
+    fn save(&self, accounts: &[Value], secret_fields: &[&str]) -> Result<()> {
+        schema::write_accounts(&self.path, accounts, secret_fields, &self.protection)
+    }
+
+    fn load(&self) -> Result<Vec<Value>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        schema::read_accounts(&self.path, &self.protection)
+    }
+
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn delete(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Service name keyring entries are stored under
+const KEYRING_SERVICE: &str = "chain-forge";
+
+/// Field that takes the place of an account's secrets in the file
+///
+/// It holds a digest of the secrets, so saving accounts whose secrets haven't
+/// changed (e.g. to update balances) doesn't rewrite their keyring entries.
+const KEYRING_FIELD: &str = "keyring";
+
+/// Public account fields in `accounts.json`, secrets in the OS keyring
+///
+/// Each account's secrets are one entry, named after the accounts file and
+/// the account's position in it. Accounts written by the file backend keep
+/// their inline secrets until the next save moves them to the keyring.
+pub struct KeyringStore {
+    path: PathBuf,
+    protection: Protection,
+}
+
+impl KeyringStore {
+    fn entry(&self, index: usize) -> Result<keyring::Entry> {
+        let user = format!("{}#{}", self.path.display(), index);
+        keyring::Entry::new(KEYRING_SERVICE, &user).map_err(keyring_error)
+    }
+
+    fn delete_entry(&self, index: usize) -> Result<()> {
+        match self.entry(index)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+
+    /// Accounts as written in the file, without keyring secrets
+    fn read_file(&self) -> Result<Vec<Value>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        schema::read_accounts(&self.path, &self.protection)
+    }
+}
+
+impl AccountStore for KeyringStore {
+    fn save(&self, accounts: &[Value], secret_fields: &[&str]) -> Result<()> {
+        let previous = self.read_file().unwrap_or_default();
+
+        let mut public = Vec::with_capacity(accounts.len());
+        for (index, account) in accounts.iter().enumerate() {
+            let mut account = account.clone();
+            let object = account
+                .as_object_mut()
+                .ok_or_else(|| ChainError::Other("expected an account object".to_string()))?;
+
+            let mut secrets = Map::new();
+            for field in secret_fields {
+                if let Some(value) = object.remove(*field) {
+                    secrets.insert(field.to_string(), value);
+                }
+            }
+            let secrets = serde_json::to_string(&secrets)?;
+            let digest = Value::String(digest(&secrets));
+
+            let stored = previous.get(index).and_then(|p| p.get(KEYRING_FIELD));
+            if stored != Some(&digest) {
+                self.entry(index)?
+                    .set_password(&secrets)
+                    .map_err(keyring_error)?;
+            }
+            object.insert(KEYRING_FIELD.to_string(), digest);
+            public.push(account);
+        }
+
+        for index in accounts.len()..previous.len() {
+            self.delete_entry(index)?;
+        }
+        schema::write_accounts(&self.path, &public, &[], &Protection::Plaintext)
+    }
+
+    fn load(&self) -> Result<Vec<Value>> {
+        let mut accounts = self.read_file()?;
+        for (index, account) in accounts.iter_mut().enumerate() {
+            let Some(object) = account.as_object_mut() else {
+                continue;
+            };
+            if object.remove(KEYRING_FIELD).is_none() {
+                continue;
+            }
+            let secrets = self.entry(index)?.get_password().map_err(keyring_error)?;
+            let secrets: Map<String, Value> = serde_json::from_str(&secrets)?;
+            object.extend(secrets);
+        }
+        Ok(accounts)
+    }
+
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn delete(&self) -> Result<()> {
+        let count = self.read_file().map(|accounts| accounts.len()).unwrap_or(0);
+        for index in 0..count {
+            self.delete_entry(index)?;
+        }
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn digest(secrets: &str) -> String {
+    Sha256::digest(secrets.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn keyring_error(e: keyring::Error) -> ChainError {
+    ChainError::Other(format!("OS keyring error: {}", e))
+}
+
+/// Accounts stored in memory, keyed by accounts file path
+static MEMORY: Mutex<BTreeMap<PathBuf, Vec<Value>>> = Mutex::new(BTreeMap::new());
+
+/// Accounts kept in process memory
+///
+/// Stores opened for the same path share their accounts, so a node started
+/// in a test and the code under test see the same ones.
+pub struct MemoryStore {
+    path: PathBuf,
+}
+
+impl AccountStore for MemoryStore {
+    fn save(&self, accounts: &[Value], _secret_fields: &[&str]) -> Result<()> {
+        MEMORY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(self.path.clone(), accounts.to_vec());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<Value>> {
+        Ok(MEMORY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&self.path)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn exists(&self) -> bool {
+        MEMORY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&self.path)
+    }
+
+    fn delete(&self) -> Result<()> {
+        MEMORY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn accounts() -> Vec<Value> {
+        vec![
+            json!({ "address": "a", "privateKey": "k1", "balance": 1.0 }),
+            json!({ "address": "b", "privateKey": "k2", "balance": 2.0 }),
+        ]
+    }
+
+    #[test]
+    fn test_memory_store_shared_by_path() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        let store = StorageBackend::Memory.open(&path, Protection::Plaintext);
+        assert!(!store.exists());
+        store.save(&accounts(), &["privateKey"]).unwrap();
+
+        // Nothing is written to disk, but another store for the path sees it
+        assert!(!path.exists());
+        let other = StorageBackend::Memory.open(&path, Protection::Plaintext);
+        assert_eq!(other.load().unwrap(), accounts());
+
+        other.delete().unwrap();
+        assert!(!store.exists());
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        let store = StorageBackend::File.open(&path, Protection::Plaintext);
+        assert!(store.load().unwrap().is_empty());
+
+        store.save(&accounts(), &["privateKey"]).unwrap();
+        assert!(path.exists());
+        assert_eq!(store.load().unwrap(), accounts());
+
+        store.delete().unwrap();
+        assert!(!store.exists());
+    }
+
+    #[test]
+    fn test_backend_names() {
+        let backend: StorageBackend = serde_json::from_str("\"keyring\"").unwrap();
+        assert_eq!(backend, StorageBackend::Keyring);
+        assert_eq!(StorageBackend::default(), StorageBackend::File);
+    }
+}
@@ -0,0 +1,198 @@
+//! Local audit log of node activity.
+//!
+//! Events (nodes started/stopped, blocks mined, funds dispensed) are appended
+//! as JSON lines to `~/.chain-forge/audit.log`. The log never leaves the
+//! machine; it backs local reports such as `cf stats`.
+
+// Allow fs2 trait methods that have the same name as std methods stabilized in Rust 1.89
+#![allow(clippy::incompatible_msrv)]
+
+use crate::{ChainError, ChainType, Result};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+/// An auditable event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A node was started
+    NodeStarted,
+    /// A node was stopped
+    NodeStopped,
+    /// Blocks were mined (Bitcoin)
+    BlocksMined { count: u64 },
+    /// Funds were sent from the node to an address, or to all accounts at startup
+    FundsDispensed {
+        address: Option<String>,
+        amount: f64,
+    },
+}
+
+/// A single audit log line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub chain: ChainType,
+    pub instance_id: String,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Append-only audit log
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Create an AuditLog using the default data directory
+    pub fn new() -> Self {
+        let path = crate::data_dir().join("audit.log");
+        Self { path }
+    }
+
+    /// Create an AuditLog with a custom path (mainly for testing)
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Record an event for a node, timestamped now
+    pub fn record(&self, chain: ChainType, instance_id: &str, event: AuditEvent) -> Result<()> {
+        self.append(&AuditEntry {
+            timestamp: Utc::now(),
+            chain,
+            instance_id: instance_id.to_string(),
+            event,
+        })
+    }
+
+    /// Append an entry to the log
+    pub fn append(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        file.lock_exclusive().map_err(|e| {
+            ChainError::Other(format!("Failed to acquire lock on audit log: {}", e))
+        })?;
+        file.write_all(line.as_bytes())?;
+        file.unlock().map_err(|e| {
+            ChainError::Other(format!("Failed to release lock on audit log: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Read all entries, skipping lines that cannot be parsed
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs::File::open(&self.path)?;
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(entry) = serde_json::from_str(&line) {
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_and_read() {
+        let dir = tempdir().unwrap();
+        let log = AuditLog::with_path(dir.path().join("audit.log"));
+
+        log.record(ChainType::Bitcoin, "dev", AuditEvent::NodeStarted)
+            .unwrap();
+        log.record(
+            ChainType::Bitcoin,
+            "dev",
+            AuditEvent::BlocksMined { count: 101 },
+        )
+        .unwrap();
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, AuditEvent::NodeStarted);
+        assert_eq!(entries[1].event, AuditEvent::BlocksMined { count: 101 });
+        assert_eq!(entries[1].instance_id, "dev");
+    }
+
+    #[test]
+    fn test_missing_log_is_empty() {
+        let dir = tempdir().unwrap();
+        let log = AuditLog::with_path(dir.path().join("audit.log"));
+        assert!(log.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_skips_malformed_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let log = AuditLog::with_path(path.clone());
+
+        log.record(
+            ChainType::Solana,
+            "default",
+            AuditEvent::FundsDispensed {
+                address: None,
+                amount: 1000.0,
+            },
+        )
+        .unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not json").unwrap();
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_entry_format() {
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            chain: ChainType::Solana,
+            instance_id: "default".to_string(),
+            event: AuditEvent::NodeStopped,
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["event"], "node_stopped");
+        assert_eq!(json["chain"], "solana");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::hooks::{HookContext, LifecycleHooks};
 use serde::{Deserialize, Serialize};
 
 /// Core trait that all blockchain implementations must implement
@@ -46,6 +47,33 @@ pub trait ChainProvider {
 
     /// Get the RPC URL for connecting to the chain
     fn get_rpc_url(&self) -> String;
+
+    /// Lifecycle hooks run by `start` and `stop`
+    fn hooks(&mut self) -> &mut LifecycleHooks;
+
+    /// Run a step before the node starts
+    fn on_before_start(&mut self, f: impl FnMut(&HookContext) -> Result<()> + Send + 'static)
+    where
+        Self: Sized,
+    {
+        self.hooks().on_before_start(f);
+    }
+
+    /// Run a step once the node is up and its accounts are funded
+    fn on_after_start(&mut self, f: impl FnMut(&HookContext) -> Result<()> + Send + 'static)
+    where
+        Self: Sized,
+    {
+        self.hooks().on_after_start(f);
+    }
+
+    /// Run a step before the node stops
+    fn on_before_stop(&mut self, f: impl FnMut(&HookContext) -> Result<()> + Send + 'static)
+    where
+        Self: Sized,
+    {
+        self.hooks().on_before_stop(f);
+    }
 }
 
 /// Common account interface
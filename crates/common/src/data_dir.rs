@@ -0,0 +1,29 @@
+//! Where chain state is kept.
+//!
+//! Instances, the node registry, and everything else live under
+//! `~/.chain-forge` unless `CHAIN_FORGE_DATA_DIR` names another directory.
+//! The CLIs set it from `--local` or the `data_dir` key in
+//! `chain-forge.toml`, so the processes they start use the same directory.
+
+use std::path::{Path, PathBuf};
+
+/// Name of the data directory in the home or project directory
+pub const DATA_DIR_NAME: &str = ".chain-forge";
+
+/// Environment variable that overrides the data directory
+pub const DATA_DIR_ENV: &str = "CHAIN_FORGE_DATA_DIR";
+
+/// The data directory: `CHAIN_FORGE_DATA_DIR` if set, else `~/.chain-forge`
+pub fn data_dir() -> PathBuf {
+    match std::env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()
+            .expect("Could not determine home directory")
+            .join(DATA_DIR_NAME),
+    }
+}
+
+/// Use `dir` as the data directory for this process and those it starts
+pub fn set_data_dir(dir: &Path) {
+    std::env::set_var(DATA_DIR_ENV, dir);
+}
@@ -25,6 +25,9 @@ pub enum ChainError {
     #[error("TOML parsing error: {0}")]
     TomlParsing(String),
 
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
     #[error("Chain not running")]
     NotRunning,
 
@@ -49,6 +52,9 @@ mod tests {
 
         let err = ChainError::AlreadyRunning;
         assert_eq!(err.to_string(), "Chain already running");
+
+        let err = ChainError::Timeout("node did not start".to_string());
+        assert_eq!(err.to_string(), "Timeout: node did not start");
     }
 
     #[test]
@@ -0,0 +1,252 @@
+//! Lifecycle hooks for providers.
+//!
+//! Integrators register hooks on a provider to run their own steps at fixed
+//! points of its lifecycle (deploy programs, seed tokens, notify services)
+//! without forking the start sequence. A hook is either a closure registered
+//! for one phase or a [`LifecycleHook`] implementation covering several.
+//!
+//! A failing `before_start` hook aborts the start before anything is
+//! spawned, and a failing `after_start` hook makes `start` return its error
+//! (dropping the provider stops the node). `before_stop` hooks can't prevent
+//! a stop: their errors are reported and the node is stopped anyway.
+
+use crate::{ChainType, Result};
+
+/// Point of a provider's lifecycle a hook runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecyclePhase {
+    /// Before any instance data is touched or a process is spawned
+    BeforeStart,
+    /// Once the node is up, its accounts funded and registered
+    AfterStart,
+    /// Before the node process is stopped, while it still answers
+    BeforeStop,
+}
+
+/// The node a hook runs for
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub chain: ChainType,
+    pub instance_id: String,
+    pub rpc_url: String,
+    /// Account addresses, in index order; empty before the accounts exist
+    pub accounts: Vec<String>,
+}
+
+/// Steps run at several points of a provider's lifecycle
+///
+/// Every method defaults to doing nothing, so implementations only override
+/// the phases they care about.
+pub trait LifecycleHook: Send {
+    fn on_before_start(&mut self, _ctx: &HookContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_after_start(&mut self, _ctx: &HookContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_before_stop(&mut self, _ctx: &HookContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+type HookFn = Box<dyn FnMut(&HookContext) -> Result<()> + Send>;
+
+/// A closure registered for a single phase
+struct PhaseHook {
+    phase: LifecyclePhase,
+    f: HookFn,
+}
+
+impl LifecycleHook for PhaseHook {
+    fn on_before_start(&mut self, ctx: &HookContext) -> Result<()> {
+        match self.phase {
+            LifecyclePhase::BeforeStart => (self.f)(ctx),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_after_start(&mut self, ctx: &HookContext) -> Result<()> {
+        match self.phase {
+            LifecyclePhase::AfterStart => (self.f)(ctx),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_before_stop(&mut self, ctx: &HookContext) -> Result<()> {
+        match self.phase {
+            LifecyclePhase::BeforeStop => (self.f)(ctx),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Hooks registered on a provider, run in registration order
+#[derive(Default)]
+pub struct LifecycleHooks {
+    hooks: Vec<Box<dyn LifecycleHook>>,
+}
+
+impl LifecycleHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a hook covering any number of phases
+    pub fn add(&mut self, hook: impl LifecycleHook + 'static) -> &mut Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Register a closure for one phase
+    pub fn on(
+        &mut self,
+        phase: LifecyclePhase,
+        f: impl FnMut(&HookContext) -> Result<()> + Send + 'static,
+    ) -> &mut Self {
+        self.add(PhaseHook {
+            phase,
+            f: Box::new(f),
+        })
+    }
+
+    pub fn on_before_start(
+        &mut self,
+        f: impl FnMut(&HookContext) -> Result<()> + Send + 'static,
+    ) -> &mut Self {
+        self.on(LifecyclePhase::BeforeStart, f)
+    }
+
+    pub fn on_after_start(
+        &mut self,
+        f: impl FnMut(&HookContext) -> Result<()> + Send + 'static,
+    ) -> &mut Self {
+        self.on(LifecyclePhase::AfterStart, f)
+    }
+
+    pub fn on_before_stop(
+        &mut self,
+        f: impl FnMut(&HookContext) -> Result<()> + Send + 'static,
+    ) -> &mut Self {
+        self.on(LifecyclePhase::BeforeStop, f)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run every hook for a phase, stopping at the first error
+    pub fn run(&mut self, phase: LifecyclePhase, ctx: &HookContext) -> Result<()> {
+        for hook in &mut self.hooks {
+            match phase {
+                LifecyclePhase::BeforeStart => hook.on_before_start(ctx)?,
+                LifecyclePhase::AfterStart => hook.on_after_start(ctx)?,
+                LifecyclePhase::BeforeStop => hook.on_before_stop(ctx)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the `before_stop` hooks, reporting errors instead of returning them
+    ///
+    /// Every hook runs even if an earlier one fails.
+    pub fn run_before_stop(&mut self, ctx: &HookContext) {
+        for hook in &mut self.hooks {
+            if let Err(e) = hook.on_before_stop(ctx) {
+                eprintln!("Warning: before-stop hook failed: {}", e);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for LifecycleHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LifecycleHooks")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChainError;
+    use std::sync::{Arc, Mutex};
+
+    fn context() -> HookContext {
+        HookContext {
+            chain: ChainType::Solana,
+            instance_id: "test".to_string(),
+            rpc_url: "http://localhost:8899".to_string(),
+            accounts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_closures_run_in_their_phase() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut hooks = LifecycleHooks::new();
+        for phase in [
+            LifecyclePhase::BeforeStart,
+            LifecyclePhase::AfterStart,
+            LifecyclePhase::BeforeStop,
+        ] {
+            let calls = calls.clone();
+            hooks.on(phase, move |_| {
+                calls.lock().unwrap().push(phase);
+                Ok(())
+            });
+        }
+
+        hooks.run(LifecyclePhase::AfterStart, &context()).unwrap();
+        hooks.run_before_stop(&context());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![LifecyclePhase::AfterStart, LifecyclePhase::BeforeStop]
+        );
+    }
+
+    #[test]
+    fn test_trait_hook() {
+        struct Seeder {
+            seeded: Arc<Mutex<Option<String>>>,
+        }
+
+        impl LifecycleHook for Seeder {
+            fn on_after_start(&mut self, ctx: &HookContext) -> Result<()> {
+                *self.seeded.lock().unwrap() = Some(ctx.instance_id.clone());
+                Ok(())
+            }
+        }
+
+        let seeded = Arc::new(Mutex::new(None));
+        let mut hooks = LifecycleHooks::new();
+        hooks.add(Seeder {
+            seeded: seeded.clone(),
+        });
+
+        hooks.run(LifecyclePhase::BeforeStart, &context()).unwrap();
+        assert!(seeded.lock().unwrap().is_none());
+        hooks.run(LifecyclePhase::AfterStart, &context()).unwrap();
+        assert_eq!(seeded.lock().unwrap().as_deref(), Some("test"));
+    }
+
+    #[test]
+    fn test_first_error_stops_the_phase() {
+        let ran = Arc::new(Mutex::new(false));
+        let mut hooks = LifecycleHooks::new();
+        hooks.on_before_start(|_| Err(ChainError::Other("deploy failed".to_string())));
+        {
+            let ran = ran.clone();
+            hooks.on_before_start(move |_| {
+                *ran.lock().unwrap() = true;
+                Ok(())
+            });
+        }
+
+        let result = hooks.run(LifecyclePhase::BeforeStart, &context());
+        assert!(result.is_err());
+        assert!(!*ran.lock().unwrap());
+    }
+}
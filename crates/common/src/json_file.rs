@@ -0,0 +1,100 @@
+//! JSON files that are written atomically.
+//!
+//! Values are serialized straight into a buffered temporary file next to the
+//! target, synced, and renamed over it. Readers see either the old file or
+//! the new one, never a partial write, and large values are never held in
+//! memory as a string.
+
+use crate::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Write `value` to `path` as pretty-printed JSON, replacing it atomically
+///
+/// Parent directories are created as needed. If serialization or the write
+/// fails, the existing file is left untouched.
+pub fn write_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp = temp_path(path);
+    if let Err(e) = write_synced(&tmp, value) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Read JSON from `path`
+pub fn read<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+fn write_synced<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    Ok(())
+}
+
+/// Temporary file for `path`, unique per process so concurrent writers
+/// don't clobber each other's partial output
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("accounts.json");
+
+        let values: Vec<u32> = (0..10_000).collect();
+        write_atomic(&path, &values).unwrap();
+        assert_eq!(read::<Vec<u32>>(&path).unwrap(), values);
+
+        // Overwrites, and leaves no temporary file behind
+        write_atomic(&path, &[1, 2, 3]).unwrap();
+        assert_eq!(read::<Vec<u32>>(&path).unwrap(), vec![1, 2, 3]);
+        let files: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_existing_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        write_atomic(&path, &["kept"]).unwrap();
+
+        // JSON object keys must be strings, so this fails mid-serialization
+        let invalid = HashMap::from([((1, 2), 3)]);
+        assert!(write_atomic(&path, &invalid).is_err());
+
+        assert_eq!(read::<Vec<String>>(&path).unwrap(), vec!["kept"]);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_output_is_pretty_printed() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("value.json");
+        write_atomic(&path, &serde_json::json!({ "a": 1 })).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\n  \"a\": 1\n}");
+    }
+}
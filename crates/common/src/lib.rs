@@ -1,10 +1,41 @@
+pub mod account_store;
+pub mod audit;
 pub mod chain;
+pub mod data_dir;
 pub mod error;
+pub mod hooks;
+pub mod json_file;
+pub mod messages;
+pub mod mock;
 pub mod registry;
+pub mod retry;
+pub mod rpc_cassette;
+pub mod rpc_metrics;
+pub mod scan;
+pub mod schema;
+pub mod secrets;
+pub mod signer;
+pub mod startup;
+pub mod trash;
 pub mod types;
+pub mod uri;
 pub mod validation;
 
+pub use account_store::{AccountStore, StorageBackend};
+pub use audit::{AuditEntry, AuditEvent, AuditLog};
 pub use chain::ChainProvider;
+pub use data_dir::{data_dir, set_data_dir, DATA_DIR_ENV, DATA_DIR_NAME};
 pub use error::{ChainError, Result};
-pub use registry::{ChainType, NodeInfo, NodeRegistry, NodeStatus};
+pub use hooks::{HookContext, LifecycleHook, LifecycleHooks, LifecyclePhase};
+pub use messages::Message;
+pub use mock::{MockAccount, MockConfig, MockProvider, MockTransaction};
+pub use registry::{ChainType, Heartbeat, NodeInfo, NodeRegistry, NodeStatus, HEARTBEAT_INTERVAL};
+pub use retry::RetryPolicy;
+pub use rpc_cassette::{Cassette, CassetteGuard, CassetteMode};
+pub use rpc_metrics::RpcMetrics;
+pub use scan::{gap_scan, FundedAccount, DEFAULT_GAP_LIMIT};
+pub use secrets::{Protection, PASSPHRASE_ENV};
+pub use signer::{RemoteSigner, Signer, SignerKeystore};
+pub use startup::{StartupPhase, StartupProfile, StartupTimer};
+pub use trash::{Trash, TrashEntry};
 pub use validation::{sanitize_name, validate_name, InvalidNameError};
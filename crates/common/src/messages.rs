@@ -0,0 +1,272 @@
+//! User-facing messages shared by the CLIs and the API.
+//!
+//! Each [`Message`] has a stable ID, such as `node.not_found`, and its text
+//! comes from a catalog keyed by that ID. The CLIs print the text and API
+//! error responses carry the ID as `code`, so scripts can match on the ID
+//! while the wording changes, and a translation is one more catalog.
+
+use crate::ChainType;
+use std::fmt;
+
+/// A message catalog: text templates by message ID
+///
+/// `{0}`, `{1}`, ... in a template stand for the message's arguments.
+pub type Catalog = &'static [(&'static str, &'static str)];
+
+/// English text of every message
+pub const ENGLISH: Catalog = &[
+    ("node.not_found", "Node not found"),
+    (
+        "node.not_registered",
+        "Node '{0}' not found in the node registry",
+    ),
+    ("node.not_running", "Node '{0}' is not running"),
+    ("node.solana_not_running", "Solana validator is not running"),
+    ("node.bitcoin_not_running", "Bitcoin node is not running"),
+    ("node.ethereum_not_running", "Ethereum node is not running"),
+    ("node.get_failed", "Failed to get node: {0}"),
+    ("node.list_failed", "Failed to list nodes: {0}"),
+    ("node.stop_failed", "Failed to stop node: {0}"),
+    ("node.status_update_failed", "Failed to update status: {0}"),
+    ("job.not_found", "Job not found"),
+    ("job.finished", "Job already finished"),
+    (
+        "request.invalid_chain",
+        "Invalid chain. Use 'solana', 'bitcoin', or 'ethereum'",
+    ),
+    ("request.amount_not_positive", "Amount must be positive"),
+    ("request.amounts_not_positive", "Amounts must be positive"),
+    ("request.no_recipients", "No recipients given"),
+    ("name.invalid_instance", "Invalid instance name: {0}"),
+    ("name.invalid_display", "Invalid display name: {0}"),
+    ("name.invalid_snapshot", "Invalid snapshot name: {0}"),
+    ("tx.failed", "Transaction failed: {0}"),
+    ("tx.transfer_failed", "Transfer failed: {0}"),
+    ("tx.payment_failed", "Payment failed: {0}"),
+    ("tx.funding_failed", "Funding failed: {0}"),
+    ("tx.airdrop_failed", "Airdrop failed: {0}"),
+    ("tx.mining_failed", "Mining failed: {0}"),
+    ("block.not_found", "Block {0} not found"),
+    (
+        "block.invalid_id",
+        "Expected a block height or a 64-character block hash",
+    ),
+    ("block.get_failed", "Failed to get block: {0}"),
+    ("block.list_failed", "Failed to get blocks: {0}"),
+    ("block.invalid_slot", "Expected a slot"),
+    (
+        "block.unsupported_chain",
+        "Blocks are only available for Bitcoin and Solana nodes",
+    ),
+    (
+        "warp.unsupported_chain",
+        "Warping is only available for Solana nodes",
+    ),
+    ("warp.invalid_target", "Give either slot or epoch"),
+];
+
+/// A user-facing message and its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    NodeNotFound,
+    /// A node ID the registry doesn't have
+    NodeNotRegistered(String),
+    NodeNotRunning(String),
+    /// The node of a chain doesn't answer RPC calls
+    ChainNotRunning(ChainType),
+    GetNodeFailed(String),
+    ListNodesFailed(String),
+    StopNodeFailed(String),
+    StatusUpdateFailed(String),
+    JobNotFound,
+    JobFinished,
+    InvalidChain,
+    AmountNotPositive,
+    AmountsNotPositive,
+    NoRecipients,
+    InvalidInstanceName(String),
+    InvalidDisplayName(String),
+    InvalidSnapshotName(String),
+    TransactionFailed(String),
+    TransferFailed(String),
+    PaymentFailed(String),
+    FundingFailed(String),
+    AirdropFailed(String),
+    MiningFailed(String),
+    BlockNotFound(String),
+    InvalidBlockId,
+    GetBlockFailed(String),
+    ListBlocksFailed(String),
+}
+
+impl Message {
+    /// Stable ID of the message, e.g. `node.not_found`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::NodeNotFound => "node.not_found",
+            Self::NodeNotRegistered(_) => "node.not_registered",
+            Self::NodeNotRunning(_) => "node.not_running",
+            Self::ChainNotRunning(ChainType::Solana) => "node.solana_not_running",
+            Self::ChainNotRunning(ChainType::Bitcoin) => "node.bitcoin_not_running",
+            Self::ChainNotRunning(ChainType::Ethereum) => "node.ethereum_not_running",
+            Self::GetNodeFailed(_) => "node.get_failed",
+            Self::ListNodesFailed(_) => "node.list_failed",
+            Self::StopNodeFailed(_) => "node.stop_failed",
+            Self::StatusUpdateFailed(_) => "node.status_update_failed",
+            Self::JobNotFound => "job.not_found",
+            Self::JobFinished => "job.finished",
+            Self::InvalidChain => "request.invalid_chain",
+            Self::AmountNotPositive => "request.amount_not_positive",
+            Self::AmountsNotPositive => "request.amounts_not_positive",
+            Self::NoRecipients => "request.no_recipients",
+            Self::InvalidInstanceName(_) => "name.invalid_instance",
+            Self::InvalidDisplayName(_) => "name.invalid_display",
+            Self::InvalidSnapshotName(_) => "name.invalid_snapshot",
+            Self::TransactionFailed(_) => "tx.failed",
+            Self::TransferFailed(_) => "tx.transfer_failed",
+            Self::PaymentFailed(_) => "tx.payment_failed",
+            Self::FundingFailed(_) => "tx.funding_failed",
+            Self::AirdropFailed(_) => "tx.airdrop_failed",
+            Self::MiningFailed(_) => "tx.mining_failed",
+            Self::WarpUnsupported => "warp.unsupported_chain",
+            Self::InvalidWarpTarget => "warp.invalid_target",
+            Self::InvalidSlot => "block.invalid_slot",
+            Self::BlocksUnsupported => "block.unsupported_chain",
+            Self::BlockNotFound(_) => "block.not_found",
+            Self::InvalidBlockId => "block.invalid_id",
+            Self::GetBlockFailed(_) => "block.get_failed",
+            Self::ListBlocksFailed(_) => "block.list_failed",
+        }
+    }
+
+    fn args(&self) -> Vec<&str> {
+        match self {
+            Self::NodeNotRegistered(arg)
+            | Self::NodeNotRunning(arg)
+            | Self::GetNodeFailed(arg)
+            | Self::ListNodesFailed(arg)
+            | Self::StopNodeFailed(arg)
+            | Self::StatusUpdateFailed(arg)
+            | Self::InvalidInstanceName(arg)
+            | Self::InvalidDisplayName(arg)
+            | Self::InvalidSnapshotName(arg)
+            | Self::TransactionFailed(arg)
+            | Self::TransferFailed(arg)
+            | Self::PaymentFailed(arg)
+            | Self::FundingFailed(arg)
+            | Self::AirdropFailed(arg)
+            | Self::MiningFailed(arg)
+            | Self::BlockNotFound(arg)
+            | Self::GetBlockFailed(arg)
+            | Self::ListBlocksFailed(arg) => vec![arg.as_str()],
+            Self::NodeNotFound
+            | Self::ChainNotRunning(_)
+            | Self::JobNotFound
+            | Self::JobFinished
+            | Self::InvalidChain
+            | Self::AmountNotPositive
+            | Self::AmountsNotPositive
+            | Self::NoRecipients
+            | Self::InvalidBlockId
+            | Self::InvalidSlot
+            | Self::BlocksUnsupported
+            | Self::WarpUnsupported
+            | Self::InvalidWarpTarget => Vec::new(),
+        }
+    }
+
+    /// The message's text in `catalog`, or in English if the catalog lacks it
+    pub fn render(&self, catalog: Catalog) -> String {
+        let id = self.id();
+        let template = [catalog, ENGLISH]
+            .iter()
+            .find_map(|catalog| catalog.iter().find(|(key, _)| *key == id))
+            .map_or(id, |(_, template)| *template);
+
+        self.args()
+            .iter()
+            .enumerate()
+            .fold(template.to_string(), |text, (i, arg)| {
+                text.replace(&format!("{{{}}}", i), arg)
+            })
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(ENGLISH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_catalog() {
+        assert_eq!(Message::NodeNotFound.to_string(), "Node not found");
+        assert_eq!(
+            Message::ListNodesFailed("disk full".to_string()).to_string(),
+            "Failed to list nodes: disk full"
+        );
+        assert_eq!(
+            Message::ChainNotRunning(ChainType::Bitcoin).to_string(),
+            "Bitcoin node is not running"
+        );
+
+        let mut ids: Vec<&str> = ENGLISH.iter().map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), ENGLISH.len(), "message IDs must be unique");
+
+        // Every message has English text rather than falling back to its ID
+        let arg = || "x".to_string();
+        let messages = [
+            Message::NodeNotFound,
+            Message::NodeNotRegistered(arg()),
+            Message::NodeNotRunning(arg()),
+            Message::ChainNotRunning(ChainType::Solana),
+            Message::ChainNotRunning(ChainType::Bitcoin),
+            Message::ChainNotRunning(ChainType::Ethereum),
+            Message::GetNodeFailed(arg()),
+            Message::ListNodesFailed(arg()),
+            Message::StopNodeFailed(arg()),
+            Message::StatusUpdateFailed(arg()),
+            Message::JobNotFound,
+            Message::JobFinished,
+            Message::InvalidChain,
+            Message::AmountNotPositive,
+            Message::AmountsNotPositive,
+            Message::NoRecipients,
+            Message::InvalidInstanceName(arg()),
+            Message::InvalidDisplayName(arg()),
+            Message::InvalidSnapshotName(arg()),
+            Message::TransactionFailed(arg()),
+            Message::TransferFailed(arg()),
+            Message::PaymentFailed(arg()),
+            Message::FundingFailed(arg()),
+            Message::AirdropFailed(arg()),
+            Message::MiningFailed(arg()),
+            Message::BlockNotFound(arg()),
+            Message::InvalidBlockId,
+            Message::GetBlockFailed(arg()),
+            Message::ListBlocksFailed(arg()),
+            Message::InvalidSlot,
+            Message::BlocksUnsupported,
+            Message::WarpUnsupported,
+            Message::InvalidWarpTarget,
+        ];
+        assert_eq!(messages.len(), ENGLISH.len());
+        for message in messages {
+            assert!(ids.contains(&message.id()), "{} has no text", message.id());
+        }
+    }
+
+    #[test]
+    fn test_render_falls_back_to_english() {
+        const PARTIAL: Catalog = &[("tx.mining_failed", "Minería fallida: {0}")];
+        let message = Message::MiningFailed("timeout".to_string());
+        assert_eq!(message.render(PARTIAL), "Minería fallida: timeout");
+        assert_eq!(Message::JobNotFound.render(PARTIAL), "Job not found");
+    }
+}
@@ -0,0 +1,423 @@
+//! A chain provider that runs no node, for building UIs against.
+//!
+//! [`MockProvider`] keeps balances in an in-memory ledger and makes up
+//! accounts and transaction IDs from the chain, instance, and a counter, so
+//! the same config gives the same data on every run. Latency and failures
+//! can be added, and changed while it runs, to see how a UI copes with slow
+//! or broken nodes.
+
+use crate::chain::ChainProvider;
+use crate::hooks::{HookContext, LifecycleHooks, LifecyclePhase};
+use crate::registry::{ChainType, NodeInfo, NodeStatus};
+use crate::{ChainError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Characters of base58 (Solana addresses and signatures)
+const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Characters of bech32 (Bitcoin addresses)
+const BECH32: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Settings of a mock chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockConfig {
+    /// Chain the accounts and transaction IDs look like
+    pub chain: ChainType,
+    pub instance_id: String,
+    pub name: Option<String>,
+    pub accounts: u32,
+    pub initial_balance: f64,
+    /// Delay added to every call
+    pub latency: Duration,
+    /// Fail every Nth call; calls never fail if unset
+    pub fail_every: Option<u32>,
+}
+
+impl MockConfig {
+    pub fn new(chain: ChainType, instance_id: &str) -> Self {
+        Self {
+            chain,
+            instance_id: instance_id.to_string(),
+            name: None,
+            accounts: 10,
+            initial_balance: 100.0,
+            latency: Duration::ZERO,
+            fail_every: None,
+        }
+    }
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self::new(ChainType::Solana, "mock")
+    }
+}
+
+/// An account of a mock chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockAccount {
+    pub address: String,
+    pub balance: f64,
+}
+
+/// A payment recorded by a mock chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockTransaction {
+    pub id: String,
+    /// Paying account; `None` for funding, which creates coins
+    pub from: Option<String>,
+    pub to: String,
+    pub amount: f64,
+}
+
+/// Balances and history of a mock chain
+#[derive(Debug, Default)]
+struct Ledger {
+    accounts: Vec<MockAccount>,
+    transactions: Vec<MockTransaction>,
+    latency: Duration,
+    fail_every: Option<u32>,
+    calls: u32,
+}
+
+impl Ledger {
+    fn account_mut(&mut self, address: &str) -> &mut MockAccount {
+        let index = match self.accounts.iter().position(|a| a.address == address) {
+            Some(index) => index,
+            None => {
+                self.accounts.push(MockAccount {
+                    address: address.to_string(),
+                    balance: 0.0,
+                });
+                self.accounts.len() - 1
+            }
+        };
+        &mut self.accounts[index]
+    }
+}
+
+/// A [`ChainProvider`] backed by an in-memory ledger
+///
+/// `start` creates `accounts` accounts holding `initial_balance` each;
+/// payments move balances between them and are recorded with made-up IDs.
+/// Nothing is registered or written to disk, and no process is started.
+#[derive(Debug)]
+pub struct MockProvider {
+    config: MockConfig,
+    ledger: Mutex<Ledger>,
+    running: bool,
+    hooks: LifecycleHooks,
+}
+
+impl MockProvider {
+    pub fn new(config: MockConfig) -> Self {
+        Self {
+            config,
+            ledger: Mutex::new(Ledger::default()),
+            running: false,
+            hooks: LifecycleHooks::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MockConfig {
+        &self.config
+    }
+
+    /// Registry entry for the mock node, as if it had been started
+    pub fn node_info(&self) -> NodeInfo {
+        let mut info = NodeInfo::new(
+            self.config.chain,
+            &self.config.instance_id,
+            self.config.name.clone(),
+            self.get_rpc_url(),
+            0,
+            self.config.accounts,
+        );
+        if !self.running {
+            info.status = NodeStatus::Stopped;
+            info.started_at = None;
+        }
+        info
+    }
+
+    /// Change the delay and failure rate of later calls
+    pub fn set_faults(&self, latency: Duration, fail_every: Option<u32>) {
+        let mut ledger = self.lock();
+        ledger.latency = latency;
+        ledger.fail_every = fail_every;
+    }
+
+    /// Move `amount` from one account to another
+    pub fn transfer(&self, from: &str, to: &str, amount: f64) -> Result<String> {
+        let mut ledger = self.call()?;
+        if amount <= 0.0 {
+            return Err(ChainError::Other("Amount must be positive".to_string()));
+        }
+
+        let source = ledger.account_mut(from);
+        if source.balance < amount {
+            return Err(ChainError::Rpc(format!(
+                "Insufficient funds: {} has {}, needs {}",
+                from, source.balance, amount
+            )));
+        }
+        source.balance -= amount;
+        ledger.account_mut(to).balance += amount;
+        Ok(self.record(&mut ledger, Some(from), to, amount))
+    }
+
+    /// Payments made since the chain started, oldest first
+    pub fn transactions(&self) -> Result<Vec<MockTransaction>> {
+        Ok(self.call()?.transactions.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The ledger of a running chain, after the configured delay, or the
+    /// configured failure
+    fn call(&self) -> Result<MutexGuard<'_, Ledger>> {
+        if !self.running {
+            return Err(ChainError::NotRunning);
+        }
+
+        let latency = self.lock().latency;
+        if !latency.is_zero() {
+            std::thread::sleep(latency);
+        }
+
+        let mut ledger = self.lock();
+        ledger.calls += 1;
+        if let Some(every) = ledger.fail_every.filter(|every| *every > 0) {
+            if ledger.calls % every == 0 {
+                return Err(ChainError::Rpc(format!(
+                    "Mock failure (call {})",
+                    ledger.calls
+                )));
+            }
+        }
+        Ok(ledger)
+    }
+
+    fn record(&self, ledger: &mut Ledger, from: Option<&str>, to: &str, amount: f64) -> String {
+        let seed = format!(
+            "{}:{}:tx:{}",
+            self.config.chain,
+            self.config.instance_id,
+            ledger.transactions.len()
+        );
+        let id = transaction_id(self.config.chain, &seed);
+        ledger.transactions.push(MockTransaction {
+            id: id.clone(),
+            from: from.map(str::to_string),
+            to: to.to_string(),
+            amount,
+        });
+        id
+    }
+
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            chain: self.config.chain,
+            instance_id: self.config.instance_id.clone(),
+            rpc_url: self.get_rpc_url(),
+            accounts: self
+                .lock()
+                .accounts
+                .iter()
+                .map(|a| a.address.clone())
+                .collect(),
+        }
+    }
+}
+
+impl ChainProvider for MockProvider {
+    type Account = MockAccount;
+    type Transaction = MockTransaction;
+    type Config = MockConfig;
+
+    fn start(&mut self, config: MockConfig) -> Result<()> {
+        if self.running {
+            return Err(ChainError::AlreadyRunning);
+        }
+        self.config = config;
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::BeforeStart, &ctx)?;
+
+        let accounts = (0..self.config.accounts)
+            .map(|index| MockAccount {
+                address: mock_address(self.config.chain, &self.config.instance_id, index),
+                balance: self.config.initial_balance,
+            })
+            .collect();
+        *self.lock() = Ledger {
+            accounts,
+            latency: self.config.latency,
+            fail_every: self.config.fail_every,
+            ..Ledger::default()
+        };
+        self.running = true;
+
+        let ctx = self.hook_context();
+        self.hooks.run(LifecyclePhase::AfterStart, &ctx)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        if self.running {
+            let ctx = self.hook_context();
+            self.hooks.run_before_stop(&ctx);
+            self.running = false;
+        }
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn get_accounts(&self) -> Result<Vec<MockAccount>> {
+        Ok(self.call()?.accounts.clone())
+    }
+
+    fn set_balance(&self, address: &str, amount: f64) -> Result<String> {
+        let mut ledger = self.call()?;
+        let account = ledger.account_mut(address);
+        let added = amount - account.balance;
+        account.balance = amount;
+        Ok(self.record(&mut ledger, None, address, added))
+    }
+
+    fn get_balance(&self, address: &str) -> Result<f64> {
+        Ok(self
+            .call()?
+            .accounts
+            .iter()
+            .find(|a| a.address == address)
+            .map_or(0.0, |a| a.balance))
+    }
+
+    fn get_rpc_url(&self) -> String {
+        format!("mock://{}/{}", self.config.chain, self.config.instance_id)
+    }
+
+    fn hooks(&mut self) -> &mut LifecycleHooks {
+        &mut self.hooks
+    }
+}
+
+/// Address of an account of a mock chain, shaped like one of `chain`
+pub fn mock_address(chain: ChainType, instance_id: &str, index: u32) -> String {
+    let digest = Sha256::digest(format!("{}:{}:{}", chain, instance_id, index).as_bytes());
+    match chain {
+        ChainType::Solana => encode(&digest, BASE58, 44),
+        ChainType::Bitcoin => format!("bcrt1q{}", encode(&digest, BECH32, 38)),
+        ChainType::Ethereum => format!("0x{}", hex(&digest[..20])),
+    }
+}
+
+fn transaction_id(chain: ChainType, seed: &str) -> String {
+    let digest = Sha256::digest(seed.as_bytes());
+    match chain {
+        ChainType::Solana => {
+            let second = Sha256::digest(digest);
+            let bytes: Vec<u8> = digest.iter().chain(second.iter()).copied().collect();
+            encode(&bytes, BASE58, 88)
+        }
+        ChainType::Bitcoin => hex(&digest),
+        ChainType::Ethereum => format!("0x{}", hex(&digest)),
+    }
+}
+
+/// `len` characters of `alphabet`, picked by successive bytes
+fn encode(bytes: &[u8], alphabet: &[u8], len: usize) -> String {
+    bytes
+        .iter()
+        .cycle()
+        .take(len)
+        .map(|byte| alphabet[*byte as usize % alphabet.len()] as char)
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started(config: MockConfig) -> MockProvider {
+        let mut provider = MockProvider::new(config.clone());
+        provider.start(config).unwrap();
+        provider
+    }
+
+    #[test]
+    fn test_accounts_are_deterministic() {
+        let config = MockConfig::new(ChainType::Bitcoin, "ui");
+        let first = started(config.clone()).get_accounts().unwrap();
+        let second = started(config).get_accounts().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 10);
+        assert!(first[0].address.starts_with("bcrt1q"));
+        assert_ne!(first[0].address, first[1].address);
+
+        let eth = mock_address(ChainType::Ethereum, "ui", 0);
+        assert_eq!(eth.len(), 42);
+        assert_eq!(mock_address(ChainType::Solana, "ui", 0).len(), 44);
+    }
+
+    #[test]
+    fn test_ledger_moves_funds() {
+        let provider = started(MockConfig::new(ChainType::Solana, "ledger"));
+        let accounts = provider.get_accounts().unwrap();
+        let (a, b) = (&accounts[0].address, &accounts[1].address);
+
+        provider.transfer(a, b, 40.0).unwrap();
+        assert_eq!(provider.get_balance(a).unwrap(), 60.0);
+        assert_eq!(provider.get_balance(b).unwrap(), 140.0);
+        assert!(provider.transfer(a, b, 1000.0).is_err());
+
+        provider.set_balance(a, 5.0).unwrap();
+        assert_eq!(provider.get_balance(a).unwrap(), 5.0);
+
+        let transactions = provider.transactions().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].from.as_deref(), Some(a.as_str()));
+        assert_eq!(transactions[1].amount, -55.0);
+        assert_ne!(transactions[0].id, transactions[1].id);
+    }
+
+    #[test]
+    fn test_injected_failures() {
+        let mut config = MockConfig::new(ChainType::Ethereum, "faults");
+        config.fail_every = Some(3);
+        let provider = started(config);
+
+        assert!(provider.get_accounts().is_ok());
+        assert!(provider.get_accounts().is_ok());
+        assert!(matches!(provider.get_accounts(), Err(ChainError::Rpc(_))));
+
+        provider.set_faults(Duration::ZERO, None);
+        assert!(provider.get_accounts().is_ok());
+    }
+
+    #[test]
+    fn test_stopped_chain() {
+        let mut provider = started(MockConfig::default());
+        assert_eq!(provider.node_info().status, NodeStatus::Running);
+        assert_eq!(provider.node_info().node_id, "solana:mock");
+
+        provider.stop().unwrap();
+        assert!(matches!(
+            provider.get_accounts(),
+            Err(ChainError::NotRunning)
+        ));
+        assert_eq!(provider.node_info().status, NodeStatus::Stopped);
+    }
+}
@@ -2,7 +2,13 @@
 //!
 //! This module provides a central registry that tracks all running nodes
 //! (Solana, Bitcoin, etc.) and their status. The registry is persisted to disk
-//! and supports file locking for safe concurrent access.
+//! and supports file locking for safe concurrent access. Reads check the
+//! processes the registry records without writing to it, so nodes whose
+//! process has died show as stopped; [`NodeRegistry::reconcile`] saves those
+//! statuses, as `cf nodes list` and the registry cleanup endpoint do.
+//! Providers also refresh a heartbeat while they run (see [`Heartbeat`]),
+//! and nodes whose heartbeat has lapsed show as unknown, which covers nodes
+//! whose process can't be checked from here.
 
 // Allow fs2 trait methods that have the same name as std methods stabilized in Rust 1.89
 #![allow(clippy::incompatible_msrv)]
@@ -14,6 +20,11 @@ use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
 // Import FileExt trait for file locking
 use fs2::FileExt;
@@ -24,6 +35,7 @@ use fs2::FileExt;
 pub enum ChainType {
     Solana,
     Bitcoin,
+    Ethereum,
 }
 
 impl std::fmt::Display for ChainType {
@@ -31,6 +43,7 @@ impl std::fmt::Display for ChainType {
         match self {
             ChainType::Solana => write!(f, "solana"),
             ChainType::Bitcoin => write!(f, "bitcoin"),
+            ChainType::Ethereum => write!(f, "ethereum"),
         }
     }
 }
@@ -75,6 +88,23 @@ pub struct NodeInfo {
     pub status: NodeStatus,
     /// When the node was started
     pub started_at: Option<DateTime<Utc>>,
+    /// Process ID of the chain CLI running the node, when it was started
+    /// through the API server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Process ID of the node itself (validator, bitcoind, or anvil)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_pid: Option<u32>,
+    /// Host the node runs on; PIDs are only checked on this host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// When the node's process was last seen alive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    /// Seconds between the heartbeats the node's provider sends; absent for
+    /// nodes without a provider running (such as attached public networks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_secs: Option<u64>,
 }
 
 impl NodeInfo {
@@ -87,6 +117,7 @@ impl NodeInfo {
         rpc_port: u16,
         accounts_count: u32,
     ) -> Self {
+        let now = Utc::now();
         Self {
             node_id: format!("{}:{}", chain, instance_id),
             name,
@@ -96,7 +127,12 @@ impl NodeInfo {
             rpc_port,
             accounts_count,
             status: NodeStatus::Running,
-            started_at: Some(Utc::now()),
+            started_at: Some(now),
+            pid: None,
+            node_pid: None,
+            host: Some(current_host().to_string()),
+            last_heartbeat: Some(now),
+            heartbeat_secs: None,
         }
     }
 
@@ -104,6 +140,156 @@ impl NodeInfo {
     pub fn display_name(&self) -> &str {
         self.name.as_deref().unwrap_or(&self.instance_id)
     }
+
+    /// How long the node has been running, if it is
+    pub fn uptime(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        match (self.status, self.started_at) {
+            (NodeStatus::Running, Some(started_at)) => Some(now - started_at),
+            _ => None,
+        }
+    }
+
+    /// Whether the node's provider has missed [`HEARTBEAT_MISSES`] heartbeats
+    ///
+    /// Nodes whose provider doesn't send heartbeats never expire.
+    pub fn heartbeat_expired(&self, now: DateTime<Utc>) -> bool {
+        match (self.heartbeat_secs, self.last_heartbeat) {
+            (Some(secs), Some(beat)) => {
+                (now - beat).to_std().unwrap_or_default()
+                    > Duration::from_secs(secs) * HEARTBEAT_MISSES
+            }
+            _ => false,
+        }
+    }
+
+    /// The node's status as observed now, without changing the registry
+    ///
+    /// A running node on this host with a recorded PID is stopped if that
+    /// process has exited. Other running nodes whose provider stopped
+    /// sending heartbeats are unknown; the rest (such as attached public
+    /// networks) keep their recorded status.
+    pub fn observed_status(&self, now: DateTime<Utc>) -> NodeStatus {
+        if self.status != NodeStatus::Running {
+            return self.status;
+        }
+        match self.tracked_pid().filter(|_| self.is_local()) {
+            Some(pid) if !process_alive(pid) => NodeStatus::Stopped,
+            Some(_) => NodeStatus::Running,
+            None if self.heartbeat_expired(now) => NodeStatus::Unknown,
+            None => NodeStatus::Running,
+        }
+    }
+
+    /// Process to check liveness by: the node itself, else the CLI running it
+    fn tracked_pid(&self) -> Option<u32> {
+        self.node_pid.or(self.pid)
+    }
+
+    /// Whether this node runs on the current host (entries without a host
+    /// predate host tracking and are assumed local)
+    fn is_local(&self) -> bool {
+        self.host
+            .as_deref()
+            .map_or(true, |host| host == current_host())
+    }
+}
+
+/// How often providers send heartbeats
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Heartbeats a node can miss before it shows as unknown
+pub const HEARTBEAT_MISSES: u32 = 3;
+
+/// Background thread that refreshes a node's heartbeat while its provider runs
+///
+/// The thread stops when the `Heartbeat` is stopped or dropped.
+pub struct Heartbeat {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// Send a heartbeat for `node_id` now and then every `interval`
+    pub fn start(node_id: String, interval: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = std::thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let registry = NodeRegistry::new();
+                let tick = Duration::from_millis(200);
+                let mut waited = interval;
+                while !stop.load(Ordering::Relaxed) {
+                    if waited >= interval {
+                        waited = Duration::ZERO;
+                        // A missed beat is retried on the next one
+                        let _ = registry.heartbeat(&node_id, interval);
+                    }
+                    std::thread::sleep(tick);
+                    waited += tick;
+                }
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop sending heartbeats and wait for the thread to finish
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Name of the current host
+fn current_host() -> &'static str {
+    static HOST: OnceLock<String> = OnceLock::new();
+    HOST.get_or_init(|| {
+        fs::read_to_string("/proc/sys/kernel/hostname")
+            .ok()
+            .or_else(|| {
+                Command::new("hostname")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())
+                    .and_then(|output| String::from_utf8(output.stdout).ok())
+            })
+            .map(|host| host.trim().to_string())
+            .filter(|host| !host.is_empty())
+            .unwrap_or_else(|| "localhost".to_string())
+    })
+}
+
+/// Whether a process with `pid` exists
+///
+/// Signal 0 only checks that the process exists; `EPERM` means it does but
+/// belongs to another user.
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // SAFETY: kill with signal 0 sends nothing and has no other effect
+    unsafe { libc::kill(pid, 0) == 0 }
+    || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without `kill`, processes are assumed alive and statuses left as is
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
 }
 
 /// Registry data stored on disk
@@ -121,7 +307,7 @@ pub struct NodeRegistry {
 impl NodeRegistry {
     /// Create a new NodeRegistry using the default data directory
     pub fn new() -> Self {
-        let data_dir = Self::data_dir();
+        let data_dir = crate::data_dir();
         let registry_path = data_dir.join("registry.json");
         let backup_path = data_dir.join("registry.json.bak");
 
@@ -140,13 +326,6 @@ impl NodeRegistry {
         }
     }
 
-    /// Get the data directory path
-    fn data_dir() -> PathBuf {
-        dirs::home_dir()
-            .expect("Could not determine home directory")
-            .join(".chain-forge")
-    }
-
     /// Ensure the data directory exists
     fn ensure_data_dir(&self) -> Result<()> {
         if let Some(parent) = self.registry_path.parent() {
@@ -155,6 +334,49 @@ impl NodeRegistry {
         Ok(())
     }
 
+    /// Lock the registry for a change until the returned file is dropped
+    ///
+    /// `registry.json` is replaced on every save, so the lock is held on a
+    /// separate `registry.json.lock` that stays put.
+    fn lock(&self) -> Result<File> {
+        self.ensure_data_dir()?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.registry_path.with_extension("json.lock"))?;
+        file.lock_exclusive().map_err(|e| {
+            ChainError::Other(format!(
+                "Failed to acquire exclusive lock on registry: {}",
+                e
+            ))
+        })?;
+        Ok(file)
+    }
+
+    /// Load, change, and save registry data while holding the exclusive
+    /// lock, so concurrent changes aren't lost
+    ///
+    /// `change` returns whether there is anything to save.
+    fn update(&self, change: impl FnOnce(&mut RegistryData) -> bool) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut data = self.load()?;
+        if change(&mut data) {
+            self.save(&data)?;
+        }
+        Ok(())
+    }
+
+    /// Load registry data with the status of each node as observed now
+    fn load_observed(&self) -> Result<RegistryData> {
+        let mut data = self.load()?;
+        let now = Utc::now();
+        for node in data.nodes.values_mut() {
+            node.status = node.observed_status(now);
+        }
+        Ok(data)
+    }
+
     /// Load registry data with file locking
     fn load(&self) -> Result<RegistryData> {
         if !self.registry_path.exists() {
@@ -245,43 +467,134 @@ impl NodeRegistry {
 
     /// Register a new node
     pub fn register(&self, node: NodeInfo) -> Result<()> {
-        let mut data = self.load()?;
-        data.nodes.insert(node.node_id.clone(), node);
-        self.save(&data)
+        self.update(|data| {
+            data.nodes.insert(node.node_id.clone(), node);
+            true
+        })
     }
 
     /// Unregister a node by its node_id
     pub fn unregister(&self, node_id: &str) -> Result<()> {
-        let mut data = self.load()?;
-        data.nodes.remove(node_id);
-        self.save(&data)
+        self.update(|data| {
+            data.nodes.remove(node_id);
+            true
+        })
     }
 
     /// Update a node's status
     pub fn update_status(&self, node_id: &str, status: NodeStatus) -> Result<()> {
-        let mut data = self.load()?;
-        if let Some(node) = data.nodes.get_mut(node_id) {
-            node.status = status;
-            self.save(&data)?;
-        }
-        Ok(())
+        self.update(|data| match data.nodes.get_mut(node_id) {
+            Some(node) => {
+                node.status = status;
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Update the status of several nodes in one write
+    ///
+    /// Nodes that aren't registered are skipped. Either every update is
+    /// saved or none is.
+    pub fn update_statuses(&self, statuses: &[(String, NodeStatus)]) -> Result<()> {
+        self.update(|data| {
+            let mut changed = false;
+            for (node_id, status) in statuses {
+                if let Some(node) = data.nodes.get_mut(node_id) {
+                    node.status = *status;
+                    changed = true;
+                }
+            }
+            changed
+        })
+    }
+
+    /// Record the process running a node
+    pub fn set_pid(&self, node_id: &str, pid: u32) -> Result<()> {
+        self.update(|data| match data.nodes.get_mut(node_id) {
+            Some(node) => {
+                node.pid = Some(pid);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Record the node's own process, after it was replaced by a new one
+    pub fn set_node_pid(&self, node_id: &str, pid: u32) -> Result<()> {
+        self.update(|data| match data.nodes.get_mut(node_id) {
+            Some(node) => {
+                node.node_pid = Some(pid);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Record a heartbeat from a node's provider, which sends one every
+    /// `interval`
+    ///
+    /// A node that showed as unknown because its heartbeat lapsed is running
+    /// again.
+    pub fn heartbeat(&self, node_id: &str, interval: Duration) -> Result<()> {
+        self.update(|data| match data.nodes.get_mut(node_id) {
+            Some(node) => {
+                node.last_heartbeat = Some(Utc::now());
+                node.heartbeat_secs = Some(interval.as_secs().max(1));
+                if node.status == NodeStatus::Unknown {
+                    node.status = NodeStatus::Running;
+                }
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Save the status of each node as observed now
+    ///
+    /// Running nodes whose process has exited are marked stopped, and those
+    /// whose heartbeat has lapsed unknown (see [`NodeInfo::observed_status`]).
+    /// Returns the IDs of nodes marked stopped.
+    pub fn reconcile(&self) -> Result<Vec<String>> {
+        let now = Utc::now();
+        let mut stopped = Vec::new();
+        self.update(|data| {
+            let mut changed = false;
+            for node in data.nodes.values_mut() {
+                let status = node.observed_status(now);
+                if status != node.status {
+                    if status == NodeStatus::Stopped {
+                        stopped.push(node.node_id.clone());
+                    }
+                    node.status = status;
+                    changed = true;
+                }
+            }
+            changed
+        })?;
+        Ok(stopped)
+    }
+
+    /// Get a specific node by ID as recorded, without checking its process
+    pub fn get_recorded(&self, node_id: &str) -> Result<Option<NodeInfo>> {
+        Ok(self.load()?.nodes.remove(node_id))
     }
 
-    /// Get a specific node by ID
+    /// Get a specific node by ID, with its status as observed now
     pub fn get(&self, node_id: &str) -> Result<Option<NodeInfo>> {
-        let data = self.load()?;
+        let data = self.load_observed()?;
         Ok(data.nodes.get(node_id).cloned())
     }
 
-    /// List all registered nodes
+    /// List all registered nodes, with their status as observed now
     pub fn list(&self) -> Result<Vec<NodeInfo>> {
-        let data = self.load()?;
+        let data = self.load_observed()?;
         Ok(data.nodes.values().cloned().collect())
     }
 
-    /// List nodes by chain type
+    /// List nodes by chain type, with their status as observed now
     pub fn list_by_chain(&self, chain: ChainType) -> Result<Vec<NodeInfo>> {
-        let data = self.load()?;
+        let data = self.load_observed()?;
         Ok(data
             .nodes
             .values()
@@ -292,21 +605,23 @@ impl NodeRegistry {
 
     /// Mark all nodes of a chain type as stopped
     pub fn mark_all_stopped(&self, chain: ChainType) -> Result<()> {
-        let mut data = self.load()?;
-        for node in data.nodes.values_mut() {
-            if node.chain == chain {
-                node.status = NodeStatus::Stopped;
+        self.update(|data| {
+            for node in data.nodes.values_mut() {
+                if node.chain == chain {
+                    node.status = NodeStatus::Stopped;
+                }
             }
-        }
-        self.save(&data)
+            true
+        })
     }
 
     /// Clear all stopped nodes from the registry
     pub fn clear_stopped(&self) -> Result<()> {
-        let mut data = self.load()?;
-        data.nodes
-            .retain(|_, node| node.status != NodeStatus::Stopped);
-        self.save(&data)
+        self.update(|data| {
+            data.nodes
+                .retain(|_, node| node.status != NodeStatus::Stopped);
+            true
+        })
     }
 
     /// Generate a node_id from chain and instance_id
@@ -426,6 +741,211 @@ mod tests {
         assert_eq!(fetched.status, NodeStatus::Stopped);
     }
 
+    #[test]
+    fn test_update_statuses() {
+        let (registry, _dir) = create_test_registry();
+        for instance in ["a", "b"] {
+            let node = NodeInfo::new(
+                ChainType::Solana,
+                instance,
+                None,
+                "http://localhost:8899".to_string(),
+                8899,
+                1,
+            );
+            registry.register(node).unwrap();
+        }
+
+        registry
+            .update_statuses(&[
+                ("solana:a".to_string(), NodeStatus::Stopped),
+                ("solana:b".to_string(), NodeStatus::Unknown),
+                ("solana:missing".to_string(), NodeStatus::Stopped),
+            ])
+            .unwrap();
+
+        let status = |id: &str| registry.get(id).unwrap().unwrap().status;
+        assert_eq!(status("solana:a"), NodeStatus::Stopped);
+        assert_eq!(status("solana:b"), NodeStatus::Unknown);
+        assert!(registry.get("solana:missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_set_pid() {
+        let (registry, _dir) = create_test_registry();
+
+        let node = NodeInfo::new(
+            ChainType::Bitcoin,
+            "btc1",
+            None,
+            "http://localhost:18443".to_string(),
+            18443,
+            5,
+        );
+        assert!(node.pid.is_none());
+
+        registry.register(node).unwrap();
+        registry.set_pid("bitcoin:btc1", 4242).unwrap();
+
+        let fetched = registry.get("bitcoin:btc1").unwrap().unwrap();
+        assert_eq!(fetched.pid, Some(4242));
+
+        // Unknown nodes are ignored
+        registry.set_pid("bitcoin:missing", 1).unwrap();
+        assert!(registry.get("bitcoin:missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_node_info_records_host() {
+        let node = NodeInfo::new(
+            ChainType::Solana,
+            "sol1",
+            None,
+            "http://localhost:8899".to_string(),
+            8899,
+            10,
+        );
+        assert_eq!(node.host.as_deref(), Some(current_host()));
+        assert!(node.last_heartbeat.is_some());
+        assert!(node.node_pid.is_none());
+    }
+
+    #[test]
+    fn test_uptime() {
+        let mut node = NodeInfo::new(
+            ChainType::Solana,
+            "sol1",
+            None,
+            "http://localhost:8899".to_string(),
+            8899,
+            10,
+        );
+        let started_at = node.started_at.unwrap();
+        let later = started_at + chrono::Duration::minutes(5);
+        assert_eq!(node.uptime(later), Some(chrono::Duration::minutes(5)));
+
+        node.status = NodeStatus::Stopped;
+        assert!(node.uptime(later).is_none());
+    }
+
+    #[test]
+    fn test_registry_without_process_fields_loads() {
+        let (registry, _dir) = create_test_registry();
+        fs::write(
+            &registry.registry_path,
+            r#"{"nodes":{"solana:old":{"node_id":"solana:old","name":null,"chain":"solana","instance_id":"old","rpc_url":"http://localhost:8899","rpc_port":8899,"accounts_count":10,"status":"running","started_at":null}}}"#,
+        )
+        .unwrap();
+
+        let node = registry.get("solana:old").unwrap().unwrap();
+        assert_eq!(node.status, NodeStatus::Running);
+        assert!(node.host.is_none());
+    }
+
+    #[test]
+    fn test_lapsed_heartbeat_marks_node_unknown() {
+        let (registry, _dir) = create_test_registry();
+
+        let mut node = NodeInfo::new(
+            ChainType::Ethereum,
+            "eth1",
+            None,
+            "http://localhost:8545".to_string(),
+            8545,
+            10,
+        );
+        let now = Utc::now();
+        node.heartbeat_secs = Some(30);
+        node.last_heartbeat = Some(now - chrono::Duration::seconds(60));
+        assert!(!node.heartbeat_expired(now));
+        node.last_heartbeat = Some(now - chrono::Duration::seconds(120));
+        assert!(node.heartbeat_expired(now));
+
+        // Nodes without a provider sending heartbeats never expire
+        let mut attached = node.clone();
+        attached.node_id = "ethereum:attached".to_string();
+        attached.heartbeat_secs = None;
+
+        registry.register(node).unwrap();
+        registry.register(attached).unwrap();
+        let status = |id: &str| registry.get(id).unwrap().unwrap().status;
+        assert_eq!(status("ethereum:eth1"), NodeStatus::Unknown);
+        assert_eq!(status("ethereum:attached"), NodeStatus::Running);
+
+        // The next heartbeat brings it back
+        registry
+            .heartbeat("ethereum:eth1", HEARTBEAT_INTERVAL)
+            .unwrap();
+        assert_eq!(status("ethereum:eth1"), NodeStatus::Running);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reconcile_marks_dead_nodes_stopped() {
+        let (registry, _dir) = create_test_registry();
+
+        let mut child = Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+
+        let mut alive = NodeInfo::new(
+            ChainType::Solana,
+            "alive",
+            None,
+            "http://localhost:8899".to_string(),
+            8899,
+            10,
+        );
+        alive.node_pid = Some(std::process::id());
+        alive.last_heartbeat = None;
+        let mut dead = NodeInfo::new(
+            ChainType::Bitcoin,
+            "dead",
+            None,
+            "http://localhost:18443".to_string(),
+            18443,
+            5,
+        );
+        dead.node_pid = Some(dead_pid);
+        let mut remote = dead.clone();
+        remote.node_id = "bitcoin:remote".to_string();
+        remote.host = Some("some-other-host".to_string());
+        let untracked = NodeInfo::new(
+            ChainType::Ethereum,
+            "untracked",
+            None,
+            "http://localhost:8545".to_string(),
+            8545,
+            10,
+        );
+
+        for node in [alive, dead, remote, untracked] {
+            registry.register(node).unwrap();
+        }
+
+        // Reads show what they observe but leave the registry as it was
+        let status = |id: &str| registry.get(id).unwrap().unwrap().status;
+        assert_eq!(status("bitcoin:dead"), NodeStatus::Stopped);
+        let recorded = |id: &str| registry.get_recorded(id).unwrap().unwrap().status;
+        assert_eq!(recorded("bitcoin:dead"), NodeStatus::Running);
+
+        assert_eq!(registry.reconcile().unwrap(), vec!["bitcoin:dead"]);
+        assert_eq!(recorded("bitcoin:dead"), NodeStatus::Stopped);
+        assert_eq!(status("solana:alive"), NodeStatus::Running);
+        assert_eq!(status("bitcoin:remote"), NodeStatus::Running);
+        assert_eq!(status("ethereum:untracked"), NodeStatus::Running);
+        // Only providers send heartbeats
+        assert!(registry
+            .get("solana:alive")
+            .unwrap()
+            .unwrap()
+            .last_heartbeat
+            .is_none());
+
+        // Reconciling again changes nothing
+        assert!(registry.reconcile().unwrap().is_empty());
+    }
+
     #[test]
     fn test_list_by_chain() {
         let (registry, _dir) = create_test_registry();
@@ -509,6 +1029,7 @@ mod tests {
     fn test_chain_type_display() {
         assert_eq!(format!("{}", ChainType::Solana), "solana");
         assert_eq!(format!("{}", ChainType::Bitcoin), "bitcoin");
+        assert_eq!(format!("{}", ChainType::Ethereum), "ethereum");
     }
 
     #[test]
@@ -0,0 +1,78 @@
+//! Retry policy for operations that fail transiently, such as airdrops
+//! from a rate-limited faucet while funding accounts at startup.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How many times to try an operation and how long to wait in between
+///
+/// The wait starts at `delay_ms` and is multiplied by `backoff` after each
+/// further failure, up to `max_delay_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts in all, including the first
+    pub max_attempts: u32,
+    /// Milliseconds to wait after the first failure
+    pub delay_ms: u64,
+    /// Factor the wait grows by after each further failure (1 keeps it fixed)
+    pub backoff: f64,
+    /// Longest wait between attempts, in milliseconds
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            delay_ms: 2000,
+            backoff: 1.0,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Attempts to make, at least one
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts.max(1)
+    }
+
+    /// Wait before the next attempt once `failures` attempts have failed
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = self
+            .backoff
+            .max(1.0)
+            .powi(failures.saturating_sub(1) as i32);
+        let millis = (self.delay_ms as f64 * factor).min(self.max_delay_ms as f64);
+        Duration::from_millis(millis as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_fixed_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.attempts(), 3);
+        assert_eq!(policy.delay(1), Duration::from_secs(2));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 0,
+            delay_ms: 500,
+            backoff: 2.0,
+            max_delay_ms: 3000,
+        };
+        assert_eq!(policy.attempts(), 1);
+        assert_eq!(policy.delay(1), Duration::from_millis(500));
+        assert_eq!(policy.delay(3), Duration::from_millis(2000));
+        assert_eq!(policy.delay(4), Duration::from_millis(3000));
+    }
+}
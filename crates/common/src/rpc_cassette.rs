@@ -0,0 +1,344 @@
+//! Recording RPC calls to a cassette file and replaying them.
+//!
+//! While a cassette records, the Solana, Bitcoin, and Ethereum RPC clients
+//! pass every JSON-RPC call through to the node and append the method,
+//! params, and result or error to the cassette file. While one replays,
+//! they answer each call from the file instead, so code built on the
+//! clients can be tested without nodes.
+//!
+//! A call is answered by the first unused interaction with the same method
+//! and params, so repeated calls get their recorded results in order.
+//! Endpoints are recorded but not matched, so a replay doesn't depend on
+//! the ports the recording used.
+//!
+//! Binaries pick a cassette up from [`RECORD_ENV`] or [`REPLAY_ENV`]; tests
+//! install one with [`Cassette::install`].
+
+use crate::{json_file, ChainError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, RwLock};
+
+/// Environment variable naming a cassette file to record calls to
+pub const RECORD_ENV: &str = "CHAIN_FORGE_RPC_RECORD";
+
+/// Environment variable naming a cassette file to replay calls from
+pub const REPLAY_ENV: &str = "CHAIN_FORGE_RPC_REPLAY";
+
+/// Error returned by a recorded call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedError {
+    /// JSON-RPC error code; absent for transport errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<i64>,
+    pub message: String,
+}
+
+/// What a call returned: its JSON-RPC result or error
+pub type Outcome = std::result::Result<Value, RecordedError>;
+
+/// One recorded call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub endpoint: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+}
+
+impl Interaction {
+    fn outcome(&self) -> Outcome {
+        match &self.error {
+            Some(error) => Err(error.clone()),
+            None => Ok(self.result.clone().unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// Whether a cassette records calls or replays them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// RPC calls recorded to, or replayed from, a file
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    interactions: Mutex<Vec<Interaction>>,
+    /// Replayed interactions already used
+    used: Mutex<Vec<bool>>,
+}
+
+impl Cassette {
+    /// Record calls to `path`, replacing what it held
+    pub fn record(path: &Path) -> Self {
+        Self {
+            mode: CassetteMode::Record,
+            path: path.to_path_buf(),
+            interactions: Mutex::new(Vec::new()),
+            used: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Replay the calls recorded in `path`
+    pub fn replay(path: &Path) -> Result<Self> {
+        let file: CassetteFile = json_file::read(path).map_err(|e| {
+            ChainError::Config(format!(
+                "Failed to read RPC cassette {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path: path.to_path_buf(),
+            used: Mutex::new(vec![false; file.interactions.len()]),
+            interactions: Mutex::new(file.interactions),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.mode == CassetteMode::Replay
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Recorded calls so far, or the calls to replay
+    pub fn interactions(&self) -> Vec<Interaction> {
+        lock(&self.interactions).clone()
+    }
+
+    /// Append a call and write the cassette file
+    ///
+    /// The file is rewritten on every call, so a recording is complete
+    /// even if the process is killed. Failing to write it only prints a
+    /// warning, since the call itself went through.
+    pub fn record_call(&self, endpoint: &str, method: &str, params: &Value, outcome: &Outcome) {
+        let mut interactions = lock(&self.interactions);
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result.clone()), None),
+            Err(error) => (None, Some(error.clone())),
+        };
+        interactions.push(Interaction {
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            params: params.clone(),
+            result,
+            error,
+        });
+        let file = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        if let Err(e) = json_file::write_atomic(&self.path, &file) {
+            eprintln!(
+                "⚠️  Failed to write RPC cassette {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+
+    /// Recorded outcome of the next call to `method` with `params`
+    ///
+    /// Calls that weren't recorded fail with an error naming them.
+    pub fn replay_call(&self, method: &str, params: &Value) -> Outcome {
+        let interactions = lock(&self.interactions);
+        let mut used = lock(&self.used);
+        let found = (0..interactions.len()).find(|&i| {
+            !used[i] && interactions[i].method == method && interactions[i].params == *params
+        });
+        match found {
+            Some(i) => {
+                used[i] = true;
+                interactions[i].outcome()
+            }
+            None => Err(RecordedError {
+                code: None,
+                message: format!(
+                    "No recorded response for {} with params {} in {}",
+                    method,
+                    params,
+                    self.path.display()
+                ),
+            }),
+        }
+    }
+
+    /// Make this the cassette every RPC client uses until the guard drops
+    ///
+    /// Installing waits for any other installed cassette to be dropped, so
+    /// tests that use cassettes don't see each other's calls.
+    pub fn install(self) -> CassetteGuard {
+        let serial = INSTALL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let cassette = Arc::new(self);
+        // Installing first means the environment can't replace it later
+        FROM_ENV.get_or_init(|| ());
+        set_active(Some(cassette.clone()));
+        CassetteGuard {
+            cassette,
+            _serial: serial,
+        }
+    }
+}
+
+/// Keeps a cassette installed; uninstalls it when dropped
+pub struct CassetteGuard {
+    cassette: Arc<Cassette>,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl CassetteGuard {
+    pub fn cassette(&self) -> &Cassette {
+        &self.cassette
+    }
+}
+
+impl Drop for CassetteGuard {
+    fn drop(&mut self) {
+        set_active(None);
+    }
+}
+
+static INSTALL_LOCK: Mutex<()> = Mutex::new(());
+
+static ACTIVE: RwLock<Option<Arc<Cassette>>> = RwLock::new(None);
+
+static FROM_ENV: OnceLock<()> = OnceLock::new();
+
+/// The cassette RPC clients record to or replay from, if any
+///
+/// The first call picks up [`RECORD_ENV`] or [`REPLAY_ENV`]. A replay
+/// cassette that can't be read replays nothing, so calls fail instead of
+/// reaching live nodes.
+pub fn active() -> Option<Arc<Cassette>> {
+    FROM_ENV.get_or_init(|| {
+        if let Some(cassette) = from_env() {
+            set_active(Some(Arc::new(cassette)));
+        }
+    });
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn set_active(cassette: Option<Arc<Cassette>>) {
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = cassette;
+}
+
+fn from_env() -> Option<Cassette> {
+    let path = |name: &str| std::env::var_os(name).filter(|path| !path.is_empty());
+    if let Some(path) = path(REPLAY_ENV) {
+        let path = PathBuf::from(path);
+        return Some(Cassette::replay(&path).unwrap_or_else(|e| {
+            eprintln!("⚠️  {}", e);
+            Cassette {
+                mode: CassetteMode::Replay,
+                path,
+                interactions: Mutex::new(Vec::new()),
+                used: Mutex::new(Vec::new()),
+            }
+        }));
+    }
+    path(RECORD_ENV).map(|path| Cassette::record(Path::new(&path)))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        let recording = Cassette::record(&path);
+        let height = |n: u64| -> Outcome { Ok(json!(n)) };
+        recording.record_call(
+            "http://localhost:18443",
+            "getblockcount",
+            &json!([]),
+            &height(101),
+        );
+        recording.record_call(
+            "http://localhost:18443",
+            "getblockcount",
+            &json!([]),
+            &height(102),
+        );
+        let missing = Err(RecordedError {
+            code: Some(-5),
+            message: "Block not found".to_string(),
+        });
+        recording.record_call(
+            "http://localhost:18443",
+            "getblock",
+            &json!(["00aa", 1]),
+            &missing,
+        );
+
+        // Repeated calls replay in order, and errors replay as errors
+        let replay = Cassette::replay(&path).unwrap();
+        assert!(replay.is_replaying());
+        assert_eq!(replay.replay_call("getblockcount", &json!([])), height(101));
+        assert_eq!(replay.replay_call("getblock", &json!(["00aa", 1])), missing);
+        assert_eq!(replay.replay_call("getblockcount", &json!([])), height(102));
+
+        let unrecorded = replay.replay_call("getblockcount", &json!([])).unwrap_err();
+        assert!(unrecorded
+            .message
+            .contains("No recorded response for getblockcount"));
+    }
+
+    #[test]
+    fn test_params_must_match() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        Cassette::record(&path).record_call(
+            "http://localhost:8899",
+            "getBalance",
+            &json!(["a"]),
+            &Ok(json!(1)),
+        );
+
+        let replay = Cassette::replay(&path).unwrap();
+        assert!(replay.replay_call("getBalance", &json!(["b"])).is_err());
+        assert_eq!(
+            replay.replay_call("getBalance", &json!(["a"])),
+            Ok(json!(1))
+        );
+    }
+
+    #[test]
+    fn test_install_sets_active() {
+        let dir = tempdir().unwrap();
+        let guard = Cassette::record(&dir.path().join("session.json")).install();
+        let current = active().unwrap();
+        assert_eq!(current.mode(), CassetteMode::Record);
+        assert_eq!(current.path(), guard.cassette().path());
+
+        drop(guard);
+        assert!(active().is_none());
+    }
+}
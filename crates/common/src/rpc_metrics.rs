@@ -0,0 +1,398 @@
+//! Per-endpoint RPC call metrics.
+//!
+//! The Solana and Bitcoin RPC clients record every call here: counts, errors,
+//! and latency per method, plus a short log of slow calls. Metrics live in
+//! the process that made the calls, so the API server reports the calls it
+//! made itself.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Latest calls per method that percentiles are computed over
+pub const LATENCY_SAMPLES: usize = 1024;
+
+/// Slow calls kept per endpoint
+pub const SLOW_CALLS: usize = 50;
+
+/// Calls slower than this are logged as slow unless changed
+pub const DEFAULT_SLOW_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Call counts and latency for one RPC method
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodStats {
+    pub method: String,
+    pub count: u64,
+    pub errors: u64,
+    /// `errors / count`
+    pub error_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// A call that took longer than the slow-call threshold
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowCall {
+    pub method: String,
+    pub duration_ms: f64,
+    pub success: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Metrics for one RPC endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointStats {
+    pub endpoint: String,
+    pub methods: Vec<MethodStats>,
+    /// Most recent first
+    pub slow_calls: Vec<SlowCall>,
+}
+
+#[derive(Default)]
+struct MethodSamples {
+    count: u64,
+    errors: u64,
+    latencies: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+struct EndpointSamples {
+    methods: BTreeMap<String, MethodSamples>,
+    slow_calls: VecDeque<SlowCall>,
+}
+
+/// RPC call metrics, keyed by endpoint URL
+pub struct RpcMetrics {
+    endpoints: Mutex<BTreeMap<String, EndpointSamples>>,
+    slow_threshold_ms: AtomicU64,
+    log_slow_calls: AtomicBool,
+}
+
+impl Default for RpcMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RpcMetrics {
+    pub fn new() -> Self {
+        Self {
+            endpoints: Mutex::new(BTreeMap::new()),
+            slow_threshold_ms: AtomicU64::new(DEFAULT_SLOW_THRESHOLD.as_millis() as u64),
+            log_slow_calls: AtomicBool::new(false),
+        }
+    }
+
+    /// Change the duration above which calls count as slow
+    pub fn set_slow_threshold(&self, threshold: Duration) {
+        self.slow_threshold_ms
+            .store(threshold.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Also print slow calls to stderr as they happen
+    pub fn set_log_slow_calls(&self, enabled: bool) {
+        self.log_slow_calls.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Record one call to `method` on `endpoint`
+    pub fn record(&self, endpoint: &str, method: &str, duration: Duration, success: bool) {
+        let slow = duration.as_millis() as u64 >= self.slow_threshold_ms.load(Ordering::Relaxed);
+        if slow && self.log_slow_calls.load(Ordering::Relaxed) {
+            eprintln!(
+                "🐢 Slow RPC call: {} on {} took {} ms{}",
+                method,
+                endpoint,
+                duration.as_millis(),
+                if success { "" } else { " (failed)" }
+            );
+        }
+
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = endpoints.entry(endpoint.to_string()).or_default();
+
+        let stats = samples.methods.entry(method.to_string()).or_default();
+        stats.count += 1;
+        if !success {
+            stats.errors += 1;
+        }
+        if stats.latencies.len() == LATENCY_SAMPLES {
+            stats.latencies.pop_front();
+        }
+        stats.latencies.push_back(duration);
+
+        if slow {
+            if samples.slow_calls.len() == SLOW_CALLS {
+                samples.slow_calls.pop_back();
+            }
+            samples.slow_calls.push_front(SlowCall {
+                method: method.to_string(),
+                duration_ms: millis(duration),
+                success,
+                timestamp: Utc::now(),
+            });
+        }
+    }
+
+    /// Metrics for one endpoint, if any call to it was recorded
+    pub fn stats(&self, endpoint: &str) -> Option<EndpointStats> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints
+            .get(endpoint)
+            .map(|samples| summarize(endpoint, samples))
+    }
+
+    /// Metrics for every endpoint
+    pub fn all(&self) -> Vec<EndpointStats> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints
+            .iter()
+            .map(|(endpoint, samples)| summarize(endpoint, samples))
+            .collect()
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let all = self.all();
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP chain_forge_rpc_requests_total RPC calls made, by endpoint and method"
+        );
+        let _ = writeln!(out, "# TYPE chain_forge_rpc_requests_total counter");
+        for endpoint in &all {
+            for method in &endpoint.methods {
+                let _ = writeln!(
+                    out,
+                    "chain_forge_rpc_requests_total{} {}",
+                    labels(&endpoint.endpoint, &method.method, None),
+                    method.count
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP chain_forge_rpc_errors_total RPC calls that failed, by endpoint and method"
+        );
+        let _ = writeln!(out, "# TYPE chain_forge_rpc_errors_total counter");
+        for endpoint in &all {
+            for method in &endpoint.methods {
+                let _ = writeln!(
+                    out,
+                    "chain_forge_rpc_errors_total{} {}",
+                    labels(&endpoint.endpoint, &method.method, None),
+                    method.errors
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP chain_forge_rpc_latency_seconds RPC latency over the latest {} calls per method",
+            LATENCY_SAMPLES
+        );
+        let _ = writeln!(out, "# TYPE chain_forge_rpc_latency_seconds summary");
+        for endpoint in &all {
+            for method in &endpoint.methods {
+                for (quantile, value) in [
+                    ("0.5", method.p50_ms),
+                    ("0.95", method.p95_ms),
+                    ("0.99", method.p99_ms),
+                ] {
+                    let _ = writeln!(
+                        out,
+                        "chain_forge_rpc_latency_seconds{} {}",
+                        labels(&endpoint.endpoint, &method.method, Some(quantile)),
+                        value / 1000.0
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn summarize(endpoint: &str, samples: &EndpointSamples) -> EndpointStats {
+    let methods = samples
+        .methods
+        .iter()
+        .map(|(method, stats)| {
+            let mut latencies: Vec<Duration> = stats.latencies.iter().copied().collect();
+            latencies.sort_unstable();
+            MethodStats {
+                method: method.clone(),
+                count: stats.count,
+                errors: stats.errors,
+                error_rate: if stats.count == 0 {
+                    0.0
+                } else {
+                    stats.errors as f64 / stats.count as f64
+                },
+                p50_ms: millis(percentile(&latencies, 50)),
+                p95_ms: millis(percentile(&latencies, 95)),
+                p99_ms: millis(percentile(&latencies, 99)),
+                max_ms: millis(latencies.last().copied().unwrap_or_default()),
+            }
+        })
+        .collect();
+
+    EndpointStats {
+        endpoint: endpoint.to_string(),
+        methods,
+        slow_calls: samples.slow_calls.iter().cloned().collect(),
+    }
+}
+
+/// Nearest-rank percentile of sorted samples; zero when there are none
+fn percentile(sorted: &[Duration], percentile: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn labels(endpoint: &str, method: &str, quantile: Option<&str>) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    };
+    match quantile {
+        Some(quantile) => format!(
+            "{{endpoint=\"{}\",method=\"{}\",quantile=\"{}\"}}",
+            escape(endpoint),
+            escape(method),
+            quantile
+        ),
+        None => format!(
+            "{{endpoint=\"{}\",method=\"{}\"}}",
+            escape(endpoint),
+            escape(method)
+        ),
+    }
+}
+
+/// Process-wide metrics the RPC clients record into
+pub fn global() -> &'static RpcMetrics {
+    static METRICS: OnceLock<RpcMetrics> = OnceLock::new();
+    METRICS.get_or_init(RpcMetrics::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDPOINT: &str = "http://localhost:8899";
+
+    #[test]
+    fn test_record_and_summarize() {
+        let metrics = RpcMetrics::new();
+        for ms in 1..=100 {
+            metrics.record(ENDPOINT, "getSlot", Duration::from_millis(ms), ms % 10 != 0);
+        }
+        metrics.record(ENDPOINT, "getBalance", Duration::from_millis(5), true);
+
+        let stats = metrics.stats(ENDPOINT).unwrap();
+        assert_eq!(stats.methods.len(), 2);
+
+        let slot = stats
+            .methods
+            .iter()
+            .find(|m| m.method == "getSlot")
+            .unwrap();
+        assert_eq!(slot.count, 100);
+        assert_eq!(slot.errors, 10);
+        assert_eq!(slot.error_rate, 0.1);
+        assert_eq!(slot.p50_ms, 50.0);
+        assert_eq!(slot.p95_ms, 95.0);
+        assert_eq!(slot.p99_ms, 99.0);
+        assert_eq!(slot.max_ms, 100.0);
+
+        assert!(metrics.stats("http://localhost:18443").is_none());
+    }
+
+    #[test]
+    fn test_slow_calls() {
+        let metrics = RpcMetrics::new();
+        metrics.set_slow_threshold(Duration::from_millis(100));
+        metrics.record(ENDPOINT, "getSlot", Duration::from_millis(10), true);
+        metrics.record(ENDPOINT, "getBlock", Duration::from_millis(150), true);
+        metrics.record(
+            ENDPOINT,
+            "sendTransaction",
+            Duration::from_millis(300),
+            false,
+        );
+
+        let slow = metrics.stats(ENDPOINT).unwrap().slow_calls;
+        assert_eq!(slow.len(), 2);
+        assert_eq!(slow[0].method, "sendTransaction");
+        assert!(!slow[0].success);
+        assert_eq!(slow[1].method, "getBlock");
+
+        for _ in 0..SLOW_CALLS + 5 {
+            metrics.record(ENDPOINT, "getBlock", Duration::from_secs(1), true);
+        }
+        assert_eq!(
+            metrics.stats(ENDPOINT).unwrap().slow_calls.len(),
+            SLOW_CALLS
+        );
+    }
+
+    #[test]
+    fn test_latency_window() {
+        let metrics = RpcMetrics::new();
+        for _ in 0..LATENCY_SAMPLES {
+            metrics.record(ENDPOINT, "getSlot", Duration::from_secs(1), true);
+        }
+        for _ in 0..LATENCY_SAMPLES {
+            metrics.record(ENDPOINT, "getSlot", Duration::from_millis(1), true);
+        }
+
+        // Old samples age out, but the count covers every call
+        let stats = metrics.stats(ENDPOINT).unwrap();
+        assert_eq!(stats.methods[0].count, 2 * LATENCY_SAMPLES as u64);
+        assert_eq!(stats.methods[0].max_ms, 1.0);
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = RpcMetrics::new();
+        metrics.record(ENDPOINT, "getSlot", Duration::from_millis(20), true);
+        metrics.record(ENDPOINT, "getSlot", Duration::from_millis(40), false);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("# TYPE chain_forge_rpc_requests_total counter"));
+        assert!(text.contains(
+            "chain_forge_rpc_requests_total{endpoint=\"http://localhost:8899\",method=\"getSlot\"} 2"
+        ));
+        assert!(text.contains(
+            "chain_forge_rpc_errors_total{endpoint=\"http://localhost:8899\",method=\"getSlot\"} 1"
+        ));
+        assert!(text.contains(
+            "chain_forge_rpc_latency_seconds{endpoint=\"http://localhost:8899\",method=\"getSlot\",quantile=\"0.5\"} 0.02"
+        ));
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        assert_eq!(
+            labels("a\"b", "m\\n", None),
+            "{endpoint=\"a\\\"b\",method=\"m\\\\n\"}"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Gap-limit scanning for funded accounts derived from a mnemonic.
+//!
+//! A mnemonic used in another wallet can have funds on accounts past the
+//! ones an instance generated. Like wallets do, the scan keeps deriving
+//! accounts until it has seen `gap_limit` empty ones in a row.
+
+use crate::Result;
+
+/// Empty accounts in a row after which a scan stops (the BIP44 default)
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// A derived account that holds funds
+#[derive(Debug, Clone)]
+pub struct FundedAccount<A> {
+    /// Derivation index of the account
+    pub index: u32,
+    pub account: A,
+    pub balance: f64,
+}
+
+/// Derive accounts from `start` on until `gap_limit` in a row hold nothing
+///
+/// Accounts are checked in batches of `gap_limit`: `balances` is given a
+/// batch and returns the balance of each account in it, in order.
+pub fn gap_scan<A>(
+    start: u32,
+    gap_limit: u32,
+    mut derive: impl FnMut(u32) -> Result<A>,
+    mut balances: impl FnMut(&[A]) -> Result<Vec<f64>>,
+) -> Result<Vec<FundedAccount<A>>> {
+    let gap_limit = gap_limit.max(1);
+    let mut funded = Vec::new();
+    let mut gap = 0;
+    let mut next = start;
+
+    while gap < gap_limit {
+        let end = next.saturating_add(gap_limit);
+        if end == next {
+            break;
+        }
+        let batch = (next..end).map(&mut derive).collect::<Result<Vec<_>>>()?;
+        let batch_balances = balances(&batch)?;
+
+        for ((index, account), balance) in (next..end).zip(batch).zip(batch_balances) {
+            if balance > 0.0 {
+                gap = 0;
+                funded.push(FundedAccount {
+                    index,
+                    account,
+                    balance,
+                });
+            } else {
+                gap += 1;
+            }
+        }
+        next = end;
+    }
+    Ok(funded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gap_scan_stops_after_gap() {
+        // Funds on 3 and 7; with a gap limit of 4, 7 is found but not 13
+        let funded_at = [3, 7, 13];
+        let mut checked = 0;
+        let found = gap_scan(0, 4, Ok, |batch: &[u32]| {
+            checked += batch.len();
+            Ok(batch
+                .iter()
+                .map(|i| if funded_at.contains(i) { 1.5 } else { 0.0 })
+                .collect())
+        })
+        .unwrap();
+
+        let indices: Vec<u32> = found.iter().map(|f| f.index).collect();
+        assert_eq!(indices, [3, 7]);
+        assert_eq!(checked, 12);
+        assert_eq!(found[0].balance, 1.5);
+    }
+
+    #[test]
+    fn test_gap_scan_from_start_index() {
+        let found = gap_scan(
+            10,
+            DEFAULT_GAP_LIMIT,
+            |i| Ok(i * 2),
+            |batch: &[u32]| Ok(batch.iter().map(|a| (*a == 24) as u8 as f64).collect()),
+        )
+        .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].index, 12);
+        assert_eq!(found[0].account, 24);
+    }
+}
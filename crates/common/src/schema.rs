@@ -0,0 +1,345 @@
+//! Versioned on-disk formats and their migrations.
+//!
+//! Files such as `accounts.json` and `instance.json` carry a top-level
+//! `version`. A [`Schema`] lists the migrations between versions; reading a
+//! file runs the ones it is missing and rewrites it in the current format,
+//! so older files keep loading after a format change. Files written before
+//! versioning existed are version 0.
+//!
+//! To change a format, append a migration that upgrades the previous
+//! version's JSON. Never edit or remove an existing one.
+
+use crate::secrets::{EncryptionHeader, Protection, SealingKey, PASSPHRASE_ENV};
+use crate::{json_file, ChainError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Upgrades a file's JSON from one version to the next
+pub type Migration = fn(Value) -> Result<Value>;
+
+/// Migrations of one file format; `migrations[n]` upgrades version `n` to `n + 1`
+pub struct Schema {
+    migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub const fn new(migrations: &'static [Migration]) -> Self {
+        Self { migrations }
+    }
+
+    /// Version files are written with
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    /// Write `value`, which must serialize to a JSON object, tagged with the
+    /// current version
+    pub fn write<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<()> {
+        json_file::write_atomic(
+            path,
+            &Tagged {
+                version: self.version(),
+                value,
+            },
+        )
+    }
+
+    /// Read a file, upgrading it on disk first if it is an older version
+    ///
+    /// Failing to rewrite an upgraded file is only a warning; the upgrade is
+    /// repeated on the next read.
+    pub fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T> {
+        let raw: Value = json_file::read(path)?;
+        let (value, upgraded) = self
+            .upgrade(raw)
+            .map_err(|e| ChainError::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+        if upgraded {
+            if let Err(e) = json_file::write_atomic(path, &value) {
+                eprintln!("Warning: Failed to save upgraded {}: {}", path.display(), e);
+            }
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Run the migrations `value` is missing; returns whether any ran
+    pub fn upgrade(&self, mut value: Value) -> Result<(Value, bool)> {
+        let from = file_version(&value)?;
+        let current = self.version();
+        if from > current {
+            return Err(ChainError::Other(format!(
+                "format version {} is newer than this version of chain-forge supports ({}); upgrade chain-forge",
+                from, current
+            )));
+        }
+
+        for migration in &self.migrations[from as usize..] {
+            value = migration(value)?;
+        }
+        if from == current {
+            return Ok((value, false));
+        }
+
+        match value.as_object_mut() {
+            Some(object) => {
+                object.insert("version".to_string(), Value::from(current));
+            }
+            None => {
+                return Err(ChainError::Other(
+                    "migrated file is not a JSON object".to_string(),
+                ))
+            }
+        }
+        Ok((value, true))
+    }
+}
+
+fn file_version(value: &Value) -> Result<u32> {
+    match value.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| ChainError::Other(format!("invalid format version {}", version))),
+    }
+}
+
+#[derive(Serialize)]
+struct Tagged<'a, T: ?Sized> {
+    version: u32,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+/// Migration for files written before versioning, whose contents don't change
+pub fn unversioned(value: Value) -> Result<Value> {
+    Ok(value)
+}
+
+/// `accounts.json`, shared by every chain
+///
+/// - 1: the account list moves from the top level to `accounts`
+/// - 2: secrets may be encrypted: an `encryption` header, with each
+///   account's secret fields replaced by a `sealed` string
+pub const ACCOUNTS_SCHEMA: Schema = Schema::new(&[wrap_account_list, allow_sealed_secrets]);
+
+fn wrap_account_list(value: Value) -> Result<Value> {
+    match value {
+        Value::Array(accounts) => Ok(serde_json::json!({ "accounts": accounts })),
+        _ => Err(ChainError::Other("expected a list of accounts".to_string())),
+    }
+}
+
+/// Plaintext files are unchanged; the version bump keeps older releases from
+/// misreading encrypted ones
+fn allow_sealed_secrets(value: Value) -> Result<Value> {
+    Ok(value)
+}
+
+#[derive(Serialize)]
+struct AccountsOut<'a, A> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encryption: Option<&'a EncryptionHeader>,
+    accounts: &'a [A],
+}
+
+#[derive(Deserialize)]
+struct AccountsIn<A> {
+    accounts: Vec<A>,
+}
+
+/// Write an accounts file in the current format
+///
+/// With [`Protection::Passphrase`], each account's `secret_fields` (as
+/// serialized) are moved into an encrypted `sealed` field.
+pub fn write_accounts<A: Serialize>(
+    path: &Path,
+    accounts: &[A],
+    secret_fields: &[&str],
+    protection: &Protection,
+) -> Result<()> {
+    let Protection::Passphrase(passphrase) = protection else {
+        return ACCOUNTS_SCHEMA.write(
+            path,
+            &AccountsOut {
+                encryption: None,
+                accounts,
+            },
+        );
+    };
+
+    let key = SealingKey::new(passphrase)?;
+    let sealed = accounts
+        .iter()
+        .map(|account| seal_account(&key, serde_json::to_value(account)?, secret_fields))
+        .collect::<Result<Vec<_>>>()?;
+    ACCOUNTS_SCHEMA.write(
+        path,
+        &AccountsOut {
+            encryption: Some(key.header()),
+            accounts: &sealed,
+        },
+    )
+}
+
+/// Read an accounts file, upgrading older formats
+///
+/// Encrypted files are decrypted with the passphrase in `protection`.
+pub fn read_accounts<A: DeserializeOwned>(path: &Path, protection: &Protection) -> Result<Vec<A>> {
+    let mut file: Value = ACCOUNTS_SCHEMA.read(path)?;
+
+    if let Some(header) = file.get("encryption") {
+        let header: EncryptionHeader = serde_json::from_value(header.clone())?;
+        let Protection::Passphrase(passphrase) = protection else {
+            return Err(ChainError::Config(format!(
+                "{} is encrypted; set {} to its passphrase",
+                path.display(),
+                PASSPHRASE_ENV
+            )));
+        };
+        let key = SealingKey::open(&header, passphrase)?;
+        if let Some(accounts) = file.get_mut("accounts").and_then(Value::as_array_mut) {
+            for account in accounts {
+                unseal_account(&key, account)?;
+            }
+        }
+    }
+
+    let file: AccountsIn<A> = serde_json::from_value(file)?;
+    Ok(file.accounts)
+}
+
+fn seal_account(key: &SealingKey, mut account: Value, secret_fields: &[&str]) -> Result<Value> {
+    let object = account
+        .as_object_mut()
+        .ok_or_else(|| ChainError::Other("expected an account object".to_string()))?;
+
+    let mut secrets = Map::new();
+    for field in secret_fields {
+        if let Some(value) = object.remove(*field) {
+            secrets.insert(field.to_string(), value);
+        }
+    }
+    let sealed = key.seal(&serde_json::to_vec(&secrets)?)?;
+    object.insert("sealed".to_string(), Value::String(sealed));
+    Ok(account)
+}
+
+fn unseal_account(key: &SealingKey, account: &mut Value) -> Result<()> {
+    let Some(object) = account.as_object_mut() else {
+        return Ok(());
+    };
+    let Some(sealed) = object.remove("sealed") else {
+        return Ok(());
+    };
+
+    let sealed = sealed
+        .as_str()
+        .ok_or_else(|| ChainError::Other("invalid sealed secrets".to_string()))?;
+    let secrets: Map<String, Value> = serde_json::from_slice(&key.unseal(sealed)?)?;
+    object.extend(secrets);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn rename_field(mut value: Value) -> Result<Value> {
+        let object = value.as_object_mut().unwrap();
+        if let Some(old) = object.remove("old") {
+            object.insert("new".to_string(), old);
+        }
+        Ok(value)
+    }
+
+    const TEST_SCHEMA: Schema = Schema::new(&[unversioned, rename_field]);
+
+    #[test]
+    fn test_upgrade_runs_missing_migrations() {
+        let (value, upgraded) = TEST_SCHEMA.upgrade(json!({ "old": 1 })).unwrap();
+        assert!(upgraded);
+        assert_eq!(value, json!({ "new": 1, "version": 2 }));
+
+        let (value, upgraded) = TEST_SCHEMA
+            .upgrade(json!({ "version": 1, "old": 1 }))
+            .unwrap();
+        assert!(upgraded);
+        assert_eq!(value, json!({ "new": 1, "version": 2 }));
+
+        let (value, upgraded) = TEST_SCHEMA
+            .upgrade(json!({ "version": 2, "new": 1 }))
+            .unwrap();
+        assert!(!upgraded);
+        assert_eq!(value, json!({ "version": 2, "new": 1 }));
+    }
+
+    #[test]
+    fn test_upgrade_rejects_newer_versions() {
+        let err = TEST_SCHEMA.upgrade(json!({ "version": 3 })).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+        assert!(TEST_SCHEMA.upgrade(json!({ "version": "2" })).is_err());
+    }
+
+    #[test]
+    fn test_legacy_accounts_file_is_upgraded_in_place() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        std::fs::write(&path, r#"[{"name": "a"}, {"name": "b"}]"#).unwrap();
+
+        let accounts: Vec<Value> = read_accounts(&path, &Protection::Plaintext).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        let on_disk: Value = json_file::read(&path).unwrap();
+        assert_eq!(on_disk["version"], 2);
+        assert_eq!(on_disk["accounts"][1]["name"], "b");
+    }
+
+    #[test]
+    fn test_write_accounts_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        write_accounts(
+            &path,
+            &["a".to_string(), "b".to_string()],
+            &[],
+            &Protection::Plaintext,
+        )
+        .unwrap();
+
+        let on_disk: Value = json_file::read(&path).unwrap();
+        assert_eq!(on_disk, json!({ "version": 2, "accounts": ["a", "b"] }));
+        assert_eq!(
+            read_accounts::<String>(&path, &Protection::Plaintext).unwrap(),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_encrypted_accounts_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        let accounts =
+            vec![json!({ "address": "a1", "secretKey": [1, 2, 3], "mnemonic": "words" })];
+        let protection = Protection::Passphrase("hunter2".to_string());
+        write_accounts(&path, &accounts, &["secretKey", "mnemonic"], &protection).unwrap();
+
+        // Addresses stay readable, secrets don't
+        let on_disk: Value = json_file::read(&path).unwrap();
+        assert_eq!(on_disk["encryption"]["cipher"], "aes-256-gcm");
+        assert_eq!(on_disk["accounts"][0]["address"], "a1");
+        assert!(on_disk["accounts"][0].get("secretKey").is_none());
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("words"));
+
+        let read: Vec<Value> = read_accounts(&path, &protection).unwrap();
+        assert_eq!(read, accounts);
+
+        let err = read_accounts::<Value>(&path, &Protection::Plaintext).unwrap_err();
+        assert!(err.to_string().contains(PASSPHRASE_ENV));
+        let wrong = Protection::Passphrase("wrong".to_string());
+        assert!(read_accounts::<Value>(&path, &wrong).is_err());
+    }
+}
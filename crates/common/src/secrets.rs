@@ -0,0 +1,220 @@
+//! Encryption of account secrets at rest.
+//!
+//! With a passphrase in [`PASSPHRASE_ENV`], accounts files are written with
+//! each account's secret fields (private keys, WIFs, mnemonics) sealed with
+//! AES-256-GCM under a key derived from the passphrase with PBKDF2-SHA256.
+//! Addresses, balances, and labels stay readable, so listing accounts still
+//! works; anything that needs a key must have the same passphrase.
+//!
+//! Key derivation is deliberately slow, so the last derived key is cached
+//! and reused (with its salt) for later reads and writes in the process.
+
+use crate::{ChainError, Result};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Mutex;
+
+/// Environment variable holding the accounts passphrase
+pub const PASSPHRASE_ENV: &str = "CHAIN_FORGE_PASSPHRASE";
+
+const CIPHER: &str = "aes-256-gcm";
+const KDF: &str = "pbkdf2-sha256";
+const ITERATIONS: u32 = 210_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// How account secrets are written to disk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Protection {
+    /// Secrets are stored as is
+    #[default]
+    Plaintext,
+    /// Secrets are sealed with a key derived from the passphrase
+    Passphrase(String),
+}
+
+impl Protection {
+    /// Passphrase from [`PASSPHRASE_ENV`] if it is set, plaintext otherwise
+    pub fn from_env() -> Self {
+        match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => Self::Passphrase(passphrase),
+            _ => Self::Plaintext,
+        }
+    }
+
+    /// Whether secrets are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, Self::Passphrase(_))
+    }
+}
+
+/// Parameters of an encrypted file, stored alongside its accounts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionHeader {
+    pub cipher: String,
+    pub kdf: String,
+    pub iterations: u32,
+    /// Base64 KDF salt
+    pub salt: String,
+}
+
+/// A derived key, remembered so repeated reads and writes skip the KDF
+struct CachedKey {
+    passphrase: String,
+    salt: Vec<u8>,
+    iterations: u32,
+    key: [u8; 32],
+}
+
+static KEY_CACHE: Mutex<Option<CachedKey>> = Mutex::new(None);
+
+/// Key that seals and unseals secrets
+pub(crate) struct SealingKey {
+    header: EncryptionHeader,
+    cipher: Aes256Gcm,
+}
+
+impl SealingKey {
+    /// Key for writing with `passphrase`, reusing the cached salt if the
+    /// passphrase matches
+    pub(crate) fn new(passphrase: &str) -> Result<Self> {
+        let cached = KEY_CACHE
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.passphrase == passphrase && cached.iterations == ITERATIONS)
+            .map(|cached| cached.salt.clone());
+        let salt = cached.unwrap_or_else(|| {
+            let mut salt = vec![0u8; SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut salt);
+            salt
+        });
+
+        Self::open(
+            &EncryptionHeader {
+                cipher: CIPHER.to_string(),
+                kdf: KDF.to_string(),
+                iterations: ITERATIONS,
+                salt: STANDARD.encode(&salt),
+            },
+            passphrase,
+        )
+    }
+
+    /// Key for a file written with `header`
+    pub(crate) fn open(header: &EncryptionHeader, passphrase: &str) -> Result<Self> {
+        if header.cipher != CIPHER || header.kdf != KDF {
+            return Err(ChainError::Config(format!(
+                "Unsupported encryption {} with {}",
+                header.cipher, header.kdf
+            )));
+        }
+        let salt = STANDARD
+            .decode(&header.salt)
+            .map_err(|e| ChainError::Config(format!("Invalid encryption salt: {}", e)))?;
+
+        let key = derive_key(passphrase, &salt, header.iterations);
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| ChainError::Other(format!("Invalid encryption key: {}", e)))?;
+        Ok(Self {
+            header: header.clone(),
+            cipher,
+        })
+    }
+
+    /// Parameters to store alongside the sealed data
+    pub(crate) fn header(&self) -> &EncryptionHeader {
+        &self.header
+    }
+
+    /// Encrypt `plaintext` as base64 of the nonce followed by the ciphertext
+    pub(crate) fn seal(&self, plaintext: &[u8]) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| ChainError::Other("Failed to encrypt secrets".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(STANDARD.encode(sealed))
+    }
+
+    /// Decrypt data produced by [`SealingKey::seal`]
+    pub(crate) fn unseal(&self, sealed: &str) -> Result<Vec<u8>> {
+        let sealed = STANDARD
+            .decode(sealed)
+            .map_err(|e| ChainError::Other(format!("Invalid sealed secrets: {}", e)))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(ChainError::Other("Invalid sealed secrets".to_string()));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                ChainError::Config(format!(
+                    "Failed to decrypt account secrets; check {}",
+                    PASSPHRASE_ENV
+                ))
+            })
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut cache = KEY_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref().filter(|cached| {
+        cached.passphrase == passphrase && cached.salt == salt && cached.iterations == iterations
+    }) {
+        return cached.key;
+    }
+
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    *cache = Some(CachedKey {
+        passphrase: passphrase.to_string(),
+        salt: salt.to_vec(),
+        iterations,
+        key,
+    });
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_roundtrip() {
+        let key = SealingKey::new("correct horse").unwrap();
+        let sealed = key.seal(b"secret").unwrap();
+        assert!(!sealed.contains("secret"));
+        assert_eq!(key.unseal(&sealed).unwrap(), b"secret");
+
+        // The same passphrase and header reopen the key
+        let reopened = SealingKey::open(key.header(), "correct horse").unwrap();
+        assert_eq!(reopened.unseal(&sealed).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let key = SealingKey::new("right").unwrap();
+        let sealed = key.seal(b"secret").unwrap();
+
+        let wrong = SealingKey::open(key.header(), "wrong").unwrap();
+        let err = wrong.unseal(&sealed).unwrap_err();
+        assert!(err.to_string().contains(PASSPHRASE_ENV));
+    }
+
+    #[test]
+    fn test_unsupported_header() {
+        let mut header = SealingKey::new("p").unwrap().header().clone();
+        header.cipher = "rot13".to_string();
+        assert!(SealingKey::open(&header, "p").is_err());
+    }
+}
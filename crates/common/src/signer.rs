@@ -0,0 +1,204 @@
+//! Signing abstraction for accounts whose keys are not distributed.
+//!
+//! Accounts marked as external signers have their private keys removed from
+//! `accounts.json`. Signing requests for them go through a [`Signer`], which
+//! may be a software emulator of a hardware wallet, a local key, or a remote
+//! signing service ([`RemoteSigner`]) fronting an HSM or KMS.
+
+use crate::{ChainError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Signs payloads on behalf of a single account
+pub trait Signer: Send + Sync {
+    /// Address of the account this signer signs for
+    fn address(&self) -> &str;
+
+    /// Sign a chain-specific payload and return the raw signature bytes
+    ///
+    /// Solana signers sign serialized message bytes (ed25519); Bitcoin
+    /// signers sign a 32-byte sighash (DER-encoded ECDSA).
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Key store backing emulated external signers
+///
+/// Holds the secret keys that were removed from the accounts file, keyed by
+/// address, in a separate file so the emulator plays the role of the device.
+pub struct SignerKeystore {
+    path: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeystoreData {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl SignerKeystore {
+    /// Create a keystore at the given path
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn load(&self) -> Result<KeystoreData> {
+        if !self.path.exists() {
+            return Ok(KeystoreData::default());
+        }
+        let json = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save(&self, data: &KeystoreData) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(data)?)?;
+        Ok(())
+    }
+
+    /// Store the secret key for an address
+    pub fn insert(&self, address: &str, secret: Vec<u8>) -> Result<()> {
+        let mut data = self.load()?;
+        data.keys.insert(address.to_string(), secret);
+        self.save(&data)
+    }
+
+    /// Get the secret key for an address
+    pub fn get(&self, address: &str) -> Result<Vec<u8>> {
+        self.load()?.keys.remove(address).ok_or_else(|| {
+            ChainError::AccountGeneration(format!("No external signer key for {}", address))
+        })
+    }
+
+    /// Addresses with keys in this keystore
+    pub fn addresses(&self) -> Result<Vec<String>> {
+        let mut addresses: Vec<String> = self.load()?.keys.into_keys().collect();
+        addresses.sort();
+        Ok(addresses)
+    }
+}
+
+/// Signer backed by an external HTTP signing service (HSM/KMS gateway)
+///
+/// Sends `POST {endpoint}/sign` with a JSON body
+/// `{"address": "...", "payload": "<hex>"}` and expects
+/// `{"signature": "<hex>"}` in return. If a token is set it is sent as a
+/// bearer token in the `Authorization` header.
+pub struct RemoteSigner {
+    endpoint: String,
+    address: String,
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    address: &'a str,
+    payload: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+impl RemoteSigner {
+    /// Create a signer for an address served by the signing service at `endpoint`
+    pub fn new(endpoint: &str, address: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            address: address.to_string(),
+            token: None,
+        }
+    }
+
+    /// Authenticate requests with a bearer token
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let url = format!("{}/sign", self.endpoint);
+        let mut request = ureq::post(&url);
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+
+        let response: SignResponse = request
+            .send_json(SignRequest {
+                address: &self.address,
+                payload: to_hex(payload),
+            })
+            .map_err(|e| ChainError::Other(format!("Remote signer request failed: {}", e)))?
+            .into_json()
+            .map_err(|e| ChainError::Other(format!("Invalid remote signer response: {}", e)))?;
+
+        from_hex(&response.signature)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let invalid = || ChainError::Other(format!("Invalid hex signature: {}", hex));
+    if hex.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2).ok_or_else(invalid)?, 16))
+        .collect::<std::result::Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let dir = tempdir().unwrap();
+        let keystore = SignerKeystore::with_path(dir.path().join("signers.json"));
+
+        keystore.insert("addr1", vec![1, 2, 3]).unwrap();
+        keystore.insert("addr2", vec![4, 5, 6]).unwrap();
+
+        assert_eq!(keystore.get("addr1").unwrap(), vec![1, 2, 3]);
+        assert_eq!(keystore.addresses().unwrap(), vec!["addr1", "addr2"]);
+    }
+
+    #[test]
+    fn test_keystore_missing_key() {
+        let dir = tempdir().unwrap();
+        let keystore = SignerKeystore::with_path(dir.path().join("signers.json"));
+        assert!(keystore.get("unknown").is_err());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = vec![0x00, 0x0f, 0xab, 0xff];
+        assert_eq!(to_hex(&bytes), "000fabff");
+        assert_eq!(from_hex("000fabff").unwrap(), bytes);
+        assert_eq!(from_hex("000FABFF").unwrap(), bytes);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_remote_signer_unreachable() {
+        let signer = RemoteSigner::new("http://127.0.0.1:1/", "addr1");
+        assert_eq!(signer.address(), "addr1");
+        assert!(signer.sign(&[1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Timings of the phases a node goes through when it starts.
+//!
+//! `start` records how long each phase took in `instance.json`, and
+//! `cf nodes profile` prints them, so a release that makes startup slower
+//! shows up as a number rather than a feeling.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// A step of node startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPhase {
+    /// Launching the node process and checking it didn't exit at once
+    Spawn,
+    /// Waiting for the node to answer RPC calls
+    RpcReady,
+    /// Creating and loading the node wallet (Bitcoin)
+    Wallet,
+    /// Mining the blocks accounts are funded from (Bitcoin)
+    Premine,
+    /// Sending the initial balances
+    Funding,
+    /// Importing accounts into the node wallet (Bitcoin)
+    Import,
+}
+
+impl std::fmt::Display for StartupPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Spawn => "spawn",
+            Self::RpcReady => "rpc_ready",
+            Self::Wallet => "wallet",
+            Self::Premine => "premine",
+            Self::Funding => "funding",
+            Self::Import => "import",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How long one phase took
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub phase: StartupPhase,
+    pub duration_ms: u64,
+}
+
+/// Phases of a node's last start, in the order they ran
+///
+/// Phases that don't apply to a chain or start (such as funding when a
+/// snapshot is restored) are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupProfile {
+    pub phases: Vec<PhaseTiming>,
+}
+
+impl StartupProfile {
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+
+    /// Time spent in all recorded phases, in milliseconds
+    pub fn total_ms(&self) -> u64 {
+        self.phases.iter().map(|timing| timing.duration_ms).sum()
+    }
+
+    /// Add a phase that took `duration`
+    pub fn record(&mut self, phase: StartupPhase, duration: Duration) {
+        self.phases.push(PhaseTiming {
+            phase,
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+}
+
+/// Times consecutive startup phases
+///
+/// Each phase runs from the previous [`lap`](Self::lap) (or
+/// [`restart`](Self::restart)) to the next one.
+#[derive(Debug, Clone)]
+pub struct StartupTimer {
+    profile: StartupProfile,
+    since: Instant,
+}
+
+impl StartupTimer {
+    pub fn new() -> Self {
+        Self {
+            profile: StartupProfile::default(),
+            since: Instant::now(),
+        }
+    }
+
+    /// End `phase` now and start timing the next one
+    pub fn lap(&mut self, phase: StartupPhase) {
+        let now = Instant::now();
+        self.profile.record(phase, now - self.since);
+        self.since = now;
+    }
+
+    /// Start timing the next phase now, leaving out the time since the last lap
+    pub fn restart(&mut self) {
+        self.since = Instant::now();
+    }
+
+    pub fn finish(self) -> StartupProfile {
+        self.profile
+    }
+}
+
+impl Default for StartupTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_round_trip() {
+        let mut profile = StartupProfile::default();
+        assert!(profile.is_empty());
+        profile.record(StartupPhase::Spawn, Duration::from_millis(1200));
+        profile.record(StartupPhase::RpcReady, Duration::from_millis(800));
+        assert_eq!(profile.total_ms(), 2000);
+
+        let json = serde_json::to_string(&profile).unwrap();
+        assert!(json.contains("\"rpc_ready\""));
+        assert_eq!(
+            serde_json::from_str::<StartupProfile>(&json).unwrap(),
+            profile
+        );
+    }
+
+    #[test]
+    fn test_timer_records_phases_in_order() {
+        let mut timer = StartupTimer::new();
+        timer.lap(StartupPhase::Spawn);
+        timer.restart();
+        timer.lap(StartupPhase::Funding);
+        let phases: Vec<_> = timer.finish().phases.iter().map(|t| t.phase).collect();
+        assert_eq!(phases, [StartupPhase::Spawn, StartupPhase::Funding]);
+    }
+}
@@ -0,0 +1,321 @@
+//! Instance data kept for a while after it is cleared.
+//!
+//! Stopping a node without `--keep-data`, or starting one over existing
+//! data, moves the instance directory to `<data dir>/trash/<timestamp>-<id>`
+//! instead of deleting it, where `<id>` is the chain and instance ID. Next to
+//! each entry, `<timestamp>-<id>.json` records where it came from, so
+//! `cf trash restore` can put it back.
+//!
+//! Each entry is kept for the retention period in effect when it was moved,
+//! and deleted once that has passed the next time anything is moved to the
+//! trash, or with `cf trash purge`.
+
+use crate::{data_dir, json_file, ChainError, ChainType, NodeRegistry, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the trash directory in the data directory
+pub const TRASH_DIR_NAME: &str = "trash";
+
+/// Days entries are kept unless `trash_retention_days` is set
+pub const DEFAULT_RETENTION_DAYS: u32 = 7;
+
+/// Instance data in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Directory name in the trash, e.g. `20250101-120000-solana-dev`
+    pub id: String,
+    pub chain: ChainType,
+    pub instance_id: String,
+    /// Instance directory the data was moved from
+    pub original_path: PathBuf,
+    pub trashed_at: DateTime<Utc>,
+    /// When [`Trash::prune`] deletes the entry
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    /// Node ID of the instance, e.g. `solana:dev`
+    pub fn node_id(&self) -> String {
+        NodeRegistry::node_id(self.chain, &self.instance_id)
+    }
+}
+
+/// The trash directory
+pub struct Trash {
+    dir: PathBuf,
+    retention_days: u32,
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trash {
+    /// The trash in the data directory, with the default retention
+    pub fn new() -> Self {
+        Self::with_dir(data_dir().join(TRASH_DIR_NAME))
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            retention_days: DEFAULT_RETENTION_DAYS,
+        }
+    }
+
+    /// Keep entries moved from now on for `days`; 0 deletes instance data
+    /// right away
+    pub fn with_retention_days(mut self, days: u32) -> Self {
+        self.retention_days = days;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn retention_days(&self) -> u32 {
+        self.retention_days
+    }
+
+    /// Move an instance's directory to the trash, deleting expired entries
+    ///
+    /// Returns `None` if there was nothing to move, or if the retention is 0
+    /// and the directory was deleted instead.
+    pub fn move_instance(
+        &self,
+        chain: ChainType,
+        instance_id: &str,
+        instance_dir: &Path,
+    ) -> Result<Option<TrashEntry>> {
+        if !instance_dir.exists() {
+            return Ok(None);
+        }
+        if self.retention_days == 0 {
+            std::fs::remove_dir_all(instance_dir).map_err(|e| {
+                ChainError::NodeManagement(format!("Failed to clear instance data: {}", e))
+            })?;
+            return Ok(None);
+        }
+
+        if let Err(e) = self.prune() {
+            eprintln!("⚠️  Failed to empty expired trash entries: {}", e);
+        }
+        std::fs::create_dir_all(&self.dir)?;
+
+        let trashed_at = Utc::now();
+        let stem = format!(
+            "{}-{}-{}",
+            trashed_at.format("%Y%m%d-%H%M%S"),
+            chain,
+            instance_id
+        );
+        // Two clears of the same instance within a second get distinct names
+        let mut id = stem.clone();
+        let mut n = 1;
+        while self.dir.join(&id).exists() {
+            n += 1;
+            id = format!("{}-{}", stem, n);
+        }
+
+        let entry = TrashEntry {
+            id,
+            chain,
+            instance_id: instance_id.to_string(),
+            original_path: instance_dir.to_path_buf(),
+            trashed_at,
+            expires_at: trashed_at + chrono::Duration::days(self.retention_days as i64),
+        };
+        std::fs::rename(instance_dir, self.dir.join(&entry.id)).map_err(|e| {
+            ChainError::NodeManagement(format!("Failed to move instance data to the trash: {}", e))
+        })?;
+        json_file::write_atomic(&self.metadata_path(&entry.id), &entry)?;
+        Ok(Some(entry))
+    }
+
+    /// Entries in the trash, newest first
+    pub fn list(&self) -> Result<Vec<TrashEntry>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for file in std::fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                entries.push(json_file::read::<TrashEntry>(&path)?);
+            }
+        }
+        entries.sort_by(|a, b| b.trashed_at.cmp(&a.trashed_at));
+        Ok(entries)
+    }
+
+    /// An entry by its ID, or the newest entry of a node ID like `solana:dev`
+    pub fn find(&self, id: &str) -> Result<TrashEntry> {
+        self.list()?
+            .into_iter()
+            .find(|entry| entry.id == id || entry.node_id() == id)
+            .ok_or_else(|| ChainError::Config(format!("'{}' is not in the trash", id)))
+    }
+
+    /// Move an entry back to the instance directory it came from
+    ///
+    /// Fails if that directory exists again, e.g. because the instance was
+    /// started since.
+    pub fn restore(&self, id: &str) -> Result<TrashEntry> {
+        let entry = self.find(id)?;
+        if entry.original_path.exists() {
+            return Err(ChainError::Config(format!(
+                "{} already has data at {}. Stop it without --keep-data first",
+                entry.node_id(),
+                entry.original_path.display()
+            )));
+        }
+
+        if let Some(parent) = entry.original_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(self.dir.join(&entry.id), &entry.original_path).map_err(|e| {
+            ChainError::NodeManagement(format!("Failed to restore instance data: {}", e))
+        })?;
+        std::fs::remove_file(self.metadata_path(&entry.id))?;
+        Ok(entry)
+    }
+
+    /// Delete an entry for good
+    pub fn purge(&self, id: &str) -> Result<TrashEntry> {
+        let entry = self.find(id)?;
+        self.delete(&entry)?;
+        Ok(entry)
+    }
+
+    /// Delete every entry
+    pub fn purge_all(&self) -> Result<Vec<TrashEntry>> {
+        let entries = self.list()?;
+        for entry in &entries {
+            self.delete(entry)?;
+        }
+        Ok(entries)
+    }
+
+    /// Delete entries whose retention period has passed
+    pub fn prune(&self) -> Result<Vec<TrashEntry>> {
+        let now = Utc::now();
+        let expired: Vec<TrashEntry> = self
+            .list()?
+            .into_iter()
+            .filter(|entry| entry.expires_at <= now)
+            .collect();
+        for entry in &expired {
+            self.delete(entry)?;
+        }
+        Ok(expired)
+    }
+
+    fn delete(&self, entry: &TrashEntry) -> Result<()> {
+        let dir = self.dir.join(&entry.id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::remove_file(self.metadata_path(&entry.id))?;
+        Ok(())
+    }
+
+    fn metadata_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn instance(root: &Path, id: &str) -> PathBuf {
+        let dir = root.join("solana").join("instances").join(id);
+        std::fs::create_dir_all(dir.join("ledger")).unwrap();
+        std::fs::write(dir.join("accounts.json"), "[]").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_move_and_restore() {
+        let root = tempdir().unwrap();
+        let trash = Trash::with_dir(root.path().join(TRASH_DIR_NAME));
+        let dir = instance(root.path(), "dev");
+
+        let entry = trash
+            .move_instance(ChainType::Solana, "dev", &dir)
+            .unwrap()
+            .unwrap();
+        assert!(!dir.exists());
+        assert!(entry.id.ends_with("-solana-dev"));
+        assert_eq!(trash.list().unwrap().len(), 1);
+
+        // A node ID finds the newest entry of that node
+        let restored = trash.restore("solana:dev").unwrap();
+        assert_eq!(restored.id, entry.id);
+        assert!(dir.join("accounts.json").exists());
+        assert!(trash.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let root = tempdir().unwrap();
+        let trash = Trash::with_dir(root.path().join(TRASH_DIR_NAME));
+        let dir = instance(root.path(), "dev");
+        let first = trash
+            .move_instance(ChainType::Solana, "dev", &dir)
+            .unwrap()
+            .unwrap();
+
+        // Started again since: the new data stays put
+        instance(root.path(), "dev");
+        assert!(trash.restore(&first.id).is_err());
+        let second = trash
+            .move_instance(ChainType::Solana, "dev", &dir)
+            .unwrap()
+            .unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(trash.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_retention() {
+        let root = tempdir().unwrap();
+        let dir = instance(root.path(), "dev");
+
+        // With no retention, data is deleted right away
+        let trash = Trash::with_dir(root.path().join(TRASH_DIR_NAME)).with_retention_days(0);
+        assert!(trash
+            .move_instance(ChainType::Solana, "dev", &dir)
+            .unwrap()
+            .is_none());
+        assert!(!dir.exists());
+        assert!(trash.list().unwrap().is_empty());
+
+        let trash = Trash::with_dir(root.path().join(TRASH_DIR_NAME)).with_retention_days(1);
+        let dir = instance(root.path(), "dev");
+        let mut entry = trash
+            .move_instance(ChainType::Solana, "dev", &dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            entry.expires_at - entry.trashed_at,
+            chrono::Duration::days(1)
+        );
+        assert!(trash.prune().unwrap().is_empty());
+
+        // Entries expire on their own schedule, whatever the trash's
+        // retention is now
+        entry.expires_at = Utc::now() - chrono::Duration::seconds(1);
+        json_file::write_atomic(&trash.metadata_path(&entry.id), &entry).unwrap();
+        let trash = trash.with_retention_days(30);
+        assert_eq!(trash.prune().unwrap().len(), 1);
+        assert!(!trash.dir().join(&entry.id).exists());
+    }
+}
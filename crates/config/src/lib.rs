@@ -1,25 +1,139 @@
-use chain_forge_common::{ChainError, Result};
+use chain_forge_common::trash::DEFAULT_RETENTION_DAYS;
+use chain_forge_common::{
+    ChainError, ChainType, Protection, Result, RetryPolicy, StorageBackend, Trash, DATA_DIR_ENV,
+    PASSPHRASE_ENV,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+pub mod ports;
+pub mod preset;
+pub mod profiles;
+
+pub use ports::{hashed_port, PortAllocator, PortChoice, PortStrategy, DEFAULT_PORT_RANGE};
+pub use preset::{Preset, PRESETS};
+pub use profiles::write_profile;
+
 #[cfg(test)]
 mod tests;
 
+pub use chain_forge_common::DATA_DIR_NAME;
+
 pub const CONFIG_FILE_NAME: &str = "chain-forge.toml";
-pub const DATA_DIR_NAME: &str = ".chain-forge";
+
+/// Look up a named profile of a chain's config table
+fn find_profile<P: Clone + Default>(
+    chain: ChainType,
+    name: &str,
+    default: Option<&P>,
+    profiles: Option<&std::collections::HashMap<String, P>>,
+) -> Result<P> {
+    if name == "default" {
+        return Ok(default.cloned().unwrap_or_default());
+    }
+    if let Some(profile) = profiles.and_then(|profiles| profiles.get(name)) {
+        return Ok(profile.clone());
+    }
+
+    let mut available: Vec<&str> = profiles
+        .map(|profiles| profiles.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    available.sort_unstable();
+    available.insert(0, "default");
+    let file = Config::find_file()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| CONFIG_FILE_NAME.to_string());
+    Err(ChainError::Config(format!(
+        "Profile '{}' not found in {}; add a [{}.{}] table (available: {})",
+        name,
+        file,
+        chain,
+        name,
+        available.join(", ")
+    )))
+}
+
+fn invalid_profile(chain: ChainType, name: &str, e: ChainError) -> ChainError {
+    let reason = match e {
+        ChainError::Config(reason) => reason,
+        other => other.to_string(),
+    };
+    ChainError::Config(format!(
+        "Profile [{}.{}] is invalid: {}",
+        chain, name, reason
+    ))
+}
 
 /// Global configuration structure
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct Config {
+    /// How `start` picks ports that aren't given on the command line
+    #[serde(default)]
+    pub port_strategy: PortStrategy,
+    /// Inclusive range hashed ports are picked from (default: 20000-39999)
+    #[serde(default)]
+    pub port_range: Option<[u16; 2]>,
+    /// Require `CHAIN_FORGE_PASSPHRASE` so account secrets are encrypted at
+    /// rest; `start --insecure-plaintext` overrides it
+    #[serde(default)]
+    pub encrypt_accounts: bool,
+    /// Where accounts are kept: `file` (default), `keyring` (secrets in the
+    /// OS keyring), or `memory` (for tests)
+    #[serde(default)]
+    pub accounts_storage: StorageBackend,
+    /// Directory chain state is kept in instead of `~/.chain-forge`; a
+    /// relative path is resolved from this file's directory, so
+    /// `.chain-forge` keeps state with the project
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// Days a stopped instance's data stays in the trash before it is
+    /// deleted (default: 7); 0 deletes it right away
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
     #[serde(default)]
     pub solana: Option<SolanaConfig>,
     #[serde(default)]
     pub bitcoin: Option<BitcoinConfig>,
-    // Future chains
-    // pub ethereum: Option<EthereumConfig>,
+    /// Settings for `cf-api`, re-read on SIGHUP or `POST /api/v1/admin/reload`
+    #[serde(default)]
+    pub api: Option<ApiConfig>,
+    #[serde(default)]
+    pub ethereum: Option<EthereumConfig>,
+    /// Retries when funding accounts at startup
+    #[serde(default)]
+    pub funding_retry: FundingRetry,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Retry policy for funding accounts, with per-chain overrides
+///
+/// An override replaces the whole policy; fields it leaves out take the
+/// built-in defaults, not those of the shared policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FundingRetry {
+    #[serde(flatten)]
+    pub default: RetryPolicy,
+    #[serde(default)]
+    pub solana: Option<RetryPolicy>,
+    #[serde(default)]
+    pub bitcoin: Option<RetryPolicy>,
+    #[serde(default)]
+    pub ethereum: Option<RetryPolicy>,
+}
+
+/// What stopping an instance does with its data, set per profile
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupPolicy {
+    /// Leave the instance directory in place
+    pub keep_data: bool,
+    /// Snapshot the instance after the node exits, before clearing it
+    pub snapshot_on_stop: bool,
+    /// Retention of the data moved to the trash; `None` uses the
+    /// top-level setting
+    pub trash_retention_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SolanaConfig {
     #[serde(default)]
     pub default: SolanaProfile,
@@ -28,7 +142,7 @@ pub struct SolanaConfig {
     pub profiles: std::collections::HashMap<String, SolanaProfile>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SolanaProfile {
     #[serde(default = "default_rpc_url")]
     pub rpc_url: String,
@@ -41,6 +155,20 @@ pub struct SolanaProfile {
 
     #[serde(default = "default_port")]
     pub port: u16,
+
+    /// Keep instance data on stop, like `start --keep-data`
+    #[serde(default)]
+    pub keep_data: bool,
+
+    /// Snapshot the ledger when the instance stops, before its data is
+    /// cleared
+    #[serde(default)]
+    pub snapshot_on_stop: bool,
+
+    /// Days this profile's cleared data stays in the trash, instead of the
+    /// top-level `trash_retention_days`
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
 }
 
 impl Default for SolanaProfile {
@@ -50,6 +178,20 @@ impl Default for SolanaProfile {
             accounts: default_accounts(),
             initial_balance: default_initial_balance(),
             port: default_port(),
+            keep_data: false,
+            snapshot_on_stop: false,
+            trash_retention_days: None,
+        }
+    }
+}
+
+impl SolanaProfile {
+    /// What stopping an instance started with this profile does with its data
+    pub fn cleanup(&self) -> CleanupPolicy {
+        CleanupPolicy {
+            keep_data: self.keep_data,
+            snapshot_on_stop: self.snapshot_on_stop,
+            trash_retention_days: self.trash_retention_days,
         }
     }
 }
@@ -70,7 +212,7 @@ fn default_port() -> u16 {
     8899
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BitcoinConfig {
     #[serde(default)]
     pub default: BitcoinProfile,
@@ -79,7 +221,7 @@ pub struct BitcoinConfig {
     pub profiles: std::collections::HashMap<String, BitcoinProfile>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BitcoinProfile {
     #[serde(default = "default_bitcoin_rpc_url")]
     pub rpc_url: String,
@@ -101,6 +243,19 @@ pub struct BitcoinProfile {
 
     #[serde(default = "default_bitcoin_rpc_password")]
     pub rpc_password: String,
+
+    /// Keep instance data on stop, like `start --keep-data`
+    #[serde(default)]
+    pub keep_data: bool,
+
+    /// Snapshot the chain when the node stops, before its data is cleared
+    #[serde(default)]
+    pub snapshot_on_stop: bool,
+
+    /// Days this profile's cleared data stays in the trash, instead of the
+    /// top-level `trash_retention_days`
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
 }
 
 impl Default for BitcoinProfile {
@@ -113,6 +268,20 @@ impl Default for BitcoinProfile {
             p2p_port: default_bitcoin_p2p_port(),
             rpc_user: default_bitcoin_rpc_user(),
             rpc_password: default_bitcoin_rpc_password(),
+            keep_data: false,
+            snapshot_on_stop: false,
+            trash_retention_days: None,
+        }
+    }
+}
+
+impl BitcoinProfile {
+    /// What stopping a node started with this profile does with its data
+    pub fn cleanup(&self) -> CleanupPolicy {
+        CleanupPolicy {
+            keep_data: self.keep_data,
+            snapshot_on_stop: self.snapshot_on_stop,
+            trash_retention_days: self.trash_retention_days,
         }
     }
 }
@@ -145,6 +314,90 @@ fn default_bitcoin_rpc_password() -> String {
     "chainforge".to_string()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EthereumConfig {
+    #[serde(default)]
+    pub default: EthereumProfile,
+
+    #[serde(flatten)]
+    pub profiles: std::collections::HashMap<String, EthereumProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EthereumProfile {
+    #[serde(default = "default_ethereum_rpc_url")]
+    pub rpc_url: String,
+
+    #[serde(default = "default_accounts")]
+    pub accounts: u32,
+
+    #[serde(default = "default_ethereum_initial_balance")]
+    pub initial_balance: f64,
+
+    #[serde(default = "default_ethereum_port")]
+    pub port: u16,
+
+    #[serde(default = "default_ethereum_chain_id")]
+    pub chain_id: u64,
+}
+
+impl Default for EthereumProfile {
+    fn default() -> Self {
+        Self {
+            rpc_url: default_ethereum_rpc_url(),
+            accounts: default_accounts(),
+            initial_balance: default_ethereum_initial_balance(),
+            port: default_ethereum_port(),
+            chain_id: default_ethereum_chain_id(),
+        }
+    }
+}
+
+fn default_ethereum_rpc_url() -> String {
+    "http://localhost:8545".to_string()
+}
+
+fn default_ethereum_initial_balance() -> f64 {
+    10000.0
+}
+
+fn default_ethereum_port() -> u16 {
+    8545
+}
+
+fn default_ethereum_chain_id() -> u64 {
+    31337
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiConfig {
+    /// Route prefixes whose request/response bodies are logged
+    #[serde(default)]
+    pub debug_routes: Vec<String>,
+
+    /// Bearer token that allows `X-Debug: true` on any route
+    #[serde(default)]
+    pub debug_token: Option<String>,
+
+    /// Seconds between node watcher polls
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            debug_routes: Vec::new(),
+            debug_token: None,
+            watch_interval_secs: default_watch_interval_secs(),
+        }
+    }
+}
+
+fn default_watch_interval_secs() -> u64 {
+    2
+}
+
 impl Config {
     /// Load configuration from a file
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -164,34 +417,194 @@ impl Config {
 
     /// Load configuration from the current directory or user's home directory
     pub fn load() -> Result<Self> {
-        // Try current directory first
+        match Self::find_file() {
+            Some(config_path) => Self::load_from_file(config_path),
+            // Return default configuration
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The config file `load` reads, if any
+    ///
+    /// The current directory is checked first, then the home directory.
+    pub fn find_file() -> Option<PathBuf> {
         let current_dir_config = std::env::current_dir()
             .ok()
             .map(|p| p.join(CONFIG_FILE_NAME));
+        let home_config = dirs::home_dir().map(|p| p.join(CONFIG_FILE_NAME));
 
-        if let Some(config_path) = current_dir_config {
-            if config_path.exists() {
-                return Self::load_from_file(config_path);
-            }
+        [current_dir_config, home_config]
+            .into_iter()
+            .flatten()
+            .find(|path| path.exists())
+    }
+
+    /// The config file to edit: the one `load` reads, or a new one in the
+    /// current directory
+    pub fn file_path() -> PathBuf {
+        Self::find_file().unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME))
+    }
+
+    /// Generate a JSON Schema describing `chain-forge.toml`
+    ///
+    /// The schema is derived from the config structs, so it always matches
+    /// what `load_from_file` accepts.
+    pub fn json_schema() -> serde_json::Value {
+        let schema = schemars::schema_for!(Config);
+        serde_json::to_value(schema).expect("JSON Schema is always serializable")
+    }
+
+    /// Check that account secrets may be written with the current
+    /// protection before starting a node
+    ///
+    /// Fails if `encrypt_accounts` is set but no passphrase is, unless
+    /// plaintext was explicitly allowed. Secrets that aren't written to the
+    /// accounts file (with the keyring or memory backends) need no passphrase.
+    pub fn check_accounts_protection(&self, insecure_plaintext: bool) -> Result<()> {
+        if self.encrypt_accounts
+            && self.accounts_storage == StorageBackend::File
+            && !insecure_plaintext
+            && !Protection::from_env().is_encrypted()
+        {
+            return Err(ChainError::Config(format!(
+                "encrypt_accounts is set but {} isn't; set it or pass --insecure-plaintext",
+                PASSPHRASE_ENV
+            )));
         }
+        Ok(())
+    }
 
-        // Try home directory
-        if let Some(home_dir) = dirs::home_dir() {
-            let config_path = home_dir.join(CONFIG_FILE_NAME);
-            if config_path.exists() {
-                return Self::load_from_file(config_path);
-            }
+    /// The `[solana.<name>]` profile, checked to be usable
+    ///
+    /// `default` is `[solana.default]`, or the built-in defaults if that
+    /// table is missing.
+    pub fn solana_profile(&self, name: &str) -> Result<SolanaProfile> {
+        let solana = self.solana.as_ref();
+        let profile = find_profile(
+            ChainType::Solana,
+            name,
+            solana.map(|s| &s.default),
+            solana.map(|s| &s.profiles),
+        )?;
+        profile
+            .validate()
+            .map_err(|e| invalid_profile(ChainType::Solana, name, e))?;
+        Ok(profile)
+    }
+
+    /// The `[bitcoin.<name>]` profile, checked to be usable
+    ///
+    /// `default` is `[bitcoin.default]`, or the built-in defaults if that
+    /// table is missing.
+    pub fn bitcoin_profile(&self, name: &str) -> Result<BitcoinProfile> {
+        let bitcoin = self.bitcoin.as_ref();
+        let profile = find_profile(
+            ChainType::Bitcoin,
+            name,
+            bitcoin.map(|b| &b.default),
+            bitcoin.map(|b| &b.profiles),
+        )?;
+        profile
+            .validate()
+            .map_err(|e| invalid_profile(ChainType::Bitcoin, name, e))?;
+        Ok(profile)
+    }
+
+    /// Retry policy for funding `chain`'s accounts
+    pub fn funding_retry(&self, chain: ChainType) -> RetryPolicy {
+        let retry = &self.funding_retry;
+        let chain_policy = match chain {
+            ChainType::Solana => retry.solana,
+            ChainType::Bitcoin => retry.bitcoin,
+            ChainType::Ethereum => retry.ethereum,
+        };
+        chain_policy.unwrap_or(retry.default)
+    }
+
+    /// Base port for an instance started without an explicit port
+    ///
+    /// `default` is the chain's usual port and `span` the number of
+    /// consecutive ports the instance uses from the base port on.
+    pub fn instance_port(
+        &self,
+        chain: ChainType,
+        instance_id: &str,
+        default: u16,
+        span: u16,
+    ) -> Result<u16> {
+        match self.port_strategy {
+            PortStrategy::Fixed => Ok(default),
+            PortStrategy::Hashed => hashed_port(
+                chain,
+                instance_id,
+                self.port_range.unwrap_or(DEFAULT_PORT_RANGE),
+                span,
+            ),
         }
+    }
 
-        // Return default configuration
-        Ok(Self::default())
+    /// Base port of a free block of `span` ports for an instance started
+    /// with `--port auto`, picked within `port_range`
+    ///
+    /// `listen` are the offsets from the base port the node binds; see
+    /// [`PortAllocator::allocate`].
+    pub fn auto_port(
+        &self,
+        chain: ChainType,
+        instance_id: &str,
+        span: u16,
+        listen: &[u16],
+    ) -> Result<u16> {
+        PortAllocator::new().allocate(
+            chain,
+            instance_id,
+            self.port_range.unwrap_or(DEFAULT_PORT_RANGE),
+            span,
+            listen,
+        )
     }
 
     /// Get the data directory path
     pub fn data_dir() -> PathBuf {
-        dirs::home_dir()
-            .expect("Could not determine home directory")
-            .join(DATA_DIR_NAME)
+        chain_forge_common::data_dir()
+    }
+
+    /// The trash in the data directory, with the configured retention
+    pub fn trash() -> Trash {
+        let days = Self::load()
+            .ok()
+            .and_then(|config| config.trash_retention_days)
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        Trash::new().with_retention_days(days)
+    }
+
+    /// The trash with a profile's retention, or the configured one if the
+    /// profile sets none
+    pub fn trash_with_retention(retention_days: Option<u32>) -> Trash {
+        match retention_days {
+            Some(days) => Trash::new().with_retention_days(days),
+            None => Self::trash(),
+        }
+    }
+
+    /// Pick the data directory for this process and the ones it starts
+    ///
+    /// `local` picks `.chain-forge` in the current directory. Otherwise
+    /// `CHAIN_FORGE_DATA_DIR` is kept if set, then `data_dir` in the config
+    /// file is used; with neither, state stays in `~/.chain-forge`.
+    pub fn select_data_dir(local: bool) -> Result<PathBuf> {
+        if local {
+            let dir = std::env::current_dir()?.join(DATA_DIR_NAME);
+            chain_forge_common::set_data_dir(&dir);
+        } else if std::env::var_os(DATA_DIR_ENV).is_none() {
+            if let Some(path) = Self::find_file() {
+                if let Some(dir) = Self::load_from_file(&path)?.data_dir {
+                    let base = path.parent().unwrap_or(Path::new("."));
+                    chain_forge_common::set_data_dir(&base.join(dir));
+                }
+            }
+        }
+        Ok(Self::data_dir())
     }
 
     /// Ensure data directory exists
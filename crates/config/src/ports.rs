@@ -0,0 +1,289 @@
+//! Port assignment for instances started without explicit ports.
+//!
+//! The `fixed` strategy uses each chain's usual default port. The `hashed`
+//! strategy derives the port from a hash of the chain and instance ID, so a
+//! given instance lands on the same ports on every machine without any
+//! per-instance configuration. The hash is FNV-1a, which (unlike std's
+//! hasher) is stable across Rust versions and platforms.
+//!
+//! `--port auto` instead scans for a block of ports that are free right now
+//! (see [`PortAllocator`]).
+
+// Allow fs2 trait methods that have the same name as std methods stabilized in Rust 1.89
+#![allow(clippy::incompatible_msrv)]
+
+use crate::Config;
+use chain_forge_common::{ChainError, ChainType, NodeRegistry, Result};
+use fs2::FileExt;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs::{self, OpenOptions};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How `start` picks ports that aren't given on the command line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PortStrategy {
+    /// The chain's default port (e.g. 8899 for Solana)
+    #[default]
+    Fixed,
+    /// A port derived from the chain and instance ID, within `port_range`
+    Hashed,
+}
+
+/// Inclusive range hashed ports are picked from when none is configured
+pub const DEFAULT_PORT_RANGE: [u16; 2] = [20000, 39999];
+
+/// FNV-1a hash of a string
+fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Stable base port for an instance
+///
+/// `span` is the number of consecutive ports the instance uses from the
+/// base port on, all of which fall within `range`.
+pub fn hashed_port(chain: ChainType, instance_id: &str, range: [u16; 2], span: u16) -> Result<u16> {
+    let [start, end] = range;
+    let choices = (end as u32 + 1)
+        .checked_sub(start as u32)
+        .and_then(|size| size.checked_sub(span.max(1) as u32 - 1))
+        .filter(|choices| *choices > 0)
+        .ok_or_else(|| {
+            ChainError::Config(format!(
+                "Port range {}-{} is too small for {} instances, which use {} ports each",
+                start, end, chain, span
+            ))
+        })?;
+
+    let hash = fnv1a(&format!("{}:{}", chain, instance_id));
+    Ok(start + (hash % choices as u64) as u16)
+}
+
+/// A port given on the command line or in an API request: a number, or
+/// `auto` to pick free ports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortChoice {
+    Auto,
+    Port(u16),
+}
+
+impl FromStr for PortChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        s.parse()
+            .map(Self::Port)
+            .map_err(|_| format!("Invalid port '{}': expected a number or 'auto'", s))
+    }
+}
+
+impl std::fmt::Display for PortChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Port(port) => write!(f, "{}", port),
+        }
+    }
+}
+
+impl Serialize for PortChoice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::Port(port) => serializer.serialize_u16(*port),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PortChoice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Port(u16),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Port(port) => Ok(Self::Port(port)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl JsonSchema for PortChoice {
+    fn schema_name() -> String {
+        "PortChoice".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let auto = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(vec!["auto".into()]),
+            ..Default::default()
+        };
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![gen.subschema_for::<u16>(), auto.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// How long an allocated block stays reserved for its instance, long enough
+/// for the node to bind its ports
+const RESERVATION_TTL_SECS: u64 = 300;
+
+/// A block of ports handed to an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Reservation {
+    node_id: String,
+    base: u16,
+    span: u16,
+    /// Unix time the block was handed out
+    reserved_at: u64,
+}
+
+impl Reservation {
+    fn overlaps(&self, base: u16, span: u16) -> bool {
+        let end = base as u32 + span as u32;
+        (base as u32) < self.base as u32 + self.span as u32 && (self.base as u32) < end
+    }
+}
+
+/// Allocates free port blocks for instances started with `--port auto`
+///
+/// Allocations are recorded in `~/.chain-forge/port-reservations.json`.
+pub struct PortAllocator {
+    path: PathBuf,
+}
+
+impl PortAllocator {
+    /// Allocator using the default data directory
+    pub fn new() -> Self {
+        Self::with_path(Config::data_dir().join("port-reservations.json"))
+    }
+
+    /// Allocator with a custom reservations file (mainly for testing)
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Pick and reserve a block of `span` ports within `range` for an instance
+    ///
+    /// Every port at one of the `listen` offsets from the base port must be
+    /// free to bind. Blocks are scanned from the instance's hashed port on,
+    /// so an instance tends to get the same ports each time. The scan and
+    /// the reservation happen under an exclusive lock, and blocks reserved
+    /// for other instances are skipped until they expire, so concurrent
+    /// starts never get the same ports before their nodes bind them.
+    pub fn allocate(
+        &self,
+        chain: ChainType,
+        instance_id: &str,
+        range: [u16; 2],
+        span: u16,
+        listen: &[u16],
+    ) -> Result<u16> {
+        let span = span.max(1);
+        let first = hashed_port(chain, instance_id, range, span)?;
+        let [start, end] = range;
+        let last = end - (span - 1);
+        let node_id = NodeRegistry::node_id(chain, instance_id);
+
+        self.update(|reservations| {
+            let now = unix_now();
+            reservations.retain(|r| {
+                r.node_id != node_id && now.saturating_sub(r.reserved_at) < RESERVATION_TTL_SECS
+            });
+
+            let free = |base: u16| {
+                !reservations.iter().any(|r| r.overlaps(base, span))
+                    && listen.iter().all(|offset| {
+                        base.checked_add(*offset)
+                            .is_some_and(|port| TcpListener::bind(("0.0.0.0", port)).is_ok())
+                    })
+            };
+            let base = (first..=last)
+                .chain(start..first)
+                .find(|base| free(*base))
+                .ok_or_else(|| {
+                    ChainError::Config(format!(
+                        "No free block of {} ports in {}-{} for {}",
+                        span, start, end, node_id
+                    ))
+                })?;
+
+            reservations.push(Reservation {
+                node_id: node_id.clone(),
+                base,
+                span,
+                reserved_at: now,
+            });
+            Ok(base)
+        })
+    }
+
+    /// Run `f` on the reservations while holding an exclusive lock, then save them
+    fn update<T>(&self, f: impl FnOnce(&mut Vec<Reservation>) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        lock.lock_exclusive().map_err(|e| {
+            ChainError::Other(format!(
+                "Failed to acquire lock on port reservations: {}",
+                e
+            ))
+        })?;
+
+        // A corrupt file only loses reservations, which expire anyway
+        let mut reservations: Vec<Reservation> = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let result = f(&mut reservations);
+        if result.is_ok() {
+            fs::write(&self.path, serde_json::to_string_pretty(&reservations)?)?;
+        }
+
+        lock.unlock().map_err(|e| {
+            ChainError::Other(format!(
+                "Failed to release lock on port reservations: {}",
+                e
+            ))
+        })?;
+        result
+    }
+}
+
+impl Default for PortAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,81 @@
+//! Built-in start presets.
+//!
+//! Presets are named bundles of start settings (`--preset ci`). They sit
+//! between the built-in defaults and anything given explicitly: a flag or
+//! request field always wins over the preset value.
+
+use chain_forge_common::{ChainError, Result};
+
+/// A built-in preset for starting nodes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preset {
+    /// Name used with `--preset`
+    pub name: &'static str,
+    /// One-line description
+    pub description: &'static str,
+    /// Number of accounts to generate
+    pub accounts: u32,
+    /// Initial balance for each Solana account in SOL
+    pub solana_balance: f64,
+    /// Initial balance for each Bitcoin account in BTC
+    pub bitcoin_balance: f64,
+    /// Labels given to the first accounts, in order
+    pub account_labels: &'static [&'static str],
+}
+
+/// Few accounts with small balances, for fast CI runs
+pub const CI: Preset = Preset {
+    name: "ci",
+    description: "Few accounts with small balances for fast CI runs",
+    accounts: 3,
+    solana_balance: 10.0,
+    bitcoin_balance: 1.0,
+    account_labels: &[],
+};
+
+/// A handful of named accounts, for demos and walkthroughs
+pub const DEMO: Preset = Preset {
+    name: "demo",
+    description: "Named accounts (alice, bob, ...) for demos",
+    accounts: 5,
+    solana_balance: 100.0,
+    bitcoin_balance: 10.0,
+    account_labels: &["alice", "bob", "carol", "dave", "erin"],
+};
+
+/// Many funded accounts, for load tests
+pub const LOAD_TEST: Preset = Preset {
+    name: "load-test",
+    description: "Many funded accounts for load tests",
+    accounts: 100,
+    solana_balance: 1000.0,
+    bitcoin_balance: 1.0,
+    account_labels: &[],
+};
+
+/// All built-in presets
+pub const PRESETS: &[Preset] = &[CI, DEMO, LOAD_TEST];
+
+impl Preset {
+    /// Look up a preset by name
+    pub fn find(name: &str) -> Result<&'static Preset> {
+        PRESETS.iter().find(|p| p.name == name).ok_or_else(|| {
+            let names: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+            ChainError::Config(format!(
+                "Unknown preset '{}'. Available presets: {}",
+                name,
+                names.join(", ")
+            ))
+        })
+    }
+
+    /// Resolve an optional preset name
+    pub fn resolve(name: Option<&str>) -> Result<Option<&'static Preset>> {
+        name.map(Self::find).transpose()
+    }
+
+    /// Account labels as owned strings
+    pub fn labels(&self) -> Vec<String> {
+        self.account_labels.iter().map(|l| l.to_string()).collect()
+    }
+}
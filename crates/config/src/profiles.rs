@@ -0,0 +1,158 @@
+//! Validating and writing named profiles in `chain-forge.toml`.
+//!
+//! Edits go through `toml_edit`, so comments and formatting elsewhere in the
+//! file are kept, and values that already exist keep their surrounding
+//! comments and spacing.
+
+use crate::{BitcoinProfile, EthereumProfile, SolanaProfile};
+use chain_forge_common::{validate_name, ChainError, ChainType, Result};
+use serde::Serialize;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item};
+
+fn invalid(message: impl Into<String>) -> ChainError {
+    ChainError::Config(message.into())
+}
+
+fn validate_rpc_url(rpc_url: &str) -> Result<()> {
+    if rpc_url.starts_with("http://") || rpc_url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "rpc_url must start with http:// or https://, got '{}'",
+            rpc_url
+        )))
+    }
+}
+
+fn validate_balance(accounts: u32, initial_balance: f64) -> Result<()> {
+    if accounts == 0 {
+        return Err(invalid("accounts must be at least 1"));
+    }
+    if !initial_balance.is_finite() || initial_balance < 0.0 {
+        return Err(invalid(format!(
+            "initial_balance must be a non-negative number, got {}",
+            initial_balance
+        )));
+    }
+    Ok(())
+}
+
+impl SolanaProfile {
+    /// Check that the profile could start a validator
+    pub fn validate(&self) -> Result<()> {
+        validate_rpc_url(&self.rpc_url)?;
+        validate_balance(self.accounts, self.initial_balance)?;
+        if self.port == 0 {
+            return Err(invalid("port must not be 0"));
+        }
+        Ok(())
+    }
+}
+
+impl BitcoinProfile {
+    /// Check that the profile could start a node
+    pub fn validate(&self) -> Result<()> {
+        validate_rpc_url(&self.rpc_url)?;
+        validate_balance(self.accounts, self.initial_balance)?;
+        if self.rpc_port == 0 || self.p2p_port == 0 {
+            return Err(invalid("rpc_port and p2p_port must not be 0"));
+        }
+        if self.rpc_port == self.p2p_port {
+            return Err(invalid("rpc_port and p2p_port must differ"));
+        }
+        if self.rpc_user.is_empty() {
+            return Err(invalid("rpc_user must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+impl EthereumProfile {
+    /// Check that the profile could start a node
+    pub fn validate(&self) -> Result<()> {
+        validate_rpc_url(&self.rpc_url)?;
+        validate_balance(self.accounts, self.initial_balance)?;
+        if self.port == 0 {
+            return Err(invalid("port must not be 0"));
+        }
+        if self.chain_id == 0 {
+            return Err(invalid("chain_id must not be 0"));
+        }
+        Ok(())
+    }
+}
+
+/// Write `profile` as `[<chain>.<name>]` in the config file at `path`
+///
+/// The file is created if it doesn't exist. Other tables and comments are
+/// left untouched.
+pub fn write_profile<P: Serialize>(
+    path: &Path,
+    chain: ChainType,
+    name: &str,
+    profile: &P,
+) -> Result<()> {
+    validate_name(name).map_err(|e| invalid(e.to_string()))?;
+
+    let contents = if path.exists() {
+        std::fs::read_to_string(path).map_err(|e| {
+            ChainError::Config(format!("Failed to read config file {:?}: {}", path, e))
+        })?
+    } else {
+        String::new()
+    };
+    let mut doc: DocumentMut = contents
+        .parse()
+        .map_err(|e| ChainError::TomlParsing(format!("Failed to parse config file: {}", e)))?;
+
+    let values: DocumentMut = toml::to_string(profile)
+        .map_err(|e| invalid(format!("Failed to serialize profile: {}", e)))?
+        .parse()
+        .map_err(|e| ChainError::TomlParsing(format!("Failed to encode profile: {}", e)))?;
+
+    let chain_key = chain.to_string();
+    if !doc.contains_key(&chain_key) {
+        let mut table = toml_edit::Table::new();
+        // Only write `[<chain>.<name>]` headers, not an empty `[<chain>]`
+        table.set_implicit(true);
+        doc.insert(&chain_key, Item::Table(table));
+    }
+    let chain_table = doc[&chain_key]
+        .as_table_like_mut()
+        .ok_or_else(|| invalid(format!("'{}' in the config file is not a table", chain_key)))?;
+    if chain_table.get(name).is_none() {
+        chain_table.insert(name, toml_edit::table());
+    }
+    let profile_table = chain_table
+        .get_mut(name)
+        .and_then(|item| item.as_table_like_mut())
+        .ok_or_else(|| {
+            invalid(format!(
+                "'{}.{}' in the config file is not a table",
+                chain_key, name
+            ))
+        })?;
+
+    for (key, value) in values.iter() {
+        let Some(new_value) = value.as_value() else {
+            continue;
+        };
+        match profile_table
+            .get_mut(key)
+            .and_then(|item| item.as_value_mut())
+        {
+            Some(existing) => {
+                let decor = existing.decor().clone();
+                *existing = new_value.clone();
+                *existing.decor_mut() = decor;
+            }
+            None => {
+                profile_table.insert(key, value.clone());
+            }
+        }
+    }
+
+    std::fs::write(path, doc.to_string())
+        .map_err(|e| ChainError::Config(format!("Failed to write config file {:?}: {}", path, e)))
+}
@@ -1,4 +1,5 @@
 use super::*;
+use chain_forge_common::ChainType;
 use std::fs;
 use tempfile::TempDir;
 
@@ -42,6 +43,24 @@ port = 9000
     assert_eq!(solana.default.port, 9000);
 }
 
+#[test]
+fn test_load_api_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    let toml_content = r#"
+[api]
+debug_routes = ["/api/v1/nodes"]
+    "#;
+
+    fs::write(&config_path, toml_content).unwrap();
+
+    let api = Config::load_from_file(&config_path).unwrap().api.unwrap();
+    assert_eq!(api.debug_routes, vec!["/api/v1/nodes".to_string()]);
+    assert!(api.debug_token.is_none());
+    assert_eq!(api.watch_interval_secs, 2);
+}
+
 #[test]
 fn test_load_config_with_multiple_profiles() {
     let temp_dir = TempDir::new().unwrap();
@@ -85,6 +104,17 @@ fn test_data_dir_creation() {
     assert!(path.is_dir());
 }
 
+#[test]
+fn test_load_data_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+    fs::write(&config_path, "data_dir = \".chain-forge\"\n").unwrap();
+
+    let config = Config::load_from_file(&config_path).unwrap();
+    assert_eq!(config.data_dir, Some(PathBuf::from(".chain-forge")));
+    assert!(Config::default().data_dir.is_none());
+}
+
 #[test]
 fn test_config_file_constants() {
     assert_eq!(CONFIG_FILE_NAME, "chain-forge.toml");
@@ -108,3 +138,418 @@ fn test_missing_file_returns_default() {
     // Should succeed even if no config file exists
     assert!(config.solana.is_none());
 }
+
+#[test]
+fn test_json_schema() {
+    let schema = Config::json_schema();
+    assert_eq!(schema["title"], "Config");
+
+    let properties = &schema["properties"];
+    assert!(properties.get("solana").is_some());
+    assert!(properties.get("bitcoin").is_some());
+
+    let definitions = &schema["definitions"];
+    assert!(definitions.get("SolanaProfile").is_some());
+    assert!(definitions.get("BitcoinProfile").is_some());
+    assert_eq!(
+        definitions["SolanaProfile"]["properties"]["port"]["default"],
+        8899
+    );
+}
+
+#[test]
+fn test_find_preset() {
+    let ci = Preset::find("ci").unwrap();
+    assert_eq!(ci.accounts, 3);
+
+    let demo = Preset::find("demo").unwrap();
+    assert_eq!(demo.labels()[0], "alice");
+    assert!(demo.account_labels.len() as u32 <= demo.accounts);
+
+    assert_eq!(Preset::find("load-test").unwrap().accounts, 100);
+}
+
+#[test]
+fn test_unknown_preset() {
+    let err = Preset::find("huge").unwrap_err().to_string();
+    assert!(err.contains("ci, demo, load-test"));
+}
+
+#[test]
+fn test_resolve_preset() {
+    assert!(Preset::resolve(None).unwrap().is_none());
+    assert_eq!(Preset::resolve(Some("ci")).unwrap().unwrap().name, "ci");
+    assert!(Preset::resolve(Some("huge")).is_err());
+}
+
+#[test]
+fn test_write_profile_preserves_comments() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    let toml_content = r#"# Team defaults
+[solana.default]
+accounts = 20 # enough for the e2e suite
+port = 9000
+
+[bitcoin.default]
+accounts = 5
+"#;
+    fs::write(&config_path, toml_content).unwrap();
+
+    let profile = SolanaProfile {
+        accounts: 30,
+        port: 9000,
+        ..SolanaProfile::default()
+    };
+    write_profile(&config_path, ChainType::Solana, "default", &profile).unwrap();
+
+    let written = fs::read_to_string(&config_path).unwrap();
+    assert!(written.starts_with("# Team defaults\n"));
+    assert!(written.contains("accounts = 30 # enough for the e2e suite"));
+    assert!(written.contains("[bitcoin.default]\naccounts = 5"));
+
+    let config = Config::load_from_file(&config_path).unwrap();
+    let solana = config.solana.unwrap();
+    assert_eq!(solana.default.accounts, 30);
+    assert_eq!(solana.default.rpc_url, "http://localhost:8899");
+}
+
+#[test]
+fn test_write_profile_creates_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    let profile = BitcoinProfile {
+        accounts: 3,
+        ..BitcoinProfile::default()
+    };
+    write_profile(&config_path, ChainType::Bitcoin, "ci", &profile).unwrap();
+
+    let written = fs::read_to_string(&config_path).unwrap();
+    assert!(written.starts_with("[bitcoin.ci]\n"));
+
+    let config = Config::load_from_file(&config_path).unwrap();
+    assert_eq!(config.bitcoin.unwrap().profiles["ci"].accounts, 3);
+}
+
+#[test]
+fn test_write_profile_rejects_bad_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    let result = write_profile(
+        &config_path,
+        ChainType::Solana,
+        "My Profile",
+        &SolanaProfile::default(),
+    );
+    assert!(result.is_err());
+    assert!(!config_path.exists());
+}
+
+#[test]
+fn test_validate_profiles() {
+    assert!(SolanaProfile::default().validate().is_ok());
+    assert!(BitcoinProfile::default().validate().is_ok());
+
+    let no_accounts = SolanaProfile {
+        accounts: 0,
+        ..SolanaProfile::default()
+    };
+    assert!(no_accounts.validate().is_err());
+
+    let bad_url = SolanaProfile {
+        rpc_url: "localhost:8899".to_string(),
+        ..SolanaProfile::default()
+    };
+    assert!(bad_url.validate().is_err());
+
+    let same_ports = BitcoinProfile {
+        p2p_port: 18443,
+        ..BitcoinProfile::default()
+    };
+    assert!(same_ports.validate().is_err());
+
+    let negative = BitcoinProfile {
+        initial_balance: -1.0,
+        ..BitcoinProfile::default()
+    };
+    assert!(negative.validate().is_err());
+
+    assert!(EthereumProfile::default().validate().is_ok());
+    let no_chain_id = EthereumProfile {
+        chain_id: 0,
+        ..EthereumProfile::default()
+    };
+    assert!(no_chain_id.validate().is_err());
+}
+
+#[test]
+fn test_ethereum_profile_from_toml() {
+    let config: Config = toml::from_str(
+        r#"
+        [ethereum.default]
+        port = 9545
+
+        [ethereum.mainnet-fork]
+        chain_id = 1
+        "#,
+    )
+    .unwrap();
+
+    let ethereum = config.ethereum.unwrap();
+    assert_eq!(ethereum.default.port, 9545);
+    assert_eq!(ethereum.default.chain_id, 31337);
+    assert_eq!(ethereum.default.initial_balance, 10000.0);
+    assert_eq!(ethereum.profiles["mainnet-fork"].chain_id, 1);
+    assert_eq!(
+        ethereum.profiles["mainnet-fork"].rpc_url,
+        "http://localhost:8545"
+    );
+}
+
+#[test]
+fn test_hashed_port_is_stable() {
+    // FNV-1a is fixed, so ports can't drift between releases or machines
+    assert_eq!(
+        hashed_port(ChainType::Solana, "ci", DEFAULT_PORT_RANGE, 1).unwrap(),
+        20237
+    );
+}
+
+#[test]
+fn test_hashed_port_depends_on_chain_and_instance() {
+    let ports: Vec<u16> = [
+        (ChainType::Solana, "a"),
+        (ChainType::Solana, "b"),
+        (ChainType::Bitcoin, "a"),
+    ]
+    .iter()
+    .map(|(chain, instance)| hashed_port(*chain, instance, DEFAULT_PORT_RANGE, 1).unwrap())
+    .collect();
+    assert_ne!(ports[0], ports[1]);
+    assert_ne!(ports[0], ports[2]);
+}
+
+#[test]
+fn test_hashed_port_range() {
+    for instance in ["a", "b", "c", "d", "e"] {
+        let port = hashed_port(ChainType::Bitcoin, instance, [30000, 30009], 2).unwrap();
+        assert!((30000..=30008).contains(&port));
+    }
+    assert_eq!(
+        hashed_port(ChainType::Ethereum, "x", [30000, 30000], 1).unwrap(),
+        30000
+    );
+    assert!(hashed_port(ChainType::Solana, "x", [30000, 30100], 1505).is_err());
+    assert!(hashed_port(ChainType::Solana, "x", [30001, 30000], 1).is_err());
+}
+
+#[test]
+fn test_instance_port_strategy() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    assert_eq!(
+        Config::default()
+            .instance_port(ChainType::Solana, "ci", 8899, 1505)
+            .unwrap(),
+        8899
+    );
+
+    fs::write(
+        &config_path,
+        "port_strategy = \"hashed\"\nport_range = [40000, 49999]\n",
+    )
+    .unwrap();
+    let config = Config::load_from_file(&config_path).unwrap();
+    assert_eq!(config.port_strategy, PortStrategy::Hashed);
+    let port = config
+        .instance_port(ChainType::Bitcoin, "ci", 18443, 2)
+        .unwrap();
+    assert!((40000..=49998).contains(&port));
+}
+
+#[test]
+fn test_encrypt_accounts_requires_passphrase() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+    fs::write(&config_path, "encrypt_accounts = true\n").unwrap();
+    let config = Config::load_from_file(&config_path).unwrap();
+    assert!(config.encrypt_accounts);
+
+    assert!(Config::default().check_accounts_protection(false).is_ok());
+    assert!(config.check_accounts_protection(true).is_ok());
+    if std::env::var(chain_forge_common::PASSPHRASE_ENV).is_err() {
+        let err = config.check_accounts_protection(false).unwrap_err();
+        assert!(err.to_string().contains("--insecure-plaintext"));
+    }
+
+    // Keyring secrets never reach the file, so there is nothing to encrypt
+    fs::write(
+        &config_path,
+        "encrypt_accounts = true\naccounts_storage = \"keyring\"\n",
+    )
+    .unwrap();
+    let config = Config::load_from_file(&config_path).unwrap();
+    assert_eq!(config.accounts_storage, StorageBackend::Keyring);
+    assert!(config.check_accounts_protection(false).is_ok());
+}
+
+#[test]
+fn test_named_profiles() {
+    let config: Config = toml::from_str(
+        r#"
+[solana.default]
+accounts = 20
+
+[solana.ci]
+accounts = 3
+port = 8999
+
+[bitcoin.bad]
+rpc_port = 18443
+p2p_port = 18443
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.solana_profile("default").unwrap().accounts, 20);
+    let ci = config.solana_profile("ci").unwrap();
+    assert_eq!((ci.accounts, ci.port), (3, 8999));
+    // Fields a profile leaves out take the built-in defaults
+    assert_eq!(ci.initial_balance, 100.0);
+
+    let err = config.solana_profile("staging").unwrap_err().to_string();
+    assert!(err.contains("[solana.staging]"));
+    assert!(err.contains("available: default, ci"));
+
+    // Without a [bitcoin.default] table, `default` is the built-in profile
+    assert_eq!(config.bitcoin_profile("default").unwrap().rpc_port, 18443);
+    let err = config.bitcoin_profile("bad").unwrap_err().to_string();
+    assert!(err.contains("must differ"));
+}
+
+#[test]
+fn test_port_choice_parsing() {
+    assert_eq!("auto".parse::<PortChoice>().unwrap(), PortChoice::Auto);
+    assert_eq!("AUTO".parse::<PortChoice>().unwrap(), PortChoice::Auto);
+    assert_eq!(
+        "9000".parse::<PortChoice>().unwrap(),
+        PortChoice::Port(9000)
+    );
+    assert!("70000".parse::<PortChoice>().is_err());
+    assert!("any".parse::<PortChoice>().is_err());
+
+    let auto: PortChoice = serde_json::from_str("\"auto\"").unwrap();
+    assert_eq!(auto, PortChoice::Auto);
+    let port: PortChoice = serde_json::from_str("8899").unwrap();
+    assert_eq!(port, PortChoice::Port(8899));
+    assert_eq!(serde_json::to_string(&port).unwrap(), "8899");
+    assert_eq!(serde_json::to_string(&auto).unwrap(), "\"auto\"");
+}
+
+#[test]
+fn test_port_allocator_reserves_disjoint_blocks() {
+    let temp_dir = TempDir::new().unwrap();
+    let allocator = PortAllocator::with_path(temp_dir.path().join("port-reservations.json"));
+    let range = [41000, 41999];
+
+    let a = allocator
+        .allocate(ChainType::Solana, "a", range, 10, &[0, 9])
+        .unwrap();
+    let b = allocator
+        .allocate(ChainType::Solana, "b", range, 10, &[0, 9])
+        .unwrap();
+    assert!((41000..=41990).contains(&a));
+    assert!(a + 10 <= b || b + 10 <= a, "{} and {} overlap", a, b);
+
+    // Allocating again for the same instance replaces its reservation
+    let again = allocator
+        .allocate(ChainType::Solana, "a", range, 10, &[0, 9])
+        .unwrap();
+    assert_eq!(again, a);
+}
+
+#[test]
+fn test_port_allocator_skips_bound_ports() {
+    let temp_dir = TempDir::new().unwrap();
+    let allocator = PortAllocator::with_path(temp_dir.path().join("port-reservations.json"));
+
+    // A one-port range whose only port is taken has nothing to offer
+    let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).unwrap();
+    let taken = listener.local_addr().unwrap().port();
+    assert!(allocator
+        .allocate(ChainType::Solana, "x", [taken, taken], 1, &[0])
+        .is_err());
+}
+
+#[test]
+fn test_funding_retry_overrides() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("chain-forge.toml");
+
+    let toml_content = r#"
+[funding_retry]
+max_attempts = 5
+backoff = 2.0
+
+[funding_retry.solana]
+max_attempts = 8
+delay_ms = 500
+    "#;
+
+    fs::write(&config_path, toml_content).unwrap();
+
+    let config = Config::load_from_file(&config_path).unwrap();
+    let bitcoin = config.funding_retry(ChainType::Bitcoin);
+    assert_eq!(bitcoin.max_attempts, 5);
+    assert_eq!(bitcoin.backoff, 2.0);
+    assert_eq!(bitcoin.delay_ms, 2000);
+
+    let solana = config.funding_retry(ChainType::Solana);
+    assert_eq!(solana.max_attempts, 8);
+    assert_eq!(solana.delay_ms, 500);
+    assert_eq!(solana.backoff, 1.0);
+
+    assert_eq!(
+        Config::default().funding_retry(ChainType::Ethereum),
+        RetryPolicy::default()
+    );
+}
+
+#[test]
+fn test_profile_cleanup() {
+    let config: Config = toml::from_str(
+        r#"
+        trash_retention_days = 14
+
+        [solana.repro]
+        keep_data = true
+        snapshot_on_stop = true
+        trash_retention_days = 30
+
+        [bitcoin.default]
+        trash_retention_days = 0
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.solana_profile("repro").unwrap().cleanup(),
+        CleanupPolicy {
+            keep_data: true,
+            snapshot_on_stop: true,
+            trash_retention_days: Some(30),
+        }
+    );
+    // Profiles that set nothing leave the top-level retention in effect
+    assert_eq!(
+        config.solana_profile("default").unwrap().cleanup(),
+        CleanupPolicy::default()
+    );
+    let bitcoin = config.bitcoin_profile("default").unwrap().cleanup();
+    assert!(!bitcoin.keep_data);
+    assert_eq!(bitcoin.trash_retention_days, Some(0));
+}
@@ -0,0 +1,485 @@
+//! Typed chain events and an in-process event bus.
+//!
+//! Producers (providers, CLIs, the API server's node watcher) publish
+//! [`ChainEvent`]s to an [`EventBus`]; consumers (the audit log, long-poll
+//! and SSE endpoints) read from it instead of each polling chain state on
+//! their own. Events are kept in a bounded buffer with increasing cursors, so
+//! clients can resume where they left off, and are broadcast live to async
+//! subscribers.
+//!
+//! The bus lives in one process. Each process gets its own through
+//! [`global()`], which records actions to the local audit log.
+
+use chain_forge_common::{AuditEvent, AuditLog, ChainType, NodeRegistry, StartupPhase};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Events kept for replay
+pub const BUFFER_CAPACITY: usize = 1024;
+
+/// Something that happened on a node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainEvent {
+    /// The node started, or was first seen running
+    NodeStarted,
+    /// The node stopped, or was first seen stopped
+    NodeStopped,
+    /// The node was removed from the registry
+    NodeRemoved,
+    /// New blocks (Bitcoin) or slots (Solana)
+    BlockMined {
+        /// Height after the new blocks, when known
+        #[serde(skip_serializing_if = "Option::is_none")]
+        height: Option<u64>,
+        count: u64,
+    },
+    /// A transaction involving a node account was confirmed
+    TxConfirmed { id: String },
+    /// An account's balance was updated
+    BalanceChanged { address: String, balance: f64 },
+    /// Funds were sent from the node to an address, or to all accounts
+    FundsDispensed {
+        address: Option<String>,
+        amount: f64,
+    },
+    /// `done` of the `total` steps of a startup phase are complete, e.g.
+    /// blocks premined or accounts funded
+    StartupProgress {
+        phase: StartupPhase,
+        done: u64,
+        total: u64,
+    },
+}
+
+impl ChainEvent {
+    /// Blocks mined at a height the producer didn't look up
+    pub fn blocks_mined(count: u64) -> Self {
+        ChainEvent::BlockMined {
+            height: None,
+            count,
+        }
+    }
+
+    /// Short name of the event type, as used in `type`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainEvent::NodeStarted => "node_started",
+            ChainEvent::NodeStopped => "node_stopped",
+            ChainEvent::NodeRemoved => "node_removed",
+            ChainEvent::BlockMined { .. } => "block_mined",
+            ChainEvent::TxConfirmed { .. } => "tx_confirmed",
+            ChainEvent::BalanceChanged { .. } => "balance_changed",
+            ChainEvent::FundsDispensed { .. } => "funds_dispensed",
+            ChainEvent::StartupProgress { .. } => "startup_progress",
+        }
+    }
+
+    /// The audit log entry for this event, if it is one the log keeps
+    pub fn audit_event(&self) -> Option<AuditEvent> {
+        match self {
+            ChainEvent::NodeStarted => Some(AuditEvent::NodeStarted),
+            ChainEvent::NodeStopped => Some(AuditEvent::NodeStopped),
+            ChainEvent::BlockMined { count, .. } => Some(AuditEvent::BlocksMined { count: *count }),
+            ChainEvent::FundsDispensed { address, amount } => Some(AuditEvent::FundsDispensed {
+                address: address.clone(),
+                amount: *amount,
+            }),
+            ChainEvent::NodeRemoved
+            | ChainEvent::TxConfirmed { .. }
+            | ChainEvent::BalanceChanged { .. }
+            | ChainEvent::StartupProgress { .. } => None,
+        }
+    }
+}
+
+/// An event with its position in the bus
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    /// Increasing position of this event; pass as `since` to resume after it
+    pub cursor: u64,
+    /// When the event was published (Unix seconds)
+    pub timestamp: i64,
+    pub chain: ChainType,
+    pub instance_id: String,
+    /// Registry ID of the node (`<chain>:<instance>`)
+    pub node_id: String,
+    #[serde(flatten)]
+    pub event: ChainEvent,
+}
+
+/// Events after a cursor
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPage {
+    pub events: Vec<Event>,
+    /// Cursor to pass as `since` on the next request
+    pub cursor: u64,
+    /// Whether events after `since` were dropped from the buffer before
+    /// they could be returned
+    pub truncated: bool,
+}
+
+/// A consumer that handles every published event as it happens
+///
+/// Sinks run synchronously on the publishing thread, so they also see events
+/// from short-lived processes such as the chain CLIs. They should be quick
+/// and must not fail the publisher.
+pub trait EventSink: Send + Sync {
+    fn handle(&self, event: &Event);
+}
+
+/// Records events that the audit log keeps (best effort)
+pub struct AuditSink {
+    log: AuditLog,
+}
+
+impl AuditSink {
+    pub fn new(log: AuditLog) -> Self {
+        Self { log }
+    }
+}
+
+impl Default for AuditSink {
+    fn default() -> Self {
+        Self::new(AuditLog::new())
+    }
+}
+
+impl EventSink for AuditSink {
+    fn handle(&self, event: &Event) {
+        let Some(audit_event) = event.event.audit_event() else {
+            return;
+        };
+        if let Err(e) = self
+            .log
+            .record(event.chain, &event.instance_id, audit_event)
+        {
+            eprintln!("Warning: Failed to write audit log: {}", e);
+        }
+    }
+}
+
+struct EventBuffer {
+    events: VecDeque<Event>,
+    capacity: usize,
+    last_cursor: u64,
+}
+
+/// Bounded, cursored event buffer with live notification
+pub struct EventBus {
+    buffer: Mutex<EventBuffer>,
+    sender: broadcast::Sender<Event>,
+    sinks: RwLock<Vec<Box<dyn EventSink>>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(BUFFER_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// Create a bus that keeps the last `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            buffer: Mutex::new(EventBuffer {
+                events: VecDeque::with_capacity(capacity),
+                capacity,
+                last_cursor: 0,
+            }),
+            sender,
+            sinks: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Register a sink for every event published from now on
+    pub fn add_sink(&self, sink: impl EventSink + 'static) {
+        self.sinks.write().unwrap().push(Box::new(sink));
+    }
+
+    /// Publish an action taken on a node
+    ///
+    /// The event is buffered, broadcast to subscribers, and handed to every
+    /// sink.
+    pub fn publish(&self, chain: ChainType, instance_id: &str, event: ChainEvent) -> Event {
+        let event = self.observe(chain, instance_id, event);
+        for sink in self.sinks.read().unwrap().iter() {
+            sink.handle(&event);
+        }
+        event
+    }
+
+    /// Publish something seen by watching chain state
+    ///
+    /// Like [`publish`](Self::publish), but sinks are skipped: whatever
+    /// caused the change already published it where it happened.
+    pub fn observe(&self, chain: ChainType, instance_id: &str, event: ChainEvent) -> Event {
+        let event = {
+            let mut buffer = self.buffer.lock().unwrap();
+            buffer.last_cursor += 1;
+            let event = Event {
+                cursor: buffer.last_cursor,
+                timestamp: chrono::Utc::now().timestamp(),
+                chain,
+                instance_id: instance_id.to_string(),
+                node_id: NodeRegistry::node_id(chain, instance_id),
+                event,
+            };
+            if buffer.events.len() == buffer.capacity {
+                buffer.events.pop_front();
+            }
+            buffer.events.push_back(event.clone());
+            event
+        };
+        // No subscribers is not an error
+        let _ = self.sender.send(event.clone());
+        event
+    }
+
+    /// Cursor of the most recent event (0 if none yet)
+    pub fn latest_cursor(&self) -> u64 {
+        self.buffer.lock().unwrap().last_cursor
+    }
+
+    /// Buffered events after `since`, optionally for one node only
+    pub fn since(&self, since: u64, node_id: Option<&str>) -> EventPage {
+        let buffer = self.buffer.lock().unwrap();
+        let truncated = buffer
+            .events
+            .front()
+            .is_some_and(|oldest| oldest.cursor > since.saturating_add(1));
+        let events = buffer
+            .events
+            .iter()
+            .filter(|e| e.cursor > since)
+            .filter(|e| node_id.map_or(true, |id| e.node_id == id))
+            .cloned()
+            .collect();
+        EventPage {
+            events,
+            cursor: buffer.last_cursor.max(since),
+            truncated,
+        }
+    }
+
+    /// Wait up to `timeout` for events after `since`
+    ///
+    /// Returns as soon as at least one matching event exists, or an empty
+    /// page once the timeout passes.
+    pub async fn wait_since(
+        &self,
+        since: u64,
+        node_id: Option<&str>,
+        timeout: Duration,
+    ) -> EventPage {
+        // Subscribe before checking the buffer so nothing published in
+        // between is missed
+        let mut receiver = self.sender.subscribe();
+        let page = self.since(since, node_id);
+        if !page.events.is_empty() || page.truncated {
+            return page;
+        }
+
+        let _ = tokio::time::timeout(timeout, async {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if node_id.map_or(true, |id| event.node_id == id) => break,
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(_)) => break,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+        .await;
+
+        self.since(since, node_id)
+    }
+
+    /// Receive live events as they are published
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+static GLOBAL: OnceLock<Arc<EventBus>> = OnceLock::new();
+
+/// The process-wide bus, created on first use with an [`AuditSink`]
+pub fn global() -> Arc<EventBus> {
+    GLOBAL
+        .get_or_init(|| {
+            let bus = EventBus::default();
+            bus.add_sink(AuditSink::default());
+            Arc::new(bus)
+        })
+        .clone()
+}
+
+/// Publish an action on the process-wide bus
+pub fn publish(chain: ChainType, instance_id: &str, event: ChainEvent) -> Event {
+    global().publish(chain, instance_id, event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingSink(Arc<Mutex<Vec<String>>>);
+
+    impl EventSink for CountingSink {
+        fn handle(&self, event: &Event) {
+            self.0.lock().unwrap().push(event.event.name().to_string());
+        }
+    }
+
+    #[test]
+    fn test_since_returns_events_after_cursor() {
+        let bus = EventBus::new(10);
+        bus.observe(ChainType::Bitcoin, "a", ChainEvent::NodeRemoved);
+        bus.observe(ChainType::Bitcoin, "b", ChainEvent::NodeRemoved);
+        bus.observe(ChainType::Bitcoin, "a", ChainEvent::NodeRemoved);
+
+        let page = bus.since(1, None);
+        assert_eq!(page.events.len(), 2);
+        assert_eq!(page.events[0].cursor, 2);
+        assert_eq!(page.cursor, 3);
+        assert!(!page.truncated);
+
+        let page = bus.since(0, Some("bitcoin:a"));
+        assert_eq!(page.events.len(), 2);
+
+        let page = bus.since(3, None);
+        assert!(page.events.is_empty());
+        assert_eq!(page.cursor, 3);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let bus = EventBus::new(2);
+        for _ in 0..5 {
+            bus.observe(ChainType::Solana, "default", ChainEvent::NodeRemoved);
+        }
+
+        let page = bus.since(0, None);
+        assert_eq!(page.events.len(), 2);
+        assert_eq!(page.events[0].cursor, 4);
+        assert!(page.truncated);
+        assert!(!bus.since(3, None).truncated);
+    }
+
+    #[tokio::test]
+    async fn test_wait_since_wakes_on_publish() {
+        let bus = Arc::new(EventBus::new(10));
+        let publisher = bus.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            publisher.publish(ChainType::Bitcoin, "dev", ChainEvent::NodeStarted);
+        });
+
+        let page = bus.wait_since(0, None, Duration::from_secs(5)).await;
+        assert_eq!(page.events.len(), 1);
+
+        let page = bus
+            .wait_since(page.cursor, None, Duration::from_millis(10))
+            .await;
+        assert!(page.events.is_empty());
+    }
+
+    #[test]
+    fn test_sinks_see_published_not_observed() {
+        let bus = EventBus::new(10);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        bus.add_sink(CountingSink(seen.clone()));
+
+        bus.publish(ChainType::Bitcoin, "dev", ChainEvent::NodeStarted);
+        bus.observe(
+            ChainType::Bitcoin,
+            "dev",
+            ChainEvent::BlockMined {
+                height: Some(102),
+                count: 1,
+            },
+        );
+
+        assert_eq!(*seen.lock().unwrap(), vec!["node_started"]);
+        assert_eq!(bus.latest_cursor(), 2);
+    }
+
+    #[test]
+    fn test_audit_sink_records_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::with_path(dir.path().join("audit.log"));
+        let bus = EventBus::new(10);
+        bus.add_sink(AuditSink::new(AuditLog::with_path(log.path().clone())));
+
+        bus.publish(
+            ChainType::Bitcoin,
+            "dev",
+            ChainEvent::BlockMined {
+                height: None,
+                count: 6,
+            },
+        );
+        bus.publish(
+            ChainType::Bitcoin,
+            "dev",
+            ChainEvent::TxConfirmed {
+                id: "abc".to_string(),
+            },
+        );
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].instance_id, "dev");
+        assert_eq!(entries[0].event, AuditEvent::BlocksMined { count: 6 });
+    }
+
+    #[test]
+    fn test_event_json() {
+        let bus = EventBus::new(10);
+        let event = bus.observe(
+            ChainType::Solana,
+            "default",
+            ChainEvent::BlockMined {
+                height: Some(42),
+                count: 2,
+            },
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "block_mined");
+        assert_eq!(json["height"], 42);
+        assert_eq!(json["chain"], "solana");
+        assert_eq!(json["node_id"], "solana:default");
+        assert_eq!(json["cursor"], 1);
+
+        let event = bus.observe(
+            ChainType::Solana,
+            "default",
+            ChainEvent::BlockMined {
+                height: None,
+                count: 1,
+            },
+        );
+        assert!(serde_json::to_value(&event)
+            .unwrap()
+            .get("height")
+            .is_none());
+
+        let event = bus.observe(
+            ChainType::Bitcoin,
+            "default",
+            ChainEvent::StartupProgress {
+                phase: StartupPhase::Premine,
+                done: 25,
+                total: 101,
+            },
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "startup_progress");
+        assert_eq!(json["phase"], "premine");
+        assert_eq!(json["done"], 25);
+    }
+}
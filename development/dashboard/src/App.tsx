@@ -5,6 +5,7 @@ import { BrowserRouter, Routes, Route } from 'react-router-dom';
 import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import { Dashboard } from './pages/Dashboard';
 import { NodeDetail } from './pages/NodeDetail';
+import { useLiveEvents } from './api/hooks';
 
 const queryClient = new QueryClient({
   defaultOptions: {
@@ -62,6 +63,8 @@ function ThemeToggle({
 }
 
 function Layout({ children }: { children: React.ReactNode }) {
+  useLiveEvents();
+
   const [darkMode, setDarkMode] = useState(() => {
     if (typeof window !== 'undefined') {
       const stored = localStorage.getItem('darkMode');
@@ -4,18 +4,23 @@ import type {
   ApiResponse,
   NodeInfo,
   AccountInfo,
+  AccountsQuery,
   HealthCheckResponse,
   StartNodeRequest,
   StartNodeResponse,
+  Job,
   StopNodeResponse,
   FundAccountRequest,
   FundResponse,
+  MineRequest,
+  MineResponse,
   CleanupResponse,
   TransactionInfo,
   TransactionDetail,
+  DecodeRequest,
 } from './types';
 
-const API_BASE = '/api/v1';
+export const API_BASE = '/api/v1';
 
 async function fetchApi<T>(
   endpoint: string,
@@ -68,12 +73,20 @@ export async function getNode(nodeId: string): Promise<ApiResponse<NodeInfo>> {
   return fetchApi<NodeInfo>(`/nodes/${encodeURIComponent(nodeId)}`);
 }
 
-// Get accounts for a node
+// Get accounts for a node, optionally filtered and paged
 export async function getNodeAccounts(
-  nodeId: string
+  nodeId: string,
+  query: AccountsQuery = {}
 ): Promise<ApiResponse<AccountInfo[]>> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query)) {
+    if (value !== undefined) {
+      params.set(key, String(value));
+    }
+  }
+  const search = params.toString();
   return fetchApi<AccountInfo[]>(
-    `/nodes/${encodeURIComponent(nodeId)}/accounts`
+    `/nodes/${encodeURIComponent(nodeId)}/accounts${search ? `?${search}` : ''}`
   );
 }
 
@@ -84,7 +97,7 @@ export async function healthCheck(): Promise<ApiResponse<HealthCheckResponse>> {
   });
 }
 
-// Start a new node (returns CLI instructions)
+// Start a new node in the background (returns a job to poll)
 export async function startNode(
   request: StartNodeRequest
 ): Promise<ApiResponse<StartNodeResponse>> {
@@ -94,6 +107,23 @@ export async function startNode(
   });
 }
 
+// Get the status of a background job
+export async function getJob(id: string): Promise<ApiResponse<Job>> {
+  return fetchApi<Job>(`/jobs/${encodeURIComponent(id)}`);
+}
+
+// List background jobs, newest first
+export async function listJobs(): Promise<ApiResponse<Job[]>> {
+  return fetchApi<Job[]>('/jobs');
+}
+
+// Cancel a running background job
+export async function cancelJob(id: string): Promise<ApiResponse<Job>> {
+  return fetchApi<Job>(`/jobs/${encodeURIComponent(id)}`, {
+    method: 'DELETE',
+  });
+}
+
 // Stop a node (marks as stopped)
 export async function stopNode(
   nodeId: string
@@ -142,3 +172,25 @@ export async function fundAccount(
     }
   );
 }
+
+// Mine blocks on a Bitcoin node
+export async function mineBlocks(
+  nodeId: string,
+  request: MineRequest
+): Promise<ApiResponse<MineResponse>> {
+  return fetchApi<MineResponse>(`/nodes/${encodeURIComponent(nodeId)}/mine`, {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+}
+
+// Decode a raw transaction, script, or message
+export async function decodePayload(
+  nodeId: string,
+  request: DecodeRequest
+): Promise<ApiResponse<unknown>> {
+  return fetchApi<unknown>(`/nodes/${encodeURIComponent(nodeId)}/decode`, {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+}
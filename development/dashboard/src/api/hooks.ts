@@ -1,15 +1,89 @@
 // React Query hooks for Chain Forge API
 
+import { useEffect } from 'react';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import * as api from './client';
-import type { StartNodeRequest, FundAccountRequest } from './types';
+import type {
+  StartNodeRequest,
+  FundAccountRequest,
+  MineRequest,
+  NodeEvent,
+  ResyncMessage,
+  DecodeRequest,
+} from './types';
+
+// Delay before reconnecting a dropped event stream
+const RECONNECT_DELAY_MS = 2000;
+
+// Keep queries fresh from the /api/v1/ws event stream instead of polling
+export function useLiveEvents() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    let socket: WebSocket | null = null;
+    let reconnect: ReturnType<typeof setTimeout> | undefined;
+    let cursor: number | null = null;
+    let closed = false;
+
+    const connect = () => {
+      const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+      const since = cursor === null ? '' : `?since=${cursor}`;
+      socket = new WebSocket(
+        `${protocol}//${window.location.host}${api.API_BASE}/ws${since}`
+      );
+
+      socket.onmessage = (message) => {
+        const event = JSON.parse(message.data) as NodeEvent | ResyncMessage;
+        cursor = event.cursor;
+
+        switch (event.type) {
+          case 'resync':
+            queryClient.invalidateQueries();
+            break;
+          case 'node_started':
+          case 'node_stopped':
+          case 'node_removed':
+            queryClient.invalidateQueries({ queryKey: ['nodes'] });
+            queryClient.invalidateQueries({ queryKey: ['node', event.node_id] });
+            break;
+          case 'block_mined':
+            queryClient.invalidateQueries({ queryKey: ['nodes'] });
+            break;
+          case 'tx_confirmed':
+            queryClient.invalidateQueries({
+              queryKey: ['transactions', event.node_id],
+            });
+            queryClient.invalidateQueries({ queryKey: ['accounts', event.node_id] });
+            break;
+          case 'balance_changed':
+          case 'funds_dispensed':
+            queryClient.invalidateQueries({ queryKey: ['accounts', event.node_id] });
+            break;
+        }
+      };
+
+      socket.onclose = () => {
+        if (!closed) {
+          reconnect = setTimeout(connect, RECONNECT_DELAY_MS);
+        }
+      };
+    };
+
+    connect();
+
+    return () => {
+      closed = true;
+      clearTimeout(reconnect);
+      socket?.close();
+    };
+  }, [queryClient]);
+}
 
-// List all nodes with auto-refresh
+// List all nodes (kept fresh by useLiveEvents)
 export function useNodes() {
   return useQuery({
     queryKey: ['nodes'],
     queryFn: api.listNodes,
-    refetchInterval: 5000, // Auto-refresh every 5 seconds
   });
 }
 
@@ -68,13 +142,12 @@ export function useStopNode() {
   });
 }
 
-// Get transactions for a node with auto-refresh
+// Get transactions for a node (kept fresh by useLiveEvents)
 export function useNodeTransactions(nodeId: string) {
   return useQuery({
     queryKey: ['transactions', nodeId],
     queryFn: () => api.getNodeTransactions(nodeId),
     enabled: !!nodeId,
-    refetchInterval: 10000,
   });
 }
 
@@ -111,3 +184,23 @@ export function useFundAccount(nodeId: string) {
     },
   });
 }
+
+// Mine blocks mutation (Bitcoin)
+export function useMineBlocks(nodeId: string) {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (request: MineRequest) => api.mineBlocks(nodeId, request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['accounts', nodeId] });
+      queryClient.invalidateQueries({ queryKey: ['transactions', nodeId] });
+    },
+  });
+}
+
+// Decode payload mutation (Bitcoin node JSON or a decoded Solana message)
+export function useDecodePayload(nodeId: string) {
+  return useMutation({
+    mutationFn: (request: DecodeRequest) => api.decodePayload(nodeId, request),
+  });
+}
@@ -16,12 +16,24 @@ export interface NodeInfo {
   accounts_count: number;
   status: 'running' | 'stopped' | 'unknown';
   started_at: string | null;
+  started: string | null;
+  uptime_secs: number | null;
+  uptime: string | null;
 }
 
 export interface AccountInfo {
   index: number;
   address: string;
   balance: number;
+  descriptor?: string;
+}
+
+export interface AccountsQuery {
+  offset?: number;
+  limit?: number;
+  min_balance?: number;
+  label?: string;
+  refresh?: boolean;
 }
 
 export interface TransactionInfo {
@@ -41,6 +53,11 @@ export interface BalanceChange {
   change: number;
 }
 
+export interface TransactionIo {
+  address: string | null;
+  amount: number;
+}
+
 export interface TransactionDetail {
   signature: string;
   slot: number;
@@ -48,6 +65,9 @@ export interface TransactionDetail {
   fee: number;
   err: string | null;
   balance_changes: BalanceChange[];
+  // Bitcoin only
+  inputs?: TransactionIo[];
+  outputs?: TransactionIo[];
 }
 
 export interface HealthCheckResponse {
@@ -61,28 +81,53 @@ export interface StartNodeRequest {
   chain: 'solana' | 'bitcoin';
   instance?: string;
   name?: string;
-  port?: number;
+  port?: number | 'auto';
   accounts?: number;
   balance?: number;
 }
 
 export interface StartNodeResponse {
+  job_id: string;
   message: string;
   command: string;
   chain: string;
   instance: string;
   port: number;
+  ports?: ValidatorPorts;
+  log_file: string;
+}
+
+export interface ValidatorPorts {
+  rpc: number;
+  faucet: number;
+  gossip: number;
+  dynamic_range: [number, number];
+}
+
+export interface Job {
+  id: string;
+  kind: string;
+  node_id: string | null;
+  status: 'running' | 'succeeded' | 'failed' | 'cancelled';
+  phase: string;
+  progress: number | null;
+  error: string | null;
+  result: unknown;
+  created_at: number;
+  updated_at: number;
 }
 
 export interface StopNodeResponse {
   message: string;
-  instruction: string;
+  instruction?: string;
   node_id: string;
+  stopped: boolean;
 }
 
 export interface FundAccountRequest {
   address: string;
   amount: number;
+  confirm?: number | 'none';
 }
 
 export interface FundResponse {
@@ -92,8 +137,46 @@ export interface FundResponse {
   amount: number;
 }
 
+export interface MineRequest {
+  blocks: number;
+  address?: string;
+}
+
+export interface MineResponse {
+  block_hashes: string[];
+  height: number;
+}
+
+export interface DecodeRequest {
+  kind?: 'transaction' | 'script' | 'message';
+  // Hex for Bitcoin; base64 or base58 for Solana
+  data: string;
+}
+
 export interface CleanupResponse {
   removed: number;
   remaining: number;
   removed_nodes: string[];
 }
+
+// Message from the /api/v1/ws event stream
+export type NodeEvent = {
+  cursor: number;
+  timestamp: number;
+  chain: 'solana' | 'bitcoin' | 'ethereum';
+  instance_id: string;
+  node_id: string;
+} & (
+  | { type: 'node_started' }
+  | { type: 'node_stopped' }
+  | { type: 'node_removed' }
+  | { type: 'block_mined'; height?: number; count: number }
+  | { type: 'tx_confirmed'; id: string }
+  | { type: 'balance_changed'; address: string; balance: number }
+  | { type: 'funds_dispensed'; address: string | null; amount: number }
+);
+
+export interface ResyncMessage {
+  type: 'resync';
+  cursor: number;
+}
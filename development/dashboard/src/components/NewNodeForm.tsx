@@ -110,13 +110,13 @@ export function NewNodeForm({ onClose }: NewNodeFormProps) {
         <div className="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-lg w-full mx-4 overflow-hidden">
           <div className="px-6 py-4 border-b border-gray-200 dark:border-gray-700">
             <h2 className="text-lg font-semibold text-gray-900 dark:text-gray-100">
-              Start Your Node
+              Node Starting
             </h2>
           </div>
 
           <div className="p-6">
             <p className="text-sm text-gray-600 dark:text-gray-400 mb-4">
-              Run this command in a terminal to start your node:
+              The API server is starting your node with:
             </p>
 
             <div className="relative">
@@ -132,7 +132,7 @@ export function NewNodeForm({ onClose }: NewNodeFormProps) {
             </div>
 
             <p className="text-xs text-gray-500 dark:text-gray-400 mt-4">
-              Keep the terminal open to keep the node running. Press Ctrl+C to stop.
+              It appears in the node list once its accounts are funded. Stop it from the node list.
             </p>
 
             <div className="flex gap-3 mt-6">
@@ -4,6 +4,7 @@ import { useNavigate } from 'react-router-dom';
 import type { NodeInfo } from '../api/types';
 import { NodeStatus } from './NodeStatus';
 import { useStopNode } from '../api/hooks';
+import { formatDuration, useNow } from '../time';
 
 interface NodeCardProps {
   node: NodeInfo;
@@ -12,6 +13,7 @@ interface NodeCardProps {
 export function NodeCard({ node }: NodeCardProps) {
   const navigate = useNavigate();
   const stopMutation = useStopNode();
+  const now = useNow();
 
   const chainColors = {
     solana: {
@@ -77,6 +79,17 @@ export function NodeCard({ node }: NodeCardProps) {
             <span className="font-mono text-sm">{node.accounts_count}</span>
           </div>
 
+          {node.status === 'running' && node.started_at && (
+            <div className="flex justify-between items-center">
+              <span className="text-gray-500 dark:text-gray-400 text-sm">
+                Uptime
+              </span>
+              <span className="font-mono text-sm">
+                {formatDuration(now - new Date(node.started_at).getTime())}
+              </span>
+            </div>
+          )}
+
           <div className="flex justify-between items-center">
             <span className="text-gray-500 dark:text-gray-400 text-sm">
               Instance
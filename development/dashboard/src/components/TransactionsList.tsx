@@ -2,7 +2,7 @@
 
 import { useState } from 'react';
 import { useNodeTransactions, useTransactionDetail } from '../api/hooks';
-import type { TransactionInfo, BalanceChange } from '../api/types';
+import type { TransactionInfo, BalanceChange, TransactionIo } from '../api/types';
 
 interface TransactionsListProps {
   nodeId: string;
@@ -108,6 +108,28 @@ function BalanceChangesTable({ changes, chain }: { changes: BalanceChange[]; cha
   );
 }
 
+function IoList({ title, entries, chain }: { title: string; entries: TransactionIo[]; chain: string }) {
+  return (
+    <div className="flex-1 min-w-0">
+      <h4 className="text-xs font-medium text-gray-500 dark:text-gray-400 uppercase mb-1">
+        {title}
+      </h4>
+      <ul className="space-y-0.5 text-sm">
+        {entries.map((entry, i) => (
+          <li key={i} className="flex justify-between gap-4">
+            <code className="font-mono text-xs text-gray-700 dark:text-gray-300">
+              {entry.address ? truncate(entry.address, 6) : title === 'From' ? 'coinbase' : '-'}
+            </code>
+            <span className="font-mono text-xs text-gray-500 dark:text-gray-400">
+              {formatAmount(entry.amount, chain)} {currencyUnit(chain)}
+            </span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
+
 function TransactionDetailRow({
   nodeId,
   signature,
@@ -168,6 +190,15 @@ function TransactionDetailRow({
             </div>
           </div>
 
+          {/* Inputs and outputs (Bitcoin) */}
+          {detail.inputs && detail.outputs && (
+            <div className="flex flex-wrap items-start gap-4">
+              <IoList title="From" entries={detail.inputs} chain={chain} />
+              <span className="self-center text-gray-400">→</span>
+              <IoList title="To" entries={detail.outputs} chain={chain} />
+            </div>
+          )}
+
           {/* Balance changes */}
           {detail.balance_changes.length > 0 ? (
             <div>
@@ -2,10 +2,11 @@
 
 import { useState } from 'react';
 import { useParams, Link } from 'react-router-dom';
-import { useNode, useNodeAccounts, useStopNode } from '../api/hooks';
+import { useMineBlocks, useNode, useNodeAccounts, useStopNode } from '../api/hooks';
 import { NodeStatus } from '../components/NodeStatus';
 import { AccountsList } from '../components/AccountsList';
 import { TransactionsList } from '../components/TransactionsList';
+import { formatDuration, formatRelative, useNow } from '../time';
 
 type Tab = 'accounts' | 'transactions';
 
@@ -17,6 +18,8 @@ export function NodeDetail() {
     nodeId ?? ''
   );
   const stopMutation = useStopNode();
+  const mineMutation = useMineBlocks(nodeId ?? '');
+  const now = useNow();
 
   if (!nodeId) {
     return <div>Invalid node ID</div>;
@@ -107,6 +110,16 @@ export function NodeDetail() {
             <div className="text-white/80 font-mono text-sm">{node.node_id}</div>
           </div>
           <div className="flex items-center gap-4">
+            {node.status === 'running' && node.chain === 'bitcoin' && (
+              <button
+                onClick={() => mineMutation.mutate({ blocks: 1 })}
+                disabled={mineMutation.isPending}
+                title="Mine a block to a wallet address"
+                className="px-4 py-2 bg-white/20 text-white rounded-lg hover:bg-white/30 disabled:opacity-50 transition-colors"
+              >
+                {mineMutation.isPending ? 'Mining...' : 'Mine Block'}
+              </button>
+            )}
             {node.status === 'running' && (
               <button
                 onClick={handleStop}
@@ -150,11 +163,24 @@ export function NodeDetail() {
           <div className="text-sm text-gray-500 dark:text-gray-400 mb-1">
             Started
           </div>
-          <div className="text-sm text-gray-900 dark:text-gray-100">
-            {node.started_at
-              ? new Date(node.started_at).toLocaleString()
-              : 'Unknown'}
-          </div>
+          {node.started_at ? (
+            <div
+              className="text-sm text-gray-900 dark:text-gray-100"
+              title={new Date(node.started_at).toLocaleString()}
+            >
+              {formatRelative(new Date(node.started_at), now)}
+              {node.status === 'running' && (
+                <span className="text-gray-500 dark:text-gray-400">
+                  {' '}
+                  (up {formatDuration(now - new Date(node.started_at).getTime())})
+                </span>
+              )}
+            </div>
+          ) : (
+            <div className="text-sm text-gray-900 dark:text-gray-100">
+              Unknown
+            </div>
+          )}
         </div>
       </div>
 
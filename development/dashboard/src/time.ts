@@ -0,0 +1,37 @@
+// Relative time and uptime formatting, matching the `cf` CLI
+
+import { useEffect, useState } from 'react';
+
+/** Format a duration with its two largest units, e.g. `2h 14m` or `45s` */
+export function formatDuration(ms: number): string {
+  const secs = Math.max(0, Math.floor(ms / 1000));
+  const days = Math.floor(secs / 86400);
+  const hours = Math.floor((secs % 86400) / 3600);
+  const minutes = Math.floor((secs % 3600) / 60);
+  const seconds = secs % 60;
+
+  if (days > 0) return `${days}d ${hours}h`;
+  if (hours > 0) return `${hours}h ${minutes}m`;
+  if (minutes > 0) return `${minutes}m ${seconds}s`;
+  return `${seconds}s`;
+}
+
+/** Format a past time relative to `now`, e.g. `3h ago` or `just now` */
+export function formatRelative(time: Date, now: number): string {
+  const secs = Math.floor((now - time.getTime()) / 1000);
+  if (secs < 10) return 'just now';
+  if (secs >= 86400) return `${Math.floor(secs / 86400)}d ago`;
+  if (secs >= 3600) return `${Math.floor(secs / 3600)}h ago`;
+  if (secs >= 60) return `${Math.floor(secs / 60)}m ago`;
+  return `${secs}s ago`;
+}
+
+/** Current time, refreshed every `intervalMs` so uptimes keep ticking */
+export function useNow(intervalMs = 1000): number {
+  const [now, setNow] = useState(() => Date.now());
+  useEffect(() => {
+    const id = setInterval(() => setNow(Date.now()), intervalMs);
+    return () => clearInterval(id);
+  }, [intervalMs]);
+  return now;
+}
@@ -6,6 +6,9 @@ import { existsSync } from 'fs';
 import { EventEmitter } from 'events';
 import { BitcoinAccount, BitcoinClientConfig, SendResult, MineResult } from './types';
 
+/** Message of the event cf-bitcoin logs once the node is up */
+const READY_MESSAGE = 'Bitcoin node is running';
+
 /**
  * Find the cf-bitcoin binary
  * Looks in multiple locations: workspace root, project root, then PATH
@@ -152,21 +155,21 @@ export class BitcoinClient extends EventEmitter {
       let resolved = false;
 
       this.nodeProcess.stdout?.on('data', (data: Buffer) => {
-        output += data.toString();
         process.stdout.write(data);
+      });
 
-        // Wait for the success message (includes instance name, e.g. "Bitcoin regtest node 'default' is running!")
-        if (!resolved && output.includes('is running!') && output.includes('Bitcoin regtest node')) {
+      // Progress is logged to stderr; wait for the event announcing the running node
+      this.nodeProcess.stderr?.on('data', (data: Buffer) => {
+        output += data.toString();
+        process.stderr.write(data);
+
+        if (!resolved && output.includes(READY_MESSAGE)) {
           resolved = true;
           this.emit('ready');
           resolve();
         }
       });
 
-      this.nodeProcess.stderr?.on('data', (data: Buffer) => {
-        process.stderr.write(data);
-      });
-
       this.nodeProcess.on('error', (error: Error) => {
         this.emit('error', error);
         if (!resolved) {
@@ -227,8 +230,9 @@ export class BitcoinClient extends EventEmitter {
 
     try {
       const data = await fs.readFile(accountsPath, 'utf-8');
-      const accounts = JSON.parse(data) as BitcoinAccount[];
-      return accounts;
+      // Versioned files wrap the list; older ones are a bare array
+      const parsed = JSON.parse(data) as BitcoinAccount[] | { accounts: BitcoinAccount[] };
+      return Array.isArray(parsed) ? parsed : parsed.accounts;
     } catch (error) {
       if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
         return [];
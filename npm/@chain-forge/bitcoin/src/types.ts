@@ -90,6 +90,11 @@ export interface BitcoinAccount {
    */
   derivationPath?: string;
 
+  /**
+   * Fingerprint of the master key the derivation path starts from
+   */
+  masterFingerprint?: string;
+
   /**
    * Current balance in BTC
    */
@@ -8,6 +8,9 @@ import { SolanaAccount, SolanaClientConfig, DeployProgramOptions, DeployProgramR
 // Import Solana web3.js types
 import type { Connection, PublicKey, Keypair } from '@solana/web3.js';
 
+/** Message of the event cf-solana logs once the validator is up */
+const READY_MESSAGE = 'Solana test validator is running';
+
 /**
  * Find the cf-solana binary
  * Looks in multiple locations: workspace root, project root, then PATH
@@ -84,17 +87,17 @@ export class SolanaClient {
       let output = '';
 
       this.validatorProcess.stdout?.on('data', (data: Buffer) => {
-        output += data.toString();
         process.stdout.write(data);
-
-        // Wait for the success message (includes instance name, e.g. "Solana test validator 'default' is running!")
-        if (output.includes('is running!') && output.includes('Solana test validator')) {
-          resolve();
-        }
       });
 
+      // Progress is logged to stderr; wait for the event announcing the running validator
       this.validatorProcess.stderr?.on('data', (data: Buffer) => {
+        output += data.toString();
         process.stderr.write(data);
+
+        if (output.includes(READY_MESSAGE)) {
+          resolve();
+        }
       });
 
       this.validatorProcess.on('error', (error: Error) => {
@@ -110,7 +113,7 @@ export class SolanaClient {
 
       // Timeout after 60 seconds
       setTimeout(() => {
-        if (this.validatorProcess && !(output.includes('is running!') && output.includes('Solana test validator'))) {
+        if (this.validatorProcess && !output.includes(READY_MESSAGE)) {
           this.stop();
           reject(new Error('Validator startup timeout'));
         }
@@ -144,8 +147,9 @@ export class SolanaClient {
 
     try {
       const data = await fs.readFile(accountsPath, 'utf-8');
-      const accounts = JSON.parse(data) as SolanaAccount[];
-      return accounts;
+      // Versioned files wrap the list; older ones are a bare array
+      const parsed = JSON.parse(data) as SolanaAccount[] | { accounts: SolanaAccount[] };
+      return Array.isArray(parsed) ? parsed : parsed.accounts;
     } catch (error) {
       if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
         return [];